@@ -0,0 +1,752 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CopyOnWriteExec`]: `DELETE`, `UPDATE` and `MERGE INTO` for
+//! [`ListingTable`] by rewriting the affected files
+//!
+//! [`ListingTable`]: crate::ListingTable
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use arrow::array::{ArrayRef, RecordBatch, RecordBatchOptions, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion_common::tree_node::TreeNodeRecursion;
+use datafusion_common::{DataFusionError, Result, internal_err};
+use datafusion_execution::object_store::ObjectStoreUrl;
+use datafusion_execution::{SendableRecordBatchStream, TaskContext};
+use datafusion_physical_expr::{Distribution, EquivalenceProperties};
+use datafusion_physical_plan::execution_plan::{Boundedness, EmissionType};
+use datafusion_physical_plan::merge_into::{MergeIntoSpec, Merger};
+use datafusion_physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion_physical_plan::{
+    ChildrenPropertiesMode, DisplayAs, DisplayFormatType, ExecutionPlan,
+    InputDistributionRequirements, Partitioning, PhysicalExpr, PlanProperties,
+    ReplaceChildrenOptions, execute_stream, validate_child_count,
+};
+use futures::{StreamExt, TryStreamExt, stream};
+use object_store::path::Path;
+use object_store::{ObjectStore, ObjectStoreExt};
+use rand::distr::SampleString;
+
+/// The operation performed by a [`CopyOnWriteExec`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CopyOnWriteOp {
+    Delete,
+    Update,
+    Merge,
+}
+
+impl fmt::Display for CopyOnWriteOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Delete => write!(f, "DELETE"),
+            Self::Update => write!(f, "UPDATE"),
+            Self::Merge => write!(f, "MERGE"),
+        }
+    }
+}
+
+/// Rewrite of a single file of the table
+#[derive(Debug, Clone)]
+pub(crate) struct FileRewrite {
+    /// The existing file
+    pub(crate) source: Path,
+    /// The staged file written by `rewrite`, if it writes a single file even
+    /// when it has no rows, in which case it is removed
+    pub(crate) staged: Option<Path>,
+    /// Returns one row per row of `source` affected by the operation
+    pub(crate) matches: Arc<dyn ExecutionPlan>,
+    /// Writes the new contents of `source` to staged files, returning the
+    /// number of rows written
+    pub(crate) rewrite: Arc<dyn ExecutionPlan>,
+}
+
+/// The [`Merger`] shared by the plans of a `MERGE INTO`, set once the source
+/// was loaded
+pub(crate) type SharedMerger = Arc<Mutex<Option<Merger>>>;
+
+/// The source and inserted rows of a `MERGE INTO`
+#[derive(Debug, Clone)]
+pub(crate) struct MergeInput {
+    /// The rows to merge into the table
+    pub(crate) source: Arc<dyn ExecutionPlan>,
+    pub(crate) spec: MergeIntoSpec,
+    /// Set to a [`Merger`] holding `source` before the files are rewritten
+    pub(crate) merger: SharedMerger,
+    /// Writes the inserted rows to staged files, returning the number of rows
+    /// written, if the merge has `WHEN NOT MATCHED` clauses
+    pub(crate) insert: Option<Arc<dyn ExecutionPlan>>,
+}
+
+/// Executes a `DELETE`, `UPDATE` or `MERGE INTO` on a [`ListingTable`] by
+/// rewriting each file containing affected rows (copy-on-write), and returns a
+/// single row with the number of affected rows, like the `MemTable`
+/// implementations.
+///
+/// The files are processed one at a time: files without affected rows are left
+/// untouched. Each candidate file is read twice, once by the `matches` plan to
+/// count the affected rows and once more by the `rewrite` plan if there are
+/// any, as a scan cannot be executed more than once. Both plans are the
+/// children of this node, two per candidate file.
+///
+/// For `MERGE INTO`, the source is first loaded in memory (see [`Merger`]), and
+/// is the first child. Every file of the table is a candidate, and the rows of
+/// the source matching no target row are inserted once all files were read,
+/// by the last child.
+///
+/// The written files are staged under a name ending with a suffix unique to
+/// the operation, which the table does not list. If any write fails, the
+/// staged files are removed and the table keeps its previous contents. Once
+/// every file was rewritten, the staged files are moved to their final name
+/// and the original files removed. An object store cannot replace several
+/// files atomically, so a concurrent reader may briefly see both the original
+/// and the rewritten version of a file during this last step, and an
+/// interruption (e.g. a crash) during it can leave both in the table.
+///
+/// [`ListingTable`]: crate::ListingTable
+#[derive(Debug)]
+pub(crate) struct CopyOnWriteExec {
+    op: CopyOnWriteOp,
+    object_store_url: ObjectStoreUrl,
+    /// The prefix of the files of the table, where the files are staged
+    prefix: Path,
+    /// Suffix of the names of the staged files, see [`staged_suffix`]
+    staged_suffix: String,
+    rewrites: Arc<Vec<FileRewrite>>,
+    merge: Option<MergeInput>,
+    schema: SchemaRef,
+    properties: Arc<PlanProperties>,
+}
+
+impl CopyOnWriteExec {
+    pub(crate) fn new(
+        op: CopyOnWriteOp,
+        object_store_url: ObjectStoreUrl,
+        prefix: Path,
+        staged_suffix: String,
+        rewrites: Vec<FileRewrite>,
+    ) -> Self {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "count",
+            DataType::UInt64,
+            false,
+        )]));
+        let properties = PlanProperties::new(
+            EquivalenceProperties::new(Arc::clone(&schema)),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        );
+        Self {
+            op,
+            object_store_url,
+            prefix,
+            staged_suffix,
+            rewrites: Arc::new(rewrites),
+            merge: None,
+            schema,
+            properties: Arc::new(properties),
+        }
+    }
+
+    /// Merges the source of `merge` into the table while rewriting the files
+    pub(crate) fn with_merge(mut self, merge: MergeInput) -> Self {
+        self.merge = Some(merge);
+        self
+    }
+}
+
+impl DisplayAs for CopyOnWriteExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(
+                    f,
+                    "CopyOnWriteExec: op={}, candidate_files={}",
+                    self.op,
+                    self.rewrites.len()
+                )
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "op={}", self.op)?;
+                write!(f, "candidate_files={}", self.rewrites.len())
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for CopyOnWriteExec {
+    fn name(&self) -> &str {
+        "CopyOnWriteExec"
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        let source = self.merge.iter().map(|merge| &merge.source);
+        let rewrites = self
+            .rewrites
+            .iter()
+            .flat_map(|rewrite| [&rewrite.matches, &rewrite.rewrite]);
+        let insert = self.merge.iter().flat_map(|merge| &merge.insert);
+        source.chain(rewrites).chain(insert).collect()
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false; self.children().len()]
+    }
+
+    fn replace_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+        _: ReplaceChildrenOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        validate_child_count!(self, children);
+        let mut children = children.into_iter();
+        let source = match &self.merge {
+            Some(_) => children.next(),
+            None => None,
+        };
+        let rewrites = self
+            .rewrites
+            .iter()
+            .map(|rewrite| {
+                let (Some(matches), Some(plan)) = (children.next(), children.next())
+                else {
+                    return internal_err!("CopyOnWriteExec is missing children");
+                };
+                Ok(FileRewrite {
+                    matches,
+                    rewrite: plan,
+                    ..rewrite.clone()
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let merge = match (&self.merge, source) {
+            (Some(merge), Some(source)) => Some(MergeInput {
+                source,
+                insert: merge.insert.as_ref().and_then(|_| children.next()),
+                ..merge.clone()
+            }),
+            _ => None,
+        };
+        Ok(Arc::new(Self {
+            rewrites: Arc::new(rewrites),
+            merge,
+            op: self.op,
+            object_store_url: self.object_store_url.clone(),
+            prefix: self.prefix.clone(),
+            staged_suffix: self.staged_suffix.clone(),
+            schema: Arc::clone(&self.schema),
+            properties: Arc::clone(&self.properties),
+        }))
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.replace_children(
+            children,
+            ReplaceChildrenOptions::new(ChildrenPropertiesMode::Recompute),
+        )
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return internal_err!("CopyOnWriteExec can only be called on partition 0!");
+        }
+        let object_store = context.runtime_env().object_store(&self.object_store_url)?;
+        let rewrites = Arc::clone(&self.rewrites);
+        let merge = self.merge.clone();
+        let staging = Staging {
+            prefix: self.prefix.clone(),
+            suffix: self.staged_suffix.clone(),
+            object_store,
+        };
+        let schema = Arc::clone(&self.schema);
+
+        let stream = stream::once(async move {
+            let result = write_files(&rewrites, merge.as_ref(), &staging, context).await;
+            if let Some(merge) = &merge {
+                // Release the memory of the source
+                merge.merger.lock().expect("Can't lock merger").take();
+            }
+            let rows_affected = match result {
+                Ok((rows_affected, replaced)) => {
+                    staging.commit(replaced).await?;
+                    rows_affected
+                }
+                Err(e) => {
+                    // The staged files may have been partially written
+                    if let Err(cleanup) = staging.remove_staged_files().await {
+                        log::warn!("Failed to remove rewritten files: {cleanup}");
+                    }
+                    return Err(e);
+                }
+            };
+            RecordBatch::try_new(
+                schema,
+                vec![Arc::new(UInt64Array::from(vec![rows_affected])) as ArrayRef],
+            )
+            .map_err(Into::into)
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(&self.schema),
+            stream,
+        )))
+    }
+
+    fn apply_expressions(
+        &self,
+        _f: &mut dyn FnMut(&Arc<dyn PhysicalExpr>) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        Ok(TreeNodeRecursion::Continue)
+    }
+}
+
+/// Writes the staged files: loads the source of `merge`, if any, rewrites the
+/// files with affected rows, and writes the inserted rows. Returns the number
+/// of affected rows and the files to remove once the staged files are moved
+/// in place.
+async fn write_files(
+    rewrites: &[FileRewrite],
+    merge: Option<&MergeInput>,
+    staging: &Staging,
+    context: Arc<TaskContext>,
+) -> Result<(u64, Vec<Path>)> {
+    if let Some(merge) = merge {
+        let mut merger = merge.spec.merger(&context);
+        merger
+            .load_source(execute_stream(
+                Arc::clone(&merge.source),
+                Arc::clone(&context),
+            )?)
+            .await?;
+        *merge.merger.lock().expect("Can't lock merger") = Some(merger);
+    }
+
+    let mut rows_affected = 0;
+    let mut replaced = vec![];
+    for rewrite in rewrites {
+        let matches = count_rows(&rewrite.matches, &context).await?;
+        if matches == 0 {
+            continue;
+        }
+        let rows_written = sum_counts(&rewrite.rewrite, &context).await?;
+        rows_affected += matches;
+        replaced.push(rewrite.source.clone());
+        if let Some(staged) = &rewrite.staged
+            && rows_written == 0
+        {
+            // Every row of the file was deleted: don't keep an empty file
+            remove_files(&staging.object_store, vec![staged.clone()]).await?;
+        }
+    }
+
+    // The rows to insert are only known once every file was read
+    if let Some(insert) = merge.and_then(|merge| merge.insert.as_ref()) {
+        rows_affected += sum_counts(insert, &context).await?;
+    }
+    Ok((rows_affected, replaced))
+}
+
+/// Returns a suffix, unique to an operation, for the names of the files it
+/// stages: the table only lists files with its file extension, so the staged
+/// files are not read by concurrent queries.
+pub(crate) fn staged_suffix() -> String {
+    let id = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 16);
+    format!(".{id}.staged")
+}
+
+/// The files staged by a [`CopyOnWriteExec`]: the files under `prefix` whose
+/// name ends with `suffix`
+struct Staging {
+    prefix: Path,
+    suffix: String,
+    object_store: Arc<dyn ObjectStore>,
+}
+
+impl Staging {
+    /// Lists the staged files
+    async fn staged_files(&self) -> Result<Vec<Path>> {
+        Ok(self
+            .object_store
+            .list(Some(&self.prefix))
+            .map_ok(|meta| meta.location)
+            .try_filter(|location| {
+                futures::future::ready(location.as_ref().ends_with(&self.suffix))
+            })
+            .try_collect()
+            .await?)
+    }
+
+    /// Removes the staged files
+    async fn remove_staged_files(&self) -> Result<()> {
+        remove_files(&self.object_store, self.staged_files().await?).await
+    }
+
+    /// Moves the staged files to their final name, without the suffix, then
+    /// removes the `replaced` files. If a staged file cannot be moved, the
+    /// files moved so far and the remaining staged files are removed instead.
+    async fn commit(&self, replaced: Vec<Path>) -> Result<()> {
+        let staged = self.staged_files().await?;
+        let targets = staged
+            .iter()
+            .map(|staged| {
+                let staged = staged.as_ref();
+                Path::from(&staged[..staged.len() - self.suffix.len()])
+            })
+            .collect::<Vec<_>>();
+        for (i, (staged_file, target)) in staged.iter().zip(&targets).enumerate() {
+            if let Err(e) = self.object_store.rename(staged_file, target).await {
+                let written = targets[..i].iter().chain(&staged[i..]).cloned().collect();
+                if let Err(cleanup) = remove_files(&self.object_store, written).await {
+                    log::warn!("Failed to remove rewritten files: {cleanup}");
+                }
+                return Err(e.into());
+            }
+        }
+        remove_files(&self.object_store, replaced).await
+    }
+}
+
+/// The rows of a target file of a `MERGE INTO` returned by a [`MergeRowsExec`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MergeRows {
+    /// One row without columns per row updated or deleted
+    Affected,
+    /// The new contents of the file: the rows kept and the rows updated
+    Rewritten,
+    /// The rows inserted for the source rows matching no target row, once
+    /// every file was read (the plan has no input)
+    Inserted,
+}
+
+impl fmt::Display for MergeRows {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Affected => write!(f, "affected"),
+            Self::Rewritten => write!(f, "rewritten"),
+            Self::Inserted => write!(f, "inserted"),
+        }
+    }
+}
+
+/// Merges the rows of a file of a [`ListingTable`] with the source of a
+/// `MERGE INTO`, using the [`Merger`] loaded by the [`CopyOnWriteExec`]
+///
+/// [`ListingTable`]: crate::ListingTable
+#[derive(Debug)]
+pub(crate) struct MergeRowsExec {
+    input: Option<Arc<dyn ExecutionPlan>>,
+    rows: MergeRows,
+    merger: SharedMerger,
+    properties: Arc<PlanProperties>,
+}
+
+impl MergeRowsExec {
+    /// Creates a new [`MergeRowsExec`] returning `rows` of `input`, a scan of
+    /// a file of the table with the `table_schema`
+    pub(crate) fn new(
+        input: Option<Arc<dyn ExecutionPlan>>,
+        rows: MergeRows,
+        merger: SharedMerger,
+        table_schema: SchemaRef,
+    ) -> Self {
+        let schema = match rows {
+            MergeRows::Affected => Arc::new(Schema::empty()),
+            MergeRows::Rewritten | MergeRows::Inserted => table_schema,
+        };
+        let properties = PlanProperties::new(
+            EquivalenceProperties::new(schema),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        );
+        Self {
+            input,
+            rows,
+            merger,
+            properties: Arc::new(properties),
+        }
+    }
+}
+
+impl DisplayAs for MergeRowsExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "MergeRowsExec: rows={}", self.rows)
+            }
+            DisplayFormatType::TreeRender => write!(f, "rows={}", self.rows),
+        }
+    }
+}
+
+impl ExecutionPlan for MergeRowsExec {
+    fn name(&self) -> &str {
+        "MergeRowsExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.properties
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        self.input.iter().collect()
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        self.input_distribution_requirements().into_per_child()
+    }
+
+    fn input_distribution_requirements(&self) -> InputDistributionRequirements {
+        InputDistributionRequirements::new(
+            self.input
+                .iter()
+                .map(|_| Distribution::SinglePartition)
+                .collect(),
+        )
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false; self.input.iter().len()]
+    }
+
+    fn replace_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+        _: ReplaceChildrenOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        validate_child_count!(self, children);
+        Ok(Arc::new(Self {
+            input: children.into_iter().next(),
+            rows: self.rows,
+            merger: Arc::clone(&self.merger),
+            properties: Arc::clone(&self.properties),
+        }))
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.replace_children(
+            children,
+            ReplaceChildrenOptions::new(ChildrenPropertiesMode::Recompute),
+        )
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return internal_err!("MergeRowsExec can only be called on partition 0!");
+        }
+        let schema = self.schema();
+        let merger = Arc::clone(&self.merger);
+        let rows = self.rows;
+        let Some(input) = &self.input else {
+            let inserted = with_merger(&merger, |merger| Ok(merger.inserted()?.1));
+            let stream = stream::iter(inserted.map_or_else(
+                |e| vec![Err(e)],
+                |batches| batches.into_iter().map(Ok).collect(),
+            ));
+            return Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)));
+        };
+
+        let output_schema = Arc::clone(&schema);
+        let stream = input
+            .execute(0, context)?
+            .map(move |batch| -> Result<_> {
+                let merged = with_merger(&merger, |merger| merger.merge_target(&batch?))?;
+                let batches = match rows {
+                    MergeRows::Affected => vec![RecordBatch::try_new_with_options(
+                        Arc::clone(&output_schema),
+                        vec![],
+                        &RecordBatchOptions::new()
+                            .with_row_count(Some(merged.rows_affected)),
+                    )?],
+                    _ => std::iter::once(merged.kept).chain(merged.updated).collect(),
+                };
+                Ok(stream::iter(
+                    batches.into_iter().map(Ok::<_, DataFusionError>),
+                ))
+            })
+            .try_flatten();
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
+
+    fn apply_expressions(
+        &self,
+        _f: &mut dyn FnMut(&Arc<dyn PhysicalExpr>) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        Ok(TreeNodeRecursion::Continue)
+    }
+}
+
+/// Calls `f` with the loaded [`Merger`]
+fn with_merger<T>(
+    merger: &SharedMerger,
+    f: impl FnOnce(&mut Merger) -> Result<T>,
+) -> Result<T> {
+    match merger.lock().expect("Can't lock merger").as_mut() {
+        Some(merger) => f(merger),
+        None => {
+            internal_err!("MergeRowsExec executed before the MERGE source was loaded")
+        }
+    }
+}
+
+/// Returns the number of rows produced by `plan`
+async fn count_rows(
+    plan: &Arc<dyn ExecutionPlan>,
+    context: &Arc<TaskContext>,
+) -> Result<u64> {
+    execute_stream(Arc::clone(plan), Arc::clone(context))?
+        .try_fold(
+            0,
+            |acc, batch| async move { Ok(acc + batch.num_rows() as u64) },
+        )
+        .await
+}
+
+/// Returns the sum of the `count` column produced by a sink `plan`
+async fn sum_counts(
+    plan: &Arc<dyn ExecutionPlan>,
+    context: &Arc<TaskContext>,
+) -> Result<u64> {
+    execute_stream(Arc::clone(plan), Arc::clone(context))?
+        .try_fold(0, |acc, batch| async move {
+            let counts = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<UInt64Array>()
+                .map(|counts| counts.values().iter().sum::<u64>())
+                .unwrap_or_default();
+            Ok(acc + counts)
+        })
+        .await
+}
+
+/// Removes `paths` from the object store, ignoring files that do not exist
+async fn remove_files(
+    object_store: &Arc<dyn ObjectStore>,
+    paths: Vec<Path>,
+) -> Result<()> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+    object_store
+        .delete_stream(stream::iter(paths.into_iter().map(Ok)).boxed())
+        .or_else(|e| async move {
+            match e {
+                object_store::Error::NotFound { path, .. } => Ok(Path::from(path)),
+                e => Err(e),
+            }
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::PutPayload;
+    use object_store::memory::InMemory;
+
+    const SUFFIX: &str = ".abc.staged";
+
+    fn staging(object_store: &Arc<dyn ObjectStore>) -> Staging {
+        Staging {
+            prefix: Path::from("t"),
+            suffix: SUFFIX.to_string(),
+            object_store: Arc::clone(object_store),
+        }
+    }
+
+    async fn list(object_store: &Arc<dyn ObjectStore>) -> Result<Vec<String>> {
+        let mut files = object_store
+            .list(None)
+            .map_ok(|meta| meta.location.to_string())
+            .try_collect::<Vec<_>>()
+            .await?;
+        files.sort();
+        Ok(files)
+    }
+
+    #[tokio::test]
+    async fn commit_moves_staged_files_in_place() -> Result<()> {
+        let object_store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        for path in [
+            "t/old.parquet",
+            "t/new.parquet.abc.staged",
+            "t/p=1/inserted.parquet.abc.staged",
+            // Staged by another operation
+            "t/other.parquet.xyz.staged",
+        ] {
+            object_store
+                .put(&Path::from(path), PutPayload::from("data"))
+                .await?;
+        }
+
+        staging(&object_store)
+            .commit(vec![Path::from("t/old.parquet")])
+            .await?;
+        assert_eq!(
+            list(&object_store).await?,
+            vec![
+                "t/new.parquet",
+                "t/other.parquet.xyz.staged",
+                "t/p=1/inserted.parquet"
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn failed_write_keeps_original_files() -> Result<()> {
+        let object_store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        for path in ["t/a.parquet", "t/a2.parquet.abc.staged", "t/b.parquet"] {
+            object_store
+                .put(&Path::from(path), PutPayload::from("data"))
+                .await?;
+        }
+
+        staging(&object_store).remove_staged_files().await?;
+        assert_eq!(
+            list(&object_store).await?,
+            vec!["t/a.parquet", "t/b.parquet"]
+        );
+        Ok(())
+    }
+}
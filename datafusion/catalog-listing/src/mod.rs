@@ -26,6 +26,7 @@
 #![cfg_attr(not(test), deny(clippy::clone_on_ref_ptr))]
 
 mod config;
+mod copy_on_write;
 pub mod helpers;
 mod options;
 mod table;
@@ -16,16 +16,24 @@
 // under the License.
 
 use crate::config::SchemaSource;
+use crate::copy_on_write::{
+    CopyOnWriteExec, CopyOnWriteOp, FileRewrite, MergeInput, MergeRows, MergeRowsExec,
+    SharedMerger, staged_suffix,
+};
 use crate::helpers::{
     expr_applicable_for_cols, filter_partitioned_file, pruned_partition_list,
 };
 use crate::{ListingOptions, ListingTableConfig};
+use arrow::array::RecordBatchOptions;
 use arrow::datatypes::{Field, Schema, SchemaBuilder, SchemaRef};
+use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
-use datafusion_catalog::{ScanArgs, ScanResult, Session, TableProvider};
+use datafusion_catalog::merge_into::create_merge_clauses;
+use datafusion_catalog::{ScanArgs, ScanResult, Session, TableProvider, TableSnapshot};
 use datafusion_common::stats::Precision;
 use datafusion_common::{
-    Constraints, DFSchema, SchemaExt, Statistics, internal_datafusion_err, plan_err,
+    Column, ColumnStatistics, Constraints, DFSchema, DFSchemaRef, ScalarValue, SchemaExt,
+    Statistics, internal_datafusion_err, internal_err, not_impl_err, plan_err,
     project_schema,
 };
 use datafusion_datasource::file::FileSource;
@@ -42,20 +50,36 @@ use datafusion_datasource::{
 use datafusion_execution::cache::cache_manager::{
     CachedFileMetadata, FileStatisticsCache, SchemaFingerprint, TableScopedPath,
 };
-use datafusion_expr::dml::InsertOp;
+use datafusion_execution::object_store::ObjectStoreUrl;
+use datafusion_expr::dml::{InsertOp, MergeIntoClause};
 use datafusion_expr::execution_props::ExecutionProps;
 use datafusion_expr::physical_planning_context::PhysicalPlanningContext;
+use datafusion_expr::utils::conjunction;
 use datafusion_expr::{
-    Expr, Partitioning as LogicalPartitioning, TableProviderFilterPushDown, TableType,
+    AlterTableOperation, Expr, ExprSchemable, Partitioning as LogicalPartitioning,
+    TableProviderFilterPushDown, TableType, when,
 };
+use datafusion_physical_expr::expressions::lit;
 use datafusion_physical_expr::{create_lex_ordering, create_physical_partitioning};
-use datafusion_physical_expr_adapter::PhysicalExprAdapterFactory;
+use datafusion_physical_expr_adapter::{
+    DefaultPhysicalExprAdapterFactory, PhysicalExprAdapterFactory,
+    SCHEMA_EVOLUTION_METADATA_KEY, SchemaEvolution, SchemaEvolutionAdapterFactory,
+    replace_columns_with_literals,
+};
+use datafusion_physical_expr_common::physical_expr::PhysicalExpr;
 use datafusion_physical_expr_common::sort_expr::LexOrdering;
 use datafusion_physical_plan::ExecutionPlan;
 use datafusion_physical_plan::empty::EmptyExec;
+use datafusion_physical_plan::filter::FilterExec;
+use datafusion_physical_plan::merge_into::MergeIntoSpec;
+use datafusion_physical_plan::metrics::Count;
+use datafusion_physical_plan::projection::ProjectionExec;
+use datafusion_pruning::FilePruner;
 use futures::future::BoxFuture;
 use futures::{Stream, StreamExt, TryStreamExt, future, stream};
-use object_store::ObjectStore;
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStore};
+use rand::distr::SampleString;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
@@ -203,10 +227,17 @@ pub struct ListingTable {
     column_defaults: HashMap<String, Expr>,
     /// Optional [`PhysicalExprAdapterFactory`] for creating physical expression adapters
     expr_adapter_factory: Option<Arc<dyn PhysicalExprAdapterFactory>>,
+    /// Changes of the columns of the table made after some of its files were
+    /// written, see [`TableProvider::alter`]. Also stored in the metadata of
+    /// `file_schema` and `table_schema`, so that they are kept wherever the
+    /// schema of the table is persisted.
+    schema_evolution: Arc<SchemaEvolution>,
     /// Precomputed fingerprint of `file_schema` for file-statistics cache
     /// validation. Constant for the table, so computed once here instead of per
     /// file.
     file_schema_fingerprint: Arc<SchemaFingerprint>,
+    /// If set, only these files are scanned, see [`TableProvider::appended_rows`]
+    selected_files: Option<Arc<HashSet<Path>>>,
 }
 
 impl ListingTable {
@@ -240,6 +271,9 @@ impl ListingTable {
         let file_schema_fingerprint =
             Arc::new(SchemaFingerprint::from_schema(&file_schema));
 
+        let schema_evolution =
+            Arc::new(SchemaEvolution::try_from_schema_metadata(&file_schema)?);
+
         let table = Self {
             table_paths: config.table_paths,
             file_schema,
@@ -251,7 +285,9 @@ impl ListingTable {
             constraints: Constraints::default(),
             column_defaults: HashMap::new(),
             expr_adapter_factory: config.expr_adapter_factory,
+            schema_evolution,
             file_schema_fingerprint,
+            selected_files: None,
         };
 
         Ok(table)
@@ -303,6 +339,28 @@ impl ListingTable {
         self.schema_source
     }
 
+    /// Set the [`SchemaEvolution`] used to read files written before the
+    /// columns of the table were altered, and store it in the metadata of the
+    /// schema of the table.
+    ///
+    /// Returns an error if it cannot be stored, see
+    /// [`SchemaEvolution::to_metadata_value`].
+    pub fn with_schema_evolution(
+        mut self,
+        schema_evolution: Arc<SchemaEvolution>,
+    ) -> datafusion_common::Result<Self> {
+        let file_schema =
+            store_schema_evolution(self.file_schema.as_ref().clone(), &schema_evolution)?;
+        self.set_file_schema(Arc::new(file_schema));
+        self.schema_evolution = schema_evolution;
+        Ok(self)
+    }
+
+    /// Get the [`SchemaEvolution`] of the table
+    pub fn schema_evolution(&self) -> &Arc<SchemaEvolution> {
+        &self.schema_evolution
+    }
+
     /// Deprecated: Set the [`SchemaAdapterFactory`] for this [`ListingTable`]
     ///
     /// `SchemaAdapterFactory` has been removed. Use [`ListingTableConfig::with_expr_adapter_factory`]
@@ -352,6 +410,22 @@ impl ListingTable {
         self.options.format.file_source(table_schema)
     }
 
+    /// Returns the [`PhysicalExprAdapterFactory`] for scans of this table,
+    /// which applies the [`SchemaEvolution`] of the table if it was altered
+    fn expr_adapter_factory(&self) -> Option<Arc<dyn PhysicalExprAdapterFactory>> {
+        if self.schema_evolution.is_empty() {
+            return self.expr_adapter_factory.clone();
+        }
+        let inner = self
+            .expr_adapter_factory
+            .clone()
+            .unwrap_or_else(|| Arc::new(DefaultPhysicalExprAdapterFactory));
+        Some(Arc::new(SchemaEvolutionAdapterFactory::new(
+            Arc::clone(&self.schema_evolution),
+            inner,
+        )))
+    }
+
     /// Creates output ordering from user-specified file_sort_order or derives
     /// from file orderings when user doesn't specify.
     ///
@@ -466,6 +540,57 @@ fn filter_file_group_by_partition_filters(
     Ok(FileGroup::new(files))
 }
 
+/// Evaluates `expr`, which does not reference any column, to a value
+fn evaluate_constant(
+    state: &dyn Session,
+    expr: &Expr,
+) -> datafusion_common::Result<ScalarValue> {
+    let batch = RecordBatch::try_new_with_options(
+        Arc::new(Schema::empty()),
+        vec![],
+        &RecordBatchOptions::new().with_row_count(Some(1)),
+    )?;
+    let value = state
+        .create_physical_expr(expr.clone(), &DFSchema::empty())?
+        .evaluate(&batch)?
+        .into_array(1)?;
+    ScalarValue::try_from_array(&value, 0)
+}
+
+/// Returns `schema` with `schema_evolution` stored in its metadata, or
+/// without it if no changes were recorded
+fn store_schema_evolution(
+    schema: Schema,
+    schema_evolution: &SchemaEvolution,
+) -> datafusion_common::Result<Schema> {
+    let mut metadata = schema.metadata().clone();
+    if schema_evolution.is_empty() {
+        metadata.remove(SCHEMA_EVOLUTION_METADATA_KEY);
+    } else {
+        metadata.insert(
+            SCHEMA_EVOLUTION_METADATA_KEY.to_string(),
+            schema_evolution.to_metadata_value()?,
+        );
+    }
+    Ok(schema.with_metadata(metadata))
+}
+
+/// Returns the schema of the files written to a table with schema `schema`.
+///
+/// The schema evolution of the table does not describe the written files, so
+/// it is not stored in them.
+fn sink_schema(schema: &SchemaRef) -> SchemaRef {
+    if !schema
+        .metadata()
+        .contains_key(SCHEMA_EVOLUTION_METADATA_KEY)
+    {
+        return Arc::clone(schema);
+    }
+    let mut metadata = schema.metadata().clone();
+    metadata.remove(SCHEMA_EVOLUTION_METADATA_KEY);
+    Arc::new(schema.as_ref().clone().with_metadata(metadata))
+}
+
 // Expressions can be used for partition pruning if they can be evaluated using
 // only the partition columns and there are partition columns.
 fn can_be_evaluated_for_partition_pruning(
@@ -572,6 +697,139 @@ impl TableProvider for ListingTable {
     fn get_column_default(&self, column: &str) -> Option<&Expr> {
         self.column_defaults.get(column)
     }
+
+    /// Deletes the rows matching `filters` by rewriting the files containing
+    /// such rows without them. See [`CopyOnWriteExec`] for details.
+    // Hand-written `#[async_trait]` expansion to reduce compile time. See
+    // <https://github.com/apache/datafusion/issues/13814#issuecomment-5292709677>
+    fn delete_from<'life0, 'life1, 'async_trait>(
+        &'life0 self,
+        state: &'life1 dyn Session,
+        filters: Vec<Expr>,
+    ) -> BoxFuture<'async_trait, datafusion_common::Result<Arc<dyn ExecutionPlan>>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        self.delete_from_boxed(state, filters)
+    }
+
+    /// Updates the rows matching `filters` by rewriting the files containing
+    /// such rows. See [`CopyOnWriteExec`] for details.
+    // Hand-written `#[async_trait]` expansion to reduce compile time. See
+    // <https://github.com/apache/datafusion/issues/13814#issuecomment-5292709677>
+    fn update<'life0, 'life1, 'async_trait>(
+        &'life0 self,
+        state: &'life1 dyn Session,
+        assignments: Vec<(String, Expr)>,
+        filters: Vec<Expr>,
+    ) -> BoxFuture<'async_trait, datafusion_common::Result<Arc<dyn ExecutionPlan>>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        self.update_boxed(state, assignments, filters)
+    }
+
+    /// Alters the columns of the table without rewriting its files, which is
+    /// only supported by file formats that read them through a
+    /// [`SchemaEvolution`], or changes the options of its file format.
+    // Hand-written `#[async_trait]` expansion to reduce compile time. See
+    // <https://github.com/apache/datafusion/issues/13814#issuecomment-5292709677>
+    fn alter<'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        state: &'life1 dyn Session,
+        operation: &'life2 AlterTableOperation,
+    ) -> BoxFuture<'async_trait, datafusion_common::Result<Arc<dyn TableProvider>>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move { self.alter_inner(state, operation) })
+    }
+
+    /// Lists the files of the table. Files are never modified by appends,
+    /// which add new files.
+    // Hand-written `#[async_trait]` expansion to reduce compile time. See
+    // <https://github.com/apache/datafusion/issues/13814#issuecomment-5292709677>
+    fn snapshot<'life0, 'life1, 'async_trait>(
+        &'life0 self,
+        state: &'life1 dyn Session,
+    ) -> BoxFuture<'async_trait, datafusion_common::Result<Option<TableSnapshot>>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(self.snapshot_inner(state))
+    }
+
+    fn appended_rows(
+        &self,
+        from: Option<&TableSnapshot>,
+        to: &TableSnapshot,
+    ) -> datafusion_common::Result<Option<Arc<dyn TableProvider>>> {
+        let Some(to) = to.downcast_ref::<ListingTableSnapshot>() else {
+            return Ok(None);
+        };
+        let selected_files = match from {
+            None => to.files.keys().cloned().collect(),
+            Some(from) => {
+                let Some(from) = from.downcast_ref::<ListingTableSnapshot>() else {
+                    return Ok(None);
+                };
+                let kept = from
+                    .files
+                    .iter()
+                    .all(|(location, meta)| to.files.get(location) == Some(meta));
+                if !kept {
+                    return Ok(None);
+                }
+                to.files
+                    .keys()
+                    .filter(|location| !from.files.contains_key(*location))
+                    .cloned()
+                    .collect()
+            }
+        };
+        let mut table = self.clone();
+        table.selected_files = Some(Arc::new(selected_files));
+        Ok(Some(Arc::new(table)))
+    }
+
+    fn same_snapshot(&self, a: &TableSnapshot, b: &TableSnapshot) -> bool {
+        let (Some(a), Some(b)) = (
+            a.downcast_ref::<ListingTableSnapshot>(),
+            b.downcast_ref::<ListingTableSnapshot>(),
+        ) else {
+            return false;
+        };
+        a.files == b.files
+    }
+
+    /// Merges `source` into the table by rewriting the files containing
+    /// updated or deleted rows. See [`CopyOnWriteExec`] for details.
+    // Hand-written `#[async_trait]` expansion to reduce compile time. See
+    // <https://github.com/apache/datafusion/issues/13814#issuecomment-5292709677>
+    fn merge_into<'life0, 'life1, 'async_trait>(
+        &'life0 self,
+        state: &'life1 dyn Session,
+        source: Arc<dyn ExecutionPlan>,
+        merge_schema: DFSchemaRef,
+        on: Expr,
+        clauses: Vec<MergeIntoClause>,
+    ) -> BoxFuture<'async_trait, datafusion_common::Result<Arc<dyn ExecutionPlan>>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        self.merge_into_boxed(state, source, merge_schema, on, clauses)
+    }
 }
 
 impl ListingTable {
@@ -737,7 +995,7 @@ impl ListingTable {
             .with_limit(limit)
             .with_output_ordering(output_ordering)
             .with_output_partitioning(output_partitioning)
-            .with_expr_adapter(self.expr_adapter_factory.clone())
+            .with_expr_adapter(self.expr_adapter_factory())
             .build();
 
         // create the execution plan
@@ -819,13 +1077,7 @@ impl ListingTable {
             state.config_options().execution.keep_partition_by_columns;
 
         // Invalidate cache entries for this table if they exist
-        if let Some(lfc) = state.runtime_env().cache_manager.get_list_files_cache() {
-            let key = TableScopedPath {
-                table: table_path.get_table_ref().clone(),
-                path: table_path.prefix().clone(),
-            };
-            let _ = lfc.remove(&key);
-        }
+        self.invalidate_list_files_cache(state);
 
         // Sink related option, apart from format
         let config = FileSinkConfig {
@@ -833,7 +1085,7 @@ impl ListingTable {
             object_store_url: self.table_paths()[0].object_store(),
             table_paths: self.table_paths().clone(),
             file_group,
-            output_schema: self.schema(),
+            output_schema: sink_schema(&self.table_schema),
             table_partition_cols: self.options.table_partition_cols.clone(),
             insert_op,
             keep_partition_by_columns,
@@ -851,6 +1103,552 @@ impl ListingTable {
             .create_writer_physical_plan(input, state, config, order_requirements)
             .await
     }
+
+    /// Removes the cached file listing of this table, which is invalidated by
+    /// writes
+    fn invalidate_list_files_cache(&self, state: &dyn Session) {
+        let table_path = &self.table_paths()[0];
+        if let Some(lfc) = state.runtime_env().cache_manager.get_list_files_cache() {
+            let key = TableScopedPath {
+                table: table_path.get_table_ref().clone(),
+                path: table_path.prefix().clone(),
+            };
+            let _ = lfc.remove(&key);
+        }
+    }
+
+    fn delete_from_boxed<'a>(
+        &'a self,
+        state: &'a dyn Session,
+        filters: Vec<Expr>,
+    ) -> BoxFuture<'a, datafusion_common::Result<Arc<dyn ExecutionPlan>>> {
+        Box::pin(self.delete_from_inner(state, filters))
+    }
+
+    async fn delete_from_inner(
+        &self,
+        state: &dyn Session,
+        filters: Vec<Expr>,
+    ) -> datafusion_common::Result<Arc<dyn ExecutionPlan>> {
+        // Keep the rows for which the predicate is false or NULL
+        // (SQL three-valued logic), or no rows without a predicate
+        let keep = conjunction(filters.clone())
+            .map(|predicate| predicate.is_not_true())
+            .unwrap_or(Expr::Literal(ScalarValue::Boolean(Some(false)), None));
+        let columns = self
+            .file_schema
+            .fields()
+            .iter()
+            .map(|field| Expr::Column(Column::new_unqualified(field.name())))
+            .collect();
+        self.copy_on_write(state, CopyOnWriteOp::Delete, &filters, Some(keep), columns)
+            .await
+    }
+
+    fn update_boxed<'a>(
+        &'a self,
+        state: &'a dyn Session,
+        assignments: Vec<(String, Expr)>,
+        filters: Vec<Expr>,
+    ) -> BoxFuture<'a, datafusion_common::Result<Arc<dyn ExecutionPlan>>> {
+        Box::pin(self.update_inner(state, assignments, filters))
+    }
+
+    async fn update_inner(
+        &self,
+        state: &dyn Session,
+        assignments: Vec<(String, Expr)>,
+        filters: Vec<Expr>,
+    ) -> datafusion_common::Result<Arc<dyn ExecutionPlan>> {
+        let mut assignments = assignments.into_iter().collect::<HashMap<_, _>>();
+        for column_name in assignments.keys() {
+            if self
+                .options
+                .table_partition_cols
+                .iter()
+                .any(|(name, _)| name == column_name)
+            {
+                return plan_err!(
+                    "UPDATE failed: cannot update partition column '{column_name}'"
+                );
+            }
+            if self.file_schema.field_with_name(column_name).is_err() {
+                let available_columns = self
+                    .file_schema
+                    .fields()
+                    .iter()
+                    .map(|f| f.name().as_str())
+                    .collect::<Vec<_>>();
+                return plan_err!(
+                    "UPDATE failed: column '{}' does not exist. Available columns: {}",
+                    column_name,
+                    available_columns.join(", ")
+                );
+            }
+        }
+
+        let df_schema = DFSchema::try_from(Arc::clone(&self.table_schema))?;
+        let predicate = conjunction(filters.clone());
+        let columns = self
+            .file_schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let column = Expr::Column(Column::new_unqualified(field.name()));
+                let Some(value) = assignments.remove(field.name()) else {
+                    return Ok(column);
+                };
+                let value = value.cast_to(field.data_type(), &df_schema)?;
+                // Only evaluate the new value for the matching rows, so that
+                // e.g. a division by zero in other rows does not fail the update
+                let value = match &predicate {
+                    Some(predicate) => {
+                        when(predicate.clone().is_true(), value).otherwise(column)?
+                    }
+                    None => value,
+                };
+                Ok(value.alias(field.name()))
+            })
+            .collect::<datafusion_common::Result<_>>()?;
+        self.copy_on_write(state, CopyOnWriteOp::Update, &filters, None, columns)
+            .await
+    }
+
+    /// Plans a copy-on-write `op`: every file that may contain rows matching
+    /// `filters` is rewritten to a new file in the same directory, keeping the
+    /// rows matching `keep` (all rows if `None`) and computing `columns` (the
+    /// columns of the file, in order).
+    ///
+    /// Files are skipped without being read when their partition values or
+    /// statistics show they cannot contain matching rows.
+    async fn copy_on_write(
+        &self,
+        state: &dyn Session,
+        op: CopyOnWriteOp,
+        filters: &[Expr],
+        keep: Option<Expr>,
+        columns: Vec<Expr>,
+    ) -> datafusion_common::Result<Arc<dyn ExecutionPlan>> {
+        let table_path = self.copy_on_write_table_path(op)?;
+        let object_store_url = table_path.object_store();
+        let df_schema = DFSchema::try_from(Arc::clone(&self.table_schema))?;
+
+        let predicate = match conjunction(filters.to_vec()) {
+            Some(predicate) => state.create_physical_expr(predicate, &df_schema)?,
+            None => lit(true),
+        };
+        let keep = keep
+            .map(|keep| state.create_physical_expr(keep, &df_schema))
+            .transpose()?;
+        let columns = columns
+            .into_iter()
+            .zip(self.file_schema.fields())
+            .map(|(expr, field)| {
+                Ok((
+                    state.create_physical_expr(expr, &df_schema)?,
+                    field.name().clone(),
+                ))
+            })
+            .collect::<datafusion_common::Result<Vec<_>>>()?;
+
+        // Only list the partitions the filters may match
+        let partition_column_names = self
+            .options
+            .table_partition_cols
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        let partition_filters = filters
+            .iter()
+            .filter(|filter| {
+                can_be_evaluated_for_partition_pruning(&partition_column_names, filter)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let ListFilesResult { file_groups, .. } = self
+            .list_files_for_scan(state, &partition_filters, None)
+            .await?;
+
+        let staged_suffix = staged_suffix();
+        let mut rewrites = vec![];
+        for file in file_groups.into_iter().flat_map(FileGroup::into_inner) {
+            if !self.may_contain_matches(&predicate, &file)? {
+                continue;
+            }
+
+            // The matching rows are counted and the file rewritten by separate
+            // scans, as a scan cannot be executed more than once
+            let scan = self.scan_file(state, &object_store_url, &file).await?;
+            let matches: Arc<dyn ExecutionPlan> =
+                Arc::new(FilterExec::try_new(Arc::clone(&predicate), scan)?);
+
+            let scan = self.scan_file(state, &object_store_url, &file).await?;
+            let kept = match &keep {
+                Some(keep) => Arc::new(FilterExec::try_new(Arc::clone(keep), scan)?) as _,
+                None => scan,
+            };
+            let input = Arc::new(ProjectionExec::try_new(columns.clone(), kept)?);
+
+            let source = file.object_meta.location;
+            let staged =
+                Path::from(format!("{}{staged_suffix}", rewritten_file_path(&source)));
+            let config = FileSinkConfig {
+                original_url: String::default(),
+                object_store_url: object_store_url.clone(),
+                table_paths: vec![ListingTableUrl::parse(format!(
+                    "{}{staged}",
+                    object_store_url.as_str()
+                ))?],
+                file_group: FileGroup::default(),
+                output_schema: sink_schema(&self.file_schema),
+                table_partition_cols: vec![],
+                insert_op: InsertOp::Append,
+                keep_partition_by_columns: false,
+                file_extension: self.options.format.get_ext(),
+                file_output_mode: FileOutputMode::SingleFile,
+            };
+            let rewrite = self
+                .options
+                .format
+                .create_writer_physical_plan(input, state, config, None)
+                .await?;
+
+            rewrites.push(FileRewrite {
+                source,
+                staged: Some(staged),
+                matches,
+                rewrite,
+            });
+        }
+
+        self.invalidate_list_files_cache(state);
+        Ok(Arc::new(CopyOnWriteExec::new(
+            op,
+            object_store_url,
+            table_path.prefix().clone(),
+            staged_suffix,
+            rewrites,
+        )))
+    }
+
+    fn merge_into_boxed<'a>(
+        &'a self,
+        state: &'a dyn Session,
+        source: Arc<dyn ExecutionPlan>,
+        merge_schema: DFSchemaRef,
+        on: Expr,
+        clauses: Vec<MergeIntoClause>,
+    ) -> BoxFuture<'a, datafusion_common::Result<Arc<dyn ExecutionPlan>>> {
+        Box::pin(self.merge_into_inner(state, source, merge_schema, on, clauses))
+    }
+
+    /// Plans a copy-on-write `MERGE INTO`: every file of the table is merged
+    /// with the source, and rewritten if any of its rows is updated or
+    /// deleted. The rewritten and inserted rows are written to new files,
+    /// partitioned like the table so that updated rows may move to another
+    /// partition.
+    async fn merge_into_inner(
+        &self,
+        state: &dyn Session,
+        source: Arc<dyn ExecutionPlan>,
+        merge_schema: DFSchemaRef,
+        on: Expr,
+        clauses: Vec<MergeIntoClause>,
+    ) -> datafusion_common::Result<Arc<dyn ExecutionPlan>> {
+        let op = CopyOnWriteOp::Merge;
+        let table_path = self.copy_on_write_table_path(op)?;
+        let object_store_url = table_path.object_store();
+        let (on, clauses) =
+            create_merge_clauses(state, self, &merge_schema, on, clauses)?;
+        let spec = MergeIntoSpec::try_new(self.schema(), source.schema(), on, clauses)?;
+        let merger = SharedMerger::default();
+        let merge_rows = |input, rows| {
+            Arc::new(MergeRowsExec::new(
+                input,
+                rows,
+                Arc::clone(&merger),
+                self.schema(),
+            )) as Arc<dyn ExecutionPlan>
+        };
+
+        let ListFilesResult { file_groups, .. } =
+            self.list_files_for_scan(state, &[], None).await?;
+        let staged_suffix = staged_suffix();
+        let mut rewrites = vec![];
+        for file in file_groups.into_iter().flat_map(FileGroup::into_inner) {
+            // The affected rows are counted and the file rewritten by separate
+            // scans, as a scan cannot be executed more than once
+            let scan = self.scan_file(state, &object_store_url, &file).await?;
+            let matches = merge_rows(Some(scan), MergeRows::Affected);
+            let scan = self.scan_file(state, &object_store_url, &file).await?;
+            let input = merge_rows(Some(scan), MergeRows::Rewritten);
+            rewrites.push(FileRewrite {
+                source: file.object_meta.location,
+                staged: None,
+                matches,
+                rewrite: self
+                    .create_staged_writer(state, input, &staged_suffix)
+                    .await?,
+            });
+        }
+        let insert = match spec.inserts() {
+            true => {
+                let input = merge_rows(None, MergeRows::Inserted);
+                Some(
+                    self.create_staged_writer(state, input, &staged_suffix)
+                        .await?,
+                )
+            }
+            false => None,
+        };
+
+        self.invalidate_list_files_cache(state);
+        let exec = CopyOnWriteExec::new(
+            op,
+            object_store_url,
+            table_path.prefix().clone(),
+            staged_suffix,
+            rewrites,
+        )
+        .with_merge(MergeInput {
+            source,
+            spec,
+            merger,
+            insert,
+        });
+        Ok(Arc::new(exec))
+    }
+
+    /// Returns the path of the table if copy-on-write `op` is supported, i.e.
+    /// the table is backed by a single directory
+    fn copy_on_write_table_path(
+        &self,
+        op: CopyOnWriteOp,
+    ) -> datafusion_common::Result<&ListingTableUrl> {
+        let Some(table_path) = self.table_paths.first() else {
+            return plan_err!("{op} requires a ListingTable with a table path");
+        };
+        if self.table_paths.len() > 1 || !table_path.is_collection() {
+            return plan_err!(
+                "{op} is only supported on a ListingTable backed by a single directory, URL is possibly missing a trailing `/`"
+            );
+        }
+        Ok(table_path)
+    }
+
+    /// Creates a sink writing the rows of `input`, with the schema of the
+    /// table, to new files of the table whose names end with `staged_suffix`
+    async fn create_staged_writer(
+        &self,
+        state: &dyn Session,
+        input: Arc<dyn ExecutionPlan>,
+        staged_suffix: &str,
+    ) -> datafusion_common::Result<Arc<dyn ExecutionPlan>> {
+        let config = FileSinkConfig {
+            original_url: String::default(),
+            object_store_url: self.table_paths[0].object_store(),
+            table_paths: self.table_paths.clone(),
+            file_group: FileGroup::default(),
+            output_schema: sink_schema(&self.table_schema),
+            table_partition_cols: self.options.table_partition_cols.clone(),
+            insert_op: InsertOp::Append,
+            keep_partition_by_columns: state
+                .config_options()
+                .execution
+                .keep_partition_by_columns,
+            file_extension: format!("{}{staged_suffix}", self.options.format.get_ext()),
+            file_output_mode: FileOutputMode::Directory,
+        };
+        self.options
+            .format
+            .create_writer_physical_plan(input, state, config, None)
+            .await
+    }
+
+    async fn snapshot_inner(
+        &self,
+        state: &dyn Session,
+    ) -> datafusion_common::Result<Option<TableSnapshot>> {
+        let Some(table_path) = self.table_paths.first() else {
+            return Ok(None);
+        };
+        let store = state.runtime_env().object_store(table_path)?;
+        let mut files = HashMap::new();
+        for table_path in &self.table_paths {
+            let mut file_list = pruned_partition_list(
+                state,
+                store.as_ref(),
+                table_path,
+                &[],
+                &self.options.file_extension,
+                &self.options.table_partition_cols,
+            )
+            .await?;
+            while let Some(file) = file_list.try_next().await? {
+                let meta = file.object_meta;
+                files.insert(meta.location.clone(), meta);
+            }
+        }
+        Ok(Some(Arc::new(ListingTableSnapshot { files })))
+    }
+
+    fn alter_inner(
+        &self,
+        state: &dyn Session,
+        operation: &AlterTableOperation,
+    ) -> datafusion_common::Result<Arc<dyn TableProvider>> {
+        let mut table = self.clone();
+        match operation {
+            AlterTableOperation::SetOptions { options } => {
+                let options = options
+                    .iter()
+                    .map(|(key, value)| match key.strip_prefix("format.") {
+                        Some(key) => Ok((key.to_string(), value.clone())),
+                        None => plan_err!("Unsupported option for ListingTable: {key}"),
+                    })
+                    .collect::<datafusion_common::Result<HashMap<_, _>>>()?;
+                table.options.format =
+                    self.options.format.with_format_options(&options)?;
+                return Ok(Arc::new(table));
+            }
+            AlterTableOperation::RenameTable { .. } => {
+                return internal_err!(
+                    "Renaming a table is not applied by its TableProvider"
+                );
+            }
+            _ => {}
+        }
+
+        if !self.options.format.supports_schema_evolution() {
+            return not_impl_err!(
+                "Altering the columns of a ListingTable is not supported for {} files",
+                self.options.format.get_ext()
+            );
+        }
+        // Validate against all the columns, including the partition columns
+        operation.apply_to_schema(&self.table_schema)?;
+        let mut schema_evolution = self.schema_evolution.as_ref().clone();
+        match operation {
+            AlterTableOperation::AddColumn { field, default, .. } => {
+                let default_value = default
+                    .as_ref()
+                    .map(|default| evaluate_constant(state, default))
+                    .transpose()?;
+                schema_evolution.add_column(field.name(), default_value)?;
+                if let Some(default) = default {
+                    table
+                        .column_defaults
+                        .insert(field.name().clone(), default.clone());
+                }
+            }
+            AlterTableOperation::DropColumn { name, .. } => {
+                self.check_not_partition_column(name)?;
+                let index = self.table_schema.index_of(name)?;
+                let remaining = (0..self.table_schema.fields().len())
+                    .filter(|i| *i != index)
+                    .collect::<Vec<_>>();
+                table.constraints =
+                    self.constraints.project(&remaining).unwrap_or_default();
+                table.column_defaults.remove(name);
+                schema_evolution.drop_column(name);
+            }
+            AlterTableOperation::RenameColumn { old_name, new_name } => {
+                self.check_not_partition_column(old_name)?;
+                if let Some(default) = table.column_defaults.remove(old_name) {
+                    table.column_defaults.insert(new_name.clone(), default);
+                }
+                schema_evolution.rename_column(old_name, new_name)?;
+            }
+            // Handled above
+            AlterTableOperation::SetOptions { .. }
+            | AlterTableOperation::RenameTable { .. } => {}
+        }
+
+        // Fails, rejecting the change, if the evolution cannot be stored
+        let file_schema = store_schema_evolution(
+            operation.apply_to_schema(&self.file_schema)?,
+            &schema_evolution,
+        )?;
+        table.set_file_schema(Arc::new(file_schema));
+        table.options.file_sort_order =
+            operation.apply_to_sort_order(&self.options.file_sort_order)?;
+        table.schema_evolution = Arc::new(schema_evolution);
+        Ok(Arc::new(table))
+    }
+
+    /// Replaces `file_schema`, and the schemas derived from it
+    fn set_file_schema(&mut self, file_schema: SchemaRef) {
+        let mut builder = SchemaBuilder::from(file_schema.as_ref().to_owned());
+        for (part_col_name, part_col_type) in &self.options.table_partition_cols {
+            builder.push(Field::new(part_col_name, part_col_type.clone(), false));
+        }
+        self.table_schema = Arc::new(
+            builder
+                .finish()
+                .with_metadata(file_schema.metadata().clone()),
+        );
+        self.file_schema_fingerprint =
+            Arc::new(SchemaFingerprint::from_schema(&file_schema));
+        self.file_schema = file_schema;
+    }
+
+    fn check_not_partition_column(&self, name: &str) -> datafusion_common::Result<()> {
+        if self
+            .options
+            .table_partition_cols
+            .iter()
+            .any(|(col, _)| col == name)
+        {
+            return not_impl_err!(
+                "Altering partition column {name} of a ListingTable is not supported"
+            );
+        }
+        Ok(())
+    }
+
+    /// Creates a scan of a single `file` of the table
+    async fn scan_file(
+        &self,
+        state: &dyn Session,
+        object_store_url: &ObjectStoreUrl,
+        file: &PartitionedFile,
+    ) -> datafusion_common::Result<Arc<dyn ExecutionPlan>> {
+        let scan_config = FileScanConfigBuilder::new(
+            object_store_url.clone(),
+            self.create_file_source(),
+        )
+        .with_file_groups(vec![FileGroup::new(vec![file.clone()])])
+        .with_expr_adapter(self.expr_adapter_factory())
+        .build();
+        self.options
+            .format
+            .create_physical_plan(state, scan_config)
+            .await
+    }
+
+    /// Returns `false` if the partition values and statistics of `file` show
+    /// that it cannot contain rows matching `predicate`
+    fn may_contain_matches(
+        &self,
+        predicate: &Arc<dyn PhysicalExpr>,
+        file: &PartitionedFile,
+    ) -> datafusion_common::Result<bool> {
+        let partition_values = self
+            .options
+            .table_partition_cols
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .zip(file.partition_values.iter())
+            .collect::<HashMap<_, _>>();
+        let predicate =
+            replace_columns_with_literals(Arc::clone(predicate), &partition_values)?;
+        let Some(mut pruner) =
+            FilePruner::try_new(predicate, &self.file_schema, file, Count::new())
+        else {
+            return Ok(true);
+        };
+        Ok(!pruner.should_prune()?)
+    }
 }
 
 impl ListingTable {
@@ -903,10 +1701,15 @@ impl ListingTable {
         // another names a file inside it. A ListingTable uses one object store,
         // so the object path uniquely identifies a file within this scan.
         let mut seen_files = HashSet::new();
+        let selected_files = self.selected_files.clone();
         let file_list = stream::iter(file_list)
             .flatten_unordered(meta_fetch_concurrency)
             .try_filter(move |file| {
-                future::ready(seen_files.insert(file.object_meta.location.clone()))
+                let location = &file.object_meta.location;
+                let selected = selected_files
+                    .as_ref()
+                    .is_none_or(|selected| selected.contains(location));
+                future::ready(selected && seen_files.insert(location.clone()))
             });
         // collect the statistics and ordering if required by the config
         let files = file_list
@@ -1112,7 +1915,23 @@ impl ListingTable {
             .infer_stats_and_ordering(ctx, store, Arc::clone(&self.file_schema), meta)
             .await?;
 
-        let statistics = Arc::new(file_meta.statistics);
+        let mut statistics = file_meta.statistics;
+        // Files written before the columns were altered store renamed columns
+        // with their previous name and lack added columns, which the format
+        // reports as all null
+        if !self.schema_evolution.is_empty() {
+            for (field, column_statistics) in self
+                .file_schema
+                .fields()
+                .iter()
+                .zip(statistics.column_statistics.iter_mut())
+            {
+                if self.schema_evolution.is_evolved(field.name()) {
+                    *column_statistics = ColumnStatistics::new_unknown();
+                }
+            }
+        }
+        let statistics = Arc::new(statistics);
 
         // Store in cache
         if let Some(cache) = &self.collected_statistics {
@@ -1204,6 +2023,26 @@ async fn get_files_with_limit(
     Ok((file_group, inexact_stats))
 }
 
+/// Path of the file replacing `source` after a copy-on-write rewrite: a new
+/// file name in the same directory, with the same extension
+fn rewritten_file_path(source: &Path) -> Path {
+    let write_id = rand::distr::Alphanumeric.sample_string(&mut rand::rng(), 16);
+    let file_name = match source.filename().and_then(|name| name.split_once('.')) {
+        Some((_, extension)) => format!("{write_id}_0.{extension}"),
+        None => format!("{write_id}_0"),
+    };
+    match source.parts().count() {
+        0 | 1 => Path::from(file_name),
+        n => Path::from_iter(source.parts().take(n - 1)).join(file_name),
+    }
+}
+
+/// The files of a [`ListingTable`] returned by [`TableProvider::snapshot`]
+#[derive(Debug)]
+struct ListingTableSnapshot {
+    files: HashMap<Path, ObjectMeta>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
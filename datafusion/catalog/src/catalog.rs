@@ -16,7 +16,9 @@
 // under the License.
 
 // Re-export from this module for backwards compatibility.
-pub use datafusion_session::{CatalogProvider, CatalogProviderList};
+pub use datafusion_session::{
+    CatalogProvider, CatalogProviderList, TableStatisticsStore,
+};
 // Re-export so users can access this type through `datafusion_catalog` and
 // `datafusion::catalog` without depending directly on `datafusion_session`.
 pub use datafusion_session::EmptyCatalogProviderList;
@@ -40,7 +40,9 @@ pub mod default_table_source;
 pub mod empty;
 pub mod information_schema;
 pub mod listing_schema;
+pub mod materialized_view;
 pub mod memory;
+pub mod merge_into;
 pub mod stream;
 pub mod streaming;
 pub mod view;
@@ -57,6 +59,7 @@ pub use datafusion_session::Session;
 pub use dynamic_file::catalog::*;
 pub use memory::{
     MemTable, MemoryCatalogProvider, MemoryCatalogProviderList, MemorySchemaProvider,
+    MemoryTableStatisticsStore,
 };
 pub use schema::*;
 pub use table::*;
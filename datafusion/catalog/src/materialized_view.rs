@@ -0,0 +1,157 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Materialized views, which store the result of their query
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::Session;
+use crate::TableProvider;
+
+use crate::TableSnapshot;
+use arrow::datatypes::SchemaRef;
+use async_trait::async_trait;
+use datafusion_common::error::Result;
+use datafusion_common::{Statistics, TableReference};
+use datafusion_expr::{Expr, LogicalPlan, TableProviderFilterPushDown, TableType};
+use datafusion_physical_plan::ExecutionPlan;
+use futures::future::BoxFuture;
+use parking_lot::Mutex;
+
+/// A view whose query result is stored in a table, the storage of the view.
+///
+/// Scanning the view reads the storage, which is only updated when the view
+/// is refreshed. Unlike [`ViewTable`], the query of the view is therefore
+/// not inlined into the queries reading the view.
+///
+/// A refresh records [`TableSnapshot`]s of the tables read by the query,
+/// which the next refresh uses to only process the rows appended since.
+///
+/// [`ViewTable`]: crate::view::ViewTable
+#[derive(Debug)]
+pub struct MaterializedView {
+    /// LogicalPlan of the query of the view
+    logical_plan: LogicalPlan,
+    /// SQL used to create the view, if available
+    definition: Option<String>,
+    /// Table storing the result of the query
+    storage: Arc<dyn TableProvider>,
+    /// State of the last refresh of the view
+    last_refresh: Mutex<MaterializedViewRefresh>,
+}
+
+/// The state of the last refresh of a [`MaterializedView`]
+#[derive(Debug, Clone, Default)]
+pub struct MaterializedViewRefresh {
+    /// When the view was refreshed, `None` if it was never refreshed
+    pub refreshed_at: Option<SystemTime>,
+    /// Snapshots of the tables read by the query, taken before the query was
+    /// run. Tables that do not support snapshots are not included.
+    pub snapshots: HashMap<TableReference, TableSnapshot>,
+}
+
+impl MaterializedView {
+    /// Create a new materialized view with the query `logical_plan`, storing
+    /// its result in `storage`.
+    ///
+    /// The schema of `storage` must match the schema of `logical_plan`. The
+    /// view is empty until it is refreshed.
+    pub fn new(
+        logical_plan: LogicalPlan,
+        definition: Option<String>,
+        storage: Arc<dyn TableProvider>,
+    ) -> Self {
+        Self {
+            logical_plan,
+            definition,
+            storage,
+            last_refresh: Mutex::new(MaterializedViewRefresh::default()),
+        }
+    }
+
+    /// Get definition ref
+    pub fn definition(&self) -> Option<&String> {
+        self.definition.as_ref()
+    }
+
+    /// Get logical_plan ref
+    pub fn logical_plan(&self) -> &LogicalPlan {
+        &self.logical_plan
+    }
+
+    /// The table storing the result of the query
+    pub fn storage(&self) -> &Arc<dyn TableProvider> {
+        &self.storage
+    }
+
+    /// The state of the last refresh of the view
+    pub fn last_refresh(&self) -> MaterializedViewRefresh {
+        self.last_refresh.lock().clone()
+    }
+
+    /// Record a completed refresh of the view
+    pub fn set_last_refresh(&self, refresh: MaterializedViewRefresh) {
+        *self.last_refresh.lock() = refresh;
+    }
+}
+
+#[async_trait]
+impl TableProvider for MaterializedView {
+    fn schema(&self) -> SchemaRef {
+        self.storage.schema()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    fn get_table_definition(&self) -> Option<&str> {
+        self.definition.as_deref()
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> Result<Vec<TableProviderFilterPushDown>> {
+        self.storage.supports_filters_pushdown(filters)
+    }
+
+    fn statistics(&self) -> Option<Statistics> {
+        self.storage.statistics()
+    }
+
+    // Hand-written `#[async_trait]` expansion to reduce compile time. See
+    // <https://github.com/apache/datafusion/issues/13814#issuecomment-5292709677>
+    fn scan<'life0, 'life1, 'life2, 'life3, 'async_trait>(
+        &'life0 self,
+        state: &'life1 dyn Session,
+        projection: Option<&'life2 [usize]>,
+        filters: &'life3 [Expr],
+        limit: Option<usize>,
+    ) -> BoxFuture<'async_trait, Result<Arc<dyn ExecutionPlan>>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        'life3: 'async_trait,
+        Self: 'async_trait,
+    {
+        self.storage.scan(state, projection, filters, limit)
+    }
+}
@@ -18,7 +18,8 @@
 //! [`MemoryCatalogProvider`], [`MemoryCatalogProviderList`]: In-memory
 //! implementations of [`CatalogProviderList`] and [`CatalogProvider`].
 
-use crate::{CatalogProvider, CatalogProviderList, SchemaProvider};
+use super::MemoryTableStatisticsStore;
+use crate::{CatalogProvider, CatalogProviderList, SchemaProvider, TableStatisticsStore};
 use dashmap::DashMap;
 use datafusion_common::exec_err;
 use std::sync::Arc;
@@ -67,6 +68,7 @@ impl CatalogProviderList for MemoryCatalogProviderList {
 #[derive(Debug)]
 pub struct MemoryCatalogProvider {
     schemas: DashMap<String, Arc<dyn SchemaProvider>>,
+    statistics: Arc<MemoryTableStatisticsStore>,
 }
 
 impl MemoryCatalogProvider {
@@ -74,6 +76,7 @@ impl MemoryCatalogProvider {
     pub fn new() -> Self {
         Self {
             schemas: DashMap::new(),
+            statistics: Arc::new(MemoryTableStatisticsStore::new()),
         }
     }
 }
@@ -111,6 +114,7 @@ impl CatalogProvider for MemoryCatalogProvider {
             match (table_names.is_empty(), cascade) {
                 (true, _) | (false, true) => {
                     let (_, removed) = self.schemas.remove(name).unwrap();
+                    self.statistics.remove_schema(name);
                     Ok(Some(removed))
                 }
                 (false, false) => exec_err!(
@@ -123,4 +127,8 @@ impl CatalogProvider for MemoryCatalogProvider {
             Ok(None)
         }
     }
+
+    fn statistics_store(&self) -> Option<Arc<dyn TableStatisticsStore>> {
+        Some(Arc::clone(&self.statistics) as _)
+    }
 }
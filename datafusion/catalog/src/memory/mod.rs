@@ -17,10 +17,12 @@
 
 pub(crate) mod catalog;
 pub(crate) mod schema;
+pub(crate) mod statistics;
 pub(crate) mod table;
 
 pub use catalog::*;
 pub use schema::*;
+pub use statistics::*;
 pub use table::*;
 
 // backward compatibility
@@ -0,0 +1,79 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! [`MemoryTableStatisticsStore`]: In-memory implementation of
+//! [`TableStatisticsStore`].
+
+use std::sync::Arc;
+
+use crate::TableStatisticsStore;
+use dashmap::DashMap;
+use datafusion_common::{Result, TableStatistics};
+
+/// Simple in-memory store of table statistics, used by
+/// [`MemoryCatalogProvider`](crate::MemoryCatalogProvider).
+#[derive(Debug, Default)]
+pub struct MemoryTableStatisticsStore {
+    statistics: DashMap<(String, String), Arc<TableStatistics>>,
+}
+
+impl MemoryTableStatisticsStore {
+    /// Instantiates a new, empty `MemoryTableStatisticsStore`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Removes the statistics of all tables in schema `schema`
+    pub fn remove_schema(&self, schema: &str) {
+        self.statistics.retain(|(s, _), _| s != schema);
+    }
+}
+
+impl TableStatisticsStore for MemoryTableStatisticsStore {
+    fn table_statistics(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Option<Arc<TableStatistics>> {
+        self.statistics
+            .get(&(schema.to_string(), table.to_string()))
+            .map(|s| Arc::clone(s.value()))
+    }
+
+    fn store_table_statistics(
+        &self,
+        schema: &str,
+        table: &str,
+        statistics: TableStatistics,
+    ) -> Result<()> {
+        self.statistics.insert(
+            (schema.to_string(), table.to_string()),
+            Arc::new(statistics),
+        );
+        Ok(())
+    }
+
+    fn remove_table_statistics(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Option<Arc<TableStatistics>>> {
+        Ok(self
+            .statistics
+            .remove(&(schema.to_string(), table.to_string()))
+            .map(|(_, s)| s))
+    }
+}
@@ -22,10 +22,12 @@ use std::fmt::Debug;
 use std::future::ready;
 use std::sync::Arc;
 
-use crate::TableProvider;
+use crate::merge_into::merge_into_with_overwrite;
+use crate::{TableProvider, TableSnapshot};
 
 use arrow::array::{
     Array, ArrayRef, BooleanArray, RecordBatch as ArrowRecordBatch, UInt64Array,
+    new_null_array,
 };
 use arrow::compute::kernels::zip::zip;
 use arrow::compute::{and, filter_record_batch};
@@ -33,13 +35,15 @@ use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use datafusion_common::error::Result;
 use datafusion_common::tree_node::TreeNodeRecursion;
-use datafusion_common::{Constraints, DFSchema, SchemaExt, not_impl_err, plan_err};
+use datafusion_common::{
+    Constraints, DFSchema, DFSchemaRef, SchemaExt, internal_err, not_impl_err, plan_err,
+};
 use datafusion_datasource::memory::{MemSink, MemorySourceConfig};
 use datafusion_datasource::sink::DataSinkExec;
 use datafusion_datasource::source::DataSourceExec;
-use datafusion_expr::dml::InsertOp;
+use datafusion_expr::dml::{InsertOp, MergeIntoClause};
 use datafusion_expr::physical_planning_context::PhysicalPlanningContext;
-use datafusion_expr::{Expr, SortExpr, TableType};
+use datafusion_expr::{AlterTableOperation, Expr, SortExpr, TableType};
 use datafusion_physical_expr::{
     LexOrdering, create_physical_expr, create_physical_sort_exprs,
 };
@@ -269,6 +273,123 @@ impl TableProvider for MemTable {
     {
         self.update_boxed(state, assignments, filters)
     }
+
+    // Hand-written `#[async_trait]` expansion to reduce compile time. See
+    // <https://github.com/apache/datafusion/issues/13814#issuecomment-5292709677>
+    fn merge_into<'life0, 'life1, 'async_trait>(
+        &'life0 self,
+        state: &'life1 dyn Session,
+        source: Arc<dyn ExecutionPlan>,
+        merge_schema: DFSchemaRef,
+        on: Expr,
+        clauses: Vec<MergeIntoClause>,
+    ) -> BoxFuture<'async_trait, Result<Arc<dyn ExecutionPlan>>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            merge_into_with_overwrite(self, state, source, &merge_schema, on, clauses)
+                .await
+        })
+    }
+
+    async fn alter(
+        &self,
+        state: &dyn Session,
+        operation: &AlterTableOperation,
+    ) -> Result<Arc<dyn TableProvider>> {
+        self.alter_inner(state, operation).await
+    }
+
+    // Hand-written `#[async_trait]` expansion to reduce compile time. See
+    // <https://github.com/apache/datafusion/issues/13814#issuecomment-5292709677>
+    fn snapshot<'life0, 'life1, 'async_trait>(
+        &'life0 self,
+        _state: &'life1 dyn Session,
+    ) -> BoxFuture<'async_trait, Result<Option<TableSnapshot>>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let mut partitions = Vec::with_capacity(self.batches.len());
+            for partition in &self.batches {
+                partitions.push(partition.read().await.clone());
+            }
+            Ok(Some(
+                Arc::new(MemTableSnapshot { partitions }) as TableSnapshot
+            ))
+        })
+    }
+
+    fn appended_rows(
+        &self,
+        from: Option<&TableSnapshot>,
+        to: &TableSnapshot,
+    ) -> Result<Option<Arc<dyn TableProvider>>> {
+        let Some(to) = to.downcast_ref::<MemTableSnapshot>() else {
+            return Ok(None);
+        };
+        let partitions = match from {
+            None => to.partitions.clone(),
+            Some(from) => {
+                let Some(from) = from.downcast_ref::<MemTableSnapshot>() else {
+                    return Ok(None);
+                };
+                if from.partitions.len() != to.partitions.len() {
+                    return Ok(None);
+                }
+                let mut partitions = Vec::with_capacity(to.partitions.len());
+                for (from, to) in from.partitions.iter().zip(&to.partitions) {
+                    // Inserts append batches to the partitions, while other
+                    // changes replace the batches of the partitions
+                    if from.len() > to.len()
+                        || !from.iter().zip(to).all(|(a, b)| is_same_batch(a, b))
+                    {
+                        return Ok(None);
+                    }
+                    partitions.push(to[from.len()..].to_vec());
+                }
+                partitions
+            }
+        };
+        Ok(Some(Arc::new(MemTable::try_new(
+            Arc::clone(&self.schema),
+            partitions,
+        )?)))
+    }
+
+    fn same_snapshot(&self, a: &TableSnapshot, b: &TableSnapshot) -> bool {
+        let (Some(a), Some(b)) = (
+            a.downcast_ref::<MemTableSnapshot>(),
+            b.downcast_ref::<MemTableSnapshot>(),
+        ) else {
+            return false;
+        };
+        a.partitions.len() == b.partitions.len()
+            && a.partitions.iter().zip(&b.partitions).all(|(a, b)| {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| is_same_batch(a, b))
+            })
+    }
+}
+
+/// The batches of a [`MemTable`] returned by [`TableProvider::snapshot`]
+#[derive(Debug)]
+struct MemTableSnapshot {
+    partitions: Vec<Vec<RecordBatch>>,
+}
+
+/// Returns true if `a` and `b` share the same column arrays
+fn is_same_batch(a: &RecordBatch, b: &RecordBatch) -> bool {
+    a.num_rows() == b.num_rows()
+        && a.num_columns() == b.num_columns()
+        && a.columns()
+            .iter()
+            .zip(b.columns())
+            .all(|(a, b)| Arc::ptr_eq(a, b))
 }
 
 impl MemTable {
@@ -349,13 +470,94 @@ impl MemTable {
         self.schema()
             .logically_equivalent_names_and_types(&input.schema())?;
 
-        if insert_op != InsertOp::Append {
+        if insert_op == InsertOp::Replace {
             return not_impl_err!("{insert_op} not implemented for MemoryTable yet");
         }
-        let sink = MemSink::try_new(self.batches.clone(), Arc::clone(&self.schema))?;
+        let sink = MemSink::try_new(self.batches.clone(), Arc::clone(&self.schema))?
+            .with_overwrite(insert_op == InsertOp::Overwrite);
         Ok(Arc::new(DataSinkExec::new(input, Arc::new(sink), None)))
     }
 
+    /// Returns a new [`MemTable`] with the altered schema and data of this table.
+    async fn alter_inner(
+        &self,
+        state: &dyn Session,
+        operation: &AlterTableOperation,
+    ) -> Result<Arc<dyn TableProvider>> {
+        let schema = Arc::new(operation.apply_to_schema(&self.schema)?);
+        let mut constraints = self.constraints.clone();
+        let mut column_defaults = self.column_defaults.clone();
+
+        let mut partitions = Vec::with_capacity(self.batches.len());
+        for partition in &self.batches {
+            partitions.push(partition.read().await.clone());
+        }
+
+        let partitions = match operation {
+            AlterTableOperation::AddColumn { field, default, .. } => {
+                let default = default
+                    .as_ref()
+                    .map(|default| {
+                        column_defaults.insert(field.name().clone(), default.clone());
+                        create_physical_expr(
+                            default,
+                            &DFSchema::empty(),
+                            state.execution_props(),
+                            &PhysicalPlanningContext::default(),
+                        )
+                    })
+                    .transpose()?;
+                map_columns(partitions, &schema, |batch| {
+                    let column = match &default {
+                        Some(default) => {
+                            default.evaluate(batch)?.into_array(batch.num_rows())?
+                        }
+                        None => new_null_array(field.data_type(), batch.num_rows()),
+                    };
+                    let mut columns = batch.columns().to_vec();
+                    columns.push(column);
+                    Ok(columns)
+                })?
+            }
+            AlterTableOperation::DropColumn { name, .. } => {
+                let index = self.schema.index_of(name)?;
+                let remaining = (0..self.schema.fields().len())
+                    .filter(|i| *i != index)
+                    .collect::<Vec<_>>();
+                constraints = constraints.project(&remaining).unwrap_or_default();
+                column_defaults.remove(name);
+                map_columns(partitions, &schema, |batch| {
+                    let mut columns = batch.columns().to_vec();
+                    columns.remove(index);
+                    Ok(columns)
+                })?
+            }
+            AlterTableOperation::RenameColumn { old_name, new_name } => {
+                if let Some(default) = column_defaults.remove(old_name) {
+                    column_defaults.insert(new_name.clone(), default);
+                }
+                map_columns(partitions, &schema, |batch| Ok(batch.columns().to_vec()))?
+            }
+            AlterTableOperation::SetOptions { .. } => {
+                return not_impl_err!(
+                    "ALTER TABLE SET OPTIONS is not supported for MemTable"
+                );
+            }
+            AlterTableOperation::RenameTable { .. } => {
+                return internal_err!(
+                    "Renaming a table is not applied by its TableProvider"
+                );
+            }
+        };
+
+        let sort_order = operation.apply_to_sort_order(&self.sort_order.lock())?;
+        let table = MemTable::try_new(schema, partitions)?
+            .with_constraints(constraints)
+            .with_column_defaults(column_defaults)
+            .with_sort_order(sort_order);
+        Ok(Arc::new(table))
+    }
+
     fn delete_from_boxed<'a>(
         &'a self,
         state: &'a dyn Session,
@@ -573,6 +775,24 @@ impl MemTable {
     }
 }
 
+/// Replaces the columns of each batch of `partitions` with the columns
+/// computed by `f`, which must match `schema`.
+fn map_columns(
+    partitions: Vec<Vec<RecordBatch>>,
+    schema: &SchemaRef,
+    f: impl Fn(&RecordBatch) -> Result<Vec<ArrayRef>>,
+) -> Result<Vec<Vec<RecordBatch>>> {
+    partitions
+        .into_iter()
+        .map(|batches| {
+            batches
+                .iter()
+                .map(|batch| Ok(RecordBatch::try_new(Arc::clone(schema), f(batch)?)?))
+                .collect()
+        })
+        .collect()
+}
+
 /// Evaluate filter expressions against a batch and return a combined boolean mask.
 /// Returns None if filters is empty (meaning "match all rows").
 /// The returned mask has true for rows that match the filter predicates.
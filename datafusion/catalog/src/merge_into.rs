@@ -0,0 +1,187 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Helpers to implement [`TableProvider::merge_into`]
+
+use std::sync::Arc;
+
+use crate::TableProvider;
+use datafusion_common::tree_node::TreeNode;
+use datafusion_common::{DFSchema, Result, ScalarValue, not_impl_err};
+use datafusion_expr::dml::{InsertOp, MergeIntoAction, MergeIntoClause};
+use datafusion_expr::{Expr, ExprSchemable};
+use datafusion_physical_expr::expressions::{Column, lit};
+use datafusion_physical_plan::merge_into::{
+    MergeIntoExec, MergeIntoResultExec, PhysicalMergeAction, PhysicalMergeClause,
+};
+use datafusion_physical_plan::{ExecutionPlan, PhysicalExpr};
+use datafusion_session::Session;
+
+/// Creates a [`MergeIntoExec`] merging `source` into `target`, a scan of all
+/// the columns of `table`, from the arguments of [`TableProvider::merge_into`]
+/// (see [`create_merge_clauses`]).
+pub fn create_merge_into_exec(
+    state: &dyn Session,
+    table: &dyn TableProvider,
+    target: Arc<dyn ExecutionPlan>,
+    source: Arc<dyn ExecutionPlan>,
+    merge_schema: &DFSchema,
+    on: Expr,
+    clauses: Vec<MergeIntoClause>,
+) -> Result<MergeIntoExec> {
+    let (on, clauses) = create_merge_clauses(state, table, merge_schema, on, clauses)?;
+    MergeIntoExec::try_new(target, source, on, clauses)
+}
+
+/// Creates the physical join condition and clauses of a merge into `table`
+/// from the arguments of [`TableProvider::merge_into`], to build a
+/// [`MergeIntoExec`] or a
+/// [`MergeIntoSpec`](datafusion_physical_plan::merge_into::MergeIntoSpec).
+///
+/// Columns missing from a `WHEN NOT MATCHED THEN INSERT` column list are set
+/// to their default value (see [`TableProvider::get_column_default`]), or
+/// null.
+pub fn create_merge_clauses(
+    state: &dyn Session,
+    table: &dyn TableProvider,
+    merge_schema: &DFSchema,
+    on: Expr,
+    clauses: Vec<MergeIntoClause>,
+) -> Result<(Arc<dyn PhysicalExpr>, Vec<PhysicalMergeClause>)> {
+    let schema = table.schema();
+    let on = create_merge_expr(state, on, merge_schema)?;
+    let clauses = clauses
+        .into_iter()
+        .map(|clause| {
+            let predicate = clause
+                .predicate
+                .map(|predicate| create_merge_expr(state, predicate, merge_schema))
+                .transpose()?;
+            let action = match clause.action {
+                MergeIntoAction::Update(mut assignments) => {
+                    let exprs = schema
+                        .fields()
+                        .iter()
+                        .enumerate()
+                        .map(|(i, field)| {
+                            match assignments
+                                .iter()
+                                .position(|(name, _)| name == field.name())
+                            {
+                                Some(position) => {
+                                    let (_, value) = assignments.swap_remove(position);
+                                    create_merge_expr(state, value, merge_schema)
+                                }
+                                None => Ok(Arc::new(Column::new(field.name(), i)) as _),
+                            }
+                        })
+                        .collect::<Result<_>>()?;
+                    PhysicalMergeAction::Update(exprs)
+                }
+                MergeIntoAction::Insert { columns, values } if columns.is_empty() => {
+                    let exprs = values
+                        .into_iter()
+                        .map(|value| create_merge_expr(state, value, merge_schema))
+                        .collect::<Result<_>>()?;
+                    PhysicalMergeAction::Insert(exprs)
+                }
+                MergeIntoAction::Insert {
+                    mut columns,
+                    mut values,
+                } => {
+                    let exprs = schema
+                        .fields()
+                        .iter()
+                        .map(|field| {
+                            if let Some(position) =
+                                columns.iter().position(|name| name == field.name())
+                            {
+                                columns.swap_remove(position);
+                                let value = values.swap_remove(position);
+                                return create_merge_expr(state, value, merge_schema);
+                            }
+                            match table.get_column_default(field.name()) {
+                                Some(default) => {
+                                    let default = default
+                                        .clone()
+                                        .cast_to(field.data_type(), merge_schema)?;
+                                    create_merge_expr(state, default, merge_schema)
+                                }
+                                None => {
+                                    Ok(lit(ScalarValue::try_from(field.data_type())?)
+                                        as Arc<dyn PhysicalExpr>)
+                                }
+                            }
+                        })
+                        .collect::<Result<_>>()?;
+                    PhysicalMergeAction::Insert(exprs)
+                }
+                MergeIntoAction::Delete => PhysicalMergeAction::Delete,
+            };
+            Ok(PhysicalMergeClause {
+                kind: clause.kind,
+                predicate,
+                action,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok((on, clauses))
+}
+
+/// Creates the physical expression of an ON or WHEN condition, or of a value
+/// of an UPDATE or INSERT action
+fn create_merge_expr(
+    state: &dyn Session,
+    expr: Expr,
+    merge_schema: &DFSchema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let has_subquery = expr.exists(|expr| {
+        Ok(matches!(
+            expr,
+            Expr::Exists(_)
+                | Expr::InSubquery(_)
+                | Expr::ScalarSubquery(_)
+                | Expr::SetComparison(_)
+        ))
+    })?;
+    if has_subquery {
+        return not_impl_err!("Subqueries in MERGE INTO are not supported");
+    }
+    state.create_physical_expr(expr, merge_schema)
+}
+
+/// Implements [`TableProvider::merge_into`] for a `table` supporting
+/// [`InsertOp::Overwrite`]: the new contents of the table, computed by a
+/// [`MergeIntoExec`] from a full scan of the table, replace its current
+/// contents.
+pub async fn merge_into_with_overwrite(
+    table: &dyn TableProvider,
+    state: &dyn Session,
+    source: Arc<dyn ExecutionPlan>,
+    merge_schema: &DFSchema,
+    on: Expr,
+    clauses: Vec<MergeIntoClause>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let target = table.scan(state, None, &[], None).await?;
+    let merge =
+        create_merge_into_exec(state, table, target, source, merge_schema, on, clauses)?;
+    let rows_affected = merge.rows_affected();
+    let write = table
+        .insert_into(state, Arc::new(merge), InsertOp::Overwrite)
+        .await?;
+    Ok(Arc::new(MergeIntoResultExec::new(write, rows_affected)))
+}
@@ -18,5 +18,5 @@
 // Re-export from this module for backwards compatibility.
 pub use datafusion_session::{
     ScanArgs, ScanResult, TableFunction, TableFunctionArgs, TableFunctionImpl,
-    TableProvider, TableProviderFactory,
+    TableProvider, TableProviderFactory, TableSnapshot,
 };
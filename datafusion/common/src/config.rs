@@ -834,6 +834,94 @@ impl Display for ConfigFilterSelectivity {
     }
 }
 
+/// Used for [`OptimizerOptions::join_enumeration_dp_threshold`] to represent
+/// the maximum number of relations enumerated with dynamic programming, when
+/// valid values are 0 to [`ConfigJoinDpThreshold::MAX`] inclusive. The
+/// enumeration keeps a plan for every connected subset of the relations, so
+/// its time and memory grow exponentially with this value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConfigJoinDpThreshold(usize);
+
+/// Private helper for hard-coded defaults in `config_namespace!`, which cannot
+/// use `?`. All external construction should use
+/// [`ConfigJoinDpThreshold::try_new`].
+const fn join_dp_threshold_default(value: usize) -> ConfigJoinDpThreshold {
+    if value <= ConfigJoinDpThreshold::MAX {
+        ConfigJoinDpThreshold(value)
+    } else {
+        panic!("value must be at most 20")
+    }
+}
+
+impl ConfigJoinDpThreshold {
+    /// The largest accepted threshold
+    pub const MAX: usize = 20;
+
+    /// Creates a [`ConfigJoinDpThreshold`], returning a configuration error
+    /// if `value` is greater than [`Self::MAX`].
+    pub fn try_new(value: usize) -> Result<Self> {
+        if value <= Self::MAX {
+            Ok(Self(value))
+        } else {
+            _config_err!("value must be at most {}, got {value}", Self::MAX)
+        }
+    }
+
+    /// Returns the wrapped `usize`.
+    pub const fn get(self) -> usize {
+        self.0
+    }
+}
+
+impl From<ConfigJoinDpThreshold> for usize {
+    fn from(value: ConfigJoinDpThreshold) -> Self {
+        value.get()
+    }
+}
+
+impl FromStr for ConfigJoinDpThreshold {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_new(default_config_transform(s)?)
+    }
+}
+
+impl ConfigField for ConfigJoinDpThreshold {
+    fn visit<V: Visit>(&self, v: &mut V, key: &str, description: &'static str) {
+        v.some(key, self, description)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        if !key.is_empty() {
+            return _config_err!(
+                "Config field join_enumeration_dp_threshold is a scalar ConfigJoinDpThreshold and does not have nested field \"{}\"",
+                key
+            );
+        }
+
+        *self = ConfigJoinDpThreshold::from_str(value)?;
+        Ok(())
+    }
+
+    fn reset(&mut self, key: &str) -> Result<()> {
+        if key.is_empty() {
+            Ok(())
+        } else {
+            _config_err!(
+                "Config field join_enumeration_dp_threshold is a scalar ConfigJoinDpThreshold and does not have nested field \"{}\"",
+                key
+            )
+        }
+    }
+}
+
+impl Display for ConfigJoinDpThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.get())
+    }
+}
+
 /// Policy for handling duplicate keys in Spark-compatible map-construction
 /// functions (`map_from_arrays`, `map_from_entries`, `str_to_map`). Mirrors
 /// Spark's [`spark.sql.mapKeyDedupPolicy`](https://github.com/apache/spark/blob/cf3a34e19dfcf70e2d679217ff1ba21302212472/sql/catalyst/src/main/scala/org/apache/spark/sql/internal/SQLConf.scala#L4961).
@@ -922,6 +1010,21 @@ config_namespace! {
         /// Support for build_side.num_rows() >= u32::MAX will be added in the future.
         pub perfect_hash_join_min_key_density: f64, default = 0.15
 
+        /// When set to true, a `HashJoinExec` whose build side does not fit in
+        /// the memory pool falls back to a grace hash join instead of failing:
+        /// both inputs are hash partitioned into spill files, and the
+        /// partitions are then joined one at a time.
+        ///
+        /// Only `Partitioned` joins, and `CollectLeft` joins with a single probe
+        /// partition, can spill. Joins with an ordered probe side, a `fetch`
+        /// limit or null-aware anti join semantics never spill.
+        pub enable_hash_join_spill: bool, default = false
+
+        /// Number of partitions each input of a spilling hash join is split
+        /// into (see `enable_hash_join_spill`). A partition that still does not
+        /// fit in memory is split again with a different hash seed.
+        pub hash_join_spill_partitions: ConfigNonZeroUsize, default = non_zero_usize_default(16)
+
         /// When set to true, record batches will be examined between each operator and
         /// small batches will be coalesced into larger batches. This is helpful when there
         /// are highly selective filters or joins that could produce tiny output batches. The
@@ -932,6 +1035,15 @@ config_namespace! {
         /// Has no effect after the table is created. Defaults to true.
         pub collect_statistics: bool, default = true
 
+        /// Maximum number of buckets of the equi-depth histograms computed by
+        /// `ANALYZE TABLE`. Set to 0 to not compute histograms.
+        pub analyze_histogram_buckets: usize, default = 64
+
+        /// Maximum number of rows sampled by `ANALYZE TABLE` to build
+        /// histograms. Tables with more rows are sampled randomly, smaller
+        /// tables are read completely, which makes their histograms exact.
+        pub analyze_sample_size: usize, default = 100_000
+
         /// Number of partitions for query execution. Increasing partitions can increase
         /// concurrency.
         ///
@@ -1074,6 +1186,13 @@ config_namespace! {
         /// Should DataFusion support recursive CTEs
         pub enable_recursive_ctes: bool, default = true
 
+        /// Should DataFusion serve read-only queries from the query result cache,
+        /// and store their results in it. Only applies when the runtime has a
+        /// result cache, see `datafusion.runtime.result_cache_limit`. Set to false
+        /// to bypass the cache, e.g. for queries that must observe the latest data
+        /// of non file-based tables.
+        pub use_result_cache: bool, default = true
+
         /// Attempt to eliminate sorts by packing & sorting files with non-overlapping
         /// statistics into the same file groups.
         /// Currently experimental
@@ -1764,6 +1883,19 @@ config_namespace! {
         /// operator's built-in `partition_statistics`.
         pub use_statistics_registry: bool, default = false
 
+        /// When set to true, the physical plan optimizer reorders trees of inner
+        /// hash joins using cost-based join enumeration. Cardinalities are
+        /// estimated with the `StatisticsRegistry` and the join order with the
+        /// smallest sum of intermediate result sizes is chosen. Has no effect
+        /// when `join_reordering` is disabled.
+        pub enable_join_enumeration: bool, default = false
+
+        /// The maximum number of relations in a join graph for which join
+        /// enumeration performs an exhaustive dynamic programming search over
+        /// connected subgraphs (DPhyp). Larger join graphs fall back to greedy
+        /// enumeration. At most 20.
+        pub join_enumeration_dp_threshold: ConfigJoinDpThreshold, default = join_dp_threshold_default(10)
+
         /// When set to true, the physical plan optimizer will prefer HashJoin over SortMergeJoin.
         /// HashJoin can work more efficiently than SortMergeJoin but consumes more memory
         pub prefer_hash_join: bool, default = true
@@ -1851,6 +1983,21 @@ config_namespace! {
         /// branches share the same source and compatible wrapper nodes such as identical
         /// projections or aliases.
         pub enable_unions_to_filter: bool, default = false
+
+        /// When set to true, the logical optimizer will rewrite parts of a query
+        /// that match the query of a materialized view to read the stored results
+        /// of the view instead, if the tables read by the view did not change
+        /// since its last refresh. Views reading tables that cannot tell whether
+        /// they changed must also be fresh enough according to
+        /// `materialized_view_max_staleness_secs`.
+        pub enable_materialized_view_rewrite: bool, default = false
+
+        /// The maximum number of seconds since the last refresh of a materialized
+        /// view for `enable_materialized_view_rewrite` to use it, if the view
+        /// reads tables that cannot tell whether they changed since. If not set,
+        /// such a materialized view is used regardless of when it was last
+        /// refreshed.
+        pub materialized_view_max_staleness_secs: Option<usize>, default = None
     }
 }
 
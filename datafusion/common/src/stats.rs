@@ -17,7 +17,11 @@
 
 //! This module provides data structures to represent statistics
 
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt::{self, Debug, Display};
+use std::ops::Bound;
+use std::sync::Arc;
 
 use crate::{Result, ScalarValue};
 
@@ -1190,6 +1194,355 @@ impl ColumnStatistics {
     }
 }
 
+/// A bucket of a [`Histogram`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistogramBucket {
+    /// Smallest value in the bucket (inclusive)
+    pub lower: ScalarValue,
+    /// Largest value in the bucket (inclusive)
+    pub upper: ScalarValue,
+    /// Number of non null values in the bucket
+    pub count: usize,
+    /// Number of distinct values in the bucket
+    pub distinct_count: usize,
+}
+
+/// An equi-depth histogram over the non null values of a column.
+///
+/// Buckets are sorted, do not overlap and hold roughly the same number of
+/// values. A value never spans two buckets, so heavy hitters end up in a
+/// bucket of their own, which keeps equality estimates for them accurate.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct Histogram {
+    buckets: Vec<HistogramBucket>,
+}
+
+impl Histogram {
+    /// Create a histogram from `buckets`, which must be sorted and must not
+    /// overlap.
+    pub fn try_new(buckets: Vec<HistogramBucket>) -> Result<Self> {
+        for bucket in &buckets {
+            if bucket.lower.partial_cmp(&bucket.upper) == Some(Ordering::Greater) {
+                return _plan_err!(
+                    "Histogram bucket lower bound {} is greater than upper bound {}",
+                    bucket.lower,
+                    bucket.upper
+                );
+            }
+        }
+        for pair in buckets.windows(2) {
+            if pair[0].upper.partial_cmp(&pair[1].lower) != Some(Ordering::Less) {
+                return _plan_err!(
+                    "Histogram buckets must be sorted and must not overlap, \
+                     but bucket ending at {} is followed by bucket starting at {}",
+                    pair[0].upper,
+                    pair[1].lower
+                );
+            }
+        }
+        Ok(Self { buckets })
+    }
+
+    /// Build an equi-depth histogram with at most `num_buckets` buckets from
+    /// sorted, non null `values`.
+    pub fn try_new_equi_depth(
+        values: &[ScalarValue],
+        num_buckets: usize,
+    ) -> Result<Self> {
+        let num_buckets = num_buckets.max(1);
+        let depth = values.len().div_ceil(num_buckets).max(1);
+        let mut buckets = Vec::with_capacity(num_buckets);
+        let mut start = 0;
+        while start < values.len() {
+            let mut end = usize::min(start + depth, values.len());
+            // keep all occurrences of a value in the same bucket
+            while end < values.len() && values[end] == values[end - 1] {
+                end += 1;
+            }
+            let distinct_count = 1 + values[start..end]
+                .windows(2)
+                .filter(|pair| pair[0] != pair[1])
+                .count();
+            buckets.push(HistogramBucket {
+                lower: values[start].clone(),
+                upper: values[end - 1].clone(),
+                count: end - start,
+                distinct_count,
+            });
+            start = end;
+        }
+        Self::try_new(buckets)
+    }
+
+    /// Returns the buckets of this histogram
+    pub fn buckets(&self) -> &[HistogramBucket] {
+        &self.buckets
+    }
+
+    /// Returns the number of values covered by this histogram
+    pub fn total_count(&self) -> usize {
+        self.buckets.iter().map(|bucket| bucket.count).sum()
+    }
+
+    /// Scale the bucket counts so that they add up to `total_count`.
+    ///
+    /// Used when the histogram was built from a sample of the column.
+    pub fn scale_to(mut self, total_count: usize) -> Self {
+        let current = self.total_count();
+        if current == 0 || current == total_count {
+            return self;
+        }
+        let factor = total_count as f64 / current as f64;
+        for bucket in &mut self.buckets {
+            bucket.count = ((bucket.count as f64 * factor).round() as usize).max(1);
+            bucket.distinct_count = bucket.distinct_count.min(bucket.count);
+        }
+        self
+    }
+
+    /// Estimate the fraction of values that are equal to `value`.
+    ///
+    /// Returns `None` if the estimate is not possible, for example because
+    /// `value` can not be compared with the bucket bounds.
+    pub fn equality_selectivity(&self, value: &ScalarValue) -> Option<f64> {
+        let total = self.total_count();
+        if total == 0 || value.is_null() {
+            return None;
+        }
+        for bucket in &self.buckets {
+            if value.partial_cmp(&bucket.lower)? == Ordering::Less {
+                return Some(0.0);
+            }
+            if value.partial_cmp(&bucket.upper)? != Ordering::Greater {
+                let rows = bucket.count as f64 / bucket.distinct_count.max(1) as f64;
+                return Some(rows / total as f64);
+            }
+        }
+        Some(0.0)
+    }
+
+    /// Estimate the fraction of values that fall between `lower` and `upper`.
+    ///
+    /// Values are assumed to be uniformly distributed within a bucket when
+    /// the bucket bounds are numeric. Returns `None` if the estimate is not
+    /// possible, for example because the bounds can not be compared with the
+    /// bucket bounds.
+    pub fn range_selectivity(
+        &self,
+        lower: Bound<&ScalarValue>,
+        upper: Bound<&ScalarValue>,
+    ) -> Option<f64> {
+        let total = self.total_count();
+        if total == 0 {
+            return None;
+        }
+        let mut rows = 0.0;
+        for bucket in &self.buckets {
+            rows += bucket.count as f64 * bucket_fraction(bucket, lower, upper)?;
+        }
+        Some((rows / total as f64).clamp(0.0, 1.0))
+    }
+
+    /// Estimate the fraction of the pairs of values of this histogram and
+    /// `other` that are equal, i.e. the selectivity of an equi-join on the
+    /// two columns.
+    ///
+    /// Each pair of overlapping buckets contributes the values in their
+    /// overlap, assuming that the side with fewer distinct values in the
+    /// overlap only holds values of the other side. Returns `None` if the
+    /// estimate is not possible, for example because the bucket bounds can
+    /// not be compared.
+    pub fn join_selectivity(&self, other: &Histogram) -> Option<f64> {
+        let (left_total, right_total) = (self.total_count(), other.total_count());
+        if left_total == 0 || right_total == 0 {
+            return None;
+        }
+        let mut rows = 0.0;
+        let (mut left_buckets, mut right_buckets) = (
+            self.buckets.iter().peekable(),
+            other.buckets.iter().peekable(),
+        );
+        while let (Some(left), Some(right)) = (left_buckets.peek(), right_buckets.peek())
+        {
+            let lower = match left.lower.partial_cmp(&right.lower)? {
+                Ordering::Less => &right.lower,
+                _ => &left.lower,
+            };
+            let upper = match left.upper.partial_cmp(&right.upper)? {
+                Ordering::Greater => &right.upper,
+                _ => &left.upper,
+            };
+            if lower.partial_cmp(upper)? != Ordering::Greater {
+                let (lower, upper) = (Bound::Included(lower), Bound::Included(upper));
+                let left_fraction = bucket_fraction(left, lower, upper)?;
+                let right_fraction = bucket_fraction(right, lower, upper)?;
+                let left_distinct = (left.distinct_count as f64 * left_fraction).max(1.0);
+                let right_distinct =
+                    (right.distinct_count as f64 * right_fraction).max(1.0);
+                rows += left.count as f64
+                    * left_fraction
+                    * right.count as f64
+                    * right_fraction
+                    / left_distinct.max(right_distinct);
+            }
+            // move past the bucket that ends first
+            match left.upper.partial_cmp(&right.upper)? {
+                Ordering::Less => {
+                    left_buckets.next();
+                }
+                Ordering::Greater => {
+                    right_buckets.next();
+                }
+                Ordering::Equal => {
+                    left_buckets.next();
+                    right_buckets.next();
+                }
+            }
+        }
+        Some((rows / (left_total as f64 * right_total as f64)).clamp(0.0, 1.0))
+    }
+}
+
+/// Fraction of the values in `bucket` that fall between `lower` and `upper`.
+fn bucket_fraction(
+    bucket: &HistogramBucket,
+    lower: Bound<&ScalarValue>,
+    upper: Bound<&ScalarValue>,
+) -> Option<f64> {
+    // clip the requested range to the bucket
+    let (low, low_inclusive) = match lower {
+        Bound::Unbounded => (&bucket.lower, true),
+        Bound::Included(v) | Bound::Excluded(v) => match v.partial_cmp(&bucket.lower)? {
+            Ordering::Less => (&bucket.lower, true),
+            Ordering::Equal => (&bucket.lower, matches!(lower, Bound::Included(_))),
+            Ordering::Greater => (v, matches!(lower, Bound::Included(_))),
+        },
+    };
+    let (high, high_inclusive) = match upper {
+        Bound::Unbounded => (&bucket.upper, true),
+        Bound::Included(v) | Bound::Excluded(v) => match v.partial_cmp(&bucket.upper)? {
+            Ordering::Greater => (&bucket.upper, true),
+            Ordering::Equal => (&bucket.upper, matches!(upper, Bound::Included(_))),
+            Ordering::Less => (v, matches!(upper, Bound::Included(_))),
+        },
+    };
+
+    match low.partial_cmp(high)? {
+        Ordering::Greater => return Some(0.0),
+        Ordering::Equal => {
+            // the range covers at most a single value
+            return Some(if low_inclusive && high_inclusive {
+                1.0 / bucket.distinct_count.max(1) as f64
+            } else {
+                0.0
+            });
+        }
+        Ordering::Less => {}
+    }
+    if low == &bucket.lower && high == &bucket.upper && low_inclusive && high_inclusive {
+        return Some(1.0);
+    }
+
+    let fraction = match (
+        scalar_to_f64(&bucket.lower),
+        scalar_to_f64(&bucket.upper),
+        scalar_to_f64(low),
+        scalar_to_f64(high),
+    ) {
+        (Some(bucket_low), Some(bucket_high), Some(low), Some(high))
+            if bucket_high > bucket_low =>
+        {
+            (high - low) / (bucket_high - bucket_low)
+        }
+        // the position of non numeric values within a bucket is unknown
+        _ => 0.5,
+    };
+    Some(fraction.clamp(0.0, 1.0))
+}
+
+/// Converts numeric and temporal values to `f64` for interpolation.
+fn scalar_to_f64(value: &ScalarValue) -> Option<f64> {
+    match value {
+        ScalarValue::Int8(v) => v.map(f64::from),
+        ScalarValue::Int16(v) => v.map(f64::from),
+        ScalarValue::Int32(v)
+        | ScalarValue::Date32(v)
+        | ScalarValue::Time32Second(v)
+        | ScalarValue::Time32Millisecond(v) => v.map(f64::from),
+        ScalarValue::Int64(v)
+        | ScalarValue::Date64(v)
+        | ScalarValue::Time64Microsecond(v)
+        | ScalarValue::Time64Nanosecond(v)
+        | ScalarValue::TimestampSecond(v, _)
+        | ScalarValue::TimestampMillisecond(v, _)
+        | ScalarValue::TimestampMicrosecond(v, _)
+        | ScalarValue::TimestampNanosecond(v, _)
+        | ScalarValue::DurationSecond(v)
+        | ScalarValue::DurationMillisecond(v)
+        | ScalarValue::DurationMicrosecond(v)
+        | ScalarValue::DurationNanosecond(v) => v.map(|v| v as f64),
+        ScalarValue::UInt8(v) => v.map(f64::from),
+        ScalarValue::UInt16(v) => v.map(f64::from),
+        ScalarValue::UInt32(v) => v.map(f64::from),
+        ScalarValue::UInt64(v) => v.map(|v| v as f64),
+        ScalarValue::Float16(v) => v.map(f64::from),
+        ScalarValue::Float32(v) => v.map(f64::from),
+        ScalarValue::Float64(v) => *v,
+        // both bounds of a bucket have the same scale
+        ScalarValue::Decimal128(v, _, _) => v.map(|v| v as f64),
+        _ => None,
+    }
+}
+
+/// Statistics of a table, computed by scanning it (for example with
+/// `ANALYZE TABLE`).
+///
+/// Column statistics and histograms are keyed by column name so that they
+/// can be matched against any projection of the table.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct TableStatistics {
+    /// Number of rows in the table
+    pub num_rows: usize,
+    /// Statistics of the analyzed columns
+    pub column_statistics: HashMap<String, ColumnStatistics>,
+    /// Histograms of the analyzed columns
+    pub histograms: HashMap<String, Arc<Histogram>>,
+}
+
+impl TableStatistics {
+    /// Returns [`Statistics`] for `schema`, using the statistics of the
+    /// column with the same name.
+    ///
+    /// All values are [`Precision::Inexact`], as the table may have changed
+    /// since the statistics were computed.
+    pub fn to_statistics(&self, schema: &Schema) -> Statistics {
+        let column_statistics = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                self.column_statistics
+                    .get(field.name())
+                    .map(|stats| stats.clone().to_inexact())
+                    .unwrap_or_else(ColumnStatistics::new_unknown)
+            })
+            .collect();
+        Statistics {
+            num_rows: Precision::Inexact(self.num_rows),
+            total_byte_size: Precision::Absent,
+            column_statistics,
+        }
+    }
+
+    /// Returns the histogram of every column of `schema`, if one exists.
+    pub fn histograms_for(&self, schema: &Schema) -> Vec<Option<Arc<Histogram>>> {
+        schema
+            .fields()
+            .iter()
+            .map(|field| self.histograms.get(field.name()).map(Arc::clone))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3376,4 +3729,168 @@ mod tests {
         stats.calculate_total_byte_size(&non_primitive_schema);
         assert_eq!(stats.total_byte_size, Precision::Inexact(999));
     }
+
+    fn int_values(values: impl IntoIterator<Item = i64>) -> Vec<ScalarValue> {
+        values
+            .into_iter()
+            .map(|v| ScalarValue::Int64(Some(v)))
+            .collect()
+    }
+
+    #[test]
+    fn test_histogram_equi_depth() {
+        // 1 is a heavy hitter, it must not span several buckets
+        let values =
+            int_values(std::iter::repeat_n(1, 6).chain([2, 3, 4, 5, 6, 7, 8, 9, 10, 11]));
+        let histogram = Histogram::try_new_equi_depth(&values, 4).unwrap();
+        let buckets = histogram
+            .buckets()
+            .iter()
+            .map(|b| (b.lower.clone(), b.upper.clone(), b.count, b.distinct_count))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            buckets,
+            vec![
+                (ScalarValue::from(1i64), ScalarValue::from(1i64), 6, 1),
+                (ScalarValue::from(2i64), ScalarValue::from(5i64), 4, 4),
+                (ScalarValue::from(6i64), ScalarValue::from(9i64), 4, 4),
+                (ScalarValue::from(10i64), ScalarValue::from(11i64), 2, 2),
+            ]
+        );
+        assert_eq!(histogram.total_count(), 16);
+    }
+
+    #[test]
+    fn test_histogram_selectivity() {
+        let values = int_values(std::iter::repeat_n(1, 50).chain(51..=100));
+        let histogram = Histogram::try_new_equi_depth(&values, 4).unwrap();
+
+        assert_eq!(
+            histogram.equality_selectivity(&ScalarValue::from(1i64)),
+            Some(0.5)
+        );
+        assert_eq!(
+            histogram.equality_selectivity(&ScalarValue::from(0i64)),
+            Some(0.0)
+        );
+        assert_eq!(
+            histogram.equality_selectivity(&ScalarValue::from(200i64)),
+            Some(0.0)
+        );
+
+        let one = ScalarValue::from(1i64);
+        let ten = ScalarValue::from(10i64);
+        assert_eq!(
+            histogram.range_selectivity(Bound::Unbounded, Bound::Included(&ten)),
+            Some(0.5)
+        );
+        assert_eq!(
+            histogram.range_selectivity(Bound::Excluded(&one), Bound::Included(&ten)),
+            Some(0.0)
+        );
+        assert_eq!(
+            histogram.range_selectivity(Bound::Unbounded, Bound::Unbounded),
+            Some(1.0)
+        );
+        // 51..=75 and 76..=100 are uniformly distributed in their buckets
+        let sel = histogram
+            .range_selectivity(
+                Bound::Included(&ScalarValue::from(64i64)),
+                Bound::Unbounded,
+            )
+            .unwrap();
+        assert!((sel - 0.37).abs() < 0.01, "{sel}");
+
+        // values that can not be compared can not be estimated
+        assert_eq!(
+            histogram.equality_selectivity(&ScalarValue::from("a")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_histogram_join_selectivity() {
+        let skewed = Histogram::try_new_equi_depth(
+            &int_values(std::iter::repeat_n(1, 90).chain(11..=20)),
+            4,
+        )
+        .unwrap();
+        let uniform = Histogram::try_new_equi_depth(&int_values(1..=100), 4).unwrap();
+
+        // 90 * 90 pairs of ones, and 10 matching pairs of 11..=20
+        let sel = skewed.join_selectivity(&skewed).unwrap();
+        assert!((sel - 8110.0 / 10_000.0).abs() < 1e-9, "{sel}");
+        // each value of `skewed` matches a single value of `uniform`
+        let sel = skewed.join_selectivity(&uniform).unwrap();
+        assert!((sel - 100.0 / 10_000.0).abs() < 1e-3, "{sel}");
+        assert_eq!(
+            uniform.join_selectivity(&skewed),
+            skewed.join_selectivity(&uniform)
+        );
+
+        let disjoint = Histogram::try_new_equi_depth(&int_values(101..=200), 4).unwrap();
+        assert_eq!(uniform.join_selectivity(&disjoint), Some(0.0));
+        assert_eq!(uniform.join_selectivity(&Histogram::default()), None);
+    }
+
+    #[test]
+    fn test_histogram_scale_to() {
+        let values = int_values(1..=10);
+        let histogram = Histogram::try_new_equi_depth(&values, 2)
+            .unwrap()
+            .scale_to(1000);
+        assert_eq!(histogram.total_count(), 1000);
+        assert_eq!(histogram.buckets()[0].distinct_count, 5);
+    }
+
+    #[test]
+    fn test_histogram_invalid_buckets() {
+        let bucket = |lower: i64, upper: i64| HistogramBucket {
+            lower: ScalarValue::from(lower),
+            upper: ScalarValue::from(upper),
+            count: 1,
+            distinct_count: 1,
+        };
+        assert!(Histogram::try_new(vec![bucket(1, 5), bucket(6, 9)]).is_ok());
+        assert!(Histogram::try_new(vec![bucket(5, 1)]).is_err());
+        assert!(Histogram::try_new(vec![bucket(1, 5), bucket(5, 9)]).is_err());
+    }
+
+    #[test]
+    fn test_table_statistics_to_statistics() {
+        let schema = Schema::new(vec![
+            Field::new("b", DataType::Int64, true),
+            Field::new("c", DataType::Int64, true),
+        ]);
+        let stats = TableStatistics {
+            num_rows: 10,
+            column_statistics: HashMap::from([(
+                "b".to_string(),
+                ColumnStatistics::new_unknown()
+                    .with_null_count(Precision::Exact(1))
+                    .with_distinct_count(Precision::Inexact(4)),
+            )]),
+            histograms: HashMap::from([(
+                "b".to_string(),
+                Arc::new(Histogram::try_new_equi_depth(&int_values(1..=9), 2).unwrap()),
+            )]),
+        };
+        let statistics = stats.to_statistics(&schema);
+        assert_eq!(statistics.num_rows, Precision::Inexact(10));
+        assert_eq!(
+            statistics.column_statistics[0].null_count,
+            Precision::Inexact(1)
+        );
+        assert_eq!(
+            statistics.column_statistics[0].distinct_count,
+            Precision::Inexact(4)
+        );
+        assert_eq!(
+            statistics.column_statistics[1],
+            ColumnStatistics::new_unknown()
+        );
+        let histograms = stats.histograms_for(&schema);
+        assert!(histograms[0].is_some());
+        assert!(histograms[1].is_none());
+    }
 }
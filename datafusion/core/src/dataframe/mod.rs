@@ -1380,6 +1380,65 @@ impl DataFrame {
         })
     }
 
+    /// ASOF join this `DataFrame` with another `DataFrame`.
+    ///
+    /// Every row of `self` is emitted exactly once, paired with the closest
+    /// row of `right` that satisfies `match_condition` (an ordered comparison
+    /// such as `ts >= quote_ts`) and every equality in `on_exprs`, or with
+    /// nulls when there is no such row.
+    ///
+    /// # Example
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion_common::assert_batches_sorted_eq;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let trades = dataframe!(
+    ///     "sym" => ["a", "a", "b"],
+    ///     "ts" => [2, 5, 3]
+    /// )?;
+    /// let quotes = dataframe!(
+    ///     "quote_sym" => ["a", "a", "b"],
+    ///     "quote_ts" => [1, 4, 4],
+    ///     "price" => [10, 11, 20]
+    /// )?;
+    ///
+    /// // Pair each trade with the latest quote for its symbol at or before it.
+    /// let joined = trades.join_asof(
+    ///     quotes,
+    ///     [col("sym").eq(col("quote_sym"))],
+    ///     col("ts").gt_eq(col("quote_ts")),
+    /// )?;
+    /// let expected = vec![
+    ///     "+-----+----+-----------+----------+-------+",
+    ///     "| sym | ts | quote_sym | quote_ts | price |",
+    ///     "+-----+----+-----------+----------+-------+",
+    ///     "| a   | 2  | a         | 1        | 10    |",
+    ///     "| a   | 5  | a         | 4        | 11    |",
+    ///     "| b   | 3  |           |          |       |",
+    ///     "+-----+----+-----------+----------+-------+",
+    /// ];
+    /// # assert_batches_sorted_eq!(expected, &joined.collect().await?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn join_asof(
+        self,
+        right: DataFrame,
+        on_exprs: impl IntoIterator<Item = Expr>,
+        match_condition: Expr,
+    ) -> Result<DataFrame> {
+        let plan = LogicalPlanBuilder::from(self.plan)
+            .join_asof(right.plan, on_exprs, match_condition)?
+            .build()?;
+        Ok(DataFrame {
+            session_state: self.session_state,
+            plan,
+            projection_requires_validation: true,
+        })
+    }
+
     /// Repartition a DataFrame based on a logical partitioning scheme.
     ///
     /// # Example
@@ -21,12 +21,16 @@ pub use datafusion_datasource_avro::file_format::*;
 
 #[cfg(test)]
 mod tests {
+    use std::fs::File;
+    use std::io::BufReader;
     use std::sync::Arc;
 
     use crate::{
-        datasource::file_format::test_util::scan_format, prelude::SessionContext,
+        datasource::file_format::test_util::scan_format,
+        prelude::{AvroReadOptions, SessionContext},
     };
     use arrow::array::{Array, as_string_array};
+    use arrow::error::ArrowError;
     use datafusion_catalog::Session;
     use datafusion_common::test_util::batches_to_string;
     use datafusion_common::{
@@ -38,7 +42,10 @@ mod tests {
         test_util,
     };
 
+    use datafusion_common::assert_contains;
     use datafusion_datasource_avro::AvroFormat;
+    use datafusion_datasource_avro::arrow_avro::compression::CompressionCodec;
+    use datafusion_datasource_avro::arrow_avro::reader::ReaderBuilder;
     use datafusion_execution::config::SessionConfig;
     use datafusion_physical_plan::{ExecutionPlan, collect};
     use futures::StreamExt;
@@ -377,6 +384,124 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn write_avro_with_compression() -> Result<()> {
+        let ctx = SessionContext::new();
+        let tmp_dir = tempfile::TempDir::new()?;
+
+        for (codec, expected) in [
+            ("uncompressed", None),
+            ("deflate", Some(CompressionCodec::Deflate)),
+            ("snappy", Some(CompressionCodec::Snappy)),
+            ("zstd", Some(CompressionCodec::ZStandard)),
+        ] {
+            let path = format!("{}/{codec}.avro", tmp_dir.path().to_string_lossy());
+            ctx.sql(&format!(
+                "COPY (VALUES (1, 'Foo'), (2, NULL), (3, 'Bar')) TO '{path}' \
+                 STORED AS AVRO OPTIONS ('format.compression' '{codec}')"
+            ))
+            .await?
+            .collect()
+            .await?;
+
+            let reader =
+                ReaderBuilder::new().build(BufReader::new(File::open(&path)?))?;
+            assert_eq!(
+                reader
+                    .avro_header()
+                    .compression()
+                    .map_err(ArrowError::from)?,
+                expected
+            );
+
+            let batches = ctx
+                .read_avro(&path, AvroReadOptions::default())
+                .await?
+                .collect()
+                .await?;
+            insta::allow_duplicates! {assert_snapshot!(batches_to_string(&batches), @r"
+            +---------+---------+
+            | column1 | column2 |
+            +---------+---------+
+            | 1       | Foo     |
+            | 2       |         |
+            | 3       | Bar     |
+            +---------+---------+
+            ");}
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_avro_unknown_compression() -> Result<()> {
+        let ctx = SessionContext::new();
+        let tmp_dir = tempfile::TempDir::new()?;
+        let path = format!("{}/gzip.avro", tmp_dir.path().to_string_lossy());
+
+        let err = ctx
+            .sql(&format!(
+                "COPY (VALUES (1)) TO '{path}' \
+                 STORED AS AVRO OPTIONS ('format.compression' 'gzip')"
+            ))
+            .await?
+            .collect()
+            .await
+            .unwrap_err();
+        assert_contains!(err.to_string(), "Unsupported Avro compression codec: gzip");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_avro_logical_types() -> Result<()> {
+        let ctx = SessionContext::new();
+        let tmp_dir = tempfile::TempDir::new()?;
+        let path = format!("{}/logical.avro", tmp_dir.path().to_string_lossy());
+
+        ctx.sql(&format!(
+            "COPY (SELECT \
+                DATE '2024-03-01' AS d, \
+                arrow_cast(TIME '12:34:56.789', 'Time64(Microsecond)') AS t, \
+                arrow_cast(TIMESTAMP '2024-03-01T12:34:56', 'Timestamp(Microsecond, Some(\"UTC\"))') AS ts, \
+                arrow_cast(TIMESTAMP '2024-03-01T12:34:56', 'Timestamp(Millisecond, None)') AS local_ts, \
+                CAST(123.45 AS DECIMAL(10, 2)) AS dec \
+            ) TO '{path}' STORED AS AVRO"
+        ))
+        .await?
+        .collect()
+        .await?;
+
+        let batches = ctx
+            .read_avro(&path, AvroReadOptions::default())
+            .await?
+            .collect()
+            .await?;
+        let types = batches[0]
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| format!("{}: {}", f.name(), f.data_type()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_snapshot!(types, @r#"
+        d: Date32
+        t: Time64(µs)
+        ts: Timestamp(µs, "+00:00")
+        local_ts: Timestamp(ms)
+        dec: Decimal128(10, 2)
+        "#);
+        assert_snapshot!(batches_to_string(&batches), @r"
+        +------------+--------------+----------------------+---------------------+--------+
+        | d          | t            | ts                   | local_ts            | dec    |
+        +------------+--------------+----------------------+---------------------+--------+
+        | 2024-03-01 | 12:34:56.789 | 2024-03-01T12:34:56Z | 2024-03-01T12:34:56 | 123.45 |
+        +------------+--------------+----------------------+---------------------+--------+
+        ");
+
+        Ok(())
+    }
+
     async fn get_exec(
         state: &dyn Session,
         file_name: &str,
@@ -385,7 +510,7 @@ mod tests {
     ) -> Result<Arc<dyn ExecutionPlan>> {
         let testdata = test_util::arrow_test_data();
         let store_root = format!("{testdata}/avro");
-        let format = AvroFormat {};
+        let format = AvroFormat::default();
         scan_format(
             state,
             &format,
@@ -1066,6 +1066,54 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_schema_evolution_stored_with_schema() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let tmp_dir = TempDir::new()?;
+        let str_path = tmp_dir
+            .path()
+            .to_str()
+            .expect("Temp path should convert to &str");
+        for sql in [
+            format!(
+                "create external table foo(a int) stored as parquet location '{str_path}/'"
+            ),
+            "insert into foo values (1), (2)".to_string(),
+            "alter table foo rename column a to b".to_string(),
+            "alter table foo add column c int default 7".to_string(),
+        ] {
+            session_ctx.sql(&sql).await?.collect().await?;
+        }
+
+        // Recreate the table from its schema, like when it is deserialized
+        let table = session_ctx.table_provider("foo").await?;
+        let table = table.downcast_ref::<ListingTable>().expect("listing table");
+        assert!(!table.schema_evolution().is_empty());
+        let config =
+            ListingTableConfig::new_with_multi_paths(table.table_paths().clone())
+                .with_listing_options(table.options().clone())
+                .with_schema(table.schema());
+        let restored = ListingTable::try_new(config)?;
+        assert_eq!(restored.schema_evolution(), table.schema_evolution());
+
+        session_ctx.deregister_table("foo")?;
+        session_ctx.register_table("foo", Arc::new(restored))?;
+        let batches = session_ctx
+            .sql("select * from foo order by b")
+            .await?
+            .collect()
+            .await?;
+        insta::assert_snapshot!(batches_to_string(&batches), @r"
+        +---+---+
+        | b | c |
+        +---+---+
+        | 1 | 7 |
+        | 2 | 7 |
+        +---+---+
+        ");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_infer_options_compressed_csv() -> Result<()> {
         let testdata = crate::test_util::arrow_test_data();
@@ -0,0 +1,121 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! `ALTER TABLE` support for [`SessionContext`]
+
+use datafusion_common::{TableReference, exec_err, not_impl_err, plan_err};
+use datafusion_expr::{AlterTable, AlterTableOperation, TableType};
+
+use super::{DataFrame, Result, SessionContext};
+
+impl SessionContext {
+    /// Applies the operations of an `ALTER TABLE` statement in order.
+    ///
+    /// Changes of the columns and options of the table are applied by
+    /// [`TableProvider::alter`], and the provider it returns replaces the
+    /// table. Renaming the table is applied last, by
+    /// [`SchemaProvider::rename_table`]. The new name is validated and all
+    /// changes are computed before the catalog is modified, so a failing
+    /// statement leaves the table unchanged.
+    ///
+    /// [`TableProvider::alter`]: datafusion_catalog::TableProvider::alter
+    /// [`SchemaProvider::rename_table`]: datafusion_catalog::SchemaProvider::rename_table
+    pub(super) async fn alter_table(&self, cmd: AlterTable) -> Result<DataFrame> {
+        let AlterTable {
+            name,
+            if_exists,
+            operations,
+            ..
+        } = cmd;
+        let (state, schema) = {
+            let state = self.state.read().clone();
+            let schema = state.schema_for_ref(name.clone())?;
+            (state, schema)
+        };
+        let table_name = name.table();
+        let Some(mut table) = schema.table(table_name).await? else {
+            if if_exists {
+                return self.return_empty_dataframe();
+            }
+            return exec_err!("Table '{name}' doesn't exist.");
+        };
+        if table.table_type() == TableType::View {
+            return plan_err!("ALTER TABLE is not supported for view '{name}'");
+        }
+
+        let new_name = operations
+            .iter()
+            .rev()
+            .find_map(|operation| match operation {
+                AlterTableOperation::RenameTable { new_name } => Some(new_name),
+                _ => None,
+            });
+        if let Some(new_name) = new_name {
+            let resolved = state.resolve_table_ref(name.clone());
+            let same_schema = match new_name {
+                TableReference::Bare { .. } => true,
+                TableReference::Partial { schema, .. } => *schema == resolved.schema,
+                TableReference::Full {
+                    catalog, schema, ..
+                } => *catalog == resolved.catalog && *schema == resolved.schema,
+            };
+            if !same_schema {
+                return not_impl_err!(
+                    "Moving table '{name}' to another schema with ALTER TABLE is not supported"
+                );
+            }
+            if schema.table_exist(new_name.table()) {
+                return exec_err!("The table {} already exists", new_name.table());
+            }
+        }
+
+        let mut altered = false;
+        for operation in &operations {
+            if matches!(operation, AlterTableOperation::RenameTable { .. }) {
+                continue;
+            }
+            let table_schema = table.schema();
+            if !operation.has_effect(&table_schema) {
+                continue;
+            }
+            operation.apply_to_schema(&table_schema)?;
+            table = table.alter(&state, operation).await?;
+            altered = true;
+        }
+
+        let result = (|| -> Result<()> {
+            let table_name = match new_name {
+                Some(new_name) => {
+                    schema.rename_table(table_name, new_name.table().to_string())?;
+                    new_name.table()
+                }
+                None => table_name,
+            };
+            if altered {
+                schema.deregister_table(table_name)?;
+                schema.register_table(table_name.to_string(), table)?;
+            }
+            Ok(())
+        })();
+        // The table may have been changed even if a later step failed
+        self.invalidate_caches(&name, TableType::Base)?;
+        if let Some(new_name) = new_name {
+            self.invalidate_caches(new_name, TableType::Base)?;
+        }
+        result?;
+        self.return_empty_dataframe()
+    }
+}
@@ -0,0 +1,281 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+//! `ANALYZE TABLE` support for [`SessionContext`]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef};
+use arrow::compute::{concat, filter, is_not_null, sort};
+use arrow::datatypes::{DataType, Field};
+use datafusion_catalog::TableProvider;
+use datafusion_common::stats::{Histogram, Precision};
+use datafusion_common::{
+    ColumnStatistics, ScalarValue, TableStatistics, exec_datafusion_err, not_impl_err,
+    plan_err,
+};
+use datafusion_expr::{AnalyzeTable, TableType, cast, ident, lit};
+use datafusion_functions::math::expr_fn::random;
+use datafusion_functions_aggregate::count::count_all;
+use datafusion_functions_aggregate::expr_fn::{approx_distinct, count, max, min};
+
+use super::{DataFrame, Result, SessionContext};
+
+impl SessionContext {
+    /// Computes the statistics of a table and stores them in the statistics
+    /// store of the table's catalog.
+    pub(super) async fn analyze_table(&self, cmd: AnalyzeTable) -> Result<DataFrame> {
+        let AnalyzeTable { name, columns, .. } = cmd;
+        let (resolved, store) = {
+            let state = self.state.read();
+            let resolved = state.resolve_table_ref(name.clone());
+            let store = state
+                .catalog_list()
+                .catalog(&resolved.catalog)
+                .and_then(|catalog| catalog.statistics_store());
+            (resolved, store)
+        };
+        let Some(store) = store else {
+            return not_impl_err!(
+                "Catalog '{}' does not support storing table statistics",
+                resolved.catalog
+            );
+        };
+
+        let provider = self.table_provider(name.clone()).await?;
+        if provider.table_type() == TableType::View {
+            return plan_err!("ANALYZE TABLE is not supported for view '{name}'");
+        }
+        let schema = provider.schema();
+        let fields = if columns.is_empty() {
+            schema
+                .fields()
+                .iter()
+                .filter(|field| is_analyzable(field.data_type()))
+                .map(Arc::clone)
+                .collect::<Vec<_>>()
+        } else {
+            columns
+                .iter()
+                .map(|column| {
+                    let field = schema.field_with_name(column)?;
+                    if !is_analyzable(field.data_type()) {
+                        return not_impl_err!(
+                            "ANALYZE TABLE does not support column {column} of type {}",
+                            field.data_type()
+                        );
+                    }
+                    Ok(Arc::new(field.clone()))
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        let statistics = self.compute_table_statistics(provider, &fields).await?;
+        store.store_table_statistics(&resolved.schema, &resolved.table, statistics)?;
+        self.return_empty_dataframe()
+    }
+
+    /// Scans `provider` to compute the row count, and the null count, min,
+    /// max, distinct count and histogram of each of `fields`.
+    async fn compute_table_statistics(
+        &self,
+        provider: Arc<dyn TableProvider>,
+        fields: &[Arc<Field>],
+    ) -> Result<TableStatistics> {
+        // A single aggregation computes the statistics of all columns
+        let mut aggregates = vec![count_all()];
+        for field in fields {
+            let column = ident(field.name());
+            let distinct_input = match field.data_type() {
+                // `approx_distinct` does not support floats, but their string
+                // representation has the same number of distinct values
+                DataType::Float32 | DataType::Float64 => {
+                    cast(column.clone(), DataType::Utf8)
+                }
+                _ => column.clone(),
+            };
+            aggregates.extend([
+                count(column.clone()),
+                min(column.clone()),
+                max(column),
+                approx_distinct(distinct_input),
+            ]);
+        }
+        let aggregates = aggregates
+            .into_iter()
+            .enumerate()
+            .map(|(i, expr)| expr.alias(format!("__analyze_{i}")))
+            .collect::<Vec<_>>();
+        let batches = self
+            .read_table(Arc::clone(&provider))?
+            .aggregate(vec![], aggregates)?
+            .collect()
+            .await?;
+        let batch = batches
+            .iter()
+            .find(|batch| batch.num_rows() > 0)
+            .ok_or_else(|| exec_datafusion_err!("ANALYZE TABLE returned no rows"))?;
+        let value = |i: usize| ScalarValue::try_from_array(batch.column(i).as_ref(), 0);
+        let as_usize = |value: ScalarValue| match value {
+            ScalarValue::Int64(Some(v)) => Ok(v as usize),
+            ScalarValue::UInt64(Some(v)) => Ok(v as usize),
+            other => Err(exec_datafusion_err!(
+                "Unexpected count in ANALYZE TABLE: {other:?}"
+            )),
+        };
+
+        let num_rows = as_usize(value(0)?)?;
+        let mut column_statistics = HashMap::with_capacity(fields.len());
+        for (i, field) in fields.iter().enumerate() {
+            let offset = 1 + 4 * i;
+            let non_null = as_usize(value(offset)?)?;
+            let bound = |value: ScalarValue| {
+                if value.is_null() {
+                    Precision::Absent
+                } else {
+                    Precision::Exact(value)
+                }
+            };
+            let statistics = ColumnStatistics::new_unknown()
+                .with_null_count(Precision::Exact(num_rows - non_null))
+                .with_min_value(bound(value(offset + 1)?))
+                .with_max_value(bound(value(offset + 2)?))
+                // the HyperLogLog estimate may exceed the number of values
+                .with_distinct_count(Precision::Inexact(usize::min(
+                    as_usize(value(offset + 3)?)?,
+                    non_null,
+                )));
+            column_statistics.insert(field.name().clone(), statistics);
+        }
+
+        let histograms = self
+            .compute_histograms(provider, fields, num_rows, &column_statistics)
+            .await?;
+        Ok(TableStatistics {
+            num_rows,
+            column_statistics,
+            histograms,
+        })
+    }
+
+    /// Builds equi-depth histograms of `fields` from a random sample of the
+    /// table, or from all rows if the table is small enough.
+    async fn compute_histograms(
+        &self,
+        provider: Arc<dyn TableProvider>,
+        fields: &[Arc<Field>],
+        num_rows: usize,
+        column_statistics: &HashMap<String, ColumnStatistics>,
+    ) -> Result<HashMap<String, Arc<Histogram>>> {
+        let (num_buckets, sample_size) = {
+            let state = self.state.read();
+            let options = &state.config_options().execution;
+            (
+                options.analyze_histogram_buckets,
+                options.analyze_sample_size,
+            )
+        };
+        if num_buckets == 0 || sample_size == 0 || num_rows == 0 || fields.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut sample = self.read_table(provider)?.select(
+            fields
+                .iter()
+                .map(|field| ident(field.name()))
+                .collect::<Vec<_>>(),
+        )?;
+        if num_rows > sample_size {
+            let fraction = sample_size as f64 / num_rows as f64;
+            sample = sample.filter(random().lt(lit(fraction)))?;
+        }
+        let batches = sample.collect().await?;
+
+        let mut histograms = HashMap::with_capacity(fields.len());
+        for (i, field) in fields.iter().enumerate() {
+            let arrays = batches
+                .iter()
+                .map(|batch| Arc::clone(batch.column(i)))
+                .collect::<Vec<_>>();
+            let Some(values) = sorted_non_null_values(&arrays)? else {
+                continue;
+            };
+            let non_null = column_statistics[field.name()]
+                .null_count
+                .get_value()
+                .map_or(num_rows, |nulls| num_rows - nulls);
+            let histogram =
+                Histogram::try_new_equi_depth(&values, num_buckets)?.scale_to(non_null);
+            histograms.insert(field.name().clone(), Arc::new(histogram));
+        }
+        Ok(histograms)
+    }
+}
+
+/// Returns the sorted non null values of `arrays`, or `None` if there are none.
+fn sorted_non_null_values(arrays: &[ArrayRef]) -> Result<Option<Vec<ScalarValue>>> {
+    if arrays.is_empty() {
+        return Ok(None);
+    }
+    let arrays = arrays
+        .iter()
+        .map(|array| array.as_ref())
+        .collect::<Vec<_>>();
+    let values = concat(&arrays)?;
+    let values = filter(&values, &is_not_null(&values)?)?;
+    if values.is_empty() {
+        return Ok(None);
+    }
+    let values = sort(&values, None)?;
+    (0..values.len())
+        .map(|i| ScalarValue::try_from_array(&values, i))
+        .collect::<Result<Vec<_>>>()
+        .map(Some)
+}
+
+/// Returns `true` if `ANALYZE TABLE` can compute statistics of a column of
+/// type `data_type`.
+fn is_analyzable(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Decimal128(_, _)
+            | DataType::Decimal256(_, _)
+            | DataType::Date32
+            | DataType::Date64
+            | DataType::Time32(_)
+            | DataType::Time64(_)
+            | DataType::Timestamp(_, _)
+            | DataType::Duration(_)
+            | DataType::Utf8
+            | DataType::LargeUtf8
+            | DataType::Utf8View
+            | DataType::Binary
+            | DataType::LargeBinary
+            | DataType::BinaryView
+    )
+}
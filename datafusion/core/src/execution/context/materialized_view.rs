@@ -0,0 +1,733 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Materialized view support for [`SessionContext`]
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, SystemTime};
+
+use arrow::datatypes::SchemaRef;
+use async_trait::async_trait;
+use datafusion_catalog::materialized_view::{MaterializedView, MaterializedViewRefresh};
+use datafusion_catalog::{MemTable, Session, TableProvider};
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRecursion};
+use datafusion_common::{
+    Column, DFSchema, TableReference, exec_err, internal_err, plan_err,
+};
+use datafusion_expr::dml::InsertOp;
+use datafusion_expr::expr::AggregateFunction;
+use datafusion_expr::utils::conjunction;
+use datafusion_expr::{
+    CreateExternalTable, CreateMaterializedView, Expr, LogicalPlan, LogicalPlanBuilder,
+    RefreshMaterializedView, TableProviderFilterPushDown, TableType, cast,
+};
+use datafusion_functions_aggregate::expr_fn::{max, min, sum};
+use datafusion_optimizer::{OptimizerConfig, OptimizerRule};
+use datafusion_physical_plan::ExecutionPlan;
+use futures::future::BoxFuture;
+use parking_lot::RwLock;
+
+use super::{DataFrame, Result, SessionContext, SessionState};
+use crate::datasource::provider_as_source;
+
+impl SessionContext {
+    /// Creates a materialized view and stores the result of its query.
+    pub(super) async fn create_materialized_view(
+        &self,
+        cmd: CreateMaterializedView,
+    ) -> Result<DataFrame> {
+        let CreateMaterializedView {
+            name,
+            input,
+            if_not_exists,
+            or_replace,
+            location,
+            file_type,
+            options,
+            definition,
+        } = cmd;
+
+        let exists = self.table_exist(name.clone())?;
+        match (if_not_exists, or_replace, exists) {
+            (true, true, _) => {
+                return exec_err!("'IF NOT EXISTS' cannot coexist with 'REPLACE'");
+            }
+            (true, false, true) => return self.return_empty_dataframe(),
+            (false, false, true) => return exec_err!("Table '{name}' already exists"),
+            _ => {}
+        }
+
+        let input = Self::apply_type_coercion(Arc::unwrap_or_clone(input))?;
+        Self::ensure_unique_column_names(input.schema())?;
+        let schema = Arc::clone(input.schema().inner());
+        let storage: Arc<dyn TableProvider> = match location {
+            Some(mut location) => {
+                // The results are written as new files of the directory
+                if !location.ends_with('/') {
+                    location.push('/');
+                }
+                let cmd = CreateExternalTable::builder(
+                    name.clone(),
+                    location,
+                    file_type,
+                    Arc::new(DFSchema::try_from(schema)?),
+                )
+                .with_options(options.into_iter().collect())
+                .build();
+                self.create_custom_table(&cmd).await?
+            }
+            None => Arc::new(MemTable::try_new(schema, vec![vec![]])?),
+        };
+
+        let view = Arc::new(MaterializedView::new(input, definition, storage));
+        self.refresh(&name, &view, true).await?;
+        if exists {
+            self.deregister_table(name.clone())?;
+        }
+        self.register_table(name.clone(), Arc::clone(&view) as _)?;
+        self.register_rewrite_candidate(name, &view)?;
+        self.return_empty_dataframe()
+    }
+
+    /// Updates the stored result of a materialized view.
+    pub(super) async fn refresh_materialized_view(
+        &self,
+        cmd: RefreshMaterializedView,
+    ) -> Result<DataFrame> {
+        let RefreshMaterializedView { name, full, .. } = cmd;
+        let provider = self.table_provider(name.clone()).await?;
+        let Some(view) = provider.downcast_ref::<MaterializedView>() else {
+            return plan_err!("'{name}' is not a materialized view");
+        };
+        self.refresh(&name, view, full).await?;
+        self.return_empty_dataframe()
+    }
+
+    /// Refreshes `view`, incrementally unless `full` is set.
+    ///
+    /// The tables read by the query of the view are read as of a snapshot
+    /// taken before running the query, if supported, so the next refresh
+    /// processes the rows appended since exactly once. The refresh is
+    /// incremental if the query reads a single table to which rows were only
+    /// appended since the last refresh, and is either row by row (such as a
+    /// projection or a filter) or an aggregation whose results can be merged
+    /// with the stored results (see [`Maintenance`]).
+    async fn refresh(
+        &self,
+        name: &TableReference,
+        view: &MaterializedView,
+        full: bool,
+    ) -> Result<()> {
+        let state = self.refresh_state();
+        let last_refresh = view.last_refresh();
+
+        let mut sources = HashMap::new();
+        let mut snapshots = HashMap::new();
+        for table_name in scanned_tables(view.logical_plan())? {
+            let provider = self.table_provider(table_name.clone()).await?;
+            if let Some(snapshot) = provider.snapshot(&state).await? {
+                snapshots.insert(table_name.clone(), snapshot);
+            }
+            sources.insert(table_name, provider);
+        }
+
+        let maintenance = match (full, last_refresh.refreshed_at, sources.len()) {
+            (false, Some(_), 1) => Maintenance::try_new(view.logical_plan()),
+            _ => None,
+        };
+        let appended = match (maintenance, sources.iter().next()) {
+            (Some(maintenance), Some((table_name, provider))) => {
+                match (
+                    last_refresh.snapshots.get(table_name),
+                    snapshots.get(table_name),
+                ) {
+                    (Some(from), Some(to)) => provider
+                        .appended_rows(Some(from), to)?
+                        .map(|appended| (maintenance, table_name.clone(), appended)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        match appended {
+            Some((maintenance, table_name, appended)) => {
+                let sources = HashMap::from([(table_name, appended)]);
+                let plan = bind_sources(view.logical_plan(), &sources)?;
+                match maintenance {
+                    Maintenance::Append => {
+                        write_to_storage(&state, name, view, plan, InsertOp::Append)
+                            .await?;
+                    }
+                    Maintenance::Aggregate(functions) => {
+                        let merged = merge_plan(name, view, plan, &functions)?;
+                        let schema = Arc::clone(merged.schema().inner());
+                        let batches = DataFrame::new(state.clone(), merged)
+                            .collect_partitioned()
+                            .await?;
+                        let merged = LogicalPlanBuilder::scan(
+                            name.clone(),
+                            provider_as_source(Arc::new(MemTable::try_new(
+                                schema, batches,
+                            )?)),
+                            None,
+                        )?
+                        .build()?;
+                        write_to_storage(&state, name, view, merged, InsertOp::Overwrite)
+                            .await?;
+                    }
+                }
+            }
+            None => {
+                for (table_name, provider) in sources.iter_mut() {
+                    if let Some(snapshot) = snapshots.get(table_name)
+                        && let Some(rows) = provider.appended_rows(None, snapshot)?
+                    {
+                        *provider = rows;
+                    }
+                }
+                let plan = bind_sources(view.logical_plan(), &sources)?;
+                write_to_storage(&state, name, view, plan, InsertOp::Overwrite).await?;
+            }
+        }
+
+        view.set_last_refresh(MaterializedViewRefresh {
+            refreshed_at: Some(SystemTime::now()),
+            snapshots,
+        });
+        Ok(())
+    }
+
+    /// Returns the state used to refresh materialized views, which does not
+    /// rewrite the queries of the views to read materialized views
+    fn refresh_state(&self) -> SessionState {
+        without_materialized_view_rewrite(self.state())
+    }
+
+    /// Makes `view` available to [`MaterializedViewRewrite`], which is added
+    /// before the other optimizer rules of the session with the first
+    /// materialized view.
+    fn register_rewrite_candidate(
+        &self,
+        name: TableReference,
+        view: &Arc<MaterializedView>,
+    ) -> Result<()> {
+        let state = self.refresh_state();
+        let plan = state.analyzer().execute_and_check(
+            view.logical_plan().clone(),
+            state.config_options(),
+            |_, _| {},
+        )?;
+
+        let mut state = self.state.write();
+        let registry = match state.config().get_extension::<MaterializedViewRegistry>() {
+            Some(registry) => registry,
+            None => {
+                let registry = Arc::new(MaterializedViewRegistry::default());
+                state.config_mut().set_extension(Arc::clone(&registry));
+                state.prepend_optimizer_rule(Arc::new(MaterializedViewRewrite {
+                    registry: Arc::clone(&registry),
+                }));
+                registry
+            }
+        };
+        let sources = scanned_tables(view.logical_plan())?;
+        registry.register(name, view, plan, sources);
+        Ok(())
+    }
+}
+
+/// Returns `state` without rewriting queries to read materialized views
+fn without_materialized_view_rewrite(mut state: SessionState) -> SessionState {
+    state
+        .config_mut()
+        .options_mut()
+        .optimizer
+        .enable_materialized_view_rewrite = false;
+    state
+}
+
+/// How the stored results of a materialized view are updated with the rows
+/// appended to the table read by its query
+#[derive(Debug)]
+enum Maintenance {
+    /// The query processes each row on its own, so its results for the
+    /// appended rows are appended to the view
+    Append,
+    /// The query is an aggregation. Its results for the appended rows are
+    /// merged with the stored results by aggregating them again, grouping by
+    /// the columns without a merge function.
+    Aggregate(Vec<Option<MergeFunction>>),
+}
+
+/// The aggregate function used to merge the results of an aggregate function
+#[derive(Debug, Clone, Copy)]
+enum MergeFunction {
+    Sum,
+    Min,
+    Max,
+}
+
+impl Maintenance {
+    /// Returns how the results of the query `plan` can be updated
+    /// incrementally, if they can
+    fn try_new(plan: &LogicalPlan) -> Option<Self> {
+        if is_row_wise(plan) {
+            return Some(Self::Append);
+        }
+
+        // The output columns of the view as indices of the output columns of
+        // the aggregation
+        let mut indices = (0..plan.schema().fields().len()).collect::<Vec<_>>();
+        let mut plan = plan;
+        let aggregate = loop {
+            match plan {
+                LogicalPlan::Projection(projection) => {
+                    indices = indices
+                        .into_iter()
+                        .map(|index| match &projection.expr[index] {
+                            Expr::Column(column) => Some(column),
+                            Expr::Alias(alias) => match alias.expr.as_ref() {
+                                Expr::Column(column) => Some(column),
+                                _ => None,
+                            },
+                            _ => None,
+                        })
+                        .map(|column| {
+                            projection.input.schema().index_of_column(column?).ok()
+                        })
+                        .collect::<Option<_>>()?;
+                    plan = projection.input.as_ref();
+                }
+                LogicalPlan::SubqueryAlias(alias) => plan = alias.input.as_ref(),
+                LogicalPlan::Aggregate(aggregate) => break aggregate,
+                _ => return None,
+            }
+        };
+
+        let group_count = aggregate.group_expr.len();
+        let distinct_indices = indices.iter().collect::<HashSet<_>>();
+        if !is_row_wise(&aggregate.input)
+            || aggregate
+                .group_expr
+                .iter()
+                .any(|expr| matches!(expr, Expr::GroupingSet(_)))
+            // Each group key must be a column of the view, and the columns
+            // are merged independently of each other
+            || (0..group_count).any(|index| !distinct_indices.contains(&index))
+            || distinct_indices.len() != indices.len()
+        {
+            return None;
+        }
+        indices
+            .into_iter()
+            .map(|index| match index.checked_sub(group_count) {
+                None => Some(None),
+                Some(index) => merge_function(&aggregate.aggr_expr[index]).map(Some),
+            })
+            .collect::<Option<_>>()
+            .map(Self::Aggregate)
+    }
+}
+
+/// Returns the function merging the results of the aggregate function
+/// `expr`, if they can be merged
+fn merge_function(expr: &Expr) -> Option<MergeFunction> {
+    let expr = match expr {
+        Expr::Alias(alias) => alias.expr.as_ref(),
+        expr => expr,
+    };
+    let Expr::AggregateFunction(AggregateFunction { func, params }) = expr else {
+        return None;
+    };
+    if params.distinct || params.filter.is_some() || !params.order_by.is_empty() {
+        return None;
+    }
+    match func.name() {
+        "sum" | "count" => Some(MergeFunction::Sum),
+        "min" => Some(MergeFunction::Min),
+        "max" => Some(MergeFunction::Max),
+        _ => None,
+    }
+}
+
+/// Returns true if `plan` reads a single table and computes each output row
+/// from a single row of the table
+fn is_row_wise(plan: &LogicalPlan) -> bool {
+    let has_subquery = |expr: &Expr| {
+        expr.exists(|expr| {
+            Ok(matches!(
+                expr,
+                Expr::ScalarSubquery(_) | Expr::Exists(_) | Expr::InSubquery(_)
+            ))
+        })
+        .unwrap_or(true)
+    };
+    match plan {
+        LogicalPlan::Projection(projection) => {
+            !projection.expr.iter().any(has_subquery) && is_row_wise(&projection.input)
+        }
+        LogicalPlan::Filter(filter) => {
+            !has_subquery(&filter.predicate) && is_row_wise(&filter.input)
+        }
+        LogicalPlan::SubqueryAlias(alias) => is_row_wise(&alias.input),
+        LogicalPlan::TableScan(scan) => scan.fetch.is_none(),
+        _ => false,
+    }
+}
+
+/// Returns the names of the tables scanned by `plan`
+fn scanned_tables(plan: &LogicalPlan) -> Result<HashSet<TableReference>> {
+    let mut tables = HashSet::new();
+    plan.apply_with_subqueries(|plan| {
+        if let LogicalPlan::TableScan(scan) = plan {
+            tables.insert(scan.table_name.clone());
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+    Ok(tables)
+}
+
+/// Replaces the tables scanned by `plan` with the tables of `sources`
+fn bind_sources(
+    plan: &LogicalPlan,
+    sources: &HashMap<TableReference, Arc<dyn TableProvider>>,
+) -> Result<LogicalPlan> {
+    plan.clone()
+        .transform_up_with_subqueries(|plan| match plan {
+            LogicalPlan::TableScan(mut scan) => match sources.get(&scan.table_name) {
+                Some(provider) => {
+                    scan.source = provider_as_source(Arc::clone(provider));
+                    Ok(Transformed::yes(LogicalPlan::TableScan(scan)))
+                }
+                None => Ok(Transformed::no(LogicalPlan::TableScan(scan))),
+            },
+            plan => Ok(Transformed::no(plan)),
+        })
+        .map(|transformed| transformed.data)
+}
+
+/// Returns a plan merging the stored results of `view` with the results
+/// `appended` of its query for the appended rows
+fn merge_plan(
+    name: &TableReference,
+    view: &MaterializedView,
+    appended: LogicalPlan,
+    functions: &[Option<MergeFunction>],
+) -> Result<LogicalPlan> {
+    let schema = view.schema();
+    let union = LogicalPlanBuilder::scan(
+        name.clone(),
+        provider_as_source(Arc::clone(view.storage())),
+        None,
+    )?
+    .union(appended)?
+    .build()?;
+
+    let mut group_expr = vec![];
+    let mut aggr_expr = vec![];
+    for (column, function) in union.schema().columns().into_iter().zip(functions) {
+        let expr = Expr::Column(column);
+        match function {
+            None => group_expr.push(expr),
+            Some(MergeFunction::Sum) => aggr_expr.push(sum(expr)),
+            Some(MergeFunction::Min) => aggr_expr.push(min(expr)),
+            Some(MergeFunction::Max) => aggr_expr.push(max(expr)),
+        }
+    }
+    let group_count = group_expr.len();
+    let aggregate = LogicalPlanBuilder::from(union)
+        .aggregate(group_expr, aggr_expr)?
+        .build()?;
+
+    // Restore the order and types of the columns of the view
+    let (mut group_index, mut aggr_index) = (0, group_count);
+    let exprs = functions
+        .iter()
+        .zip(schema.fields())
+        .map(|(function, field)| {
+            let index = if function.is_none() {
+                group_index += 1;
+                group_index - 1
+            } else {
+                aggr_index += 1;
+                aggr_index - 1
+            };
+            let column = Column::from(aggregate.schema().qualified_field(index));
+            cast(Expr::Column(column), field.data_type().clone()).alias(field.name())
+        })
+        .collect::<Vec<_>>();
+    LogicalPlanBuilder::from(aggregate).project(exprs)?.build()
+}
+
+/// Writes the results of `plan` to the storage of `view`
+async fn write_to_storage(
+    state: &SessionState,
+    name: &TableReference,
+    view: &MaterializedView,
+    plan: LogicalPlan,
+    insert_op: InsertOp,
+) -> Result<()> {
+    let plan = LogicalPlanBuilder::insert_into(
+        plan,
+        name.clone(),
+        provider_as_source(Arc::clone(view.storage())),
+        insert_op,
+    )?
+    .build()?;
+    DataFrame::new(state.clone(), plan).collect().await?;
+    Ok(())
+}
+
+/// The materialized views of a session that [`MaterializedViewRewrite`] can
+/// read, stored as an extension of the session config
+#[derive(Debug, Default)]
+struct MaterializedViewRegistry {
+    views: RwLock<Vec<RewriteCandidate>>,
+}
+
+#[derive(Debug)]
+struct RewriteCandidate {
+    /// The name the view was created with
+    name: TableReference,
+    /// The view, which is dropped with the last reference from the catalog
+    view: Weak<MaterializedView>,
+    /// The analyzed plan of the query of the view
+    plan: Arc<LogicalPlan>,
+    /// The tables read by the query of the view
+    sources: HashSet<TableReference>,
+}
+
+impl MaterializedViewRegistry {
+    fn register(
+        &self,
+        name: TableReference,
+        view: &Arc<MaterializedView>,
+        plan: LogicalPlan,
+        sources: HashSet<TableReference>,
+    ) {
+        let mut views = self.views.write();
+        views.retain(|candidate| {
+            candidate.name != name && candidate.view.strong_count() > 0
+        });
+        views.push(RewriteCandidate {
+            name,
+            view: Arc::downgrade(view),
+            plan: Arc::new(plan),
+            sources,
+        });
+    }
+
+    /// Returns the refreshed views that can be read in place of their query.
+    ///
+    /// Whether a view is up to date is decided when it is scanned, by
+    /// comparing the snapshots of the tables read by its query with the
+    /// snapshots taken by its last refresh, see [`RewrittenMaterializedView`].
+    /// Only a view reading a table without snapshots must also have been
+    /// refreshed within `max_staleness`.
+    fn fresh_views(
+        &self,
+        max_staleness: Option<Duration>,
+    ) -> Vec<(TableReference, Arc<MaterializedView>, Arc<LogicalPlan>)> {
+        let now = SystemTime::now();
+        self.views
+            .read()
+            .iter()
+            .filter_map(|candidate| {
+                let view = candidate.view.upgrade()?;
+                let last_refresh = view.last_refresh();
+                let refreshed_at = last_refresh.refreshed_at?;
+                let has_snapshots = candidate
+                    .sources
+                    .iter()
+                    .all(|table_name| last_refresh.snapshots.contains_key(table_name));
+                let staleness = now.duration_since(refreshed_at).unwrap_or_default();
+                if !has_snapshots
+                    && max_staleness
+                        .is_some_and(|max_staleness| staleness > max_staleness)
+                {
+                    return None;
+                }
+                Some((candidate.name.clone(), view, Arc::clone(&candidate.plan)))
+            })
+            .collect()
+    }
+}
+
+/// A materialized view read in place of its query by
+/// [`MaterializedViewRewrite`].
+///
+/// When scanned, the current snapshots of the tables read by the query are
+/// compared with the snapshots taken by the last refresh of the view. If a
+/// table changed since, the stored results are outdated, and the query of the
+/// view is run instead.
+#[derive(Debug)]
+struct RewrittenMaterializedView {
+    /// The name the view was created with
+    name: TableReference,
+    view: Arc<MaterializedView>,
+}
+
+impl RewrittenMaterializedView {
+    /// Returns true if the tables read by the query of the view hold the
+    /// same rows as when the view was last refreshed
+    async fn is_up_to_date(&self, state: &SessionState) -> Result<bool> {
+        for (table_name, snapshot) in &self.view.last_refresh().snapshots {
+            let schema = state.schema_for_ref(table_name.clone())?;
+            let Some(provider) = schema.table(table_name.table()).await? else {
+                return Ok(false);
+            };
+            match provider.snapshot(state).await? {
+                Some(current) if provider.same_snapshot(snapshot, &current) => {}
+                _ => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl TableProvider for RewrittenMaterializedView {
+    fn schema(&self) -> SchemaRef {
+        self.view.schema()
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::View
+    }
+
+    fn supports_filters_pushdown(
+        &self,
+        filters: &[&Expr],
+    ) -> Result<Vec<TableProviderFilterPushDown>> {
+        self.view.supports_filters_pushdown(filters)
+    }
+
+    // Hand-written `#[async_trait]` expansion to reduce compile time. See
+    // <https://github.com/apache/datafusion/issues/13814#issuecomment-5292709677>
+    fn scan<'life0, 'life1, 'life2, 'life3, 'async_trait>(
+        &'life0 self,
+        state: &'life1 dyn Session,
+        projection: Option<&'life2 [usize]>,
+        filters: &'life3 [Expr],
+        limit: Option<usize>,
+    ) -> BoxFuture<'async_trait, Result<Arc<dyn ExecutionPlan>>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        'life3: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let Some(state) = state.as_any().downcast_ref::<SessionState>() else {
+                return internal_err!(
+                    "Materialized view '{}' must be scanned with a SessionState",
+                    self.name
+                );
+            };
+            if self.is_up_to_date(state).await? {
+                return self.view.scan(state, projection, filters, limit).await;
+            }
+
+            // Run the query of the view, which must not be rewritten to read
+            // the view again
+            let state = without_materialized_view_rewrite(state.clone());
+            let mut builder = LogicalPlanBuilder::from(self.view.logical_plan().clone())
+                .alias(self.name.clone())?;
+            if let Some(predicate) = conjunction(filters.to_vec()) {
+                builder = builder.filter(predicate)?;
+            }
+            if let Some(projection) = projection {
+                builder = builder.select(projection.iter().copied())?;
+            }
+            if limit.is_some() {
+                builder = builder.limit(0, limit)?;
+            }
+            state.create_physical_plan(&builder.build()?).await
+        })
+    }
+}
+
+/// Optimizer rule replacing the parts of a plan equal to the query of a
+/// fresh materialized view with a scan of the view, see
+/// [`RewrittenMaterializedView`].
+///
+/// The rule runs before the other optimizer rules, so the analyzed plans of
+/// queries are compared with the analyzed plans of the views.
+///
+/// Enabled by `datafusion.optimizer.enable_materialized_view_rewrite`.
+#[derive(Debug)]
+struct MaterializedViewRewrite {
+    registry: Arc<MaterializedViewRegistry>,
+}
+
+impl OptimizerRule for MaterializedViewRewrite {
+    fn name(&self) -> &str {
+        "materialized_view_rewrite"
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        let options = config.options();
+        if !options.optimizer.enable_materialized_view_rewrite {
+            return Ok(Transformed::no(plan));
+        }
+        let max_staleness = options
+            .optimizer
+            .materialized_view_max_staleness_secs
+            .map(|secs| Duration::from_secs(secs as u64));
+        let views = self.registry.fresh_views(max_staleness);
+        if views.is_empty() {
+            return Ok(Transformed::no(plan));
+        }
+
+        plan.transform_down_with_subqueries(|plan| {
+            let Some((name, view, _)) = views
+                .iter()
+                .find(|(_, _, view_plan)| view_plan.as_ref() == &plan)
+            else {
+                return Ok(Transformed::no(plan));
+            };
+            let scan = LogicalPlanBuilder::scan(
+                name.clone(),
+                provider_as_source(Arc::new(RewrittenMaterializedView {
+                    name: name.clone(),
+                    view: Arc::clone(view),
+                })),
+                None,
+            )?
+            .build()?;
+            // Keep the qualified names of the columns of the replaced plan
+            let exprs = scan
+                .schema()
+                .columns()
+                .into_iter()
+                .zip(plan.schema().iter())
+                .map(|(column, (qualifier, field))| {
+                    Expr::Column(column).alias_qualified(qualifier.cloned(), field.name())
+                })
+                .collect::<Vec<_>>();
+            let plan = LogicalPlanBuilder::from(scan).project(exprs)?.build()?;
+            Ok(Transformed::new(plan, true, TreeNodeRecursion::Jump))
+        })
+    }
+}
@@ -76,8 +76,9 @@ use datafusion_common::{
 };
 pub use datafusion_execution::TaskContext;
 use datafusion_execution::cache::cache_manager::{
-    DEFAULT_FILE_STATISTICS_MEMORY_LIMIT, DEFAULT_LIST_FILES_CACHE_MEMORY_LIMIT,
-    DEFAULT_LIST_FILES_CACHE_TTL, DEFAULT_METADATA_CACHE_LIMIT,
+    DEFAULT_FILE_STATISTICS_MEMORY_LIMIT, DEFAULT_JOIN_BUILD_SIDE_CACHE_LIMIT,
+    DEFAULT_LIST_FILES_CACHE_MEMORY_LIMIT, DEFAULT_LIST_FILES_CACHE_TTL,
+    DEFAULT_METADATA_CACHE_LIMIT, DEFAULT_RESULT_CACHE_LIMIT,
 };
 pub use datafusion_execution::config::SessionConfig;
 use datafusion_execution::disk_manager::{
@@ -107,8 +108,11 @@ use object_store::ObjectStore;
 use parking_lot::RwLock;
 use url::Url;
 
+mod alter;
+mod analyze;
 mod csv;
 mod json;
+mod materialized_view;
 #[cfg(feature = "parquet")]
 mod parquet;
 
@@ -712,6 +716,18 @@ impl SessionContext {
                         Box::pin(self.create_function(*cmd)).await
                     }
                     DdlStatement::DropFunction(cmd) => self.drop_function(&cmd),
+                    DdlStatement::AnalyzeTable(cmd) => {
+                        Box::pin(self.analyze_table(cmd)).await
+                    }
+                    DdlStatement::AlterTable(cmd) => {
+                        Box::pin(self.alter_table(cmd)).await
+                    }
+                    DdlStatement::CreateMaterializedView(cmd) => {
+                        Box::pin(self.create_materialized_view(cmd)).await
+                    }
+                    DdlStatement::RefreshMaterializedView(cmd) => {
+                        Box::pin(self.refresh_materialized_view(cmd)).await
+                    }
                     ddl => Ok(DataFrame::new(self.state(), LogicalPlan::Ddl(ddl))),
                 }
             }
@@ -1199,6 +1215,14 @@ impl SessionContext {
                 let limit = Self::parse_capacity_limit(variable, value)?;
                 builder.with_file_statistics_cache_limit(limit)
             }
+            "result_cache_limit" => {
+                let limit = Self::parse_capacity_limit(variable, value)?;
+                builder.with_result_cache_limit(limit)
+            }
+            "join_build_side_cache_limit" => {
+                let limit = Self::parse_capacity_limit(variable, value)?;
+                builder.with_join_build_side_cache_limit(limit)
+            }
             "max_spill_merge_fan_in" => {
                 let fan_in = value.parse::<usize>().map_err(|e| {
                     DataFusionError::Plan(format!(
@@ -1251,6 +1275,14 @@ impl SessionContext {
                     DEFAULT_FILE_STATISTICS_MEMORY_LIMIT,
                 );
             }
+            "result_cache_limit" => {
+                builder = builder.with_result_cache_limit(DEFAULT_RESULT_CACHE_LIMIT);
+            }
+            "join_build_side_cache_limit" => {
+                builder = builder.with_join_build_side_cache_limit(
+                    DEFAULT_JOIN_BUILD_SIDE_CACHE_LIMIT,
+                );
+            }
             "max_spill_merge_fan_in" => {
                 builder =
                     builder.with_max_spill_merge_fan_in(DEFAULT_MAX_SPILL_MERGE_FAN_IN);
@@ -1485,6 +1517,15 @@ impl SessionContext {
                 fsc.drop_table_entries(table_ref)?;
             }
         }
+        let state = self.state.read();
+        let resolved = state.resolve_table_ref(table_ref.clone());
+        if let Some(store) = state
+            .catalog_list()
+            .catalog(&resolved.catalog)
+            .and_then(|catalog| catalog.statistics_store())
+        {
+            store.remove_table_statistics(&resolved.schema, &resolved.table)?;
+        }
         Ok(())
     }
 
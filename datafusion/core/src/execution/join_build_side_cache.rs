@@ -0,0 +1,102 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Sharing the build sides of hash joins through the [`JoinBuildSideCache`]
+//! of the runtime.
+//!
+//! [`JoinBuildSideCache`]: datafusion_execution::cache::cache_manager::JoinBuildSideCache
+
+use std::sync::Arc;
+
+use datafusion_common::Result;
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRecursion};
+use datafusion_physical_expr::expressions::DynamicFilterPhysicalExpr;
+use datafusion_physical_expr_common::physical_expr::is_volatile;
+use datafusion_physical_plan::ExecutionPlan;
+use datafusion_physical_plan::joins::{HashJoinExec, HashJoinExecBuilder, PartitionMode};
+
+use super::result_cache::source_files;
+use crate::execution::SessionState;
+
+/// Records the versions of the files read by the build side of the
+/// [`PartitionMode::CollectLeft`] hash joins of `physical_plan`, so that they
+/// can share their build side through the join build side cache of the
+/// runtime (see [`HashJoinExec::build_side_snapshot`]).
+///
+/// Only build sides whose output is fully determined by the files they read
+/// are eligible: all their leaves must be file scans (or produce constant
+/// rows), and they must not contain volatile expressions or dynamic filters.
+pub(crate) fn plan_with_join_build_side_cache(
+    state: &SessionState,
+    physical_plan: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if state
+        .runtime_env()
+        .cache_manager
+        .get_join_build_side_cache()
+        .is_none()
+    {
+        return Ok(physical_plan);
+    }
+
+    physical_plan
+        .transform_up(|plan| {
+            let Some(join) = plan.downcast_ref::<HashJoinExec>() else {
+                return Ok(Transformed::no(plan));
+            };
+            if *join.partition_mode() != PartitionMode::CollectLeft
+                || !is_deterministic(join.left())?
+            {
+                return Ok(Transformed::no(plan));
+            }
+            let sources = match source_files(join.left())? {
+                Some(sources) if !sources.is_empty() => sources,
+                _ => return Ok(Transformed::no(plan)),
+            };
+
+            let join = HashJoinExecBuilder::from(join)
+                .with_build_side_snapshot(Some(sources))
+                .build()?;
+            Ok(Transformed::yes(Arc::new(join) as Arc<dyn ExecutionPlan>))
+        })
+        .map(|transformed| transformed.data)
+}
+
+/// Returns true if `plan` has no volatile expressions nor dynamic filters, so
+/// that its output only depends on the data it reads.
+fn is_deterministic(plan: &Arc<dyn ExecutionPlan>) -> Result<bool> {
+    let mut deterministic = true;
+    plan.apply(|node| {
+        node.apply_expressions(&mut |root| {
+            root.apply(|expr| {
+                deterministic = !is_volatile(expr)
+                    && expr.downcast_ref::<DynamicFilterPhysicalExpr>().is_none();
+                Ok(if deterministic {
+                    TreeNodeRecursion::Continue
+                } else {
+                    TreeNodeRecursion::Stop
+                })
+            })
+        })?;
+        Ok(if deterministic {
+            TreeNodeRecursion::Continue
+        } else {
+            TreeNodeRecursion::Stop
+        })
+    })?;
+    Ok(deterministic)
+}
@@ -21,6 +21,8 @@ pub mod context;
 pub mod session_state;
 pub use session_state::{SessionState, SessionStateBuilder};
 
+mod join_build_side_cache;
+mod result_cache;
 mod session_state_defaults;
 
 pub use session_state_defaults::SessionStateDefaults;
@@ -0,0 +1,310 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Serving queries from the [`ResultCache`] of the runtime.
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::array::RecordBatch;
+use arrow_schema::SchemaRef;
+use datafusion_common::Result;
+use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
+use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_datasource::memory::MemorySourceConfig;
+use datafusion_datasource::source::DataSourceExec;
+use datafusion_execution::cache::cache_manager::{
+    CachedQueryResult, ResultCache, ResultCacheKey,
+};
+use datafusion_execution::{RecordBatchStream, SendableRecordBatchStream, TaskContext};
+use datafusion_expr::LogicalPlan;
+use datafusion_optimizer::LogicalPlanSignature;
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion_physical_plan::empty::EmptyExec;
+use datafusion_physical_plan::placeholder_row::PlaceholderRowExec;
+use datafusion_physical_plan::{
+    ChildrenPropertiesMode, DisplayAs, DisplayFormatType, ExecutionPlan,
+    ExecutionPlanProperties, PlanProperties, ReplaceChildrenOptions,
+    validate_child_count,
+};
+use futures::{Stream, StreamExt};
+use object_store::ObjectMeta;
+
+use crate::execution::SessionState;
+
+/// Serves the query planned as `physical_plan` from the result cache of the
+/// runtime, if any, or wraps it so that its results are stored in the cache.
+///
+/// A query is only cached when
+/// * the session has not disabled the cache (`datafusion.execution.use_result_cache`),
+/// * its logical plan is a read-only query without volatile expressions, and
+/// * all the leaves of its physical plan are file scans (or produce constant
+///   rows), so that the versions of the files it reads identify its results,
+///   and it reads at least one file.
+///
+/// Cache entries are keyed by the fingerprint of the optimized `logical_plan`
+/// (see [`LogicalPlanSignature`]), so queries that optimize to the same plan
+/// share their results, together with the versions of the scanned files.
+pub(crate) fn plan_with_result_cache(
+    state: &SessionState,
+    logical_plan: LogicalPlan,
+    physical_plan: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if !state.config_options().execution.use_result_cache {
+        return Ok(physical_plan);
+    }
+    let Some(cache) = state.runtime_env().cache_manager.get_result_cache() else {
+        return Ok(physical_plan);
+    };
+    if physical_plan.boundedness().is_unbounded() || !is_cacheable(&logical_plan)? {
+        return Ok(physical_plan);
+    }
+    // queries not reading any file are cheap, and not worth caching
+    let sources = match source_files(&physical_plan)? {
+        Some(sources) if !sources.is_empty() => sources,
+        _ => return Ok(physical_plan),
+    };
+
+    let fingerprint = LogicalPlanSignature::new(&logical_plan).fingerprint();
+    let key = ResultCacheKey::new(fingerprint, Arc::new(logical_plan), sources);
+    if let Some(cached) = cache.get(&key) {
+        return Ok(MemorySourceConfig::try_new_exec(
+            &[cached.batches.as_ref().clone()],
+            physical_plan.schema(),
+            None,
+        )?);
+    }
+
+    Ok(Arc::new(ResultCacheExec::new(physical_plan, cache, key)))
+}
+
+/// Returns true if the results of `plan` only depend on the data it reads.
+fn is_cacheable(plan: &LogicalPlan) -> Result<bool> {
+    let mut cacheable = true;
+    plan.apply_with_subqueries(|node| {
+        let read_only = !matches!(
+            node,
+            LogicalPlan::Dml(_)
+                | LogicalPlan::Ddl(_)
+                | LogicalPlan::Copy(_)
+                | LogicalPlan::Explain(_)
+                | LogicalPlan::Analyze(_)
+                | LogicalPlan::Statement(_)
+                | LogicalPlan::DescribeTable(_)
+        );
+        cacheable = read_only && !node.expressions().iter().any(|e| e.is_volatile());
+        Ok(if cacheable {
+            TreeNodeRecursion::Continue
+        } else {
+            TreeNodeRecursion::Stop
+        })
+    })?;
+    Ok(cacheable)
+}
+
+/// Returns the files read by `plan`, or `None` if any of its leaves is not a
+/// file scan or a constant.
+pub(super) fn source_files(
+    plan: &Arc<dyn ExecutionPlan>,
+) -> Result<Option<Vec<ObjectMeta>>> {
+    let mut files = Some(vec![]);
+    plan.apply(|node| {
+        if !node.children().is_empty()
+            || node.downcast_ref::<EmptyExec>().is_some()
+            || node.downcast_ref::<PlaceholderRowExec>().is_some()
+        {
+            return Ok(TreeNodeRecursion::Continue);
+        }
+        let file_scan = node
+            .downcast_ref::<DataSourceExec>()
+            .and_then(|exec| exec.data_source().downcast_ref::<FileScanConfig>());
+        match (file_scan, files.as_mut()) {
+            (Some(config), Some(files)) => {
+                files.extend(
+                    config
+                        .file_groups
+                        .iter()
+                        .flat_map(|group| group.iter())
+                        .map(|file| file.object_meta.clone()),
+                );
+                Ok(TreeNodeRecursion::Continue)
+            }
+            _ => {
+                files = None;
+                Ok(TreeNodeRecursion::Stop)
+            }
+        }
+    })?;
+    Ok(files)
+}
+
+/// Passes through the results of its input, storing them in a
+/// [`ResultCache`] once the input has been fully read.
+///
+/// Results larger than the limit of the cache are not stored.
+#[derive(Debug)]
+struct ResultCacheExec {
+    input: Arc<dyn ExecutionPlan>,
+    cache: Arc<ResultCache>,
+    key: ResultCacheKey,
+}
+
+impl ResultCacheExec {
+    fn new(
+        input: Arc<dyn ExecutionPlan>,
+        cache: Arc<ResultCache>,
+        key: ResultCacheKey,
+    ) -> Self {
+        // the results are cached as a single partition
+        let input = if input.output_partitioning().partition_count() > 1 {
+            Arc::new(CoalescePartitionsExec::new(input))
+        } else {
+            input
+        };
+        Self { input, cache, key }
+    }
+}
+
+impl DisplayAs for ResultCacheExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "ResultCacheExec: cache={}", self.cache.name())
+            }
+            DisplayFormatType::TreeRender => write!(f, "cache={}", self.cache.name()),
+        }
+    }
+}
+
+impl ExecutionPlan for ResultCacheExec {
+    fn name(&self) -> &'static str {
+        "ResultCacheExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        self.input.properties()
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn apply_expressions(
+        &self,
+        _f: &mut dyn FnMut(&Arc<dyn PhysicalExpr>) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        Ok(TreeNodeRecursion::Continue)
+    }
+
+    fn replace_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+        _options: ReplaceChildrenOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        validate_child_count!(self, children);
+        Ok(Arc::new(Self::new(
+            children.swap_remove(0),
+            Arc::clone(&self.cache),
+            self.key.clone(),
+        )))
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.replace_children(
+            children,
+            ReplaceChildrenOptions::new(ChildrenPropertiesMode::Recompute),
+        )
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let input = self.input.execute(partition, context)?;
+        Ok(Box::pin(ResultCacheStream {
+            schema: self.schema(),
+            input,
+            batches: Some(vec![]),
+            size: 0,
+            cache: Arc::clone(&self.cache),
+            key: self.key.clone(),
+        }))
+    }
+}
+
+/// Stream of [`ResultCacheExec`].
+struct ResultCacheStream {
+    schema: SchemaRef,
+    input: SendableRecordBatchStream,
+    /// The batches read so far, or `None` if the results will not be cached.
+    batches: Option<Vec<RecordBatch>>,
+    /// Memory size of `batches`.
+    size: usize,
+    cache: Arc<ResultCache>,
+    key: ResultCacheKey,
+}
+
+impl Stream for ResultCacheStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.input.poll_next_unpin(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(batch))) => {
+                self.size += batch.get_array_memory_size();
+                if self.size > self.cache.cache_limit() {
+                    self.batches = None;
+                } else if let Some(batches) = self.batches.as_mut() {
+                    batches.push(batch.clone());
+                }
+            }
+            Poll::Ready(Some(Err(_))) => self.batches = None,
+            Poll::Ready(None) => {
+                if let Some(batches) = self.batches.take() {
+                    let result =
+                        CachedQueryResult::new(Arc::clone(&self.schema), batches);
+                    self.cache.put(&self.key, result);
+                }
+            }
+            Poll::Pending => {}
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for ResultCacheStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
@@ -29,6 +29,8 @@ use crate::datasource::file_format::FileFormatFactory;
 use crate::datasource::provider_as_source;
 use crate::execution::SessionStateDefaults;
 use crate::execution::context::{EmptySerializerRegistry, FunctionFactory, QueryPlanner};
+use crate::execution::join_build_side_cache::plan_with_join_build_side_cache;
+use crate::execution::result_cache::plan_with_result_cache;
 use crate::physical_planner::{DefaultPhysicalPlanner, PhysicalPlanner};
 #[cfg(feature = "sql")]
 use arrow_schema::DataType;
@@ -454,6 +456,18 @@ impl SessionState {
             .push(optimizer_rule);
     }
 
+    /// Adds `optimizer_rule` before the other optimizer rules, so it is
+    /// applied to the analyzed plan in the first pass.
+    pub(crate) fn prepend_optimizer_rule(
+        &mut self,
+        optimizer_rule: Arc<dyn OptimizerRule + Send + Sync>,
+    ) {
+        Arc::make_mut(&mut self.inner)
+            .optimizer
+            .rules
+            .insert(0, optimizer_rule);
+    }
+
     /// Removes an optimizer rule by name, returning `true` if it existed.
     pub(crate) fn remove_optimizer_rule(&mut self, name: &str) -> bool {
         let optimizer = &mut Arc::make_mut(&mut self.inner).optimizer;
@@ -830,10 +844,13 @@ impl SessionState {
         logical_plan: &LogicalPlan,
     ) -> datafusion_common::Result<Arc<dyn ExecutionPlan>> {
         let logical_plan = self.optimize(logical_plan)?;
-        self.inner
+        let physical_plan = self
+            .inner
             .query_planner
             .create_physical_plan(&logical_plan, self)
-            .await
+            .await?;
+        let physical_plan = plan_with_join_build_side_cache(self, physical_plan)?;
+        plan_with_result_cache(self, logical_plan, physical_plan)
     }
 
     /// Create a [`PhysicalExpr`] from an [`Expr`] after applying type
@@ -1254,7 +1271,8 @@ impl SessionStateBuilder {
     }
 
     /// Adds defaults for table_factories, file formats, expr_planners and builtin
-    /// scalar, aggregate and windows functions.
+    /// scalar, aggregate and windows functions, and the default statistics
+    /// registry if none is set.
     ///
     /// Note overwrites any previously registered items with the same name.
     pub fn with_default_features(mut self) -> Self {
@@ -1299,6 +1317,9 @@ impl SessionStateBuilder {
                     .map(|f| (f.name().to_string(), f)),
             );
 
+        self.statistics_registry
+            .get_or_insert_with(SessionStateDefaults::default_statistics_registry);
+
         self
     }
 
@@ -26,6 +26,7 @@ use crate::datasource::file_format::json::JsonFormatFactory;
 #[cfg(feature = "parquet")]
 use crate::datasource::file_format::parquet::ParquetFormatFactory;
 use crate::datasource::provider::DefaultTableFactory;
+use crate::datasource::source::DataSourceStatisticsProvider;
 use crate::execution::context::SessionState;
 #[cfg(feature = "nested_expressions")]
 use crate::functions_nested;
@@ -38,6 +39,7 @@ use datafusion_execution::runtime_env::RuntimeEnv;
 use datafusion_expr::planner::ExprPlanner;
 use datafusion_expr::registry::ExtensionTypeRegistrationRef;
 use datafusion_expr::{AggregateUDF, HigherOrderUDF, ScalarUDF, WindowUDF};
+use datafusion_physical_plan::operator_statistics::StatisticsRegistry;
 use std::collections::HashMap;
 use std::sync::Arc;
 use url::Url;
@@ -95,6 +97,8 @@ impl SessionStateDefaults {
             Arc::new(functions::datetime::planner::DatetimeFunctionPlanner),
             #[cfg(feature = "unicode_expressions")]
             Arc::new(functions::unicode::planner::UnicodeFunctionPlanner),
+            #[cfg(feature = "json_expressions")]
+            Arc::new(functions::json::planner::JsonFunctionPlanner),
             Arc::new(functions_aggregate::planner::AggregateFunctionPlanner),
             Arc::new(functions_window::planner::WindowFunctionPlanner),
         ];
@@ -104,12 +108,13 @@ impl SessionStateDefaults {
 
     /// returns the list of default [`ScalarUDF`]s
     pub fn default_scalar_functions() -> Vec<Arc<ScalarUDF>> {
-        #[cfg_attr(not(feature = "nested_expressions"), expect(unused_mut))]
         let mut functions: Vec<Arc<ScalarUDF>> = functions::all_default_functions();
 
         #[cfg(feature = "nested_expressions")]
         functions.append(&mut functions_nested::all_default_nested_functions());
 
+        functions.append(&mut functions_aggregate::all_default_sketch_functions());
+
         functions
     }
 
@@ -144,6 +149,15 @@ impl SessionStateDefaults {
         functions_table::all_default_table_functions()
     }
 
+    /// returns the default [`StatisticsRegistry`]: the built-in providers,
+    /// preceded by a [`DataSourceStatisticsProvider`] so that the histograms
+    /// computed by `ANALYZE TABLE` are used to estimate filters
+    pub fn default_statistics_registry() -> StatisticsRegistry {
+        let mut registry = StatisticsRegistry::default_with_builtin_providers();
+        registry.register(Arc::new(DataSourceStatisticsProvider));
+        registry
+    }
+
     /// returns the list of default [`FileFormatFactory`]s
     pub fn default_file_formats() -> Vec<Arc<dyn FileFormatFactory>> {
         let file_formats: Vec<Arc<dyn FileFormatFactory>> = vec![
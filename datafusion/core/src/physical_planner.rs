@@ -41,7 +41,8 @@ use crate::physical_plan::explain::ExplainExec;
 use crate::physical_plan::filter::FilterExecBuilder;
 use crate::physical_plan::joins::utils as join_utils;
 use crate::physical_plan::joins::{
-    CrossJoinExec, HashJoinExec, NestedLoopJoinExec, PartitionMode, SortMergeJoinExec,
+    AsOfJoinExec, AsOfMatchExpr, CrossJoinExec, HashJoinExec, NestedLoopJoinExec,
+    PartitionMode, SortMergeJoinExec,
 };
 use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
 use crate::physical_plan::projection::{ProjectionExec, ProjectionExpr};
@@ -73,10 +74,12 @@ use datafusion_common::{
     not_impl_err, plan_err,
 };
 use datafusion_common::{
-    TableReference, assert_eq_or_internal_err, assert_or_internal_err,
+    ResolvedTableReference, TableReference, assert_eq_or_internal_err,
+    assert_or_internal_err,
 };
 use datafusion_datasource::file_groups::FileGroup;
 use datafusion_datasource::memory::MemorySourceConfig;
+use datafusion_datasource::source::DataSourceExec;
 use datafusion_expr::dml::{CopyTo, InsertOp};
 use datafusion_expr::expr::{
     Alias, GroupingSet, NullTreatment, WindowFunction, WindowFunctionParams,
@@ -90,9 +93,9 @@ use datafusion_expr::physical_planning_context::{
 };
 use datafusion_expr::utils::{expr_to_columns, split_conjunction};
 use datafusion_expr::{
-    Analyze, BinaryExpr, DescribeTable, DmlStatement, Explain, ExplainFormat, Extension,
-    FetchType, Filter, JoinType, Operator, RecursiveQuery, SkipType, StringifiedPlan,
-    WindowFrame, WindowFrameBound, WriteOp,
+    Analyze, AsOfJoin, BinaryExpr, DescribeTable, DmlStatement, Explain, ExplainFormat,
+    Extension, FetchType, Filter, JoinType, Operator, RecursiveQuery, SkipType,
+    StringifiedPlan, WindowFrame, WindowFrameBound, WriteOp,
 };
 use datafusion_physical_expr::aggregate::{
     AggregateFunctionExpr, LoweredAggregate, LoweredAggregateBuilder,
@@ -108,7 +111,9 @@ use datafusion_physical_plan::placeholder_row::PlaceholderRowExec;
 use datafusion_physical_plan::recursive_query::RecursiveQueryExec;
 use datafusion_physical_plan::scalar_subquery::{ScalarSubqueryExec, ScalarSubqueryLink};
 use datafusion_physical_plan::unnest::ListUnnest;
-use datafusion_session::{PhysicalOptimizerContext, PhysicalOptimizerRule, Session};
+use datafusion_session::{
+    PhysicalOptimizerContext, PhysicalOptimizerRule, Session, TableStatisticsStore,
+};
 
 use async_trait::async_trait;
 use datafusion_physical_plan::async_func::{AsyncFuncExec, AsyncMapper};
@@ -580,6 +585,9 @@ impl DefaultPhysicalPlanner {
         children: ChildrenContainer,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         let execution_props = session_state.execution_props();
+        if let LogicalPlan::Dml(dml) = node {
+            remove_stored_table_statistics(session_state, &dml.table_name)?;
+        }
         let exec_node: Arc<dyn ExecutionPlan> = match node {
             // Leaves (no children)
             LogicalPlan::TableScan(scan) => {
@@ -607,7 +615,11 @@ impl DefaultPhysicalPlanner {
                         .with_limit(*fetch)
                         .with_statistics_requests(&stats_requests);
                     let res = source.scan_with_args(session_state, opts).await?;
-                    Arc::clone(res.plan())
+                    with_stored_table_statistics(
+                        session_state,
+                        &scan.table_name,
+                        Arc::clone(res.plan()),
+                    )
                 } else {
                     let mut maybe_plan = None;
                     for planner in &self.extension_planners {
@@ -1790,6 +1802,49 @@ impl DefaultPhysicalPlanner {
                     join
                 }
             }
+            LogicalPlan::AsOfJoin(AsOfJoin {
+                left,
+                right,
+                on,
+                match_condition,
+                ..
+            }) => {
+                let [physical_left, physical_right] = children.two()?;
+                let left_df_schema = left.schema();
+                let right_df_schema = right.schema();
+                let create_left = |expr| {
+                    create_physical_expr(
+                        expr,
+                        left_df_schema,
+                        execution_props,
+                        planning_ctx,
+                    )
+                };
+                let create_right = |expr| {
+                    create_physical_expr(
+                        expr,
+                        right_df_schema,
+                        execution_props,
+                        planning_ctx,
+                    )
+                };
+                let join_on = on
+                    .iter()
+                    .map(|(l, r)| Ok((create_left(l)?, create_right(r)?)))
+                    .collect::<Result<join_utils::JoinOn>>()?;
+                let match_condition = AsOfMatchExpr::new(
+                    create_left(&match_condition.left)?,
+                    match_condition.op,
+                    create_right(&match_condition.right)?,
+                );
+                Arc::new(AsOfJoinExec::try_new(
+                    physical_left,
+                    physical_right,
+                    join_on,
+                    match_condition,
+                    None,
+                )?)
+            }
             LogicalPlan::RecursiveQuery(RecursiveQuery {
                 name,
                 is_distinct,
@@ -1951,6 +2006,61 @@ impl DefaultPhysicalPlanner {
     }
 }
 
+/// Attaches the statistics stored for `table_name` in the statistics store of
+/// its catalog (see `ANALYZE TABLE`) to a scan of the table.
+///
+/// Only [`DataSourceExec`] scans can carry table statistics; other plans are
+/// returned unchanged.
+fn with_stored_table_statistics(
+    session_state: &dyn Session,
+    table_name: &TableReference,
+    plan: Arc<dyn ExecutionPlan>,
+) -> Arc<dyn ExecutionPlan> {
+    let Some(exec) = plan.downcast_ref::<DataSourceExec>() else {
+        return plan;
+    };
+    let table_statistics = table_statistics_store(session_state, table_name).and_then(
+        |(store, resolved)| store.table_statistics(&resolved.schema, &resolved.table),
+    );
+    match table_statistics {
+        Some(table_statistics) => {
+            Arc::new(exec.clone().with_table_statistics(table_statistics))
+        }
+        None => plan,
+    }
+}
+
+/// Removes the statistics stored for `table_name` (see `ANALYZE TABLE`) when
+/// a write to the table is planned, as they no longer describe the table once
+/// the write is executed.
+fn remove_stored_table_statistics(
+    session_state: &dyn Session,
+    table_name: &TableReference,
+) -> Result<()> {
+    if let Some((store, resolved)) = table_statistics_store(session_state, table_name) {
+        store.remove_table_statistics(&resolved.schema, &resolved.table)?;
+    }
+    Ok(())
+}
+
+/// Returns the statistics store of the catalog of `table_name`, if any, with
+/// the resolved name of the table.
+fn table_statistics_store(
+    session_state: &dyn Session,
+    table_name: &TableReference,
+) -> Option<(Arc<dyn TableStatisticsStore>, ResolvedTableReference)> {
+    let catalog_options = &session_state.config_options().catalog;
+    let resolved = table_name.clone().resolve(
+        &catalog_options.default_catalog,
+        &catalog_options.default_schema,
+    );
+    let store = session_state
+        .catalog_list()
+        .catalog(&resolved.catalog)?
+        .statistics_store()?;
+    Some((store, resolved))
+}
+
 /// Expand and align a GROUPING SET expression.
 /// (see <https://www.postgresql.org/docs/current/queries-table-expressions.html#QUERIES-GROUPING-SETS>)
 ///
@@ -2291,6 +2401,7 @@ fn extract_dml_filters(
             | LogicalPlan::Sort(_)
             | LogicalPlan::Union(_)
             | LogicalPlan::Join(_)
+            | LogicalPlan::AsOfJoin(_)
             | LogicalPlan::Repartition(_)
             | LogicalPlan::Aggregate(_)
             | LogicalPlan::Window(_)
@@ -21,9 +21,11 @@ use datafusion::datasource::empty::EmptyTable;
 use datafusion::datasource::listing::{
     ListingTable, ListingTableConfig, ListingTableConfigExt, ListingTableUrl,
 };
-use datafusion::prelude::SessionContext;
+use datafusion::prelude::{SessionConfig, SessionContext};
 use datafusion_catalog::memory::*;
 use datafusion_catalog::{SchemaProvider, TableProvider};
+use datafusion_common::ScalarValue;
+use datafusion_common::stats::Precision;
 use datafusion_common::test_util::batches_to_string;
 use insta::assert_snapshot;
 use std::sync::Arc;
@@ -179,3 +181,90 @@ async fn test_schema_register_listing_table() {
     +----+----------+
     ");
 }
+
+/// Creates a table with 900 rows with `v = 1` and 100 rows with `v` in
+/// 901..=1000, and analyzes it in a session with the default statistics
+/// registry
+async fn analyzed_skewed_table() -> SessionContext {
+    let config = SessionConfig::new().with_target_partitions(1);
+    let ctx = SessionContext::new_with_config(config);
+    ctx.sql(
+        "CREATE TABLE t AS SELECT CASE WHEN x <= 900 THEN 1 ELSE x END AS v, \
+         CASE WHEN x % 2 = 0 THEN 'even' END AS s \
+         FROM generate_series(1, 1000) g(x)",
+    )
+    .await
+    .unwrap();
+    ctx.sql("ANALYZE TABLE t")
+        .await
+        .unwrap()
+        .collect()
+        .await
+        .unwrap();
+    ctx
+}
+
+#[tokio::test]
+async fn analyze_table_stores_statistics() {
+    let ctx = analyzed_skewed_table().await;
+
+    let store = ctx
+        .catalog("datafusion")
+        .unwrap()
+        .statistics_store()
+        .unwrap();
+    let stats = store.table_statistics("public", "t").unwrap();
+    assert_eq!(stats.num_rows, 1000);
+
+    let v = &stats.column_statistics["v"];
+    assert_eq!(v.null_count, Precision::Exact(0));
+    assert_eq!(v.min_value, Precision::Exact(ScalarValue::Int64(Some(1))));
+    assert_eq!(
+        v.max_value,
+        Precision::Exact(ScalarValue::Int64(Some(1000)))
+    );
+    // HyperLogLog estimate of the 101 distinct values
+    assert_eq!(v.distinct_count, Precision::Inexact(100));
+
+    let s = &stats.column_statistics["s"];
+    assert_eq!(s.null_count, Precision::Exact(500));
+    assert_eq!(s.distinct_count, Precision::Inexact(1));
+
+    // the table is smaller than the sample size, so the histograms are exact
+    let histogram = &stats.histograms["v"];
+    assert_eq!(histogram.total_count(), 1000);
+    assert_eq!(histogram.buckets()[0].count, 900);
+    assert_eq!(stats.histograms["s"].total_count(), 500);
+
+    // dropping the table drops its statistics
+    ctx.sql("DROP TABLE t").await.unwrap();
+    assert!(store.table_statistics("public", "t").is_none());
+}
+
+#[tokio::test]
+async fn analyze_table_histograms_improve_filter_estimates() {
+    let ctx = analyzed_skewed_table().await;
+    let state = ctx.state();
+    let registry = state.statistics_registry().unwrap();
+
+    for (sql, expected_rows) in [
+        ("SELECT * FROM t WHERE v < 10", 900),
+        ("SELECT * FROM t WHERE v = 1", 900),
+        ("SELECT * FROM t WHERE v > 950", 51),
+        ("SELECT * FROM t WHERE v = 1 AND s = 'even'", 450),
+    ] {
+        let plan = ctx
+            .sql(sql)
+            .await
+            .unwrap()
+            .create_physical_plan()
+            .await
+            .unwrap();
+        let stats = registry.compute(plan.as_ref()).unwrap();
+        assert_eq!(
+            stats.base().num_rows,
+            Precision::Inexact(expected_rows),
+            "{sql}"
+        );
+    }
+}
@@ -24,7 +24,7 @@ use std::{
 
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
-use datafusion_common::config::ConfigOptions;
+use datafusion_common::config::{ConfigJoinDpThreshold, ConfigOptions};
 use datafusion_common::tree_node::TreeNodeRecursion;
 use datafusion_common::{ColumnStatistics, JoinType, ScalarValue, stats::Precision};
 use datafusion_common::{JoinSide, NullEquality};
@@ -1150,6 +1150,194 @@ async fn test_join_selection_partitioned() {
     check_join_partition_mode(big, empty, join_on, false, PartitionMode::Partitioned);
 }
 
+/// Create a fact table with two foreign keys and two dimension tables, where
+/// joining `dim_b` is far more selective than joining `dim_a`
+fn create_star_schema() -> (
+    Arc<dyn ExecutionPlan>,
+    Arc<dyn ExecutionPlan>,
+    Arc<dyn ExecutionPlan>,
+) {
+    let fact = Arc::new(StatisticsExec::new(
+        Statistics {
+            num_rows: Precision::Inexact(1_000_000),
+            total_byte_size: Precision::Inexact(16_000_000),
+            column_statistics: [
+                create_column_stats(None, None, Some(100)),
+                create_column_stats(None, None, Some(1000)),
+            ]
+            .concat(),
+        },
+        Schema::new(vec![
+            Field::new("fact_a", DataType::Int32, false),
+            Field::new("fact_b", DataType::Int32, false),
+        ]),
+    ));
+    let dim_a = Arc::new(StatisticsExec::new(
+        Statistics {
+            num_rows: Precision::Inexact(100),
+            total_byte_size: Precision::Inexact(800),
+            column_statistics: create_column_stats(None, None, Some(100)),
+        },
+        Schema::new(vec![Field::new("dim_a", DataType::Int32, false)]),
+    ));
+    let dim_b = Arc::new(StatisticsExec::new(
+        Statistics {
+            num_rows: Precision::Inexact(10),
+            total_byte_size: Precision::Inexact(80),
+            column_statistics: create_column_stats(None, None, Some(10)),
+        },
+        Schema::new(vec![Field::new("dim_b", DataType::Int32, false)]),
+    ));
+    (fact, dim_a, dim_b)
+}
+
+/// Create `(fact JOIN dim_a) JOIN dim_b` in the order written in the query
+fn create_star_join() -> Arc<dyn ExecutionPlan> {
+    let (fact, dim_a, dim_b) = create_star_schema();
+    let fact_dim_a = Arc::new(
+        HashJoinExec::try_new(
+            Arc::clone(&fact),
+            Arc::clone(&dim_a),
+            vec![(
+                col("fact_a", &fact.schema()).unwrap(),
+                col("dim_a", &dim_a.schema()).unwrap(),
+            )],
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            NullEquality::NullEqualsNothing,
+            false,
+        )
+        .unwrap(),
+    );
+    Arc::new(
+        HashJoinExec::try_new(
+            Arc::clone(&fact_dim_a) as _,
+            Arc::clone(&dim_b),
+            vec![(
+                col("fact_b", &fact_dim_a.schema()).unwrap(),
+                col("dim_b", &dim_b.schema()).unwrap(),
+            )],
+            None,
+            &JoinType::Inner,
+            None,
+            PartitionMode::Partitioned,
+            NullEquality::NullEqualsNothing,
+            false,
+        )
+        .unwrap(),
+    )
+}
+
+fn optimize_with_join_enumeration(
+    plan: Arc<dyn ExecutionPlan>,
+    dp_threshold: usize,
+) -> String {
+    let mut config = ConfigOptions::new();
+    config.optimizer.enable_join_enumeration = true;
+    config.optimizer.join_enumeration_dp_threshold =
+        ConfigJoinDpThreshold::try_new(dp_threshold).unwrap();
+    let optimized = JoinSelection::new().optimize(plan, &config).unwrap();
+    displayable(optimized.as_ref())
+        .indent(true)
+        .to_string()
+        .trim()
+        .to_string()
+}
+
+#[tokio::test]
+async fn test_join_enumeration_dynamic_programming() {
+    let optimized = optimize_with_join_enumeration(create_star_join(), 10);
+    assert_snapshot!(
+        optimized,
+        @r"
+    ProjectionExec: expr=[fact_a@2 as fact_a, fact_b@3 as fact_b, dim_a@0 as dim_a, dim_b@1 as dim_b]
+      HashJoinExec: mode=Partitioned, join_type=Inner, on=[(dim_a@0, fact_a@1)], estimated_rows=10000, estimated_cost=20000
+        StatisticsExec: col_count=1, row_count=Inexact(100)
+        HashJoinExec: mode=Partitioned, join_type=Inner, on=[(dim_b@0, fact_b@1)], estimated_rows=10000, estimated_cost=10000
+          StatisticsExec: col_count=1, row_count=Inexact(10)
+          StatisticsExec: col_count=2, row_count=Inexact(1000000)
+    "
+    );
+}
+
+#[tokio::test]
+async fn test_join_enumeration_greedy() {
+    let optimized = optimize_with_join_enumeration(create_star_join(), 2);
+    assert_snapshot!(
+        optimized,
+        @r"
+    ProjectionExec: expr=[fact_a@2 as fact_a, fact_b@3 as fact_b, dim_a@0 as dim_a, dim_b@1 as dim_b]
+      HashJoinExec: mode=Partitioned, join_type=Inner, on=[(dim_a@0, fact_a@1)], estimated_rows=10000, estimated_cost=20000
+        StatisticsExec: col_count=1, row_count=Inexact(100)
+        HashJoinExec: mode=Partitioned, join_type=Inner, on=[(dim_b@0, fact_b@1)], estimated_rows=10000, estimated_cost=10000
+          StatisticsExec: col_count=1, row_count=Inexact(10)
+          StatisticsExec: col_count=2, row_count=Inexact(1000000)
+    "
+    );
+}
+
+#[tokio::test]
+async fn test_join_enumeration_too_many_relations() {
+    // A chain of 70 joined relations, more than a 64 bit set can track
+    let relation = |i: usize| -> Arc<dyn ExecutionPlan> {
+        Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Precision::Inexact(10 * (i + 1)),
+                total_byte_size: Precision::Inexact(80 * (i + 1)),
+                column_statistics: create_column_stats(None, None, Some(10)),
+            },
+            Schema::new(vec![Field::new(format!("c{i}"), DataType::Int32, false)]),
+        ))
+    };
+    let plan = (1..70).fold(relation(0), |plan, i| {
+        let right = relation(i);
+        let on = vec![(
+            col(&format!("c{}", i - 1), &plan.schema()).unwrap(),
+            col(&format!("c{i}"), &right.schema()).unwrap(),
+        )];
+        Arc::new(
+            HashJoinExec::try_new(
+                plan,
+                right,
+                on,
+                None,
+                &JoinType::Inner,
+                None,
+                PartitionMode::Partitioned,
+                NullEquality::NullEqualsNothing,
+                false,
+            )
+            .unwrap(),
+        )
+    });
+    let optimized = optimize_with_join_enumeration(plan, 10);
+    assert_eq!(optimized.matches("StatisticsExec").count(), 70);
+    assert!(!optimized.contains("estimated_cost"), "{optimized}");
+}
+
+#[tokio::test]
+async fn test_join_enumeration_disabled() {
+    // Only the build and probe sides of the individual joins are swapped
+    let optimized = JoinSelection::new()
+        .optimize(create_star_join(), &ConfigOptions::new())
+        .unwrap();
+    let actual = displayable(optimized.as_ref()).indent(true).to_string();
+    assert_snapshot!(
+        actual.trim(),
+        @r"
+    ProjectionExec: expr=[fact_a@1 as fact_a, fact_b@2 as fact_b, dim_a@3 as dim_a, dim_b@0 as dim_b]
+      HashJoinExec: mode=Partitioned, join_type=Inner, on=[(dim_b@0, fact_b@1)]
+        StatisticsExec: col_count=1, row_count=Inexact(10)
+        ProjectionExec: expr=[fact_a@1 as fact_a, fact_b@2 as fact_b, dim_a@0 as dim_a]
+          HashJoinExec: mode=Partitioned, join_type=Inner, on=[(dim_a@0, fact_a@0)]
+            StatisticsExec: col_count=1, row_count=Inexact(100)
+            StatisticsExec: col_count=2, row_count=Inexact(1000000)
+    "
+    );
+}
+
 fn check_join_partition_mode(
     left: Arc<StatisticsExec>,
     right: Arc<StatisticsExec>,
@@ -0,0 +1,148 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tests for the join build side cache
+
+use datafusion::physical_plan::joins::HashJoinExec;
+use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
+
+use super::*;
+
+/// Creates a single partition context with a join build side cache, and tables
+/// `dim` and `fact` backed by CSV files with the given contents.
+async fn setup(dim: &str, fact: &str) -> Result<(SessionContext, TempDir)> {
+    let config = SessionConfig::new().with_target_partitions(1);
+    let ctx = SessionContext::new_with_config(config);
+    ctx.sql("SET datafusion.runtime.join_build_side_cache_limit = '1M'")
+        .await?
+        .collect()
+        .await?;
+
+    let tmp_dir = TempDir::new()?;
+    for (name, contents) in [("dim", dim), ("fact", fact)] {
+        let path = tmp_dir.path().join(format!("{name}.csv"));
+        std::fs::write(&path, contents)?;
+        ctx.register_csv(name, path.to_str().unwrap(), CsvReadOptions::new())
+            .await?;
+    }
+    Ok((ctx, tmp_dir))
+}
+
+/// Runs `sql`, returning the results and the number of build side cache hits
+/// and misses of its hash join.
+async fn run(ctx: &SessionContext, sql: &str) -> Result<(String, usize, usize)> {
+    let plan = ctx.sql(sql).await?.create_physical_plan().await?;
+    let batches = collect(Arc::clone(&plan), ctx.task_ctx()).await?;
+
+    let mut join = None;
+    plan.apply(|node| {
+        if node.downcast_ref::<HashJoinExec>().is_some() {
+            join = Some(Arc::clone(node));
+            return Ok(TreeNodeRecursion::Stop);
+        }
+        Ok(TreeNodeRecursion::Continue)
+    })?;
+    let metrics = join.expect("hash join").metrics().unwrap();
+    let count = |name| metrics.sum_by_name(name).map_or(0, |v| v.as_usize());
+    Ok((
+        batches_to_sort_string(&batches),
+        count("build_side_cache_hits"),
+        count("build_side_cache_misses"),
+    ))
+}
+
+const DIM: &str = "k,name\n1,one\n2,two\n3,three\n";
+
+const FACT: &str = "k,v\n1,10\n2,20\n2,21\n4,40\n";
+
+const SQL: &str = "SELECT d.name, f.v FROM dim d JOIN fact f ON d.k = f.k";
+
+#[tokio::test]
+async fn join_build_side_cache_serves_repeated_joins() -> Result<()> {
+    let (ctx, _tmp_dir) = setup(DIM, FACT).await?;
+
+    let (first, hits, misses) = run(&ctx, SQL).await?;
+    assert_eq!((hits, misses), (0, 1));
+    assert_eq!(
+        ctx.runtime_env()
+            .cache_manager
+            .get_join_build_side_cache()
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // the build side is reused by later executions of the join
+    let (second, hits, misses) = run(&ctx, SQL).await?;
+    assert_eq!((hits, misses), (1, 0));
+    assert_eq!(first, second);
+
+    // even when they belong to a different query
+    let (_, hits, misses) = run(
+        &ctx,
+        &format!("SELECT name, sum(v) FROM ({SQL}) WHERE v > 10 GROUP BY name"),
+    )
+    .await?;
+    assert_eq!((hits, misses), (1, 0));
+
+    // but not by joins with a different build side
+    let (_, hits, misses) = run(
+        &ctx,
+        "SELECT d.name, f.v FROM dim d JOIN fact f ON d.k = f.k WHERE d.k > 1",
+    )
+    .await?;
+    assert_eq!((hits, misses), (0, 1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn join_build_side_cache_invalidated_by_file_changes() -> Result<()> {
+    let (ctx, tmp_dir) = setup(DIM, FACT).await?;
+
+    let (first, _, _) = run(&ctx, SQL).await?;
+    let (_, hits, _) = run(&ctx, SQL).await?;
+    assert_eq!(hits, 1);
+
+    std::fs::write(tmp_dir.path().join("dim.csv"), "k,name\n2,deux\n4,quatre\n")?;
+    let (second, hits, misses) = run(&ctx, SQL).await?;
+    assert_eq!((hits, misses), (0, 1));
+    assert_ne!(first, second);
+    assert_contains!(&second, "quatre");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn join_build_side_cache_disabled_by_default() -> Result<()> {
+    let (ctx, _tmp_dir) = setup(DIM, FACT).await?;
+    ctx.sql("RESET datafusion.runtime.join_build_side_cache_limit")
+        .await?
+        .collect()
+        .await?;
+    assert!(
+        ctx.runtime_env()
+            .cache_manager
+            .get_join_build_side_cache()
+            .is_none()
+    );
+
+    let (_, hits, misses) = run(&ctx, SQL).await?;
+    assert_eq!((hits, misses), (0, 0));
+
+    Ok(())
+}
@@ -65,8 +65,10 @@ macro_rules! assert_metrics {
 pub mod aggregates;
 pub mod create_drop;
 pub mod explain_analyze;
+mod join_build_side_cache;
 pub mod joins;
 mod path_partition;
+mod result_cache;
 mod runtime_config;
 pub mod select;
 mod sql_api;
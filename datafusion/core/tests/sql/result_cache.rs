@@ -0,0 +1,152 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tests for the query result cache
+
+use super::*;
+
+/// Creates a context with a result cache, and a table `t` backed by a CSV file
+/// with the given contents.
+async fn setup(contents: &str) -> Result<(SessionContext, TempDir, PathBuf)> {
+    let ctx = SessionContext::new();
+    ctx.sql("SET datafusion.runtime.result_cache_limit = '1M'")
+        .await?
+        .collect()
+        .await?;
+
+    let tmp_dir = TempDir::new()?;
+    let path = tmp_dir.path().join("t.csv");
+    std::fs::write(&path, contents)?;
+    ctx.register_csv("t", path.to_str().unwrap(), CsvReadOptions::new())
+        .await?;
+    Ok((ctx, tmp_dir, path))
+}
+
+/// Plans and runs `sql`, returning the displayed physical plan and the results.
+async fn run(ctx: &SessionContext, sql: &str) -> Result<(String, String)> {
+    let plan = ctx.sql(sql).await?.create_physical_plan().await?;
+    let displayed = displayable(plan.as_ref()).indent(false).to_string();
+    let batches = collect(plan, ctx.task_ctx()).await?;
+    Ok((displayed, batches_to_sort_string(&batches)))
+}
+
+fn result_cache_len(ctx: &SessionContext) -> usize {
+    ctx.runtime_env()
+        .cache_manager
+        .get_result_cache()
+        .map_or(0, |cache| cache.len())
+}
+
+#[tokio::test]
+async fn result_cache_serves_repeated_queries() -> Result<()> {
+    let (ctx, _tmp_dir, _path) = setup("a,b\n1,x\n2,y\n2,z\n").await?;
+    let sql = "SELECT a, count(*) AS n FROM t GROUP BY a";
+
+    let (plan, first) = run(&ctx, sql).await?;
+    assert_contains!(&plan, "ResultCacheExec: cache=DefaultResultCache");
+    assert_eq!(result_cache_len(&ctx), 1);
+
+    // identical queries are served from the cache, without scanning the file
+    let (plan, second) = run(&ctx, sql).await?;
+    assert_contains!(&plan, "DataSourceExec: partitions=1");
+    assert_not_contains!(&plan, "AggregateExec");
+    assert_eq!(first, second);
+
+    // so are queries that optimize to the same plan
+    let (plan, third) = run(&ctx, &format!("{sql} HAVING true")).await?;
+    assert_not_contains!(&plan, "AggregateExec");
+    assert_eq!(first, third);
+    assert_eq!(result_cache_len(&ctx), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn result_cache_invalidated_by_file_changes() -> Result<()> {
+    let (ctx, _tmp_dir, path) = setup("a,b\n1,x\n2,y\n").await?;
+    let sql = "SELECT sum(a) AS s FROM t";
+
+    let (_, first) = run(&ctx, sql).await?;
+    let (plan, _) = run(&ctx, sql).await?;
+    assert_not_contains!(&plan, "AggregateExec");
+
+    std::fs::write(&path, "a,b\n1,x\n2,y\n30,z\n")?;
+    let (plan, second) = run(&ctx, sql).await?;
+    assert_contains!(&plan, "AggregateExec");
+    assert_ne!(first, second);
+    assert_contains!(&second, "33");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn result_cache_bypassed() -> Result<()> {
+    let (ctx, _tmp_dir, _path) = setup("a,b\n1,x\n2,y\n").await?;
+
+    // by sessions disabling it
+    ctx.sql("SET datafusion.execution.use_result_cache = false")
+        .await?
+        .collect()
+        .await?;
+    let (plan, _) = run(&ctx, "SELECT a FROM t").await?;
+    assert_not_contains!(&plan, "ResultCacheExec");
+    ctx.sql("SET datafusion.execution.use_result_cache = true")
+        .await?
+        .collect()
+        .await?;
+
+    // by queries with volatile functions
+    let (plan, _) = run(&ctx, "SELECT a, random() FROM t").await?;
+    assert_not_contains!(&plan, "ResultCacheExec");
+
+    // by queries reading tables that are not file-based
+    ctx.sql("CREATE TABLE m AS VALUES (1), (2)")
+        .await?
+        .collect()
+        .await?;
+    let (plan, _) = run(&ctx, "SELECT * FROM m").await?;
+    assert_not_contains!(&plan, "ResultCacheExec");
+
+    assert_eq!(result_cache_len(&ctx), 0);
+
+    // and the cache is disabled by default
+    let ctx = SessionContext::new();
+    assert!(ctx.runtime_env().cache_manager.get_result_cache().is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn result_cache_limit() -> Result<()> {
+    let contents = format!("a,b\n{}", "1,x\n".repeat(1000));
+    let (ctx, _tmp_dir, _path) = setup(&contents).await?;
+    ctx.sql("SET datafusion.runtime.result_cache_limit = '1K'")
+        .await?
+        .collect()
+        .await?;
+
+    // results larger than the limit are not cached
+    let (plan, _) = run(&ctx, "SELECT * FROM t").await?;
+    assert_contains!(&plan, "ResultCacheExec");
+    assert_eq!(result_cache_len(&ctx), 0);
+
+    // smaller ones are
+    run(&ctx, "SELECT count(*) FROM t").await?;
+    assert_eq!(result_cache_len(&ctx), 1);
+
+    Ok(())
+}
@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use datafusion::assert_batches_eq;
 use datafusion::prelude::*;
 use datafusion_common::assert_contains;
 
@@ -231,6 +232,20 @@ async fn assert_merge_physical_error(ctx: &SessionContext, sql: &str, expected:
     assert_contains!(err.strip_backtrace(), expected);
 }
 
+async fn assert_merge_rows_affected(ctx: &SessionContext, sql: &str, expected: u64) {
+    let batches = ctx.sql(sql).await.unwrap().collect().await.unwrap();
+    assert_batches_eq!(
+        [
+            "+-------+",
+            "| count |",
+            "+-------+",
+            &format!("| {expected:<5} |"),
+            "+-------+",
+        ],
+        &batches
+    );
+}
+
 #[tokio::test]
 async fn merge_into_rejects_source_alias_colliding_with_target_name() {
     // Canonicalizing `t.id` to `target.id` must not collapse it onto a source
@@ -265,8 +280,8 @@ async fn merge_into_rejects_subqueries_correlated_to_target_alias() {
     )
     .await;
 
-    // Source-correlated and uncorrelated subqueries remain supported through
-    // logical optimization.
+    // Source-correlated and uncorrelated subqueries are planned, but cannot be
+    // executed yet
     for sql in [
         "MERGE INTO target AS t USING source AS s \
          ON EXISTS (SELECT 1 FROM source AS x WHERE x.id = s.id) \
@@ -275,8 +290,12 @@ async fn merge_into_rejects_subqueries_correlated_to_target_alias() {
          ON t.id = ANY (SELECT id FROM source) \
          WHEN MATCHED THEN DELETE",
     ] {
-        assert_merge_physical_error(&ctx, sql, "MERGE INTO not supported for Base table")
-            .await;
+        assert_merge_physical_error(
+            &ctx,
+            sql,
+            "Subqueries in MERGE INTO are not supported",
+        )
+        .await;
     }
 }
 
@@ -294,14 +313,18 @@ async fn merge_into_requires_boolean_conditions() {
              WHEN MATCHED AND 1 THEN DELETE",
             "MERGE WHEN condition must be boolean type, but got Int64",
         ),
-        (
-            "MERGE INTO target USING source ON NULL \
-             WHEN MATCHED AND NULL THEN DELETE",
-            "MERGE INTO not supported for Base table",
-        ),
     ] {
         assert_merge_physical_error(&ctx, sql, expected).await;
     }
+
+    // Null conditions are coerced to boolean and never match
+    assert_merge_rows_affected(
+        &ctx,
+        "MERGE INTO target USING source ON NULL \
+         WHEN MATCHED AND NULL THEN DELETE",
+        0,
+    )
+    .await;
 }
 
 #[tokio::test]
@@ -17,29 +17,57 @@
 
 //! Apache Avro [`FileFormat`] abstractions
 use std::collections::HashMap;
-use std::fmt;
+use std::fmt::{self, Debug};
 use std::sync::Arc;
 
 use crate::read_avro_schema_from_reader;
 use crate::source::AvroSource;
 
+use arrow::array::RecordBatch;
 use arrow::datatypes::Schema;
 use arrow::datatypes::SchemaRef;
+use arrow::error::ArrowError;
+use arrow_avro::compression::CompressionCodec;
+use arrow_avro::schema::SCHEMA_METADATA_KEY;
+use arrow_avro::writer::WriterBuilder;
+use arrow_avro::writer::format::AvroOcfFormat;
 use datafusion_common::DEFAULT_AVRO_EXTENSION;
 use datafusion_common::GetExt;
-use datafusion_common::internal_err;
 use datafusion_common::parsers::CompressionTypeVariant;
-use datafusion_common::{Result, Statistics};
+use datafusion_common::{DataFusionError, Result, Statistics};
+use datafusion_common::{config_err, internal_err};
+use datafusion_common_runtime::{JoinSet, SpawnedTask};
+use datafusion_datasource::display::FileGroupDisplay;
 use datafusion_datasource::file::FileSource;
 use datafusion_datasource::file_compression_type::FileCompressionType;
 use datafusion_datasource::file_format::{FileFormat, FileFormatFactory};
 use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_datasource::file_sink_config::{FileSink, FileSinkConfig};
+use datafusion_datasource::sink::{DataSink, DataSinkExec};
 use datafusion_datasource::source::DataSourceExec;
-use datafusion_physical_plan::ExecutionPlan;
+use datafusion_datasource::write::demux::DemuxedStreamReceiver;
+use datafusion_datasource::write::{
+    ObjectWriterBuilder, SharedBuffer, get_writer_schema,
+};
+use datafusion_execution::{SendableRecordBatchStream, TaskContext};
+use datafusion_physical_expr_common::sort_expr::LexRequirement;
+use datafusion_physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan};
 use datafusion_session::Session;
 
 use async_trait::async_trait;
 use object_store::{GetResultPayload, ObjectMeta, ObjectStore, ObjectStoreExt};
+use tokio::io::AsyncWriteExt;
+
+/// Initial writing buffer size. Note this is just a size hint for efficiency. It
+/// will grow beyond the set value if needed.
+const INITIAL_BUFFER_BYTES: usize = 1048576;
+
+/// If the buffered Avro data exceeds this size, it is flushed to object store
+const BUFFER_FLUSH_BYTES: usize = 1024000;
+
+/// Option key used to select the Avro block compression codec, e.g.
+/// `COPY ... STORED AS AVRO OPTIONS ('format.compression' 'zstd')`
+const COMPRESSION_OPTION_KEY: &str = "format.compression";
 
 #[derive(Default)]
 /// Factory struct used to create [`AvroFormat`]
@@ -56,17 +84,23 @@ impl FileFormatFactory for AvroFormatFactory {
     fn create(
         &self,
         _state: &dyn Session,
-        _format_options: &HashMap<String, String>,
+        format_options: &HashMap<String, String>,
     ) -> Result<Arc<dyn FileFormat>> {
-        Ok(Arc::new(AvroFormat))
+        let compression = match format_options.get(COMPRESSION_OPTION_KEY) {
+            Some(codec) => parse_compression_codec(codec)?,
+            None => None,
+        };
+        Ok(Arc::new(
+            AvroFormat::default().with_compression(compression),
+        ))
     }
 
     fn default(&self) -> Arc<dyn FileFormat> {
-        Arc::new(AvroFormat)
+        Arc::new(AvroFormat::default())
     }
 }
 
-impl fmt::Debug for AvroFormatFactory {
+impl Debug for AvroFormatFactory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("AvroFormatFactory").finish()
     }
@@ -79,9 +113,86 @@ impl GetExt for AvroFormatFactory {
     }
 }
 
+/// Parses the name of an Avro block compression codec.
+///
+/// `uncompressed` (or the Avro spec name `null`) disables block compression.
+pub fn parse_compression_codec(value: &str) -> Result<Option<CompressionCodec>> {
+    match value.to_lowercase().as_str() {
+        "uncompressed" | "null" => Ok(None),
+        "deflate" => Ok(Some(CompressionCodec::Deflate)),
+        "snappy" => Ok(Some(CompressionCodec::Snappy)),
+        "zstd" | "zstandard" => Ok(Some(CompressionCodec::ZStandard)),
+        "bzip2" => Ok(Some(CompressionCodec::Bzip2)),
+        "xz" => Ok(Some(CompressionCodec::Xz)),
+        _ => config_err!(
+            "Unsupported Avro compression codec: {value}. Valid values are: \
+             uncompressed, deflate, snappy, zstd, bzip2, xz"
+        ),
+    }
+}
+
+/// Returns the name of an Avro block compression codec, as accepted by
+/// [`parse_compression_codec`].
+pub fn compression_codec_name(codec: CompressionCodec) -> &'static str {
+    match codec {
+        CompressionCodec::Deflate => "deflate",
+        CompressionCodec::Snappy => "snappy",
+        CompressionCodec::ZStandard => "zstd",
+        CompressionCodec::Bzip2 => "bzip2",
+        CompressionCodec::Xz => "xz",
+    }
+}
+
+/// Encodes a block compression codec for protobuf, where uncompressed blocks
+/// are represented by an empty string.
+#[cfg(feature = "proto")]
+fn compression_to_proto(codec: Option<CompressionCodec>) -> String {
+    codec
+        .map(compression_codec_name)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Decodes a block compression codec encoded by [`compression_to_proto`].
+#[cfg(feature = "proto")]
+fn compression_from_proto(name: &str) -> Result<Option<CompressionCodec>> {
+    if name.is_empty() {
+        Ok(None)
+    } else {
+        parse_compression_codec(name)
+    }
+}
+
 /// Avro [`FileFormat`] implementation.
 #[derive(Default, Debug)]
-pub struct AvroFormat;
+pub struct AvroFormat {
+    /// Block compression codec used when writing Avro files
+    compression: Option<CompressionCodec>,
+}
+
+/// The [`AvroFormat`] writing uncompressed blocks, equal to
+/// [`AvroFormat::default`].
+///
+/// `AvroFormat` used to be a unit struct, so this keeps `AvroFormat` usable as
+/// an expression, such as in `Arc::new(AvroFormat)`.
+#[expect(non_upper_case_globals)]
+pub const AvroFormat: AvroFormat = AvroFormat { compression: None };
+
+impl AvroFormat {
+    /// Set the block compression codec used when writing Avro object container
+    /// files. `None` (the default) writes uncompressed blocks.
+    ///
+    /// Reading is unaffected: the codec of each file is taken from its header.
+    pub fn with_compression(mut self, compression: Option<CompressionCodec>) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Return the block compression codec used when writing
+    pub fn compression(&self) -> Option<CompressionCodec> {
+        self.compression
+    }
+}
 
 #[async_trait]
 impl FileFormat for AvroFormat {
@@ -147,6 +258,18 @@ impl FileFormat for AvroFormat {
         Ok(DataSourceExec::from_data_source(conf))
     }
 
+    async fn create_writer_physical_plan(
+        &self,
+        input: Arc<dyn ExecutionPlan>,
+        _state: &dyn Session,
+        conf: FileSinkConfig,
+        order_requirements: Option<LexRequirement>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let sink = Arc::new(AvroSink::new(conf, self.compression));
+
+        Ok(Arc::new(DataSinkExec::new(input, sink, order_requirements)) as _)
+    }
+
     fn file_source(
         &self,
         table_schema: datafusion_datasource::TableSchema,
@@ -154,3 +277,285 @@ impl FileFormat for AvroFormat {
         Arc::new(AvroSource::new(table_schema))
     }
 }
+
+/// Implements [`FileSink`] for writing to Avro object container files.
+///
+/// Arrow types are mapped to their Avro counterparts by `arrow-avro`, using
+/// Avro logical types where one exists (e.g. `date`, `time-micros`,
+/// `timestamp-micros`, `local-timestamp-micros` and `decimal`).
+pub struct AvroSink {
+    /// Config options for writing data
+    config: FileSinkConfig,
+    /// Block compression codec
+    compression: Option<CompressionCodec>,
+}
+
+impl AvroSink {
+    /// Create from config.
+    pub fn new(config: FileSinkConfig, compression: Option<CompressionCodec>) -> Self {
+        Self {
+            config,
+            compression,
+        }
+    }
+
+    /// Retrieve the block compression codec
+    pub fn compression(&self) -> Option<CompressionCodec> {
+        self.compression
+    }
+
+    /// Schema of the written files: the output schema without partition
+    /// columns, and without any Avro schema carried over in the metadata so the
+    /// Avro schema is always derived from the Arrow types
+    fn writer_schema(&self) -> SchemaRef {
+        let schema = get_writer_schema(&self.config);
+        if !schema.metadata().contains_key(SCHEMA_METADATA_KEY) {
+            return schema;
+        }
+        let mut metadata = schema.metadata().clone();
+        metadata.remove(SCHEMA_METADATA_KEY);
+        Arc::new(Schema::new_with_metadata(schema.fields().clone(), metadata))
+    }
+}
+
+#[async_trait]
+impl FileSink for AvroSink {
+    fn config(&self) -> &FileSinkConfig {
+        &self.config
+    }
+
+    async fn spawn_writer_tasks_and_join(
+        &self,
+        context: &Arc<TaskContext>,
+        demux_task: SpawnedTask<Result<()>>,
+        mut file_stream_rx: DemuxedStreamReceiver,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> Result<u64> {
+        let mut file_write_tasks: JoinSet<std::result::Result<usize, DataFusionError>> =
+            JoinSet::new();
+
+        let schema = self.writer_schema();
+        while let Some((path, mut rx)) = file_stream_rx.recv().await {
+            let shared_buffer = SharedBuffer::new(INITIAL_BUFFER_BYTES);
+            let mut avro_writer = WriterBuilder::new(schema.as_ref().clone())
+                .with_compression(self.compression)
+                .build::<_, AvroOcfFormat>(shared_buffer.clone())
+                .map_err(ArrowError::from)?;
+            let mut object_store_writer = ObjectWriterBuilder::new(
+                FileCompressionType::UNCOMPRESSED,
+                &path,
+                Arc::clone(&object_store),
+            )
+            .with_buffer_size(Some(
+                context
+                    .session_config()
+                    .options()
+                    .execution
+                    .objectstore_writer_buffer_size,
+            ))
+            .build()?;
+            let schema = Arc::clone(&schema);
+            file_write_tasks.spawn(async move {
+                let mut row_count = 0;
+                while let Some(batch) = rx.recv().await {
+                    row_count += batch.num_rows();
+                    // The Avro writer requires the batch fields to match the
+                    // writer schema exactly, including names and nullability
+                    let batch = RecordBatch::try_new(
+                        Arc::clone(&schema),
+                        batch.columns().to_vec(),
+                    )?;
+                    avro_writer.write(&batch).map_err(ArrowError::from)?;
+                    let mut buff_to_flush = shared_buffer.buffer.try_lock().unwrap();
+                    if buff_to_flush.len() > BUFFER_FLUSH_BYTES {
+                        object_store_writer
+                            .write_all(buff_to_flush.as_slice())
+                            .await?;
+                        buff_to_flush.clear();
+                    }
+                }
+                avro_writer.finish().map_err(ArrowError::from)?;
+                let final_buff = shared_buffer.buffer.try_lock().unwrap();
+
+                object_store_writer.write_all(final_buff.as_slice()).await?;
+                object_store_writer.shutdown().await?;
+                Ok(row_count)
+            });
+        }
+
+        let mut row_count = 0;
+        while let Some(result) = file_write_tasks.join_next().await {
+            match result {
+                Ok(r) => {
+                    row_count += r?;
+                }
+                Err(e) => {
+                    if e.is_panic() {
+                        std::panic::resume_unwind(e.into_panic());
+                    } else {
+                        unreachable!();
+                    }
+                }
+            }
+        }
+
+        demux_task
+            .join_unwind()
+            .await
+            .map_err(|e| DataFusionError::ExecutionJoin(Box::new(e)))??;
+        Ok(row_count as u64)
+    }
+}
+
+impl Debug for AvroSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AvroSink")
+            .field("compression", &self.compression)
+            .finish()
+    }
+}
+
+impl DisplayAs for AvroSink {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "AvroSink(file_groups=",)?;
+                FileGroupDisplay(&self.config.file_group).fmt_as(t, f)?;
+                write!(f, ")")
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "format: avro")?;
+                write!(f, "file={}", self.config.original_url)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DataSink for AvroSink {
+    fn schema(&self) -> &SchemaRef {
+        self.config.output_schema()
+    }
+
+    async fn write_all(
+        &self,
+        data: SendableRecordBatchStream,
+        context: &Arc<TaskContext>,
+    ) -> Result<u64> {
+        FileSink::write_all(self, data, context).await
+    }
+
+    #[cfg(feature = "proto")]
+    fn try_to_proto(
+        &self,
+        exec: &DataSinkExec,
+        ctx: &datafusion_physical_plan::proto::ExecutionPlanEncodeCtx<'_>,
+    ) -> Result<Option<datafusion_proto_models::protobuf::PhysicalPlanNode>> {
+        use datafusion_proto_models::protobuf;
+        use protobuf::physical_plan_node::PhysicalPlanType;
+
+        let input = ctx.encode_child(exec.input())?;
+        let sort_order = exec.encode_sort_order(ctx)?;
+        let sink = protobuf::AvroSink::try_from(self)?;
+        let node = protobuf::AvroSinkExecNode {
+            input: Some(Box::new(input)),
+            sink: Some(sink),
+            sink_schema: Some(exec.schema().as_ref().try_into()?),
+            sort_order,
+        };
+        Ok(Some(protobuf::PhysicalPlanNode {
+            physical_plan_type: Some(PhysicalPlanType::AvroSink(Box::new(node))),
+        }))
+    }
+}
+
+#[cfg(feature = "proto")]
+impl TryFrom<&AvroSink> for datafusion_proto_models::protobuf::AvroSink {
+    type Error = DataFusionError;
+
+    fn try_from(value: &AvroSink) -> Result<Self> {
+        Ok(Self {
+            config: Some(value.config().try_into()?),
+            compression: compression_to_proto(value.compression()),
+        })
+    }
+}
+
+#[cfg(feature = "proto")]
+impl TryFrom<&datafusion_proto_models::protobuf::AvroSink> for AvroSink {
+    type Error = DataFusionError;
+
+    fn try_from(value: &datafusion_proto_models::protobuf::AvroSink) -> Result<Self> {
+        let config =
+            FileSinkConfig::try_from(value.config.as_ref().ok_or_else(|| {
+                datafusion_common::internal_datafusion_err!(
+                    "AvroSink is missing required field 'config'"
+                )
+            })?)?;
+        Ok(Self::new(
+            config,
+            compression_from_proto(&value.compression)?,
+        ))
+    }
+}
+
+#[cfg(feature = "proto")]
+impl AvroSink {
+    /// Reconstructs a [`DataSinkExec`] containing an `AvroSink` from protobuf.
+    pub fn try_from_proto(
+        node: &datafusion_proto_models::protobuf::PhysicalPlanNode,
+        ctx: &datafusion_physical_plan::proto::ExecutionPlanDecodeCtx<'_>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        use datafusion_proto_models::protobuf;
+
+        let sink_node = datafusion_physical_plan::expect_plan_variant!(
+            node,
+            protobuf::physical_plan_node::PhysicalPlanType::AvroSink,
+            "AvroSink",
+        );
+        let input = ctx.decode_required_child(
+            sink_node.input.as_deref(),
+            "AvroSinkExecNode",
+            "input",
+        )?;
+        let proto_sink = sink_node.sink.as_ref().ok_or_else(|| {
+            datafusion_common::internal_datafusion_err!(
+                "AvroSinkExecNode is missing required field 'sink'"
+            )
+        })?;
+        let data_sink = AvroSink::try_from(proto_sink)?;
+        let sort_order = DataSinkExec::decode_sort_order(
+            sink_node.sort_order.as_ref(),
+            ctx,
+            input.schema().as_ref(),
+        )?;
+
+        Ok(Arc::new(DataSinkExec::new(
+            input,
+            Arc::new(data_sink),
+            sort_order,
+        )))
+    }
+}
+
+/// Encodes the write options of an [`AvroFormat`] as its protobuf form.
+#[cfg(feature = "proto")]
+impl From<&AvroFormat> for datafusion_proto_models::datafusion_common::AvroFormat {
+    fn from(format: &AvroFormat) -> Self {
+        Self {
+            compression: compression_to_proto(format.compression()),
+        }
+    }
+}
+
+#[cfg(feature = "proto")]
+impl TryFrom<&datafusion_proto_models::datafusion_common::AvroFormat> for AvroFormat {
+    type Error = DataFusionError;
+
+    fn try_from(
+        value: &datafusion_proto_models::datafusion_common::AvroFormat,
+    ) -> Result<Self> {
+        Ok(AvroFormat::default()
+            .with_compression(compression_from_proto(&value.compression)?))
+    }
+}
@@ -31,7 +31,6 @@ use datafusion_common::config::{ConfigField, ConfigFileType, CsvOptions};
 use datafusion_common::file_options::csv_writer::CsvWriterOptions;
 use datafusion_common::{
     DEFAULT_CSV_EXTENSION, DataFusionError, GetExt, Result, Statistics, exec_err,
-    not_impl_err,
 };
 use datafusion_common_runtime::SpawnedTask;
 use datafusion_datasource::TableSchema;
@@ -50,7 +49,6 @@ use datafusion_datasource::write::BatchSerializer;
 use datafusion_datasource::write::demux::DemuxedStreamReceiver;
 use datafusion_datasource::write::orchestration::spawn_writer_tasks_and_join;
 use datafusion_execution::{SendableRecordBatchStream, TaskContext};
-use datafusion_expr::dml::InsertOp;
 use datafusion_physical_expr_common::sort_expr::LexRequirement;
 use datafusion_physical_plan::{DisplayAs, DisplayFormatType, ExecutionPlan};
 use datafusion_session::Session;
@@ -472,10 +470,6 @@ impl FileFormat for CsvFormat {
         conf: FileSinkConfig,
         order_requirements: Option<LexRequirement>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        if conf.insert_op != InsertOp::Append {
-            return not_impl_err!("Overwrites are not implemented yet for CSV");
-        }
-
         // `has_header` and `newlines_in_values` fields of CsvOptions may inherit
         // their values from session from configuration settings. To support
         // this logic, writer options are built from the copy of `self.options`
@@ -509,6 +503,17 @@ impl FileFormat for CsvFormat {
         }
         Arc::new(CsvSource::new(table_schema).with_csv_options(csv_options))
     }
+
+    fn with_format_options(
+        &self,
+        options: &HashMap<String, String>,
+    ) -> Result<Arc<dyn FileFormat>> {
+        let mut csv_options = self.options.clone();
+        for (key, value) in options {
+            csv_options.set(key, value)?;
+        }
+        Ok(Arc::new(CsvFormat::default().with_options(csv_options)))
+    }
 }
 
 impl CsvFormat {
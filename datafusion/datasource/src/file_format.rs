@@ -210,6 +210,29 @@ pub trait FileFormat: Any + Send + Sync + fmt::Debug {
     /// # Arguments
     /// * `table_schema` - The table schema to use for the FileSource (includes partition columns)
     fn file_source(&self, table_schema: crate::TableSchema) -> Arc<dyn FileSource>;
+
+    /// Returns a copy of this format with the given options changed, such as
+    /// `compression`. The keys are the format options of `CREATE EXTERNAL
+    /// TABLE ... OPTIONS`, without the `format.` prefix.
+    fn with_format_options(
+        &self,
+        _options: &HashMap<String, String>,
+    ) -> Result<Arc<dyn FileFormat>> {
+        not_impl_err!(
+            "Changing the options of {} files is not supported",
+            self.get_ext()
+        )
+    }
+
+    /// Returns `true` if the [`FileSource`] of this format adapts the schema
+    /// of each file to the table schema with the
+    /// [`FileScanConfig::expr_adapter_factory`], matching columns by name.
+    ///
+    /// Files written before columns of the table were added, dropped or
+    /// renamed can then still be read.
+    fn supports_schema_evolution(&self) -> bool {
+        false
+    }
 }
 
 impl dyn FileFormat {
@@ -21,15 +21,19 @@ use crate::ListingTableUrl;
 use crate::file_groups::FileGroup;
 use crate::sink::DataSink;
 use crate::write::demux::{DemuxedStreamReceiver, start_demuxer_task};
+use crate::write::orchestration::{
+    remove_replaced_files, remove_written_files, track_written_files,
+};
 
 use arrow::datatypes::{DataType, SchemaRef};
-use datafusion_common::Result;
+use datafusion_common::{DataFusionError, Result};
 use datafusion_common_runtime::SpawnedTask;
 use datafusion_execution::object_store::ObjectStoreUrl;
 use datafusion_execution::{SendableRecordBatchStream, TaskContext};
 use datafusion_expr::dml::InsertOp;
 
 use async_trait::async_trait;
+use log::warn;
 use object_store::ObjectStore;
 
 #[cfg(feature = "proto")]
@@ -126,13 +130,36 @@ pub trait FileSink: DataSink {
             .runtime_env()
             .object_store(&config.object_store_url)?;
         let (demux_task, file_stream_rx) = start_demuxer_task(config, data, context);
-        self.spawn_writer_tasks_and_join(
-            context,
-            demux_task,
-            file_stream_rx,
-            object_store,
-        )
-        .await
+        let (file_stream_rx, written_files) = track_written_files(file_stream_rx);
+        let result = self
+            .spawn_writer_tasks_and_join(
+                context,
+                demux_task,
+                file_stream_rx,
+                Arc::clone(&object_store),
+            )
+            .await;
+        let written_files = written_files
+            .join_unwind()
+            .await
+            .map_err(|e| DataFusionError::ExecutionJoin(Box::new(e)))?;
+        let row_count = match result {
+            Ok(row_count) => row_count,
+            Err(e) => {
+                // Don't leave the files written before the failure behind
+                if let Err(cleanup) =
+                    remove_written_files(config, written_files, &object_store).await
+                {
+                    warn!("Failed to remove files of a failed write: {cleanup}");
+                }
+                return Err(e);
+            }
+        };
+
+        // Overwrite / replace: the existing files are only removed once all
+        // new files have been written successfully
+        remove_replaced_files(config, &written_files, &object_store).await?;
+        Ok(row_count)
     }
 }
 
@@ -171,3 +198,135 @@ impl FileSinkConfig {
         &self.output_schema
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PartitionedFile;
+
+    use arrow::array::{Int32Array, RecordBatch};
+    use arrow::datatypes::{Field, Schema};
+    use datafusion_common::exec_err;
+    use datafusion_physical_plan::stream::RecordBatchStreamAdapter;
+    use datafusion_physical_plan::{DisplayAs, DisplayFormatType};
+    use futures::{TryStreamExt, stream};
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::{ObjectStoreExt, PutPayload};
+    use std::fmt::{self, Debug, Formatter};
+
+    /// Stores every file it is handed, then fails the write
+    struct FailingSink {
+        config: FileSinkConfig,
+    }
+
+    impl Debug for FailingSink {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.debug_struct("FailingSink").finish()
+        }
+    }
+
+    impl DisplayAs for FailingSink {
+        fn fmt_as(&self, _t: DisplayFormatType, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "FailingSink")
+        }
+    }
+
+    #[async_trait]
+    impl DataSink for FailingSink {
+        fn schema(&self) -> &SchemaRef {
+            &self.config.output_schema
+        }
+
+        async fn write_all(
+            &self,
+            data: SendableRecordBatchStream,
+            context: &Arc<TaskContext>,
+        ) -> Result<u64> {
+            FileSink::write_all(self, data, context).await
+        }
+    }
+
+    #[async_trait]
+    impl FileSink for FailingSink {
+        fn config(&self) -> &FileSinkConfig {
+            &self.config
+        }
+
+        async fn spawn_writer_tasks_and_join(
+            &self,
+            _context: &Arc<TaskContext>,
+            demux_task: SpawnedTask<Result<()>>,
+            mut file_stream_rx: DemuxedStreamReceiver,
+            object_store: Arc<dyn ObjectStore>,
+        ) -> Result<u64> {
+            while let Some((path, _batches)) = file_stream_rx.recv().await {
+                object_store.put(&path, PutPayload::from("new")).await?;
+            }
+            demux_task
+                .join_unwind()
+                .await
+                .map_err(|e| DataFusionError::ExecutionJoin(Box::new(e)))??;
+            exec_err!("writer failed")
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_write_removes_written_files() -> Result<()> {
+        let object_store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let object_store_url = ObjectStoreUrl::parse("memory://")?;
+        let context = Arc::new(TaskContext::default());
+        context
+            .runtime_env()
+            .register_object_store(object_store_url.as_ref(), Arc::clone(&object_store));
+
+        let existing = Path::from("table/existing.csv");
+        object_store.put(&existing, PutPayload::from("old")).await?;
+
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let config = FileSinkConfig {
+            original_url: "memory:///table/".to_string(),
+            object_store_url,
+            file_group: FileGroup::new(vec![PartitionedFile::new(
+                existing.to_string(),
+                3,
+            )]),
+            table_paths: vec![ListingTableUrl::parse("memory:///table/")?],
+            output_schema: Arc::clone(&schema),
+            table_partition_cols: vec![],
+            insert_op: InsertOp::Overwrite,
+            keep_partition_by_columns: false,
+            file_extension: "csv".to_string(),
+            file_output_mode: FileOutputMode::Directory,
+        };
+        let sink = FailingSink { config };
+
+        let batches = (0..4)
+            .map(|i| {
+                RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![Arc::new(Int32Array::from(vec![i]))],
+                )
+                .map_err(Into::into)
+            })
+            .collect::<Vec<_>>();
+        let data = Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(&schema),
+            stream::iter(batches),
+        ));
+
+        let err = FileSink::write_all(&sink, data, &context)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("writer failed"), "{err}");
+
+        // Only the previous contents of the table remain
+        let files = object_store
+            .list(None)
+            .map_ok(|meta| meta.location)
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(files, vec![existing]);
+        Ok(())
+    }
+}
@@ -868,12 +868,15 @@ pub struct MemSink {
     /// Target locations for writing data
     batches: Vec<PartitionData>,
     schema: SchemaRef,
+    /// Whether the written data replaces the existing data
+    overwrite: bool,
 }
 
 impl Debug for MemSink {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("MemSink")
             .field("num_partitions", &self.batches.len())
+            .field("overwrite", &self.overwrite)
             .finish()
     }
 }
@@ -901,7 +904,18 @@ impl MemSink {
         if batches.is_empty() {
             return plan_err!("Cannot insert into MemTable with zero partitions");
         }
-        Ok(Self { batches, schema })
+        Ok(Self {
+            batches,
+            schema,
+            overwrite: false,
+        })
+    }
+
+    /// Sets whether the written data replaces the existing data instead of
+    /// being appended to it
+    pub fn with_overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
     }
 }
 
@@ -931,8 +945,13 @@ impl DataSink for MemSink {
 
         // write the outputs into the batches
         for (target, mut batches) in self.batches.iter().zip(new_batches) {
-            // Append all the new batches in one go to minimize locking overhead
-            target.write().await.append(&mut batches);
+            let mut target = target.write().await;
+            if self.overwrite {
+                *target = batches;
+            } else {
+                // Append all the new batches in one go to minimize locking overhead
+                target.append(&mut batches);
+            }
         }
 
         Ok(row_count as u64)
@@ -22,6 +22,7 @@ use std::fmt;
 use std::fmt::{Debug, Formatter};
 use std::sync::{Arc, OnceLock};
 
+use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::projection::ProjectionExprs;
 use datafusion_physical_plan::execution_plan::{
     Boundedness, EmissionType, SchedulingType,
@@ -30,6 +31,9 @@ use datafusion_physical_plan::metrics::SplitMetrics;
 use datafusion_physical_plan::metrics::{
     BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet,
 };
+use datafusion_physical_plan::operator_statistics::{
+    ColumnHistograms, ExtendedStatistics, StatisticsProvider, StatisticsResult,
+};
 use datafusion_physical_plan::projection::ProjectionExec;
 use datafusion_physical_plan::stream::BatchSplitStream;
 use datafusion_physical_plan::{
@@ -41,13 +45,14 @@ use itertools::Itertools;
 use crate::file::FileSource;
 use crate::file_scan_config::FileScanConfig;
 use datafusion_common::config::ConfigOptions;
+use datafusion_common::stats::Precision;
 use datafusion_common::tree_node::TreeNodeRecursion;
-use datafusion_common::{Constraints, Result, Statistics};
+use datafusion_common::{Constraints, Result, Statistics, TableStatistics};
 use datafusion_execution::{SendableRecordBatchStream, TaskContext};
 use datafusion_physical_expr::{EquivalenceProperties, Partitioning, PhysicalExpr};
 use datafusion_physical_expr_common::sort_expr::{LexOrdering, PhysicalSortExpr};
 use datafusion_physical_plan::SortOrderPushdownResult;
-use datafusion_physical_plan::StatisticsArgs;
+use datafusion_physical_plan::{StatisticsArgs, StatisticsContext};
 use datafusion_physical_plan::filter_pushdown::{
     ChildPushdownResult, FilterPushdownPhase, FilterPushdownPropagation, PushedDown,
 };
@@ -374,6 +379,9 @@ pub struct DataSourceExec {
     /// and then passed to
     /// [`DataSource::open_with_args`].
     execution_state: Arc<OnceLock<Option<Arc<dyn Any + Send + Sync>>>>,
+    /// Statistics of the scanned table, for example computed by
+    /// `ANALYZE TABLE`, used for statistics the data source does not know
+    table_statistics: Option<Arc<TableStatistics>>,
 }
 
 impl DisplayAs for DataSourceExec {
@@ -503,7 +511,16 @@ impl ExecutionPlan for DataSourceExec {
         _input_stats: &[Arc<Statistics>],
         args: &StatisticsArgs,
     ) -> Result<Arc<Statistics>> {
-        self.data_source.partition_statistics(args.partition())
+        let statistics = self.data_source.partition_statistics(args.partition())?;
+        match (&self.table_statistics, args.partition()) {
+            (Some(table_statistics), None) => {
+                Ok(Arc::new(self.fill_from_table_statistics(
+                    Arc::unwrap_or_clone(statistics),
+                    table_statistics,
+                )))
+            }
+            _ => Ok(statistics),
+        }
     }
 
     fn with_fetch(&self, limit: Option<usize>) -> Option<Arc<dyn ExecutionPlan>> {
@@ -515,6 +532,7 @@ impl ExecutionPlan for DataSourceExec {
             data_source,
             cache,
             execution_state,
+            table_statistics: self.table_statistics.clone(),
         }))
     }
 
@@ -531,7 +549,13 @@ impl ExecutionPlan for DataSourceExec {
             .try_swapping_with_projection(projection.projection_expr())?
         {
             Some(new_data_source) => {
-                Ok(Some(Arc::new(DataSourceExec::new(new_data_source))))
+                let mut new_exec = DataSourceExec::new(new_data_source);
+                if let Some(table_statistics) = &self.table_statistics {
+                    new_exec = new_exec.with_table_statistics(Arc::new(
+                        project_table_statistics(table_statistics, projection),
+                    ));
+                }
+                Ok(Some(Arc::new(new_exec)))
             }
             None => Ok(None),
         }
@@ -640,6 +664,7 @@ impl DataSourceExec {
             data_source,
             cache: Arc::new(cache),
             execution_state: Arc::new(OnceLock::new()),
+            table_statistics: None,
         }
     }
 
@@ -655,6 +680,57 @@ impl DataSourceExec {
         self
     }
 
+    /// Assign statistics of the scanned table, such as the ones computed by
+    /// `ANALYZE TABLE`.
+    ///
+    /// They are used for the statistics that the data source does not know,
+    /// and are matched to the output columns by name.
+    pub fn with_table_statistics(
+        mut self,
+        table_statistics: Arc<TableStatistics>,
+    ) -> Self {
+        self.table_statistics = Some(table_statistics);
+        self
+    }
+
+    /// Return the statistics of the scanned table, if any
+    pub fn table_statistics(&self) -> Option<&Arc<TableStatistics>> {
+        self.table_statistics.as_ref()
+    }
+
+    /// Fill the statistics the data source does not know from
+    /// `table_statistics`
+    fn fill_from_table_statistics(
+        &self,
+        mut statistics: Statistics,
+        table_statistics: &TableStatistics,
+    ) -> Statistics {
+        let analyzed = table_statistics.to_statistics(&self.schema());
+        // the table statistics are not limited by a fetch
+        if statistics.num_rows == Precision::Absent && self.fetch().is_none() {
+            statistics.num_rows = analyzed.num_rows;
+        }
+        for (column, analyzed) in statistics
+            .column_statistics
+            .iter_mut()
+            .zip(analyzed.column_statistics)
+        {
+            if column.null_count == Precision::Absent {
+                column.null_count = analyzed.null_count;
+            }
+            if column.min_value == Precision::Absent {
+                column.min_value = analyzed.min_value;
+            }
+            if column.max_value == Precision::Absent {
+                column.max_value = analyzed.max_value;
+            }
+            if column.distinct_count == Precision::Absent {
+                column.distinct_count = analyzed.distinct_count;
+            }
+        }
+        statistics
+    }
+
     /// Assign constraints
     pub fn with_constraints(mut self, constraints: Constraints) -> Self {
         Arc::make_mut(&mut self.cache).set_constraints(constraints);
@@ -696,6 +772,67 @@ impl DataSourceExec {
     }
 }
 
+/// Rename the column statistics in `table_statistics` to the output names of
+/// `projection`, dropping the ones of columns that are not projected as is.
+fn project_table_statistics(
+    table_statistics: &TableStatistics,
+    projection: &ProjectionExec,
+) -> TableStatistics {
+    let mut projected = TableStatistics {
+        num_rows: table_statistics.num_rows,
+        ..Default::default()
+    };
+    for proj_expr in projection.expr() {
+        let Some(column) = proj_expr.expr.downcast_ref::<Column>() else {
+            continue;
+        };
+        if let Some(stats) = table_statistics.column_statistics.get(column.name()) {
+            projected
+                .column_statistics
+                .insert(proj_expr.alias.clone(), stats.clone());
+        }
+        if let Some(histogram) = table_statistics.histograms.get(column.name()) {
+            projected
+                .histograms
+                .insert(proj_expr.alias.clone(), Arc::clone(histogram));
+        }
+    }
+    projected
+}
+
+/// [`StatisticsProvider`] for [`DataSourceExec`]s with table statistics,
+/// such as the ones computed by `ANALYZE TABLE`.
+///
+/// In addition to the base statistics, it provides the histograms of the
+/// table statistics as [`ColumnHistograms`], which lets the
+/// [`FilterStatisticsProvider`](datafusion_physical_plan::operator_statistics::FilterStatisticsProvider)
+/// estimate the selectivity of range and equality predicates on the table.
+#[derive(Debug, Default)]
+pub struct DataSourceStatisticsProvider;
+
+impl StatisticsProvider for DataSourceStatisticsProvider {
+    fn compute_statistics(
+        &self,
+        plan: &dyn ExecutionPlan,
+        _child_stats: &[ExtendedStatistics],
+    ) -> Result<StatisticsResult> {
+        let Some(exec) = plan.downcast_ref::<DataSourceExec>() else {
+            return Ok(StatisticsResult::Delegate);
+        };
+        let Some(table_statistics) = exec.table_statistics() else {
+            return Ok(StatisticsResult::Delegate);
+        };
+        let base = StatisticsContext::new().compute(plan, &StatisticsArgs::new())?;
+        let mut statistics = ExtendedStatistics::new_arc(base);
+        let histograms =
+            ColumnHistograms::new(table_statistics.histograms_for(&exec.schema()));
+        if !histograms.is_empty() {
+            statistics.set_extension(histograms);
+        }
+        Ok(StatisticsResult::Computed(statistics))
+    }
+}
+
 /// Create a new `DataSourceExec` from a `DataSource`
 impl<S> From<S> for DataSourceExec
 where
@@ -19,11 +19,13 @@
 //! orchestrating file serialization, streaming to object store,
 //! parallelization, and abort handling
 
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use super::demux::DemuxedStreamReceiver;
 use super::{BatchSerializer, ObjectWriterBuilder};
 use crate::file_compression_type::FileCompressionType;
+use crate::file_sink_config::FileSinkConfig;
 use datafusion_common::error::Result;
 
 use arrow::array::RecordBatch;
@@ -32,10 +34,12 @@ use datafusion_common::{
 };
 use datafusion_common_runtime::{JoinSet, SpawnedTask};
 use datafusion_execution::TaskContext;
+use datafusion_expr::dml::InsertOp;
 
 use bytes::Bytes;
-use futures::join;
+use futures::{StreamExt, TryStreamExt, join, stream};
 use object_store::ObjectStore;
+use object_store::path::Path;
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 use tokio::sync::mpsc::{self, Receiver};
 
@@ -303,3 +307,142 @@ pub async fn spawn_writer_tasks_and_join(
         internal_datafusion_err!("Did not receive row count from write coordinator")
     })
 }
+
+/// Forwards the files opened by the demuxer to the writers, recording the path
+/// of each one.
+///
+/// The returned task resolves to the written paths once the demuxer has
+/// finished, so the files they replace can be removed with
+/// [`remove_replaced_files`] after all writers have completed successfully,
+/// or the written files themselves with [`remove_written_files`] if any
+/// writer failed.
+pub(crate) fn track_written_files(
+    mut file_stream_rx: DemuxedStreamReceiver,
+) -> (DemuxedStreamReceiver, SpawnedTask<Vec<Path>>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let task = SpawnedTask::spawn(async move {
+        let mut written = vec![];
+        while let Some((path, batches)) = file_stream_rx.recv().await {
+            written.push(path.clone());
+            if tx.send((path, batches)).is_err() {
+                break;
+            }
+        }
+        written
+    });
+    (rx, task)
+}
+
+/// Removes the existing files of the table (`config.file_group`) that are
+/// replaced by the newly `written` files:
+///
+/// - [`InsertOp::Overwrite`] removes every existing file
+/// - [`InsertOp::Replace`] removes the existing files of the Hive partitions
+///   that received new files, leaving all other partitions untouched. An
+///   unpartitioned table is a single partition.
+///
+/// Must only be called once the new files were written successfully, so a
+/// failed write never loses the previous contents of the table.
+pub(crate) async fn remove_replaced_files(
+    config: &FileSinkConfig,
+    written: &[Path],
+    object_store: &Arc<dyn ObjectStore>,
+) -> Result<()> {
+    let Some(table_path) = config.table_paths.first() else {
+        return Ok(());
+    };
+    let partition_depth = config.table_partition_cols.len();
+    // The partition directories of a file, e.g. `["year=2024", "month=01"]`
+    let partition_of = |path: &Path| -> Option<Vec<String>> {
+        let parts = path
+            .prefix_match(table_path.prefix())?
+            .map(|part| part.as_ref().to_string())
+            .collect::<Vec<_>>();
+        (parts.len() > partition_depth).then(|| parts[..partition_depth].to_vec())
+    };
+
+    // Only files below the table path are ever removed
+    let written_files = written.iter().collect::<HashSet<_>>();
+    let existing_files = config
+        .file_group
+        .iter()
+        .map(|file| &file.object_meta.location)
+        .filter(|location| {
+            !written_files.contains(location)
+                && location.prefix_matches(table_path.prefix())
+        });
+    let to_remove: Vec<Path> = match config.insert_op {
+        InsertOp::Append => vec![],
+        InsertOp::Overwrite => existing_files.cloned().collect(),
+        InsertOp::Replace => {
+            let replaced_partitions = written
+                .iter()
+                .filter_map(partition_of)
+                .collect::<HashSet<_>>();
+            existing_files
+                .filter(|location| {
+                    partition_of(location)
+                        .is_some_and(|p| replaced_partitions.contains(&p))
+                })
+                .cloned()
+                .collect()
+        }
+    };
+    delete_files(to_remove, object_store).await
+}
+
+/// Removes the files written by a failed write so they do not become part of
+/// the table.
+///
+/// Only files whose name was generated by the demuxer are removed: a single
+/// output file named by the user, or a file that already belonged to the
+/// table, may still hold its previous contents as an unfinished upload never
+/// replaces the existing object.
+pub(crate) async fn remove_written_files(
+    config: &FileSinkConfig,
+    written: Vec<Path>,
+    object_store: &Arc<dyn ObjectStore>,
+) -> Result<()> {
+    let Some(table_path) = config.table_paths.first() else {
+        return Ok(());
+    };
+    if config.table_partition_cols.is_empty()
+        && config.file_output_mode.single_file_output(table_path)
+    {
+        return Ok(());
+    }
+    let existing_files = config
+        .file_group
+        .iter()
+        .map(|file| &file.object_meta.location)
+        .collect::<HashSet<_>>();
+    let to_remove = written
+        .into_iter()
+        .filter(|location| !existing_files.contains(location))
+        .collect();
+    delete_files(to_remove, object_store).await
+}
+
+/// Deletes `paths` from `object_store`, ignoring files that no longer exist.
+async fn delete_files(
+    paths: Vec<Path>,
+    object_store: &Arc<dyn ObjectStore>,
+) -> Result<()> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    object_store
+        .delete_stream(stream::iter(paths.into_iter().map(Ok)).boxed())
+        .or_else(|e| async move {
+            match e {
+                // Already removed, e.g. by a concurrent overwrite, or never
+                // created by a writer that failed
+                object_store::Error::NotFound { path, .. } => Ok(Path::from(path)),
+                e => Err(e),
+            }
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+    Ok(())
+}
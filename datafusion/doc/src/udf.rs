@@ -32,6 +32,8 @@ pub mod scalar_doc_sections {
             DOC_SECTION_MAP,
             DOC_SECTION_HASHING,
             DOC_SECTION_UNION,
+            DOC_SECTION_JSON,
+            DOC_SECTION_SKETCH,
             DOC_SECTION_OTHER,
         ]
     }
@@ -49,6 +51,8 @@ pub mod scalar_doc_sections {
             DOC_SECTION_MAP,
             DOC_SECTION_HASHING,
             DOC_SECTION_UNION,
+            DOC_SECTION_JSON,
+            DOC_SECTION_SKETCH,
             DOC_SECTION_OTHER,
         ]
     }
@@ -126,6 +130,22 @@ The following regular expression functions are supported:"#,
         description: None,
     };
 
+    pub const DOC_SECTION_JSON: DocSection = DocSection {
+        include: true,
+        label: "JSON Functions",
+        description: Some(
+            "Functions to extract values from JSON documents stored as strings. The `->` and `->>` operators are shorthands for `json_get` and `json_as_text`.",
+        ),
+    };
+
+    pub const DOC_SECTION_SKETCH: DocSection = DocSection {
+        include: true,
+        label: "Sketch Functions",
+        description: Some(
+            "Functions to read the sketches and bitmaps returned by the sketch and bitmap aggregate functions, such as `hll_sketch_agg`, `kll_sketch_agg`, `theta_sketch_agg` and `bitmap_construct_agg`.",
+        ),
+    };
+
     pub const DOC_SECTION_OTHER: DocSection = DocSection {
         include: true,
         label: "Other Functions",
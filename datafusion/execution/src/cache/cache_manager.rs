@@ -16,15 +16,21 @@
 // under the License.
 
 use crate::cache::default_cache::DefaultCache;
-pub use crate::cache::{Cache, CacheValue, SchemaFingerprint, TableScopedPath};
-use datafusion_common::HashMap;
+pub use crate::cache::{Cache, CacheKey, CacheValue, SchemaFingerprint, TableScopedPath};
+use crate::memory_pool::{MemoryPool, UnboundedMemoryPool};
+use arrow::array::RecordBatch;
+use arrow::datatypes::SchemaRef;
 use datafusion_common::heap_size::{DFHeapSize, DFHeapSizeCtx};
+use datafusion_common::{HashMap, TableReference};
 use datafusion_common::{Result, Statistics};
+use datafusion_expr::LogicalPlan;
 use datafusion_physical_expr_common::sort_expr::LexOrdering;
 use object_store::ObjectMeta;
 use object_store::path::Path;
 use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
 use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 use std::sync::Arc;
 use std::time::Duration;
@@ -37,6 +43,10 @@ pub const DEFAULT_FILE_STATISTICS_MEMORY_LIMIT: usize = 20 * 1024 * 1024; // 20M
 
 pub const DEFAULT_METADATA_CACHE_LIMIT: usize = 50 * 1024 * 1024; // 50M
 
+pub const DEFAULT_RESULT_CACHE_LIMIT: usize = 0; // Disabled
+
+pub const DEFAULT_JOIN_BUILD_SIDE_CACHE_LIMIT: usize = 0; // Disabled
+
 /// A cache for file statistics and orderings.
 ///
 /// This cache stores [`CachedFileMetadata`] which includes:
@@ -89,6 +99,33 @@ pub type ListFilesCache = dyn Cache<TableScopedPath, CachedFileList>;
 /// [`ListingTable`]: https://docs.rs/datafusion/latest/datafusion/datasource/listing/struct.ListingTable.html
 pub type FileMetadataCache = dyn Cache<Path, CachedFileMetadataEntry>;
 
+/// A cache for storing the results of queries.
+///
+/// Entries are keyed by a [`ResultCacheKey`], which identifies both the
+/// (optimized) logical plan of a query and the versions of the files it reads,
+/// so an entry is never returned once any of those files has been modified.
+///
+/// If enabled via [`CacheManagerConfig::with_result_cache_limit`] this cache
+/// avoids re-executing identical read-only queries, such as the ones issued by
+/// dashboards refreshing periodically. It is disabled by default, and sessions
+/// can bypass it with the `datafusion.execution.use_result_cache` option.
+///
+/// See [`crate::runtime_env::RuntimeEnv`] for more details.
+pub type ResultCache = dyn Cache<ResultCacheKey, CachedQueryResult>;
+
+/// A cache for storing the build sides of hash joins.
+///
+/// Entries are keyed by a [`JoinBuildSideCacheKey`], which identifies both the
+/// build side of a join and the versions of the files it reads, so an entry is
+/// never returned once any of those files has been modified.
+///
+/// If enabled via [`CacheManagerConfig::with_join_build_side_cache_limit`] this
+/// cache lets repeated joins against an unchanged (typically small "dimension")
+/// table skip building their hash table. It is disabled by default.
+///
+/// See [`crate::runtime_env::RuntimeEnv`] for more details.
+pub type JoinBuildSideCache = dyn Cache<JoinBuildSideCacheKey, CachedJoinBuildSide>;
+
 /// Cached metadata for a file, including statistics and ordering.
 ///
 /// This struct embeds the [`ObjectMeta`] used for cache validation,
@@ -296,6 +333,248 @@ impl Debug for CachedFileMetadataEntry {
     }
 }
 
+/// Key of a [`ResultCache`] entry.
+///
+/// Identifies a query by the fingerprint of its logical plan and the versions
+/// (location, size, last modification time, e-tag and version) of the files it
+/// reads. The plan itself is kept as well: like [`SchemaFingerprint`], the
+/// fingerprint is only used as a cheap hash gate, and keys are compared
+/// exactly, so a fingerprint collision can never return the results of a
+/// different query.
+#[derive(Clone, Debug)]
+pub struct ResultCacheKey {
+    plan: Arc<LogicalPlan>,
+    sources: Arc<[ObjectMeta]>,
+    /// Hash of the plan fingerprint and `sources`, computed once in `new`.
+    hash: u64,
+}
+
+impl ResultCacheKey {
+    /// Create a key for the results of `plan` over the files in `sources`.
+    ///
+    /// `fingerprint` must be derived from `plan` only, so that equal plans
+    /// have equal fingerprints. The order of `sources` is irrelevant.
+    pub fn new(
+        fingerprint: u64,
+        plan: Arc<LogicalPlan>,
+        sources: Vec<ObjectMeta>,
+    ) -> Self {
+        let (sources, hash) = hash_sources(fingerprint, sources);
+        Self {
+            plan,
+            sources,
+            hash,
+        }
+    }
+
+    /// The logical plan of the query.
+    pub fn plan(&self) -> &Arc<LogicalPlan> {
+        &self.plan
+    }
+
+    /// The files read by the query, ordered by location.
+    pub fn sources(&self) -> &[ObjectMeta] {
+        &self.sources
+    }
+}
+
+impl PartialEq for ResultCacheKey {
+    fn eq(&self, other: &Self) -> bool {
+        // Cheap hash gate first, then an exact comparison so collisions are safe.
+        self.hash == other.hash
+            && self.sources == other.sources
+            && (Arc::ptr_eq(&self.plan, &other.plan) || self.plan == other.plan)
+    }
+}
+
+impl Eq for ResultCacheKey {}
+
+impl Hash for ResultCacheKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl CacheKey for ResultCacheKey {
+    fn size(&self) -> usize {
+        // The heap size of the plan itself is not tracked.
+        size_of::<LogicalPlan>()
+            + self.sources.len() * size_of::<ObjectMeta>()
+            + self.sources.iter().map(meta_heap_bytes).sum::<usize>()
+    }
+
+    fn table_ref(&self) -> Option<&TableReference> {
+        // Entries are invalidated through the versions of their source files.
+        None
+    }
+}
+
+/// Cached results of a query.
+#[derive(Debug, Clone)]
+pub struct CachedQueryResult {
+    /// Schema of the results.
+    pub schema: SchemaRef,
+    /// The result batches, in output order.
+    pub batches: Arc<Vec<RecordBatch>>,
+}
+
+impl CachedQueryResult {
+    /// Create a new cached query result.
+    pub fn new(schema: SchemaRef, batches: Vec<RecordBatch>) -> Self {
+        Self {
+            schema,
+            batches: Arc::new(batches),
+        }
+    }
+}
+
+impl CacheValue for CachedQueryResult {
+    fn size(&self) -> usize {
+        // Count the fixed overhead as well so that empty results are cached too.
+        size_of::<Self>()
+            + self
+                .batches
+                .iter()
+                .map(RecordBatch::get_array_memory_size)
+                .sum::<usize>()
+    }
+}
+
+/// Key of a [`JoinBuildSideCache`] entry.
+///
+/// Identifies the build side of a join by a `signature` describing both its
+/// plan and the join parameters that affect the built table, and by the
+/// versions of the files it reads. Like [`ResultCacheKey`], keys are compared
+/// exactly, the hash of the signature only being used as a cheap gate.
+#[derive(Clone, Debug)]
+pub struct JoinBuildSideCacheKey {
+    signature: Arc<str>,
+    sources: Arc<[ObjectMeta]>,
+    /// Hash of `signature` and `sources`, computed once in `new`.
+    hash: u64,
+}
+
+impl JoinBuildSideCacheKey {
+    /// Create a key for the build side described by `signature` over the files
+    /// in `sources`.
+    ///
+    /// The order of `sources` is irrelevant.
+    pub fn new(signature: impl Into<Arc<str>>, sources: Vec<ObjectMeta>) -> Self {
+        let signature = signature.into();
+        let mut hasher = DefaultHasher::new();
+        signature.hash(&mut hasher);
+        let (sources, hash) = hash_sources(hasher.finish(), sources);
+        Self {
+            signature,
+            sources,
+            hash,
+        }
+    }
+
+    /// The description of the build side.
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// The files read by the build side, ordered by location.
+    pub fn sources(&self) -> &[ObjectMeta] {
+        &self.sources
+    }
+}
+
+impl PartialEq for JoinBuildSideCacheKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+            && self.sources == other.sources
+            && self.signature == other.signature
+    }
+}
+
+impl Eq for JoinBuildSideCacheKey {}
+
+impl Hash for JoinBuildSideCacheKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl CacheKey for JoinBuildSideCacheKey {
+    fn size(&self) -> usize {
+        size_of::<Self>()
+            + self.signature.len()
+            + self.sources.len() * size_of::<ObjectMeta>()
+            + self.sources.iter().map(meta_heap_bytes).sum::<usize>()
+    }
+
+    fn table_ref(&self) -> Option<&TableReference> {
+        // Entries are invalidated through the versions of their source files.
+        None
+    }
+}
+
+/// The build side of a join, as built by a join operator and stored in the
+/// [`JoinBuildSideCache`].
+///
+/// The representation is private to the join operator, which downcasts the
+/// cached entries it reads back.
+pub trait JoinBuildSide: Any + Send + Sync {
+    /// Returns the build side as [`Any`] so that it can be downcast to a
+    /// specific implementation.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns the memory used by the build side, in bytes.
+    fn memory_size(&self) -> usize;
+}
+
+/// Cached build side of a join.
+#[derive(Clone)]
+pub struct CachedJoinBuildSide {
+    /// The built table.
+    pub build_side: Arc<dyn JoinBuildSide>,
+}
+
+impl CachedJoinBuildSide {
+    /// Create a new cached build side.
+    pub fn new(build_side: Arc<dyn JoinBuildSide>) -> Self {
+        Self { build_side }
+    }
+}
+
+impl CacheValue for CachedJoinBuildSide {
+    fn size(&self) -> usize {
+        self.build_side.memory_size()
+    }
+}
+
+impl Debug for CachedJoinBuildSide {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedJoinBuildSide")
+            .field("memory_size", &self.build_side.memory_size())
+            .finish()
+    }
+}
+
+/// Sorts and deduplicates `sources`, returning them with a hash of
+/// `fingerprint` and the versions of the files.
+fn hash_sources(
+    fingerprint: u64,
+    mut sources: Vec<ObjectMeta>,
+) -> (Arc<[ObjectMeta]>, u64) {
+    sources.sort_unstable_by(|a, b| a.location.cmp(&b.location));
+    sources.dedup();
+
+    let mut hasher = DefaultHasher::new();
+    fingerprint.hash(&mut hasher);
+    for meta in &sources {
+        meta.location.hash(&mut hasher);
+        meta.size.hash(&mut hasher);
+        meta.last_modified.hash(&mut hasher);
+        meta.e_tag.hash(&mut hasher);
+        meta.version.hash(&mut hasher);
+    }
+    (sources.into(), hasher.finish())
+}
+
 /// Manages various caches used in DataFusion.
 ///
 /// Following DataFusion design principles, DataFusion provides default cache
@@ -308,10 +587,22 @@ pub struct CacheManager {
     file_statistic_cache: Option<Arc<FileStatisticsCache>>,
     list_files_cache: Option<Arc<ListFilesCache>>,
     file_metadata_cache: Arc<FileMetadataCache>,
+    result_cache: Option<Arc<ResultCache>>,
+    join_build_side_cache: Option<Arc<JoinBuildSideCache>>,
 }
 
 impl CacheManager {
     pub fn try_new(config: &CacheManagerConfig) -> Result<Arc<Self>> {
+        let memory_pool: Arc<dyn MemoryPool> = Arc::new(UnboundedMemoryPool::default());
+        Self::try_new_with_memory_pool(config, &memory_pool)
+    }
+
+    /// Create a [`CacheManager`] whose result and join build side caches, if
+    /// enabled, account the memory they use in `memory_pool`.
+    pub fn try_new_with_memory_pool(
+        config: &CacheManagerConfig,
+        memory_pool: &Arc<dyn MemoryPool>,
+    ) -> Result<Arc<Self>> {
         let file_statistic_cache: Option<Arc<FileStatisticsCache>> =
             match &config.file_statistics_cache {
                 Some(fsc) if config.file_statistics_cache_limit > 0 => {
@@ -362,10 +653,47 @@ impl CacheManager {
         // the cache memory limit might have changed, ensure the limit is updated
         file_metadata_cache.update_cache_limit(config.metadata_cache_limit);
 
+        let result_cache: Option<Arc<ResultCache>> = match &config.result_cache {
+            Some(rc) if config.result_cache_limit > 0 => {
+                // the cache memory limit or pool might have changed, ensure they are updated
+                rc.update_cache_limit(config.result_cache_limit);
+                rc.update_memory_pool(memory_pool);
+                Some(Arc::clone(rc))
+            }
+            None if config.result_cache_limit > 0 => Some(Arc::new(
+                DefaultCache::<ResultCacheKey, CachedQueryResult>::new(
+                    config.result_cache_limit,
+                )
+                .with_name("DefaultResultCache")
+                .with_memory_pool(memory_pool),
+            )),
+            _ => None,
+        };
+
+        let join_build_side_cache: Option<Arc<JoinBuildSideCache>> =
+            match &config.join_build_side_cache {
+                Some(jc) if config.join_build_side_cache_limit > 0 => {
+                    // the cache memory limit or pool might have changed, ensure they are updated
+                    jc.update_cache_limit(config.join_build_side_cache_limit);
+                    jc.update_memory_pool(memory_pool);
+                    Some(Arc::clone(jc))
+                }
+                None if config.join_build_side_cache_limit > 0 => Some(Arc::new(
+                    DefaultCache::<JoinBuildSideCacheKey, CachedJoinBuildSide>::new(
+                        config.join_build_side_cache_limit,
+                    )
+                    .with_name("DefaultJoinBuildSideCache")
+                    .with_memory_pool(memory_pool),
+                )),
+                _ => None,
+            };
+
         Ok(Arc::new(CacheManager {
             file_statistic_cache,
             list_files_cache,
             file_metadata_cache,
+            result_cache,
+            join_build_side_cache,
         }))
     }
 
@@ -407,6 +735,28 @@ impl CacheManager {
     pub fn get_metadata_cache_limit(&self) -> usize {
         self.file_metadata_cache.cache_limit()
     }
+
+    /// Get the cache for storing query results.
+    pub fn get_result_cache(&self) -> Option<Arc<ResultCache>> {
+        self.result_cache.clone()
+    }
+
+    /// Get the memory limit of the result cache.
+    pub fn get_result_cache_limit(&self) -> usize {
+        self.result_cache.as_ref().map_or(0, |c| c.cache_limit())
+    }
+
+    /// Get the cache for storing the build sides of hash joins.
+    pub fn get_join_build_side_cache(&self) -> Option<Arc<JoinBuildSideCache>> {
+        self.join_build_side_cache.clone()
+    }
+
+    /// Get the memory limit of the join build side cache.
+    pub fn get_join_build_side_cache_limit(&self) -> usize {
+        self.join_build_side_cache
+            .as_ref()
+            .map_or(0, |c| c.cache_limit())
+    }
 }
 
 #[derive(Clone)]
@@ -437,6 +787,23 @@ pub struct CacheManagerConfig {
     pub file_metadata_cache: Option<Arc<FileMetadataCache>>,
     /// Limit of the file-embedded metadata cache, in bytes.
     pub metadata_cache_limit: usize,
+    /// Cache of query results, used to avoid re-executing identical queries over
+    /// unchanged files. If not provided, the [`CacheManager`] will create it when
+    /// `result_cache_limit` is not zero.
+    pub result_cache: Option<Arc<ResultCache>>,
+    /// Limit of the result cache, in bytes. The memory used by the default
+    /// result cache is also reserved from the runtime's memory pool. Default: 0
+    /// (disabled).
+    pub result_cache_limit: usize,
+    /// Cache of the build sides of hash joins, used to avoid rebuilding the
+    /// hash table of joins against unchanged files. If not provided, the
+    /// [`CacheManager`] will create it when `join_build_side_cache_limit` is
+    /// not zero.
+    pub join_build_side_cache: Option<Arc<JoinBuildSideCache>>,
+    /// Limit of the join build side cache, in bytes. The memory used by the
+    /// default join build side cache is also reserved from the runtime's memory
+    /// pool. Default: 0 (disabled).
+    pub join_build_side_cache_limit: usize,
 }
 
 impl Default for CacheManagerConfig {
@@ -449,6 +816,10 @@ impl Default for CacheManagerConfig {
             list_files_cache_ttl: DEFAULT_LIST_FILES_CACHE_TTL,
             file_metadata_cache: Default::default(),
             metadata_cache_limit: DEFAULT_METADATA_CACHE_LIMIT,
+            result_cache: Default::default(),
+            result_cache_limit: DEFAULT_RESULT_CACHE_LIMIT,
+            join_build_side_cache: Default::default(),
+            join_build_side_cache_limit: DEFAULT_JOIN_BUILD_SIDE_CACHE_LIMIT,
         }
     }
 }
@@ -507,6 +878,37 @@ impl CacheManagerConfig {
         self.metadata_cache_limit = limit;
         self
     }
+
+    /// Sets the cache for query results.
+    pub fn with_result_cache(mut self, cache: Option<Arc<ResultCache>>) -> Self {
+        self.result_cache = cache;
+        self
+    }
+
+    /// Sets the limit of the result cache, in bytes.
+    ///
+    /// Default: 0 (disabled).
+    pub fn with_result_cache_limit(mut self, limit: usize) -> Self {
+        self.result_cache_limit = limit;
+        self
+    }
+
+    /// Sets the cache for the build sides of hash joins.
+    pub fn with_join_build_side_cache(
+        mut self,
+        cache: Option<Arc<JoinBuildSideCache>>,
+    ) -> Self {
+        self.join_build_side_cache = cache;
+        self
+    }
+
+    /// Sets the limit of the join build side cache, in bytes.
+    ///
+    /// Default: 0 (disabled).
+    pub fn with_join_build_side_cache_limit(mut self, limit: usize) -> Self {
+        self.join_build_side_cache_limit = limit;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -576,4 +978,126 @@ mod tests {
             "TTL should be overridden to 60 seconds when set in config"
         );
     }
+
+    fn object_meta(path: &str, size: u64) -> ObjectMeta {
+        ObjectMeta {
+            location: Path::from(path),
+            last_modified: chrono::DateTime::parse_from_rfc3339(
+                "2025-07-29T12:12:12+00:00",
+            )
+            .unwrap()
+            .into(),
+            size,
+            e_tag: None,
+            version: None,
+        }
+    }
+
+    fn empty_plan(produce_one_row: bool) -> Arc<LogicalPlan> {
+        Arc::new(LogicalPlan::EmptyRelation(datafusion_expr::EmptyRelation {
+            produce_one_row,
+            schema: Arc::new(datafusion_common::DFSchema::empty()),
+        }))
+    }
+
+    #[test]
+    fn test_result_cache_key() {
+        let plan = empty_plan(false);
+        let key = ResultCacheKey::new(
+            1,
+            Arc::clone(&plan),
+            vec![object_meta("a", 10), object_meta("b", 20)],
+        );
+
+        // the order of the sources does not matter
+        let same = ResultCacheKey::new(
+            1,
+            empty_plan(false),
+            vec![object_meta("b", 20), object_meta("a", 10)],
+        );
+        assert_eq!(key, same);
+
+        // a modified file produces a different key
+        let modified = ResultCacheKey::new(
+            1,
+            Arc::clone(&plan),
+            vec![object_meta("a", 10), object_meta("b", 21)],
+        );
+        assert_ne!(key, modified);
+
+        // a fingerprint collision does not make different plans equal
+        let collision = ResultCacheKey::new(
+            1,
+            empty_plan(true),
+            vec![object_meta("a", 10), object_meta("b", 20)],
+        );
+        assert_ne!(key, collision);
+    }
+
+    #[test]
+    fn test_result_cache_disabled_by_default() {
+        let cache_manager =
+            CacheManager::try_new(&CacheManagerConfig::default()).unwrap();
+        assert!(cache_manager.get_result_cache().is_none());
+        assert_eq!(cache_manager.get_result_cache_limit(), 0);
+
+        let config = CacheManagerConfig::default().with_result_cache_limit(1024);
+        let cache_manager = CacheManager::try_new(&config).unwrap();
+        let result_cache = cache_manager.get_result_cache().unwrap();
+        assert_eq!(result_cache.name(), "DefaultResultCache");
+        assert_eq!(cache_manager.get_result_cache_limit(), 1024);
+    }
+
+    #[test]
+    fn test_join_build_side_cache() {
+        struct BuildSide(usize);
+
+        impl JoinBuildSide for BuildSide {
+            fn as_any(&self) -> &dyn Any {
+                self
+            }
+
+            fn memory_size(&self) -> usize {
+                self.0
+            }
+        }
+
+        let cache_manager =
+            CacheManager::try_new(&CacheManagerConfig::default()).unwrap();
+        assert!(cache_manager.get_join_build_side_cache().is_none());
+        assert_eq!(cache_manager.get_join_build_side_cache_limit(), 0);
+
+        let config = CacheManagerConfig::default().with_join_build_side_cache_limit(1024);
+        let cache_manager = CacheManager::try_new(&config).unwrap();
+        let cache = cache_manager.get_join_build_side_cache().unwrap();
+        assert_eq!(cache.name(), "DefaultJoinBuildSideCache");
+        assert_eq!(cache_manager.get_join_build_side_cache_limit(), 1024);
+
+        let key = JoinBuildSideCacheKey::new(
+            "build",
+            vec![object_meta("a", 10), object_meta("b", 20)],
+        );
+        cache.put(&key, CachedJoinBuildSide::new(Arc::new(BuildSide(100))));
+
+        // the order of the sources does not matter
+        let same = JoinBuildSideCacheKey::new(
+            "build",
+            vec![object_meta("b", 20), object_meta("a", 10)],
+        );
+        let cached = cache.get(&same).unwrap();
+        let build_side = cached.build_side.as_any().downcast_ref::<BuildSide>();
+        assert_eq!(build_side.unwrap().0, 100);
+
+        // a modified file or another build side produce different keys
+        let modified = JoinBuildSideCacheKey::new(
+            "build",
+            vec![object_meta("a", 10), object_meta("b", 21)],
+        );
+        assert!(cache.get(&modified).is_none());
+        let other = JoinBuildSideCacheKey::new(
+            "other",
+            vec![object_meta("a", 10), object_meta("b", 20)],
+        );
+        assert!(cache.get(&other).is_none());
+    }
 }
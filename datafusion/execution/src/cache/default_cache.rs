@@ -24,6 +24,7 @@ use datafusion_common::{HashMap, Result};
 
 use crate::cache::lru_queue::LruQueue;
 use crate::cache::{Cache, CacheEntryInfo, CacheKey, CacheValue};
+use crate::memory_pool::{MemoryConsumer, MemoryPool, MemoryReservation};
 
 /// Source of the current time used by a [`DefaultCache`] when applying TTLs.
 pub trait TimeProvider: Send + Sync {
@@ -55,6 +56,8 @@ struct DefaultCacheState<K: CacheKey, V: CacheValue> {
     memory_limit: usize,
     memory_used: usize,
     ttl: Option<Duration>,
+    /// Reservation mirroring `memory_used` in a [`MemoryPool`], if any.
+    reservation: Option<MemoryReservation>,
 }
 
 impl<K: CacheKey, V: CacheValue> DefaultCacheState<K, V> {
@@ -65,6 +68,7 @@ impl<K: CacheKey, V: CacheValue> DefaultCacheState<K, V> {
             memory_limit,
             memory_used: 0,
             ttl,
+            reservation: None,
         }
     }
 
@@ -130,12 +134,25 @@ impl<K: CacheKey, V: CacheValue> DefaultCacheState<K, V> {
         self.memory_used -= key.size();
         self.memory_used -= entry.value.size();
         self.hits.remove(key);
+        self.sync_reservation();
         Some(entry.value)
     }
 
+    /// Removes the least recently used entry, returning `false` if the cache
+    /// is empty.
+    fn evict_lru(&mut self) -> bool {
+        let Some((evicted_key, evicted)) = self.lru_queue.pop() else {
+            return false;
+        };
+        self.memory_used -= evicted_key.size();
+        self.memory_used -= evicted.value.size();
+        self.hits.remove(&evicted_key);
+        true
+    }
+
     fn evict_entries(&mut self) {
         while self.memory_used > self.memory_limit {
-            let Some((evicted_key, evicted)) = self.lru_queue.pop() else {
+            if !self.evict_lru() {
                 // cache is empty while memory_used > memory_limit, cannot happen
                 log::error!(
                     "DefaultCache memory accounting bug: memory_used={} but cache is empty",
@@ -143,11 +160,20 @@ impl<K: CacheKey, V: CacheValue> DefaultCacheState<K, V> {
                 );
                 debug_assert!(false, "memory_used > limit with empty cache");
                 self.memory_used = 0;
-                return;
-            };
-            self.memory_used -= evicted_key.size();
-            self.memory_used -= evicted.value.size();
-            self.hits.remove(&evicted_key);
+                break;
+            }
+        }
+        self.sync_reservation();
+    }
+
+    /// Resizes the memory pool reservation, if any, to `memory_used`, evicting
+    /// least recently used entries until the pool is able to grant it.
+    fn sync_reservation(&mut self) {
+        while let Some(reservation) = &self.reservation
+            && reservation.try_resize(self.memory_used).is_err()
+        {
+            // an empty cache needs no memory, so the loop always terminates
+            self.evict_lru();
         }
     }
 
@@ -155,6 +181,7 @@ impl<K: CacheKey, V: CacheValue> DefaultCacheState<K, V> {
         self.lru_queue.clear();
         self.hits.clear();
         self.memory_used = 0;
+        self.sync_reservation();
     }
 }
 
@@ -166,6 +193,11 @@ impl<K: CacheKey, V: CacheValue> DefaultCacheState<K, V> {
 /// limit are rejected (and any prior entry under the same key is removed).
 /// When a TTL is configured, the expiration is stamped onto each entry at
 /// insertion time and checked lazily on access. Entries with size 0 are rejected.
+///
+/// When a [`MemoryPool`] is attached via [`DefaultCache::with_memory_pool`],
+/// the memory used by the entries is also reserved from that pool, and least
+/// recently used entries are evicted whenever the pool cannot grant more
+/// memory, so the cache gives way to query execution under memory pressure.
 pub struct DefaultCache<K: CacheKey, V: CacheValue> {
     state: Mutex<DefaultCacheState<K, V>>,
     time_provider: Arc<dyn TimeProvider>,
@@ -200,6 +232,14 @@ impl<K: CacheKey, V: CacheValue> DefaultCache<K, V> {
         self
     }
 
+    /// Also account the memory used by the entries in `memory_pool`.
+    ///
+    /// See [`Cache::update_memory_pool`] for details.
+    pub fn with_memory_pool(self, memory_pool: &Arc<dyn MemoryPool>) -> Self {
+        self.update_memory_pool(memory_pool);
+        self
+    }
+
     /// Number of bytes currently accounted for by live entries.
     pub fn memory_used(&self) -> usize {
         self.state.lock().unwrap().memory_used
@@ -261,6 +301,14 @@ impl<K: CacheKey, V: CacheValue> Cache<K, V> for DefaultCache<K, V> {
         state.ttl = ttl;
     }
 
+    fn update_memory_pool(&self, memory_pool: &Arc<dyn MemoryPool>) {
+        let mut state = self.state.lock().unwrap();
+        // release the memory reserved from the previous pool, if any
+        state.reservation =
+            Some(MemoryConsumer::new(self.name.clone()).register(memory_pool));
+        state.sync_reservation();
+    }
+
     fn drop_table_entries(&self, table_ref: &TableReference) -> Result<()> {
         let mut state = self.state.lock().unwrap();
         let to_remove: Vec<K> = state
@@ -311,6 +359,7 @@ mod tests {
     use crate::cache::{Cache, CacheEntryInfo};
     use crate::cache::{CacheKey, CacheValue};
     use crate::cache::{SchemaFingerprint, TableScopedPath};
+    use crate::memory_pool::{GreedyMemoryPool, MemoryConsumer, MemoryPool};
     use arrow::array::{Int32Array, ListArray, RecordBatch};
     use arrow::buffer::{OffsetBuffer, ScalarBuffer};
     use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
@@ -609,6 +658,71 @@ mod tests {
         assert!(cache.contains_key(&object_meta14.location));
     }
 
+    #[test]
+    fn test_default_cache_with_memory_pool() {
+        let pool: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(1000));
+        // the cache limit alone would fit all the entries below
+        let cache = DefaultCache::new(10_000).with_memory_pool(&pool);
+
+        let (object_meta1, metadata1) = generate_test_metadata_with_size("01", 300);
+        let (object_meta2, metadata2) = generate_test_metadata_with_size("02", 300);
+        let (object_meta3, metadata3) = generate_test_metadata_with_size("03", 300);
+        cache.put(
+            &object_meta1.location,
+            CachedFileMetadataEntry::new(object_meta1.clone(), metadata1),
+        );
+        cache.put(
+            &object_meta2.location,
+            CachedFileMetadataEntry::new(object_meta2.clone(), metadata2),
+        );
+        cache.put(
+            &object_meta3.location,
+            CachedFileMetadataEntry::new(object_meta3.clone(), metadata3),
+        );
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.memory_used(), 906);
+        assert_eq!(pool.reserved(), 906);
+
+        // the pool cannot grant the memory for a new entry, so the LRU ("01") is evicted
+        let _ = cache.get(&object_meta2.location);
+        let (object_meta4, metadata4) = generate_test_metadata_with_size("04", 200);
+        cache.put(
+            &object_meta4.location,
+            CachedFileMetadataEntry::new(object_meta4.clone(), metadata4),
+        );
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.memory_used(), 806);
+        assert_eq!(pool.reserved(), 806);
+        assert!(!cache.contains_key(&object_meta1.location));
+        assert!(cache.contains_key(&object_meta2.location));
+
+        // memory used by other consumers takes precedence over the cached entries
+        let other = MemoryConsumer::new("other").register(&pool);
+        other.try_grow(150).unwrap();
+        let (object_meta5, metadata5) = generate_test_metadata_with_size("05", 100);
+        cache.put(
+            &object_meta5.location,
+            CachedFileMetadataEntry::new(object_meta5.clone(), metadata5),
+        );
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.memory_used(), 606);
+        assert_eq!(pool.reserved(), 756);
+        assert!(!cache.contains_key(&object_meta3.location));
+        assert!(cache.contains_key(&object_meta5.location));
+        drop(other);
+
+        // moving to a smaller pool evicts the entries that do not fit
+        let small_pool: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(150));
+        cache.update_memory_pool(&small_pool);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.contains_key(&object_meta5.location));
+        assert_eq!(pool.reserved(), 0);
+        assert_eq!(small_pool.reserved(), 102);
+
+        cache.clear();
+        assert_eq!(small_pool.reserved(), 0);
+    }
+
     #[test]
     fn test_default_file_metadata_cache_entries_info() {
         // Create a cache with 1000 bytes + 4 bytes for 4 keys each key 1 byte
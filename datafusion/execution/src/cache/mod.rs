@@ -20,6 +20,7 @@ pub mod lru_queue;
 
 pub mod default_cache;
 
+use crate::memory_pool::MemoryPool;
 use datafusion_common::arrow::datatypes::{DataType, Schema};
 use datafusion_common::heap_size::{DFHeapSize, DFHeapSizeCtx};
 use datafusion_common::instant::Instant;
@@ -28,6 +29,7 @@ use object_store::path::Path;
 use std::collections::hash_map::DefaultHasher;
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Base trait for cache implementations with common operations.
@@ -82,6 +84,13 @@ pub trait Cache<K: CacheKey, V: CacheValue>: Send + Sync {
     /// Change the TTL applied to subsequent inserts.
     fn update_cache_ttl(&self, _ttl: Option<Duration>);
 
+    /// Account the memory used by the cached entries in `memory_pool`,
+    /// evicting entries when the pool cannot grant more memory.
+    ///
+    /// Replaces any previously attached pool. The default implementation does
+    /// nothing, leaving the cache bounded by [`Self::cache_limit`] only.
+    fn update_memory_pool(&self, _memory_pool: &Arc<dyn MemoryPool>) {}
+
     /// Invalidate every entry associated with `table_ref`.
     fn drop_table_entries(
         &self,
@@ -99,6 +99,8 @@ struct RuntimeConfigValues {
     list_files_cache_limit: Option<String>,
     list_files_cache_ttl: Option<String>,
     file_statistics_cache_limit: Option<String>,
+    result_cache_limit: Option<String>,
+    join_build_side_cache_limit: Option<String>,
 }
 
 impl RuntimeConfigValues {
@@ -117,6 +119,8 @@ impl RuntimeConfigValues {
             list_files_cache_limit,
             list_files_cache_ttl,
             file_statistics_cache_limit,
+            result_cache_limit,
+            join_build_side_cache_limit,
         } = self;
         vec![
             ConfigEntry {
@@ -159,6 +163,16 @@ impl RuntimeConfigValues {
                 value: file_statistics_cache_limit,
                 description: "Maximum memory to use for file statistics cache. Supports suffixes K (kilobytes), M (megabytes), and G (gigabytes) or '0' for 0. Example: '2G' for 2 gigabytes.",
             },
+            ConfigEntry {
+                key: "datafusion.runtime.result_cache_limit".to_string(),
+                value: result_cache_limit,
+                description: "Maximum memory to use for query result cache, which is off when '0'. Supports suffixes K (kilobytes), M (megabytes), and G (gigabytes) or '0' for 0. Example: '2G' for 2 gigabytes.",
+            },
+            ConfigEntry {
+                key: "datafusion.runtime.join_build_side_cache_limit".to_string(),
+                value: join_build_side_cache_limit,
+                description: "Maximum memory to use for hash join build side cache, which is off when '0'. Supports suffixes K (kilobytes), M (megabytes), and G (gigabytes) or '0' for 0. Example: '2G'.",
+            },
         ]
     }
 }
@@ -331,6 +345,21 @@ impl RuntimeEnv {
                 .expect("File statistics cache size conversion failed"),
         );
 
+        let result_cache_limit = self.cache_manager.get_result_cache_limit();
+        let result_cache_value = format_byte_size(
+            result_cache_limit
+                .try_into()
+                .expect("Result cache size conversion failed"),
+        );
+
+        let join_build_side_cache_limit =
+            self.cache_manager.get_join_build_side_cache_limit();
+        let join_build_side_cache_value = format_byte_size(
+            join_build_side_cache_limit
+                .try_into()
+                .expect("Join build side cache size conversion failed"),
+        );
+
         RuntimeConfigValues {
             memory_limit: memory_limit_value,
             max_temp_directory_size: Some(max_temp_dir_value),
@@ -340,6 +369,8 @@ impl RuntimeEnv {
             list_files_cache_limit: Some(list_files_cache_value),
             list_files_cache_ttl,
             file_statistics_cache_limit: Some(file_statistics_cache_value),
+            result_cache_limit: Some(result_cache_value),
+            join_build_side_cache_limit: Some(join_build_side_cache_value),
         }
         .into_config_entries()
     }
@@ -480,6 +511,22 @@ impl RuntimeEnvBuilder {
         self
     }
 
+    /// Specifies the memory limit for the query result cache, in bytes.
+    ///
+    /// The result cache is disabled when the limit is 0 (the default).
+    pub fn with_result_cache_limit(mut self, limit: usize) -> Self {
+        self.cache_manager = self.cache_manager.with_result_cache_limit(limit);
+        self
+    }
+
+    /// Specifies the memory limit for the hash join build side cache, in bytes.
+    ///
+    /// The join build side cache is disabled when the limit is 0 (the default).
+    pub fn with_join_build_side_cache_limit(mut self, limit: usize) -> Self {
+        self.cache_manager = self.cache_manager.with_join_build_side_cache_limit(limit);
+        self
+    }
+
     /// Build a RuntimeEnv
     pub fn build(self) -> Result<RuntimeEnv> {
         let Self {
@@ -500,10 +547,13 @@ impl RuntimeEnvBuilder {
             (None, None) => Arc::new(DiskManagerBuilder::default().build()?),
         };
 
+        let cache_manager =
+            CacheManager::try_new_with_memory_pool(&cache_manager, &memory_pool)?;
+
         Ok(RuntimeEnv {
             memory_pool,
             disk_manager,
-            cache_manager: CacheManager::try_new(&cache_manager)?,
+            cache_manager,
             object_store_registry,
             #[cfg(feature = "parquet_encryption")]
             parquet_encryption_factory_registry,
@@ -531,6 +581,12 @@ impl RuntimeEnvBuilder {
                 runtime_env.cache_manager.get_file_metadata_cache(),
             ),
             metadata_cache_limit: runtime_env.cache_manager.get_metadata_cache_limit(),
+            result_cache: runtime_env.cache_manager.get_result_cache(),
+            result_cache_limit: runtime_env.cache_manager.get_result_cache_limit(),
+            join_build_side_cache: runtime_env.cache_manager.get_join_build_side_cache(),
+            join_build_side_cache_limit: runtime_env
+                .cache_manager
+                .get_join_build_side_cache_limit(),
         };
 
         Self {
@@ -557,6 +613,8 @@ impl RuntimeEnvBuilder {
             list_files_cache_limit: Some("1M".to_owned()),
             list_files_cache_ttl: None,
             file_statistics_cache_limit: Some("20M".to_owned()),
+            result_cache_limit: Some("0".to_owned()),
+            join_build_side_cache_limit: Some("0".to_owned()),
         }
         .into_config_entries()
     }
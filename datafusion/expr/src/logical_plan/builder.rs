@@ -31,8 +31,8 @@ use crate::expr_rewriter::{
     rewrite_sort_cols_by_aggs,
 };
 use crate::logical_plan::{
-    Aggregate, Analyze, Distinct, DistinctOn, EmptyRelation, Explain, Filter, Join,
-    JoinConstraint, JoinType, Limit, LogicalPlan, Partitioning, PlanType, Prepare,
+    Aggregate, Analyze, AsOfJoin, Distinct, DistinctOn, EmptyRelation, Explain, Filter,
+    Join, JoinConstraint, JoinType, Limit, LogicalPlan, Partitioning, PlanType, Prepare,
     Projection, Repartition, Sort, SubqueryAlias, TableScanBuilder, Union, Unnest,
     Values, Window,
 };
@@ -40,11 +40,12 @@ use crate::select_expr::SelectExpr;
 use crate::utils::{
     can_hash, columnize_expr, compare_sort_expr, expand_qualified_wildcard,
     expand_wildcard, expr_to_columns, find_valid_equijoin_key_pair,
-    group_window_expr_by_sort_keys,
+    group_window_expr_by_sort_keys, split_conjunction_owned,
 };
 use crate::{
-    DmlStatement, ExplainOption, Expr, ExprSchemable, Operator, RecursiveQuery,
-    Statement, TableProviderFilterPushDown, TableSource, WriteOp, and, binary_expr, lit,
+    BinaryExpr, DmlStatement, ExplainOption, Expr, ExprSchemable, Operator,
+    RecursiveQuery, Statement, TableProviderFilterPushDown, TableSource, WriteOp, and,
+    binary_expr, lit,
 };
 
 use super::dml::InsertOp;
@@ -1268,6 +1269,43 @@ impl LogicalPlanBuilder {
         Ok(Self::new(LogicalPlan::Join(join)))
     }
 
+    /// Apply an ASOF join: join every row of this plan with the closest row
+    /// of `right` that satisfies `match_condition`, among the rows whose join
+    /// keys are equal.
+    ///
+    /// `on_exprs` are equalities, and `match_condition` is a `<`, `<=`, `>` or
+    /// `>=` comparison, between an expression of each input. Rows of this
+    /// plan without a match are joined with nulls. See [`AsOfJoin`] for
+    /// details.
+    pub fn join_asof(
+        self,
+        right: LogicalPlan,
+        on_exprs: impl IntoIterator<Item = Expr>,
+        match_condition: Expr,
+    ) -> Result<Self> {
+        let schemas = [self.schema().as_ref(), right.schema().as_ref()];
+        let normalize =
+            |expr| normalize_col_with_schemas_and_ambiguity_check(expr, &[&schemas], &[]);
+        let on = on_exprs
+            .into_iter()
+            .flat_map(split_conjunction_owned)
+            .map(|expr| match normalize(expr)? {
+                Expr::BinaryExpr(BinaryExpr {
+                    left,
+                    op: Operator::Eq,
+                    right,
+                }) => Ok((*left, *right)),
+                other => {
+                    plan_err!("ASOF join condition must be an equality, got {other}")
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let match_condition = normalize(match_condition)?;
+
+        let join = AsOfJoin::try_new(self.plan, Arc::new(right), on, match_condition)?;
+        Ok(Self::new(LogicalPlan::AsOfJoin(join)))
+    }
+
     /// Repartition
     pub fn repartition(self, partitioning_scheme: Partitioning) -> Result<Self> {
         Ok(Self::new(LogicalPlan::Repartition(Repartition {
@@ -2622,6 +2660,50 @@ mod tests {
         table_scan(Some(name), &schema, None)?.build()
     }
 
+    #[test]
+    fn plan_builder_asof_join() -> Result<()> {
+        // Keys and the match condition are oriented left-to-right, flipping
+        // the comparison when it is written right-side first
+        let plan = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .join_asof(
+                test_table_scan_with_name("t2")?,
+                vec![col("t2.a").eq(col("t1.a"))],
+                col("t2.b").lt(col("t1.b")),
+            )?
+            .build()?;
+        assert_snapshot!(plan, @r"
+        AsOf Join: t1.a = t2.a, Match: t1.b > t2.b
+          TableScan: t1
+          TableScan: t2
+        ");
+        // The right side is nullable, as in a left join
+        assert!(
+            plan.schema()
+                .field_with_name(Some(&"t2".into()), "a")?
+                .is_nullable()
+        );
+
+        let err = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .join_asof(
+                test_table_scan_with_name("t2")?,
+                vec![],
+                col("t1.b").eq(col("t2.b")),
+            )
+            .unwrap_err();
+        assert_snapshot!(err.strip_backtrace(), @"Error during planning: ASOF join match condition must use <, <=, > or >=, got =");
+
+        let err = LogicalPlanBuilder::from(test_table_scan_with_name("t1")?)
+            .join_asof(
+                test_table_scan_with_name("t2")?,
+                vec![col("t1.a").gt(col("t2.a"))],
+                col("t1.b").gt(col("t2.b")),
+            )
+            .unwrap_err();
+        assert_snapshot!(err.strip_backtrace(), @"Error during planning: ASOF join condition must be an equality, got t1.a > t2.a");
+
+        Ok(())
+    }
+
     #[test]
     fn plan_builder_intersect_different_num_columns_error() -> Result<()> {
         let plan1 =
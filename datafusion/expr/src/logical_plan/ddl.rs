@@ -27,10 +27,13 @@ use std::{
 use crate::expr::Sort;
 #[cfg(not(feature = "sql"))]
 use crate::sql::Ident;
-use arrow::datatypes::DataType;
-use datafusion_common::tree_node::{Transformed, TreeNodeContainer, TreeNodeRecursion};
+use arrow::datatypes::{DataType, FieldRef, Schema};
+use datafusion_common::tree_node::{
+    Transformed, TreeNode, TreeNodeContainer, TreeNodeRecursion,
+};
 use datafusion_common::{
-    Constraints, DFSchemaRef, Result, SchemaReference, TableReference,
+    Column, Constraints, DFSchemaRef, Result, SchemaReference, TableReference,
+    internal_err, plan_err,
 };
 #[cfg(feature = "sql")]
 use sqlparser::ast::Ident;
@@ -63,6 +66,14 @@ pub enum DdlStatement {
     CreateFunction(Box<CreateFunction>),
     /// Drop function statement
     DropFunction(DropFunction),
+    /// Collects statistics for a table (`ANALYZE TABLE`).
+    AnalyzeTable(AnalyzeTable),
+    /// Alters the definition of a table (`ALTER TABLE`).
+    AlterTable(AlterTable),
+    /// Creates a materialized view.
+    CreateMaterializedView(CreateMaterializedView),
+    /// Recomputes the stored results of a materialized view.
+    RefreshMaterializedView(RefreshMaterializedView),
 }
 
 impl DdlStatement {
@@ -71,7 +82,11 @@ impl DdlStatement {
         match self {
             DdlStatement::CreateExternalTable(ce) => &ce.schema,
             DdlStatement::CreateMemoryTable(CreateMemoryTable { input, .. })
-            | DdlStatement::CreateView(CreateView { input, .. }) => input.schema(),
+            | DdlStatement::CreateView(CreateView { input, .. })
+            | DdlStatement::CreateMaterializedView(CreateMaterializedView {
+                input,
+                ..
+            }) => input.schema(),
             DdlStatement::CreateCatalogSchema(CreateCatalogSchema { schema, .. }) => {
                 schema
             }
@@ -82,6 +97,12 @@ impl DdlStatement {
             DdlStatement::DropCatalogSchema(DropCatalogSchema { schema, .. }) => schema,
             DdlStatement::CreateFunction(cf) => &cf.schema,
             DdlStatement::DropFunction(DropFunction { schema, .. }) => schema,
+            DdlStatement::AnalyzeTable(AnalyzeTable { schema, .. }) => schema,
+            DdlStatement::AlterTable(AlterTable { schema, .. }) => schema,
+            DdlStatement::RefreshMaterializedView(RefreshMaterializedView {
+                schema,
+                ..
+            }) => schema,
         }
     }
 
@@ -100,6 +121,10 @@ impl DdlStatement {
             DdlStatement::DropCatalogSchema(_) => "DropCatalogSchema",
             DdlStatement::CreateFunction(_) => "CreateFunction",
             DdlStatement::DropFunction(_) => "DropFunction",
+            DdlStatement::AnalyzeTable(_) => "AnalyzeTable",
+            DdlStatement::AlterTable(_) => "AlterTable",
+            DdlStatement::CreateMaterializedView(_) => "CreateMaterializedView",
+            DdlStatement::RefreshMaterializedView(_) => "RefreshMaterializedView",
         }
     }
 
@@ -119,6 +144,13 @@ impl DdlStatement {
             DdlStatement::DropCatalogSchema(_) => vec![],
             DdlStatement::CreateFunction(_) => vec![],
             DdlStatement::DropFunction(_) => vec![],
+            DdlStatement::AnalyzeTable(_) => vec![],
+            DdlStatement::AlterTable(_) => vec![],
+            DdlStatement::CreateMaterializedView(CreateMaterializedView {
+                input,
+                ..
+            }) => vec![input],
+            DdlStatement::RefreshMaterializedView(_) => vec![],
         }
     }
 
@@ -197,6 +229,53 @@ impl DdlStatement {
                     DdlStatement::DropFunction(DropFunction { name, .. }) => {
                         write!(f, "DropFunction: name {name:?}")
                     }
+                    DdlStatement::AnalyzeTable(AnalyzeTable {
+                        name, columns, ..
+                    }) => {
+                        if columns.is_empty() {
+                            write!(f, "AnalyzeTable: {name:?}")
+                        } else {
+                            write!(
+                                f,
+                                "AnalyzeTable: {name:?} columns=[{}]",
+                                columns.join(", ")
+                            )
+                        }
+                    }
+                    DdlStatement::AlterTable(AlterTable {
+                        name,
+                        if_exists,
+                        operations,
+                        ..
+                    }) => {
+                        write!(
+                            f,
+                            "AlterTable: {name:?} if exists:={if_exists} operations=[{}]",
+                            operations
+                                .iter()
+                                .map(|op| op.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    }
+                    DdlStatement::CreateMaterializedView(CreateMaterializedView {
+                        name,
+                        location,
+                        ..
+                    }) => match location {
+                        Some(location) => write!(
+                            f,
+                            "CreateMaterializedView: {name:?} location:={location}"
+                        ),
+                        None => write!(f, "CreateMaterializedView: {name:?}"),
+                    },
+                    DdlStatement::RefreshMaterializedView(RefreshMaterializedView {
+                        name,
+                        full,
+                        ..
+                    }) => {
+                        write!(f, "RefreshMaterializedView: {name:?} full:={full}")
+                    }
                 }
             }
         }
@@ -507,6 +586,54 @@ pub struct CreateView {
     pub temporary: bool,
 }
 
+/// Creates a materialized view, which stores the results of its query until
+/// it is refreshed.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Hash)]
+pub struct CreateMaterializedView {
+    /// The view name
+    pub name: TableReference,
+    /// The logical plan of the query of the view
+    pub input: Arc<LogicalPlan>,
+    /// Do nothing if a table with the same name already exists
+    pub if_not_exists: bool,
+    /// Replace the view if it already exists
+    pub or_replace: bool,
+    /// The location of the files storing the results. The results are stored
+    /// in memory if `None`.
+    pub location: Option<String>,
+    /// The file type of the files storing the results, such as `parquet`
+    pub file_type: String,
+    /// The options of the file format, as in `CREATE EXTERNAL TABLE ... OPTIONS`
+    pub options: Vec<(String, String)>,
+    /// SQL used to create the view, if available
+    pub definition: Option<String>,
+}
+
+/// Recomputes the stored results of a materialized view
+/// (`REFRESH MATERIALIZED VIEW`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RefreshMaterializedView {
+    /// The view name
+    pub name: TableReference,
+    /// Recompute the results from all the rows of the source tables, even if
+    /// they could be updated with the rows appended since the last refresh
+    pub full: bool,
+    /// Dummy schema
+    pub schema: DFSchemaRef,
+}
+
+// Manual implementation needed because of `schema` field. Comparison excludes this field.
+impl PartialOrd for RefreshMaterializedView {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.name.partial_cmp(&other.name) {
+            Some(Ordering::Equal) => self.full.partial_cmp(&other.full),
+            cmp => cmp,
+        }
+        // TODO (https://github.com/apache/datafusion/issues/17477) avoid recomparing all fields
+        .filter(|cmp| *cmp != Ordering::Equal || self == other)
+    }
+}
+
 /// Creates a catalog (aka "Database").
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CreateCatalog {
@@ -773,6 +900,278 @@ impl PartialOrd for DropFunction {
     }
 }
 
+/// Computes statistics for a table and stores them in the statistics store
+/// of the table's catalog (`ANALYZE TABLE`).
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AnalyzeTable {
+    /// The table to analyze
+    pub name: TableReference,
+    /// The columns to compute statistics for. Empty means all columns.
+    pub columns: Vec<String>,
+    /// Dummy schema
+    pub schema: DFSchemaRef,
+}
+
+// Manual implementation needed because of `schema` field. Comparison excludes this field.
+impl PartialOrd for AnalyzeTable {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.name.partial_cmp(&other.name) {
+            Some(Ordering::Equal) => self.columns.partial_cmp(&other.columns),
+            cmp => cmp,
+        }
+        // TODO (https://github.com/apache/datafusion/issues/17477) avoid recomparing all fields
+        .filter(|cmp| *cmp != Ordering::Equal || self == other)
+    }
+}
+
+/// Alters the definition of a table (`ALTER TABLE`).
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AlterTable {
+    /// The table to alter
+    pub name: TableReference,
+    /// Do nothing if the table does not exist
+    pub if_exists: bool,
+    /// The operations to apply, in order
+    pub operations: Vec<AlterTableOperation>,
+    /// Dummy schema
+    pub schema: DFSchemaRef,
+}
+
+// Manual implementation needed because of `schema` field. Comparison excludes this field.
+impl PartialOrd for AlterTable {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        #[derive(PartialEq, PartialOrd)]
+        struct ComparableAlterTable<'a> {
+            pub name: &'a TableReference,
+            pub if_exists: &'a bool,
+            pub operations: &'a Vec<AlterTableOperation>,
+        }
+        let comparable_self = ComparableAlterTable {
+            name: &self.name,
+            if_exists: &self.if_exists,
+            operations: &self.operations,
+        };
+        let comparable_other = ComparableAlterTable {
+            name: &other.name,
+            if_exists: &other.if_exists,
+            operations: &other.operations,
+        };
+        comparable_self
+            .partial_cmp(&comparable_other)
+            // TODO (https://github.com/apache/datafusion/issues/17477) avoid recomparing all fields
+            .filter(|cmp| *cmp != Ordering::Equal || self == other)
+    }
+}
+
+/// A single operation of an [`AlterTable`] statement.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Hash, Debug)]
+pub enum AlterTableOperation {
+    /// Adds a column after the existing columns
+    AddColumn {
+        /// The new column
+        field: FieldRef,
+        /// The value of the column for the existing rows, which is also the
+        /// default value of inserted rows. Existing rows are null if `None`.
+        default: Option<Expr>,
+        /// Do nothing if a column with the same name exists
+        if_not_exists: bool,
+    },
+    /// Drops a column
+    DropColumn {
+        /// The column to drop
+        name: String,
+        /// Do nothing if the column does not exist
+        if_exists: bool,
+    },
+    /// Renames a column
+    RenameColumn {
+        /// The current name of the column
+        old_name: String,
+        /// The new name of the column
+        new_name: String,
+    },
+    /// Renames the table, within its schema
+    RenameTable {
+        /// The new name of the table
+        new_name: TableReference,
+    },
+    /// Sets options of the table, such as the options of its file format
+    SetOptions {
+        /// The option keys and values
+        options: Vec<(String, String)>,
+    },
+}
+
+impl AlterTableOperation {
+    /// Returns `false` if this operation does nothing for a table with
+    /// `schema`, because of its `IF [NOT] EXISTS` clause.
+    pub fn has_effect(&self, schema: &Schema) -> bool {
+        match self {
+            AlterTableOperation::AddColumn {
+                field,
+                if_not_exists: true,
+                ..
+            } => schema.field_with_name(field.name()).is_err(),
+            AlterTableOperation::DropColumn {
+                name,
+                if_exists: true,
+            } => schema.field_with_name(name).is_ok(),
+            _ => true,
+        }
+    }
+
+    /// Returns the schema of a table with `schema` after this operation.
+    ///
+    /// Returns an error if the operation is not valid for the table, for
+    /// example when it drops a column that does not exist.
+    pub fn apply_to_schema(&self, schema: &Schema) -> Result<Schema> {
+        let mut fields = schema.fields().to_vec();
+        match self {
+            AlterTableOperation::AddColumn { field, default, .. } => {
+                if schema.field_with_name(field.name()).is_ok() {
+                    return plan_err!("Column {} already exists", field.name());
+                }
+                if !field.is_nullable() && default.is_none() {
+                    return plan_err!(
+                        "Cannot add column {} that is NOT NULL without a DEFAULT value",
+                        field.name()
+                    );
+                }
+                fields.push(Arc::clone(field));
+            }
+            AlterTableOperation::DropColumn { name, .. } => {
+                let index = schema.index_of(name)?;
+                if fields.len() == 1 {
+                    return plan_err!(
+                        "Cannot drop column {name}, the only column of the table"
+                    );
+                }
+                fields.remove(index);
+            }
+            AlterTableOperation::RenameColumn { old_name, new_name } => {
+                let index = schema.index_of(old_name)?;
+                if schema.field_with_name(new_name).is_ok() {
+                    return plan_err!("Column {new_name} already exists");
+                }
+                fields[index] =
+                    Arc::new(fields[index].as_ref().clone().with_name(new_name));
+            }
+            AlterTableOperation::RenameTable { .. }
+            | AlterTableOperation::SetOptions { .. } => {}
+        }
+        Ok(Schema::new_with_metadata(fields, schema.metadata().clone()))
+    }
+
+    /// Returns the sort orders of a table after this operation.
+    ///
+    /// Renamed columns are renamed in the sort expressions. A sort order is
+    /// truncated before the first expression referencing a dropped column,
+    /// as the rows are still sorted by the remaining prefix.
+    pub fn apply_to_sort_order(
+        &self,
+        sort_order: &[Vec<SortExpr>],
+    ) -> Result<Vec<Vec<SortExpr>>> {
+        match self {
+            AlterTableOperation::DropColumn { name, .. } => Ok(sort_order
+                .iter()
+                .map(|exprs| {
+                    exprs
+                        .iter()
+                        .take_while(|sort| {
+                            !sort.expr.column_refs().iter().any(|c| &c.name == name)
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>()
+                })
+                .filter(|exprs| !exprs.is_empty())
+                .collect()),
+            AlterTableOperation::RenameColumn { old_name, new_name } => sort_order
+                .iter()
+                .map(|exprs| {
+                    exprs
+                        .iter()
+                        .map(|sort| {
+                            let expr = sort
+                                .expr
+                                .clone()
+                                .transform(|expr| match expr {
+                                    Expr::Column(Column {
+                                        relation,
+                                        name,
+                                        spans,
+                                    }) if &name == old_name => {
+                                        Ok(Transformed::yes(Expr::Column(Column {
+                                            relation,
+                                            name: new_name.clone(),
+                                            spans,
+                                        })))
+                                    }
+                                    _ => Ok(Transformed::no(expr)),
+                                })?
+                                .data;
+                            Ok(sort.with_expr(expr))
+                        })
+                        .collect()
+                })
+                .collect(),
+            AlterTableOperation::RenameTable { .. } => {
+                internal_err!("Sort orders are not affected by renaming a table")
+            }
+            AlterTableOperation::AddColumn { .. }
+            | AlterTableOperation::SetOptions { .. } => Ok(sort_order.to_vec()),
+        }
+    }
+}
+
+impl Display for AlterTableOperation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AlterTableOperation::AddColumn {
+                field,
+                default,
+                if_not_exists,
+            } => {
+                write!(f, "ADD COLUMN ")?;
+                if *if_not_exists {
+                    write!(f, "IF NOT EXISTS ")?;
+                }
+                write!(f, "{} {}", field.name(), field.data_type())?;
+                if !field.is_nullable() {
+                    write!(f, " NOT NULL")?;
+                }
+                if let Some(default) = default {
+                    write!(f, " DEFAULT {default}")?;
+                }
+                Ok(())
+            }
+            AlterTableOperation::DropColumn { name, if_exists } => {
+                write!(f, "DROP COLUMN ")?;
+                if *if_exists {
+                    write!(f, "IF EXISTS ")?;
+                }
+                write!(f, "{name}")
+            }
+            AlterTableOperation::RenameColumn { old_name, new_name } => {
+                write!(f, "RENAME COLUMN {old_name} TO {new_name}")
+            }
+            AlterTableOperation::RenameTable { new_name } => {
+                write!(f, "RENAME TO {new_name}")
+            }
+            AlterTableOperation::SetOptions { options } => {
+                write!(
+                    f,
+                    "SET ({})",
+                    options
+                        .iter()
+                        .map(|(key, value)| format!("'{key}' = '{value}'"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct CreateIndex {
     pub name: Option<String>,
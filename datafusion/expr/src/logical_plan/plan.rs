@@ -42,13 +42,14 @@ use crate::logical_plan::extension::UserDefinedLogicalNode;
 use crate::logical_plan::{DmlStatement, Statement, WriteOp};
 use crate::utils::{
     check_aggregate_and_window_nesting, enumerate_grouping_sets, exprlist_to_fields,
-    find_out_reference_exprs, grouping_set_expr_count, grouping_set_to_exprlist,
-    merge_schema, split_conjunction,
+    find_out_reference_exprs, find_valid_equijoin_key_pair, grouping_set_expr_count,
+    grouping_set_to_exprlist, merge_schema, split_conjunction,
 };
 use crate::{
-    BinaryExpr, CreateMemoryTable, CreateView, Execute, Expr, ExprSchemable, GroupingSet,
-    LogicalPlanBuilder, Operator, Prepare, TableProviderFilterPushDown, TableSource,
-    WindowFunctionDefinition, build_join_schema, expr_vec_fmt, requalify_sides_if_needed,
+    BinaryExpr, CreateMaterializedView, CreateMemoryTable, CreateView, Execute, Expr,
+    ExprSchemable, GroupingSet, LogicalPlanBuilder, Operator, Prepare,
+    TableProviderFilterPushDown, TableSource, WindowFunctionDefinition,
+    build_join_schema, expr_vec_fmt, requalify_sides_if_needed,
 };
 
 use crate::statistics::StatisticsRequest;
@@ -65,7 +66,7 @@ use datafusion_common::{
     FunctionalDependence, FunctionalDependencies, NullEquality, ParamValues, Result,
     ScalarValue, Spans, SplitPoint, TableReference, UnnestOptions,
     aggregate_functional_dependencies, assert_eq_or_internal_err, assert_or_internal_err,
-    internal_err, plan_err, validate_range_split_points,
+    internal_err, plan_datafusion_err, plan_err, validate_range_split_points,
 };
 use indexmap::IndexSet;
 use itertools::Itertools as _;
@@ -239,6 +240,11 @@ pub enum LogicalPlan {
     /// Join two logical plans on one or more join columns.
     /// This is used to implement SQL `JOIN`
     Join(Join),
+    /// Join every left row with the closest right row satisfying an ordered
+    /// comparison. This is used to implement SQL `ASOF JOIN`.
+    ///
+    /// See [`AsOfJoin`] for more details
+    AsOfJoin(AsOfJoin),
     /// Repartitions the input based on a partitioning scheme. This is
     /// used to add parallelism and is sometimes referred to as an
     /// "exchange" operator in other systems
@@ -342,6 +348,7 @@ impl LogicalPlan {
             LogicalPlan::Aggregate(Aggregate { schema, .. }) => schema,
             LogicalPlan::Sort(Sort { input, .. }) => input.schema(),
             LogicalPlan::Join(Join { schema, .. }) => schema,
+            LogicalPlan::AsOfJoin(AsOfJoin { schema, .. }) => schema,
             LogicalPlan::Repartition(Repartition { input, .. }) => input.schema(),
             LogicalPlan::Limit(Limit { input, .. }) => input.schema(),
             LogicalPlan::Statement(statement) => statement.schema(),
@@ -370,7 +377,8 @@ impl LogicalPlan {
             | LogicalPlan::Projection(_)
             | LogicalPlan::Aggregate(_)
             | LogicalPlan::Unnest(_)
-            | LogicalPlan::Join(_) => self
+            | LogicalPlan::Join(_)
+            | LogicalPlan::AsOfJoin(_) => self
                 .inputs()
                 .iter()
                 .map(|input| input.schema().as_ref())
@@ -460,6 +468,7 @@ impl LogicalPlan {
             LogicalPlan::Aggregate(Aggregate { input, .. }) => vec![input],
             LogicalPlan::Sort(Sort { input, .. }) => vec![input],
             LogicalPlan::Join(Join { left, right, .. }) => vec![left, right],
+            LogicalPlan::AsOfJoin(AsOfJoin { left, right, .. }) => vec![left, right],
             LogicalPlan::Limit(Limit { input, .. }) => vec![input],
             LogicalPlan::Subquery(Subquery { subquery, .. }) => vec![subquery],
             LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. }) => vec![input],
@@ -568,6 +577,13 @@ impl LogicalPlan {
                     right.head_output_expr()
                 }
             },
+            LogicalPlan::AsOfJoin(AsOfJoin { left, right, .. }) => {
+                if left.schema().fields().is_empty() {
+                    right.head_output_expr()
+                } else {
+                    left.head_output_expr()
+                }
+            }
             LogicalPlan::RecursiveQuery(RecursiveQuery { static_term, .. }) => {
                 static_term.head_output_expr()
             }
@@ -691,6 +707,23 @@ impl LogicalPlan {
                     null_aware,
                 }))
             }
+            LogicalPlan::AsOfJoin(AsOfJoin {
+                left,
+                right,
+                on,
+                match_condition,
+                schema: _,
+            }) => {
+                let schema =
+                    build_join_schema(left.schema(), right.schema(), &JoinType::Left)?;
+                Ok(LogicalPlan::AsOfJoin(AsOfJoin {
+                    left,
+                    right,
+                    on,
+                    match_condition,
+                    schema: DFSchemaRef::new(schema),
+                }))
+            }
             LogicalPlan::Subquery(_) => Ok(self),
             LogicalPlan::SubqueryAlias(SubqueryAlias {
                 input,
@@ -994,6 +1027,43 @@ impl LogicalPlan {
                     null_aware: *null_aware,
                 }))
             }
+            LogicalPlan::AsOfJoin(AsOfJoin {
+                on,
+                match_condition,
+                ..
+            }) => {
+                let (left, right) = self.only_two_inputs(inputs)?;
+                let schema =
+                    build_join_schema(left.schema(), right.schema(), &JoinType::Left)?;
+
+                // The equi-exprs, as `left-expr, right-expr` pairs, followed
+                // by the two sides of the match condition
+                assert_eq_or_internal_err!(
+                    expr.len(),
+                    on.len() * 2 + 2,
+                    "Invalid number of new ASOF join expressions"
+                );
+                let match_right = expr.pop().unwrap();
+                let match_left = expr.pop().unwrap();
+                let new_on = expr
+                    .into_iter()
+                    .tuples()
+                    // SimplifyExpression rule may add alias to the equi_expr.
+                    .map(|(left, right): (Expr, Expr)| (left.unalias(), right.unalias()))
+                    .collect();
+
+                Ok(LogicalPlan::AsOfJoin(AsOfJoin {
+                    left: Arc::new(left),
+                    right: Arc::new(right),
+                    on: new_on,
+                    match_condition: Box::new(AsOfMatch {
+                        left: match_left.unalias(),
+                        op: match_condition.op,
+                        right: match_right.unalias(),
+                    }),
+                    schema: DFSchemaRef::new(schema),
+                }))
+            }
             LogicalPlan::Subquery(Subquery {
                 outer_ref_columns,
                 spans,
@@ -1072,6 +1142,18 @@ impl LogicalPlan {
                     definition: definition.clone(),
                 })))
             }
+            LogicalPlan::Ddl(DdlStatement::CreateMaterializedView(
+                create @ CreateMaterializedView { .. },
+            )) => {
+                self.assert_no_expressions(expr)?;
+                let input = self.only_input(inputs)?;
+                Ok(LogicalPlan::Ddl(DdlStatement::CreateMaterializedView(
+                    CreateMaterializedView {
+                        input: Arc::new(input),
+                        ..create.clone()
+                    },
+                )))
+            }
             LogicalPlan::Extension(e) => Ok(LogicalPlan::Extension(Extension {
                 node: e.node.with_exprs_and_inputs(expr, inputs)?,
             })),
@@ -1419,6 +1501,7 @@ impl LogicalPlan {
                     right.max_rows()
                 }
             },
+            LogicalPlan::AsOfJoin(AsOfJoin { left, .. }) => left.max_rows(),
             LogicalPlan::Repartition(Repartition { input, .. }) => input.max_rows(),
             LogicalPlan::Union(Union { inputs, .. }) => {
                 inputs.iter().try_fold(0usize, |mut acc, plan| {
@@ -1494,6 +1577,7 @@ impl LogicalPlan {
                 | JoinType::LeftAnti
                 | JoinType::RightAnti => 0,
             },
+            LogicalPlan::AsOfJoin(AsOfJoin { left, .. }) => left.min_rows(),
             LogicalPlan::Union(Union { inputs, .. }) => inputs
                 .iter()
                 .fold(0, |rows, input| rows.saturating_add(input.min_rows())),
@@ -1547,6 +1631,7 @@ impl LogicalPlan {
             LogicalPlan::Window(_) => Ok(None),
             LogicalPlan::Aggregate(_) => Ok(None),
             LogicalPlan::Join(_) => Ok(None),
+            LogicalPlan::AsOfJoin(_) => Ok(None),
             LogicalPlan::Repartition(_) => Ok(None),
             LogicalPlan::Union(_) => Ok(None),
             LogicalPlan::EmptyRelation(_) => Ok(None),
@@ -1585,6 +1670,7 @@ impl LogicalPlan {
             LogicalPlan::Window(_) => Ok(None),
             LogicalPlan::Aggregate(_) => Ok(None),
             LogicalPlan::Join(_) => Ok(None),
+            LogicalPlan::AsOfJoin(_) => Ok(None),
             LogicalPlan::Repartition(_) => Ok(None),
             LogicalPlan::Union(_) => Ok(None),
             LogicalPlan::EmptyRelation(_) => Ok(None),
@@ -2213,6 +2299,23 @@ impl LogicalPlan {
                             }
                         }
                     }
+                    LogicalPlan::AsOfJoin(AsOfJoin {
+                        on,
+                        match_condition,
+                        ..
+                    }) => {
+                        write!(f, "AsOf Join:")?;
+                        for (l, r) in on {
+                            write!(f, " {l} = {r},")?;
+                        }
+                        write!(
+                            f,
+                            " Match: {} {} {}",
+                            match_condition.left,
+                            match_condition.op,
+                            match_condition.right
+                        )
+                    }
                     LogicalPlan::Repartition(Repartition {
                         partitioning_scheme,
                         ..
@@ -4482,6 +4585,159 @@ impl PartialOrd for Join {
     }
 }
 
+/// ASOF join: for every left row, the closest right row that satisfies an
+/// ordered comparison, within the group of right rows with equal join keys.
+///
+/// This follows Snowflake's [ASOF JOIN] semantics:
+///
+/// ```text
+/// SELECT * FROM trades ASOF JOIN quotes
+///   MATCH_CONDITION(trades.ts >= quotes.ts)
+///   ON trades.symbol = quotes.symbol
+/// ```
+///
+/// returns each trade with the latest quote of the same symbol at or before
+/// the trade. Left rows without such a right row are joined with nulls, so
+/// the output schema is that of a left outer join.
+///
+/// [ASOF JOIN]: https://docs.snowflake.com/en/sql-reference/constructs/asof-join
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AsOfJoin {
+    /// Left input
+    pub left: Arc<LogicalPlan>,
+    /// Right input
+    pub right: Arc<LogicalPlan>,
+    /// Equijoin clause expressed as pairs of (left, right) join expressions
+    pub on: Vec<(Expr, Expr)>,
+    /// The ordered comparison selecting the closest right row, boxed so it
+    /// does not grow [`LogicalPlan`]
+    pub match_condition: Box<AsOfMatch>,
+    /// The output schema, containing fields from the left and right inputs
+    pub schema: DFSchemaRef,
+}
+
+/// The `MATCH_CONDITION` of an [`AsOfJoin`]: `left op right`, where `op` is
+/// one of `<`, `<=`, `>` or `>=`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+pub struct AsOfMatch {
+    /// Expression evaluated against the left input
+    pub left: Expr,
+    /// Ordered comparison operator
+    pub op: Operator,
+    /// Expression evaluated against the right input
+    pub right: Expr,
+}
+
+impl AsOfJoin {
+    /// Creates a new ASOF join with automatically computed schema.
+    ///
+    /// Each `on` pair must compare an expression of one input to an
+    /// expression of the other, and `match_condition` must be such a
+    /// comparison using `<`, `<=`, `>` or `>=`. Pairs given in right-to-left
+    /// order are swapped (along with the match operator).
+    pub fn try_new(
+        left: Arc<LogicalPlan>,
+        right: Arc<LogicalPlan>,
+        on: Vec<(Expr, Expr)>,
+        match_condition: Expr,
+    ) -> Result<Self> {
+        let left_schema = left.schema();
+        let right_schema = right.schema();
+        let on = on
+            .into_iter()
+            .map(|(l, r)| {
+                find_valid_equijoin_key_pair(&l, &r, left_schema, right_schema)?
+                    .ok_or_else(|| {
+                        plan_datafusion_err!(
+                            "ASOF join key {l} = {r} must compare a column of each input"
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let (match_left, op, match_right) = match match_condition {
+            Expr::BinaryExpr(BinaryExpr { left, op, right }) => (left, op, right),
+            other => {
+                return plan_err!(
+                    "ASOF join match condition must be a comparison, got {other}"
+                );
+            }
+        };
+        if !matches!(
+            op,
+            Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq
+        ) {
+            return plan_err!(
+                "ASOF join match condition must use <, <=, > or >=, got {op}"
+            );
+        }
+        let match_condition = match find_valid_equijoin_key_pair(
+            &match_left,
+            &match_right,
+            left_schema,
+            right_schema,
+        )? {
+            Some((l, r)) if l == *match_left => AsOfMatch {
+                left: l,
+                op,
+                right: r,
+            },
+            Some((l, r)) => AsOfMatch {
+                left: l,
+                // swapping an ordered comparison always succeeds
+                op: op.swap().unwrap_or(op),
+                right: r,
+            },
+            None => {
+                return plan_err!(
+                    "ASOF join match condition {match_left} {op} {match_right} must compare a column of each input"
+                );
+            }
+        };
+
+        let schema = build_join_schema(left_schema, right_schema, &JoinType::Left)?;
+        Ok(Self {
+            left,
+            right,
+            on,
+            match_condition: Box::new(match_condition),
+            schema: Arc::new(schema),
+        })
+    }
+}
+
+// Manual implementation needed because of `schema` field. Comparison excludes this field.
+impl PartialOrd for AsOfJoin {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        #[derive(PartialEq, PartialOrd)]
+        struct ComparableAsOfJoin<'a> {
+            /// Left input
+            pub left: &'a Arc<LogicalPlan>,
+            /// Right input
+            pub right: &'a Arc<LogicalPlan>,
+            /// Equijoin clause expressed as pairs of (left, right) join expressions
+            pub on: &'a Vec<(Expr, Expr)>,
+            /// The ordered comparison selecting the closest right row
+            pub match_condition: &'a AsOfMatch,
+        }
+        let comparable_self = ComparableAsOfJoin {
+            left: &self.left,
+            right: &self.right,
+            on: &self.on,
+            match_condition: &self.match_condition,
+        };
+        let comparable_other = ComparableAsOfJoin {
+            left: &other.left,
+            right: &other.right,
+            on: &other.on,
+            match_condition: &other.match_condition,
+        };
+        comparable_self
+            .partial_cmp(&comparable_other)
+            .filter(|cmp| *cmp != Ordering::Equal || self == other)
+    }
+}
+
 /// Subquery
 #[derive(Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub struct Subquery {
@@ -41,11 +41,12 @@ use std::sync::Arc;
 
 use crate::logical_plan::plan::RangePartitioning;
 use crate::{
-    Aggregate, Analyze, CreateMemoryTable, CreateView, DdlStatement, Distinct,
-    DistinctOn, DmlStatement, Execute, Explain, Expr, Extension, Filter, Join, Limit,
-    LogicalPlan, Partitioning, Prepare, Projection, RecursiveQuery, Repartition, Sort,
-    Statement, Subquery, SubqueryAlias, TableScan, Union, Unnest, UserDefinedLogicalNode,
-    Values, Window, WriteOp, builder::unnest_with_options, dml::CopyTo,
+    Aggregate, Analyze, AsOfJoin, AsOfMatch, CreateMaterializedView, CreateMemoryTable,
+    CreateView, DdlStatement, Distinct, DistinctOn, DmlStatement, Execute, Explain, Expr,
+    Extension, Filter, Join, Limit, LogicalPlan, Partitioning, Prepare, Projection,
+    RecursiveQuery, Repartition, Sort, Statement, Subquery, SubqueryAlias, TableScan,
+    Union, Unnest, UserDefinedLogicalNode, Values, Window, WriteOp,
+    builder::unnest_with_options, dml::CopyTo,
 };
 use datafusion_common::tree_node::TreeNodeRefContainer;
 
@@ -150,6 +151,21 @@ impl TreeNode for LogicalPlan {
                     null_aware,
                 })
             }),
+            LogicalPlan::AsOfJoin(AsOfJoin {
+                left,
+                right,
+                on,
+                match_condition,
+                schema,
+            }) => (left, right).map_elements(f)?.update_data(|(left, right)| {
+                LogicalPlan::AsOfJoin(AsOfJoin {
+                    left,
+                    right,
+                    on,
+                    match_condition,
+                    schema,
+                })
+            }),
             LogicalPlan::Limit(Limit { skip, fetch, input }) => input
                 .map_elements(f)?
                 .update_data(|input| LogicalPlan::Limit(Limit { skip, fetch, input })),
@@ -303,6 +319,27 @@ impl TreeNode for LogicalPlan {
                             temporary,
                         })
                     }),
+                    DdlStatement::CreateMaterializedView(CreateMaterializedView {
+                        name,
+                        input,
+                        if_not_exists,
+                        or_replace,
+                        location,
+                        file_type,
+                        options,
+                        definition,
+                    }) => input.map_elements(f)?.update_data(|input| {
+                        DdlStatement::CreateMaterializedView(CreateMaterializedView {
+                            name,
+                            input,
+                            if_not_exists,
+                            or_replace,
+                            location,
+                            file_type,
+                            options,
+                            definition,
+                        })
+                    }),
                     // no inputs in these statements
                     DdlStatement::CreateExternalTable(_)
                     | DdlStatement::CreateCatalogSchema(_)
@@ -312,7 +349,10 @@ impl TreeNode for LogicalPlan {
                     | DdlStatement::DropView(_)
                     | DdlStatement::DropCatalogSchema(_)
                     | DdlStatement::CreateFunction(_)
-                    | DdlStatement::DropFunction(_) => Transformed::no(ddl),
+                    | DdlStatement::DropFunction(_)
+                    | DdlStatement::AnalyzeTable(_)
+                    | DdlStatement::AlterTable(_)
+                    | DdlStatement::RefreshMaterializedView(_) => Transformed::no(ddl),
                 }
                 .update_data(LogicalPlan::Ddl)
             }
@@ -447,6 +487,15 @@ impl LogicalPlan {
             LogicalPlan::Join(Join { on, filter, .. }) => {
                 (on, filter).apply_ref_elements(f)
             }
+            // The equijoin expressions, followed by the two sides of the match
+            // condition.
+            LogicalPlan::AsOfJoin(AsOfJoin {
+                on,
+                match_condition,
+                ..
+            }) => {
+                (on, &match_condition.left, &match_condition.right).apply_ref_elements(f)
+            }
             LogicalPlan::Sort(Sort { expr, .. }) => expr.apply_elements(f),
             LogicalPlan::Extension(extension) => {
                 // would be nice to avoid this copy -- maybe can
@@ -614,6 +663,32 @@ impl LogicalPlan {
                     null_aware,
                 })
             }),
+            LogicalPlan::AsOfJoin(AsOfJoin {
+                left,
+                right,
+                on,
+                match_condition,
+                schema,
+            }) => {
+                let AsOfMatch {
+                    left: l,
+                    op,
+                    right: r,
+                } = *match_condition;
+                (on, l, r).map_elements(f)?.update_data(|(on, l, r)| {
+                    LogicalPlan::AsOfJoin(AsOfJoin {
+                        left,
+                        right,
+                        on,
+                        match_condition: Box::new(AsOfMatch {
+                            left: l,
+                            op,
+                            right: r,
+                        }),
+                        schema,
+                    })
+                })
+            }
             LogicalPlan::Sort(Sort { expr, input, fetch }) => expr
                 .map_elements(f)?
                 .update_data(|expr| LogicalPlan::Sort(Sort { expr, input, fetch })),
@@ -279,6 +279,22 @@ pub trait ExprPlanner: Debug + Send + Sync {
     fn plan_window(&self, expr: RawWindowExpr) -> Result<PlannerResult<RawWindowExpr>> {
         Ok(PlannerResult::Original(expr))
     }
+
+    /// Plans generator functions, such as Spark's `explode(<expr>)`, used as a
+    /// top-level `SELECT` item or in a `LATERAL VIEW`
+    ///
+    /// A generator produces zero or more rows for each input row. A planned
+    /// generator must be an [`Expr::Unnest`] of a list of structs: every
+    /// field of the struct becomes an output column named after the field.
+    ///
+    /// Returns original expression if not possible
+    fn plan_generator(
+        &self,
+        expr: Expr,
+        _schema: &DFSchema,
+    ) -> Result<PlannerResult<Expr>> {
+        Ok(PlannerResult::Original(expr))
+    }
 }
 
 /// An operator with two arguments to plan
@@ -0,0 +1,319 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! FFI support for [`AnalyzerRule`]s.
+//!
+//! Plans cross the boundary serialized with the rule's logical extension
+//! codec, so every node of a plan passed to a foreign rule, including its
+//! table scans, must be supported by that codec.
+
+use std::ffi::c_void;
+use std::sync::Arc;
+
+use datafusion_common::Result;
+use datafusion_common::config::ConfigOptions;
+use datafusion_execution::TaskContext;
+use datafusion_expr::LogicalPlan;
+use datafusion_optimizer::AnalyzerRule;
+use datafusion_proto::bytes::{
+    logical_plan_from_bytes_with_extension_codec,
+    logical_plan_to_bytes_with_extension_codec,
+};
+use datafusion_proto::logical_plan::LogicalExtensionCodec;
+use stabby::string::String as SString;
+use stabby::vec::Vec as SVec;
+use tokio::runtime::Handle;
+
+use crate::config::FFI_ConfigOptions;
+use crate::execution::FFI_TaskContextProvider;
+use crate::proto::logical_extension_codec::FFI_LogicalExtensionCodec;
+use crate::util::FFI_Result;
+use crate::{df_result, sresult_return};
+
+/// A stable struct for sharing an [`AnalyzerRule`] across FFI boundaries.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_AnalyzerRule {
+    analyze: unsafe extern "C" fn(
+        &Self,
+        plan_serialized: SVec<u8>,
+        config: FFI_ConfigOptions,
+    ) -> FFI_Result<SVec<u8>>,
+
+    pub name: unsafe extern "C" fn(&Self) -> SString,
+
+    /// Codec used to encode and decode the plans passed to the rule.
+    pub logical_codec: FFI_LogicalExtensionCodec,
+
+    /// Used to create a clone on the rule. This should
+    /// only need to be called by the receiver of the rule.
+    pub clone: unsafe extern "C" fn(rule: &Self) -> Self,
+
+    /// Release the memory of the private data when it is no longer being used.
+    pub release: unsafe extern "C" fn(arg: &mut Self),
+
+    /// Return the major DataFusion version number of this rule.
+    pub version: unsafe extern "C" fn() -> u64,
+
+    /// Internal data. This is only to be accessed by the provider of the rule.
+    /// A [`ForeignAnalyzerRule`] should never attempt to access this data.
+    pub private_data: *mut c_void,
+
+    /// Utility to identify when FFI objects are accessed locally through
+    /// the foreign interface. See [`crate::get_library_marker_id`] and
+    /// the crate's `README.md` for more information.
+    pub library_marker_id: extern "C" fn() -> usize,
+}
+
+unsafe impl Send for FFI_AnalyzerRule {}
+unsafe impl Sync for FFI_AnalyzerRule {}
+
+struct AnalyzerRulePrivateData {
+    rule: Arc<dyn AnalyzerRule + Send + Sync>,
+    runtime: Option<Handle>,
+}
+
+impl FFI_AnalyzerRule {
+    fn inner(&self) -> &Arc<dyn AnalyzerRule + Send + Sync> {
+        let private_data = self.private_data as *const AnalyzerRulePrivateData;
+        unsafe { &(*private_data).rule }
+    }
+
+    fn runtime(&self) -> Option<Handle> {
+        let private_data = self.private_data as *const AnalyzerRulePrivateData;
+        unsafe { (*private_data).runtime.clone() }
+    }
+}
+
+unsafe extern "C" fn analyze_fn_wrapper(
+    rule: &FFI_AnalyzerRule,
+    plan_serialized: SVec<u8>,
+    config: FFI_ConfigOptions,
+) -> FFI_Result<SVec<u8>> {
+    let task_ctx: Arc<TaskContext> =
+        sresult_return!((&rule.logical_codec.task_ctx_provider).try_into());
+    let codec: Arc<dyn LogicalExtensionCodec> = (&rule.logical_codec).into();
+
+    let plan = sresult_return!(logical_plan_from_bytes_with_extension_codec(
+        plan_serialized.as_slice(),
+        task_ctx.as_ref(),
+        codec.as_ref(),
+    ));
+    let config = sresult_return!(ConfigOptions::try_from(config));
+
+    let plan = sresult_return!(rule.inner().analyze(plan, &config));
+    let plan = sresult_return!(logical_plan_to_bytes_with_extension_codec(
+        &plan,
+        codec.as_ref()
+    ));
+
+    FFI_Result::Ok(SVec::from(plan.as_ref()))
+}
+
+unsafe extern "C" fn name_fn_wrapper(rule: &FFI_AnalyzerRule) -> SString {
+    rule.inner().name().into()
+}
+
+unsafe extern "C" fn release_fn_wrapper(rule: &mut FFI_AnalyzerRule) {
+    unsafe {
+        debug_assert!(!rule.private_data.is_null());
+        let private_data =
+            Box::from_raw(rule.private_data as *mut AnalyzerRulePrivateData);
+        drop(private_data);
+        rule.private_data = std::ptr::null_mut();
+    }
+}
+
+unsafe extern "C" fn clone_fn_wrapper(rule: &FFI_AnalyzerRule) -> FFI_AnalyzerRule {
+    FFI_AnalyzerRule::new_with_ffi_codec(
+        Arc::clone(rule.inner()),
+        rule.runtime(),
+        rule.logical_codec.clone(),
+    )
+}
+
+impl Drop for FFI_AnalyzerRule {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+impl Clone for FFI_AnalyzerRule {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+impl FFI_AnalyzerRule {
+    /// Creates a new [`FFI_AnalyzerRule`] with a native logical extension
+    /// codec, used to pass plans to the rule.
+    pub fn new(
+        rule: Arc<dyn AnalyzerRule + Send + Sync>,
+        runtime: Option<Handle>,
+        task_ctx_provider: impl Into<FFI_TaskContextProvider>,
+        logical_codec: Arc<dyn LogicalExtensionCodec>,
+    ) -> Self {
+        let logical_codec = FFI_LogicalExtensionCodec::new(
+            logical_codec,
+            runtime.clone(),
+            task_ctx_provider.into(),
+        );
+        Self::new_with_ffi_codec(rule, runtime, logical_codec)
+    }
+
+    /// Creates a new [`FFI_AnalyzerRule`] using a prebuilt FFI extension codec.
+    pub fn new_with_ffi_codec(
+        rule: Arc<dyn AnalyzerRule + Send + Sync>,
+        runtime: Option<Handle>,
+        logical_codec: FFI_LogicalExtensionCodec,
+    ) -> Self {
+        let private_data = Box::new(AnalyzerRulePrivateData { rule, runtime });
+        let private_data = Box::into_raw(private_data) as *mut c_void;
+
+        Self {
+            analyze: analyze_fn_wrapper,
+            name: name_fn_wrapper,
+            logical_codec,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            version: super::version,
+            private_data,
+            library_marker_id: crate::get_library_marker_id,
+        }
+    }
+}
+
+/// This wrapper struct exists on the receiver side of the FFI interface, so it has
+/// no guarantees about being able to access the data in `private_data`. Any functions
+/// defined on this struct must only use the stable functions provided in
+/// FFI_AnalyzerRule to interact with the foreign rule.
+#[derive(Debug)]
+pub struct ForeignAnalyzerRule {
+    name: String,
+    rule: FFI_AnalyzerRule,
+}
+
+unsafe impl Send for ForeignAnalyzerRule {}
+unsafe impl Sync for ForeignAnalyzerRule {}
+
+impl From<&FFI_AnalyzerRule> for Arc<dyn AnalyzerRule + Send + Sync> {
+    fn from(rule: &FFI_AnalyzerRule) -> Self {
+        if (rule.library_marker_id)() == crate::get_library_marker_id() {
+            return Arc::clone(rule.inner());
+        }
+
+        let name = unsafe { (rule.name)(rule).to_string() };
+        Arc::new(ForeignAnalyzerRule {
+            name,
+            rule: rule.clone(),
+        })
+    }
+}
+
+impl AnalyzerRule for ForeignAnalyzerRule {
+    fn analyze(&self, plan: LogicalPlan, config: &ConfigOptions) -> Result<LogicalPlan> {
+        let codec: Arc<dyn LogicalExtensionCodec> = (&self.rule.logical_codec).into();
+        let plan_serialized =
+            logical_plan_to_bytes_with_extension_codec(&plan, codec.as_ref())?;
+
+        let plan_serialized = unsafe {
+            df_result!((self.rule.analyze)(
+                &self.rule,
+                SVec::from(plan_serialized.as_ref()),
+                config.into(),
+            ))?
+        };
+
+        let task_ctx: Arc<TaskContext> =
+            (&self.rule.logical_codec.task_ctx_provider).try_into()?;
+        logical_plan_from_bytes_with_extension_codec(
+            plan_serialized.as_slice(),
+            task_ctx.as_ref(),
+            codec.as_ref(),
+        )
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
+    use datafusion_expr::{LogicalPlanBuilder, lit};
+    use datafusion_proto::logical_plan::DefaultLogicalExtensionCodec;
+
+    use super::*;
+
+    /// Removes every `LIMIT` when the `datafusion.optimizer.skip_failed_rules`
+    /// option is set, to check the options reach the rule.
+    #[derive(Debug)]
+    struct RemoveLimits;
+
+    impl AnalyzerRule for RemoveLimits {
+        fn analyze(
+            &self,
+            plan: LogicalPlan,
+            config: &ConfigOptions,
+        ) -> Result<LogicalPlan> {
+            if !config.optimizer.skip_failed_rules {
+                return Ok(plan);
+            }
+            plan.transform_up(|plan| match plan {
+                LogicalPlan::Limit(limit) => {
+                    Ok(Transformed::yes(Arc::unwrap_or_clone(limit.input)))
+                }
+                plan => Ok(Transformed::no(plan)),
+            })
+            .data()
+        }
+
+        fn name(&self) -> &str {
+            "remove_limits"
+        }
+    }
+
+    #[test]
+    fn test_round_trip_ffi_analyzer_rule() -> Result<()> {
+        let (_ctx, task_ctx_provider) = crate::util::tests::test_session_and_ctx();
+        let mut ffi_rule = FFI_AnalyzerRule::new(
+            Arc::new(RemoveLimits),
+            None,
+            task_ctx_provider,
+            Arc::new(DefaultLogicalExtensionCodec {}),
+        );
+        ffi_rule.library_marker_id = crate::mock_foreign_marker_id;
+
+        let foreign_rule: Arc<dyn AnalyzerRule + Send + Sync> = (&ffi_rule).into();
+        assert_eq!(foreign_rule.name(), "remove_limits");
+
+        let values = LogicalPlanBuilder::values(vec![vec![lit(1i64)]])?.build()?;
+        let plan = LogicalPlanBuilder::from(values.clone())
+            .limit(0, Some(1))?
+            .build()?;
+
+        let mut config = ConfigOptions::new();
+        config.optimizer.skip_failed_rules = false;
+        assert_eq!(foreign_rule.analyze(plan.clone(), &config)?, plan);
+
+        config.optimizer.skip_failed_rules = true;
+        assert_eq!(foreign_rule.analyze(plan, &config)?, values);
+
+        Ok(())
+    }
+}
@@ -29,6 +29,7 @@ use datafusion_expr::{
 use stabby::string::String as SString;
 use stabby::vec::Vec as SVec;
 
+use crate::object_store::FFI_ObjectStoreRegistry;
 use crate::session::config::FFI_SessionConfig;
 use crate::udaf::FFI_AggregateUDF;
 use crate::udf::FFI_ScalarUDF;
@@ -58,6 +59,10 @@ pub struct FFI_TaskContext {
     /// Returns a vec of name-function pairs for window functions.
     pub window_functions: unsafe extern "C" fn(&Self) -> SVec<(SString, FFI_WindowUDF)>,
 
+    /// Return the object store registry of the runtime environment, so that
+    /// plans executed by the foreign library can read from the same stores.
+    pub object_store_registry: unsafe extern "C" fn(&Self) -> FFI_ObjectStoreRegistry,
+
     /// Release the memory of the private data when it is no longer being used.
     pub release: unsafe extern "C" fn(arg: &mut Self),
 
@@ -150,6 +155,18 @@ unsafe extern "C" fn window_functions_fn_wrapper(
     }
 }
 
+unsafe extern "C" fn object_store_registry_fn_wrapper(
+    ctx: &FFI_TaskContext,
+) -> FFI_ObjectStoreRegistry {
+    unsafe {
+        let ctx = ctx.inner();
+        FFI_ObjectStoreRegistry::new(
+            Arc::clone(&ctx.runtime_env().object_store_registry),
+            None,
+        )
+    }
+}
+
 unsafe extern "C" fn release_fn_wrapper(ctx: &mut FFI_TaskContext) {
     unsafe {
         let private_data = Box::from_raw(ctx.private_data as *mut TaskContextPrivateData);
@@ -174,6 +191,7 @@ impl From<Arc<TaskContext>> for FFI_TaskContext {
             scalar_functions: scalar_functions_fn_wrapper,
             aggregate_functions: aggregate_functions_fn_wrapper,
             window_functions: window_functions_fn_wrapper,
+            object_store_registry: object_store_registry_fn_wrapper,
             release: release_fn_wrapper,
             private_data: Box::into_raw(private_data) as *mut c_void,
             library_marker_id: crate::get_library_marker_id,
@@ -228,7 +246,11 @@ impl From<FFI_TaskContext> for Arc<TaskContext> {
                 })
                 .collect();
 
-            let runtime = Arc::new(RuntimeEnv::default());
+            let object_store_registry = (ffi_ctx.object_store_registry)(&ffi_ctx);
+            let runtime = Arc::new(RuntimeEnv {
+                object_store_registry: (&object_store_registry).into(),
+                ..Default::default()
+            });
 
             Arc::new(TaskContext::new(
                 task_id,
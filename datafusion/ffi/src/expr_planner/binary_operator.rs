@@ -0,0 +1,153 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use sqlparser::ast::BinaryOperator;
+use stabby::string::String as SString;
+use stabby::vec::Vec as SVec;
+
+macro_rules! ffi_binary_operator {
+    ($($variant:ident),+ $(,)?) => {
+        /// FFI safe version of the SQL AST [`BinaryOperator`].
+        #[repr(C, u8)]
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum FFI_BinaryOperator {
+            $($variant,)+
+            Custom(SString),
+            PGCustomBinaryOperator(SVec<SString>),
+        }
+
+        impl From<&BinaryOperator> for FFI_BinaryOperator {
+            fn from(op: &BinaryOperator) -> Self {
+                match op {
+                    $(BinaryOperator::$variant => Self::$variant,)+
+                    BinaryOperator::Custom(op) => Self::Custom(op.as_str().into()),
+                    BinaryOperator::PGCustomBinaryOperator(parts) => {
+                        Self::PGCustomBinaryOperator(
+                            parts.iter().map(|part| part.as_str().into()).collect(),
+                        )
+                    }
+                }
+            }
+        }
+
+        impl From<&FFI_BinaryOperator> for BinaryOperator {
+            fn from(op: &FFI_BinaryOperator) -> Self {
+                match op {
+                    $(FFI_BinaryOperator::$variant => Self::$variant,)+
+                    FFI_BinaryOperator::Custom(op) => Self::Custom(op.to_string()),
+                    FFI_BinaryOperator::PGCustomBinaryOperator(parts) => {
+                        Self::PGCustomBinaryOperator(
+                            parts.iter().map(|part| part.to_string()).collect(),
+                        )
+                    }
+                }
+            }
+        }
+    };
+}
+
+ffi_binary_operator!(
+    Plus,
+    Minus,
+    Multiply,
+    Divide,
+    Modulo,
+    StringConcat,
+    Gt,
+    Lt,
+    GtEq,
+    LtEq,
+    Spaceship,
+    Eq,
+    NotEq,
+    And,
+    Or,
+    Xor,
+    BitwiseOr,
+    BitwiseAnd,
+    BitwiseXor,
+    DuckIntegerDivide,
+    MyIntegerDivide,
+    Match,
+    Regexp,
+    PGBitwiseXor,
+    PGBitwiseShiftLeft,
+    PGBitwiseShiftRight,
+    PGExp,
+    PGOverlap,
+    PGRegexMatch,
+    PGRegexIMatch,
+    PGRegexNotMatch,
+    PGRegexNotIMatch,
+    PGLikeMatch,
+    PGILikeMatch,
+    PGNotLikeMatch,
+    PGNotILikeMatch,
+    PGStartsWith,
+    Arrow,
+    LongArrow,
+    HashArrow,
+    HashLongArrow,
+    AtAt,
+    AtArrow,
+    ArrowAt,
+    HashMinus,
+    AtQuestion,
+    Question,
+    QuestionAnd,
+    QuestionPipe,
+    Overlaps,
+    DoubleHash,
+    LtDashGt,
+    AndLt,
+    AndGt,
+    LtLtPipe,
+    PipeGtGt,
+    AndLtPipe,
+    PipeAndGt,
+    LtCaret,
+    GtCaret,
+    QuestionHash,
+    QuestionDash,
+    QuestionDashPipe,
+    QuestionDoublePipe,
+    At,
+    TildeEq,
+    Assignment,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_binary_operator() {
+        for op in [
+            BinaryOperator::Plus,
+            BinaryOperator::LongArrow,
+            BinaryOperator::Assignment,
+            BinaryOperator::Custom("~~~".to_string()),
+            BinaryOperator::PGCustomBinaryOperator(vec![
+                "schema".to_string(),
+                "+".to_string(),
+            ]),
+        ] {
+            let ffi_op: FFI_BinaryOperator = (&op).into();
+            assert_eq!(BinaryOperator::from(&ffi_op), op);
+        }
+    }
+}
@@ -0,0 +1,1125 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! FFI support for SQL [`ExprPlanner`]s.
+//!
+//! Expressions cross the boundary serialized with the planner's logical
+//! extension codec, and schemas as prost-encoded
+//! `datafusion_proto_common::DfSchema` bytes so that column qualifiers are
+//! preserved. Since every binary operator of a query is offered to each
+//! registered planner, keep the number of foreign planners small.
+
+use std::ffi::c_void;
+use std::sync::Arc;
+
+use arrow::datatypes::Field;
+use arrow::ffi::FFI_ArrowSchema;
+use datafusion_common::{DFSchema, Result, TableReference, internal_err};
+use datafusion_execution::TaskContext;
+use datafusion_expr::expr::{
+    AggregateFunction, AggregateFunctionParams, WindowFunction, WindowFunctionParams,
+};
+use datafusion_expr::planner::{
+    ExprPlanner, PlannerResult, RawAggregateExpr, RawBinaryExpr, RawDictionaryExpr,
+    RawFieldAccessExpr, RawWindowExpr,
+};
+use datafusion_expr::{Expr, GetFieldAccess, lit};
+use datafusion_proto::bytes::{
+    logical_exprs_from_bytes_with_extension_codec,
+    logical_exprs_to_bytes_with_extension_codec,
+};
+use datafusion_proto::logical_plan::LogicalExtensionCodec;
+use prost::Message;
+use stabby::string::String as SString;
+use stabby::vec::Vec as SVec;
+use tokio::runtime::Handle;
+
+use crate::arrow_wrappers::WrappedSchema;
+use crate::execution::FFI_TaskContextProvider;
+use crate::proto::logical_extension_codec::FFI_LogicalExtensionCodec;
+use crate::util::{FFI_Option, FFI_Result};
+use crate::{df_result, sresult_return};
+
+mod binary_operator;
+
+pub use binary_operator::FFI_BinaryOperator;
+
+/// The kind of [`GetFieldAccess`] passed to a foreign planner. The accessed
+/// expression is always sent first, followed by the name of the field for
+/// [`GetFieldAccess::NamedStructField`] as a literal, the key for
+/// [`GetFieldAccess::ListIndex`], or the start, stop and stride for
+/// [`GetFieldAccess::ListRange`].
+#[expect(non_camel_case_types)]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FFI_FieldAccessKind {
+    NamedStructField,
+    ListIndex,
+    ListRange,
+}
+
+/// Planned expression returned by the foreign planner, or `None` when the
+/// planner returned [`PlannerResult::Original`].
+#[expect(non_camel_case_types)]
+type FFI_PlannedExpr = FFI_Result<FFI_Option<SVec<u8>>>;
+
+/// A stable struct for sharing an [`ExprPlanner`] across FFI boundaries.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_ExprPlanner {
+    plan_binary_op: unsafe extern "C" fn(
+        &Self,
+        op: FFI_BinaryOperator,
+        exprs_serialized: SVec<u8>,
+        schema_serialized: SVec<u8>,
+    ) -> FFI_PlannedExpr,
+
+    plan_field_access: unsafe extern "C" fn(
+        &Self,
+        kind: FFI_FieldAccessKind,
+        exprs_serialized: SVec<u8>,
+        schema_serialized: SVec<u8>,
+    ) -> FFI_PlannedExpr,
+
+    plan_array_literal: unsafe extern "C" fn(
+        &Self,
+        exprs_serialized: SVec<u8>,
+        schema_serialized: SVec<u8>,
+    ) -> FFI_PlannedExpr,
+
+    plan_position:
+        unsafe extern "C" fn(&Self, args_serialized: SVec<u8>) -> FFI_PlannedExpr,
+
+    /// The keys of the dictionary are sent first, followed by its values.
+    plan_dictionary_literal: unsafe extern "C" fn(
+        &Self,
+        exprs_serialized: SVec<u8>,
+        schema_serialized: SVec<u8>,
+    ) -> FFI_PlannedExpr,
+
+    plan_extract:
+        unsafe extern "C" fn(&Self, args_serialized: SVec<u8>) -> FFI_PlannedExpr,
+
+    plan_substring:
+        unsafe extern "C" fn(&Self, args_serialized: SVec<u8>) -> FFI_PlannedExpr,
+
+    plan_struct_literal: unsafe extern "C" fn(
+        &Self,
+        args_serialized: SVec<u8>,
+        is_named_struct: bool,
+    ) -> FFI_PlannedExpr,
+
+    plan_overlay:
+        unsafe extern "C" fn(&Self, args_serialized: SVec<u8>) -> FFI_PlannedExpr,
+
+    plan_make_map:
+        unsafe extern "C" fn(&Self, args_serialized: SVec<u8>) -> FFI_PlannedExpr,
+
+    plan_compound_identifier: unsafe extern "C" fn(
+        &Self,
+        field: WrappedSchema,
+        qualifier: FFI_Option<SVec<SString>>,
+        nested_names: SVec<SString>,
+    ) -> FFI_PlannedExpr,
+
+    /// The aggregate is sent as a single [`Expr::AggregateFunction`].
+    plan_aggregate:
+        unsafe extern "C" fn(&Self, expr_serialized: SVec<u8>) -> FFI_PlannedExpr,
+
+    /// The window function is sent as a single [`Expr::WindowFunction`].
+    plan_window:
+        unsafe extern "C" fn(&Self, expr_serialized: SVec<u8>) -> FFI_PlannedExpr,
+
+    plan_generator: unsafe extern "C" fn(
+        &Self,
+        expr_serialized: SVec<u8>,
+        schema_serialized: SVec<u8>,
+    ) -> FFI_PlannedExpr,
+
+    /// Codec used to encode and decode the expressions passed to the planner.
+    pub logical_codec: FFI_LogicalExtensionCodec,
+
+    /// Used to create a clone on the planner. This should
+    /// only need to be called by the receiver of the planner.
+    pub clone: unsafe extern "C" fn(planner: &Self) -> Self,
+
+    /// Release the memory of the private data when it is no longer being used.
+    pub release: unsafe extern "C" fn(arg: &mut Self),
+
+    /// Return the major DataFusion version number of this planner.
+    pub version: unsafe extern "C" fn() -> u64,
+
+    /// Internal data. This is only to be accessed by the provider of the planner.
+    /// A [`ForeignExprPlanner`] should never attempt to access this data.
+    pub private_data: *mut c_void,
+
+    /// Utility to identify when FFI objects are accessed locally through
+    /// the foreign interface. See [`crate::get_library_marker_id`] and
+    /// the crate's `README.md` for more information.
+    pub library_marker_id: extern "C" fn() -> usize,
+}
+
+unsafe impl Send for FFI_ExprPlanner {}
+unsafe impl Sync for FFI_ExprPlanner {}
+
+struct ExprPlannerPrivateData {
+    planner: Arc<dyn ExprPlanner>,
+    runtime: Option<Handle>,
+}
+
+impl FFI_ExprPlanner {
+    fn inner(&self) -> &Arc<dyn ExprPlanner> {
+        let private_data = self.private_data as *const ExprPlannerPrivateData;
+        unsafe { &(*private_data).planner }
+    }
+
+    fn runtime(&self) -> Option<Handle> {
+        let private_data = self.private_data as *const ExprPlannerPrivateData;
+        unsafe { (*private_data).runtime.clone() }
+    }
+}
+
+/// Serialize expressions with the FFI logical extension codec.
+pub(crate) fn exprs_to_ffi<'a>(
+    exprs: impl IntoIterator<Item = &'a Expr>,
+    codec: &FFI_LogicalExtensionCodec,
+) -> Result<SVec<u8>> {
+    let codec: Arc<dyn LogicalExtensionCodec> = codec.into();
+    let bytes = logical_exprs_to_bytes_with_extension_codec(exprs, codec.as_ref())?;
+    Ok(SVec::from(bytes.as_ref()))
+}
+
+/// Deserialize expressions with the FFI logical extension codec, resolving
+/// functions against the codec's task context.
+pub(crate) fn exprs_from_ffi(
+    exprs_serialized: &SVec<u8>,
+    codec: &FFI_LogicalExtensionCodec,
+) -> Result<Vec<Expr>> {
+    let task_ctx: Arc<TaskContext> = (&codec.task_ctx_provider).try_into()?;
+    let codec: Arc<dyn LogicalExtensionCodec> = codec.into();
+    logical_exprs_from_bytes_with_extension_codec(
+        exprs_serialized.as_slice(),
+        task_ctx.as_ref(),
+        codec.as_ref(),
+    )
+}
+
+/// Serialize a [`DFSchema`] to prost-encoded `datafusion_proto_common::DfSchema`
+/// bytes.
+pub(crate) fn df_schema_to_ffi(schema: &DFSchema) -> Result<SVec<u8>> {
+    let schema = datafusion_proto_common::DfSchema::try_from(schema)?;
+    Ok(SVec::from(schema.encode_to_vec().as_slice()))
+}
+
+/// Decode prost-encoded `datafusion_proto_common::DfSchema` bytes back into
+/// a [`DFSchema`].
+pub(crate) fn df_schema_from_ffi(schema_serialized: &SVec<u8>) -> Result<DFSchema> {
+    let schema = datafusion_proto_common::DfSchema::decode(schema_serialized.as_slice())
+        .map_err(|e| {
+            datafusion_common::DataFusionError::Plan(format!(
+                "failed to decode DFSchema: {e}"
+            ))
+        })?;
+    Ok(DFSchema::try_from(&schema)?)
+}
+
+/// Convert a [`TableReference`] into its parts, from the outermost to the table.
+pub(crate) fn table_reference_to_ffi(reference: &TableReference) -> SVec<SString> {
+    match reference {
+        TableReference::Bare { table } => vec![table.as_ref()],
+        TableReference::Partial { schema, table } => {
+            vec![schema.as_ref(), table.as_ref()]
+        }
+        TableReference::Full {
+            catalog,
+            schema,
+            table,
+        } => vec![catalog.as_ref(), schema.as_ref(), table.as_ref()],
+    }
+    .into_iter()
+    .map(SString::from)
+    .collect()
+}
+
+/// Build a [`TableReference`] from the parts produced by
+/// [`table_reference_to_ffi`].
+pub(crate) fn table_reference_from_ffi(parts: &SVec<SString>) -> Result<TableReference> {
+    Ok(match parts.as_slice() {
+        [table] => TableReference::bare(table.as_str()),
+        [schema, table] => TableReference::partial(schema.as_str(), table.as_str()),
+        [catalog, schema, table] => {
+            TableReference::full(catalog.as_str(), schema.as_str(), table.as_str())
+        }
+        _ => return internal_err!("Invalid table reference with {} parts", parts.len()),
+    })
+}
+
+fn exprs_into_array<const N: usize>(exprs: Vec<Expr>) -> Result<[Expr; N]> {
+    let len = exprs.len();
+    match exprs.try_into() {
+        Ok(exprs) => Ok(exprs),
+        Err(_) => internal_err!("Expected {N} expressions but received {len}"),
+    }
+}
+
+fn planned<T>(result: PlannerResult<T>) -> Option<Expr> {
+    match result {
+        PlannerResult::Planned(expr) => Some(expr),
+        PlannerResult::Original(_) => None,
+    }
+}
+
+/// Decode the expressions sent by a [`ForeignExprPlanner`], plan them and
+/// encode the planned expression, if any.
+fn plan_exprs(
+    planner: &FFI_ExprPlanner,
+    exprs_serialized: &SVec<u8>,
+    plan: impl FnOnce(&dyn ExprPlanner, Vec<Expr>) -> Result<Option<Expr>>,
+) -> FFI_PlannedExpr {
+    let exprs = sresult_return!(exprs_from_ffi(exprs_serialized, &planner.logical_codec));
+    let planned = sresult_return!(plan(planner.inner().as_ref(), exprs))
+        .map(|expr| exprs_to_ffi([&expr], &planner.logical_codec));
+
+    FFI_Result::Ok(sresult_return!(planned.transpose()).into())
+}
+
+unsafe extern "C" fn plan_binary_op_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    op: FFI_BinaryOperator,
+    exprs_serialized: SVec<u8>,
+    schema_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &exprs_serialized, |planner, exprs| {
+        let schema = df_schema_from_ffi(&schema_serialized)?;
+        let [left, right] = exprs_into_array(exprs)?;
+        let expr = RawBinaryExpr {
+            op: (&op).into(),
+            left,
+            right,
+        };
+        Ok(planned(planner.plan_binary_op(expr, &schema)?))
+    })
+}
+
+unsafe extern "C" fn plan_field_access_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    kind: FFI_FieldAccessKind,
+    exprs_serialized: SVec<u8>,
+    schema_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &exprs_serialized, |planner, exprs| {
+        let schema = df_schema_from_ffi(&schema_serialized)?;
+        let expr = match kind {
+            FFI_FieldAccessKind::NamedStructField => {
+                let [expr, name] = exprs_into_array(exprs)?;
+                let Expr::Literal(name, _) = name else {
+                    return internal_err!("Expected a literal field name, found {name}");
+                };
+                RawFieldAccessExpr {
+                    field_access: GetFieldAccess::NamedStructField { name },
+                    expr,
+                }
+            }
+            FFI_FieldAccessKind::ListIndex => {
+                let [expr, key] = exprs_into_array(exprs)?;
+                RawFieldAccessExpr {
+                    field_access: GetFieldAccess::ListIndex { key: Box::new(key) },
+                    expr,
+                }
+            }
+            FFI_FieldAccessKind::ListRange => {
+                let [expr, start, stop, stride] = exprs_into_array(exprs)?;
+                RawFieldAccessExpr {
+                    field_access: GetFieldAccess::ListRange {
+                        start: Box::new(start),
+                        stop: Box::new(stop),
+                        stride: Box::new(stride),
+                    },
+                    expr,
+                }
+            }
+        };
+        Ok(planned(planner.plan_field_access(expr, &schema)?))
+    })
+}
+
+unsafe extern "C" fn plan_array_literal_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    exprs_serialized: SVec<u8>,
+    schema_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &exprs_serialized, |planner, exprs| {
+        let schema = df_schema_from_ffi(&schema_serialized)?;
+        Ok(planned(planner.plan_array_literal(exprs, &schema)?))
+    })
+}
+
+unsafe extern "C" fn plan_position_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    args_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &args_serialized, |planner, args| {
+        Ok(planned(planner.plan_position(args)?))
+    })
+}
+
+unsafe extern "C" fn plan_dictionary_literal_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    exprs_serialized: SVec<u8>,
+    schema_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &exprs_serialized, |planner, mut keys| {
+        let schema = df_schema_from_ffi(&schema_serialized)?;
+        if keys.len() % 2 != 0 {
+            return internal_err!("Dictionary literal must have as many keys as values");
+        }
+        let values = keys.split_off(keys.len() / 2);
+        let expr = RawDictionaryExpr { keys, values };
+        Ok(planned(planner.plan_dictionary_literal(expr, &schema)?))
+    })
+}
+
+unsafe extern "C" fn plan_extract_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    args_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &args_serialized, |planner, args| {
+        Ok(planned(planner.plan_extract(args)?))
+    })
+}
+
+unsafe extern "C" fn plan_substring_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    args_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &args_serialized, |planner, args| {
+        Ok(planned(planner.plan_substring(args)?))
+    })
+}
+
+unsafe extern "C" fn plan_struct_literal_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    args_serialized: SVec<u8>,
+    is_named_struct: bool,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &args_serialized, |planner, args| {
+        Ok(planned(planner.plan_struct_literal(args, is_named_struct)?))
+    })
+}
+
+unsafe extern "C" fn plan_overlay_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    args_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &args_serialized, |planner, args| {
+        Ok(planned(planner.plan_overlay(args)?))
+    })
+}
+
+unsafe extern "C" fn plan_make_map_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    args_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &args_serialized, |planner, args| {
+        Ok(planned(planner.plan_make_map(args)?))
+    })
+}
+
+unsafe extern "C" fn plan_compound_identifier_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    field: WrappedSchema,
+    qualifier: FFI_Option<SVec<SString>>,
+    nested_names: SVec<SString>,
+) -> FFI_PlannedExpr {
+    let field = sresult_return!(Field::try_from(&field.0));
+    let qualifier =
+        sresult_return!(qualifier.as_ref().map(table_reference_from_ffi).transpose());
+    let nested_names = nested_names
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<Vec<_>>();
+
+    let result = sresult_return!(planner.inner().plan_compound_identifier(
+        &field,
+        qualifier.as_ref(),
+        &nested_names
+    ));
+    let planned =
+        planned(result).map(|expr| exprs_to_ffi([&expr], &planner.logical_codec));
+
+    FFI_Result::Ok(sresult_return!(planned.transpose()).into())
+}
+
+unsafe extern "C" fn plan_aggregate_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    expr_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &expr_serialized, |planner, exprs| {
+        let [expr] = exprs_into_array(exprs)?;
+        let Expr::AggregateFunction(AggregateFunction {
+            func,
+            params:
+                AggregateFunctionParams {
+                    args,
+                    distinct,
+                    filter,
+                    order_by,
+                    null_treatment,
+                },
+        }) = expr
+        else {
+            return internal_err!("Expected an aggregate function, found {expr}");
+        };
+        let expr = RawAggregateExpr {
+            func,
+            args,
+            distinct,
+            filter,
+            order_by,
+            null_treatment,
+        };
+        Ok(planned(planner.plan_aggregate(expr)?))
+    })
+}
+
+unsafe extern "C" fn plan_window_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    expr_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &expr_serialized, |planner, exprs| {
+        let [expr] = exprs_into_array(exprs)?;
+        let Expr::WindowFunction(window) = expr else {
+            return internal_err!("Expected a window function, found {expr}");
+        };
+        let WindowFunction {
+            fun,
+            params:
+                WindowFunctionParams {
+                    args,
+                    partition_by,
+                    order_by,
+                    window_frame,
+                    filter,
+                    null_treatment,
+                    distinct,
+                },
+        } = *window;
+        let expr = RawWindowExpr {
+            func_def: fun,
+            args,
+            partition_by,
+            order_by,
+            window_frame,
+            filter,
+            null_treatment,
+            distinct,
+        };
+        Ok(planned(planner.plan_window(expr)?))
+    })
+}
+
+unsafe extern "C" fn plan_generator_fn_wrapper(
+    planner: &FFI_ExprPlanner,
+    expr_serialized: SVec<u8>,
+    schema_serialized: SVec<u8>,
+) -> FFI_PlannedExpr {
+    plan_exprs(planner, &expr_serialized, |planner, exprs| {
+        let schema = df_schema_from_ffi(&schema_serialized)?;
+        let [expr] = exprs_into_array(exprs)?;
+        Ok(planned(planner.plan_generator(expr, &schema)?))
+    })
+}
+
+unsafe extern "C" fn release_fn_wrapper(planner: &mut FFI_ExprPlanner) {
+    unsafe {
+        debug_assert!(!planner.private_data.is_null());
+        let private_data =
+            Box::from_raw(planner.private_data as *mut ExprPlannerPrivateData);
+        drop(private_data);
+        planner.private_data = std::ptr::null_mut();
+    }
+}
+
+unsafe extern "C" fn clone_fn_wrapper(planner: &FFI_ExprPlanner) -> FFI_ExprPlanner {
+    FFI_ExprPlanner::new_with_ffi_codec(
+        Arc::clone(planner.inner()),
+        planner.runtime(),
+        planner.logical_codec.clone(),
+    )
+}
+
+impl Drop for FFI_ExprPlanner {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+impl Clone for FFI_ExprPlanner {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+impl FFI_ExprPlanner {
+    /// Creates a new [`FFI_ExprPlanner`] with a native logical extension
+    /// codec, used to pass expressions to the planner.
+    pub fn new(
+        planner: Arc<dyn ExprPlanner>,
+        runtime: Option<Handle>,
+        task_ctx_provider: impl Into<FFI_TaskContextProvider>,
+        logical_codec: Arc<dyn LogicalExtensionCodec>,
+    ) -> Self {
+        let logical_codec = FFI_LogicalExtensionCodec::new(
+            logical_codec,
+            runtime.clone(),
+            task_ctx_provider.into(),
+        );
+        Self::new_with_ffi_codec(planner, runtime, logical_codec)
+    }
+
+    /// Creates a new [`FFI_ExprPlanner`] using a prebuilt FFI extension codec.
+    pub fn new_with_ffi_codec(
+        planner: Arc<dyn ExprPlanner>,
+        runtime: Option<Handle>,
+        logical_codec: FFI_LogicalExtensionCodec,
+    ) -> Self {
+        let private_data = Box::new(ExprPlannerPrivateData { planner, runtime });
+        let private_data = Box::into_raw(private_data) as *mut c_void;
+
+        Self {
+            plan_binary_op: plan_binary_op_fn_wrapper,
+            plan_field_access: plan_field_access_fn_wrapper,
+            plan_array_literal: plan_array_literal_fn_wrapper,
+            plan_position: plan_position_fn_wrapper,
+            plan_dictionary_literal: plan_dictionary_literal_fn_wrapper,
+            plan_extract: plan_extract_fn_wrapper,
+            plan_substring: plan_substring_fn_wrapper,
+            plan_struct_literal: plan_struct_literal_fn_wrapper,
+            plan_overlay: plan_overlay_fn_wrapper,
+            plan_make_map: plan_make_map_fn_wrapper,
+            plan_compound_identifier: plan_compound_identifier_fn_wrapper,
+            plan_aggregate: plan_aggregate_fn_wrapper,
+            plan_window: plan_window_fn_wrapper,
+            plan_generator: plan_generator_fn_wrapper,
+            logical_codec,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            version: super::version,
+            private_data,
+            library_marker_id: crate::get_library_marker_id,
+        }
+    }
+}
+
+/// This wrapper struct exists on the receiver side of the FFI interface, so it has
+/// no guarantees about being able to access the data in `private_data`. Any functions
+/// defined on this struct must only use the stable functions provided in
+/// FFI_ExprPlanner to interact with the foreign planner.
+#[derive(Debug)]
+pub struct ForeignExprPlanner(FFI_ExprPlanner);
+
+unsafe impl Send for ForeignExprPlanner {}
+unsafe impl Sync for ForeignExprPlanner {}
+
+impl From<&FFI_ExprPlanner> for Arc<dyn ExprPlanner> {
+    fn from(planner: &FFI_ExprPlanner) -> Self {
+        if (planner.library_marker_id)() == crate::get_library_marker_id() {
+            return Arc::clone(planner.inner());
+        }
+
+        Arc::new(ForeignExprPlanner(planner.clone()))
+    }
+}
+
+impl ForeignExprPlanner {
+    /// Serialize the expressions, hand them to the foreign planner and decode
+    /// the planned expression, if any.
+    fn plan_exprs<'a>(
+        &self,
+        exprs: impl IntoIterator<Item = &'a Expr>,
+        plan: impl FnOnce(&FFI_ExprPlanner, SVec<u8>) -> FFI_PlannedExpr,
+    ) -> Result<Option<Expr>> {
+        let exprs_serialized = exprs_to_ffi(exprs, &self.0.logical_codec)?;
+        let planned = df_result!(plan(&self.0, exprs_serialized))?;
+        self.planned_expr(planned)
+    }
+
+    fn planned_expr(&self, planned: FFI_Option<SVec<u8>>) -> Result<Option<Expr>> {
+        planned
+            .into_option()
+            .map(|expr_serialized| {
+                let [expr] = exprs_into_array(exprs_from_ffi(
+                    &expr_serialized,
+                    &self.0.logical_codec,
+                )?)?;
+                Ok(expr)
+            })
+            .transpose()
+    }
+}
+
+fn planner_result<T>(planned: Option<Expr>, original: T) -> PlannerResult<T> {
+    match planned {
+        Some(expr) => PlannerResult::Planned(expr),
+        None => PlannerResult::Original(original),
+    }
+}
+
+impl ExprPlanner for ForeignExprPlanner {
+    fn plan_binary_op(
+        &self,
+        expr: RawBinaryExpr,
+        schema: &DFSchema,
+    ) -> Result<PlannerResult<RawBinaryExpr>> {
+        let schema = df_schema_to_ffi(schema)?;
+        let planned =
+            self.plan_exprs([&expr.left, &expr.right], |planner, exprs| unsafe {
+                (planner.plan_binary_op)(planner, (&expr.op).into(), exprs, schema)
+            })?;
+        Ok(planner_result(planned, expr))
+    }
+
+    fn plan_field_access(
+        &self,
+        expr: RawFieldAccessExpr,
+        schema: &DFSchema,
+    ) -> Result<PlannerResult<RawFieldAccessExpr>> {
+        let schema = df_schema_to_ffi(schema)?;
+        let (kind, exprs) = match &expr.field_access {
+            GetFieldAccess::NamedStructField { name } => (
+                FFI_FieldAccessKind::NamedStructField,
+                vec![expr.expr.clone(), lit(name.clone())],
+            ),
+            GetFieldAccess::ListIndex { key } => (
+                FFI_FieldAccessKind::ListIndex,
+                vec![expr.expr.clone(), key.as_ref().clone()],
+            ),
+            GetFieldAccess::ListRange {
+                start,
+                stop,
+                stride,
+            } => (
+                FFI_FieldAccessKind::ListRange,
+                vec![
+                    expr.expr.clone(),
+                    start.as_ref().clone(),
+                    stop.as_ref().clone(),
+                    stride.as_ref().clone(),
+                ],
+            ),
+        };
+        let planned = self.plan_exprs(&exprs, |planner, exprs| unsafe {
+            (planner.plan_field_access)(planner, kind, exprs, schema)
+        })?;
+        Ok(planner_result(planned, expr))
+    }
+
+    fn plan_array_literal(
+        &self,
+        exprs: Vec<Expr>,
+        schema: &DFSchema,
+    ) -> Result<PlannerResult<Vec<Expr>>> {
+        let schema = df_schema_to_ffi(schema)?;
+        let planned = self.plan_exprs(&exprs, |planner, exprs| unsafe {
+            (planner.plan_array_literal)(planner, exprs, schema)
+        })?;
+        Ok(planner_result(planned, exprs))
+    }
+
+    fn plan_position(&self, args: Vec<Expr>) -> Result<PlannerResult<Vec<Expr>>> {
+        let planned = self.plan_exprs(&args, |planner, args| unsafe {
+            (planner.plan_position)(planner, args)
+        })?;
+        Ok(planner_result(planned, args))
+    }
+
+    fn plan_dictionary_literal(
+        &self,
+        expr: RawDictionaryExpr,
+        schema: &DFSchema,
+    ) -> Result<PlannerResult<RawDictionaryExpr>> {
+        if expr.keys.len() != expr.values.len() {
+            return internal_err!("Dictionary literal must have as many keys as values");
+        }
+        let schema = df_schema_to_ffi(schema)?;
+        let planned = self.plan_exprs(
+            expr.keys.iter().chain(&expr.values),
+            |planner, exprs| unsafe {
+                (planner.plan_dictionary_literal)(planner, exprs, schema)
+            },
+        )?;
+        Ok(planner_result(planned, expr))
+    }
+
+    fn plan_extract(&self, args: Vec<Expr>) -> Result<PlannerResult<Vec<Expr>>> {
+        let planned = self.plan_exprs(&args, |planner, args| unsafe {
+            (planner.plan_extract)(planner, args)
+        })?;
+        Ok(planner_result(planned, args))
+    }
+
+    fn plan_substring(&self, args: Vec<Expr>) -> Result<PlannerResult<Vec<Expr>>> {
+        let planned = self.plan_exprs(&args, |planner, args| unsafe {
+            (planner.plan_substring)(planner, args)
+        })?;
+        Ok(planner_result(planned, args))
+    }
+
+    fn plan_struct_literal(
+        &self,
+        args: Vec<Expr>,
+        is_named_struct: bool,
+    ) -> Result<PlannerResult<Vec<Expr>>> {
+        let planned = self.plan_exprs(&args, |planner, args| unsafe {
+            (planner.plan_struct_literal)(planner, args, is_named_struct)
+        })?;
+        Ok(planner_result(planned, args))
+    }
+
+    fn plan_overlay(&self, args: Vec<Expr>) -> Result<PlannerResult<Vec<Expr>>> {
+        let planned = self.plan_exprs(&args, |planner, args| unsafe {
+            (planner.plan_overlay)(planner, args)
+        })?;
+        Ok(planner_result(planned, args))
+    }
+
+    fn plan_make_map(&self, args: Vec<Expr>) -> Result<PlannerResult<Vec<Expr>>> {
+        let planned = self.plan_exprs(&args, |planner, args| unsafe {
+            (planner.plan_make_map)(planner, args)
+        })?;
+        Ok(planner_result(planned, args))
+    }
+
+    fn plan_compound_identifier(
+        &self,
+        field: &Field,
+        qualifier: Option<&TableReference>,
+        nested_names: &[String],
+    ) -> Result<PlannerResult<Vec<Expr>>> {
+        let field = WrappedSchema(FFI_ArrowSchema::try_from(field)?);
+        let qualifier = qualifier.map(table_reference_to_ffi).into();
+        let nested_names = nested_names
+            .iter()
+            .map(|name| SString::from(name.as_str()))
+            .collect();
+
+        let planned = unsafe {
+            df_result!((self.0.plan_compound_identifier)(
+                &self.0,
+                field,
+                qualifier,
+                nested_names
+            ))?
+        };
+        Ok(planner_result(self.planned_expr(planned)?, vec![]))
+    }
+
+    fn plan_aggregate(
+        &self,
+        expr: RawAggregateExpr,
+    ) -> Result<PlannerResult<RawAggregateExpr>> {
+        let aggregate = Expr::AggregateFunction(AggregateFunction::new_udf(
+            Arc::clone(&expr.func),
+            expr.args.clone(),
+            expr.distinct,
+            expr.filter.clone(),
+            expr.order_by.clone(),
+            expr.null_treatment,
+        ));
+        let planned = self.plan_exprs([&aggregate], |planner, expr| unsafe {
+            (planner.plan_aggregate)(planner, expr)
+        })?;
+        Ok(planner_result(planned, expr))
+    }
+
+    fn plan_window(&self, expr: RawWindowExpr) -> Result<PlannerResult<RawWindowExpr>> {
+        let window = Expr::from(WindowFunction {
+            fun: expr.func_def.clone(),
+            params: WindowFunctionParams {
+                args: expr.args.clone(),
+                partition_by: expr.partition_by.clone(),
+                order_by: expr.order_by.clone(),
+                window_frame: expr.window_frame.clone(),
+                filter: expr.filter.clone(),
+                null_treatment: expr.null_treatment,
+                distinct: expr.distinct,
+            },
+        });
+        let planned = self.plan_exprs([&window], |planner, expr| unsafe {
+            (planner.plan_window)(planner, expr)
+        })?;
+        Ok(planner_result(planned, expr))
+    }
+
+    fn plan_generator(
+        &self,
+        expr: Expr,
+        schema: &DFSchema,
+    ) -> Result<PlannerResult<Expr>> {
+        let schema = df_schema_to_ffi(schema)?;
+        let planned = self.plan_exprs([&expr], |planner, exprs| unsafe {
+            (planner.plan_generator)(planner, exprs, schema)
+        })?;
+        Ok(planner_result(planned, expr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::DataType;
+    use datafusion::prelude::SessionContext;
+    use datafusion_common::{Column, ScalarValue};
+    use datafusion_expr::col;
+    use datafusion_proto::logical_plan::DefaultLogicalExtensionCodec;
+    use sqlparser::ast::BinaryOperator;
+
+    use super::*;
+
+    /// Plans `->` as an addition when the schema has the qualified column
+    /// `t.a`, and records the other raw expressions it is given.
+    #[derive(Debug)]
+    struct TestPlanner;
+
+    impl ExprPlanner for TestPlanner {
+        fn plan_binary_op(
+            &self,
+            expr: RawBinaryExpr,
+            schema: &DFSchema,
+        ) -> Result<PlannerResult<RawBinaryExpr>> {
+            let has_column = schema.has_column(&Column::new(Some("t"), "a"));
+            match expr.op {
+                BinaryOperator::Arrow if has_column => {
+                    Ok(PlannerResult::Planned(expr.left + expr.right))
+                }
+                _ => Ok(PlannerResult::Original(expr)),
+            }
+        }
+
+        fn plan_field_access(
+            &self,
+            expr: RawFieldAccessExpr,
+            _schema: &DFSchema,
+        ) -> Result<PlannerResult<RawFieldAccessExpr>> {
+            match &expr.field_access {
+                GetFieldAccess::NamedStructField { name } => {
+                    Ok(PlannerResult::Planned(expr.expr.alias(name.to_string())))
+                }
+                _ => Ok(PlannerResult::Original(expr)),
+            }
+        }
+
+        fn plan_dictionary_literal(
+            &self,
+            mut expr: RawDictionaryExpr,
+            _schema: &DFSchema,
+        ) -> Result<PlannerResult<RawDictionaryExpr>> {
+            let (Some(key), Some(value)) = (expr.keys.pop(), expr.values.pop()) else {
+                return Ok(PlannerResult::Original(expr));
+            };
+            Ok(PlannerResult::Planned(key + value))
+        }
+
+        fn plan_compound_identifier(
+            &self,
+            field: &Field,
+            qualifier: Option<&TableReference>,
+            nested_names: &[String],
+        ) -> Result<PlannerResult<Vec<Expr>>> {
+            let name = format!("{}.{}", field.name(), nested_names.join("."));
+            Ok(PlannerResult::Planned(Expr::Column(Column::new(
+                qualifier.cloned(),
+                name,
+            ))))
+        }
+    }
+
+    fn create_foreign_planner() -> (Arc<SessionContext>, Arc<dyn ExprPlanner>) {
+        let (ctx, task_ctx_provider) = crate::util::tests::test_session_and_ctx();
+        let mut ffi_planner = FFI_ExprPlanner::new(
+            Arc::new(TestPlanner),
+            None,
+            task_ctx_provider,
+            Arc::new(DefaultLogicalExtensionCodec {}),
+        );
+        ffi_planner.library_marker_id = crate::mock_foreign_marker_id;
+
+        (ctx, (&ffi_planner).into())
+    }
+
+    #[test]
+    fn test_round_trip_plan_binary_op() -> Result<()> {
+        let (_ctx, planner) = create_foreign_planner();
+        let schema = DFSchema::try_from_qualified_schema(
+            "t",
+            &arrow::datatypes::Schema::new(vec![Field::new("a", DataType::Int64, true)]),
+        )?;
+
+        let expr = RawBinaryExpr {
+            op: BinaryOperator::Arrow,
+            left: col("t.a"),
+            right: lit(1i64),
+        };
+        let PlannerResult::Planned(planned) = planner.plan_binary_op(expr, &schema)?
+        else {
+            panic!("Expected the binary operator to be planned");
+        };
+        assert_eq!(planned, col("t.a") + lit(1i64));
+
+        let expr = RawBinaryExpr {
+            op: BinaryOperator::LongArrow,
+            left: col("t.a"),
+            right: lit(1i64),
+        };
+        let PlannerResult::Original(original) = planner.plan_binary_op(expr, &schema)?
+        else {
+            panic!("Expected the binary operator to be returned");
+        };
+        assert_eq!(original.op, BinaryOperator::LongArrow);
+
+        // Without the qualifier the planner declines
+        let expr = RawBinaryExpr {
+            op: BinaryOperator::Arrow,
+            left: col("a"),
+            right: lit(1i64),
+        };
+        let unqualified =
+            DFSchema::try_from(arrow::datatypes::Schema::new(vec![Field::new(
+                "a",
+                DataType::Int64,
+                true,
+            )]))?;
+        assert!(matches!(
+            planner.plan_binary_op(expr, &unqualified)?,
+            PlannerResult::Original(_)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip_plan_field_access() -> Result<()> {
+        let (_ctx, planner) = create_foreign_planner();
+        let schema = DFSchema::empty();
+
+        let expr = RawFieldAccessExpr {
+            field_access: GetFieldAccess::NamedStructField {
+                name: ScalarValue::from("b"),
+            },
+            expr: col("a"),
+        };
+        let PlannerResult::Planned(planned) = planner.plan_field_access(expr, &schema)?
+        else {
+            panic!("Expected the field access to be planned");
+        };
+        assert_eq!(planned, col("a").alias("b"));
+
+        let expr = RawFieldAccessExpr {
+            field_access: GetFieldAccess::ListRange {
+                start: Box::new(lit(1i64)),
+                stop: Box::new(lit(2i64)),
+                stride: Box::new(lit(1i64)),
+            },
+            expr: col("a"),
+        };
+        assert!(matches!(
+            planner.plan_field_access(expr, &schema)?,
+            PlannerResult::Original(_)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip_plan_dictionary_literal() -> Result<()> {
+        let (_ctx, planner) = create_foreign_planner();
+
+        let expr = RawDictionaryExpr {
+            keys: vec![col("a"), col("b")],
+            values: vec![lit(1i64), lit(2i64)],
+        };
+        let PlannerResult::Planned(planned) =
+            planner.plan_dictionary_literal(expr, &DFSchema::empty())?
+        else {
+            panic!("Expected the dictionary literal to be planned");
+        };
+        assert_eq!(planned, col("b") + lit(2i64));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_round_trip_plan_compound_identifier() -> Result<()> {
+        let (_ctx, planner) = create_foreign_planner();
+
+        let field = Field::new("s", DataType::Int64, true);
+        let qualifier = TableReference::partial("public", "t");
+        let PlannerResult::Planned(planned) = planner.plan_compound_identifier(
+            &field,
+            Some(&qualifier),
+            &["x".to_string(), "y".to_string()],
+        )?
+        else {
+            panic!("Expected the compound identifier to be planned");
+        };
+        assert_eq!(planned, Expr::Column(Column::new(Some(qualifier), "s.x.y")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_methods_return_original() -> Result<()> {
+        let (_ctx, planner) = create_foreign_planner();
+
+        let args = vec![col("a"), lit(1i64)];
+        let PlannerResult::Original(original) = planner.plan_position(args.clone())?
+        else {
+            panic!("Expected the arguments to be returned");
+        };
+        assert_eq!(original, args);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_reference_round_trip() -> Result<()> {
+        for reference in [
+            TableReference::bare("t"),
+            TableReference::partial("s", "t"),
+            TableReference::full("c", "s", "t"),
+        ] {
+            let parts = table_reference_to_ffi(&reference);
+            assert_eq!(table_reference_from_ffi(&parts)?, reference);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_bypass() {
+        let (_ctx, task_ctx_provider) = crate::util::tests::test_session_and_ctx();
+        let planner: Arc<dyn ExprPlanner> = Arc::new(TestPlanner);
+        let ffi_planner = FFI_ExprPlanner::new(
+            Arc::clone(&planner),
+            None,
+            task_ctx_provider,
+            Arc::new(DefaultLogicalExtensionCodec {}),
+        );
+
+        let recovered: Arc<dyn ExprPlanner> = (&ffi_planner).into();
+        assert!(Arc::ptr_eq(&planner, &recovered));
+    }
+}
@@ -0,0 +1,189 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::ffi::c_void;
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use async_ffi::{FfiFuture, FutureExt as _};
+use datafusion_common::{DataFusionError, Result};
+use datafusion_datasource::PartitionedFile;
+use datafusion_datasource::file_stream::{FileOpenFuture, FileOpener};
+use datafusion_physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion_proto::protobuf;
+use futures::{FutureExt, StreamExt};
+use prost::Message;
+use stabby::vec::Vec as SVec;
+use tokio::runtime::Handle;
+
+use crate::record_batch_stream::FFI_RecordBatchStream;
+use crate::util::FFI_Result;
+use crate::{df_result, sresult_return};
+
+/// A stable struct for sharing a [`FileOpener`] across FFI boundaries.
+///
+/// Files are passed as serialized [`protobuf::PartitionedFile`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_FileOpener {
+    pub open: unsafe extern "C" fn(
+        &Self,
+        file_serialized: SVec<u8>,
+    ) -> FfiFuture<FFI_Result<FFI_RecordBatchStream>>,
+
+    /// Used to create a clone on the opener. This should
+    /// only need to be called by the receiver of the opener.
+    pub clone: unsafe extern "C" fn(opener: &Self) -> Self,
+
+    /// Release the memory of the private data when it is no longer being used.
+    pub release: unsafe extern "C" fn(arg: &mut Self),
+
+    /// Return the major DataFusion version number of this opener.
+    pub version: unsafe extern "C" fn() -> u64,
+
+    /// Internal data. This is only to be accessed by the provider of the opener.
+    /// A [`ForeignFileOpener`] should never attempt to access this data.
+    pub private_data: *mut c_void,
+
+    /// Utility to identify when FFI objects are accessed locally through
+    /// the foreign interface.
+    pub library_marker_id: extern "C" fn() -> usize,
+}
+
+unsafe impl Send for FFI_FileOpener {}
+unsafe impl Sync for FFI_FileOpener {}
+
+struct FileOpenerPrivateData {
+    opener: Arc<dyn FileOpener>,
+    schema: SchemaRef,
+    runtime: Option<Handle>,
+}
+
+impl FFI_FileOpener {
+    /// Creates a new [`FFI_FileOpener`]. The streams of the opened files are
+    /// exported with `schema`, which is the projected schema of the scan.
+    pub fn new(
+        opener: Arc<dyn FileOpener>,
+        schema: SchemaRef,
+        runtime: Option<Handle>,
+    ) -> Self {
+        let private_data = Box::new(FileOpenerPrivateData {
+            opener,
+            schema,
+            runtime,
+        });
+        let private_data = Box::into_raw(private_data) as *mut c_void;
+
+        Self {
+            open: open_fn_wrapper,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            version: crate::version,
+            private_data,
+            library_marker_id: crate::get_library_marker_id,
+        }
+    }
+
+    fn private_data(&self) -> &FileOpenerPrivateData {
+        unsafe { &*(self.private_data as *const FileOpenerPrivateData) }
+    }
+}
+
+unsafe extern "C" fn open_fn_wrapper(
+    opener: &FFI_FileOpener,
+    file_serialized: SVec<u8>,
+) -> FfiFuture<FFI_Result<FFI_RecordBatchStream>> {
+    let private_data = opener.private_data();
+    let opener = Arc::clone(&private_data.opener);
+    let schema = Arc::clone(&private_data.schema);
+    let runtime = private_data.runtime.clone();
+
+    async move {
+        let file = sresult_return!(
+            protobuf::PartitionedFile::decode(file_serialized.as_slice())
+                .map_err(|e| DataFusionError::External(Box::new(e)))
+                .and_then(|file| PartitionedFile::try_from(&file))
+        );
+        let stream = sresult_return!(sresult_return!(opener.open(file)).await);
+        let stream = Box::pin(RecordBatchStreamAdapter::new(schema, stream));
+
+        FFI_Result::Ok(FFI_RecordBatchStream::new(stream, runtime))
+    }
+    .into_ffi()
+}
+
+unsafe extern "C" fn clone_fn_wrapper(opener: &FFI_FileOpener) -> FFI_FileOpener {
+    let private_data = opener.private_data();
+    FFI_FileOpener::new(
+        Arc::clone(&private_data.opener),
+        Arc::clone(&private_data.schema),
+        private_data.runtime.clone(),
+    )
+}
+
+unsafe extern "C" fn release_fn_wrapper(opener: &mut FFI_FileOpener) {
+    unsafe {
+        debug_assert!(!opener.private_data.is_null());
+        let private_data =
+            Box::from_raw(opener.private_data as *mut FileOpenerPrivateData);
+        drop(private_data);
+        opener.private_data = std::ptr::null_mut();
+    }
+}
+
+impl Drop for FFI_FileOpener {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+impl Clone for FFI_FileOpener {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+/// This wrapper struct exists on the receiver side of the FFI interface, so it has
+/// no guarantees about being able to access the data in `private_data`. Any functions
+/// defined on this struct must only use the stable functions provided in
+/// FFI_FileOpener to interact with the foreign opener.
+#[derive(Debug)]
+pub struct ForeignFileOpener(FFI_FileOpener);
+
+impl From<&FFI_FileOpener> for Arc<dyn FileOpener> {
+    fn from(opener: &FFI_FileOpener) -> Self {
+        if (opener.library_marker_id)() == crate::get_library_marker_id() {
+            return Arc::clone(&opener.private_data().opener);
+        }
+
+        Arc::new(ForeignFileOpener(opener.clone()))
+    }
+}
+
+impl FileOpener for ForeignFileOpener {
+    fn open(&self, partitioned_file: PartitionedFile) -> Result<FileOpenFuture> {
+        let file = protobuf::PartitionedFile::try_from(&partitioned_file)?;
+        let file: SVec<u8> = file.encode_to_vec().into_iter().collect();
+        let stream = unsafe { (self.0.open)(&self.0, file) };
+
+        Ok(async move {
+            let stream = df_result!(stream.await)?;
+            Ok(stream.boxed())
+        }
+        .boxed())
+    }
+}
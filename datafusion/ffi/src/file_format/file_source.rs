@@ -0,0 +1,585 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::ffi::c_void;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use arrow::datatypes::{Schema, SchemaRef};
+use datafusion_common::Result;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::TreeNodeRecursion;
+use datafusion_datasource::TableSchema;
+use datafusion_datasource::file::FileSource;
+use datafusion_datasource::file_scan_config::{FileScanConfig, FileScanConfigBuilder};
+use datafusion_datasource::file_stream::FileOpener;
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_expr::projection::{ProjectionExpr, ProjectionExprs};
+use datafusion_physical_plan::filter_pushdown::{FilterPushdownPropagation, PushedDown};
+use datafusion_physical_plan::metrics::ExecutionPlanMetricsSet;
+use datafusion_physical_plan::{DisplayFormatType, apply_expression_roots};
+use datafusion_proto::physical_plan::PhysicalExtensionCodec;
+use object_store::ObjectStore;
+use stabby::string::String as SString;
+use stabby::vec::Vec as SVec;
+use tokio::runtime::Handle;
+
+use super::file_opener::FFI_FileOpener;
+use super::{file_scan_config_from_bytes, file_scan_config_to_bytes};
+use crate::arrow_wrappers::WrappedSchema;
+use crate::config::FFI_ConfigOptions;
+use crate::execution::FFI_TaskContextProvider;
+use crate::object_store::FFI_ObjectStore;
+use crate::physical_expr::FFI_PhysicalExpr;
+use crate::proto::physical_extension_codec::FFI_PhysicalExtensionCodec;
+use crate::util::{FFI_Option, FFI_Result};
+use crate::{df_result, sresult_return};
+
+/// An FFI-safe [`ProjectionExpr`].
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct FFI_ProjectionExpr {
+    pub expr: FFI_PhysicalExpr,
+    pub alias: SString,
+}
+
+fn projection_to_ffi(projection: &ProjectionExprs) -> SVec<FFI_ProjectionExpr> {
+    projection
+        .iter()
+        .map(|expr| FFI_ProjectionExpr {
+            expr: Arc::clone(&expr.expr).into(),
+            alias: expr.alias.as_str().into(),
+        })
+        .collect()
+}
+
+fn projection_from_ffi(projection: &SVec<FFI_ProjectionExpr>) -> ProjectionExprs {
+    ProjectionExprs::new(
+        projection
+            .iter()
+            .map(|expr| ProjectionExpr::new((&expr.expr).into(), expr.alias.as_str())),
+    )
+}
+
+/// FFI-safe version of [`DisplayFormatType`].
+#[expect(non_camel_case_types)]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy)]
+pub enum FFI_DisplayFormatType {
+    Default,
+    Verbose,
+    TreeRender,
+}
+
+impl From<DisplayFormatType> for FFI_DisplayFormatType {
+    fn from(value: DisplayFormatType) -> Self {
+        match value {
+            DisplayFormatType::Default => Self::Default,
+            DisplayFormatType::Verbose => Self::Verbose,
+            DisplayFormatType::TreeRender => Self::TreeRender,
+        }
+    }
+}
+
+impl From<FFI_DisplayFormatType> for DisplayFormatType {
+    fn from(value: FFI_DisplayFormatType) -> Self {
+        match value {
+            FFI_DisplayFormatType::Default => Self::Default,
+            FFI_DisplayFormatType::Verbose => Self::Verbose,
+            FFI_DisplayFormatType::TreeRender => Self::TreeRender,
+        }
+    }
+}
+
+/// The result of [`FileSource::try_pushdown_filters`]. `filters` holds
+/// whether each of the given filters was pushed down.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_FilterPushdown {
+    pub filters: SVec<bool>,
+    pub updated_source: FFI_Option<FFI_FileSource>,
+}
+
+/// A stable struct for sharing a [`FileSource`] across FFI boundaries.
+///
+/// The [`FileScanConfig`] given to [`FileSource::create_file_opener`] is
+/// serialized with the physical extension codec of the source.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_FileSource {
+    pub create_file_opener: unsafe extern "C" fn(
+        &Self,
+        store: FFI_ObjectStore,
+        config_serialized: SVec<u8>,
+        partition: usize,
+    ) -> FFI_Result<FFI_FileOpener>,
+
+    /// The schema of the files, without the partition columns.
+    pub file_schema: unsafe extern "C" fn(&Self) -> WrappedSchema,
+
+    /// The partition columns of the table, as the fields of a schema.
+    pub table_partition_cols: unsafe extern "C" fn(&Self) -> WrappedSchema,
+
+    pub with_batch_size: unsafe extern "C" fn(&Self, batch_size: usize) -> Self,
+
+    pub filter: unsafe extern "C" fn(&Self) -> FFI_Option<FFI_PhysicalExpr>,
+
+    pub projection: unsafe extern "C" fn(&Self) -> FFI_Option<SVec<FFI_ProjectionExpr>>,
+
+    pub file_type: unsafe extern "C" fn(&Self) -> SString,
+
+    pub fmt_extra: unsafe extern "C" fn(&Self, t: FFI_DisplayFormatType) -> SString,
+
+    pub supports_repartitioning: unsafe extern "C" fn(&Self) -> bool,
+
+    pub try_pushdown_filters: unsafe extern "C" fn(
+        &Self,
+        filters: SVec<FFI_PhysicalExpr>,
+        config: FFI_ConfigOptions,
+    ) -> FFI_Result<FFI_FilterPushdown>,
+
+    pub try_pushdown_projection: unsafe extern "C" fn(
+        &Self,
+        projection: SVec<FFI_ProjectionExpr>,
+    )
+        -> FFI_Result<FFI_Option<Self>>,
+
+    /// Codec used to encode and decode the scan configuration.
+    pub physical_codec: FFI_PhysicalExtensionCodec,
+
+    /// Used to create a clone on the source. This should
+    /// only need to be called by the receiver of the source.
+    pub clone: unsafe extern "C" fn(source: &Self) -> Self,
+
+    /// Release the memory of the private data when it is no longer being used.
+    pub release: unsafe extern "C" fn(arg: &mut Self),
+
+    /// Return the major DataFusion version number of this source.
+    pub version: unsafe extern "C" fn() -> u64,
+
+    /// Internal data. This is only to be accessed by the provider of the source.
+    /// A [`ForeignFileSource`] should never attempt to access this data.
+    pub private_data: *mut c_void,
+
+    /// Utility to identify when FFI objects are accessed locally through
+    /// the foreign interface.
+    pub library_marker_id: extern "C" fn() -> usize,
+}
+
+unsafe impl Send for FFI_FileSource {}
+unsafe impl Sync for FFI_FileSource {}
+
+struct FileSourcePrivateData {
+    source: Arc<dyn FileSource>,
+    runtime: Option<Handle>,
+}
+
+impl FFI_FileSource {
+    fn inner(&self) -> &Arc<dyn FileSource> {
+        let private_data = self.private_data as *const FileSourcePrivateData;
+        unsafe { &(*private_data).source }
+    }
+
+    fn runtime(&self) -> Option<Handle> {
+        let private_data = self.private_data as *const FileSourcePrivateData;
+        unsafe { (*private_data).runtime.clone() }
+    }
+
+    /// Wraps another source of the same provider, such as one returned by a
+    /// pushdown, sharing the codec of this source.
+    fn with_source(&self, source: Arc<dyn FileSource>) -> Self {
+        Self::new_with_ffi_codec(source, self.runtime(), self.physical_codec.clone())
+    }
+}
+
+unsafe extern "C" fn create_file_opener_fn_wrapper(
+    source: &FFI_FileSource,
+    store: FFI_ObjectStore,
+    config_serialized: SVec<u8>,
+    partition: usize,
+) -> FFI_Result<FFI_FileOpener> {
+    let task_ctx: Arc<TaskContext> =
+        sresult_return!((&source.physical_codec.task_ctx_provider).try_into());
+    let codec: Arc<dyn PhysicalExtensionCodec> = (&source.physical_codec).into();
+    let store: Arc<dyn ObjectStore> = (&store).into();
+    let inner = source.inner();
+
+    let config = sresult_return!(file_scan_config_from_bytes(
+        config_serialized.as_slice(),
+        &task_ctx,
+        codec.as_ref(),
+        Arc::clone(inner),
+    ));
+    let schema = sresult_return!(config.projected_schema());
+    let opener = sresult_return!(inner.create_file_opener(store, &config, partition));
+
+    FFI_Result::Ok(FFI_FileOpener::new(opener, schema, source.runtime()))
+}
+
+unsafe extern "C" fn file_schema_fn_wrapper(source: &FFI_FileSource) -> WrappedSchema {
+    Arc::clone(source.inner().table_schema().file_schema()).into()
+}
+
+unsafe extern "C" fn table_partition_cols_fn_wrapper(
+    source: &FFI_FileSource,
+) -> WrappedSchema {
+    let fields = source.inner().table_schema().table_partition_cols().clone();
+    SchemaRef::new(Schema::new(fields)).into()
+}
+
+unsafe extern "C" fn with_batch_size_fn_wrapper(
+    source: &FFI_FileSource,
+    batch_size: usize,
+) -> FFI_FileSource {
+    source.with_source(source.inner().with_batch_size(batch_size))
+}
+
+unsafe extern "C" fn filter_fn_wrapper(
+    source: &FFI_FileSource,
+) -> FFI_Option<FFI_PhysicalExpr> {
+    source.inner().filter().map(FFI_PhysicalExpr::from).into()
+}
+
+unsafe extern "C" fn projection_fn_wrapper(
+    source: &FFI_FileSource,
+) -> FFI_Option<SVec<FFI_ProjectionExpr>> {
+    source.inner().projection().map(projection_to_ffi).into()
+}
+
+unsafe extern "C" fn file_type_fn_wrapper(source: &FFI_FileSource) -> SString {
+    source.inner().file_type().into()
+}
+
+unsafe extern "C" fn fmt_extra_fn_wrapper(
+    source: &FFI_FileSource,
+    t: FFI_DisplayFormatType,
+) -> SString {
+    struct FmtExtra<'a>(&'a dyn FileSource, DisplayFormatType);
+
+    impl std::fmt::Display for FmtExtra<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            self.0.fmt_extra(self.1, f)
+        }
+    }
+
+    FmtExtra(source.inner().as_ref(), t.into())
+        .to_string()
+        .as_str()
+        .into()
+}
+
+unsafe extern "C" fn supports_repartitioning_fn_wrapper(source: &FFI_FileSource) -> bool {
+    source.inner().supports_repartitioning()
+}
+
+unsafe extern "C" fn try_pushdown_filters_fn_wrapper(
+    source: &FFI_FileSource,
+    filters: SVec<FFI_PhysicalExpr>,
+    config: FFI_ConfigOptions,
+) -> FFI_Result<FFI_FilterPushdown> {
+    let filters = filters.iter().map(Into::into).collect();
+    let config = sresult_return!(ConfigOptions::try_from(config));
+    let propagation =
+        sresult_return!(source.inner().try_pushdown_filters(filters, &config));
+
+    FFI_Result::Ok(FFI_FilterPushdown {
+        filters: propagation
+            .filters
+            .iter()
+            .map(|pushed| matches!(pushed, PushedDown::Yes))
+            .collect(),
+        updated_source: propagation
+            .updated_node
+            .map(|updated| source.with_source(updated))
+            .into(),
+    })
+}
+
+unsafe extern "C" fn try_pushdown_projection_fn_wrapper(
+    source: &FFI_FileSource,
+    projection: SVec<FFI_ProjectionExpr>,
+) -> FFI_Result<FFI_Option<FFI_FileSource>> {
+    let projection = projection_from_ffi(&projection);
+    let updated = sresult_return!(source.inner().try_pushdown_projection(&projection));
+
+    FFI_Result::Ok(updated.map(|updated| source.with_source(updated)).into())
+}
+
+unsafe extern "C" fn release_fn_wrapper(source: &mut FFI_FileSource) {
+    unsafe {
+        debug_assert!(!source.private_data.is_null());
+        let private_data =
+            Box::from_raw(source.private_data as *mut FileSourcePrivateData);
+        drop(private_data);
+        source.private_data = std::ptr::null_mut();
+    }
+}
+
+unsafe extern "C" fn clone_fn_wrapper(source: &FFI_FileSource) -> FFI_FileSource {
+    source.with_source(Arc::clone(source.inner()))
+}
+
+impl Drop for FFI_FileSource {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+impl Clone for FFI_FileSource {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+impl FFI_FileSource {
+    /// Creates a new [`FFI_FileSource`] with a native physical extension
+    /// codec, used to pass the scan configuration to the source.
+    pub fn new(
+        source: Arc<dyn FileSource>,
+        runtime: Option<Handle>,
+        task_ctx_provider: impl Into<FFI_TaskContextProvider>,
+        physical_codec: Arc<dyn PhysicalExtensionCodec>,
+    ) -> Self {
+        let physical_codec = FFI_PhysicalExtensionCodec::new(
+            physical_codec,
+            runtime.clone(),
+            task_ctx_provider.into(),
+        );
+        Self::new_with_ffi_codec(source, runtime, physical_codec)
+    }
+
+    /// Creates a new [`FFI_FileSource`] using a prebuilt FFI extension codec.
+    pub fn new_with_ffi_codec(
+        source: Arc<dyn FileSource>,
+        runtime: Option<Handle>,
+        physical_codec: FFI_PhysicalExtensionCodec,
+    ) -> Self {
+        if let Some(source) = source.downcast_ref::<ForeignFileSource>() {
+            return source.source.clone();
+        }
+
+        let private_data = Box::new(FileSourcePrivateData { source, runtime });
+        let private_data = Box::into_raw(private_data) as *mut c_void;
+
+        Self {
+            create_file_opener: create_file_opener_fn_wrapper,
+            file_schema: file_schema_fn_wrapper,
+            table_partition_cols: table_partition_cols_fn_wrapper,
+            with_batch_size: with_batch_size_fn_wrapper,
+            filter: filter_fn_wrapper,
+            projection: projection_fn_wrapper,
+            file_type: file_type_fn_wrapper,
+            fmt_extra: fmt_extra_fn_wrapper,
+            supports_repartitioning: supports_repartitioning_fn_wrapper,
+            try_pushdown_filters: try_pushdown_filters_fn_wrapper,
+            try_pushdown_projection: try_pushdown_projection_fn_wrapper,
+            physical_codec,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            version: crate::version,
+            private_data,
+            library_marker_id: crate::get_library_marker_id,
+        }
+    }
+}
+
+/// This wrapper struct exists on the receiver side of the FFI interface, so it has
+/// no guarantees about being able to access the data in `private_data`. Any functions
+/// defined on this struct must only use the stable functions provided in
+/// FFI_FileSource to interact with the foreign source.
+///
+/// The schema, file type, filter and projection of a source do not change, so
+/// they are fetched once when the source is received.
+#[derive(Debug, Clone)]
+pub struct ForeignFileSource {
+    source: FFI_FileSource,
+    table_schema: TableSchema,
+    file_type: String,
+    filter: Option<Arc<dyn PhysicalExpr>>,
+    projection: Option<ProjectionExprs>,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl From<FFI_FileSource> for ForeignFileSource {
+    fn from(source: FFI_FileSource) -> Self {
+        unsafe {
+            let file_schema: SchemaRef = (source.file_schema)(&source).into();
+            let partition_cols: SchemaRef = (source.table_partition_cols)(&source).into();
+            let file_type = (source.file_type)(&source).to_string();
+            let filter = (source.filter)(&source)
+                .into_option()
+                .map(|filter| (&filter).into());
+            let projection = (source.projection)(&source)
+                .into_option()
+                .map(|projection| projection_from_ffi(&projection));
+
+            Self {
+                table_schema: TableSchema::builder(file_schema)
+                    .with_table_partition_cols(partition_cols.fields().clone())
+                    .build(),
+                file_type,
+                filter,
+                projection,
+                metrics: ExecutionPlanMetricsSet::new(),
+                source,
+            }
+        }
+    }
+}
+
+impl From<&FFI_FileSource> for Arc<dyn FileSource> {
+    fn from(source: &FFI_FileSource) -> Self {
+        if (source.library_marker_id)() == crate::get_library_marker_id() {
+            return Arc::clone(source.inner());
+        }
+
+        Arc::new(ForeignFileSource::from(source.clone()))
+    }
+}
+
+impl FileSource for ForeignFileSource {
+    fn create_file_opener(
+        &self,
+        object_store: Arc<dyn ObjectStore>,
+        base_config: &FileScanConfig,
+        partition: usize,
+    ) -> Result<Arc<dyn FileOpener>> {
+        let codec: Arc<dyn PhysicalExtensionCodec> = (&self.source.physical_codec).into();
+        let config = file_scan_config_to_bytes(base_config, codec.as_ref())?;
+        let store = FFI_ObjectStore::new(object_store, None);
+
+        let opener = unsafe {
+            df_result!((self.source.create_file_opener)(
+                &self.source,
+                store,
+                config,
+                partition
+            ))?
+        };
+        Ok((&opener).into())
+    }
+
+    fn table_schema(&self) -> &TableSchema {
+        &self.table_schema
+    }
+
+    fn with_batch_size(&self, batch_size: usize) -> Arc<dyn FileSource> {
+        let source = unsafe { (self.source.with_batch_size)(&self.source, batch_size) };
+        Arc::new(Self {
+            source,
+            ..self.clone()
+        })
+    }
+
+    fn filter(&self) -> Option<Arc<dyn PhysicalExpr>> {
+        self.filter.clone()
+    }
+
+    fn projection(&self) -> Option<&ProjectionExprs> {
+        self.projection.as_ref()
+    }
+
+    fn metrics(&self) -> &ExecutionPlanMetricsSet {
+        &self.metrics
+    }
+
+    fn file_type(&self) -> &str {
+        &self.file_type
+    }
+
+    fn fmt_extra(&self, t: DisplayFormatType, f: &mut Formatter) -> std::fmt::Result {
+        let extra = unsafe { (self.source.fmt_extra)(&self.source, t.into()) };
+        write!(f, "{}", extra.as_str())
+    }
+
+    fn supports_repartitioning(&self) -> bool {
+        unsafe { (self.source.supports_repartitioning)(&self.source) }
+    }
+
+    fn try_pushdown_filters(
+        &self,
+        filters: Vec<Arc<dyn PhysicalExpr>>,
+        config: &ConfigOptions,
+    ) -> Result<FilterPushdownPropagation<Arc<dyn FileSource>>> {
+        let filters = filters.into_iter().map(FFI_PhysicalExpr::from).collect();
+        let pushdown = unsafe {
+            df_result!((self.source.try_pushdown_filters)(
+                &self.source,
+                filters,
+                config.into()
+            ))?
+        };
+
+        Ok(FilterPushdownPropagation {
+            filters: pushdown
+                .filters
+                .iter()
+                .map(|pushed| {
+                    if *pushed {
+                        PushedDown::Yes
+                    } else {
+                        PushedDown::No
+                    }
+                })
+                .collect(),
+            updated_node: pushdown
+                .updated_source
+                .into_option()
+                .map(|source| (&source).into()),
+        })
+    }
+
+    fn try_pushdown_projection(
+        &self,
+        projection: &ProjectionExprs,
+    ) -> Result<Option<Arc<dyn FileSource>>> {
+        let updated = unsafe {
+            df_result!((self.source.try_pushdown_projection)(
+                &self.source,
+                projection_to_ffi(projection)
+            ))?
+        };
+        Ok(updated.into_option().map(|source| (&source).into()))
+    }
+
+    fn apply_expressions(
+        &self,
+        f: &mut dyn FnMut(&Arc<dyn PhysicalExpr>) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        let projection = self
+            .projection
+            .iter()
+            .flat_map(|p| p.iter().map(|p| &p.expr));
+        apply_expression_roots(self.filter.iter().chain(projection), f)
+    }
+}
+
+/// Returns `config` with the projection of a [`ForeignFileSource`] removed,
+/// since its foreign expressions cannot be serialized. The provider of the
+/// source applies the projection itself.
+pub(super) fn scan_config_without_projection(config: &FileScanConfig) -> FileScanConfig {
+    match config.file_source().downcast_ref::<ForeignFileSource>() {
+        Some(source) => FileScanConfigBuilder::from(config.clone())
+            .with_source(Arc::new(ForeignFileSource {
+                projection: None,
+                ..source.clone()
+            }))
+            .build(),
+        None => config.clone(),
+    }
+}
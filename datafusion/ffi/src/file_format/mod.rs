@@ -0,0 +1,704 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! FFI bindings for [`FileFormat`] and the [`FileSource`] and [`FileOpener`]
+//! used to scan its files.
+//!
+//! Scan configurations cross the boundary serialized with the physical
+//! extension codec of the format. Writing files and inferring the ordering of
+//! files are not supported through these bindings.
+//!
+//! [`FileOpener`]: datafusion_datasource::file_stream::FileOpener
+
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow::datatypes::{Schema, SchemaRef};
+use async_ffi::{FfiFuture, FutureExt};
+use async_trait::async_trait;
+use datafusion_common::parsers::CompressionTypeVariant;
+use datafusion_common::{DataFusionError, Result, Statistics};
+use datafusion_datasource::TableSchema;
+use datafusion_datasource::file::FileSource;
+use datafusion_datasource::file_compression_type::FileCompressionType;
+use datafusion_datasource::file_format::FileFormat;
+use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_execution::TaskContext;
+use datafusion_physical_plan::ExecutionPlan;
+use datafusion_proto::logical_plan::LogicalExtensionCodec;
+use datafusion_proto::physical_plan::from_proto::parse_protobuf_file_scan_config;
+use datafusion_proto::physical_plan::to_proto::serialize_file_scan_config;
+use datafusion_proto::physical_plan::{
+    DefaultPhysicalProtoConverter, PhysicalExtensionCodec, PhysicalPlanDecodeContext,
+};
+use datafusion_proto::protobuf;
+use datafusion_session::Session;
+use object_store::{ObjectMeta, ObjectStore};
+use prost::Message;
+use stabby::string::String as SString;
+use stabby::vec::Vec as SVec;
+use tokio::runtime::Handle;
+
+use crate::arrow_wrappers::WrappedSchema;
+use crate::execution::FFI_TaskContextProvider;
+use crate::execution_plan::FFI_ExecutionPlan;
+use crate::object_store::{FFI_ObjectMeta, FFI_ObjectStore};
+use crate::proto::logical_extension_codec::FFI_LogicalExtensionCodec;
+use crate::proto::physical_extension_codec::FFI_PhysicalExtensionCodec;
+use crate::session::{FFI_SessionRef, ForeignSession};
+use crate::statistics::{deserialize_statistics, serialize_statistics};
+use crate::util::{FFI_Option, FFI_Result};
+use crate::{df_result, sresult_return};
+
+mod file_opener;
+mod file_source;
+
+pub use file_opener::{FFI_FileOpener, ForeignFileOpener};
+pub use file_source::{
+    FFI_DisplayFormatType, FFI_FileSource, FFI_FilterPushdown, FFI_ProjectionExpr,
+    ForeignFileSource,
+};
+
+/// A stable struct for sharing a [`FileFormat`] across FFI boundaries.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_FileFormat {
+    pub get_ext: unsafe extern "C" fn(&Self) -> SString,
+
+    /// Returns the extension of files compressed with the given
+    /// [`CompressionTypeVariant`].
+    pub get_ext_with_compression:
+        unsafe extern "C" fn(&Self, compression: SString) -> FFI_Result<SString>,
+
+    pub compression_type: unsafe extern "C" fn(&Self) -> FFI_Option<SString>,
+
+    infer_schema: unsafe extern "C" fn(
+        &Self,
+        session: FFI_SessionRef,
+        store: FFI_ObjectStore,
+        objects: SVec<FFI_ObjectMeta>,
+    ) -> FfiFuture<FFI_Result<WrappedSchema>>,
+
+    /// Returns the serialized statistics of the object.
+    infer_stats: unsafe extern "C" fn(
+        &Self,
+        session: FFI_SessionRef,
+        store: FFI_ObjectStore,
+        table_schema: WrappedSchema,
+        object: FFI_ObjectMeta,
+    ) -> FfiFuture<FFI_Result<SVec<u8>>>,
+
+    create_physical_plan:
+        unsafe extern "C" fn(
+            &Self,
+            session: FFI_SessionRef,
+            config_serialized: SVec<u8>,
+            source: FFI_FileSource,
+        ) -> FfiFuture<FFI_Result<FFI_ExecutionPlan>>,
+
+    /// Returns the source of a table with the given file schema and partition
+    /// columns, given as the fields of a schema.
+    pub file_source: unsafe extern "C" fn(
+        &Self,
+        file_schema: WrappedSchema,
+        partition_cols: WrappedSchema,
+    ) -> FFI_FileSource,
+
+    pub with_format_options: unsafe extern "C" fn(
+        &Self,
+        options: SVec<(SString, SString)>,
+    ) -> FFI_Result<Self>,
+
+    pub supports_schema_evolution: unsafe extern "C" fn(&Self) -> bool,
+
+    /// Codec used to encode and decode logical extension nodes of sessions.
+    pub logical_codec: FFI_LogicalExtensionCodec,
+
+    /// Codec used to encode and decode scan configurations.
+    pub physical_codec: FFI_PhysicalExtensionCodec,
+
+    /// Used to create a clone on the format. This should
+    /// only need to be called by the receiver of the format.
+    pub clone: unsafe extern "C" fn(format: &Self) -> Self,
+
+    /// Release the memory of the private data when it is no longer being used.
+    pub release: unsafe extern "C" fn(arg: &mut Self),
+
+    /// Return the major DataFusion version number of this format.
+    pub version: unsafe extern "C" fn() -> u64,
+
+    /// Internal data. This is only to be accessed by the provider of the format.
+    /// A [`ForeignFileFormat`] should never attempt to access this data.
+    pub private_data: *mut c_void,
+
+    /// Utility to identify when FFI objects are accessed locally through
+    /// the foreign interface.
+    pub library_marker_id: extern "C" fn() -> usize,
+}
+
+unsafe impl Send for FFI_FileFormat {}
+unsafe impl Sync for FFI_FileFormat {}
+
+struct FileFormatPrivateData {
+    format: Arc<dyn FileFormat>,
+    runtime: Option<Handle>,
+}
+
+impl FFI_FileFormat {
+    fn inner(&self) -> &Arc<dyn FileFormat> {
+        let private_data = self.private_data as *const FileFormatPrivateData;
+        unsafe { &(*private_data).format }
+    }
+
+    fn runtime(&self) -> Option<Handle> {
+        let private_data = self.private_data as *const FileFormatPrivateData;
+        unsafe { (*private_data).runtime.clone() }
+    }
+
+    fn with_format(&self, format: Arc<dyn FileFormat>) -> Self {
+        Self::new_with_ffi_codecs(
+            format,
+            self.runtime(),
+            self.logical_codec.clone(),
+            self.physical_codec.clone(),
+        )
+    }
+}
+
+/// Serializes a scan configuration to pass it to the provider of its source.
+///
+/// The provider already applies the projection of its source, so the
+/// projection is not serialized to avoid applying it twice.
+pub(crate) fn file_scan_config_to_bytes(
+    config: &FileScanConfig,
+    codec: &dyn PhysicalExtensionCodec,
+) -> Result<SVec<u8>> {
+    let config = file_source::scan_config_without_projection(config);
+    let mut proto =
+        serialize_file_scan_config(&config, codec, &DefaultPhysicalProtoConverter {})?;
+    proto.projection_exprs = None;
+
+    Ok(proto.encode_to_vec().into_iter().collect())
+}
+
+pub(crate) fn file_scan_config_from_bytes(
+    bytes: &[u8],
+    task_ctx: &TaskContext,
+    codec: &dyn PhysicalExtensionCodec,
+    file_source: Arc<dyn FileSource>,
+) -> Result<FileScanConfig> {
+    let proto = protobuf::FileScanExecConf::decode(bytes)
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+    parse_protobuf_file_scan_config(
+        &proto,
+        &PhysicalPlanDecodeContext::new(task_ctx, codec),
+        &DefaultPhysicalProtoConverter {},
+        file_source,
+    )
+}
+
+fn objects_from_ffi(objects: SVec<FFI_ObjectMeta>) -> Result<Vec<ObjectMeta>> {
+    objects
+        .into_iter()
+        .map(|object| ObjectMeta::try_from(object).map_err(DataFusionError::from))
+        .collect()
+}
+
+unsafe extern "C" fn get_ext_fn_wrapper(format: &FFI_FileFormat) -> SString {
+    format.inner().get_ext().as_str().into()
+}
+
+unsafe extern "C" fn get_ext_with_compression_fn_wrapper(
+    format: &FFI_FileFormat,
+    compression: SString,
+) -> FFI_Result<SString> {
+    let compression =
+        sresult_return!(CompressionTypeVariant::from_str(compression.as_str()));
+    let ext = sresult_return!(
+        format
+            .inner()
+            .get_ext_with_compression(&FileCompressionType::from(compression))
+    );
+
+    FFI_Result::Ok(ext.as_str().into())
+}
+
+unsafe extern "C" fn compression_type_fn_wrapper(
+    format: &FFI_FileFormat,
+) -> FFI_Option<SString> {
+    format
+        .inner()
+        .compression_type()
+        .map(|compression| compression.get_variant().to_string().as_str().into())
+        .into()
+}
+
+unsafe extern "C" fn infer_schema_fn_wrapper(
+    format: &FFI_FileFormat,
+    session: FFI_SessionRef,
+    store: FFI_ObjectStore,
+    objects: SVec<FFI_ObjectMeta>,
+) -> FfiFuture<FFI_Result<WrappedSchema>> {
+    let format = Arc::clone(format.inner());
+
+    async move {
+        let mut foreign_session = None;
+        let session = sresult_return!(
+            session
+                .as_local()
+                .map(Ok::<&dyn Session, DataFusionError>)
+                .unwrap_or_else(|| {
+                    foreign_session = Some(ForeignSession::try_from(&session)?);
+                    Ok(foreign_session.as_ref().unwrap())
+                })
+        );
+        let store: Arc<dyn ObjectStore> = (&store).into();
+        let objects = sresult_return!(objects_from_ffi(objects));
+
+        let schema =
+            sresult_return!(format.infer_schema(session, &store, &objects).await);
+
+        FFI_Result::Ok(schema.into())
+    }
+    .into_ffi()
+}
+
+unsafe extern "C" fn infer_stats_fn_wrapper(
+    format: &FFI_FileFormat,
+    session: FFI_SessionRef,
+    store: FFI_ObjectStore,
+    table_schema: WrappedSchema,
+    object: FFI_ObjectMeta,
+) -> FfiFuture<FFI_Result<SVec<u8>>> {
+    let format = Arc::clone(format.inner());
+
+    async move {
+        let mut foreign_session = None;
+        let session = sresult_return!(
+            session
+                .as_local()
+                .map(Ok::<&dyn Session, DataFusionError>)
+                .unwrap_or_else(|| {
+                    foreign_session = Some(ForeignSession::try_from(&session)?);
+                    Ok(foreign_session.as_ref().unwrap())
+                })
+        );
+        let store: Arc<dyn ObjectStore> = (&store).into();
+        let object = sresult_return!(ObjectMeta::try_from(object));
+
+        let statistics = sresult_return!(
+            format
+                .infer_stats(session, &store, table_schema.into(), &object)
+                .await
+        );
+
+        FFI_Result::Ok(serialize_statistics(&statistics).into_iter().collect())
+    }
+    .into_ffi()
+}
+
+unsafe extern "C" fn create_physical_plan_fn_wrapper(
+    format: &FFI_FileFormat,
+    session: FFI_SessionRef,
+    config_serialized: SVec<u8>,
+    source: FFI_FileSource,
+) -> FfiFuture<FFI_Result<FFI_ExecutionPlan>> {
+    let runtime = format.runtime();
+    let physical_codec: Arc<dyn PhysicalExtensionCodec> = (&format.physical_codec).into();
+    let format = Arc::clone(format.inner());
+
+    async move {
+        let mut foreign_session = None;
+        let session = sresult_return!(
+            session
+                .as_local()
+                .map(Ok::<&dyn Session, DataFusionError>)
+                .unwrap_or_else(|| {
+                    foreign_session = Some(ForeignSession::try_from(&session)?);
+                    Ok(foreign_session.as_ref().unwrap())
+                })
+        );
+        let config = sresult_return!(file_scan_config_from_bytes(
+            config_serialized.as_slice(),
+            session.task_ctx().as_ref(),
+            physical_codec.as_ref(),
+            (&source).into(),
+        ));
+
+        let plan = sresult_return!(format.create_physical_plan(session, config).await);
+
+        FFI_Result::Ok(FFI_ExecutionPlan::new(plan, runtime))
+    }
+    .into_ffi()
+}
+
+unsafe extern "C" fn file_source_fn_wrapper(
+    format: &FFI_FileFormat,
+    file_schema: WrappedSchema,
+    partition_cols: WrappedSchema,
+) -> FFI_FileSource {
+    let partition_cols: SchemaRef = partition_cols.into();
+    let table_schema = TableSchema::builder(file_schema.into())
+        .with_table_partition_cols(partition_cols.fields().clone())
+        .build();
+    let source = format.inner().file_source(table_schema);
+
+    FFI_FileSource::new_with_ffi_codec(
+        source,
+        format.runtime(),
+        format.physical_codec.clone(),
+    )
+}
+
+unsafe extern "C" fn with_format_options_fn_wrapper(
+    format: &FFI_FileFormat,
+    options: SVec<(SString, SString)>,
+) -> FFI_Result<FFI_FileFormat> {
+    let options: HashMap<String, String> = options
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let updated = sresult_return!(format.inner().with_format_options(&options));
+
+    FFI_Result::Ok(format.with_format(updated))
+}
+
+unsafe extern "C" fn supports_schema_evolution_fn_wrapper(
+    format: &FFI_FileFormat,
+) -> bool {
+    format.inner().supports_schema_evolution()
+}
+
+unsafe extern "C" fn release_fn_wrapper(format: &mut FFI_FileFormat) {
+    unsafe {
+        debug_assert!(!format.private_data.is_null());
+        let private_data =
+            Box::from_raw(format.private_data as *mut FileFormatPrivateData);
+        drop(private_data);
+        format.private_data = std::ptr::null_mut();
+    }
+}
+
+unsafe extern "C" fn clone_fn_wrapper(format: &FFI_FileFormat) -> FFI_FileFormat {
+    format.with_format(Arc::clone(format.inner()))
+}
+
+impl Drop for FFI_FileFormat {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+impl Clone for FFI_FileFormat {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+impl FFI_FileFormat {
+    /// Creates a new [`FFI_FileFormat`] with native extension codecs.
+    ///
+    /// The physical codec encodes the scan configurations passed to the
+    /// format and its sources, and the logical codec is used for sessions
+    /// passed to the format.
+    pub fn new(
+        format: Arc<dyn FileFormat>,
+        runtime: Option<Handle>,
+        task_ctx_provider: impl Into<FFI_TaskContextProvider>,
+        logical_codec: Arc<dyn LogicalExtensionCodec>,
+        physical_codec: Arc<dyn PhysicalExtensionCodec>,
+    ) -> Self {
+        let task_ctx_provider = task_ctx_provider.into();
+        let logical_codec = FFI_LogicalExtensionCodec::new(
+            logical_codec,
+            runtime.clone(),
+            task_ctx_provider.clone(),
+        );
+        let physical_codec = FFI_PhysicalExtensionCodec::new(
+            physical_codec,
+            runtime.clone(),
+            task_ctx_provider,
+        );
+        Self::new_with_ffi_codecs(format, runtime, logical_codec, physical_codec)
+    }
+
+    /// Creates a new [`FFI_FileFormat`] using prebuilt FFI extension codecs.
+    pub fn new_with_ffi_codecs(
+        format: Arc<dyn FileFormat>,
+        runtime: Option<Handle>,
+        logical_codec: FFI_LogicalExtensionCodec,
+        physical_codec: FFI_PhysicalExtensionCodec,
+    ) -> Self {
+        if let Some(format) = format.downcast_ref::<ForeignFileFormat>() {
+            return format.0.clone();
+        }
+
+        let private_data = Box::new(FileFormatPrivateData { format, runtime });
+        let private_data = Box::into_raw(private_data) as *mut c_void;
+
+        Self {
+            get_ext: get_ext_fn_wrapper,
+            get_ext_with_compression: get_ext_with_compression_fn_wrapper,
+            compression_type: compression_type_fn_wrapper,
+            infer_schema: infer_schema_fn_wrapper,
+            infer_stats: infer_stats_fn_wrapper,
+            create_physical_plan: create_physical_plan_fn_wrapper,
+            file_source: file_source_fn_wrapper,
+            with_format_options: with_format_options_fn_wrapper,
+            supports_schema_evolution: supports_schema_evolution_fn_wrapper,
+            logical_codec,
+            physical_codec,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            version: crate::version,
+            private_data,
+            library_marker_id: crate::get_library_marker_id,
+        }
+    }
+
+    fn session_ref(&self, session: &dyn Session) -> FFI_SessionRef {
+        FFI_SessionRef::new_with_ffi_codecs(
+            session,
+            None,
+            self.logical_codec.clone(),
+            self.physical_codec.clone(),
+        )
+    }
+}
+
+/// This wrapper struct exists on the receiver side of the FFI interface, so it has
+/// no guarantees about being able to access the data in `private_data`. Any functions
+/// defined on this struct must only use the stable functions provided in
+/// FFI_FileFormat to interact with the foreign format.
+#[derive(Debug)]
+pub struct ForeignFileFormat(pub FFI_FileFormat);
+
+impl From<&FFI_FileFormat> for Arc<dyn FileFormat> {
+    fn from(format: &FFI_FileFormat) -> Self {
+        if (format.library_marker_id)() == crate::get_library_marker_id() {
+            return Arc::clone(format.inner());
+        }
+
+        Arc::new(ForeignFileFormat(format.clone()))
+    }
+}
+
+#[async_trait]
+impl FileFormat for ForeignFileFormat {
+    fn get_ext(&self) -> String {
+        unsafe { (self.0.get_ext)(&self.0).to_string() }
+    }
+
+    fn get_ext_with_compression(
+        &self,
+        file_compression_type: &FileCompressionType,
+    ) -> Result<String> {
+        let compression = file_compression_type.get_variant().to_string();
+        let ext = unsafe {
+            df_result!((self.0.get_ext_with_compression)(
+                &self.0,
+                compression.as_str().into()
+            ))?
+        };
+        Ok(ext.to_string())
+    }
+
+    fn compression_type(&self) -> Option<FileCompressionType> {
+        unsafe { (self.0.compression_type)(&self.0) }
+            .into_option()
+            .and_then(|compression| {
+                CompressionTypeVariant::from_str(compression.as_str()).ok()
+            })
+            .map(FileCompressionType::from)
+    }
+
+    async fn infer_schema(
+        &self,
+        state: &dyn Session,
+        store: &Arc<dyn ObjectStore>,
+        objects: &[ObjectMeta],
+    ) -> Result<SchemaRef> {
+        let schema = unsafe {
+            (self.0.infer_schema)(
+                &self.0,
+                self.0.session_ref(state),
+                FFI_ObjectStore::new(Arc::clone(store), None),
+                objects.iter().map(FFI_ObjectMeta::from).collect(),
+            )
+        };
+        let schema = df_result!(schema.await)?;
+        Ok(schema.into())
+    }
+
+    async fn infer_stats(
+        &self,
+        state: &dyn Session,
+        store: &Arc<dyn ObjectStore>,
+        table_schema: SchemaRef,
+        object: &ObjectMeta,
+    ) -> Result<Statistics> {
+        let statistics = unsafe {
+            (self.0.infer_stats)(
+                &self.0,
+                self.0.session_ref(state),
+                FFI_ObjectStore::new(Arc::clone(store), None),
+                table_schema.into(),
+                object.into(),
+            )
+        };
+        let statistics = df_result!(statistics.await)?;
+        deserialize_statistics(statistics.as_slice())
+    }
+
+    async fn create_physical_plan(
+        &self,
+        state: &dyn Session,
+        conf: FileScanConfig,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let codec: Arc<dyn PhysicalExtensionCodec> = (&self.0.physical_codec).into();
+        let config = file_scan_config_to_bytes(&conf, codec.as_ref())?;
+        let source = FFI_FileSource::new_with_ffi_codec(
+            Arc::clone(conf.file_source()),
+            None,
+            self.0.physical_codec.clone(),
+        );
+
+        let plan = unsafe {
+            (self.0.create_physical_plan)(
+                &self.0,
+                self.0.session_ref(state),
+                config,
+                source,
+            )
+        };
+        let plan = df_result!(plan.await)?;
+        (&plan).try_into()
+    }
+
+    fn file_source(&self, table_schema: TableSchema) -> Arc<dyn FileSource> {
+        let partition_cols = Schema::new(table_schema.table_partition_cols().clone());
+        let source = unsafe {
+            (self.0.file_source)(
+                &self.0,
+                Arc::clone(table_schema.file_schema()).into(),
+                SchemaRef::new(partition_cols).into(),
+            )
+        };
+        (&source).into()
+    }
+
+    fn with_format_options(
+        &self,
+        options: &HashMap<String, String>,
+    ) -> Result<Arc<dyn FileFormat>> {
+        let options = options
+            .iter()
+            .map(|(key, value)| (key.as_str().into(), value.as_str().into()))
+            .collect();
+        let format =
+            unsafe { df_result!((self.0.with_format_options)(&self.0, options))? };
+        Ok((&format).into())
+    }
+
+    fn supports_schema_evolution(&self) -> bool {
+        unsafe { (self.0.supports_schema_evolution)(&self.0) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::assert_batches_eq;
+    use datafusion::datasource::file_format::csv::CsvFormat;
+    use datafusion::datasource::listing::{
+        ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
+    };
+    use datafusion::prelude::SessionContext;
+    use datafusion_execution::TaskContextProvider;
+    use datafusion_proto::logical_plan::DefaultLogicalExtensionCodec;
+    use datafusion_proto::physical_plan::DefaultPhysicalExtensionCodec;
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::{ObjectStoreExt, PutPayload};
+    use url::Url;
+
+    use super::*;
+
+    fn create_foreign_format(ctx: &Arc<SessionContext>) -> Arc<dyn FileFormat> {
+        let task_ctx_provider = Arc::clone(ctx) as Arc<dyn TaskContextProvider>;
+        let mut ffi_format = FFI_FileFormat::new(
+            Arc::new(CsvFormat::default().with_has_header(true)),
+            None,
+            &task_ctx_provider,
+            Arc::new(DefaultLogicalExtensionCodec {}),
+            Arc::new(DefaultPhysicalExtensionCodec {}),
+        );
+        ffi_format.library_marker_id = crate::mock_foreign_marker_id;
+
+        (&ffi_format).into()
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_ffi_file_format() -> Result<()> {
+        let ctx = Arc::new(SessionContext::new());
+        let format = create_foreign_format(&ctx);
+        assert!(format.downcast_ref::<ForeignFileFormat>().is_some());
+        assert_eq!(format.get_ext(), "csv");
+        assert_eq!(
+            format.get_ext_with_compression(&FileCompressionType::GZIP)?,
+            "csv.gz"
+        );
+        assert!(format.compression_type().is_some());
+
+        let options = HashMap::from([("delimiter".to_string(), ";".to_string())]);
+        let with_options = format.with_format_options(&options)?;
+        assert_eq!(with_options.get_ext(), "csv");
+
+        let store = Arc::new(InMemory::new());
+        store
+            .put(
+                &Path::from("data/a.csv"),
+                PutPayload::from_static(b"a,b\n1,x\n2,y\n3,z\n"),
+            )
+            .await?;
+        ctx.register_object_store(&Url::parse("memory://").unwrap(), store);
+
+        let table_url = ListingTableUrl::parse("memory:///data/")?;
+        let options = ListingOptions::new(format).with_file_extension("csv");
+        let schema = options.infer_schema(&ctx.state(), &table_url).await?;
+        assert_eq!(schema.fields().len(), 2);
+
+        let config = ListingTableConfig::new(table_url)
+            .with_listing_options(options)
+            .with_schema(schema);
+        ctx.register_table("t", Arc::new(ListingTable::try_new(config)?))?;
+
+        let batches = ctx
+            .sql("SELECT b FROM t WHERE a > 1")
+            .await?
+            .collect()
+            .await?;
+        assert_batches_eq!(
+            ["+---+", "| b |", "+---+", "| y |", "| z |", "+---+"],
+            &batches
+        );
+
+        Ok(())
+    }
+}
@@ -25,6 +25,7 @@
 #![deny(clippy::clone_on_ref_ptr)]
 #![cfg_attr(test, allow(clippy::needless_pass_by_value))]
 
+pub mod analyzer_rule;
 pub mod arrow_wrappers;
 pub mod catalog_provider;
 pub mod catalog_provider_list;
@@ -32,8 +33,13 @@ pub mod config;
 pub mod execution;
 pub mod execution_plan;
 pub mod expr;
+#[cfg(feature = "sql")]
+pub mod expr_planner;
 pub mod ffi_option;
+pub mod file_format;
 pub mod insert_op;
+pub mod object_store;
+pub mod optimizer_rule;
 pub mod physical_expr;
 pub mod physical_optimizer;
 pub mod placement;
@@ -41,6 +47,8 @@ pub mod plan_properties;
 pub mod proto;
 pub mod query_planner;
 pub mod record_batch_stream;
+#[cfg(feature = "sql")]
+pub mod relation_planner;
 pub mod schema_provider;
 pub mod session;
 pub mod statistics;
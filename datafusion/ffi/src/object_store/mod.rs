@@ -0,0 +1,788 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! FFI bindings for [`ObjectStore`] and [`ObjectStoreRegistry`].
+//!
+//! Streams of bytes cannot cross the FFI boundary, so the payloads of get and
+//! put requests are buffered in full and listings are collected before they
+//! are returned. Tags and extensions of the request options are not forwarded.
+//!
+//! [`ObjectStoreRegistry`]: datafusion_execution::object_store::ObjectStoreRegistry
+
+use std::ffi::c_void;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::ops::Range;
+use std::sync::Arc;
+
+use async_ffi::{BorrowingFfiFuture, FfiFuture, FutureExt as _};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{
+    CopyOptions, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, ObjectStoreExt, PutMultipartOptions, PutOptions, PutPayload,
+    PutResult, RenameOptions,
+};
+use stabby::string::String as SString;
+use stabby::vec::Vec as SVec;
+use tokio::runtime::Handle;
+
+use crate::util::FFI_Option;
+
+mod multipart;
+mod registry;
+mod types;
+
+pub use multipart::{FFI_MultipartUpload, ForeignMultipartUpload};
+pub use registry::{FFI_ObjectStoreRegistry, ForeignObjectStoreRegistry};
+pub use types::*;
+
+/// A stable struct for sharing an [`ObjectStore`] across FFI boundaries.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_ObjectStore {
+    pub put_opts: unsafe extern "C" fn(
+        &Self,
+        location: SString,
+        payload: SVec<u8>,
+        mode: FFI_PutMode,
+        attributes: SVec<FFI_Attribute>,
+    )
+        -> FfiFuture<FFI_ObjectStoreResult<FFI_PutResult>>,
+
+    pub put_multipart_opts: unsafe extern "C" fn(
+        &Self,
+        location: SString,
+        attributes: SVec<FFI_Attribute>,
+    ) -> FfiFuture<
+        FFI_ObjectStoreResult<FFI_MultipartUpload>,
+    >,
+
+    pub get_opts: unsafe extern "C" fn(
+        &Self,
+        location: SString,
+        options: FFI_GetOptions,
+    )
+        -> FfiFuture<FFI_ObjectStoreResult<FFI_GetResult>>,
+
+    pub get_ranges:
+        unsafe extern "C" fn(
+            &Self,
+            location: SString,
+            ranges: SVec<FFI_Range>,
+        ) -> FfiFuture<FFI_ObjectStoreResult<SVec<SVec<u8>>>>,
+
+    pub delete: unsafe extern "C" fn(
+        &Self,
+        location: SString,
+    ) -> FfiFuture<FFI_ObjectStoreResult<()>>,
+
+    /// List all objects under `prefix` whose location is greater than `offset`.
+    pub list: unsafe extern "C" fn(
+        &Self,
+        prefix: FFI_Option<SString>,
+        offset: FFI_Option<SString>,
+    ) -> FfiFuture<
+        FFI_ObjectStoreResult<SVec<FFI_ObjectMeta>>,
+    >,
+
+    pub list_with_delimiter:
+        unsafe extern "C" fn(
+            &Self,
+            prefix: FFI_Option<SString>,
+        ) -> FfiFuture<FFI_ObjectStoreResult<FFI_ListResult>>,
+
+    pub copy: unsafe extern "C" fn(
+        &Self,
+        from: SString,
+        to: SString,
+        mode: FFI_CopyMode,
+    ) -> FfiFuture<FFI_ObjectStoreResult<()>>,
+
+    pub rename: unsafe extern "C" fn(
+        &Self,
+        from: SString,
+        to: SString,
+        mode: FFI_CopyMode,
+    ) -> FfiFuture<FFI_ObjectStoreResult<()>>,
+
+    /// The [`Display`] representation of the store.
+    pub display: unsafe extern "C" fn(&Self) -> SString,
+
+    /// Used to create a clone on the store. This should
+    /// only need to be called by the receiver of the store.
+    pub clone: unsafe extern "C" fn(store: &Self) -> Self,
+
+    /// Release the memory of the private data when it is no longer being used.
+    pub release: unsafe extern "C" fn(arg: &mut Self),
+
+    /// Return the major DataFusion version number of this store.
+    pub version: unsafe extern "C" fn() -> u64,
+
+    /// Internal data. This is only to be accessed by the provider of the store.
+    /// A [`ForeignObjectStore`] should never attempt to access this data.
+    pub private_data: *mut c_void,
+
+    /// Utility to identify when FFI objects are accessed locally through
+    /// the foreign interface.
+    pub library_marker_id: extern "C" fn() -> usize,
+}
+
+unsafe impl Send for FFI_ObjectStore {}
+unsafe impl Sync for FFI_ObjectStore {}
+
+struct ObjectStorePrivateData {
+    store: Arc<dyn ObjectStore>,
+    runtime: Option<Handle>,
+}
+
+impl FFI_ObjectStore {
+    fn inner(&self) -> &Arc<dyn ObjectStore> {
+        let private_data = self.private_data as *const ObjectStorePrivateData;
+        unsafe { &(*private_data).store }
+    }
+
+    fn runtime(&self) -> Option<Handle> {
+        let private_data = self.private_data as *const ObjectStorePrivateData;
+        unsafe { (*private_data).runtime.clone() }
+    }
+}
+
+/// Converts a future of the provider's store into an FFI future. The future
+/// is polled within `runtime`, if given, since stores such as those backed
+/// by HTTP clients expect to run within the runtime of their own library.
+pub(crate) fn into_ffi_future<'a, T: 'a>(
+    runtime: Option<Handle>,
+    future: impl Future<Output = object_store::Result<T>> + Send + 'a,
+) -> BorrowingFfiFuture<'a, FFI_ObjectStoreResult<T>> {
+    let mut future = Box::pin(future);
+    futures::future::poll_fn(move |cx| {
+        let _guard = runtime.as_ref().map(|rt| rt.enter());
+        future.as_mut().poll(cx).map(Into::into)
+    })
+    .into_ffi()
+}
+
+pub(crate) fn payload_to_ffi(payload: &PutPayload) -> SVec<u8> {
+    payload
+        .iter()
+        .flat_map(|bytes| bytes.iter().copied())
+        .collect()
+}
+
+#[expect(clippy::result_large_err)]
+fn prefix_from_ffi(prefix: &FFI_Option<SString>) -> object_store::Result<Option<Path>> {
+    prefix.as_ref().map(path_from_ffi).transpose()
+}
+
+unsafe extern "C" fn put_opts_fn_wrapper(
+    store: &FFI_ObjectStore,
+    location: SString,
+    payload: SVec<u8>,
+    mode: FFI_PutMode,
+    attributes: SVec<FFI_Attribute>,
+) -> FfiFuture<FFI_ObjectStoreResult<FFI_PutResult>> {
+    let runtime = store.runtime();
+    let store = Arc::clone(store.inner());
+
+    into_ffi_future(runtime, async move {
+        let location = path_from_ffi(&location)?;
+        let options = PutOptions {
+            mode: mode.into(),
+            attributes: attributes_from_ffi(attributes),
+            ..Default::default()
+        };
+        let result = store
+            .put_opts(&location, payload.to_vec().into(), options)
+            .await?;
+        Ok(FFI_PutResult::from(&result))
+    })
+}
+
+unsafe extern "C" fn put_multipart_opts_fn_wrapper(
+    store: &FFI_ObjectStore,
+    location: SString,
+    attributes: SVec<FFI_Attribute>,
+) -> FfiFuture<FFI_ObjectStoreResult<FFI_MultipartUpload>> {
+    let runtime = store.runtime();
+    let store = Arc::clone(store.inner());
+
+    into_ffi_future(runtime.clone(), async move {
+        let location = path_from_ffi(&location)?;
+        let options = PutMultipartOptions {
+            attributes: attributes_from_ffi(attributes),
+            ..Default::default()
+        };
+        let upload = store.put_multipart_opts(&location, options).await?;
+        Ok(FFI_MultipartUpload::new(upload, runtime))
+    })
+}
+
+unsafe extern "C" fn get_opts_fn_wrapper(
+    store: &FFI_ObjectStore,
+    location: SString,
+    options: FFI_GetOptions,
+) -> FfiFuture<FFI_ObjectStoreResult<FFI_GetResult>> {
+    let runtime = store.runtime();
+    let store = Arc::clone(store.inner());
+
+    into_ffi_future(runtime, async move {
+        let location = path_from_ffi(&location)?;
+        let result = store.get_opts(&location, options.try_into()?).await?;
+        let meta = FFI_ObjectMeta::from(&result.meta);
+        let range = FFI_Range::from(&result.range);
+        let attributes = attributes_to_ffi(&result.attributes);
+        let payload = result.bytes().await?;
+
+        Ok(FFI_GetResult {
+            payload: payload.iter().copied().collect(),
+            meta,
+            range,
+            attributes,
+        })
+    })
+}
+
+unsafe extern "C" fn get_ranges_fn_wrapper(
+    store: &FFI_ObjectStore,
+    location: SString,
+    ranges: SVec<FFI_Range>,
+) -> FfiFuture<FFI_ObjectStoreResult<SVec<SVec<u8>>>> {
+    let runtime = store.runtime();
+    let store = Arc::clone(store.inner());
+
+    into_ffi_future(runtime, async move {
+        let location = path_from_ffi(&location)?;
+        let ranges: Vec<Range<u64>> = ranges.into_iter().map(Into::into).collect();
+        let payloads = store.get_ranges(&location, &ranges).await?;
+        Ok(payloads
+            .into_iter()
+            .map(|payload| payload.iter().copied().collect())
+            .collect())
+    })
+}
+
+unsafe extern "C" fn delete_fn_wrapper(
+    store: &FFI_ObjectStore,
+    location: SString,
+) -> FfiFuture<FFI_ObjectStoreResult<()>> {
+    let runtime = store.runtime();
+    let store = Arc::clone(store.inner());
+
+    into_ffi_future(runtime, async move {
+        store.delete(&path_from_ffi(&location)?).await
+    })
+}
+
+unsafe extern "C" fn list_fn_wrapper(
+    store: &FFI_ObjectStore,
+    prefix: FFI_Option<SString>,
+    offset: FFI_Option<SString>,
+) -> FfiFuture<FFI_ObjectStoreResult<SVec<FFI_ObjectMeta>>> {
+    let runtime = store.runtime();
+    let store = Arc::clone(store.inner());
+
+    into_ffi_future(runtime, async move {
+        let prefix = prefix_from_ffi(&prefix)?;
+        let stream = match prefix_from_ffi(&offset)? {
+            Some(offset) => store.list_with_offset(prefix.as_ref(), &offset),
+            None => store.list(prefix.as_ref()),
+        };
+        stream
+            .map_ok(|meta| FFI_ObjectMeta::from(&meta))
+            .try_collect::<Vec<_>>()
+            .await
+            .map(|objects| objects.into_iter().collect())
+    })
+}
+
+unsafe extern "C" fn list_with_delimiter_fn_wrapper(
+    store: &FFI_ObjectStore,
+    prefix: FFI_Option<SString>,
+) -> FfiFuture<FFI_ObjectStoreResult<FFI_ListResult>> {
+    let runtime = store.runtime();
+    let store = Arc::clone(store.inner());
+
+    into_ffi_future(runtime, async move {
+        let prefix = prefix_from_ffi(&prefix)?;
+        let result = store.list_with_delimiter(prefix.as_ref()).await?;
+        Ok(FFI_ListResult::from(&result))
+    })
+}
+
+unsafe extern "C" fn copy_fn_wrapper(
+    store: &FFI_ObjectStore,
+    from: SString,
+    to: SString,
+    mode: FFI_CopyMode,
+) -> FfiFuture<FFI_ObjectStoreResult<()>> {
+    let runtime = store.runtime();
+    let store = Arc::clone(store.inner());
+
+    into_ffi_future(runtime, async move {
+        let options = CopyOptions::new().with_mode(mode.into());
+        store
+            .copy_opts(&path_from_ffi(&from)?, &path_from_ffi(&to)?, options)
+            .await
+    })
+}
+
+unsafe extern "C" fn rename_fn_wrapper(
+    store: &FFI_ObjectStore,
+    from: SString,
+    to: SString,
+    mode: FFI_CopyMode,
+) -> FfiFuture<FFI_ObjectStoreResult<()>> {
+    let runtime = store.runtime();
+    let store = Arc::clone(store.inner());
+
+    into_ffi_future(runtime, async move {
+        let options = RenameOptions::new().with_target_mode(mode.into());
+        store
+            .rename_opts(&path_from_ffi(&from)?, &path_from_ffi(&to)?, options)
+            .await
+    })
+}
+
+unsafe extern "C" fn display_fn_wrapper(store: &FFI_ObjectStore) -> SString {
+    store.inner().to_string().as_str().into()
+}
+
+unsafe extern "C" fn release_fn_wrapper(store: &mut FFI_ObjectStore) {
+    unsafe {
+        debug_assert!(!store.private_data.is_null());
+        let private_data =
+            Box::from_raw(store.private_data as *mut ObjectStorePrivateData);
+        drop(private_data);
+        store.private_data = std::ptr::null_mut();
+    }
+}
+
+unsafe extern "C" fn clone_fn_wrapper(store: &FFI_ObjectStore) -> FFI_ObjectStore {
+    FFI_ObjectStore::new(Arc::clone(store.inner()), store.runtime())
+}
+
+impl Drop for FFI_ObjectStore {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+impl Clone for FFI_ObjectStore {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+impl FFI_ObjectStore {
+    /// Creates a new [`FFI_ObjectStore`].
+    pub fn new(store: Arc<dyn ObjectStore>, runtime: Option<Handle>) -> Self {
+        let private_data = Box::new(ObjectStorePrivateData { store, runtime });
+        let private_data = Box::into_raw(private_data) as *mut c_void;
+
+        Self {
+            put_opts: put_opts_fn_wrapper,
+            put_multipart_opts: put_multipart_opts_fn_wrapper,
+            get_opts: get_opts_fn_wrapper,
+            get_ranges: get_ranges_fn_wrapper,
+            delete: delete_fn_wrapper,
+            list: list_fn_wrapper,
+            list_with_delimiter: list_with_delimiter_fn_wrapper,
+            copy: copy_fn_wrapper,
+            rename: rename_fn_wrapper,
+            display: display_fn_wrapper,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            version: crate::version,
+            private_data,
+            library_marker_id: crate::get_library_marker_id,
+        }
+    }
+}
+
+/// This wrapper struct exists on the receiver side of the FFI interface, so it has
+/// no guarantees about being able to access the data in `private_data`. Any functions
+/// defined on this struct must only use the stable functions provided in
+/// FFI_ObjectStore to interact with the foreign store.
+#[derive(Debug)]
+pub struct ForeignObjectStore(FFI_ObjectStore);
+
+impl From<&FFI_ObjectStore> for Arc<dyn ObjectStore> {
+    fn from(store: &FFI_ObjectStore) -> Self {
+        if (store.library_marker_id)() == crate::get_library_marker_id() {
+            return Arc::clone(store.inner());
+        }
+
+        Arc::new(ForeignObjectStore(store.clone()))
+    }
+}
+
+impl Display for ForeignObjectStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let display = unsafe { (self.0.display)(&self.0) };
+        write!(f, "{}", display.as_str())
+    }
+}
+
+impl ForeignObjectStore {
+    fn list_objects(
+        &self,
+        prefix: Option<&Path>,
+        offset: Option<&Path>,
+    ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        let objects = unsafe {
+            (self.0.list)(
+                &self.0,
+                prefix.map(path_to_ffi).into(),
+                offset.map(path_to_ffi).into(),
+            )
+        };
+
+        futures::stream::once(objects)
+            .flat_map(|objects| {
+                let objects = match object_store::Result::from(objects) {
+                    Ok(objects) => objects
+                        .into_iter()
+                        .map(ObjectMeta::try_from)
+                        .collect::<Vec<_>>(),
+                    Err(e) => vec![Err(e)],
+                };
+                futures::stream::iter(objects)
+            })
+            .boxed()
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ForeignObjectStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> object_store::Result<PutResult> {
+        let result: object_store::Result<FFI_PutResult> = unsafe {
+            (self.0.put_opts)(
+                &self.0,
+                path_to_ffi(location),
+                payload_to_ffi(&payload),
+                (&opts.mode).into(),
+                attributes_to_ffi(&opts.attributes),
+            )
+        }
+        .await
+        .into();
+        result.map(Into::into)
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> object_store::Result<Box<dyn MultipartUpload>> {
+        let upload: object_store::Result<FFI_MultipartUpload> = unsafe {
+            (self.0.put_multipart_opts)(
+                &self.0,
+                path_to_ffi(location),
+                attributes_to_ffi(&opts.attributes),
+            )
+        }
+        .await
+        .into();
+        upload.map(Into::into)
+    }
+
+    async fn get_opts(
+        &self,
+        location: &Path,
+        options: GetOptions,
+    ) -> object_store::Result<GetResult> {
+        let result: object_store::Result<FFI_GetResult> = unsafe {
+            (self.0.get_opts)(&self.0, path_to_ffi(location), (&options).into())
+        }
+        .await
+        .into();
+        let FFI_GetResult {
+            payload,
+            meta,
+            range,
+            attributes,
+        } = result?;
+
+        let payload = Bytes::from(payload.to_vec());
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(
+                futures::stream::once(async move { Ok(payload) }).boxed(),
+            ),
+            meta: meta.try_into()?,
+            range: range.into(),
+            attributes: attributes_from_ffi(attributes),
+        })
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[Range<u64>],
+    ) -> object_store::Result<Vec<Bytes>> {
+        let payloads: object_store::Result<SVec<SVec<u8>>> = unsafe {
+            (self.0.get_ranges)(
+                &self.0,
+                path_to_ffi(location),
+                ranges.iter().map(FFI_Range::from).collect(),
+            )
+        }
+        .await
+        .into();
+        Ok(payloads?
+            .iter()
+            .map(|payload| Bytes::from(payload.to_vec()))
+            .collect())
+    }
+
+    fn delete_stream(
+        &self,
+        locations: BoxStream<'static, object_store::Result<Path>>,
+    ) -> BoxStream<'static, object_store::Result<Path>> {
+        let store = self.0.clone();
+        locations
+            .and_then(move |location| {
+                let deleted = unsafe { (store.delete)(&store, path_to_ffi(&location)) };
+                async move {
+                    object_store::Result::from(deleted.await)?;
+                    Ok(location)
+                }
+            })
+            .boxed()
+    }
+
+    fn list(
+        &self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        self.list_objects(prefix, None)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, object_store::Result<ObjectMeta>> {
+        self.list_objects(prefix, Some(offset))
+    }
+
+    async fn list_with_delimiter(
+        &self,
+        prefix: Option<&Path>,
+    ) -> object_store::Result<ListResult> {
+        let result: object_store::Result<FFI_ListResult> = unsafe {
+            (self.0.list_with_delimiter)(&self.0, prefix.map(path_to_ffi).into())
+        }
+        .await
+        .into();
+        result?.try_into()
+    }
+
+    async fn copy_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: CopyOptions,
+    ) -> object_store::Result<()> {
+        unsafe {
+            (self.0.copy)(
+                &self.0,
+                path_to_ffi(from),
+                path_to_ffi(to),
+                options.mode.into(),
+            )
+        }
+        .await
+        .into()
+    }
+
+    async fn rename_opts(
+        &self,
+        from: &Path,
+        to: &Path,
+        options: RenameOptions,
+    ) -> object_store::Result<()> {
+        unsafe {
+            (self.0.rename)(
+                &self.0,
+                path_to_ffi(from),
+                path_to_ffi(to),
+                options.target_mode.into(),
+            )
+        }
+        .await
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::memory::InMemory;
+    use object_store::{PutMode, UpdateVersion};
+
+    use datafusion_common::Result;
+
+    use super::*;
+
+    fn foreign_store() -> (Arc<dyn ObjectStore>, Arc<dyn ObjectStore>) {
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        let mut ffi_store = FFI_ObjectStore::new(Arc::clone(&store), None);
+        ffi_store.library_marker_id = crate::mock_foreign_marker_id;
+
+        let foreign_store: Arc<dyn ObjectStore> =
+            Arc::new(ForeignObjectStore(ffi_store.clone()));
+        (store, foreign_store)
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_ffi_object_store() -> Result<()> {
+        let (store, foreign_store) = foreign_store();
+        assert_eq!(foreign_store.to_string(), store.to_string());
+
+        let location = Path::from("data/a.txt");
+        foreign_store
+            .put(&location, PutPayload::from_static(b"hello world"))
+            .await?;
+
+        let bytes = store.get(&location).await?.bytes().await?;
+        assert_eq!(bytes.as_ref(), b"hello world");
+
+        let result = foreign_store.get(&location).await?;
+        assert_eq!(result.meta.location, location);
+        assert_eq!(result.meta.size, 11);
+        assert_eq!(result.bytes().await?.as_ref(), b"hello world");
+
+        let ranges = foreign_store.get_ranges(&location, &[0..5, 6..11]).await?;
+        assert_eq!(ranges, vec![Bytes::from("hello"), Bytes::from("world")]);
+
+        let range = foreign_store.get_range(&location, 6..11).await?;
+        assert_eq!(range.as_ref(), b"world");
+
+        // Modification times cross the boundary with microsecond precision
+        let head = foreign_store.head(&location).await?;
+        let expected = store.head(&location).await?;
+        assert_eq!(head.location, expected.location);
+        assert_eq!(head.size, expected.size);
+        assert_eq!(head.e_tag, expected.e_tag);
+
+        let copied = Path::from("data/b.txt");
+        foreign_store.copy(&location, &copied).await?;
+        let renamed = Path::from("other/c.txt");
+        foreign_store.rename(&copied, &renamed).await?;
+
+        let mut listed = foreign_store
+            .list(Some(&Path::from("data")))
+            .map_ok(|meta| meta.location)
+            .try_collect::<Vec<_>>()
+            .await?;
+        listed.sort();
+        assert_eq!(listed, vec![location.clone()]);
+
+        let listed = foreign_store
+            .list_with_offset(None, &location)
+            .map_ok(|meta| meta.location)
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert_eq!(listed, vec![renamed.clone()]);
+
+        let result = foreign_store.list_with_delimiter(None).await?;
+        let mut prefixes = result.common_prefixes;
+        prefixes.sort();
+        assert_eq!(prefixes, vec![Path::from("data"), Path::from("other")]);
+        assert!(result.objects.is_empty());
+
+        foreign_store.delete(&renamed).await?;
+        assert!(store.head(&renamed).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ffi_object_store_errors() -> Result<()> {
+        let (_, foreign_store) = foreign_store();
+
+        let location = Path::from("missing.txt");
+        let err = foreign_store.get(&location).await.unwrap_err();
+        assert!(matches!(err, object_store::Error::NotFound { .. }), "{err}");
+
+        foreign_store
+            .put(&location, PutPayload::from_static(b"data"))
+            .await?;
+        let err = foreign_store
+            .put_opts(
+                &location,
+                PutPayload::from_static(b"data"),
+                PutMode::Create.into(),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, object_store::Error::AlreadyExists { .. }),
+            "{err}"
+        );
+
+        let err = foreign_store
+            .put_opts(
+                &location,
+                PutPayload::from_static(b"data"),
+                PutMode::Update(UpdateVersion {
+                    e_tag: Some("not-a-tag".to_string()),
+                    version: None,
+                })
+                .into(),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, object_store::Error::Precondition { .. }),
+            "{err}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ffi_multipart_upload() -> Result<()> {
+        let (store, foreign_store) = foreign_store();
+
+        let location = Path::from("multipart.txt");
+        let mut upload = foreign_store.put_multipart(&location).await?;
+        upload.put_part(PutPayload::from_static(b"hello ")).await?;
+        upload.put_part(PutPayload::from_static(b"world")).await?;
+        upload.complete().await?;
+
+        let bytes = store.get(&location).await?.bytes().await?;
+        assert_eq!(bytes.as_ref(), b"hello world");
+
+        let mut upload = foreign_store
+            .put_multipart(&Path::from("aborted.txt"))
+            .await?;
+        upload.put_part(PutPayload::from_static(b"data")).await?;
+        upload.abort().await?;
+        assert!(store.head(&Path::from("aborted.txt")).await.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::ffi::c_void;
+
+use async_ffi::{BorrowingFfiFuture, FfiFuture};
+use async_trait::async_trait;
+use futures::FutureExt;
+use object_store::{MultipartUpload, PutPayload, PutResult, UploadPart};
+use stabby::vec::Vec as SVec;
+use tokio::runtime::Handle;
+
+use super::{FFI_ObjectStoreResult, FFI_PutResult, into_ffi_future, payload_to_ffi};
+
+/// A stable struct for sharing a [`MultipartUpload`] across FFI boundaries.
+///
+/// An upload is owned by a single writer, so unlike most FFI structs in this
+/// crate it cannot be cloned.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_MultipartUpload {
+    /// Upload the next part of the object.
+    pub put_part: unsafe extern "C" fn(
+        &mut Self,
+        payload: SVec<u8>,
+    ) -> FfiFuture<FFI_ObjectStoreResult<()>>,
+
+    /// Complete the upload once all parts have been written.
+    pub complete: unsafe extern "C" fn(
+        &mut Self,
+    ) -> BorrowingFfiFuture<
+        '_,
+        FFI_ObjectStoreResult<FFI_PutResult>,
+    >,
+
+    /// Abort the upload and clean up the parts written so far.
+    pub abort: unsafe extern "C" fn(
+        &mut Self,
+    )
+        -> BorrowingFfiFuture<'_, FFI_ObjectStoreResult<()>>,
+
+    /// Release the memory of the private data when it is no longer being used.
+    pub release: unsafe extern "C" fn(arg: &mut Self),
+
+    /// Return the major DataFusion version number of this upload.
+    pub version: unsafe extern "C" fn() -> u64,
+
+    /// Internal data. This is only to be accessed by the provider of the upload.
+    /// A [`ForeignMultipartUpload`] should never attempt to access this data.
+    pub private_data: *mut c_void,
+
+    /// Utility to identify when FFI objects are accessed locally through
+    /// the foreign interface.
+    pub library_marker_id: extern "C" fn() -> usize,
+}
+
+unsafe impl Send for FFI_MultipartUpload {}
+unsafe impl Sync for FFI_MultipartUpload {}
+
+struct MultipartUploadPrivateData {
+    upload: Box<dyn MultipartUpload>,
+    runtime: Option<Handle>,
+}
+
+impl FFI_MultipartUpload {
+    /// Creates a new [`FFI_MultipartUpload`].
+    pub fn new(upload: Box<dyn MultipartUpload>, runtime: Option<Handle>) -> Self {
+        let private_data = Box::new(MultipartUploadPrivateData { upload, runtime });
+        let private_data = Box::into_raw(private_data) as *mut c_void;
+
+        Self {
+            put_part: put_part_fn_wrapper,
+            complete: complete_fn_wrapper,
+            abort: abort_fn_wrapper,
+            release: release_fn_wrapper,
+            version: crate::version,
+            private_data,
+            library_marker_id: crate::get_library_marker_id,
+        }
+    }
+
+    fn private_data_mut(&mut self) -> &mut MultipartUploadPrivateData {
+        unsafe { &mut *(self.private_data as *mut MultipartUploadPrivateData) }
+    }
+}
+
+unsafe extern "C" fn put_part_fn_wrapper(
+    upload: &mut FFI_MultipartUpload,
+    payload: SVec<u8>,
+) -> FfiFuture<FFI_ObjectStoreResult<()>> {
+    let private_data = upload.private_data_mut();
+    let part = private_data
+        .upload
+        .put_part(PutPayload::from(payload.to_vec()));
+
+    into_ffi_future(private_data.runtime.clone(), part)
+}
+
+unsafe extern "C" fn complete_fn_wrapper(
+    upload: &mut FFI_MultipartUpload,
+) -> BorrowingFfiFuture<'_, FFI_ObjectStoreResult<FFI_PutResult>> {
+    let private_data = upload.private_data_mut();
+    let runtime = private_data.runtime.clone();
+    let upload = &mut private_data.upload;
+
+    into_ffi_future(runtime, async move {
+        upload
+            .complete()
+            .await
+            .map(|result| FFI_PutResult::from(&result))
+    })
+}
+
+unsafe extern "C" fn abort_fn_wrapper(
+    upload: &mut FFI_MultipartUpload,
+) -> BorrowingFfiFuture<'_, FFI_ObjectStoreResult<()>> {
+    let private_data = upload.private_data_mut();
+    let runtime = private_data.runtime.clone();
+
+    into_ffi_future(runtime, private_data.upload.abort())
+}
+
+unsafe extern "C" fn release_fn_wrapper(upload: &mut FFI_MultipartUpload) {
+    unsafe {
+        debug_assert!(!upload.private_data.is_null());
+        let private_data =
+            Box::from_raw(upload.private_data as *mut MultipartUploadPrivateData);
+        drop(private_data);
+        upload.private_data = std::ptr::null_mut();
+    }
+}
+
+impl Drop for FFI_MultipartUpload {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+/// This wrapper struct exists on the receiver side of the FFI interface, so it has
+/// no guarantees about being able to access the data in `private_data`. Any functions
+/// defined on this struct must only use the stable functions provided in
+/// FFI_MultipartUpload to interact with the foreign upload.
+#[derive(Debug)]
+pub struct ForeignMultipartUpload(FFI_MultipartUpload);
+
+impl From<FFI_MultipartUpload> for Box<dyn MultipartUpload> {
+    fn from(upload: FFI_MultipartUpload) -> Self {
+        Box::new(ForeignMultipartUpload(upload))
+    }
+}
+
+#[async_trait]
+impl MultipartUpload for ForeignMultipartUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let part = unsafe { (self.0.put_part)(&mut self.0, payload_to_ffi(&data)) };
+        part.map(Into::into).boxed()
+    }
+
+    async fn complete(&mut self) -> object_store::Result<PutResult> {
+        let result: object_store::Result<FFI_PutResult> =
+            unsafe { (self.0.complete)(&mut self.0) }.await.into();
+        result.map(Into::into)
+    }
+
+    async fn abort(&mut self) -> object_store::Result<()> {
+        unsafe { (self.0.abort)(&mut self.0) }.await.into()
+    }
+}
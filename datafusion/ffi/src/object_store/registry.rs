@@ -0,0 +1,273 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::ffi::c_void;
+use std::sync::Arc;
+
+use datafusion_common::{DataFusionError, Result};
+use datafusion_execution::object_store::ObjectStoreRegistry;
+use object_store::ObjectStore;
+use stabby::string::String as SString;
+use tokio::runtime::Handle;
+use url::Url;
+
+use super::FFI_ObjectStore;
+use crate::util::{FFI_Option, FFI_Result};
+use crate::{df_result, sresult_return};
+
+/// A stable struct for sharing an [`ObjectStoreRegistry`] across FFI boundaries.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_ObjectStoreRegistry {
+    /// Register `store` for `url`, returning the store previously
+    /// registered for it.
+    pub register_store: unsafe extern "C" fn(
+        &Self,
+        url: SString,
+        store: FFI_ObjectStore,
+    )
+        -> FFI_Result<FFI_Option<FFI_ObjectStore>>,
+
+    pub deregister_store:
+        unsafe extern "C" fn(&Self, url: SString) -> FFI_Result<FFI_ObjectStore>,
+
+    pub get_store:
+        unsafe extern "C" fn(&Self, url: SString) -> FFI_Result<FFI_ObjectStore>,
+
+    /// Used to create a clone on the registry. This should
+    /// only need to be called by the receiver of the registry.
+    pub clone: unsafe extern "C" fn(registry: &Self) -> Self,
+
+    /// Release the memory of the private data when it is no longer being used.
+    pub release: unsafe extern "C" fn(arg: &mut Self),
+
+    /// Return the major DataFusion version number of this registry.
+    pub version: unsafe extern "C" fn() -> u64,
+
+    /// Internal data. This is only to be accessed by the provider of the registry.
+    /// A [`ForeignObjectStoreRegistry`] should never attempt to access this data.
+    pub private_data: *mut c_void,
+
+    /// Utility to identify when FFI objects are accessed locally through
+    /// the foreign interface.
+    pub library_marker_id: extern "C" fn() -> usize,
+}
+
+unsafe impl Send for FFI_ObjectStoreRegistry {}
+unsafe impl Sync for FFI_ObjectStoreRegistry {}
+
+struct RegistryPrivateData {
+    registry: Arc<dyn ObjectStoreRegistry>,
+    runtime: Option<Handle>,
+}
+
+impl FFI_ObjectStoreRegistry {
+    fn inner(&self) -> &Arc<dyn ObjectStoreRegistry> {
+        let private_data = self.private_data as *const RegistryPrivateData;
+        unsafe { &(*private_data).registry }
+    }
+
+    fn runtime(&self) -> Option<Handle> {
+        let private_data = self.private_data as *const RegistryPrivateData;
+        unsafe { (*private_data).runtime.clone() }
+    }
+}
+
+fn parse_url(url: &SString) -> Result<Url> {
+    Url::parse(url.as_str()).map_err(|e| DataFusionError::External(Box::new(e)))
+}
+
+unsafe extern "C" fn register_store_fn_wrapper(
+    registry: &FFI_ObjectStoreRegistry,
+    url: SString,
+    store: FFI_ObjectStore,
+) -> FFI_Result<FFI_Option<FFI_ObjectStore>> {
+    let runtime = registry.runtime();
+    let url = sresult_return!(parse_url(&url));
+    let store: Arc<dyn ObjectStore> = (&store).into();
+
+    FFI_Result::Ok(
+        registry
+            .inner()
+            .register_store(&url, store)
+            .map(|store| FFI_ObjectStore::new(store, runtime))
+            .into(),
+    )
+}
+
+unsafe extern "C" fn deregister_store_fn_wrapper(
+    registry: &FFI_ObjectStoreRegistry,
+    url: SString,
+) -> FFI_Result<FFI_ObjectStore> {
+    let runtime = registry.runtime();
+    let url = sresult_return!(parse_url(&url));
+    let store = sresult_return!(registry.inner().deregister_store(&url));
+
+    FFI_Result::Ok(FFI_ObjectStore::new(store, runtime))
+}
+
+unsafe extern "C" fn get_store_fn_wrapper(
+    registry: &FFI_ObjectStoreRegistry,
+    url: SString,
+) -> FFI_Result<FFI_ObjectStore> {
+    let runtime = registry.runtime();
+    let url = sresult_return!(parse_url(&url));
+    let store = sresult_return!(registry.inner().get_store(&url));
+
+    FFI_Result::Ok(FFI_ObjectStore::new(store, runtime))
+}
+
+unsafe extern "C" fn release_fn_wrapper(registry: &mut FFI_ObjectStoreRegistry) {
+    unsafe {
+        debug_assert!(!registry.private_data.is_null());
+        let private_data =
+            Box::from_raw(registry.private_data as *mut RegistryPrivateData);
+        drop(private_data);
+        registry.private_data = std::ptr::null_mut();
+    }
+}
+
+unsafe extern "C" fn clone_fn_wrapper(
+    registry: &FFI_ObjectStoreRegistry,
+) -> FFI_ObjectStoreRegistry {
+    FFI_ObjectStoreRegistry::new(Arc::clone(registry.inner()), registry.runtime())
+}
+
+impl Drop for FFI_ObjectStoreRegistry {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+impl Clone for FFI_ObjectStoreRegistry {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+impl FFI_ObjectStoreRegistry {
+    /// Creates a new [`FFI_ObjectStoreRegistry`].
+    pub fn new(registry: Arc<dyn ObjectStoreRegistry>, runtime: Option<Handle>) -> Self {
+        let private_data = Box::new(RegistryPrivateData { registry, runtime });
+        let private_data = Box::into_raw(private_data) as *mut c_void;
+
+        Self {
+            register_store: register_store_fn_wrapper,
+            deregister_store: deregister_store_fn_wrapper,
+            get_store: get_store_fn_wrapper,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            version: crate::version,
+            private_data,
+            library_marker_id: crate::get_library_marker_id,
+        }
+    }
+}
+
+/// This wrapper struct exists on the receiver side of the FFI interface, so it has
+/// no guarantees about being able to access the data in `private_data`. Any functions
+/// defined on this struct must only use the stable functions provided in
+/// FFI_ObjectStoreRegistry to interact with the foreign registry.
+#[derive(Debug)]
+pub struct ForeignObjectStoreRegistry(FFI_ObjectStoreRegistry);
+
+impl From<&FFI_ObjectStoreRegistry> for Arc<dyn ObjectStoreRegistry> {
+    fn from(registry: &FFI_ObjectStoreRegistry) -> Self {
+        if (registry.library_marker_id)() == crate::get_library_marker_id() {
+            return Arc::clone(registry.inner());
+        }
+
+        Arc::new(ForeignObjectStoreRegistry(registry.clone()))
+    }
+}
+
+impl ObjectStoreRegistry for ForeignObjectStoreRegistry {
+    fn register_store(
+        &self,
+        url: &Url,
+        store: Arc<dyn ObjectStore>,
+    ) -> Option<Arc<dyn ObjectStore>> {
+        let store = FFI_ObjectStore::new(store, None);
+        let previous =
+            unsafe { (self.0.register_store)(&self.0, url.as_str().into(), store) };
+        // The registry only fails to parse URLs that were valid on this side,
+        // which leaves nothing to report through the infallible signature
+        previous
+            .into_result()
+            .ok()
+            .and_then(|previous| previous.into_option())
+            .map(|previous| (&previous).into())
+    }
+
+    fn deregister_store(&self, url: &Url) -> Result<Arc<dyn ObjectStore>> {
+        let store = df_result!(unsafe {
+            (self.0.deregister_store)(&self.0, url.as_str().into())
+        })?;
+        Ok((&store).into())
+    }
+
+    fn get_store(&self, url: &Url) -> Result<Arc<dyn ObjectStore>> {
+        let store =
+            df_result!(unsafe { (self.0.get_store)(&self.0, url.as_str().into()) })?;
+        Ok((&store).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion_execution::object_store::DefaultObjectStoreRegistry;
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::{ObjectStoreExt, PutPayload};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_round_trip_ffi_object_store_registry() -> Result<()> {
+        let registry: Arc<dyn ObjectStoreRegistry> =
+            Arc::new(DefaultObjectStoreRegistry::new());
+        let mut ffi_registry = FFI_ObjectStoreRegistry::new(Arc::clone(&registry), None);
+        ffi_registry.library_marker_id = crate::mock_foreign_marker_id;
+
+        let foreign_registry: Arc<dyn ObjectStoreRegistry> = (&ffi_registry).into();
+
+        let url = Url::parse("memory://bucket").unwrap();
+        assert!(foreign_registry.get_store(&url).is_err());
+
+        let store: Arc<dyn ObjectStore> = Arc::new(InMemory::new());
+        assert!(
+            foreign_registry
+                .register_store(&url, Arc::clone(&store))
+                .is_none()
+        );
+
+        let location = Path::from("a.txt");
+        foreign_registry
+            .get_store(&url)?
+            .put(&location, PutPayload::from_static(b"data"))
+            .await?;
+        assert_eq!(store.head(&location).await?.size, 4);
+
+        let previous = foreign_registry.register_store(&url, Arc::new(InMemory::new()));
+        assert!(previous.is_some());
+
+        foreign_registry.deregister_store(&url)?;
+        assert!(registry.get_store(&url).is_err());
+
+        Ok(())
+    }
+}
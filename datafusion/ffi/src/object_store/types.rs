@@ -0,0 +1,537 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::ops::Range;
+
+use chrono::{DateTime, Utc};
+use object_store::path::Path;
+use object_store::{
+    Attribute, AttributeValue, Attributes, CopyMode, GetOptions, GetRange, ListResult,
+    ObjectMeta, PutMode, PutResult, RenameTargetMode, UpdateVersion,
+};
+use stabby::string::String as SString;
+use stabby::vec::Vec as SVec;
+
+use crate::util::FFI_Option;
+
+/// The kind of an [`object_store::Error`] that crosses the FFI boundary.
+///
+/// Callers match on the kind of some errors, such as a missing object, so it
+/// is preserved along with the message of the error.
+#[expect(non_camel_case_types)]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FFI_ObjectStoreErrorKind {
+    Generic,
+    NotFound,
+    AlreadyExists,
+    Precondition,
+    NotModified,
+    NotSupported,
+    PermissionDenied,
+    Unauthenticated,
+}
+
+/// An FFI-safe [`object_store::Error`].
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct FFI_ObjectStoreError {
+    pub kind: FFI_ObjectStoreErrorKind,
+    /// Location the error refers to, empty for errors without a location.
+    pub path: SString,
+    pub message: SString,
+}
+
+impl From<object_store::Error> for FFI_ObjectStoreError {
+    fn from(err: object_store::Error) -> Self {
+        let (kind, path, message) = match err {
+            object_store::Error::NotFound { path, source } => {
+                (FFI_ObjectStoreErrorKind::NotFound, path, source.to_string())
+            }
+            object_store::Error::AlreadyExists { path, source } => (
+                FFI_ObjectStoreErrorKind::AlreadyExists,
+                path,
+                source.to_string(),
+            ),
+            object_store::Error::Precondition { path, source } => (
+                FFI_ObjectStoreErrorKind::Precondition,
+                path,
+                source.to_string(),
+            ),
+            object_store::Error::NotModified { path, source } => (
+                FFI_ObjectStoreErrorKind::NotModified,
+                path,
+                source.to_string(),
+            ),
+            object_store::Error::NotSupported { source } => (
+                FFI_ObjectStoreErrorKind::NotSupported,
+                String::new(),
+                source.to_string(),
+            ),
+            object_store::Error::PermissionDenied { path, source } => (
+                FFI_ObjectStoreErrorKind::PermissionDenied,
+                path,
+                source.to_string(),
+            ),
+            object_store::Error::Unauthenticated { path, source } => (
+                FFI_ObjectStoreErrorKind::Unauthenticated,
+                path,
+                source.to_string(),
+            ),
+            err => (
+                FFI_ObjectStoreErrorKind::Generic,
+                String::new(),
+                err.to_string(),
+            ),
+        };
+
+        Self {
+            kind,
+            path: path.as_str().into(),
+            message: message.as_str().into(),
+        }
+    }
+}
+
+impl From<FFI_ObjectStoreError> for object_store::Error {
+    fn from(err: FFI_ObjectStoreError) -> Self {
+        let path = err.path.to_string();
+        let source = err.message.to_string().into();
+        match err.kind {
+            FFI_ObjectStoreErrorKind::Generic => Self::Generic {
+                store: "FFI",
+                source,
+            },
+            FFI_ObjectStoreErrorKind::NotFound => Self::NotFound { path, source },
+            FFI_ObjectStoreErrorKind::AlreadyExists => {
+                Self::AlreadyExists { path, source }
+            }
+            FFI_ObjectStoreErrorKind::Precondition => Self::Precondition { path, source },
+            FFI_ObjectStoreErrorKind::NotModified => Self::NotModified { path, source },
+            FFI_ObjectStoreErrorKind::NotSupported => Self::NotSupported { source },
+            FFI_ObjectStoreErrorKind::PermissionDenied => {
+                Self::PermissionDenied { path, source }
+            }
+            FFI_ObjectStoreErrorKind::Unauthenticated => {
+                Self::Unauthenticated { path, source }
+            }
+        }
+    }
+}
+
+/// An FFI-safe [`object_store::Result`], which keeps the kind of its error.
+#[repr(C, u8)]
+#[derive(Debug)]
+pub enum FFI_ObjectStoreResult<T> {
+    Ok(T),
+    Err(FFI_ObjectStoreError),
+}
+
+impl<T> From<object_store::Result<T>> for FFI_ObjectStoreResult<T> {
+    fn from(result: object_store::Result<T>) -> Self {
+        match result {
+            Ok(v) => Self::Ok(v),
+            Err(e) => Self::Err(e.into()),
+        }
+    }
+}
+
+impl<T> From<FFI_ObjectStoreResult<T>> for object_store::Result<T> {
+    fn from(result: FFI_ObjectStoreResult<T>) -> Self {
+        match result {
+            FFI_ObjectStoreResult::Ok(v) => Ok(v),
+            FFI_ObjectStoreResult::Err(e) => Err(e.into()),
+        }
+    }
+}
+
+pub(crate) fn path_to_ffi(path: &Path) -> SString {
+    path.as_ref().into()
+}
+
+#[expect(clippy::result_large_err)]
+pub(crate) fn path_from_ffi(path: &SString) -> object_store::Result<Path> {
+    Ok(Path::parse(path.as_str())?)
+}
+
+fn timestamp_to_ffi(timestamp: &DateTime<Utc>) -> i64 {
+    timestamp.timestamp_micros()
+}
+
+#[expect(clippy::result_large_err)]
+fn timestamp_from_ffi(micros: i64) -> object_store::Result<DateTime<Utc>> {
+    DateTime::from_timestamp_micros(micros).ok_or_else(|| object_store::Error::Generic {
+        store: "FFI",
+        source: format!("Invalid timestamp {micros}").into(),
+    })
+}
+
+fn option_to_ffi(value: &Option<String>) -> FFI_Option<SString> {
+    value.as_deref().map(SString::from).into()
+}
+
+fn option_from_ffi(value: FFI_Option<SString>) -> Option<String> {
+    value.into_option().map(|value| value.to_string())
+}
+
+/// An FFI-safe [`ObjectMeta`]. The modification time is in microseconds
+/// since the epoch.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct FFI_ObjectMeta {
+    pub location: SString,
+    pub last_modified: i64,
+    pub size: u64,
+    pub e_tag: FFI_Option<SString>,
+    pub version: FFI_Option<SString>,
+}
+
+impl From<&ObjectMeta> for FFI_ObjectMeta {
+    fn from(meta: &ObjectMeta) -> Self {
+        Self {
+            location: path_to_ffi(&meta.location),
+            last_modified: timestamp_to_ffi(&meta.last_modified),
+            size: meta.size,
+            e_tag: option_to_ffi(&meta.e_tag),
+            version: option_to_ffi(&meta.version),
+        }
+    }
+}
+
+impl TryFrom<FFI_ObjectMeta> for ObjectMeta {
+    type Error = object_store::Error;
+
+    fn try_from(meta: FFI_ObjectMeta) -> object_store::Result<Self> {
+        Ok(Self {
+            location: path_from_ffi(&meta.location)?,
+            last_modified: timestamp_from_ffi(meta.last_modified)?,
+            size: meta.size,
+            e_tag: option_from_ffi(meta.e_tag),
+            version: option_from_ffi(meta.version),
+        })
+    }
+}
+
+/// An FFI-safe [`Attribute`] and its value. User defined metadata is keyed
+/// by `metadata:` followed by its name.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct FFI_Attribute {
+    pub key: SString,
+    pub value: SString,
+}
+
+const METADATA_PREFIX: &str = "metadata:";
+
+pub(crate) fn attributes_to_ffi(attributes: &Attributes) -> SVec<FFI_Attribute> {
+    attributes
+        .iter()
+        .map(|(attribute, value)| {
+            let key = match attribute {
+                Attribute::ContentDisposition => "content-disposition".to_string(),
+                Attribute::ContentEncoding => "content-encoding".to_string(),
+                Attribute::ContentLanguage => "content-language".to_string(),
+                Attribute::ContentType => "content-type".to_string(),
+                Attribute::CacheControl => "cache-control".to_string(),
+                Attribute::StorageClass => "storage-class".to_string(),
+                Attribute::Metadata(name) => format!("{METADATA_PREFIX}{name}"),
+                _ => format!("{attribute:?}"),
+            };
+            FFI_Attribute {
+                key: key.as_str().into(),
+                value: value.as_ref().into(),
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn attributes_from_ffi(attributes: SVec<FFI_Attribute>) -> Attributes {
+    attributes
+        .into_iter()
+        .filter_map(|FFI_Attribute { key, value }| {
+            let attribute = match key.as_str() {
+                "content-disposition" => Attribute::ContentDisposition,
+                "content-encoding" => Attribute::ContentEncoding,
+                "content-language" => Attribute::ContentLanguage,
+                "content-type" => Attribute::ContentType,
+                "cache-control" => Attribute::CacheControl,
+                "storage-class" => Attribute::StorageClass,
+                key => Attribute::Metadata(
+                    key.strip_prefix(METADATA_PREFIX)?.to_string().into(),
+                ),
+            };
+            Some((attribute, AttributeValue::from(value.to_string())))
+        })
+        .collect()
+}
+
+/// An FFI-safe [`PutMode`].
+#[repr(C, u8)]
+#[derive(Debug, Clone)]
+pub enum FFI_PutMode {
+    Overwrite,
+    Create,
+    Update {
+        e_tag: FFI_Option<SString>,
+        version: FFI_Option<SString>,
+    },
+}
+
+impl From<&PutMode> for FFI_PutMode {
+    fn from(mode: &PutMode) -> Self {
+        match mode {
+            PutMode::Overwrite => Self::Overwrite,
+            PutMode::Create => Self::Create,
+            PutMode::Update(version) => Self::Update {
+                e_tag: option_to_ffi(&version.e_tag),
+                version: option_to_ffi(&version.version),
+            },
+        }
+    }
+}
+
+impl From<FFI_PutMode> for PutMode {
+    fn from(mode: FFI_PutMode) -> Self {
+        match mode {
+            FFI_PutMode::Overwrite => Self::Overwrite,
+            FFI_PutMode::Create => Self::Create,
+            FFI_PutMode::Update { e_tag, version } => Self::Update(UpdateVersion {
+                e_tag: option_from_ffi(e_tag),
+                version: option_from_ffi(version),
+            }),
+        }
+    }
+}
+
+/// An FFI-safe [`PutResult`].
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct FFI_PutResult {
+    pub e_tag: FFI_Option<SString>,
+    pub version: FFI_Option<SString>,
+}
+
+impl From<&PutResult> for FFI_PutResult {
+    fn from(result: &PutResult) -> Self {
+        Self {
+            e_tag: option_to_ffi(&result.e_tag),
+            version: option_to_ffi(&result.version),
+        }
+    }
+}
+
+impl From<FFI_PutResult> for PutResult {
+    fn from(result: FFI_PutResult) -> Self {
+        Self {
+            e_tag: option_from_ffi(result.e_tag),
+            version: option_from_ffi(result.version),
+        }
+    }
+}
+
+/// An FFI-safe byte range.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FFI_Range {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl From<&Range<u64>> for FFI_Range {
+    fn from(range: &Range<u64>) -> Self {
+        Self {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+impl From<FFI_Range> for Range<u64> {
+    fn from(range: FFI_Range) -> Self {
+        range.start..range.end
+    }
+}
+
+/// An FFI-safe [`GetRange`].
+#[repr(C, u8)]
+#[derive(Debug, Clone, Copy)]
+pub enum FFI_GetRange {
+    Bounded(FFI_Range),
+    Offset(u64),
+    Suffix(u64),
+}
+
+/// An FFI-safe [`GetOptions`]. Timestamps are in microseconds since the epoch.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct FFI_GetOptions {
+    pub if_match: FFI_Option<SString>,
+    pub if_none_match: FFI_Option<SString>,
+    pub if_modified_since: FFI_Option<i64>,
+    pub if_unmodified_since: FFI_Option<i64>,
+    pub range: FFI_Option<FFI_GetRange>,
+    pub version: FFI_Option<SString>,
+    pub head: bool,
+}
+
+impl From<&GetOptions> for FFI_GetOptions {
+    fn from(options: &GetOptions) -> Self {
+        Self {
+            if_match: option_to_ffi(&options.if_match),
+            if_none_match: option_to_ffi(&options.if_none_match),
+            if_modified_since: options
+                .if_modified_since
+                .as_ref()
+                .map(timestamp_to_ffi)
+                .into(),
+            if_unmodified_since: options
+                .if_unmodified_since
+                .as_ref()
+                .map(timestamp_to_ffi)
+                .into(),
+            range: options
+                .range
+                .as_ref()
+                .map(|range| match range {
+                    GetRange::Bounded(range) => FFI_GetRange::Bounded(range.into()),
+                    GetRange::Offset(offset) => FFI_GetRange::Offset(*offset),
+                    GetRange::Suffix(suffix) => FFI_GetRange::Suffix(*suffix),
+                })
+                .into(),
+            version: option_to_ffi(&options.version),
+            head: options.head,
+        }
+    }
+}
+
+impl TryFrom<FFI_GetOptions> for GetOptions {
+    type Error = object_store::Error;
+
+    fn try_from(options: FFI_GetOptions) -> object_store::Result<Self> {
+        #[expect(clippy::result_large_err)]
+        let timestamp = |timestamp: FFI_Option<i64>| {
+            timestamp.into_option().map(timestamp_from_ffi).transpose()
+        };
+        Ok(Self {
+            if_match: option_from_ffi(options.if_match),
+            if_none_match: option_from_ffi(options.if_none_match),
+            if_modified_since: timestamp(options.if_modified_since)?,
+            if_unmodified_since: timestamp(options.if_unmodified_since)?,
+            range: options.range.into_option().map(|range| match range {
+                FFI_GetRange::Bounded(range) => GetRange::Bounded(range.into()),
+                FFI_GetRange::Offset(offset) => GetRange::Offset(offset),
+                FFI_GetRange::Suffix(suffix) => GetRange::Suffix(suffix),
+            }),
+            version: option_from_ffi(options.version),
+            head: options.head,
+            ..Default::default()
+        })
+    }
+}
+
+/// An FFI-safe [`ListResult`].
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct FFI_ListResult {
+    pub common_prefixes: SVec<SString>,
+    pub objects: SVec<FFI_ObjectMeta>,
+}
+
+impl From<&ListResult> for FFI_ListResult {
+    fn from(result: &ListResult) -> Self {
+        Self {
+            common_prefixes: result.common_prefixes.iter().map(path_to_ffi).collect(),
+            objects: result.objects.iter().map(FFI_ObjectMeta::from).collect(),
+        }
+    }
+}
+
+impl TryFrom<FFI_ListResult> for ListResult {
+    type Error = object_store::Error;
+
+    fn try_from(result: FFI_ListResult) -> object_store::Result<Self> {
+        Ok(Self {
+            common_prefixes: result
+                .common_prefixes
+                .iter()
+                .map(path_from_ffi)
+                .collect::<object_store::Result<_>>()?,
+            objects: result
+                .objects
+                .into_iter()
+                .map(ObjectMeta::try_from)
+                .collect::<object_store::Result<_>>()?,
+        })
+    }
+}
+
+/// The result of a get request. The payload of the object is buffered in
+/// full, since streams of bytes cannot cross the FFI boundary.
+#[repr(C)]
+#[derive(Debug, Clone)]
+pub struct FFI_GetResult {
+    pub payload: SVec<u8>,
+    pub meta: FFI_ObjectMeta,
+    pub range: FFI_Range,
+    pub attributes: SVec<FFI_Attribute>,
+}
+
+/// Whether a copy or rename may overwrite an existing object, shared by
+/// [`CopyMode`] and [`RenameTargetMode`].
+#[expect(non_camel_case_types)]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FFI_CopyMode {
+    Overwrite,
+    Create,
+}
+
+impl From<CopyMode> for FFI_CopyMode {
+    fn from(mode: CopyMode) -> Self {
+        match mode {
+            CopyMode::Overwrite => Self::Overwrite,
+            CopyMode::Create => Self::Create,
+        }
+    }
+}
+
+impl From<FFI_CopyMode> for CopyMode {
+    fn from(mode: FFI_CopyMode) -> Self {
+        match mode {
+            FFI_CopyMode::Overwrite => Self::Overwrite,
+            FFI_CopyMode::Create => Self::Create,
+        }
+    }
+}
+
+impl From<RenameTargetMode> for FFI_CopyMode {
+    fn from(mode: RenameTargetMode) -> Self {
+        match mode {
+            RenameTargetMode::Overwrite => Self::Overwrite,
+            RenameTargetMode::Create => Self::Create,
+        }
+    }
+}
+
+impl From<FFI_CopyMode> for RenameTargetMode {
+    fn from(mode: FFI_CopyMode) -> Self {
+        match mode {
+            FFI_CopyMode::Overwrite => Self::Overwrite,
+            FFI_CopyMode::Create => Self::Create,
+        }
+    }
+}
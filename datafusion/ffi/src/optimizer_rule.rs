@@ -0,0 +1,519 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! FFI support for logical [`OptimizerRule`]s.
+//!
+//! Plans cross the boundary serialized with the rule's logical extension
+//! codec, so every node of a plan passed to a foreign rule, including its
+//! table scans, must be supported by that codec. Rules with an
+//! [`ApplyOrder`] are invoked once per plan node, which serializes the
+//! subtree of each node; rules that recurse on their own avoid this cost.
+
+use std::ffi::c_void;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use datafusion_common::Result;
+use datafusion_common::alias::AliasGenerator;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::Transformed;
+use datafusion_execution::TaskContext;
+use datafusion_expr::LogicalPlan;
+use datafusion_expr::registry::FunctionRegistry;
+use datafusion_optimizer::{ApplyOrder, OptimizerConfig, OptimizerRule};
+use datafusion_proto::bytes::{
+    logical_plan_from_bytes_with_extension_codec,
+    logical_plan_to_bytes_with_extension_codec,
+};
+use datafusion_proto::logical_plan::LogicalExtensionCodec;
+use stabby::string::String as SString;
+use stabby::vec::Vec as SVec;
+use tokio::runtime::Handle;
+
+use crate::config::FFI_ConfigOptions;
+use crate::execution::FFI_TaskContextProvider;
+use crate::proto::logical_extension_codec::FFI_LogicalExtensionCodec;
+use crate::util::{FFI_Option, FFI_Result};
+use crate::{df_result, sresult_return};
+
+/// FFI safe version of [`ApplyOrder`].
+#[expect(non_camel_case_types)]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy)]
+pub enum FFI_ApplyOrder {
+    TopDown,
+    BottomUp,
+}
+
+impl From<ApplyOrder> for FFI_ApplyOrder {
+    fn from(value: ApplyOrder) -> Self {
+        match value {
+            ApplyOrder::TopDown => FFI_ApplyOrder::TopDown,
+            ApplyOrder::BottomUp => FFI_ApplyOrder::BottomUp,
+        }
+    }
+}
+
+impl From<FFI_ApplyOrder> for ApplyOrder {
+    fn from(value: FFI_ApplyOrder) -> Self {
+        match value {
+            FFI_ApplyOrder::TopDown => ApplyOrder::TopDown,
+            FFI_ApplyOrder::BottomUp => ApplyOrder::BottomUp,
+        }
+    }
+}
+
+/// A stable struct for sharing an [`OptimizerConfig`] across FFI boundaries.
+///
+/// The alias generator cannot be shared, so the foreign rule continues from
+/// the last alias id generated by the caller and reports its own last id
+/// back in [`FFI_TransformedPlan::alias_id`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_OptimizerConfig {
+    /// Query execution start time in nanoseconds since the epoch.
+    pub query_execution_start_time: FFI_Option<i64>,
+
+    /// The last alias id generated by the caller's alias generator.
+    pub alias_id: usize,
+
+    pub options: FFI_ConfigOptions,
+}
+
+impl FFI_OptimizerConfig {
+    fn new(config: &dyn OptimizerConfig) -> Self {
+        Self {
+            query_execution_start_time: config
+                .query_execution_start_time()
+                .and_then(|time| time.timestamp_nanos_opt())
+                .into(),
+            alias_id: last_alias_id(config.alias_generator()),
+            options: config.options().as_ref().into(),
+        }
+    }
+}
+
+/// Returns the last id handed out by `alias_generator`. The generator does not
+/// expose its state, so this draws, and thereby skips, one id.
+fn last_alias_id(alias_generator: &AliasGenerator) -> usize {
+    alias_generator
+        .next("")
+        .trim_start_matches('_')
+        .parse()
+        .unwrap_or_default()
+}
+
+/// [`OptimizerConfig`] reconstructed on the provider side of the FFI boundary.
+/// Functions are resolved through the task context of the rule's codec.
+struct ForeignOptimizerConfig {
+    query_execution_start_time: Option<DateTime<Utc>>,
+    alias_generator: Arc<AliasGenerator>,
+    options: Arc<ConfigOptions>,
+    task_ctx: Arc<TaskContext>,
+}
+
+impl ForeignOptimizerConfig {
+    fn try_new(config: FFI_OptimizerConfig, task_ctx: Arc<TaskContext>) -> Result<Self> {
+        let alias_generator = AliasGenerator::new();
+        alias_generator.update_min_id(config.alias_id);
+
+        Ok(Self {
+            query_execution_start_time: config
+                .query_execution_start_time
+                .into_option()
+                .map(DateTime::from_timestamp_nanos),
+            alias_generator: Arc::new(alias_generator),
+            options: Arc::new(config.options.try_into()?),
+            task_ctx,
+        })
+    }
+}
+
+impl OptimizerConfig for ForeignOptimizerConfig {
+    fn query_execution_start_time(&self) -> Option<DateTime<Utc>> {
+        self.query_execution_start_time
+    }
+
+    fn alias_generator(&self) -> &Arc<AliasGenerator> {
+        &self.alias_generator
+    }
+
+    fn options(&self) -> Arc<ConfigOptions> {
+        Arc::clone(&self.options)
+    }
+
+    fn function_registry(&self) -> Option<&dyn FunctionRegistry> {
+        Some(self.task_ctx.as_ref())
+    }
+}
+
+/// The result of rewriting a plan with an [`FFI_OptimizerRule`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_TransformedPlan {
+    /// The serialized rewritten plan, or `None` if the rule did not
+    /// transform the plan.
+    pub plan: FFI_Option<SVec<u8>>,
+
+    /// The last alias id generated while rewriting the plan.
+    pub alias_id: usize,
+}
+
+/// A stable struct for sharing an [`OptimizerRule`] across FFI boundaries.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_OptimizerRule {
+    rewrite: unsafe extern "C" fn(
+        &Self,
+        plan_serialized: SVec<u8>,
+        config: FFI_OptimizerConfig,
+    ) -> FFI_Result<FFI_TransformedPlan>,
+
+    pub name: unsafe extern "C" fn(&Self) -> SString,
+
+    pub apply_order: unsafe extern "C" fn(&Self) -> FFI_Option<FFI_ApplyOrder>,
+
+    /// Codec used to encode and decode the plans passed to the rule.
+    pub logical_codec: FFI_LogicalExtensionCodec,
+
+    /// Used to create a clone on the rule. This should
+    /// only need to be called by the receiver of the rule.
+    pub clone: unsafe extern "C" fn(rule: &Self) -> Self,
+
+    /// Release the memory of the private data when it is no longer being used.
+    pub release: unsafe extern "C" fn(arg: &mut Self),
+
+    /// Return the major DataFusion version number of this rule.
+    pub version: unsafe extern "C" fn() -> u64,
+
+    /// Internal data. This is only to be accessed by the provider of the rule.
+    /// A [`ForeignOptimizerRule`] should never attempt to access this data.
+    pub private_data: *mut c_void,
+
+    /// Utility to identify when FFI objects are accessed locally through
+    /// the foreign interface. See [`crate::get_library_marker_id`] and
+    /// the crate's `README.md` for more information.
+    pub library_marker_id: extern "C" fn() -> usize,
+}
+
+unsafe impl Send for FFI_OptimizerRule {}
+unsafe impl Sync for FFI_OptimizerRule {}
+
+struct OptimizerRulePrivateData {
+    rule: Arc<dyn OptimizerRule + Send + Sync>,
+    runtime: Option<Handle>,
+}
+
+impl FFI_OptimizerRule {
+    fn inner(&self) -> &Arc<dyn OptimizerRule + Send + Sync> {
+        let private_data = self.private_data as *const OptimizerRulePrivateData;
+        unsafe { &(*private_data).rule }
+    }
+
+    fn runtime(&self) -> Option<Handle> {
+        let private_data = self.private_data as *const OptimizerRulePrivateData;
+        unsafe { (*private_data).runtime.clone() }
+    }
+}
+
+unsafe extern "C" fn rewrite_fn_wrapper(
+    rule: &FFI_OptimizerRule,
+    plan_serialized: SVec<u8>,
+    config: FFI_OptimizerConfig,
+) -> FFI_Result<FFI_TransformedPlan> {
+    let task_ctx: Arc<TaskContext> =
+        sresult_return!((&rule.logical_codec.task_ctx_provider).try_into());
+    let codec: Arc<dyn LogicalExtensionCodec> = (&rule.logical_codec).into();
+
+    let plan = sresult_return!(logical_plan_from_bytes_with_extension_codec(
+        plan_serialized.as_slice(),
+        task_ctx.as_ref(),
+        codec.as_ref(),
+    ));
+    let config = sresult_return!(ForeignOptimizerConfig::try_new(config, task_ctx));
+
+    let transformed = sresult_return!(rule.inner().rewrite(plan, &config));
+    let plan = match transformed.transformed {
+        true => {
+            let plan = sresult_return!(logical_plan_to_bytes_with_extension_codec(
+                &transformed.data,
+                codec.as_ref(),
+            ));
+            Some(SVec::from(plan.as_ref()))
+        }
+        false => None,
+    };
+
+    FFI_Result::Ok(FFI_TransformedPlan {
+        plan: plan.into(),
+        alias_id: last_alias_id(&config.alias_generator),
+    })
+}
+
+unsafe extern "C" fn name_fn_wrapper(rule: &FFI_OptimizerRule) -> SString {
+    rule.inner().name().into()
+}
+
+unsafe extern "C" fn apply_order_fn_wrapper(
+    rule: &FFI_OptimizerRule,
+) -> FFI_Option<FFI_ApplyOrder> {
+    rule.inner().apply_order().map(Into::into).into()
+}
+
+unsafe extern "C" fn release_fn_wrapper(rule: &mut FFI_OptimizerRule) {
+    unsafe {
+        debug_assert!(!rule.private_data.is_null());
+        let private_data =
+            Box::from_raw(rule.private_data as *mut OptimizerRulePrivateData);
+        drop(private_data);
+        rule.private_data = std::ptr::null_mut();
+    }
+}
+
+unsafe extern "C" fn clone_fn_wrapper(rule: &FFI_OptimizerRule) -> FFI_OptimizerRule {
+    FFI_OptimizerRule::new_with_ffi_codec(
+        Arc::clone(rule.inner()),
+        rule.runtime(),
+        rule.logical_codec.clone(),
+    )
+}
+
+impl Drop for FFI_OptimizerRule {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+impl Clone for FFI_OptimizerRule {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+impl FFI_OptimizerRule {
+    /// Creates a new [`FFI_OptimizerRule`] with a native logical extension
+    /// codec, used to pass plans to the rule.
+    pub fn new(
+        rule: Arc<dyn OptimizerRule + Send + Sync>,
+        runtime: Option<Handle>,
+        task_ctx_provider: impl Into<FFI_TaskContextProvider>,
+        logical_codec: Arc<dyn LogicalExtensionCodec>,
+    ) -> Self {
+        let logical_codec = FFI_LogicalExtensionCodec::new(
+            logical_codec,
+            runtime.clone(),
+            task_ctx_provider.into(),
+        );
+        Self::new_with_ffi_codec(rule, runtime, logical_codec)
+    }
+
+    /// Creates a new [`FFI_OptimizerRule`] using a prebuilt FFI extension codec.
+    pub fn new_with_ffi_codec(
+        rule: Arc<dyn OptimizerRule + Send + Sync>,
+        runtime: Option<Handle>,
+        logical_codec: FFI_LogicalExtensionCodec,
+    ) -> Self {
+        let private_data = Box::new(OptimizerRulePrivateData { rule, runtime });
+        let private_data = Box::into_raw(private_data) as *mut c_void;
+
+        Self {
+            rewrite: rewrite_fn_wrapper,
+            name: name_fn_wrapper,
+            apply_order: apply_order_fn_wrapper,
+            logical_codec,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            version: super::version,
+            private_data,
+            library_marker_id: crate::get_library_marker_id,
+        }
+    }
+}
+
+/// This wrapper struct exists on the receiver side of the FFI interface, so it has
+/// no guarantees about being able to access the data in `private_data`. Any functions
+/// defined on this struct must only use the stable functions provided in
+/// FFI_OptimizerRule to interact with the foreign rule.
+#[derive(Debug)]
+pub struct ForeignOptimizerRule {
+    name: String,
+    apply_order: Option<ApplyOrder>,
+    rule: FFI_OptimizerRule,
+}
+
+unsafe impl Send for ForeignOptimizerRule {}
+unsafe impl Sync for ForeignOptimizerRule {}
+
+impl From<&FFI_OptimizerRule> for Arc<dyn OptimizerRule + Send + Sync> {
+    fn from(rule: &FFI_OptimizerRule) -> Self {
+        if (rule.library_marker_id)() == crate::get_library_marker_id() {
+            return Arc::clone(rule.inner());
+        }
+
+        let (name, apply_order) = unsafe {
+            (
+                (rule.name)(rule).to_string(),
+                (rule.apply_order)(rule).into_option().map(Into::into),
+            )
+        };
+        Arc::new(ForeignOptimizerRule {
+            name,
+            apply_order,
+            rule: rule.clone(),
+        })
+    }
+}
+
+impl OptimizerRule for ForeignOptimizerRule {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        self.apply_order
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        let codec: Arc<dyn LogicalExtensionCodec> = (&self.rule.logical_codec).into();
+        let plan_serialized =
+            logical_plan_to_bytes_with_extension_codec(&plan, codec.as_ref())?;
+
+        let transformed = unsafe {
+            df_result!((self.rule.rewrite)(
+                &self.rule,
+                SVec::from(plan_serialized.as_ref()),
+                FFI_OptimizerConfig::new(config),
+            ))?
+        };
+        config.alias_generator().update_min_id(transformed.alias_id);
+
+        let Some(plan_serialized) = transformed.plan.into_option() else {
+            return Ok(Transformed::no(plan));
+        };
+        let task_ctx: Arc<TaskContext> =
+            (&self.rule.logical_codec.task_ctx_provider).try_into()?;
+        let plan = logical_plan_from_bytes_with_extension_codec(
+            plan_serialized.as_slice(),
+            task_ctx.as_ref(),
+            codec.as_ref(),
+        )?;
+        Ok(Transformed::yes(plan))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::prelude::SessionContext;
+    use datafusion_common::tree_node::Transformed;
+    use datafusion_expr::{LogicalPlanBuilder, lit};
+    use datafusion_optimizer::OptimizerContext;
+    use datafusion_proto::logical_plan::DefaultLogicalExtensionCodec;
+
+    use super::*;
+
+    /// Replaces a `LIMIT` with its input.
+    #[derive(Debug)]
+    struct RemoveLimit;
+
+    impl OptimizerRule for RemoveLimit {
+        fn name(&self) -> &str {
+            "remove_limit"
+        }
+
+        fn apply_order(&self) -> Option<ApplyOrder> {
+            Some(ApplyOrder::TopDown)
+        }
+
+        fn rewrite(
+            &self,
+            plan: LogicalPlan,
+            config: &dyn OptimizerConfig,
+        ) -> Result<Transformed<LogicalPlan>> {
+            config.alias_generator().next("__remove_limit");
+            match plan {
+                LogicalPlan::Limit(limit) => {
+                    Ok(Transformed::yes(Arc::unwrap_or_clone(limit.input)))
+                }
+                plan => Ok(Transformed::no(plan)),
+            }
+        }
+    }
+
+    fn create_foreign_rule() -> (Arc<SessionContext>, Arc<dyn OptimizerRule + Send + Sync>)
+    {
+        let (ctx, task_ctx_provider) = crate::util::tests::test_session_and_ctx();
+        let mut ffi_rule = FFI_OptimizerRule::new(
+            Arc::new(RemoveLimit),
+            None,
+            task_ctx_provider,
+            Arc::new(DefaultLogicalExtensionCodec {}),
+        );
+        ffi_rule.library_marker_id = crate::mock_foreign_marker_id;
+
+        (ctx, (&ffi_rule).into())
+    }
+
+    #[test]
+    fn test_round_trip_ffi_optimizer_rule() -> Result<()> {
+        let (_ctx, foreign_rule) = create_foreign_rule();
+
+        assert_eq!(foreign_rule.name(), "remove_limit");
+        assert_eq!(foreign_rule.apply_order(), Some(ApplyOrder::TopDown));
+
+        let values = LogicalPlanBuilder::values(vec![vec![lit(1i64)]])?.build()?;
+        let plan = LogicalPlanBuilder::from(values.clone())
+            .limit(0, Some(1))?
+            .build()?;
+
+        let config = OptimizerContext::new();
+        config.alias_generator().next("__caller");
+
+        let transformed = foreign_rule.rewrite(plan, &config)?;
+        assert!(transformed.transformed);
+        assert_eq!(transformed.data, values);
+
+        // The caller's alias generator continues after the foreign rule's ids
+        assert_eq!(config.alias_generator().next("__caller"), "__caller_5");
+
+        let transformed = foreign_rule.rewrite(values.clone(), &config)?;
+        assert!(!transformed.transformed);
+        assert_eq!(transformed.data, values);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_bypass() -> Result<()> {
+        let (_ctx, task_ctx_provider) = crate::util::tests::test_session_and_ctx();
+        let rule: Arc<dyn OptimizerRule + Send + Sync> = Arc::new(RemoveLimit);
+        let ffi_rule = FFI_OptimizerRule::new(
+            Arc::clone(&rule),
+            None,
+            task_ctx_provider,
+            Arc::new(DefaultLogicalExtensionCodec {}),
+        );
+
+        let recovered: Arc<dyn OptimizerRule + Send + Sync> = (&ffi_rule).into();
+        assert!(Arc::ptr_eq(&rule, &recovered));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,780 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! FFI support for SQL [`RelationPlanner`]s.
+//!
+//! The SQL AST has no stable layout, so table factors, expressions and object
+//! names cross the boundary as SQL text and are parsed again on the other side
+//! with the session's configured dialect. Planned relations are serialized
+//! with the planner's logical extension codec.
+
+use std::ffi::c_void;
+use std::sync::Arc;
+
+use arrow::datatypes::DataType;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::{
+    DFSchema, Result, TableReference, plan_datafusion_err, plan_err,
+};
+use datafusion_execution::TaskContext;
+use datafusion_expr::planner::{
+    ContextProvider, PlannedRelation, RelationPlanner, RelationPlannerContext,
+    RelationPlanning,
+};
+use datafusion_expr::{
+    AggregateUDF, Expr, HigherOrderUDF, LogicalPlan, ScalarUDF, TableSource, WindowUDF,
+};
+use datafusion_proto::bytes::{
+    logical_plan_from_bytes_with_extension_codec,
+    logical_plan_to_bytes_with_extension_codec,
+};
+use datafusion_proto::logical_plan::LogicalExtensionCodec;
+use sqlparser::ast::{Expr as SQLExpr, Ident, ObjectName, TableFactor};
+use sqlparser::dialect::{Dialect, dialect_from_str};
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::Token;
+use stabby::string::String as SString;
+use stabby::vec::Vec as SVec;
+use tokio::runtime::Handle;
+
+use crate::execution::FFI_TaskContextProvider;
+use crate::expr_planner::{
+    df_schema_from_ffi, df_schema_to_ffi, exprs_from_ffi, exprs_to_ffi,
+    table_reference_from_ffi, table_reference_to_ffi,
+};
+use crate::proto::logical_extension_codec::FFI_LogicalExtensionCodec;
+use crate::util::{FFI_Option, FFI_Result};
+use crate::{df_result, sresult_return};
+
+/// A relation planned by a foreign [`RelationPlanner`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_PlannedRelation {
+    /// The serialized logical plan of the relation.
+    pub plan: SVec<u8>,
+
+    /// The SQL text of the table alias of the relation, if any.
+    pub alias: FFI_Option<SString>,
+}
+
+/// A stable struct for sharing a [`RelationPlanner`] across FFI boundaries.
+#[repr(C)]
+#[derive(Debug)]
+pub struct FFI_RelationPlanner {
+    /// Returns `None` when the planner returned [`RelationPlanning::Original`].
+    plan_relation: unsafe extern "C" fn(
+        &Self,
+        relation_sql: SString,
+        context: FFI_RelationPlannerContext,
+    )
+        -> FFI_Result<FFI_Option<FFI_PlannedRelation>>,
+
+    /// Codec used to encode and decode the plans and expressions passed
+    /// between the planner and its context.
+    pub logical_codec: FFI_LogicalExtensionCodec,
+
+    /// Used to create a clone on the planner. This should
+    /// only need to be called by the receiver of the planner.
+    pub clone: unsafe extern "C" fn(planner: &Self) -> Self,
+
+    /// Release the memory of the private data when it is no longer being used.
+    pub release: unsafe extern "C" fn(arg: &mut Self),
+
+    /// Return the major DataFusion version number of this planner.
+    pub version: unsafe extern "C" fn() -> u64,
+
+    /// Internal data. This is only to be accessed by the provider of the planner.
+    /// A [`ForeignRelationPlanner`] should never attempt to access this data.
+    pub private_data: *mut c_void,
+
+    /// Utility to identify when FFI objects are accessed locally through
+    /// the foreign interface. See [`crate::get_library_marker_id`] and
+    /// the crate's `README.md` for more information.
+    pub library_marker_id: extern "C" fn() -> usize,
+}
+
+unsafe impl Send for FFI_RelationPlanner {}
+unsafe impl Sync for FFI_RelationPlanner {}
+
+struct RelationPlannerPrivateData {
+    planner: Arc<dyn RelationPlanner>,
+    runtime: Option<Handle>,
+}
+
+impl FFI_RelationPlanner {
+    fn inner(&self) -> &Arc<dyn RelationPlanner> {
+        let private_data = self.private_data as *const RelationPlannerPrivateData;
+        unsafe { &(*private_data).planner }
+    }
+
+    fn runtime(&self) -> Option<Handle> {
+        let private_data = self.private_data as *const RelationPlannerPrivateData;
+        unsafe { (*private_data).runtime.clone() }
+    }
+}
+
+/// A stable struct for sharing a [`RelationPlannerContext`] across FFI
+/// boundaries for the duration of a single [`RelationPlanner::plan_relation`]
+/// call.
+///
+/// Like [`crate::session::FFI_SessionRef`], the private data of this struct
+/// borrows the context and the lifetime of that borrow is lost within the
+/// `*mut c_void`. It must not outlive the call it was created for.
+#[repr(C)]
+#[derive(Debug)]
+pub(crate) struct FFI_RelationPlannerContext {
+    plan: unsafe extern "C" fn(&mut Self, relation_sql: SString) -> FFI_Result<SVec<u8>>,
+
+    sql_to_expr: unsafe extern "C" fn(
+        &mut Self,
+        expr_sql: SString,
+        schema_serialized: SVec<u8>,
+    ) -> FFI_Result<SVec<u8>>,
+
+    sql_expr_to_logical_expr: unsafe extern "C" fn(
+        &mut Self,
+        expr_sql: SString,
+        schema_serialized: SVec<u8>,
+    ) -> FFI_Result<SVec<u8>>,
+
+    normalize_ident: unsafe extern "C" fn(
+        &Self,
+        value: SString,
+        quote_style: FFI_Option<u32>,
+    ) -> SString,
+
+    object_name_to_table_reference:
+        unsafe extern "C" fn(&Self, name_sql: SString) -> FFI_Result<SVec<SString>>,
+
+    logical_codec: FFI_LogicalExtensionCodec,
+
+    /// Release the memory of the private data when it is no longer being used.
+    release: unsafe extern "C" fn(arg: &mut Self),
+
+    /// Return the major DataFusion version number of this context.
+    pub version: unsafe extern "C" fn() -> u64,
+
+    /// Internal data. This is only to be accessed by the provider of the context.
+    private_data: *mut c_void,
+
+    /// Utility to identify when FFI objects are accessed locally through
+    /// the foreign interface.
+    pub library_marker_id: extern "C" fn() -> usize,
+}
+
+struct RelationPlannerContextPrivateData<'a> {
+    context: &'a mut dyn RelationPlannerContext,
+    dialect: Box<dyn Dialect>,
+}
+
+impl FFI_RelationPlannerContext {
+    #[expect(clippy::mut_from_ref)]
+    fn private_data(&self) -> &mut RelationPlannerContextPrivateData<'_> {
+        let private_data = self.private_data as *mut RelationPlannerContextPrivateData;
+        unsafe { &mut *private_data }
+    }
+}
+
+fn dialect_from_options(options: &ConfigOptions) -> Result<Box<dyn Dialect>> {
+    let dialect = &options.sql_parser.dialect;
+    dialect_from_str(dialect)
+        .ok_or_else(|| plan_datafusion_err!("Unsupported SQL dialect: {dialect}"))
+}
+
+/// Parse the entire `sql` text with `parse`.
+fn parse_sql<T>(
+    dialect: &dyn Dialect,
+    sql: &str,
+    parse: impl FnOnce(&mut Parser) -> Result<T, ParserError>,
+) -> Result<T> {
+    let mut parser = Parser::new(dialect).try_with_sql(sql)?;
+    let parsed = parse(&mut parser)?;
+    parser.expect_token(&Token::EOF)?;
+    Ok(parsed)
+}
+
+fn plan_to_ffi(
+    plan: &LogicalPlan,
+    codec: &FFI_LogicalExtensionCodec,
+) -> Result<SVec<u8>> {
+    let codec: Arc<dyn LogicalExtensionCodec> = codec.into();
+    let bytes = logical_plan_to_bytes_with_extension_codec(plan, codec.as_ref())?;
+    Ok(SVec::from(bytes.as_ref()))
+}
+
+fn plan_from_ffi(
+    plan_serialized: &SVec<u8>,
+    codec: &FFI_LogicalExtensionCodec,
+) -> Result<LogicalPlan> {
+    let task_ctx: Arc<TaskContext> = (&codec.task_ctx_provider).try_into()?;
+    let codec: Arc<dyn LogicalExtensionCodec> = codec.into();
+    logical_plan_from_bytes_with_extension_codec(
+        plan_serialized.as_slice(),
+        task_ctx.as_ref(),
+        codec.as_ref(),
+    )
+}
+
+fn expr_from_ffi(
+    expr_serialized: &SVec<u8>,
+    codec: &FFI_LogicalExtensionCodec,
+) -> Result<Expr> {
+    let mut exprs = exprs_from_ffi(expr_serialized, codec)?;
+    match (exprs.pop(), exprs.is_empty()) {
+        (Some(expr), true) => Ok(expr),
+        _ => plan_err!("Expected a single expression from the relation planner context"),
+    }
+}
+
+unsafe extern "C" fn context_plan_fn_wrapper(
+    context: &mut FFI_RelationPlannerContext,
+    relation_sql: SString,
+) -> FFI_Result<SVec<u8>> {
+    let private_data = context.private_data();
+    let relation = sresult_return!(parse_sql(
+        private_data.dialect.as_ref(),
+        &relation_sql,
+        |parser| parser.parse_table_factor()
+    ));
+    let plan = sresult_return!(private_data.context.plan(relation));
+
+    plan_to_ffi(&plan, &context.logical_codec).into()
+}
+
+unsafe extern "C" fn context_sql_to_expr_fn_wrapper(
+    context: &mut FFI_RelationPlannerContext,
+    expr_sql: SString,
+    schema_serialized: SVec<u8>,
+) -> FFI_Result<SVec<u8>> {
+    let private_data = context.private_data();
+    let expr = sresult_return!(parse_sql(
+        private_data.dialect.as_ref(),
+        &expr_sql,
+        |parser| parser.parse_expr()
+    ));
+    let schema = sresult_return!(df_schema_from_ffi(&schema_serialized));
+    let expr = sresult_return!(private_data.context.sql_to_expr(expr, &schema));
+
+    exprs_to_ffi([&expr], &context.logical_codec).into()
+}
+
+unsafe extern "C" fn context_sql_expr_to_logical_expr_fn_wrapper(
+    context: &mut FFI_RelationPlannerContext,
+    expr_sql: SString,
+    schema_serialized: SVec<u8>,
+) -> FFI_Result<SVec<u8>> {
+    let private_data = context.private_data();
+    let expr = sresult_return!(parse_sql(
+        private_data.dialect.as_ref(),
+        &expr_sql,
+        |parser| parser.parse_expr()
+    ));
+    let schema = sresult_return!(df_schema_from_ffi(&schema_serialized));
+    let expr =
+        sresult_return!(private_data.context.sql_expr_to_logical_expr(expr, &schema));
+
+    exprs_to_ffi([&expr], &context.logical_codec).into()
+}
+
+unsafe extern "C" fn context_normalize_ident_fn_wrapper(
+    context: &FFI_RelationPlannerContext,
+    value: SString,
+    quote_style: FFI_Option<u32>,
+) -> SString {
+    let ident = Ident {
+        value: value.to_string(),
+        quote_style: quote_style.into_option().and_then(char::from_u32),
+        ..Ident::new("")
+    };
+    context.private_data().context.normalize_ident(ident).into()
+}
+
+unsafe extern "C" fn context_object_name_to_table_reference_fn_wrapper(
+    context: &FFI_RelationPlannerContext,
+    name_sql: SString,
+) -> FFI_Result<SVec<SString>> {
+    let private_data = context.private_data();
+    let name = sresult_return!(parse_sql(
+        private_data.dialect.as_ref(),
+        &name_sql,
+        |parser| parser.parse_object_name(false)
+    ));
+    let reference =
+        sresult_return!(private_data.context.object_name_to_table_reference(name));
+
+    FFI_Result::Ok(table_reference_to_ffi(&reference))
+}
+
+unsafe extern "C" fn context_release_fn_wrapper(
+    context: &mut FFI_RelationPlannerContext,
+) {
+    unsafe {
+        let private_data =
+            Box::from_raw(context.private_data as *mut RelationPlannerContextPrivateData);
+        drop(private_data);
+    }
+}
+
+impl Drop for FFI_RelationPlannerContext {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+impl FFI_RelationPlannerContext {
+    /// Creates a new [`FFI_RelationPlannerContext`] borrowing `context`. The
+    /// caller is responsible for not using the returned struct past the
+    /// lifetime of the borrow.
+    fn new(
+        context: &mut dyn RelationPlannerContext,
+        logical_codec: FFI_LogicalExtensionCodec,
+    ) -> Result<Self> {
+        let dialect = dialect_from_options(context.context_provider().options())?;
+        let private_data =
+            Box::new(RelationPlannerContextPrivateData { context, dialect });
+
+        Ok(Self {
+            plan: context_plan_fn_wrapper,
+            sql_to_expr: context_sql_to_expr_fn_wrapper,
+            sql_expr_to_logical_expr: context_sql_expr_to_logical_expr_fn_wrapper,
+            normalize_ident: context_normalize_ident_fn_wrapper,
+            object_name_to_table_reference:
+                context_object_name_to_table_reference_fn_wrapper,
+            logical_codec,
+            release: context_release_fn_wrapper,
+            version: super::version,
+            private_data: Box::into_raw(private_data) as *mut c_void,
+            library_marker_id: crate::get_library_marker_id,
+        })
+    }
+}
+
+/// [`ContextProvider`] available to a relation planner through a
+/// [`ForeignRelationPlannerContext`]. Functions and configuration are
+/// resolved against the task context of the planner's codec. Tables are not
+/// available and must instead be planned through
+/// [`RelationPlannerContext::plan`].
+struct TaskContextFunctionProvider {
+    task_ctx: Arc<TaskContext>,
+}
+
+impl ContextProvider for TaskContextFunctionProvider {
+    fn get_table_source(&self, name: TableReference) -> Result<Arc<dyn TableSource>> {
+        plan_err!(
+            "Table {name} is not available to a foreign relation planner, plan it through the relation planner context instead"
+        )
+    }
+
+    fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
+        self.task_ctx.scalar_functions().get(name).cloned()
+    }
+
+    fn get_higher_order_meta(&self, name: &str) -> Option<Arc<HigherOrderUDF>> {
+        self.task_ctx.higher_order_functions().get(name).cloned()
+    }
+
+    fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>> {
+        self.task_ctx.aggregate_functions().get(name).cloned()
+    }
+
+    fn get_window_meta(&self, name: &str) -> Option<Arc<WindowUDF>> {
+        self.task_ctx.window_functions().get(name).cloned()
+    }
+
+    fn get_variable_type(&self, _variable_names: &[String]) -> Option<DataType> {
+        None
+    }
+
+    fn options(&self) -> &ConfigOptions {
+        self.task_ctx.session_config().options()
+    }
+
+    fn udf_names(&self) -> Vec<String> {
+        self.task_ctx.scalar_functions().keys().cloned().collect()
+    }
+
+    fn higher_order_function_names(&self) -> Vec<String> {
+        self.task_ctx
+            .higher_order_functions()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    fn udaf_names(&self) -> Vec<String> {
+        self.task_ctx
+            .aggregate_functions()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    fn udwf_names(&self) -> Vec<String> {
+        self.task_ctx.window_functions().keys().cloned().collect()
+    }
+}
+
+/// The receiver side of an [`FFI_RelationPlannerContext`], handed to the
+/// planner by [`FFI_RelationPlanner`].
+struct ForeignRelationPlannerContext {
+    context: FFI_RelationPlannerContext,
+    provider: TaskContextFunctionProvider,
+}
+
+impl RelationPlannerContext for ForeignRelationPlannerContext {
+    fn context_provider(&self) -> &dyn ContextProvider {
+        &self.provider
+    }
+
+    fn plan(&mut self, relation: TableFactor) -> Result<LogicalPlan> {
+        let plan_serialized = unsafe {
+            df_result!((self.context.plan)(
+                &mut self.context,
+                relation.to_string().as_str().into()
+            ))?
+        };
+        plan_from_ffi(&plan_serialized, &self.context.logical_codec)
+    }
+
+    fn sql_to_expr(&mut self, expr: SQLExpr, schema: &DFSchema) -> Result<Expr> {
+        let schema = df_schema_to_ffi(schema)?;
+        let expr_serialized = unsafe {
+            df_result!((self.context.sql_to_expr)(
+                &mut self.context,
+                expr.to_string().as_str().into(),
+                schema
+            ))?
+        };
+        expr_from_ffi(&expr_serialized, &self.context.logical_codec)
+    }
+
+    fn sql_expr_to_logical_expr(
+        &mut self,
+        expr: SQLExpr,
+        schema: &DFSchema,
+    ) -> Result<Expr> {
+        let schema = df_schema_to_ffi(schema)?;
+        let expr_serialized = unsafe {
+            df_result!((self.context.sql_expr_to_logical_expr)(
+                &mut self.context,
+                expr.to_string().as_str().into(),
+                schema
+            ))?
+        };
+        expr_from_ffi(&expr_serialized, &self.context.logical_codec)
+    }
+
+    fn normalize_ident(&self, ident: Ident) -> String {
+        unsafe {
+            (self.context.normalize_ident)(
+                &self.context,
+                ident.value.as_str().into(),
+                ident.quote_style.map(u32::from).into(),
+            )
+            .to_string()
+        }
+    }
+
+    fn object_name_to_table_reference(&self, name: ObjectName) -> Result<TableReference> {
+        let reference = unsafe {
+            df_result!((self.context.object_name_to_table_reference)(
+                &self.context,
+                name.to_string().as_str().into()
+            ))?
+        };
+        table_reference_from_ffi(&reference)
+    }
+}
+
+unsafe extern "C" fn plan_relation_fn_wrapper(
+    planner: &FFI_RelationPlanner,
+    relation_sql: SString,
+    context: FFI_RelationPlannerContext,
+) -> FFI_Result<FFI_Option<FFI_PlannedRelation>> {
+    let task_ctx: Arc<TaskContext> =
+        sresult_return!((&planner.logical_codec.task_ctx_provider).try_into());
+    let provider = TaskContextFunctionProvider { task_ctx };
+    let dialect = sresult_return!(dialect_from_options(provider.options()));
+    let relation =
+        sresult_return!(parse_sql(dialect.as_ref(), &relation_sql, |parser| {
+            parser.parse_table_factor()
+        }));
+
+    let mut context = ForeignRelationPlannerContext { context, provider };
+    let planned =
+        match sresult_return!(planner.inner().plan_relation(relation, &mut context)) {
+            RelationPlanning::Planned(planned) => planned,
+            RelationPlanning::Original(_) => return FFI_Result::Ok(FFI_Option::None),
+        };
+
+    let plan = sresult_return!(plan_to_ffi(&planned.plan, &planner.logical_codec));
+    let alias = planned
+        .alias
+        .map(|alias| SString::from(alias.to_string().as_str()));
+
+    FFI_Result::Ok(FFI_Option::Some(FFI_PlannedRelation {
+        plan,
+        alias: alias.into(),
+    }))
+}
+
+unsafe extern "C" fn release_fn_wrapper(planner: &mut FFI_RelationPlanner) {
+    unsafe {
+        debug_assert!(!planner.private_data.is_null());
+        let private_data =
+            Box::from_raw(planner.private_data as *mut RelationPlannerPrivateData);
+        drop(private_data);
+        planner.private_data = std::ptr::null_mut();
+    }
+}
+
+unsafe extern "C" fn clone_fn_wrapper(
+    planner: &FFI_RelationPlanner,
+) -> FFI_RelationPlanner {
+    FFI_RelationPlanner::new_with_ffi_codec(
+        Arc::clone(planner.inner()),
+        planner.runtime(),
+        planner.logical_codec.clone(),
+    )
+}
+
+impl Drop for FFI_RelationPlanner {
+    fn drop(&mut self) {
+        unsafe { (self.release)(self) }
+    }
+}
+
+impl Clone for FFI_RelationPlanner {
+    fn clone(&self) -> Self {
+        unsafe { (self.clone)(self) }
+    }
+}
+
+impl FFI_RelationPlanner {
+    /// Creates a new [`FFI_RelationPlanner`] with a native logical extension
+    /// codec, used to pass plans and expressions to and from the planner.
+    pub fn new(
+        planner: Arc<dyn RelationPlanner>,
+        runtime: Option<Handle>,
+        task_ctx_provider: impl Into<FFI_TaskContextProvider>,
+        logical_codec: Arc<dyn LogicalExtensionCodec>,
+    ) -> Self {
+        let logical_codec = FFI_LogicalExtensionCodec::new(
+            logical_codec,
+            runtime.clone(),
+            task_ctx_provider.into(),
+        );
+        Self::new_with_ffi_codec(planner, runtime, logical_codec)
+    }
+
+    /// Creates a new [`FFI_RelationPlanner`] using a prebuilt FFI extension codec.
+    pub fn new_with_ffi_codec(
+        planner: Arc<dyn RelationPlanner>,
+        runtime: Option<Handle>,
+        logical_codec: FFI_LogicalExtensionCodec,
+    ) -> Self {
+        let private_data = Box::new(RelationPlannerPrivateData { planner, runtime });
+        let private_data = Box::into_raw(private_data) as *mut c_void;
+
+        Self {
+            plan_relation: plan_relation_fn_wrapper,
+            logical_codec,
+            clone: clone_fn_wrapper,
+            release: release_fn_wrapper,
+            version: super::version,
+            private_data,
+            library_marker_id: crate::get_library_marker_id,
+        }
+    }
+}
+
+/// This wrapper struct exists on the receiver side of the FFI interface, so it has
+/// no guarantees about being able to access the data in `private_data`. Any functions
+/// defined on this struct must only use the stable functions provided in
+/// FFI_RelationPlanner to interact with the foreign planner.
+///
+/// When the foreign planner returns [`RelationPlanning::Original`], the
+/// relation originally passed to it is returned, ignoring any changes the
+/// foreign planner made to its copy.
+#[derive(Debug)]
+pub struct ForeignRelationPlanner(FFI_RelationPlanner);
+
+unsafe impl Send for ForeignRelationPlanner {}
+unsafe impl Sync for ForeignRelationPlanner {}
+
+impl From<&FFI_RelationPlanner> for Arc<dyn RelationPlanner> {
+    fn from(planner: &FFI_RelationPlanner) -> Self {
+        if (planner.library_marker_id)() == crate::get_library_marker_id() {
+            return Arc::clone(planner.inner());
+        }
+
+        Arc::new(ForeignRelationPlanner(planner.clone()))
+    }
+}
+
+impl RelationPlanner for ForeignRelationPlanner {
+    fn plan_relation(
+        &self,
+        relation: TableFactor,
+        context: &mut dyn RelationPlannerContext,
+    ) -> Result<RelationPlanning> {
+        let relation_sql = relation.to_string();
+        let ffi_context =
+            FFI_RelationPlannerContext::new(context, self.0.logical_codec.clone())?;
+
+        let planned = unsafe {
+            df_result!((self.0.plan_relation)(
+                &self.0,
+                relation_sql.as_str().into(),
+                ffi_context
+            ))?
+        };
+        let Some(planned) = planned.into_option() else {
+            return Ok(RelationPlanning::Original(Box::new(relation)));
+        };
+
+        let plan = plan_from_ffi(&planned.plan, &self.0.logical_codec)?;
+        let alias = match planned.alias.into_option() {
+            Some(alias) => {
+                let dialect = dialect_from_options(context.context_provider().options())?;
+                // Parse the alias in the position it takes after a table name
+                let aliased = format!("t {alias}");
+                match parse_sql(dialect.as_ref(), &aliased, |parser| {
+                    parser.parse_table_factor()
+                })? {
+                    TableFactor::Table { alias, .. } => alias,
+                    _ => None,
+                }
+            }
+            None => None,
+        };
+
+        Ok(RelationPlanning::Planned(Box::new(PlannedRelation::new(
+            plan, alias,
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::assert_batches_eq;
+    use datafusion_expr::LogicalPlanBuilder;
+    use datafusion_proto::logical_plan::DefaultLogicalExtensionCodec;
+    use sqlparser::dialect::GenericDialect;
+
+    use super::*;
+
+    /// Plans the table `numbers` as the values greater than one out of
+    /// `(VALUES (1), (2), (3))`, planning both through the context.
+    #[derive(Debug)]
+    struct NumbersPlanner;
+
+    impl RelationPlanner for NumbersPlanner {
+        fn plan_relation(
+            &self,
+            relation: TableFactor,
+            context: &mut dyn RelationPlannerContext,
+        ) -> Result<RelationPlanning> {
+            let TableFactor::Table { name, alias, .. } = &relation else {
+                return Ok(RelationPlanning::Original(Box::new(relation)));
+            };
+            if context.object_name_to_table_reference(name.clone())?
+                != TableReference::bare("numbers")
+            {
+                return Ok(RelationPlanning::Original(Box::new(relation)));
+            }
+            let alias = alias.clone();
+
+            let values = parse_sql(&GenericDialect {}, "(VALUES (1), (2), (3))", |p| {
+                p.parse_table_factor()
+            })?;
+            let plan = context.plan(values)?;
+            let predicate =
+                parse_sql(&GenericDialect {}, "column1 > 1", |p| p.parse_expr())?;
+            let predicate = context.sql_to_expr(predicate, plan.schema())?;
+            let column = context.normalize_ident(Ident::new("N"));
+
+            let plan = LogicalPlanBuilder::from(plan)
+                .filter(predicate)?
+                .project(vec![datafusion_expr::col("column1").alias(column)])?
+                .build()?;
+
+            Ok(RelationPlanning::Planned(Box::new(PlannedRelation::new(
+                plan, alias,
+            ))))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_ffi_relation_planner() -> Result<()> {
+        let (ctx, task_ctx_provider) = crate::util::tests::test_session_and_ctx();
+        let mut ffi_planner = FFI_RelationPlanner::new(
+            Arc::new(NumbersPlanner),
+            None,
+            task_ctx_provider,
+            Arc::new(DefaultLogicalExtensionCodec {}),
+        );
+        ffi_planner.library_marker_id = crate::mock_foreign_marker_id;
+
+        let foreign_planner: Arc<dyn RelationPlanner> = (&ffi_planner).into();
+        ctx.register_relation_planner(foreign_planner)?;
+
+        let results = ctx
+            .sql("SELECT x.n FROM NUMBERS AS x ORDER BY x.n")
+            .await?
+            .collect()
+            .await?;
+
+        assert_batches_eq!(
+            ["+---+", "| n |", "+---+", "| 2 |", "| 3 |", "+---+"],
+            &results
+        );
+
+        let results = ctx
+            .sql("SELECT * FROM (VALUES (4)) AS v")
+            .await?
+            .collect()
+            .await?;
+        assert_batches_eq!(
+            [
+                "+---------+",
+                "| column1 |",
+                "+---------+",
+                "| 4       |",
+                "+---------+"
+            ],
+            &results
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_local_bypass() {
+        let (_ctx, task_ctx_provider) = crate::util::tests::test_session_and_ctx();
+        let planner: Arc<dyn RelationPlanner> = Arc::new(NumbersPlanner);
+        let ffi_planner = FFI_RelationPlanner::new(
+            Arc::clone(&planner),
+            None,
+            task_ctx_provider,
+            Arc::new(DefaultLogicalExtensionCodec {}),
+        );
+
+        let recovered: Arc<dyn RelationPlanner> = (&ffi_planner).into();
+        assert!(Arc::ptr_eq(&planner, &recovered));
+    }
+}
@@ -604,6 +604,8 @@ impl TryFrom<&FFI_SessionRef> for ForeignSession {
                     )
                 })
                 .collect();
+            // Shares the object stores of the session's runtime environment
+            let task_ctx: Arc<TaskContext> = (session.task_ctx)(session).into();
             Ok(Self {
                 session: session.clone(),
                 config,
@@ -614,7 +616,7 @@ impl TryFrom<&FFI_SessionRef> for ForeignSession {
                 aggregate_functions,
                 window_functions,
                 extension_types: Arc::new(MemoryExtensionTypeRegistry::default()),
-                runtime_env: Default::default(),
+                runtime_env: task_ctx.runtime_env(),
                 props: Default::default(),
                 query_planner: OnceLock::new(),
                 physical_optimizers: OnceLock::new(),
@@ -0,0 +1,225 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{Int64Array, RecordBatch, StringArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use async_trait::async_trait;
+use datafusion_common::tree_node::TreeNodeRecursion;
+use datafusion_common::{Result, Statistics, not_impl_err};
+use datafusion_datasource::PartitionedFile;
+use datafusion_datasource::TableSchema;
+use datafusion_datasource::file::FileSource;
+use datafusion_datasource::file_compression_type::FileCompressionType;
+use datafusion_datasource::file_format::FileFormat;
+use datafusion_datasource::file_scan_config::FileScanConfig;
+use datafusion_datasource::file_stream::{FileOpenFuture, FileOpener};
+use datafusion_datasource::source::DataSourceExec;
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_expr::projection::ProjectionExprs;
+use datafusion_physical_plan::metrics::ExecutionPlanMetricsSet;
+use datafusion_physical_plan::{ExecutionPlan, apply_expression_roots};
+use datafusion_session::Session;
+use futures::StreamExt;
+use object_store::{ObjectMeta, ObjectStore, ObjectStoreExt};
+
+use crate::file_format::FFI_FileFormat;
+use crate::proto::logical_extension_codec::FFI_LogicalExtensionCodec;
+use crate::proto::physical_extension_codec::FFI_PhysicalExtensionCodec;
+
+/// Schema of `.txt` files: each line of a file becomes a row holding the
+/// line and its length.
+fn text_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("line", DataType::Utf8, false),
+        Field::new("len", DataType::Int64, false),
+    ]))
+}
+
+/// A format reading text files line by line.
+#[derive(Debug)]
+struct TextFormat;
+
+#[async_trait]
+impl FileFormat for TextFormat {
+    fn get_ext(&self) -> String {
+        "txt".to_string()
+    }
+
+    fn get_ext_with_compression(
+        &self,
+        file_compression_type: &FileCompressionType,
+    ) -> Result<String> {
+        if file_compression_type.is_compressed() {
+            return not_impl_err!("Compressed text files are not supported");
+        }
+        Ok(self.get_ext())
+    }
+
+    fn compression_type(&self) -> Option<FileCompressionType> {
+        None
+    }
+
+    async fn infer_schema(
+        &self,
+        _state: &dyn Session,
+        _store: &Arc<dyn ObjectStore>,
+        _objects: &[ObjectMeta],
+    ) -> Result<SchemaRef> {
+        Ok(text_schema())
+    }
+
+    async fn infer_stats(
+        &self,
+        _state: &dyn Session,
+        _store: &Arc<dyn ObjectStore>,
+        table_schema: SchemaRef,
+        _object: &ObjectMeta,
+    ) -> Result<Statistics> {
+        Ok(Statistics::new_unknown(&table_schema))
+    }
+
+    async fn create_physical_plan(
+        &self,
+        _state: &dyn Session,
+        conf: FileScanConfig,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(DataSourceExec::from_data_source(conf))
+    }
+
+    fn file_source(&self, table_schema: TableSchema) -> Arc<dyn FileSource> {
+        Arc::new(TextSource {
+            table_schema,
+            projection: None,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+}
+
+#[derive(Clone)]
+struct TextSource {
+    table_schema: TableSchema,
+    projection: Option<ProjectionExprs>,
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl FileSource for TextSource {
+    fn create_file_opener(
+        &self,
+        object_store: Arc<dyn ObjectStore>,
+        _base_config: &FileScanConfig,
+        _partition: usize,
+    ) -> Result<Arc<dyn FileOpener>> {
+        Ok(Arc::new(TextOpener {
+            store: object_store,
+            file_schema: Arc::clone(self.table_schema.file_schema()),
+            projection: self.projection.clone(),
+        }))
+    }
+
+    fn table_schema(&self) -> &TableSchema {
+        &self.table_schema
+    }
+
+    fn with_batch_size(&self, _batch_size: usize) -> Arc<dyn FileSource> {
+        Arc::new(self.clone())
+    }
+
+    fn projection(&self) -> Option<&ProjectionExprs> {
+        self.projection.as_ref()
+    }
+
+    fn metrics(&self) -> &ExecutionPlanMetricsSet {
+        &self.metrics
+    }
+
+    fn file_type(&self) -> &str {
+        "txt"
+    }
+
+    fn try_pushdown_projection(
+        &self,
+        projection: &ProjectionExprs,
+    ) -> Result<Option<Arc<dyn FileSource>>> {
+        let projection = match &self.projection {
+            Some(current) => current.try_merge(projection)?,
+            None => projection.clone(),
+        };
+        Ok(Some(Arc::new(Self {
+            projection: Some(projection),
+            ..self.clone()
+        })))
+    }
+
+    fn apply_expressions(
+        &self,
+        f: &mut dyn FnMut(&Arc<dyn PhysicalExpr>) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        let projection = self
+            .projection
+            .iter()
+            .flat_map(|p| p.iter().map(|p| &p.expr));
+        apply_expression_roots(projection, f)
+    }
+}
+
+struct TextOpener {
+    store: Arc<dyn ObjectStore>,
+    file_schema: SchemaRef,
+    projection: Option<ProjectionExprs>,
+}
+
+impl FileOpener for TextOpener {
+    fn open(&self, file: PartitionedFile) -> Result<FileOpenFuture> {
+        let store = Arc::clone(&self.store);
+        let file_schema = Arc::clone(&self.file_schema);
+        let projection = self.projection.clone();
+
+        Ok(Box::pin(async move {
+            let bytes = store.get(&file.object_meta.location).await?.bytes().await?;
+            let text = String::from_utf8_lossy(&bytes);
+            let lines = text.lines().collect::<Vec<_>>();
+            let lengths =
+                Int64Array::from_iter_values(lines.iter().map(|line| line.len() as i64));
+            let lines = StringArray::from(lines);
+
+            let mut batch = RecordBatch::try_new(
+                file_schema,
+                vec![Arc::new(lines), Arc::new(lengths)],
+            )?;
+            if let Some(projection) = projection {
+                batch = projection
+                    .make_projector(batch.schema_ref())?
+                    .project_batch(&batch)?;
+            }
+            Ok(futures::stream::iter([Ok(batch)]).boxed())
+        }))
+    }
+}
+
+pub(crate) extern "C" fn create_file_format(
+    logical_codec: FFI_LogicalExtensionCodec,
+    physical_codec: FFI_PhysicalExtensionCodec,
+) -> FFI_FileFormat {
+    FFI_FileFormat::new_with_ffi_codecs(
+        Arc::new(TextFormat),
+        None,
+        logical_codec,
+        physical_codec,
+    )
+}
@@ -0,0 +1,95 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::error::Result;
+use datafusion_common::plan_err;
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRecursion};
+use datafusion_expr::LogicalPlan;
+use datafusion_optimizer::optimizer::ApplyOrder;
+use datafusion_optimizer::{AnalyzerRule, OptimizerConfig, OptimizerRule};
+
+use crate::analyzer_rule::FFI_AnalyzerRule;
+use crate::optimizer_rule::FFI_OptimizerRule;
+use crate::proto::logical_extension_codec::FFI_LogicalExtensionCodec;
+
+/// A rule that replaces every `LIMIT` with its input, which tests can observe
+/// in the number of rows returned.
+#[derive(Debug)]
+struct RemoveLimitRule;
+
+impl OptimizerRule for RemoveLimitRule {
+    fn name(&self) -> &str {
+        "remove_limit_rule"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+
+    fn rewrite(
+        &self,
+        plan: LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> Result<Transformed<LogicalPlan>> {
+        match plan {
+            LogicalPlan::Limit(limit) => {
+                Ok(Transformed::yes(Arc::unwrap_or_clone(limit.input)))
+            }
+            plan => Ok(Transformed::no(plan)),
+        }
+    }
+}
+
+pub(crate) extern "C" fn create_optimizer_rule(
+    logical_codec: FFI_LogicalExtensionCodec,
+) -> FFI_OptimizerRule {
+    FFI_OptimizerRule::new_with_ffi_codec(Arc::new(RemoveLimitRule), None, logical_codec)
+}
+
+/// A rule that rejects plans containing a `LIMIT`.
+#[derive(Debug)]
+struct DenyLimitRule;
+
+impl AnalyzerRule for DenyLimitRule {
+    fn analyze(&self, plan: LogicalPlan, _config: &ConfigOptions) -> Result<LogicalPlan> {
+        let mut has_limit = false;
+        plan.apply(|plan| {
+            has_limit = matches!(plan, LogicalPlan::Limit(_));
+            Ok(match has_limit {
+                true => TreeNodeRecursion::Stop,
+                false => TreeNodeRecursion::Continue,
+            })
+        })?;
+        if has_limit {
+            return plan_err!("LIMIT is not allowed");
+        }
+        Ok(plan)
+    }
+
+    fn name(&self) -> &str {
+        "deny_limit_rule"
+    }
+}
+
+pub(crate) extern "C" fn create_analyzer_rule(
+    logical_codec: FFI_LogicalExtensionCodec,
+) -> FFI_AnalyzerRule {
+    FFI_AnalyzerRule::new_with_ffi_codec(Arc::new(DenyLimitRule), None, logical_codec)
+}
@@ -38,15 +38,21 @@ use udf_udaf_udwf::{
     create_ffi_table_func,
 };
 
+use crate::analyzer_rule::FFI_AnalyzerRule;
 use crate::catalog_provider::FFI_CatalogProvider;
 use crate::catalog_provider_list::FFI_CatalogProviderList;
 use crate::config::extension_options::FFI_ExtensionOptions;
 use crate::execution_plan::FFI_ExecutionPlan;
 use crate::execution_plan::tests::{EmptyExec, create_dynamic_filter};
+use crate::expr_planner::FFI_ExprPlanner;
+use crate::file_format::FFI_FileFormat;
+use crate::object_store::FFI_ObjectStore;
+use crate::optimizer_rule::FFI_OptimizerRule;
 use crate::physical_optimizer::FFI_PhysicalOptimizerRule;
 use crate::proto::logical_extension_codec::FFI_LogicalExtensionCodec;
 use crate::proto::physical_extension_codec::FFI_PhysicalExtensionCodec;
 use crate::query_planner::FFI_QueryPlanner;
+use crate::relation_planner::FFI_RelationPlanner;
 use crate::table_provider::FFI_TableProvider;
 use crate::table_provider_factory::FFI_TableProviderFactory;
 use crate::tests::catalog::create_catalog_provider_list;
@@ -59,8 +65,12 @@ use crate::util::FFI_Option;
 mod async_provider;
 pub mod catalog;
 pub mod config;
+mod file_format;
+mod logical_optimizer;
+pub mod object_store;
 mod physical_optimizer;
 mod query_planner;
+mod sql_planner;
 mod sync_provider;
 mod table_provider_factory;
 mod udf_udaf_udwf;
@@ -139,6 +149,31 @@ pub struct ForeignLibraryModule {
 
     /// Create an aggregate UDAF using first_value
     pub create_first_value_udaf: extern "C" fn() -> FFI_AggregateUDF,
+
+    /// Create a file format reading the lines of `.txt` files
+    pub create_file_format: extern "C" fn(
+        logical_codec: FFI_LogicalExtensionCodec,
+        physical_codec: FFI_PhysicalExtensionCodec,
+    ) -> FFI_FileFormat,
+
+    /// Create an in-memory object store holding a single text file
+    pub create_object_store: extern "C" fn() -> FFI_ObjectStore,
+
+    /// Create a logical optimizer rule removing every `LIMIT`
+    pub create_optimizer_rule:
+        extern "C" fn(logical_codec: FFI_LogicalExtensionCodec) -> FFI_OptimizerRule,
+
+    /// Create an analyzer rule rejecting plans with a `LIMIT`
+    pub create_analyzer_rule:
+        extern "C" fn(logical_codec: FFI_LogicalExtensionCodec) -> FFI_AnalyzerRule,
+
+    /// Create an expression planner planning `->>` as an addition
+    pub create_expr_planner:
+        extern "C" fn(logical_codec: FFI_LogicalExtensionCodec) -> FFI_ExprPlanner,
+
+    /// Create a relation planner for the table `numbers`
+    pub create_relation_planner:
+        extern "C" fn(logical_codec: FFI_LogicalExtensionCodec) -> FFI_RelationPlanner,
 }
 
 pub fn create_test_schema() -> Arc<Schema> {
@@ -370,5 +405,11 @@ pub extern "C" fn datafusion_ffi_get_module() -> ForeignLibraryModule {
         create_query_planner: query_planner::create_query_planner,
         version: super::version,
         create_first_value_udaf: create_ffi_first_value_func,
+        create_file_format: file_format::create_file_format,
+        create_object_store: object_store::create_object_store,
+        create_optimizer_rule: logical_optimizer::create_optimizer_rule,
+        create_analyzer_rule: logical_optimizer::create_analyzer_rule,
+        create_expr_planner: sql_planner::create_expr_planner,
+        create_relation_planner: sql_planner::create_relation_planner,
     }
 }
@@ -0,0 +1,40 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use object_store::memory::InMemory;
+use object_store::path::Path;
+use object_store::{ObjectStoreExt, PutPayload};
+
+use crate::object_store::FFI_ObjectStore;
+
+/// Location of the object preloaded into the store created by
+/// [`create_object_store`].
+pub const TEST_OBJECT_LOCATION: &str = "data/lines.txt";
+
+/// Create an in-memory object store holding a single text file.
+pub(crate) extern "C" fn create_object_store() -> FFI_ObjectStore {
+    let store = InMemory::new();
+    let payload = PutPayload::from_static(b"hello\nforeign\nworld");
+
+    // The in-memory store completes its futures without waiting on IO
+    futures::executor::block_on(store.put(&Path::from(TEST_OBJECT_LOCATION), payload))
+        .expect("in-memory put should not fail");
+
+    FFI_ObjectStore::new(Arc::new(store), None)
+}
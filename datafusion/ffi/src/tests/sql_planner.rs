@@ -0,0 +1,97 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use datafusion_common::{DFSchema, Result, ScalarValue};
+use datafusion_expr::planner::{
+    ExprPlanner, PlannedRelation, PlannerResult, RawBinaryExpr, RelationPlanner,
+    RelationPlannerContext, RelationPlanning,
+};
+use datafusion_expr::{Expr, LogicalPlanBuilder, col};
+use sqlparser::ast::{BinaryOperator, TableFactor};
+
+use crate::expr_planner::FFI_ExprPlanner;
+use crate::proto::logical_extension_codec::FFI_LogicalExtensionCodec;
+use crate::relation_planner::FFI_RelationPlanner;
+
+/// Plans the `->>` operator as an addition.
+#[derive(Debug)]
+struct LongArrowPlanner;
+
+impl ExprPlanner for LongArrowPlanner {
+    fn plan_binary_op(
+        &self,
+        expr: RawBinaryExpr,
+        _schema: &DFSchema,
+    ) -> Result<PlannerResult<RawBinaryExpr>> {
+        match expr.op {
+            BinaryOperator::LongArrow => {
+                Ok(PlannerResult::Planned(expr.left + expr.right))
+            }
+            _ => Ok(PlannerResult::Original(expr)),
+        }
+    }
+}
+
+pub(crate) extern "C" fn create_expr_planner(
+    logical_codec: FFI_LogicalExtensionCodec,
+) -> FFI_ExprPlanner {
+    FFI_ExprPlanner::new_with_ffi_codec(Arc::new(LongArrowPlanner), None, logical_codec)
+}
+
+/// Plans the table `numbers` as the values one to three in a column named
+/// `number`.
+#[derive(Debug)]
+struct NumbersPlanner;
+
+impl RelationPlanner for NumbersPlanner {
+    fn plan_relation(
+        &self,
+        relation: TableFactor,
+        context: &mut dyn RelationPlannerContext,
+    ) -> Result<RelationPlanning> {
+        let TableFactor::Table { name, alias, .. } = &relation else {
+            return Ok(RelationPlanning::Original(Box::new(relation)));
+        };
+        if context
+            .object_name_to_table_reference(name.clone())?
+            .table()
+            != "numbers"
+        {
+            return Ok(RelationPlanning::Original(Box::new(relation)));
+        }
+        let alias = alias.clone();
+
+        let rows = (1..=3)
+            .map(|v| vec![Expr::Literal(ScalarValue::Int64(Some(v)), None)])
+            .collect();
+        let plan = LogicalPlanBuilder::values(rows)?
+            .project(vec![col("column1").alias("number")])?
+            .build()?;
+
+        Ok(RelationPlanning::Planned(Box::new(PlannedRelation::new(
+            plan, alias,
+        ))))
+    }
+}
+
+pub(crate) extern "C" fn create_relation_planner(
+    logical_codec: FFI_LogicalExtensionCodec,
+) -> FFI_RelationPlanner {
+    FFI_RelationPlanner::new_with_ffi_codec(Arc::new(NumbersPlanner), None, logical_codec)
+}
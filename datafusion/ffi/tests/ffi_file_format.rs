@@ -0,0 +1,105 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+mod utils;
+
+/// Add an additional module here for convenience to scope this to only
+/// when the feature integration-tests is built
+#[cfg(feature = "integration-tests")]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::assert_batches_eq;
+    use datafusion::datasource::file_format::FileFormat;
+    use datafusion::datasource::listing::{
+        ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
+    };
+    use datafusion::error::Result;
+    use datafusion_execution::TaskContextProvider;
+    use datafusion_ffi::execution::FFI_TaskContextProvider;
+    use datafusion_ffi::file_format::ForeignFileFormat;
+    use datafusion_ffi::proto::physical_extension_codec::FFI_PhysicalExtensionCodec;
+    use datafusion_ffi::tests::utils::get_module;
+    use datafusion_proto::physical_plan::DefaultPhysicalExtensionCodec;
+    use object_store::ObjectStore;
+    use url::Url;
+
+    #[tokio::test]
+    async fn test_ffi_file_format() -> Result<()> {
+        let module = get_module()?;
+        let (ctx, logical_codec) = crate::utils::ctx_and_codec();
+        let task_ctx_provider = Arc::clone(&ctx) as Arc<dyn TaskContextProvider>;
+        let task_ctx_provider = FFI_TaskContextProvider::from(&task_ctx_provider);
+        let physical_codec = FFI_PhysicalExtensionCodec::new(
+            Arc::new(DefaultPhysicalExtensionCodec {}),
+            None,
+            task_ctx_provider,
+        );
+
+        // The store is foreign to the host, which makes it available to the
+        // plans of the foreign format through the task context
+        let ffi_store = (module.create_object_store)();
+        let store: Arc<dyn ObjectStore> = (&ffi_store).into();
+        ctx.register_object_store(&Url::parse("memory://plugin").unwrap(), store);
+
+        let ffi_format = (module.create_file_format)(logical_codec, physical_codec);
+        let format: Arc<dyn FileFormat> = (&ffi_format).into();
+        assert!(format.downcast_ref::<ForeignFileFormat>().is_some());
+        assert_eq!(format.get_ext(), "txt");
+
+        let table_url = ListingTableUrl::parse("memory://plugin/data/")?;
+        let options = ListingOptions::new(format).with_file_extension("txt");
+        let schema = options.infer_schema(&ctx.state(), &table_url).await?;
+        let config = ListingTableConfig::new(table_url)
+            .with_listing_options(options)
+            .with_schema(schema);
+        ctx.register_table("lines", Arc::new(ListingTable::try_new(config)?))?;
+
+        let batches = ctx.sql("SELECT * FROM lines").await?.collect().await?;
+        assert_batches_eq!(
+            [
+                "+---------+-----+",
+                "| line    | len |",
+                "+---------+-----+",
+                "| hello   | 5   |",
+                "| foreign | 7   |",
+                "| world   | 5   |",
+                "+---------+-----+",
+            ],
+            &batches
+        );
+
+        // Projections and filters are applied to the foreign scan
+        let batches = ctx
+            .sql("SELECT line FROM lines WHERE len > 5")
+            .await?
+            .collect()
+            .await?;
+        assert_batches_eq!(
+            [
+                "+---------+",
+                "| line    |",
+                "+---------+",
+                "| foreign |",
+                "+---------+",
+            ],
+            &batches
+        );
+
+        Ok(())
+    }
+}
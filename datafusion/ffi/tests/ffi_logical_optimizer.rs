@@ -0,0 +1,79 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+mod utils;
+
+/// Add an additional module here for convenience to scope this to only
+/// when the feature integration-tests is built
+#[cfg(feature = "integration-tests")]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::assert_batches_eq;
+    use datafusion::error::Result;
+    use datafusion_ffi::tests::utils::get_module;
+    use datafusion_optimizer::{AnalyzerRule, OptimizerRule};
+
+    const LIMIT_QUERY: &str = "SELECT a FROM (VALUES (1), (2), (3)) AS t(a) LIMIT 1";
+
+    #[tokio::test]
+    async fn test_ffi_optimizer_rule() -> Result<()> {
+        let module = get_module()?;
+        let (ctx, logical_codec) = crate::utils::ctx_and_codec();
+
+        let ffi_rule = (module.create_optimizer_rule)(logical_codec);
+        let rule: Arc<dyn OptimizerRule + Send + Sync> = (&ffi_rule).into();
+        assert_eq!(rule.name(), "remove_limit_rule");
+        ctx.add_optimizer_rule(rule);
+
+        let batches = ctx.sql(LIMIT_QUERY).await?.collect().await?;
+        assert_batches_eq!(
+            [
+                "+---+", "| a |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+"
+            ],
+            &batches
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ffi_analyzer_rule() -> Result<()> {
+        let module = get_module()?;
+        let (ctx, logical_codec) = crate::utils::ctx_and_codec();
+
+        let ffi_rule = (module.create_analyzer_rule)(logical_codec);
+        let rule: Arc<dyn AnalyzerRule + Send + Sync> = (&ffi_rule).into();
+        assert_eq!(rule.name(), "deny_limit_rule");
+        ctx.add_analyzer_rule(rule);
+
+        let batches = ctx
+            .sql("SELECT a FROM (VALUES (1), (2)) AS t(a) ORDER BY a")
+            .await?
+            .collect()
+            .await?;
+        assert_batches_eq!(
+            ["+---+", "| a |", "+---+", "| 1 |", "| 2 |", "+---+"],
+            &batches
+        );
+
+        let err = ctx.sql(LIMIT_QUERY).await?.collect().await.unwrap_err();
+        assert!(err.to_string().contains("LIMIT is not allowed"), "{err}");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+/// Add an additional module here for convenience to scope this to only
+/// when the feature integration-tests is built
+#[cfg(feature = "integration-tests")]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::error::{DataFusionError, Result};
+    use datafusion_ffi::tests::object_store::TEST_OBJECT_LOCATION;
+    use datafusion_ffi::tests::utils::get_module;
+    use futures::TryStreamExt;
+    use object_store::path::Path;
+    use object_store::{ObjectStore, ObjectStoreExt, PutPayload};
+
+    #[tokio::test]
+    async fn test_ffi_object_store() -> Result<()> {
+        let module = get_module()?;
+
+        let ffi_store = (module.create_object_store)();
+        let store: Arc<dyn ObjectStore> = (&ffi_store).into();
+        assert_eq!(store.to_string(), "InMemory");
+
+        let location = Path::from(TEST_OBJECT_LOCATION);
+        let bytes = store.get(&location).await?.bytes().await?;
+        assert_eq!(bytes.as_ref(), b"hello\nforeign\nworld");
+
+        let written = Path::from("data/written.txt");
+        store
+            .put(&written, PutPayload::from_static(b"from the host"))
+            .await?;
+
+        let mut listed = store
+            .list(Some(&Path::from("data")))
+            .map_ok(|meta| meta.location)
+            .try_collect::<Vec<_>>()
+            .await?;
+        listed.sort();
+        assert_eq!(listed, vec![location, written.clone()]);
+
+        store.delete(&written).await?;
+        let err = store.head(&written).await.unwrap_err();
+        assert!(matches!(err, object_store::Error::NotFound { .. }), "{err}");
+
+        Ok::<(), DataFusionError>(())
+    }
+}
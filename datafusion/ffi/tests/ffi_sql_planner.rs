@@ -0,0 +1,91 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+mod utils;
+
+/// Add an additional module here for convenience to scope this to only
+/// when the feature integration-tests is built
+#[cfg(feature = "integration-tests")]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion::assert_batches_eq;
+    use datafusion::error::Result;
+    use datafusion::execution::FunctionRegistry;
+    use datafusion_expr::planner::{ExprPlanner, RelationPlanner};
+    use datafusion_ffi::tests::utils::get_module;
+
+    #[tokio::test]
+    async fn test_ffi_expr_planner() -> Result<()> {
+        let module = get_module()?;
+        let (ctx, logical_codec) = crate::utils::ctx_and_codec();
+
+        let ffi_planner = (module.create_expr_planner)(logical_codec);
+        let planner: Arc<dyn ExprPlanner> = (&ffi_planner).into();
+
+        let mut state = ctx.state();
+        state.register_expr_planner(planner)?;
+        let ctx = datafusion::prelude::SessionContext::new_with_state(state);
+        ctx.sql("SET datafusion.sql_parser.dialect = 'postgres'")
+            .await?
+            .collect()
+            .await?;
+
+        let batches = ctx
+            .sql("SELECT a ->> 2 AS b FROM (VALUES (1), (2)) AS t(a) ORDER BY b")
+            .await?
+            .collect()
+            .await?;
+        assert_batches_eq!(
+            ["+---+", "| b |", "+---+", "| 3 |", "| 4 |", "+---+"],
+            &batches
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ffi_relation_planner() -> Result<()> {
+        let module = get_module()?;
+        let (ctx, logical_codec) = crate::utils::ctx_and_codec();
+
+        let ffi_planner = (module.create_relation_planner)(logical_codec);
+        let planner: Arc<dyn RelationPlanner> = (&ffi_planner).into();
+        ctx.register_relation_planner(planner)?;
+
+        let batches = ctx
+            .sql(
+                "SELECT n.number, v.column1 FROM numbers AS n \
+                 JOIN (VALUES (2)) AS v ON n.number = v.column1",
+            )
+            .await?
+            .collect()
+            .await?;
+        assert_batches_eq!(
+            [
+                "+--------+---------+",
+                "| number | column1 |",
+                "+--------+---------+",
+                "| 2      | 2       |",
+                "+--------+---------+",
+            ],
+            &batches
+        );
+
+        Ok(())
+    }
+}
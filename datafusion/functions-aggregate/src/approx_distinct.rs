@@ -259,7 +259,7 @@ where
 /// `GROUP BY` only observe a handful of distinct values, so keeping their state
 /// as a small list of hashes saves a huge amount of memory (both while
 /// aggregating and when serializing the partial state for the final phase).
-const SPARSE_LIMIT: usize = 256;
+pub(crate) const SPARSE_LIMIT: usize = 256;
 
 /// Per-group HyperLogLog state used by [`HllGroupsAccumulator`].
 ///
@@ -270,7 +270,7 @@ const SPARSE_LIMIT: usize = 256;
 /// by one, so the cardinality estimate is identical to the per-group
 /// [`Accumulator`] path.
 #[derive(Clone, Debug)]
-enum GroupHll {
+pub(crate) enum GroupHll {
     /// Distinct hashes seen so far. May contain duplicates between compactions.
     Sparse(Vec<u64>),
     Dense(Box<HyperLogLog<u8>>),
@@ -295,7 +295,7 @@ impl GroupHll {
     /// Add a pre-computed hash, returning the change in heap-allocated bytes so
     /// the accumulator can track its memory usage incrementally.
     #[inline]
-    fn add_hash(&mut self, hash: u64) -> isize {
+    pub(crate) fn add_hash(&mut self, hash: u64) -> isize {
         match self {
             GroupHll::Dense(hll) => {
                 hll.add_hashed(hash);
@@ -336,7 +336,7 @@ impl GroupHll {
 
     /// Merge a serialized state (produced by [`Self::serialize`] or by the
     /// per-group [`Accumulator`]) into this sketch.
-    fn merge_serialized(&mut self, bytes: &[u8]) -> Result<isize> {
+    pub(crate) fn merge_serialized(&mut self, bytes: &[u8]) -> Result<isize> {
         if bytes.is_empty() {
             return Ok(0);
         }
@@ -387,7 +387,7 @@ impl GroupHll {
     }
 
     /// The approximate number of distinct values seen by this group.
-    fn count(&self) -> u64 {
+    pub(crate) fn count(&self) -> u64 {
         match self {
             GroupHll::Dense(hll) => hll.count() as u64,
             // Estimate directly from the stored hashes; this produces exactly the
@@ -400,7 +400,7 @@ impl GroupHll {
     /// Heap bytes held by this sketch. Mirrors the deltas accrued in
     /// [`Self::add_hash`] / [`Self::merge_dense`] so emitting a group can
     /// precisely reverse them.
-    fn heap_bytes(&self) -> usize {
+    pub(crate) fn heap_bytes(&self) -> usize {
         match self {
             GroupHll::Sparse(v) => v.capacity() * size_of::<u64>(),
             GroupHll::Dense(_) => NUM_REGISTERS,
@@ -413,7 +413,7 @@ impl GroupHll {
     /// distinct hashes in little-endian order unless it has crossed
     /// [`SPARSE_LIMIT`], in which case it is emitted as dense state so the final
     /// merge path accepts it.
-    fn serialize(&mut self, scratch: &mut Vec<u8>) {
+    pub(crate) fn serialize(&mut self, scratch: &mut Vec<u8>) {
         scratch.clear();
         match self {
             GroupHll::Dense(hll) => {
@@ -0,0 +1,418 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the `approx_top_k` aggregate function, which returns the most
+//! frequent values and their approximate counts using a Misra-Gries summary.
+//!
+//! The summary keeps a bounded number of counters. When it runs out of
+//! counters, the counts of all values are decreased by the count of the
+//! median kept value, and the values whose count drops to zero are evicted.
+//! Counts are therefore lower bounds of the actual counts, off by at most
+//! the total decrease, and any value more frequent than that is kept.
+
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, StructArray, UInt64Array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use arrow::row::{RowConverter, Rows, SortField};
+use datafusion_common::types::{NativeType, logical_int64};
+use datafusion_common::utils::SingleRowListArrayBuilder;
+use datafusion_common::{Result, ScalarValue, exec_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Coercion, Documentation, GroupsAccumulator, Signature,
+    TypeSignatureClass, Volatility,
+};
+use datafusion_macros::user_doc;
+
+use crate::sketch::{
+    Sketch, SketchAccumulator, SketchGroupsAccumulator, SketchInput, SketchOutput,
+    validate_sketch_parameter,
+};
+
+make_udaf_expr_and_func!(
+    ApproxTopK,
+    approx_top_k,
+    expression k,
+    "most frequent values and their approximate counts",
+    approx_top_k_udaf
+);
+
+/// The bounds of the `k` argument of `approx_top_k`
+const MIN_K: usize = 1;
+const MAX_K: usize = 100_000;
+
+/// The minimum number of counters kept by the summary
+const MIN_CAPACITY: usize = 128;
+
+/// A Misra-Gries summary of the frequencies of a set of values
+///
+/// The values are stored in the row format of `converter`. The summary is
+/// serialized as
+///
+/// ```text
+/// offset: u64 | (row_len: u32 | row: [u8] | count: u64) for each counter
+/// ```
+///
+/// with little-endian integers.
+#[derive(Debug, Clone)]
+struct TopKSketch {
+    converter: Arc<RowConverter>,
+    /// The maximum number of counters
+    capacity: usize,
+    counters: HashMap<Box<[u8]>, u64>,
+    /// The total decrease of the counts, which bounds their error
+    offset: u64,
+    /// Heap bytes held by the keys of `counters`
+    key_bytes: usize,
+}
+
+impl TopKSketch {
+    fn new(converter: Arc<RowConverter>, k: usize) -> Self {
+        Self {
+            converter,
+            capacity: (8 * k).max(MIN_CAPACITY),
+            counters: HashMap::new(),
+            offset: 0,
+            key_bytes: 0,
+        }
+    }
+
+    fn add(&mut self, row: &[u8], count: u64) {
+        match self.counters.get_mut(row) {
+            Some(counter) => *counter += count,
+            None => {
+                self.key_bytes += row.len();
+                self.counters.insert(row.into(), count);
+            }
+        }
+        if self.counters.len() > self.capacity {
+            self.purge();
+        }
+    }
+
+    /// Decreases all counts by the count of the median counter of the full
+    /// summary, evicting at least half of the counters
+    fn purge(&mut self) {
+        let mut counts = self.counters.values().copied().collect::<Vec<_>>();
+        let median = counts.len() - self.capacity / 2;
+        let (_, &mut decrease, _) = counts.select_nth_unstable(median);
+        self.offset += decrease;
+        self.counters.retain(|row, count| {
+            if *count > decrease {
+                *count -= decrease;
+                true
+            } else {
+                self.key_bytes -= row.len();
+                false
+            }
+        });
+    }
+
+    /// Returns the `k` values with the highest counts, as a list of structs
+    /// of the value and its count
+    fn top_k(&self, k: usize, value_field: &FieldRef) -> Result<ScalarValue> {
+        let mut top = self.counters.iter().collect::<Vec<_>>();
+        top.sort_unstable_by(|(a_row, a_count), (b_row, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_row.cmp(b_row))
+        });
+        top.truncate(k);
+
+        let parser = self.converter.parser();
+        let rows = top.iter().map(|(row, _)| parser.parse(row));
+        let values = self.converter.convert_rows(rows)?;
+        let values = cast(&values[0], value_field.data_type())?;
+        let counts = UInt64Array::from_iter_values(top.iter().map(|(_, count)| **count));
+        let entries = StructArray::try_new(
+            entry_fields(value_field),
+            vec![values, Arc::new(counts)],
+            None,
+        )?;
+        Ok(SingleRowListArrayBuilder::new(Arc::new(entries)).build_list_scalar())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            size_of::<u64>() + self.counters.len() * 12 + self.key_bytes,
+        );
+        bytes.extend_from_slice(&self.offset.to_le_bytes());
+        for (row, count) in &self.counters {
+            bytes.extend_from_slice(&(row.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(row);
+            bytes.extend_from_slice(&count.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+impl Sketch for TopKSketch {
+    type Batch = Rows;
+
+    fn prepare(&self, values: &[ArrayRef]) -> Result<Rows> {
+        Ok(self.converter.convert_columns(&values[..1])?)
+    }
+
+    fn update(&mut self, rows: &Rows, row: usize) {
+        self.add(rows.row(row).as_ref(), 1);
+    }
+
+    fn merge(&mut self, bytes: &[u8]) -> Result<()> {
+        let invalid = || exec_err!("Invalid approx_top_k state of {} bytes", bytes.len());
+        let Some((offset, mut rest)) = bytes.split_first_chunk::<8>() else {
+            return invalid();
+        };
+        self.offset += u64::from_le_bytes(*offset);
+        while !rest.is_empty() {
+            let Some((len, remaining)) = rest.split_first_chunk::<4>() else {
+                return invalid();
+            };
+            let len = u32::from_le_bytes(*len) as usize;
+            if remaining.len() < len + size_of::<u64>() {
+                return invalid();
+            }
+            let (row, remaining) = remaining.split_at(len);
+            let (count, remaining) = remaining.split_first_chunk::<8>().unwrap();
+            self.add(row, u64::from_le_bytes(*count));
+            rest = remaining;
+        }
+        Ok(())
+    }
+
+    fn serialize(&mut self) -> Option<Vec<u8>> {
+        Some(self.to_bytes())
+    }
+
+    fn size(&self) -> usize {
+        self.counters.capacity() * (size_of::<Box<[u8]>>() + size_of::<u64>())
+            + self.key_bytes
+    }
+}
+
+/// The fields of the structs returned by `approx_top_k`
+fn entry_fields(value_field: &FieldRef) -> Fields {
+    Fields::from(vec![
+        Arc::clone(value_field),
+        Arc::new(Field::new("count", DataType::UInt64, false)),
+    ])
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Returns the approximate `k` most frequent values and their counts, as a list of structs with `value` and `count` fields ordered by decreasing count. Null values are ignored. The counts are lower bounds of the actual counts.",
+    syntax_example = "approx_top_k(expression, k)",
+    sql_example = r#"```sql
+> SELECT approx_top_k(column_name, 2) FROM table_name;
++----------------------------------------------------------+
+| approx_top_k(table_name.column_name,Int64(2))            |
++----------------------------------------------------------+
+| [{value: apple, count: 120}, {value: banana, count: 75}] |
++----------------------------------------------------------+
+```"#,
+    standard_argument(name = "expression",),
+    argument(
+        name = "k",
+        description = "Number of values to return, between 1 and 100000."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ApproxTopK {
+    signature: Signature,
+}
+
+impl Default for ApproxTopK {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApproxTopK {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![
+                    Coercion::new_exact(TypeSignatureClass::Any),
+                    Coercion::new_implicit(
+                        TypeSignatureClass::Native(logical_int64()),
+                        vec![TypeSignatureClass::Integer],
+                        NativeType::Int64,
+                    ),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+
+    fn empty_sketch_and_output(
+        &self,
+        args: &AccumulatorArgs,
+    ) -> Result<(TopKSketch, SketchOutput<TopKSketch>)> {
+        let k =
+            validate_sketch_parameter(&args.exprs[1], self.name(), "k", MIN_K..=MAX_K)?;
+        let value_type = args.expr_fields[0].data_type();
+        let converter = RowConverter::new(vec![SortField::new(value_type.clone())])?;
+        let value_field = Arc::new(Field::new("value", value_type.clone(), true));
+        let output = SketchOutput::estimate(
+            args.return_type().clone(),
+            move |sketch: &mut TopKSketch| sketch.top_k(k, &value_field),
+        );
+        Ok((TopKSketch::new(Arc::new(converter), k), output))
+    }
+}
+
+impl AggregateUDFImpl for ApproxTopK {
+    fn name(&self) -> &str {
+        "approx_top_k"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        let value_field = Arc::new(Field::new("value", arg_types[0].clone(), true));
+        Ok(DataType::new_list(
+            DataType::Struct(entry_fields(&value_field)),
+            true,
+        ))
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(vec![
+            Field::new(
+                format_state_name(args.name, "approx_top_k"),
+                DataType::Binary,
+                true,
+            )
+            .into(),
+        ])
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        let (sketch, output) = self.empty_sketch_and_output(&acc_args)?;
+        Ok(Box::new(SketchAccumulator::new(
+            sketch,
+            SketchInput::Values,
+            output,
+        )))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        let (sketch, output) = self.empty_sketch_and_output(&args)?;
+        Ok(Box::new(SketchGroupsAccumulator::new(
+            sketch,
+            SketchInput::Values,
+            output,
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int64Array, StringArray};
+
+    fn int64_sketch(k: usize) -> TopKSketch {
+        let converter = RowConverter::new(vec![SortField::new(DataType::Int64)]).unwrap();
+        TopKSketch::new(Arc::new(converter), k)
+    }
+
+    fn update(sketch: &mut TopKSketch, values: ArrayRef) -> Result<()> {
+        let rows = sketch.prepare(&[values])?;
+        (0..rows.num_rows()).for_each(|row| sketch.update(&rows, row));
+        Ok(())
+    }
+
+    #[test]
+    fn heavy_hitters_survive_purges() -> Result<()> {
+        let mut sketch = int64_sketch(2);
+        // Two heavy hitters among 10000 values seen once
+        let values = (0..10_000)
+            .flat_map(|i| [i, -1, -1, -2])
+            .collect::<Int64Array>();
+        update(&mut sketch, Arc::new(values))?;
+        assert!(sketch.counters.len() <= MIN_CAPACITY);
+        assert!(sketch.offset > 0);
+
+        let value_field = Arc::new(Field::new("value", DataType::Int64, true));
+        let ScalarValue::List(list) = sketch.top_k(2, &value_field)? else {
+            unreachable!()
+        };
+        let entries = list.value(0);
+        let entries = entries.as_any().downcast_ref::<StructArray>().unwrap();
+        let values = entries
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        let counts = entries
+            .column(1)
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(values.values(), &[-1, -2]);
+        // The counts are lower bounds, off by at most the offset
+        assert!(counts.value(0) <= 20_000 && counts.value(0) + sketch.offset >= 20_000);
+        assert!(counts.value(1) <= 10_000 && counts.value(1) + sketch.offset >= 10_000);
+        Ok(())
+    }
+
+    #[test]
+    fn merge_roundtrip() -> Result<()> {
+        let converter = RowConverter::new(vec![SortField::new(DataType::Utf8)])?;
+        let mut a = TopKSketch::new(Arc::new(converter), 3);
+        let mut b = a.clone();
+        update(&mut a, Arc::new(StringArray::from(vec!["x", "y", "x"])))?;
+        update(&mut b, Arc::new(StringArray::from(vec!["y", "y", "z"])))?;
+        a.merge(&b.to_bytes())?;
+
+        let value_field = Arc::new(Field::new("value", DataType::Utf8, true));
+        assert_eq!(
+            a.top_k(3, &value_field)?.to_string(),
+            "[{value: y, count: 3}, {value: x, count: 2}, {value: z, count: 1}]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_state() {
+        let mut sketch = int64_sketch(1);
+        let err = sketch.merge(&[0; 9]).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Invalid approx_top_k state of 9 bytes"
+        );
+    }
+}
@@ -0,0 +1,607 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the `bitmap_construct_agg` and `bitmap_or_agg` aggregate functions
+//! and the `bitmap_count` scalar function.
+//!
+//! The bitmaps are [Roaring bitmaps] of 32-bit unsigned integers, stored as
+//! binary values in the portable serialization format shared with the Roaring
+//! libraries of other languages. Their size grows with the number of positions
+//! set rather than with the largest position, so they can count distinct
+//! integers exactly over the whole `u32` range.
+//!
+//! [Roaring bitmaps]: https://roaringbitmap.org/
+
+use std::mem::{size_of, size_of_val};
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::{Array, ArrayRef, AsArray, BinaryArray, BooleanArray, Int64Array};
+use arrow::buffer::NullBuffer;
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, FieldRef, Int64Type};
+use datafusion_common::types::{NativeType, logical_int64};
+use datafusion_common::{Result, ScalarValue, exec_datafusion_err, exec_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Coercion, ColumnarValue, Documentation, EmitTo,
+    GroupsAccumulator, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature,
+    TypeSignatureClass, Volatility,
+};
+use datafusion_functions_aggregate_common::aggregate::groups_accumulator::accumulate::accumulate;
+use datafusion_functions_aggregate_common::aggregate::groups_accumulator::nulls::filter_to_nulls;
+use datafusion_macros::user_doc;
+use roaring::RoaringBitmap;
+
+make_udaf_expr_and_func!(
+    BitmapConstructAgg,
+    bitmap_construct_agg,
+    bit_position,
+    "Returns a bitmap with the bits at the input positions set",
+    bitmap_construct_agg_udaf
+);
+
+make_udaf_expr_and_func!(
+    BitmapOrAgg,
+    bitmap_or_agg,
+    bitmap,
+    "Returns the bitwise OR of the input bitmaps",
+    bitmap_or_agg_udaf
+);
+
+/// Returns the [`ScalarUDF`] for [`BitmapCount`]
+pub fn bitmap_count_udf() -> Arc<ScalarUDF> {
+    static INSTANCE: LazyLock<Arc<ScalarUDF>> =
+        LazyLock::new(|| Arc::new(ScalarUDF::from(BitmapCount::new())));
+    Arc::clone(&INSTANCE)
+}
+
+/// Number of set bits in a bitmap
+pub fn bitmap_count(bitmap: datafusion_expr::Expr) -> datafusion_expr::Expr {
+    bitmap_count_udf().call(vec![bitmap])
+}
+
+/// What the bitmap accumulators are updated with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BitmapInput {
+    /// Positions of the bits to set
+    Positions,
+    /// Bitmaps to OR together
+    Bitmaps,
+}
+
+fn bitmap_state_fields(name: &str) -> Vec<FieldRef> {
+    vec![Field::new(format_state_name(name, "bitmap"), DataType::Binary, false).into()]
+}
+
+/// Reads a bitmap in the portable serialization format
+fn deserialize_bitmap(bytes: &[u8]) -> Result<RoaringBitmap> {
+    RoaringBitmap::deserialize_from(bytes)
+        .map_err(|e| exec_datafusion_err!("Invalid Roaring bitmap: {e}"))
+}
+
+/// Writes a bitmap in the portable serialization format
+fn serialize_bitmap(bitmap: &RoaringBitmap) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(bitmap.serialized_size());
+    bitmap.serialize_into(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Computes one bitmap per group
+#[derive(Debug)]
+struct BitmapGroupsAccumulator {
+    input: BitmapInput,
+    /// The bitmap of each group
+    bitmaps: Vec<RoaringBitmap>,
+}
+
+impl BitmapGroupsAccumulator {
+    fn new(input: BitmapInput) -> Self {
+        Self {
+            input,
+            bitmaps: Vec::new(),
+        }
+    }
+
+    fn set_positions(
+        &mut self,
+        positions: &ArrayRef,
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+    ) -> Result<()> {
+        let positions = positions.as_primitive::<Int64Type>();
+        let mut invalid_position = None;
+        accumulate(
+            group_indices,
+            positions,
+            opt_filter,
+            |group_index, position| match u32::try_from(position) {
+                Ok(position) => {
+                    self.bitmaps[group_index].insert(position);
+                }
+                Err(_) => {
+                    invalid_position.get_or_insert(position);
+                }
+            },
+        );
+        match invalid_position {
+            Some(position) => {
+                exec_err!("Bit position {position} is not between 0 and {}", u32::MAX)
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn or_bitmaps(
+        &mut self,
+        bitmaps: &ArrayRef,
+        group_indices: &[usize],
+        nulls: Option<&NullBuffer>,
+    ) -> Result<()> {
+        let bitmaps = cast(bitmaps, &DataType::Binary)?;
+        let bitmaps = bitmaps.as_binary::<i32>();
+        let nulls = NullBuffer::union(nulls, bitmaps.nulls());
+        for (row, &group_index) in group_indices.iter().enumerate() {
+            if nulls.as_ref().is_none_or(|nulls| nulls.is_valid(row)) {
+                self.bitmaps[group_index] |= deserialize_bitmap(bitmaps.value(row))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the bitmaps of the emitted groups, returning them as an array
+    fn take_bitmaps(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let bitmaps = emit_to
+            .take_needed(&mut self.bitmaps)
+            .iter()
+            .map(serialize_bitmap)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(BinaryArray::from_iter_values(bitmaps)))
+    }
+}
+
+impl GroupsAccumulator for BitmapGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.bitmaps
+            .resize_with(total_num_groups, RoaringBitmap::new);
+        match self.input {
+            BitmapInput::Positions => {
+                self.set_positions(&values[0], group_indices, opt_filter)
+            }
+            BitmapInput::Bitmaps => {
+                let filter_nulls = opt_filter.map(filter_to_nulls);
+                self.or_bitmaps(&values[0], group_indices, filter_nulls.as_ref())
+            }
+        }
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.bitmaps
+            .resize_with(total_num_groups, RoaringBitmap::new);
+        self.or_bitmaps(&values[0], group_indices, None)
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        self.take_bitmaps(emit_to)
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        Ok(vec![self.take_bitmaps(emit_to)?])
+    }
+
+    /// Converts each input row to a bitmap of that row alone, which is empty
+    /// if the row is null or filtered out
+    fn convert_to_state(
+        &self,
+        values: &[ArrayRef],
+        opt_filter: Option<&BooleanArray>,
+    ) -> Result<Vec<ArrayRef>> {
+        let num_rows = values[0].len();
+        let mut accumulator = Self::new(self.input);
+        let group_indices = (0..num_rows).collect::<Vec<_>>();
+        accumulator.update_batch(values, &group_indices, opt_filter, num_rows)?;
+        accumulator.state(EmitTo::All)
+    }
+
+    /// The serialized size of a bitmap approximates its memory use
+    fn size(&self) -> usize {
+        self.bitmaps.capacity() * size_of::<RoaringBitmap>()
+            + self
+                .bitmaps
+                .iter()
+                .map(RoaringBitmap::serialized_size)
+                .sum::<usize>()
+    }
+}
+
+/// Computes the bitmap of a single group
+#[derive(Debug)]
+struct BitmapAccumulator {
+    groups: BitmapGroupsAccumulator,
+}
+
+impl BitmapAccumulator {
+    fn new(input: BitmapInput) -> Self {
+        let mut groups = BitmapGroupsAccumulator::new(input);
+        groups.bitmaps.push(RoaringBitmap::new());
+        Self { groups }
+    }
+}
+
+impl Accumulator for BitmapAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let group_indices = vec![0; values[0].len()];
+        self.groups.update_batch(values, &group_indices, None, 1)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let group_indices = vec![0; states[0].len()];
+        self.groups.merge_batch(states, &group_indices, 1)
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let bitmap = serialize_bitmap(&self.groups.bitmaps[0])?;
+        Ok(ScalarValue::Binary(Some(bitmap)))
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self) + self.groups.size()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "General Functions"),
+    description = "Returns a Roaring bitmap with the bits at the non-null input positions set, serialized in the portable Roaring format. Positions must be between 0 and 4294967295.",
+    syntax_example = "bitmap_construct_agg(bit_position)",
+    sql_example = r#"```sql
+> SELECT bitmap_count(bitmap_construct_agg(column_name)) FROM table_name;
++------------------------------------------------------------+
+| bitmap_count(bitmap_construct_agg(table_name.column_name)) |
++------------------------------------------------------------+
+| 3                                                          |
++------------------------------------------------------------+
+```"#,
+    argument(
+        name = "bit_position",
+        description = "Integer expression of the positions of the bits to set."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct BitmapConstructAgg {
+    signature: Signature,
+}
+
+impl Default for BitmapConstructAgg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitmapConstructAgg {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![Coercion::new_implicit(
+                    TypeSignatureClass::Native(logical_int64()),
+                    vec![TypeSignatureClass::Integer],
+                    NativeType::Int64,
+                )],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl AggregateUDFImpl for BitmapConstructAgg {
+    fn name(&self) -> &str {
+        "bitmap_construct_agg"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(bitmap_state_fields(args.name))
+    }
+
+    fn accumulator(&self, _acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(BitmapAccumulator::new(BitmapInput::Positions)))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        _args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(BitmapGroupsAccumulator::new(
+            BitmapInput::Positions,
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "General Functions"),
+    description = "Returns the bitwise OR of the non-null input bitmaps, such as the ones returned by `bitmap_construct_agg`. The bitmaps must be Roaring bitmaps serialized in the portable Roaring format.",
+    syntax_example = "bitmap_or_agg(bitmap)",
+    sql_example = r#"```sql
+> SELECT bitmap_count(bitmap_or_agg(bitmap)) FROM daily_bitmaps;
++---------------------------------------------------+
+| bitmap_count(bitmap_or_agg(daily_bitmaps.bitmap)) |
++---------------------------------------------------+
+| 5                                                 |
++---------------------------------------------------+
+```"#,
+    argument(name = "bitmap", description = "Binary expression of the bitmaps.")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct BitmapOrAgg {
+    signature: Signature,
+}
+
+impl Default for BitmapOrAgg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitmapOrAgg {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![Coercion::new_exact(TypeSignatureClass::Binary)],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl AggregateUDFImpl for BitmapOrAgg {
+    fn name(&self) -> &str {
+        "bitmap_or_agg"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(bitmap_state_fields(args.name))
+    }
+
+    fn accumulator(&self, _acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(BitmapAccumulator::new(BitmapInput::Bitmaps)))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        _args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(BitmapGroupsAccumulator::new(BitmapInput::Bitmaps)))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Sketch Functions"),
+    description = "Returns the number of set bits in a Roaring bitmap, such as the ones returned by `bitmap_construct_agg` and `bitmap_or_agg`.",
+    syntax_example = "bitmap_count(bitmap)",
+    sql_example = r#"```sql
+> SELECT day, bitmap_count(bitmap) FROM daily_bitmaps;
++-----+------------------------------------+
+| day | bitmap_count(daily_bitmaps.bitmap) |
++-----+------------------------------------+
+| 1   | 3                                  |
+| 2   | 4                                  |
++-----+------------------------------------+
+```"#,
+    argument(name = "bitmap", description = "Binary expression of the bitmap.")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct BitmapCount {
+    signature: Signature,
+}
+
+impl Default for BitmapCount {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitmapCount {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![Coercion::new_exact(TypeSignatureClass::Binary)],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for BitmapCount {
+    fn name(&self) -> &str {
+        "bitmap_count"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Int64)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let count = |array: &ArrayRef| -> Result<ArrayRef> {
+            let bitmaps = cast(array, &DataType::Binary)?;
+            let counts = bitmaps
+                .as_binary::<i32>()
+                .iter()
+                .map(|bitmap| {
+                    bitmap
+                        .map(|bitmap| Ok(deserialize_bitmap(bitmap)?.len() as i64))
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Arc::new(Int64Array::from(counts)))
+        };
+        match &args.args[0] {
+            ColumnarValue::Array(array) => Ok(ColumnarValue::Array(count(array)?)),
+            ColumnarValue::Scalar(scalar) => {
+                let counts = count(&scalar.to_array()?)?;
+                Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+                    &counts, 0,
+                )?))
+            }
+        }
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bitmaps(array: &ArrayRef) -> Result<Vec<RoaringBitmap>> {
+        array
+            .as_binary::<i32>()
+            .iter()
+            .map(|bitmap| deserialize_bitmap(bitmap.unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn construct_and_or_groups() -> Result<()> {
+        let mut construct = BitmapGroupsAccumulator::new(BitmapInput::Positions);
+        let positions: ArrayRef = Arc::new(Int64Array::from(vec![
+            Some(0),
+            Some(9),
+            None,
+            Some(9),
+            Some(u32::MAX as i64),
+        ]));
+        construct.update_batch(&[positions], &[0, 0, 1, 1, 1], None, 3)?;
+        let constructed = construct.evaluate(EmitTo::All)?;
+        assert_eq!(
+            bitmaps(&constructed)?,
+            vec![
+                RoaringBitmap::from([0, 9]),
+                RoaringBitmap::from([9, u32::MAX]),
+                RoaringBitmap::new(),
+            ]
+        );
+
+        let mut or = BitmapGroupsAccumulator::new(BitmapInput::Bitmaps);
+        or.update_batch(&[constructed], &[0, 0, 0], None, 1)?;
+        let result = or.evaluate(EmitTo::All)?;
+        assert_eq!(
+            bitmaps(&result)?,
+            vec![RoaringBitmap::from([0, 9, u32::MAX])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn emit_first_groups() -> Result<()> {
+        let mut construct = BitmapGroupsAccumulator::new(BitmapInput::Positions);
+        let positions: ArrayRef = Arc::new(Int64Array::from(vec![1, 2, 3]));
+        construct.update_batch(&[positions], &[0, 1, 2], None, 3)?;
+        let first = construct.evaluate(EmitTo::First(2))?;
+        assert_eq!(first.len(), 2);
+        let rest = construct.evaluate(EmitTo::All)?;
+        assert_eq!(bitmaps(&rest)?, vec![RoaringBitmap::from([3])]);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_position() {
+        for position in [-1, 1 << 32] {
+            let mut construct = BitmapGroupsAccumulator::new(BitmapInput::Positions);
+            let positions: ArrayRef = Arc::new(Int64Array::from(vec![position]));
+            let err = construct
+                .update_batch(&[positions], &[0], None, 1)
+                .unwrap_err();
+            assert_eq!(
+                err.strip_backtrace(),
+                format!(
+                    "Execution error: Bit position {position} is not between 0 and 4294967295"
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_bitmap() {
+        let mut or = BitmapGroupsAccumulator::new(BitmapInput::Bitmaps);
+        let bitmaps: ArrayRef = Arc::new(BinaryArray::from_iter_values([[1u8]]));
+        let err = or.update_batch(&[bitmaps], &[0], None, 1).unwrap_err();
+        assert!(
+            err.strip_backtrace()
+                .starts_with("Execution error: Invalid Roaring bitmap"),
+            "{err}"
+        );
+    }
+}
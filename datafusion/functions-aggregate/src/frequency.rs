@@ -0,0 +1,491 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Accumulators counting the occurrences of each distinct value, shared by
+//! `mode` and `histogram`
+//!
+//! The values are counted in the row format of a [`RowConverter`], so that
+//! values of any type can be counted and compared by their encoded bytes.
+//! The intermediate state is a list of the distinct values and a list of
+//! their counts.
+
+use std::collections::HashMap;
+use std::mem::{size_of, size_of_val};
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, BooleanArray, Int64Array, ListArray, MapArray, StructArray,
+    UInt32Array,
+};
+use arrow::buffer::{NullBuffer, OffsetBuffer};
+use arrow::compute::{cast, take};
+use arrow::datatypes::{DataType, Field, FieldRef, Fields, Int64Type};
+use arrow::row::{RowConverter, Rows, SortField};
+use datafusion_common::hash_utils::RandomState;
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{Accumulator, EmitTo, GroupsAccumulator};
+use datafusion_functions_aggregate_common::aggregate::groups_accumulator::nulls::filter_to_nulls;
+
+/// What the frequency accumulators return
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FrequencyOutput {
+    /// The most frequent value, choosing the first value in the sort order
+    /// of the converter among equally frequent values
+    Mode,
+    /// A map of each value to its count, in the sort order of the converter
+    Histogram,
+}
+
+/// The occurrences of the distinct values of a group, keyed by their rows
+#[derive(Debug, Default)]
+struct ValueCounts {
+    counts: HashMap<Box<[u8]>, i64, RandomState>,
+    /// Heap bytes held by the keys of `counts`
+    key_bytes: usize,
+}
+
+impl ValueCounts {
+    fn add(&mut self, row: &[u8], count: i64) {
+        match self.counts.get_mut(row) {
+            Some(counter) => *counter += count,
+            None => {
+                self.key_bytes += row.len();
+                self.counts.insert(row.into(), count);
+            }
+        }
+    }
+
+    /// Returns the row of the most frequent value, breaking ties by the
+    /// smallest row
+    fn mode(&self) -> Option<&[u8]> {
+        self.counts
+            .iter()
+            .max_by(|(a_row, a_count), (b_row, b_count)| {
+                a_count.cmp(b_count).then_with(|| b_row.cmp(a_row))
+            })
+            .map(|(row, _)| row.as_ref())
+    }
+
+    /// Returns the rows and their counts, ordered by row
+    fn sorted(&self) -> Vec<(&[u8], i64)> {
+        let mut entries = self
+            .counts
+            .iter()
+            .map(|(row, &count)| (row.as_ref(), count))
+            .collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(row, _)| *row);
+        entries
+    }
+
+    fn size(&self) -> usize {
+        self.counts.capacity() * (size_of::<Box<[u8]>>() + size_of::<i64>())
+            + self.key_bytes
+    }
+}
+
+/// The intermediate state fields of the frequency accumulators
+pub(crate) fn frequency_state_fields(name: &str, value_type: &DataType) -> Vec<FieldRef> {
+    vec![
+        Field::new_list(
+            format_state_name(name, "values"),
+            Field::new_list_field(value_type.clone(), true),
+            true,
+        )
+        .into(),
+        Field::new_list(
+            format_state_name(name, "counts"),
+            Field::new_list_field(DataType::Int64, true),
+            true,
+        )
+        .into(),
+    ]
+}
+
+/// The type of the map returned by `histogram` for values of `value_type`
+pub(crate) fn histogram_type(value_type: &DataType) -> DataType {
+    DataType::Map(Arc::new(histogram_entries_field(value_type)), false)
+}
+
+fn histogram_entries_field(value_type: &DataType) -> Field {
+    Field::new(
+        "entries",
+        DataType::Struct(Fields::from(vec![
+            Field::new("key", value_type.clone(), false),
+            Field::new("value", DataType::Int64, false),
+        ])),
+        false,
+    )
+}
+
+/// Counts the values of a single group
+#[derive(Debug)]
+pub(crate) struct FrequencyAccumulator {
+    groups: FrequencyGroupsAccumulator,
+}
+
+impl FrequencyAccumulator {
+    pub(crate) fn try_new(
+        value_type: &DataType,
+        sort_field: SortField,
+        output: FrequencyOutput,
+    ) -> Result<Self> {
+        let mut groups =
+            FrequencyGroupsAccumulator::try_new(value_type, sort_field, output)?;
+        groups.ensure_groups(1);
+        Ok(Self { groups })
+    }
+}
+
+impl Accumulator for FrequencyAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let group_indices = vec![0; values[0].len()];
+        self.groups.update_batch(values, &group_indices, None, 1)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let group_indices = vec![0; states[0].len()];
+        self.groups.merge_batch(states, &group_indices, 1)
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        self.groups
+            .state_of(&self.groups.groups)?
+            .iter()
+            .map(|array| ScalarValue::try_from_array(array, 0))
+            .collect()
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        ScalarValue::try_from_array(&self.groups.evaluate_of(&self.groups.groups)?, 0)
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self) + self.groups.size()
+    }
+}
+
+/// Counts the values of each group
+#[derive(Debug)]
+pub(crate) struct FrequencyGroupsAccumulator {
+    converter: RowConverter,
+    value_type: DataType,
+    output: FrequencyOutput,
+    /// Per-group counts, indexed by `group_index`
+    groups: Vec<ValueCounts>,
+    /// Incrementally maintained heap bytes used by `groups`
+    allocated_bytes: usize,
+}
+
+impl FrequencyGroupsAccumulator {
+    pub(crate) fn try_new(
+        value_type: &DataType,
+        sort_field: SortField,
+        output: FrequencyOutput,
+    ) -> Result<Self> {
+        Ok(Self {
+            converter: RowConverter::new(vec![sort_field])?,
+            value_type: value_type.clone(),
+            output,
+            groups: Vec::new(),
+            allocated_bytes: 0,
+        })
+    }
+
+    fn ensure_groups(&mut self, total_num_groups: usize) {
+        if total_num_groups > self.groups.len() {
+            self.groups
+                .resize_with(total_num_groups, ValueCounts::default);
+        }
+    }
+
+    fn add(&mut self, group_index: usize, row: &[u8], count: i64) {
+        let group = &mut self.groups[group_index];
+        let before = group.size();
+        group.add(row, count);
+        self.allocated_bytes = (self.allocated_bytes + group.size()) - before;
+    }
+
+    /// Adds the rows of `rows` that are valid in `nulls` to their groups
+    fn add_rows(
+        &mut self,
+        rows: &Rows,
+        group_indices: &[usize],
+        nulls: Option<&NullBuffer>,
+    ) {
+        for (row, &group_index) in group_indices.iter().enumerate() {
+            if nulls.is_none_or(|nulls| nulls.is_valid(row)) {
+                self.add(group_index, rows.row(row).as_ref(), 1);
+            }
+        }
+    }
+
+    /// Converts rows back to values of the input type
+    fn convert_rows<'a>(
+        &self,
+        rows: impl IntoIterator<Item = &'a [u8]>,
+    ) -> Result<ArrayRef> {
+        let parser = self.converter.parser();
+        let values = self
+            .converter
+            .convert_rows(rows.into_iter().map(|row| parser.parse(row)))?;
+        Ok(cast(&values[0], &self.value_type)?)
+    }
+
+    fn take_groups(&mut self, emit_to: EmitTo) -> Vec<ValueCounts> {
+        let groups = emit_to.take_needed(&mut self.groups);
+        let freed = groups.iter().map(ValueCounts::size).sum::<usize>();
+        self.allocated_bytes = self.allocated_bytes.saturating_sub(freed);
+        groups
+    }
+
+    fn evaluate_of(&self, groups: &[ValueCounts]) -> Result<ArrayRef> {
+        match self.output {
+            FrequencyOutput::Mode => self.evaluate_mode(groups),
+            FrequencyOutput::Histogram => self.evaluate_histogram(groups),
+        }
+    }
+
+    /// Returns the state of `groups`: the lists of their distinct values and
+    /// of the counts of these values
+    fn state_of(&self, groups: &[ValueCounts]) -> Result<Vec<ArrayRef>> {
+        let entries = groups.iter().map(ValueCounts::sorted).collect::<Vec<_>>();
+        let values = self.convert_rows(entries.iter().flatten().map(|(row, _)| *row))?;
+        let counts = entries
+            .iter()
+            .flatten()
+            .map(|(_, count)| *count)
+            .collect::<Int64Array>();
+        let offsets = OffsetBuffer::from_lengths(entries.iter().map(Vec::len));
+        self.state_lists(offsets, values, Arc::new(counts))
+    }
+
+    fn state_lists(
+        &self,
+        offsets: OffsetBuffer<i32>,
+        values: ArrayRef,
+        counts: ArrayRef,
+    ) -> Result<Vec<ArrayRef>> {
+        Ok(vec![
+            Arc::new(ListArray::try_new(
+                Arc::new(Field::new_list_field(self.value_type.clone(), true)),
+                offsets.clone(),
+                values,
+                None,
+            )?),
+            Arc::new(ListArray::try_new(
+                Arc::new(Field::new_list_field(DataType::Int64, true)),
+                offsets,
+                counts,
+                None,
+            )?),
+        ])
+    }
+
+    fn evaluate_mode(&self, groups: &[ValueCounts]) -> Result<ArrayRef> {
+        let modes = groups.iter().filter_map(ValueCounts::mode);
+        let values = self.convert_rows(modes)?;
+        // Empty groups have no mode
+        let mut next = 0;
+        let indices = groups
+            .iter()
+            .map(|group| {
+                (!group.counts.is_empty()).then(|| {
+                    next += 1;
+                    next - 1
+                })
+            })
+            .collect::<UInt32Array>();
+        Ok(take(&values, &indices, None)?)
+    }
+
+    fn evaluate_histogram(&self, groups: &[ValueCounts]) -> Result<ArrayRef> {
+        let entries = groups.iter().map(ValueCounts::sorted).collect::<Vec<_>>();
+        let keys = self.convert_rows(entries.iter().flatten().map(|(row, _)| *row))?;
+        let counts = entries
+            .iter()
+            .flatten()
+            .map(|(_, count)| *count)
+            .collect::<Int64Array>();
+        let offsets = OffsetBuffer::from_lengths(entries.iter().map(Vec::len));
+        let nulls =
+            NullBuffer::from_iter(entries.iter().map(|entries| !entries.is_empty()));
+
+        let entries_field = histogram_entries_field(&self.value_type);
+        let DataType::Struct(fields) = entries_field.data_type() else {
+            unreachable!()
+        };
+        let entries =
+            StructArray::try_new(fields.clone(), vec![keys, Arc::new(counts)], None)?;
+        Ok(Arc::new(MapArray::try_new(
+            Arc::new(entries_field),
+            offsets,
+            entries,
+            Some(nulls),
+            false,
+        )?))
+    }
+}
+
+impl GroupsAccumulator for FrequencyGroupsAccumulator {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.ensure_groups(total_num_groups);
+        let rows = self.converter.convert_columns(&values[..1])?;
+        let filter_nulls = opt_filter.map(filter_to_nulls);
+        let value_nulls = values[0].logical_nulls();
+        let nulls = NullBuffer::union(filter_nulls.as_ref(), value_nulls.as_ref());
+        self.add_rows(&rows, group_indices, nulls.as_ref());
+        Ok(())
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.ensure_groups(total_num_groups);
+        let value_lists = values[0].as_list::<i32>();
+        let count_lists = values[1].as_list::<i32>();
+        let rows = self
+            .converter
+            .convert_columns(&[Arc::clone(value_lists.values())])?;
+        let counts = count_lists.values().as_primitive::<Int64Type>();
+        for (list_index, &group_index) in group_indices.iter().enumerate() {
+            if value_lists.is_null(list_index) {
+                continue;
+            }
+            let values_start = value_lists.value_offsets()[list_index] as usize;
+            let counts_start = count_lists.value_offsets()[list_index] as usize;
+            for i in 0..value_lists.value_length(list_index) as usize {
+                let row = rows.row(values_start + i);
+                let count = counts.value(counts_start + i);
+                self.add(group_index, row.as_ref(), count);
+            }
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let groups = self.take_groups(emit_to);
+        self.evaluate_of(&groups)
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let groups = self.take_groups(emit_to);
+        self.state_of(&groups)
+    }
+
+    /// Converts each input row to a list of its value with a count of one,
+    /// or to empty lists if it is null or filtered out
+    fn convert_to_state(
+        &self,
+        values: &[ArrayRef],
+        opt_filter: Option<&BooleanArray>,
+    ) -> Result<Vec<ArrayRef>> {
+        let filter_nulls = opt_filter.map(filter_to_nulls);
+        let value_nulls = values[0].logical_nulls();
+        let nulls = NullBuffer::union(filter_nulls.as_ref(), value_nulls.as_ref());
+        let is_valid = |row| nulls.as_ref().is_none_or(|nulls| nulls.is_valid(row));
+
+        let num_rows = values[0].len();
+        let indices = (0..num_rows as u32)
+            .filter(|&row| is_valid(row as usize))
+            .collect::<UInt32Array>();
+        let valid_values = take(&values[0], &indices, None)?;
+        let counts = Int64Array::from_value(1, valid_values.len());
+        let offsets =
+            OffsetBuffer::from_lengths((0..num_rows).map(|row| is_valid(row) as usize));
+        self.state_lists(offsets, valid_values, Arc::new(counts))
+    }
+
+    fn size(&self) -> usize {
+        self.groups.capacity() * size_of::<ValueCounts>()
+            + self.allocated_bytes
+            + self.converter.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::StringArray;
+    use arrow::compute::SortOptions;
+
+    fn strings(values: &[Option<&str>]) -> ArrayRef {
+        Arc::new(StringArray::from(values.to_vec()))
+    }
+
+    fn accumulator(
+        sort_field: SortField,
+        output: FrequencyOutput,
+    ) -> Result<FrequencyGroupsAccumulator> {
+        FrequencyGroupsAccumulator::try_new(&DataType::Utf8, sort_field, output)
+    }
+
+    #[test]
+    fn mode_breaks_ties_by_sort_order() -> Result<()> {
+        let values = strings(&[Some("b"), Some("a"), None, Some("b"), Some("a"), None]);
+        let group_indices = [0, 0, 0, 0, 0, 1];
+
+        let mut ascending =
+            accumulator(SortField::new(DataType::Utf8), FrequencyOutput::Mode)?;
+        ascending.update_batch(&[Arc::clone(&values)], &group_indices, None, 2)?;
+        let modes = ascending.evaluate(EmitTo::All)?;
+        assert_eq!(modes.as_ref(), strings(&[Some("a"), None]).as_ref());
+
+        let descending = SortOptions::default().desc();
+        let mut descending = accumulator(
+            SortField::new_with_options(DataType::Utf8, descending),
+            FrequencyOutput::Mode,
+        )?;
+        descending.update_batch(&[values], &group_indices, None, 2)?;
+        let modes = descending.evaluate(EmitTo::All)?;
+        assert_eq!(modes.as_ref(), strings(&[Some("b"), None]).as_ref());
+        Ok(())
+    }
+
+    #[test]
+    fn merge_converted_state() -> Result<()> {
+        let values = strings(&[Some("x"), Some("y"), Some("x"), None]);
+        let filter = BooleanArray::from(vec![true, true, true, false]);
+
+        let partial =
+            accumulator(SortField::new(DataType::Utf8), FrequencyOutput::Histogram)?;
+        let state = partial.convert_to_state(&[values], Some(&filter))?;
+        assert_eq!(state[0].len(), 4);
+        assert_eq!(state[0].as_list::<i32>().value_length(3), 0);
+
+        let mut histogram =
+            accumulator(SortField::new(DataType::Utf8), FrequencyOutput::Histogram)?;
+        histogram.merge_batch(&state, &[0, 0, 0, 1], 2)?;
+        let result = histogram.evaluate(EmitTo::All)?;
+        assert_eq!(
+            ScalarValue::try_from_array(&result, 0)?.to_string(),
+            "[{x:2,y:1}]"
+        );
+        assert!(result.is_null(1));
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the `histogram` aggregate function, which returns the number of
+//! occurrences of each distinct value.
+
+use std::fmt::Debug;
+
+use arrow::datatypes::{DataType, FieldRef};
+use arrow::row::SortField;
+use datafusion_common::Result;
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Documentation, GroupsAccumulator, Signature,
+    Volatility,
+};
+use datafusion_macros::user_doc;
+
+use crate::frequency::{
+    FrequencyAccumulator, FrequencyGroupsAccumulator, FrequencyOutput,
+    frequency_state_fields, histogram_type,
+};
+
+make_udaf_expr_and_func!(
+    Histogram,
+    histogram,
+    expression,
+    "Returns a map of each distinct value to its number of occurrences",
+    histogram_udaf
+);
+
+#[user_doc(
+    doc_section(label = "General Functions"),
+    description = "Returns a map of each distinct non-null value to its number of occurrences, ordered by value. Returns null if there are no non-null values.",
+    syntax_example = "histogram(expression)",
+    sql_example = r#"```sql
+> SELECT histogram(column_name) FROM table_name;
++-----------------------------------+
+| histogram(table_name.column_name) |
++-----------------------------------+
+| {a: 3, b: 1, c: 2}                |
++-----------------------------------+
+```"#,
+    standard_argument(name = "expression",)
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Histogram {
+    signature: Signature,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for Histogram {
+    fn name(&self) -> &str {
+        "histogram"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(histogram_type(&arg_types[0]))
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(frequency_state_fields(
+            args.name,
+            args.input_fields[0].data_type(),
+        ))
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        let data_type = acc_args.expr_fields[0].data_type();
+        Ok(Box::new(FrequencyAccumulator::try_new(
+            data_type,
+            SortField::new(data_type.clone()),
+            FrequencyOutput::Histogram,
+        )?))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        let data_type = args.expr_fields[0].data_type();
+        Ok(Box::new(FrequencyGroupsAccumulator::try_new(
+            data_type,
+            SortField::new(data_type.clone()),
+            FrequencyOutput::Histogram,
+        )?))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
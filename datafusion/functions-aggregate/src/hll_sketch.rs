@@ -0,0 +1,432 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the `hll_sketch_agg` and `hll_merge` aggregate functions and the
+//! `hll_estimate` scalar function.
+//!
+//! The sketches are the serialized HyperLogLog sketches used as the state of
+//! `approx_distinct`: either the distinct hashes of up to [`SPARSE_LIMIT`]
+//! values in little-endian order, or the [`NUM_REGISTERS`] registers of a
+//! dense sketch.
+
+use std::mem::size_of;
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, Field, FieldRef, UInt64Type};
+use datafusion_common::hash_utils::create_hashes;
+use datafusion_common::{Result, ScalarValue, exec_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Coercion, ColumnarValue, Documentation,
+    GroupsAccumulator, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature,
+    TypeSignatureClass, Volatility,
+};
+use datafusion_macros::user_doc;
+
+use crate::approx_distinct::{GroupHll, SPARSE_LIMIT};
+use crate::hyperloglog::{HLL_HASH_STATE, NUM_REGISTERS};
+use crate::sketch::{
+    Sketch, SketchAccumulator, SketchGroupsAccumulator, SketchInput, SketchOutput,
+    map_sketches,
+};
+
+make_udaf_expr_and_func!(
+    HllSketchAgg,
+    hll_sketch_agg,
+    expression,
+    "HyperLogLog sketch of the distinct input values",
+    hll_sketch_agg_udaf
+);
+
+make_udaf_expr_and_func!(
+    HllMerge,
+    hll_merge,
+    sketch,
+    "union of HyperLogLog sketches",
+    hll_merge_udaf
+);
+
+/// Returns the [`ScalarUDF`] for [`HllEstimate`]
+pub fn hll_estimate_udf() -> Arc<ScalarUDF> {
+    static INSTANCE: LazyLock<Arc<ScalarUDF>> =
+        LazyLock::new(|| Arc::new(ScalarUDF::from(HllEstimate::new())));
+    Arc::clone(&INSTANCE)
+}
+
+/// Approximate number of distinct values summarized by a HyperLogLog sketch
+pub fn hll_estimate(sketch: datafusion_expr::Expr) -> datafusion_expr::Expr {
+    hll_estimate_udf().call(vec![sketch])
+}
+
+/// Returns an error unless `bytes` is a serialized HyperLogLog sketch
+fn validate_sketch(bytes: &[u8]) -> Result<()> {
+    if bytes.len() == NUM_REGISTERS
+        || (bytes.len().is_multiple_of(size_of::<u64>())
+            && bytes.len() <= SPARSE_LIMIT * size_of::<u64>())
+    {
+        Ok(())
+    } else {
+        exec_err!("Invalid HyperLogLog sketch of {} bytes", bytes.len())
+    }
+}
+
+impl Sketch for GroupHll {
+    type Batch = Vec<u64>;
+
+    fn prepare(&self, values: &[ArrayRef]) -> Result<Vec<u64>> {
+        let mut hashes = vec![0; values[0].len()];
+        create_hashes([values[0].as_ref()], &HLL_HASH_STATE, &mut hashes)?;
+        Ok(hashes)
+    }
+
+    fn update(&mut self, hashes: &Vec<u64>, row: usize) {
+        self.add_hash(hashes[row]);
+    }
+
+    fn merge(&mut self, bytes: &[u8]) -> Result<()> {
+        validate_sketch(bytes)?;
+        self.merge_serialized(bytes)?;
+        Ok(())
+    }
+
+    fn serialize(&mut self) -> Option<Vec<u8>> {
+        let mut bytes = Vec::new();
+        GroupHll::serialize(self, &mut bytes);
+        Some(bytes)
+    }
+
+    fn size(&self) -> usize {
+        self.heap_bytes()
+    }
+}
+
+fn sketch_state_fields(name: &str) -> Vec<FieldRef> {
+    vec![
+        Field::new(
+            format_state_name(name, "hll_sketch"),
+            DataType::Binary,
+            true,
+        )
+        .into(),
+    ]
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Returns a HyperLogLog sketch of the distinct input values, which can be stored and later combined with `hll_merge` and read with `hll_estimate`. The sketch has the format of the intermediate state of `approx_distinct`.",
+    syntax_example = "hll_sketch_agg(expression)",
+    sql_example = r#"```sql
+> SELECT hll_estimate(hll_sketch_agg(column_name)) FROM table_name;
++-------------------------------------------------+
+| hll_estimate(hll_sketch_agg(table.column_name)) |
++-------------------------------------------------+
+| 42                                              |
++-------------------------------------------------+
+```"#,
+    standard_argument(name = "expression",)
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct HllSketchAgg {
+    signature: Signature,
+}
+
+impl Default for HllSketchAgg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HllSketchAgg {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for HllSketchAgg {
+    fn name(&self) -> &str {
+        "hll_sketch_agg"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+
+    fn accumulator(&self, _acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(SketchAccumulator::new(
+            GroupHll::default(),
+            SketchInput::Values,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        _args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(SketchGroupsAccumulator::new(
+            GroupHll::default(),
+            SketchInput::Values,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn default_value(&self, _data_type: &DataType) -> Result<ScalarValue> {
+        Ok(ScalarValue::Binary(Some(vec![])))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Returns the union of HyperLogLog sketches returned by `hll_sketch_agg` or `hll_merge`, ignoring null sketches.",
+    syntax_example = "hll_merge(sketch)",
+    sql_example = r#"```sql
+> SELECT day, hll_estimate(hll_merge(users_sketch)) FROM daily_rollup GROUP BY day;
++-----+----------------------------------------------------+
+| day | hll_estimate(hll_merge(daily_rollup.users_sketch)) |
++-----+----------------------------------------------------+
+| 1   | 42                                                 |
++-----+----------------------------------------------------+
+```"#,
+    argument(
+        name = "sketch",
+        description = "Binary column of HyperLogLog sketches."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct HllMerge {
+    signature: Signature,
+}
+
+impl Default for HllMerge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HllMerge {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![Coercion::new_exact(TypeSignatureClass::Binary)],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl AggregateUDFImpl for HllMerge {
+    fn name(&self) -> &str {
+        "hll_merge"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+
+    fn accumulator(&self, _acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(SketchAccumulator::new(
+            GroupHll::default(),
+            SketchInput::Sketches,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        _args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(SketchGroupsAccumulator::new(
+            GroupHll::default(),
+            SketchInput::Sketches,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn default_value(&self, _data_type: &DataType) -> Result<ScalarValue> {
+        Ok(ScalarValue::Binary(Some(vec![])))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Sketch Functions"),
+    description = "Returns the approximate number of distinct values summarized by a HyperLogLog sketch returned by `hll_sketch_agg` or `hll_merge`.",
+    syntax_example = "hll_estimate(sketch)",
+    sql_example = r#"```sql
+> SELECT hll_estimate(hll_sketch_agg(column_name)) FROM table_name;
++-------------------------------------------------+
+| hll_estimate(hll_sketch_agg(table.column_name)) |
++-------------------------------------------------+
+| 42                                              |
++-------------------------------------------------+
+```"#,
+    argument(name = "sketch", description = "HyperLogLog sketch.")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct HllEstimate {
+    signature: Signature,
+}
+
+impl Default for HllEstimate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HllEstimate {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![Coercion::new_exact(TypeSignatureClass::Binary)],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for HllEstimate {
+    fn name(&self) -> &str {
+        "hll_estimate"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::UInt64)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        map_sketches::<UInt64Type>(&args, |bytes, _| {
+            let mut sketch = GroupHll::default();
+            Sketch::merge(&mut sketch, bytes)?;
+            Ok(Some(sketch.count()))
+        })
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{AsArray, BinaryArray, Int64Array};
+    use datafusion_expr::EmitTo;
+
+    fn sketch_of(values: impl IntoIterator<Item = i64>) -> Result<Vec<u8>> {
+        let mut acc = SketchAccumulator::new(
+            GroupHll::default(),
+            SketchInput::Values,
+            SketchOutput::Sketch,
+        );
+        let values: ArrayRef = Arc::new(values.into_iter().collect::<Int64Array>());
+        acc.update_batch(&[values])?;
+        match acc.evaluate()? {
+            ScalarValue::Binary(Some(bytes)) => Ok(bytes),
+            other => panic!("unexpected sketch {other:?}"),
+        }
+    }
+
+    fn estimate(bytes: &[u8]) -> Result<u64> {
+        let mut sketch = GroupHll::default();
+        Sketch::merge(&mut sketch, bytes)?;
+        Ok(sketch.count())
+    }
+
+    #[test]
+    fn merged_sketches_estimate_union() -> Result<()> {
+        let small = sketch_of(0..100)?;
+        let large = sketch_of(50..10_000)?;
+        assert_eq!(estimate(&small)?, 100);
+
+        let sketches: ArrayRef = Arc::new(BinaryArray::from_iter(vec![
+            Some(small.as_slice()),
+            None,
+            Some(large.as_slice()),
+            Some(small.as_slice()),
+        ]));
+        let mut acc = SketchGroupsAccumulator::new(
+            GroupHll::default(),
+            SketchInput::Sketches,
+            SketchOutput::Sketch,
+        );
+        acc.update_batch(&[sketches], &[0, 0, 0, 1], None, 2)?;
+        let merged = acc.evaluate(EmitTo::All)?;
+        let merged = merged.as_binary::<i32>();
+
+        let union = estimate(merged.value(0))? as f64;
+        assert!((union - 10_000.0).abs() / 10_000.0 < 0.02, "{union}");
+        assert_eq!(estimate(merged.value(1))?, 100);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_sketch() {
+        let err = estimate(&[1, 2, 3]).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Invalid HyperLogLog sketch of 3 bytes"
+        );
+    }
+}
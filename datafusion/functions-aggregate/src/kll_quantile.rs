@@ -0,0 +1,808 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the `kll_quantile`, `kll_sketch_agg` and `kll_merge` aggregate
+//! functions and the `kll_sketch_quantile` scalar function.
+//!
+//! A KLL sketch keeps a hierarchy of levels of sampled values, where each
+//! value of level `h` stands for `2^h` input values. When the sketch grows
+//! beyond its capacity, a level is compacted by sorting it and promoting
+//! every other value to the next level. Lower levels get geometrically
+//! smaller capacities, so the sketch size grows only logarithmically with
+//! the number of input values.
+
+use std::mem::size_of;
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::{Array, ArrayRef, AsArray, Float64Array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, FieldRef, Float64Type};
+use datafusion_common::types::{NativeType, logical_float64};
+use datafusion_common::{Result, ScalarValue, exec_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Coercion, ColumnarValue, Documentation,
+    GroupsAccumulator, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature,
+    TypeSignature, TypeSignatureClass, Volatility,
+};
+use datafusion_macros::user_doc;
+
+use crate::sketch::{
+    Sketch, SketchAccumulator, SketchGroupsAccumulator, SketchInput, SketchOutput,
+    map_sketches, validate_sketch_parameter,
+};
+use crate::utils::validate_percentile_expr;
+
+make_udaf_expr_and_func!(
+    KllQuantile,
+    kll_quantile,
+    expression quantile,
+    "approximate quantile of the input values using a KLL sketch",
+    kll_quantile_udaf
+);
+
+make_udaf_expr_and_func!(
+    KllSketchAgg,
+    kll_sketch_agg,
+    expression,
+    "KLL sketch of the input values",
+    kll_sketch_agg_udaf
+);
+
+make_udaf_expr_and_func!(
+    KllMerge,
+    kll_merge,
+    sketch,
+    "union of KLL sketches",
+    kll_merge_udaf
+);
+
+/// Returns the [`ScalarUDF`] for [`KllSketchQuantile`]
+pub fn kll_sketch_quantile_udf() -> Arc<ScalarUDF> {
+    static INSTANCE: LazyLock<Arc<ScalarUDF>> =
+        LazyLock::new(|| Arc::new(ScalarUDF::from(KllSketchQuantile::new())));
+    Arc::clone(&INSTANCE)
+}
+
+/// Approximate quantile of the values summarized by a KLL sketch
+pub fn kll_sketch_quantile(
+    sketch: datafusion_expr::Expr,
+    quantile: datafusion_expr::Expr,
+) -> datafusion_expr::Expr {
+    kll_sketch_quantile_udf().call(vec![sketch, quantile])
+}
+
+/// The default capacity of the top level of a KLL sketch, for a normalized
+/// rank error of about 1.3%
+pub const DEFAULT_K: usize = 200;
+
+/// The bounds of the `k` argument of the KLL functions
+const MIN_K: usize = 8;
+const MAX_K: usize = u16::MAX as usize;
+
+/// The minimum capacity of a level
+const MIN_LEVEL_CAPACITY: usize = 8;
+
+/// Version of the serialized format, which is
+///
+/// ```text
+/// version: u8 | k: u16 | n: u64 | min: f64 | max: f64 | num_levels: u8
+///   | (level_len: u32 | values: [f64]) for each level
+/// ```
+///
+/// with little-endian numbers and the levels from the lowest weight up.
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: usize = 1 + 2 + 8 + 8 + 8 + 1;
+
+/// A KLL sketch of a set of `f64` values
+#[derive(Debug, Clone)]
+pub(crate) struct KllSketch {
+    k: usize,
+    /// Number of values summarized by the sketch
+    n: u64,
+    min: f64,
+    max: f64,
+    /// `levels[h]` holds the values standing for `2^h` input values each
+    levels: Vec<Vec<f64>>,
+}
+
+impl KllSketch {
+    pub(crate) fn new(k: usize) -> Self {
+        Self {
+            k,
+            n: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            levels: vec![vec![]],
+        }
+    }
+
+    fn insert(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+        self.n += 1;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.levels[0].push(value);
+        self.compress();
+    }
+
+    /// Capacity of `level`, which shrinks by 2/3 for each level below the top
+    fn level_capacity(&self, level: usize) -> usize {
+        let depth = (self.levels.len() - level - 1) as i32;
+        let capacity = (self.k as f64 * (2.0f64 / 3.0).powi(depth)).ceil() as usize;
+        capacity.max(MIN_LEVEL_CAPACITY)
+    }
+
+    fn num_retained(&self) -> usize {
+        self.levels.iter().map(Vec::len).sum()
+    }
+
+    fn total_capacity(&self) -> usize {
+        (0..self.levels.len())
+            .map(|level| self.level_capacity(level))
+            .sum()
+    }
+
+    /// Compacts the lowest levels over their capacity until the sketch fits
+    /// into its total capacity
+    fn compress(&mut self) {
+        while self.num_retained() > self.total_capacity() {
+            let Some(level) = (0..self.levels.len())
+                .find(|&level| self.levels[level].len() > self.level_capacity(level))
+            else {
+                return;
+            };
+            self.compact_level(level);
+        }
+    }
+
+    /// Promotes every other value of `level` to the next level, keeping the
+    /// last value in `level` if there is an odd number of them
+    fn compact_level(&mut self, level: usize) {
+        if level + 1 == self.levels.len() {
+            self.levels.push(vec![]);
+        }
+        let mut values = std::mem::take(&mut self.levels[level]);
+        values.sort_unstable_by(f64::total_cmp);
+        let leftover = (values.len() % 2 == 1).then(|| values.pop().unwrap());
+        // Alternate between the odd and even values to avoid biasing the
+        // ranks, deterministically so that results are reproducible
+        let offset = (self.n as usize + level) % 2;
+        let promoted = values.iter().skip(offset).step_by(2).copied();
+        self.levels[level + 1].extend(promoted);
+        values.clear();
+        values.extend(leftover);
+        self.levels[level] = values;
+    }
+
+    fn union(&mut self, other: &KllSketch) {
+        self.k = self.k.min(other.k);
+        self.n += other.n;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        if other.levels.len() > self.levels.len() {
+            self.levels.resize_with(other.levels.len(), Vec::new);
+        }
+        for (level, values) in self.levels.iter_mut().zip(&other.levels) {
+            level.extend_from_slice(values);
+        }
+        self.compress();
+    }
+
+    /// Returns the approximate `quantile` of the values, which must be
+    /// between 0 and 1, or `None` if the sketch is empty
+    pub(crate) fn quantile(&self, quantile: f64) -> Option<f64> {
+        if self.n == 0 {
+            return None;
+        }
+        if quantile <= 0.0 {
+            return Some(self.min);
+        }
+        if quantile >= 1.0 {
+            return Some(self.max);
+        }
+        let mut weighted = self
+            .levels
+            .iter()
+            .enumerate()
+            .flat_map(|(level, values)| values.iter().map(move |&v| (v, 1u64 << level)))
+            .collect::<Vec<_>>();
+        weighted.sort_unstable_by(|(a, _), (b, _)| a.total_cmp(b));
+        let total = weighted.iter().map(|(_, weight)| weight).sum::<u64>();
+        let rank = quantile * total as f64;
+        let mut cumulative = 0;
+        for (value, weight) in weighted {
+            cumulative += weight;
+            if cumulative as f64 >= rank {
+                return Some(value);
+            }
+        }
+        Some(self.max)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            HEADER_LEN + self.levels.len() * 4 + self.num_retained() * 8,
+        );
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&(self.k as u16).to_le_bytes());
+        bytes.extend_from_slice(&self.n.to_le_bytes());
+        bytes.extend_from_slice(&self.min.to_le_bytes());
+        bytes.extend_from_slice(&self.max.to_le_bytes());
+        bytes.push(self.levels.len() as u8);
+        for level in &self.levels {
+            bytes.extend_from_slice(&(level.len() as u32).to_le_bytes());
+            for value in level {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    fn try_from_bytes(bytes: &[u8]) -> Result<Self> {
+        let invalid = || exec_err!("Invalid KLL sketch of {} bytes", bytes.len());
+        if bytes.len() < HEADER_LEN || bytes[0] != FORMAT_VERSION {
+            return invalid();
+        }
+        let k = u16::from_le_bytes(bytes[1..3].try_into().unwrap()) as usize;
+        let n = u64::from_le_bytes(bytes[3..11].try_into().unwrap());
+        let min = f64::from_le_bytes(bytes[11..19].try_into().unwrap());
+        let max = f64::from_le_bytes(bytes[19..27].try_into().unwrap());
+        let num_levels = bytes[27] as usize;
+        if k < MIN_K || num_levels == 0 || num_levels >= 64 {
+            return invalid();
+        }
+
+        let mut rest = &bytes[HEADER_LEN..];
+        let mut levels = Vec::with_capacity(num_levels);
+        for _ in 0..num_levels {
+            let Some((len, values)) = rest.split_first_chunk::<4>() else {
+                return invalid();
+            };
+            let len = u32::from_le_bytes(*len) as usize * size_of::<f64>();
+            if values.len() < len {
+                return invalid();
+            }
+            let (values, remaining) = values.split_at(len);
+            levels.push(
+                values
+                    .chunks_exact(size_of::<f64>())
+                    .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()))
+                    .collect::<Vec<_>>(),
+            );
+            rest = remaining;
+        }
+        let weight = levels
+            .iter()
+            .enumerate()
+            .map(|(level, values)| values.len() as u64 * (1 << level))
+            .sum::<u64>();
+        if !rest.is_empty() || weight != n {
+            return invalid();
+        }
+        Ok(Self {
+            k,
+            n,
+            min,
+            max,
+            levels,
+        })
+    }
+}
+
+impl Sketch for KllSketch {
+    type Batch = Float64Array;
+
+    fn prepare(&self, values: &[ArrayRef]) -> Result<Float64Array> {
+        Ok(cast(&values[0], &DataType::Float64)?
+            .as_primitive::<Float64Type>()
+            .clone())
+    }
+
+    fn update(&mut self, values: &Float64Array, row: usize) {
+        self.insert(values.value(row));
+    }
+
+    fn merge(&mut self, bytes: &[u8]) -> Result<()> {
+        self.union(&KllSketch::try_from_bytes(bytes)?);
+        Ok(())
+    }
+
+    fn serialize(&mut self) -> Option<Vec<u8>> {
+        Some(self.to_bytes())
+    }
+
+    fn size(&self) -> usize {
+        self.levels.capacity() * size_of::<Vec<f64>>()
+            + self
+                .levels
+                .iter()
+                .map(|level| level.capacity() * size_of::<f64>())
+                .sum::<usize>()
+    }
+}
+
+fn sketch_state_fields(name: &str) -> Vec<FieldRef> {
+    vec![
+        Field::new(
+            format_state_name(name, "kll_sketch"),
+            DataType::Binary,
+            true,
+        )
+        .into(),
+    ]
+}
+
+/// Returns the `k` argument of a KLL function at `index`, or the default
+fn k_argument(args: &AccumulatorArgs, index: usize, fn_name: &str) -> Result<usize> {
+    match args.exprs.get(index) {
+        Some(expr) => validate_sketch_parameter(expr, fn_name, "k", MIN_K..=MAX_K),
+        None => Ok(DEFAULT_K),
+    }
+}
+
+/// Coerces a numeric argument to `Float64`
+fn float64_coercion() -> Coercion {
+    Coercion::new_implicit(
+        TypeSignatureClass::Native(logical_float64()),
+        vec![TypeSignatureClass::Numeric],
+        NativeType::Float64,
+    )
+}
+
+/// Coerces an integer argument to `Int64`
+fn int64_coercion() -> Coercion {
+    Coercion::new_implicit(
+        TypeSignatureClass::Integer,
+        vec![TypeSignatureClass::Numeric],
+        NativeType::Int64,
+    )
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Returns the approximate quantile of input values using a KLL sketch.",
+    syntax_example = "kll_quantile(expression, quantile[, k])",
+    sql_example = r#"```sql
+> SELECT kll_quantile(column_name, 0.75) FROM table_name;
++----------------------------------------------------+
+| kll_quantile(table_name.column_name,Float64(0.75)) |
++----------------------------------------------------+
+| 65.0                                               |
++----------------------------------------------------+
+```"#,
+    standard_argument(name = "expression",),
+    argument(
+        name = "quantile",
+        description = "Quantile to compute. Must be a float value between 0 and 1 (inclusive)."
+    ),
+    argument(
+        name = "k",
+        description = "Capacity of the top level of the sketch, between 8 and 65535. Larger sketches are more accurate. Defaults to 200."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct KllQuantile {
+    signature: Signature,
+}
+
+impl Default for KllQuantile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KllQuantile {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Coercible(vec![
+                        float64_coercion(),
+                        float64_coercion(),
+                    ]),
+                    TypeSignature::Coercible(vec![
+                        float64_coercion(),
+                        float64_coercion(),
+                        int64_coercion(),
+                    ]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+
+    fn empty_sketch_and_output(
+        &self,
+        args: &AccumulatorArgs,
+    ) -> Result<(KllSketch, SketchOutput<KllSketch>)> {
+        let quantile = validate_percentile_expr(&args.exprs[1], self.name())?;
+        let k = k_argument(args, 2, self.name())?;
+        let output =
+            SketchOutput::estimate(DataType::Float64, move |sketch: &mut KllSketch| {
+                Ok(ScalarValue::Float64(sketch.quantile(quantile)))
+            });
+        Ok((KllSketch::new(k), output))
+    }
+}
+
+impl AggregateUDFImpl for KllQuantile {
+    fn name(&self) -> &str {
+        "kll_quantile"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        let (sketch, output) = self.empty_sketch_and_output(&acc_args)?;
+        Ok(Box::new(SketchAccumulator::new(
+            sketch,
+            SketchInput::Values,
+            output,
+        )))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        let (sketch, output) = self.empty_sketch_and_output(&args)?;
+        Ok(Box::new(SketchGroupsAccumulator::new(
+            sketch,
+            SketchInput::Values,
+            output,
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Returns a KLL sketch of the input values, which can be stored and later combined with `kll_merge` and read with `kll_sketch_quantile`.",
+    syntax_example = "kll_sketch_agg(expression[, k])",
+    sql_example = r#"```sql
+> SELECT kll_sketch_quantile(kll_sketch_agg(column_name), 0.5) FROM table_name;
++--------------------------------------------------------------------------+
+| kll_sketch_quantile(kll_sketch_agg(table_name.column_name),Float64(0.5)) |
++--------------------------------------------------------------------------+
+| 42.0                                                                     |
++--------------------------------------------------------------------------+
+```"#,
+    standard_argument(name = "expression",),
+    argument(
+        name = "k",
+        description = "Capacity of the top level of the sketch, between 8 and 65535. Larger sketches are more accurate. Defaults to 200."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct KllSketchAgg {
+    signature: Signature,
+}
+
+impl Default for KllSketchAgg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KllSketchAgg {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Coercible(vec![float64_coercion()]),
+                    TypeSignature::Coercible(vec![float64_coercion(), int64_coercion()]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl AggregateUDFImpl for KllSketchAgg {
+    fn name(&self) -> &str {
+        "kll_sketch_agg"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(SketchAccumulator::new(
+            KllSketch::new(k_argument(&acc_args, 1, self.name())?),
+            SketchInput::Values,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(SketchGroupsAccumulator::new(
+            KllSketch::new(k_argument(&args, 1, self.name())?),
+            SketchInput::Values,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Returns the union of KLL sketches returned by `kll_sketch_agg` or `kll_merge`, ignoring null sketches.",
+    syntax_example = "kll_merge(sketch)",
+    sql_example = r#"```sql
+> SELECT kll_sketch_quantile(kll_merge(latency_sketch), 0.99) FROM daily_rollup;
++---------------------------------------------------------------------------+
+| kll_sketch_quantile(kll_merge(daily_rollup.latency_sketch),Float64(0.99)) |
++---------------------------------------------------------------------------+
+| 1250.0                                                                    |
++---------------------------------------------------------------------------+
+```"#,
+    argument(name = "sketch", description = "Binary column of KLL sketches.")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct KllMerge {
+    signature: Signature,
+}
+
+impl Default for KllMerge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KllMerge {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![Coercion::new_exact(TypeSignatureClass::Binary)],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl AggregateUDFImpl for KllMerge {
+    fn name(&self) -> &str {
+        "kll_merge"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+
+    fn accumulator(&self, _acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(SketchAccumulator::new(
+            KllSketch::new(MAX_K),
+            SketchInput::Sketches,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        _args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(SketchGroupsAccumulator::new(
+            KllSketch::new(MAX_K),
+            SketchInput::Sketches,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Sketch Functions"),
+    description = "Returns the approximate quantile of the values summarized by a KLL sketch returned by `kll_sketch_agg` or `kll_merge`.",
+    syntax_example = "kll_sketch_quantile(sketch, quantile)",
+    sql_example = r#"```sql
+> SELECT kll_sketch_quantile(kll_sketch_agg(column_name), 0.5) FROM table_name;
++--------------------------------------------------------------------------+
+| kll_sketch_quantile(kll_sketch_agg(table_name.column_name),Float64(0.5)) |
++--------------------------------------------------------------------------+
+| 42.0                                                                     |
++--------------------------------------------------------------------------+
+```"#,
+    argument(name = "sketch", description = "KLL sketch."),
+    argument(
+        name = "quantile",
+        description = "Quantile to compute. Must be a float value between 0 and 1 (inclusive)."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct KllSketchQuantile {
+    signature: Signature,
+}
+
+impl Default for KllSketchQuantile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KllSketchQuantile {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::coercible(
+                vec![
+                    Coercion::new_exact(TypeSignatureClass::Binary),
+                    float64_coercion(),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for KllSketchQuantile {
+    fn name(&self) -> &str {
+        "kll_sketch_quantile"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Float64)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let quantiles = args.args[1].to_array(args.number_rows)?;
+        let quantiles = quantiles.as_primitive::<Float64Type>();
+        map_sketches::<Float64Type>(&args, |bytes, row| {
+            if quantiles.is_null(row) {
+                return Ok(None);
+            }
+            let quantile = quantiles.value(row);
+            if !(0.0..=1.0).contains(&quantile) {
+                return exec_err!(
+                    "Quantile value must be between 0.0 and 1.0 inclusive, {quantile} is invalid"
+                );
+            }
+            Ok(KllSketch::try_from_bytes(bytes)?.quantile(quantile))
+        })
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the rank of `value` among `0..n`, as a fraction of `n`
+    fn rank_error(value: f64, quantile: f64, n: usize) -> f64 {
+        (value / n as f64 - quantile).abs()
+    }
+
+    #[test]
+    fn quantiles_within_rank_error() -> Result<()> {
+        let n = 100_000;
+        let mut sketch = KllSketch::new(DEFAULT_K);
+        // Insert in an order that is neither sorted nor random
+        for i in 0..n {
+            sketch.insert(((i * 7919) % n) as f64);
+        }
+        assert!(sketch.num_retained() < 1000);
+        assert_eq!(sketch.quantile(0.0), Some(0.0));
+        assert_eq!(sketch.quantile(1.0), Some((n - 1) as f64));
+        for quantile in [0.01, 0.25, 0.5, 0.75, 0.99] {
+            let value = sketch.quantile(quantile).unwrap();
+            assert!(rank_error(value, quantile, n) < 0.02, "{quantile}: {value}");
+        }
+
+        let roundtrip = KllSketch::try_from_bytes(&sketch.to_bytes())?;
+        assert_eq!(roundtrip.quantile(0.5), sketch.quantile(0.5));
+        Ok(())
+    }
+
+    #[test]
+    fn merged_sketches() {
+        let mut low = KllSketch::new(DEFAULT_K);
+        let mut high = KllSketch::new(DEFAULT_K);
+        for i in 0..10_000 {
+            low.insert(i as f64);
+            high.insert((i + 10_000) as f64);
+        }
+        low.union(&high);
+        assert_eq!(low.n, 20_000);
+        let median = low.quantile(0.5).unwrap();
+        assert!(rank_error(median, 0.5, 20_000) < 0.02, "{median}");
+    }
+
+    #[test]
+    fn empty_and_nan() {
+        let mut sketch = KllSketch::new(DEFAULT_K);
+        assert_eq!(sketch.quantile(0.5), None);
+        sketch.insert(f64::NAN);
+        assert_eq!(sketch.quantile(0.5), None);
+    }
+
+    #[test]
+    fn invalid_sketch() {
+        let err = KllSketch::try_from_bytes(&[1, 2, 3]).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Invalid KLL sketch of 3 bytes"
+        );
+    }
+}
@@ -70,34 +70,43 @@ pub mod approx_distinct;
 pub mod approx_median;
 pub mod approx_percentile_cont;
 pub mod approx_percentile_cont_with_weight;
+pub mod approx_top_k;
 pub mod array_agg;
 pub mod average;
 pub mod bit_and_or_xor;
+pub mod bitmap_agg;
 pub mod bool_and_or;
 pub mod correlation;
 pub mod count;
 pub mod covariance;
 pub mod first_last;
 pub mod grouping;
+pub mod histogram;
+pub mod hll_sketch;
 pub mod hyperloglog;
+pub mod kll_quantile;
 pub mod median;
 pub mod min_max;
+pub mod mode;
 pub mod nth_value;
 pub mod percentile_cont;
 pub mod regr;
 pub mod stddev;
 pub mod string_agg;
 pub mod sum;
+pub mod theta_sketch;
 pub mod variance;
 
+mod frequency;
 pub mod planner;
+mod sketch;
 mod utils;
 
 use crate::approx_percentile_cont::approx_percentile_cont_udaf;
 use crate::approx_percentile_cont_with_weight::approx_percentile_cont_with_weight_udaf;
 use datafusion_common::Result;
 use datafusion_execution::FunctionRegistry;
-use datafusion_expr::AggregateUDF;
+use datafusion_expr::{AggregateUDF, ScalarUDF};
 use log::debug;
 use std::sync::Arc;
 
@@ -108,12 +117,16 @@ pub mod expr_fn {
     pub use super::approx_median::approx_median;
     pub use super::approx_percentile_cont::approx_percentile_cont;
     pub use super::approx_percentile_cont_with_weight::approx_percentile_cont_with_weight;
+    pub use super::approx_top_k::approx_top_k;
     pub use super::array_agg::array_agg;
     pub use super::average::avg;
     pub use super::average::avg_distinct;
     pub use super::bit_and_or_xor::bit_and;
     pub use super::bit_and_or_xor::bit_or;
     pub use super::bit_and_or_xor::bit_xor;
+    pub use super::bitmap_agg::bitmap_construct_agg;
+    pub use super::bitmap_agg::bitmap_count;
+    pub use super::bitmap_agg::bitmap_or_agg;
     pub use super::bool_and_or::bool_and;
     pub use super::bool_and_or::bool_or;
     pub use super::correlation::corr;
@@ -124,9 +137,18 @@ pub mod expr_fn {
     pub use super::first_last::first_value;
     pub use super::first_last::last_value;
     pub use super::grouping::grouping;
+    pub use super::histogram::histogram;
+    pub use super::hll_sketch::hll_estimate;
+    pub use super::hll_sketch::hll_merge;
+    pub use super::hll_sketch::hll_sketch_agg;
+    pub use super::kll_quantile::kll_merge;
+    pub use super::kll_quantile::kll_quantile;
+    pub use super::kll_quantile::kll_sketch_agg;
+    pub use super::kll_quantile::kll_sketch_quantile;
     pub use super::median::median;
     pub use super::min_max::max;
     pub use super::min_max::min;
+    pub use super::mode::mode;
     pub use super::nth_value::nth_value;
     pub use super::percentile_cont::percentile_cont;
     pub use super::regr::regr_avgx;
@@ -142,6 +164,10 @@ pub mod expr_fn {
     pub use super::stddev::stddev_pop;
     pub use super::sum::sum;
     pub use super::sum::sum_distinct;
+    pub use super::theta_sketch::theta_estimate;
+    pub use super::theta_sketch::theta_intersect;
+    pub use super::theta_sketch::theta_sketch_agg;
+    pub use super::theta_sketch::theta_union;
     pub use super::variance::var_pop;
     pub use super::variance::var_sample;
 }
@@ -160,6 +186,8 @@ pub fn all_default_aggregate_functions() -> Vec<Arc<AggregateUDF>> {
         min_max::max_udaf(),
         min_max::min_udaf(),
         median::median_udaf(),
+        mode::mode_udaf(),
+        histogram::histogram_udaf(),
         count::count_udaf(),
         regr::regr_slope_udaf(),
         regr::regr_intercept_udaf(),
@@ -178,11 +206,22 @@ pub fn all_default_aggregate_functions() -> Vec<Arc<AggregateUDF>> {
         approx_distinct::approx_distinct_udaf(),
         approx_percentile_cont_udaf(),
         approx_percentile_cont_with_weight_udaf(),
+        approx_top_k::approx_top_k_udaf(),
+        hll_sketch::hll_sketch_agg_udaf(),
+        hll_sketch::hll_merge_udaf(),
+        kll_quantile::kll_quantile_udaf(),
+        kll_quantile::kll_sketch_agg_udaf(),
+        kll_quantile::kll_merge_udaf(),
+        theta_sketch::theta_sketch_agg_udaf(),
+        theta_sketch::theta_union_udaf(),
+        theta_sketch::theta_intersect_udaf(),
         percentile_cont::percentile_cont_udaf(),
         string_agg::string_agg_udaf(),
         bit_and_or_xor::bit_and_udaf(),
         bit_and_or_xor::bit_or_udaf(),
         bit_and_or_xor::bit_xor_udaf(),
+        bitmap_agg::bitmap_construct_agg_udaf(),
+        bitmap_agg::bitmap_or_agg_udaf(),
         bool_and_or::bool_and_udaf(),
         bool_and_or::bool_or_udaf(),
         average::avg_udaf(),
@@ -191,6 +230,17 @@ pub fn all_default_aggregate_functions() -> Vec<Arc<AggregateUDF>> {
     ]
 }
 
+/// Returns the scalar functions reading the sketches and bitmaps returned by
+/// the sketch and bitmap aggregate functions
+pub fn all_default_sketch_functions() -> Vec<Arc<ScalarUDF>> {
+    vec![
+        bitmap_agg::bitmap_count_udf(),
+        hll_sketch::hll_estimate_udf(),
+        kll_quantile::kll_sketch_quantile_udf(),
+        theta_sketch::theta_estimate_udf(),
+    ]
+}
+
 /// Registers all enabled packages with a [`FunctionRegistry`]
 pub fn register_all(registry: &mut dyn FunctionRegistry) -> Result<()> {
     let functions: Vec<Arc<AggregateUDF>> = all_default_aggregate_functions();
@@ -203,6 +253,16 @@ pub fn register_all(registry: &mut dyn FunctionRegistry) -> Result<()> {
         Ok(()) as Result<()>
     })?;
 
+    all_default_sketch_functions()
+        .into_iter()
+        .try_for_each(|udf| {
+            let existing_udf = registry.register_udf(udf)?;
+            if let Some(existing_udf) = existing_udf {
+                debug!("Overwrite existing UDF: {}", existing_udf.name());
+            }
+            Ok(()) as Result<()>
+        })?;
+
     Ok(())
 }
 
@@ -0,0 +1,148 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the `mode` aggregate function, which returns the most frequent
+//! value.
+
+use std::fmt::Debug;
+
+use arrow::datatypes::{DataType, FieldRef};
+use arrow::row::SortField;
+use datafusion_common::Result;
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Documentation, GroupsAccumulator, Signature,
+    Volatility,
+};
+use datafusion_macros::user_doc;
+
+use crate::frequency::{
+    FrequencyAccumulator, FrequencyGroupsAccumulator, FrequencyOutput,
+    frequency_state_fields,
+};
+
+make_udaf_expr_and_func!(
+    Mode,
+    mode,
+    expression,
+    "Returns the most frequent value",
+    mode_udaf
+);
+
+#[user_doc(
+    doc_section(label = "General Functions"),
+    description = "Returns the most frequent non-null value. If several values are equally frequent, returns the smallest of them, or the largest if ordered by `DESC` in the `WITHIN GROUP` clause.",
+    syntax_example = "mode(expression)",
+    sql_example = r#"```sql
+> SELECT mode(column_name) FROM table_name;
++------------------------------+
+| mode(table_name.column_name) |
++------------------------------+
+| 7                            |
++------------------------------+
+```
+The ordered-set aggregate syntax is also supported:
+```sql
+> SELECT mode() WITHIN GROUP (ORDER BY column_name DESC) FROM table_name;
++---------------------------------------------------------------+
+| mode() WITHIN GROUP [table_name.column_name DESC NULLS FIRST] |
++---------------------------------------------------------------+
+| 9                                                             |
++---------------------------------------------------------------+
+```"#,
+    standard_argument(name = "expression",)
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Mode {
+    signature: Signature,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Mode {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+
+    /// Returns how the values are ordered to break ties, which is by the
+    /// `WITHIN GROUP` clause if any
+    fn sort_field(args: &AccumulatorArgs) -> SortField {
+        let data_type = args.expr_fields[0].data_type().clone();
+        match args.order_bys.first() {
+            Some(sort_expr) => SortField::new_with_options(data_type, sort_expr.options),
+            None => SortField::new(data_type),
+        }
+    }
+}
+
+impl AggregateUDFImpl for Mode {
+    fn name(&self) -> &str {
+        "mode"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(frequency_state_fields(
+            args.name,
+            args.input_fields[0].data_type(),
+        ))
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(FrequencyAccumulator::try_new(
+            acc_args.expr_fields[0].data_type(),
+            Self::sort_field(&acc_args),
+            FrequencyOutput::Mode,
+        )?))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(FrequencyGroupsAccumulator::try_new(
+            args.expr_fields[0].data_type(),
+            Self::sort_field(&args),
+            FrequencyOutput::Mode,
+        )?))
+    }
+
+    fn supports_within_group_clause(&self) -> bool {
+        true
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
@@ -0,0 +1,444 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Accumulators shared by the aggregate functions building mergeable sketches
+//!
+//! A sketch summarizes the values of a group and is serialized as a binary
+//! value. The serialized sketch is the intermediate state of the aggregate
+//! functions, and can also be returned to be stored in a column and merged
+//! later, for example by rollup tables.
+
+use std::fmt::{Debug, Formatter};
+use std::mem::{size_of, size_of_val};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, BinaryArray, BinaryBuilder, BooleanArray, PrimitiveBuilder,
+    new_empty_array,
+};
+use arrow::buffer::NullBuffer;
+use arrow::compute::cast;
+use arrow::datatypes::{ArrowPrimitiveType, DataType};
+use datafusion_common::{DataFusionError, Result, ScalarValue, plan_err};
+use datafusion_expr::{
+    Accumulator, ColumnarValue, EmitTo, GroupsAccumulator, ScalarFunctionArgs,
+};
+use datafusion_functions_aggregate_common::aggregate::groups_accumulator::nulls::filter_to_nulls;
+use datafusion_physical_expr_common::physical_expr::PhysicalExpr;
+
+use crate::utils::get_scalar_value;
+
+/// A mergeable summary of a set of values
+pub(crate) trait Sketch: Clone + Debug + Send + Sync + 'static {
+    /// The argument values of a batch prepared for [`Self::update`], such as
+    /// their hashes
+    type Batch;
+
+    /// Prepares the argument values of a batch
+    fn prepare(&self, values: &[ArrayRef]) -> Result<Self::Batch>;
+
+    /// Adds the value of `row` of a prepared batch
+    fn update(&mut self, batch: &Self::Batch, row: usize);
+
+    /// Merges a sketch serialized by [`Self::serialize`]
+    fn merge(&mut self, bytes: &[u8]) -> Result<()>;
+
+    /// Serializes the sketch, or returns `None` if it does not summarize any
+    /// set yet (such as an intersection of no sketches)
+    fn serialize(&mut self) -> Option<Vec<u8>>;
+
+    /// Heap bytes held by this sketch
+    fn size(&self) -> usize;
+}
+
+/// What the accumulators of a sketch are updated with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SketchInput {
+    /// The values to summarize
+    Values,
+    /// Serialized sketches to merge
+    Sketches,
+}
+
+/// Estimates the result of an aggregate function from a sketch
+type EstimateFn<S> = Arc<dyn Fn(&mut S) -> Result<ScalarValue> + Send + Sync>;
+
+/// What the accumulators of a sketch return
+pub(crate) enum SketchOutput<S> {
+    /// The serialized sketch, as a `Binary` value
+    Sketch,
+    /// A value of type `data_type` estimated from the sketch
+    Estimate {
+        data_type: DataType,
+        estimate: EstimateFn<S>,
+    },
+}
+
+impl<S> SketchOutput<S> {
+    pub(crate) fn estimate(
+        data_type: DataType,
+        estimate: impl Fn(&mut S) -> Result<ScalarValue> + Send + Sync + 'static,
+    ) -> Self {
+        Self::Estimate {
+            data_type,
+            estimate: Arc::new(estimate),
+        }
+    }
+
+    fn evaluate(&self, sketch: &mut S) -> Result<ScalarValue>
+    where
+        S: Sketch,
+    {
+        match self {
+            Self::Sketch => Ok(ScalarValue::Binary(sketch.serialize())),
+            Self::Estimate { estimate, .. } => estimate(sketch),
+        }
+    }
+}
+
+impl<S> Debug for SketchOutput<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sketch => write!(f, "Sketch"),
+            Self::Estimate { data_type, .. } => f
+                .debug_struct("Estimate")
+                .field("data_type", data_type)
+                .finish(),
+        }
+    }
+}
+
+/// Calls `f` with the index of each row that is not null in `nulls`
+fn for_each_valid_row(
+    len: usize,
+    nulls: Option<&NullBuffer>,
+    f: impl FnMut(usize) -> Result<()>,
+) -> Result<()> {
+    match nulls {
+        None => (0..len).try_for_each(f),
+        Some(nulls) => nulls.valid_indices().try_for_each(f),
+    }
+}
+
+/// Casts serialized sketches to `Binary`
+fn binary_sketches(array: &ArrayRef) -> Result<ArrayRef> {
+    Ok(cast(array, &DataType::Binary)?)
+}
+
+/// [`Accumulator`] building a single [`Sketch`]
+#[derive(Debug)]
+pub(crate) struct SketchAccumulator<S: Sketch> {
+    sketch: S,
+    input: SketchInput,
+    output: SketchOutput<S>,
+}
+
+impl<S: Sketch> SketchAccumulator<S> {
+    pub(crate) fn new(sketch: S, input: SketchInput, output: SketchOutput<S>) -> Self {
+        Self {
+            sketch,
+            input,
+            output,
+        }
+    }
+
+    fn merge_sketches(&mut self, sketches: &ArrayRef) -> Result<()> {
+        let sketches = binary_sketches(sketches)?;
+        let sketches = sketches.as_binary::<i32>();
+        for_each_valid_row(sketches.len(), sketches.nulls(), |row| {
+            self.sketch.merge(sketches.value(row))
+        })
+    }
+}
+
+impl<S: Sketch> Accumulator for SketchAccumulator<S> {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        match self.input {
+            SketchInput::Values => {
+                let batch = self.sketch.prepare(values)?;
+                let nulls = values[0].logical_nulls();
+                for_each_valid_row(values[0].len(), nulls.as_ref(), |row| {
+                    self.sketch.update(&batch, row);
+                    Ok(())
+                })
+            }
+            SketchInput::Sketches => self.merge_sketches(&values[0]),
+        }
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.merge_sketches(&states[0])
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::Binary(self.sketch.serialize())])
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        self.output.evaluate(&mut self.sketch)
+    }
+
+    fn size(&self) -> usize {
+        size_of_val(self) + self.sketch.size()
+    }
+}
+
+/// [`GroupsAccumulator`] building one [`Sketch`] per group
+#[derive(Debug)]
+pub(crate) struct SketchGroupsAccumulator<S: Sketch> {
+    /// The sketch new groups start with
+    empty: S,
+    input: SketchInput,
+    output: SketchOutput<S>,
+    /// Per-group sketches, indexed by `group_index`
+    sketches: Vec<S>,
+    /// Incrementally maintained heap bytes used by `sketches`
+    allocated_bytes: usize,
+}
+
+impl<S: Sketch> SketchGroupsAccumulator<S> {
+    pub(crate) fn new(empty: S, input: SketchInput, output: SketchOutput<S>) -> Self {
+        Self {
+            empty,
+            input,
+            output,
+            sketches: Vec::new(),
+            allocated_bytes: 0,
+        }
+    }
+
+    fn ensure_groups(&mut self, total_num_groups: usize) {
+        if total_num_groups > self.sketches.len() {
+            let new_groups = total_num_groups - self.sketches.len();
+            self.allocated_bytes += self.empty.size() * new_groups;
+            self.sketches
+                .resize_with(total_num_groups, || self.empty.clone());
+        }
+    }
+
+    /// Applies `f` to the sketch of a group, keeping track of its size
+    #[inline]
+    fn with_sketch(
+        &mut self,
+        group_index: usize,
+        f: impl FnOnce(&mut S) -> Result<()>,
+    ) -> Result<()> {
+        let sketch = &mut self.sketches[group_index];
+        let before = sketch.size();
+        f(sketch)?;
+        self.allocated_bytes =
+            (self.allocated_bytes + sketch.size()).saturating_sub(before);
+        Ok(())
+    }
+
+    fn merge_sketches(
+        &mut self,
+        sketches: &ArrayRef,
+        group_indices: &[usize],
+        nulls: Option<&NullBuffer>,
+    ) -> Result<()> {
+        let sketches = binary_sketches(sketches)?;
+        let sketches = sketches.as_binary::<i32>();
+        let nulls = NullBuffer::union(nulls, sketches.nulls());
+        for_each_valid_row(sketches.len(), nulls.as_ref(), |row| {
+            self.with_sketch(group_indices[row], |sketch| {
+                sketch.merge(sketches.value(row))
+            })
+        })
+    }
+
+    /// Removes the emitted sketches, releasing their tracked bytes
+    fn take_sketches(&mut self, emit_to: EmitTo) -> Vec<S> {
+        let sketches = emit_to.take_needed(&mut self.sketches);
+        let freed = sketches.iter().map(Sketch::size).sum::<usize>();
+        self.allocated_bytes = self.allocated_bytes.saturating_sub(freed);
+        sketches
+    }
+}
+
+fn serialize_sketches<S: Sketch>(sketches: &mut [S]) -> ArrayRef {
+    let mut builder = BinaryBuilder::with_capacity(sketches.len(), 0);
+    for sketch in sketches {
+        builder.append_option(sketch.serialize());
+    }
+    Arc::new(builder.finish())
+}
+
+impl<S: Sketch> GroupsAccumulator for SketchGroupsAccumulator<S> {
+    fn update_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        opt_filter: Option<&BooleanArray>,
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.ensure_groups(total_num_groups);
+        let filter_nulls = opt_filter.map(filter_to_nulls);
+        match self.input {
+            SketchInput::Values => {
+                let batch = self.empty.prepare(values)?;
+                let value_nulls = values[0].logical_nulls();
+                let nulls =
+                    NullBuffer::union(filter_nulls.as_ref(), value_nulls.as_ref());
+                for_each_valid_row(values[0].len(), nulls.as_ref(), |row| {
+                    self.with_sketch(group_indices[row], |sketch| {
+                        sketch.update(&batch, row);
+                        Ok(())
+                    })
+                })
+            }
+            SketchInput::Sketches => {
+                self.merge_sketches(&values[0], group_indices, filter_nulls.as_ref())
+            }
+        }
+    }
+
+    fn merge_batch(
+        &mut self,
+        values: &[ArrayRef],
+        group_indices: &[usize],
+        total_num_groups: usize,
+    ) -> Result<()> {
+        self.ensure_groups(total_num_groups);
+        self.merge_sketches(&values[0], group_indices, None)
+    }
+
+    fn evaluate(&mut self, emit_to: EmitTo) -> Result<ArrayRef> {
+        let mut sketches = self.take_sketches(emit_to);
+        match &self.output {
+            SketchOutput::Sketch => Ok(serialize_sketches(&mut sketches)),
+            SketchOutput::Estimate { data_type, .. } if sketches.is_empty() => {
+                Ok(new_empty_array(data_type))
+            }
+            output => {
+                let estimates = sketches
+                    .iter_mut()
+                    .map(|sketch| output.evaluate(sketch))
+                    .collect::<Result<Vec<_>>>()?;
+                ScalarValue::iter_to_array(estimates)
+            }
+        }
+    }
+
+    fn state(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        let mut sketches = self.take_sketches(emit_to);
+        Ok(vec![serialize_sketches(&mut sketches)])
+    }
+
+    /// Converts each input row to a sketch of that row alone, or to a null
+    /// sketch if it is null or filtered out
+    fn convert_to_state(
+        &self,
+        values: &[ArrayRef],
+        opt_filter: Option<&BooleanArray>,
+    ) -> Result<Vec<ArrayRef>> {
+        let filter_nulls = opt_filter.map(filter_to_nulls);
+        match self.input {
+            SketchInput::Values => {
+                let batch = self.empty.prepare(values)?;
+                let value_nulls = values[0].logical_nulls();
+                let nulls =
+                    NullBuffer::union(filter_nulls.as_ref(), value_nulls.as_ref());
+                let mut builder = BinaryBuilder::with_capacity(values[0].len(), 0);
+                for row in 0..values[0].len() {
+                    if nulls.as_ref().is_none_or(|nulls| nulls.is_valid(row)) {
+                        let mut sketch = self.empty.clone();
+                        sketch.update(&batch, row);
+                        builder.append_option(sketch.serialize());
+                    } else {
+                        builder.append_null();
+                    }
+                }
+                Ok(vec![Arc::new(builder.finish())])
+            }
+            SketchInput::Sketches => {
+                let sketches = binary_sketches(&values[0])?;
+                let sketches = sketches.as_binary::<i32>();
+                let nulls = NullBuffer::union(filter_nulls.as_ref(), sketches.nulls());
+                let (offsets, values, _) = sketches.clone().into_parts();
+                Ok(vec![Arc::new(BinaryArray::new(offsets, values, nulls))])
+            }
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.sketches.capacity() * size_of::<S>() + self.allocated_bytes
+    }
+}
+
+/// Returns the value of a literal integer argument of a sketch function,
+/// which must be within `range`
+pub(crate) fn validate_sketch_parameter(
+    expr: &Arc<dyn PhysicalExpr>,
+    fn_name: &str,
+    parameter: &str,
+    range: RangeInclusive<usize>,
+) -> Result<usize> {
+    let scalar_value = get_scalar_value(expr).map_err(|_| {
+        DataFusionError::Plan(format!("The {parameter} of '{fn_name}' must be a literal"))
+    })?;
+    let value = match scalar_value {
+        ScalarValue::Int64(Some(value)) if value >= 0 => value as usize,
+        ScalarValue::UInt64(Some(value)) => value as usize,
+        sv => {
+            return plan_err!(
+                "The {parameter} of '{fn_name}' must be a non-negative integer literal (got {sv})"
+            );
+        }
+    };
+    if !range.contains(&value) {
+        return plan_err!(
+            "The {parameter} of '{fn_name}' must be between {} and {}, {value} is invalid",
+            range.start(),
+            range.end()
+        );
+    }
+    Ok(value)
+}
+
+/// Evaluates a scalar function reading the serialized sketches of its first
+/// argument, calling `f` with each sketch that is not null and its row.
+pub(crate) fn map_sketches<T: ArrowPrimitiveType>(
+    args: &ScalarFunctionArgs,
+    mut f: impl FnMut(&[u8], usize) -> Result<Option<T::Native>>,
+) -> Result<ColumnarValue> {
+    let is_scalar = args
+        .args
+        .iter()
+        .all(|arg| matches!(arg, ColumnarValue::Scalar(_)));
+    let num_rows = if is_scalar { 1 } else { args.number_rows };
+    let sketches = binary_sketches(&args.args[0].to_array(num_rows)?)?;
+    let sketches = sketches.as_binary::<i32>();
+
+    let mut builder = PrimitiveBuilder::<T>::with_capacity(num_rows);
+    for row in 0..num_rows {
+        if sketches.is_null(row) {
+            builder.append_null();
+        } else {
+            builder.append_option(f(sketches.value(row), row)?);
+        }
+    }
+    let array = builder.finish();
+    if is_scalar {
+        Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+            &array, 0,
+        )?))
+    } else {
+        Ok(ColumnarValue::Array(Arc::new(array)))
+    }
+}
@@ -0,0 +1,689 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the `theta_sketch_agg`, `theta_union` and `theta_intersect`
+//! aggregate functions and the `theta_estimate` scalar function.
+//!
+//! A theta sketch (also known as a K minimum values sketch) keeps the hashes
+//! of the distinct values that are smaller than a threshold `theta`, which is
+//! lowered to keep at most `nominal_entries` hashes. The number of distinct
+//! values is estimated as the number of kept hashes divided by the fraction
+//! of the hash space below `theta`. Unlike HyperLogLog sketches, theta
+//! sketches can be intersected as well as united.
+
+use std::mem::size_of;
+use std::sync::{Arc, LazyLock};
+
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, Field, FieldRef, UInt64Type};
+use datafusion_common::hash_utils::create_hashes;
+use datafusion_common::types::{NativeType, logical_int64};
+use datafusion_common::{Result, exec_err, internal_err};
+use datafusion_expr::function::{AccumulatorArgs, StateFieldsArgs};
+use datafusion_expr::utils::format_state_name;
+use datafusion_expr::{
+    Accumulator, AggregateUDFImpl, Coercion, ColumnarValue, Documentation,
+    GroupsAccumulator, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature,
+    TypeSignature, TypeSignatureClass, Volatility,
+};
+use datafusion_macros::user_doc;
+
+use crate::hyperloglog::HLL_HASH_STATE;
+use crate::sketch::{
+    Sketch, SketchAccumulator, SketchGroupsAccumulator, SketchInput, SketchOutput,
+    map_sketches, validate_sketch_parameter,
+};
+
+make_udaf_expr_and_func!(
+    ThetaSketchAgg,
+    theta_sketch_agg,
+    expression,
+    "theta sketch of the distinct input values",
+    theta_sketch_agg_udaf
+);
+
+make_udaf_expr_and_func!(
+    ThetaUnion,
+    theta_union,
+    sketch,
+    "union of theta sketches",
+    theta_union_udaf
+);
+
+make_udaf_expr_and_func!(
+    ThetaIntersect,
+    theta_intersect,
+    sketch,
+    "intersection of theta sketches",
+    theta_intersect_udaf
+);
+
+/// Returns the [`ScalarUDF`] for [`ThetaEstimate`]
+pub fn theta_estimate_udf() -> Arc<ScalarUDF> {
+    static INSTANCE: LazyLock<Arc<ScalarUDF>> =
+        LazyLock::new(|| Arc::new(ScalarUDF::from(ThetaEstimate::new())));
+    Arc::clone(&INSTANCE)
+}
+
+/// Approximate number of distinct values summarized by a theta sketch
+pub fn theta_estimate(sketch: datafusion_expr::Expr) -> datafusion_expr::Expr {
+    theta_estimate_udf().call(vec![sketch])
+}
+
+/// The default maximum number of hashes kept by a theta sketch, for a
+/// relative standard error of about 1.6%
+pub const DEFAULT_NOMINAL_ENTRIES: usize = 4096;
+
+/// The bounds of the `nominal_entries` argument of `theta_sketch_agg`
+const MIN_NOMINAL_ENTRIES: usize = 16;
+const MAX_NOMINAL_ENTRIES: usize = 1 << 26;
+
+/// Version of the serialized format, which is
+///
+/// ```text
+/// version: u8 | nominal_entries: u32 | theta: u64 | num_hashes: u32 | hashes: [u64]
+/// ```
+///
+/// with little-endian integers and the hashes in ascending order.
+const FORMAT_VERSION: u8 = 1;
+const HEADER_LEN: usize = 1 + 4 + 8 + 4;
+
+/// A theta sketch of a set of values
+#[derive(Debug, Clone)]
+pub(crate) struct ThetaSketch {
+    nominal_entries: usize,
+    /// Only hashes smaller than `theta` are kept
+    theta: u64,
+    /// The kept hashes, possibly with duplicates and more than
+    /// `nominal_entries` of them until [`Self::compact`] is called
+    hashes: Vec<u64>,
+}
+
+impl ThetaSketch {
+    pub(crate) fn new(nominal_entries: usize) -> Self {
+        Self {
+            nominal_entries,
+            theta: u64::MAX,
+            hashes: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, hash: u64) {
+        if hash < self.theta {
+            self.hashes.push(hash);
+            if self.hashes.len() >= 2 * self.nominal_entries {
+                self.compact();
+            }
+        }
+    }
+
+    /// Sorts and deduplicates the kept hashes, lowering `theta` to the
+    /// smallest hash that does not fit into the sketch
+    fn compact(&mut self) {
+        self.hashes.sort_unstable();
+        self.hashes.dedup();
+        if self.hashes.len() > self.nominal_entries {
+            self.theta = self.hashes[self.nominal_entries];
+            self.hashes.truncate(self.nominal_entries);
+        }
+    }
+
+    /// Lowers `theta`, dropping the hashes that are not smaller
+    fn lower_theta(&mut self, theta: u64) {
+        if theta < self.theta {
+            self.theta = theta;
+            self.hashes.retain(|&hash| hash < theta);
+        }
+    }
+
+    fn union(&mut self, other: &ThetaSketch) {
+        self.nominal_entries = self.nominal_entries.min(other.nominal_entries);
+        self.lower_theta(other.theta);
+        let theta = self.theta;
+        self.hashes
+            .extend(other.hashes.iter().filter(|&&hash| hash < theta));
+        self.compact();
+    }
+
+    /// Intersects with `other`, whose hashes must be sorted and deduplicated
+    fn intersect(&mut self, other: &ThetaSketch) {
+        self.compact();
+        self.lower_theta(other.theta);
+        let mut other_hashes = other.hashes.iter().peekable();
+        self.hashes.retain(|hash| {
+            while other_hashes.next_if(|&other| other < hash).is_some() {}
+            other_hashes.peek() == Some(&hash)
+        });
+    }
+
+    fn estimate(&mut self) -> u64 {
+        self.compact();
+        if self.theta == u64::MAX {
+            self.hashes.len() as u64
+        } else {
+            let fraction = self.theta as f64 / u64::MAX as f64;
+            (self.hashes.len() as f64 / fraction).round() as u64
+        }
+    }
+
+    fn compacted_bytes(&mut self) -> Vec<u8> {
+        self.compact();
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.hashes.len() * 8);
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&(self.nominal_entries as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.theta.to_le_bytes());
+        bytes.extend_from_slice(&(self.hashes.len() as u32).to_le_bytes());
+        for hash in &self.hashes {
+            bytes.extend_from_slice(&hash.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn try_from_bytes(bytes: &[u8]) -> Result<Self> {
+        let invalid = || exec_err!("Invalid theta sketch of {} bytes", bytes.len());
+        if bytes.len() < HEADER_LEN || bytes[0] != FORMAT_VERSION {
+            return invalid();
+        }
+        let nominal_entries = u32::from_le_bytes(bytes[1..5].try_into().unwrap());
+        let theta = u64::from_le_bytes(bytes[5..13].try_into().unwrap());
+        let num_hashes = u32::from_le_bytes(bytes[13..17].try_into().unwrap());
+        let hashes = &bytes[HEADER_LEN..];
+        if nominal_entries == 0 || hashes.len() != num_hashes as usize * 8 {
+            return invalid();
+        }
+        let hashes = hashes
+            .chunks_exact(size_of::<u64>())
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        if hashes.is_sorted_by(|a, b| a < b) && hashes.last().is_none_or(|&h| h < theta) {
+            Ok(Self {
+                nominal_entries: nominal_entries as usize,
+                theta,
+                hashes,
+            })
+        } else {
+            invalid()
+        }
+    }
+}
+
+impl Sketch for ThetaSketch {
+    type Batch = Vec<u64>;
+
+    fn prepare(&self, values: &[ArrayRef]) -> Result<Vec<u64>> {
+        let mut hashes = vec![0; values[0].len()];
+        create_hashes([values[0].as_ref()], &HLL_HASH_STATE, &mut hashes)?;
+        Ok(hashes)
+    }
+
+    fn update(&mut self, hashes: &Vec<u64>, row: usize) {
+        self.insert(hashes[row]);
+    }
+
+    fn merge(&mut self, bytes: &[u8]) -> Result<()> {
+        self.union(&ThetaSketch::try_from_bytes(bytes)?);
+        Ok(())
+    }
+
+    fn serialize(&mut self) -> Option<Vec<u8>> {
+        Some(self.compacted_bytes())
+    }
+
+    fn size(&self) -> usize {
+        self.hashes.capacity() * size_of::<u64>()
+    }
+}
+
+/// The intersection of the theta sketches merged into it, which is unknown
+/// until the first sketch is merged
+#[derive(Debug, Clone, Default)]
+struct ThetaIntersection(Option<ThetaSketch>);
+
+impl Sketch for ThetaIntersection {
+    type Batch = ();
+
+    fn prepare(&self, _values: &[ArrayRef]) -> Result<()> {
+        internal_err!("theta_intersect can only merge sketches")
+    }
+
+    fn update(&mut self, _batch: &(), _row: usize) {}
+
+    fn merge(&mut self, bytes: &[u8]) -> Result<()> {
+        let other = ThetaSketch::try_from_bytes(bytes)?;
+        match &mut self.0 {
+            Some(sketch) => sketch.intersect(&other),
+            None => self.0 = Some(other),
+        }
+        Ok(())
+    }
+
+    fn serialize(&mut self) -> Option<Vec<u8>> {
+        self.0.as_mut().map(ThetaSketch::compacted_bytes)
+    }
+
+    fn size(&self) -> usize {
+        self.0.as_ref().map(Sketch::size).unwrap_or_default()
+    }
+}
+
+fn sketch_state_fields(name: &str) -> Vec<FieldRef> {
+    vec![
+        Field::new(
+            format_state_name(name, "theta_sketch"),
+            DataType::Binary,
+            true,
+        )
+        .into(),
+    ]
+}
+
+/// Signature of the functions taking a single binary sketch argument
+fn sketch_signature() -> Signature {
+    Signature::coercible(
+        vec![Coercion::new_exact(TypeSignatureClass::Binary)],
+        Volatility::Immutable,
+    )
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Returns a theta sketch of the distinct input values, which can be stored and later combined with `theta_union` and `theta_intersect` and read with `theta_estimate`.",
+    syntax_example = "theta_sketch_agg(expression[, nominal_entries])",
+    sql_example = r#"```sql
+> SELECT theta_estimate(theta_sketch_agg(column_name)) FROM table_name;
++-----------------------------------------------------+
+| theta_estimate(theta_sketch_agg(table.column_name)) |
++-----------------------------------------------------+
+| 42                                                  |
++-----------------------------------------------------+
+```"#,
+    standard_argument(name = "expression",),
+    argument(
+        name = "nominal_entries",
+        description = "Maximum number of hashes kept by the sketch, between 16 and 67108864. Larger sketches are more accurate. Defaults to 4096."
+    )
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ThetaSketchAgg {
+    signature: Signature,
+}
+
+impl Default for ThetaSketchAgg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThetaSketchAgg {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Any(1),
+                    TypeSignature::Coercible(vec![
+                        Coercion::new_exact(TypeSignatureClass::Any),
+                        Coercion::new_implicit(
+                            TypeSignatureClass::Native(logical_int64()),
+                            vec![TypeSignatureClass::Integer],
+                            NativeType::Int64,
+                        ),
+                    ]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+
+    fn empty_sketch(&self, args: &AccumulatorArgs) -> Result<ThetaSketch> {
+        let nominal_entries = match args.exprs.get(1) {
+            Some(expr) => validate_sketch_parameter(
+                expr,
+                self.name(),
+                "nominal_entries",
+                MIN_NOMINAL_ENTRIES..=MAX_NOMINAL_ENTRIES,
+            )?,
+            None => DEFAULT_NOMINAL_ENTRIES,
+        };
+        Ok(ThetaSketch::new(nominal_entries))
+    }
+}
+
+impl AggregateUDFImpl for ThetaSketchAgg {
+    fn name(&self) -> &str {
+        "theta_sketch_agg"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(SketchAccumulator::new(
+            self.empty_sketch(&acc_args)?,
+            SketchInput::Values,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(SketchGroupsAccumulator::new(
+            self.empty_sketch(&args)?,
+            SketchInput::Values,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Returns the union of theta sketches returned by `theta_sketch_agg`, `theta_union` or `theta_intersect`, ignoring null sketches.",
+    syntax_example = "theta_union(sketch)",
+    sql_example = r#"```sql
+> SELECT theta_estimate(theta_union(users_sketch)) FROM daily_rollup;
++--------------------------------------------------------+
+| theta_estimate(theta_union(daily_rollup.users_sketch)) |
++--------------------------------------------------------+
+| 42                                                     |
++--------------------------------------------------------+
+```"#,
+    argument(name = "sketch", description = "Binary column of theta sketches.")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ThetaUnion {
+    signature: Signature,
+}
+
+impl Default for ThetaUnion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThetaUnion {
+    pub fn new() -> Self {
+        Self {
+            signature: sketch_signature(),
+        }
+    }
+}
+
+impl AggregateUDFImpl for ThetaUnion {
+    fn name(&self) -> &str {
+        "theta_union"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn is_nullable(&self) -> bool {
+        false
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+
+    fn accumulator(&self, _acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(SketchAccumulator::new(
+            ThetaSketch::new(MAX_NOMINAL_ENTRIES),
+            SketchInput::Sketches,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        _args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(SketchGroupsAccumulator::new(
+            ThetaSketch::new(MAX_NOMINAL_ENTRIES),
+            SketchInput::Sketches,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Approximate Functions"),
+    description = "Returns the intersection of theta sketches returned by `theta_sketch_agg`, `theta_union` or `theta_intersect`, ignoring null sketches. Returns null if there are no sketches.",
+    syntax_example = "theta_intersect(sketch)",
+    sql_example = r#"```sql
+> SELECT theta_estimate(theta_intersect(users_sketch)) FROM daily_rollup;
++------------------------------------------------------------+
+| theta_estimate(theta_intersect(daily_rollup.users_sketch)) |
++------------------------------------------------------------+
+| 7                                                          |
++------------------------------------------------------------+
+```"#,
+    argument(name = "sketch", description = "Binary column of theta sketches.")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ThetaIntersect {
+    signature: Signature,
+}
+
+impl Default for ThetaIntersect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThetaIntersect {
+    pub fn new() -> Self {
+        Self {
+            signature: sketch_signature(),
+        }
+    }
+}
+
+impl AggregateUDFImpl for ThetaIntersect {
+    fn name(&self) -> &str {
+        "theta_intersect"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Binary)
+    }
+
+    fn state_fields(&self, args: StateFieldsArgs) -> Result<Vec<FieldRef>> {
+        Ok(sketch_state_fields(args.name))
+    }
+
+    fn accumulator(&self, _acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(SketchAccumulator::new(
+            ThetaIntersection::default(),
+            SketchInput::Sketches,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn groups_accumulator_supported(&self, _args: AccumulatorArgs) -> bool {
+        true
+    }
+
+    fn create_groups_accumulator(
+        &self,
+        _args: AccumulatorArgs,
+    ) -> Result<Box<dyn GroupsAccumulator>> {
+        Ok(Box::new(SketchGroupsAccumulator::new(
+            ThetaIntersection::default(),
+            SketchInput::Sketches,
+            SketchOutput::Sketch,
+        )))
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[user_doc(
+    doc_section(label = "Sketch Functions"),
+    description = "Returns the approximate number of distinct values summarized by a theta sketch returned by `theta_sketch_agg`, `theta_union` or `theta_intersect`.",
+    syntax_example = "theta_estimate(sketch)",
+    sql_example = r#"```sql
+> SELECT theta_estimate(theta_sketch_agg(column_name)) FROM table_name;
++-----------------------------------------------------+
+| theta_estimate(theta_sketch_agg(table.column_name)) |
++-----------------------------------------------------+
+| 42                                                  |
++-----------------------------------------------------+
+```"#,
+    argument(name = "sketch", description = "Theta sketch.")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ThetaEstimate {
+    signature: Signature,
+}
+
+impl Default for ThetaEstimate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ThetaEstimate {
+    pub fn new() -> Self {
+        Self {
+            signature: sketch_signature(),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ThetaEstimate {
+    fn name(&self) -> &str {
+        "theta_estimate"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::UInt64)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        map_sketches::<UInt64Type>(&args, |bytes, _| {
+            Ok(Some(ThetaSketch::try_from_bytes(bytes)?.estimate()))
+        })
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::BuildHasher;
+
+    fn sketch_of(
+        nominal_entries: usize,
+        values: impl Iterator<Item = u64>,
+    ) -> ThetaSketch {
+        let mut sketch = ThetaSketch::new(nominal_entries);
+        for value in values {
+            sketch.insert(HLL_HASH_STATE.hash_one(value));
+        }
+        sketch
+    }
+
+    fn assert_close(estimate: u64, expected: u64, tolerance: f64) {
+        let error = (estimate as f64 - expected as f64).abs() / expected as f64;
+        assert!(error < tolerance, "{estimate} is not close to {expected}");
+    }
+
+    #[test]
+    fn exact_below_nominal_entries() {
+        let mut sketch = sketch_of(1024, (0..1000).chain(0..1000));
+        assert_eq!(sketch.estimate(), 1000);
+        assert_eq!(sketch.theta, u64::MAX);
+    }
+
+    #[test]
+    fn estimate_union_and_intersection() -> Result<()> {
+        let mut a = sketch_of(4096, 0..100_000);
+        let b = sketch_of(4096, 50_000..200_000);
+        assert_close(a.estimate(), 100_000, 0.05);
+
+        let mut union = a.clone();
+        union.union(&b);
+        assert_close(union.estimate(), 200_000, 0.05);
+
+        // The intersection keeps fewer hashes, so it is less accurate
+        let mut intersection = a.clone();
+        let mut b = b;
+        b.compact();
+        intersection.intersect(&b);
+        assert_close(intersection.estimate(), 50_000, 0.1);
+
+        let mut roundtrip = ThetaSketch::try_from_bytes(&a.compacted_bytes())?;
+        assert_eq!(roundtrip.estimate(), a.estimate());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_sketch() {
+        let err = ThetaSketch::try_from_bytes(&[1, 2, 3]).unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: Invalid theta sketch of 3 bytes"
+        );
+    }
+}
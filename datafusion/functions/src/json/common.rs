@@ -0,0 +1,256 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Argument handling shared by the JSON functions
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::datatypes::DataType;
+use datafusion_common::{Result, ScalarValue, exec_err, plan_err};
+use datafusion_expr::ColumnarValue;
+use serde_json::Value;
+
+/// An element of the path to a value in a JSON document
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathElement {
+    /// The member of an object with this key, or the element of an array
+    /// at this index if the key is an integer
+    Key(String),
+    /// The element of an array at this index, counting from the end if
+    /// negative
+    Index(i64),
+}
+
+/// Returns the value at `path` in `value`, or `None` if it does not exist
+fn get_path<'a>(mut value: &'a Value, path: &[PathElement]) -> Option<&'a Value> {
+    for element in path {
+        value = match (value, element) {
+            (Value::Object(object), PathElement::Key(key)) => object.get(key)?,
+            (Value::Array(array), PathElement::Key(key)) => {
+                get_index(array, key.parse().ok()?)?
+            }
+            (Value::Array(array), PathElement::Index(idx)) => get_index(array, *idx)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+fn get_index(array: &[Value], idx: i64) -> Option<&Value> {
+    let idx = if idx < 0 {
+        array.len().checked_sub(idx.unsigned_abs() as usize)?
+    } else {
+        idx as usize
+    };
+    array.get(idx)
+}
+
+/// Coerces the arguments of a function called as `f(json, path...)`
+///
+/// The JSON argument keeps its string type, while the path elements are
+/// coerced to `Utf8` keys or `Int64` array indexes.
+pub(super) fn coerce_json_args(
+    name: &str,
+    arg_types: &[DataType],
+) -> Result<Vec<DataType>> {
+    let Some((json_type, path_types)) = arg_types.split_first() else {
+        return plan_err!("{name} expects at least 1 argument, got 0");
+    };
+    let json_type = match json_type {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => json_type.clone(),
+        DataType::Null => DataType::Utf8,
+        other => {
+            return plan_err!("{name} expects a string JSON argument, got {other}");
+        }
+    };
+    let path_types = path_types.iter().map(|path_type| match path_type {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View | DataType::Null => {
+            Ok(DataType::Utf8)
+        }
+        path_type if path_type.is_integer() => Ok(DataType::Int64),
+        other => plan_err!(
+            "{name} expects path elements that are strings or integers, got {other}"
+        ),
+    });
+    std::iter::once(Ok(json_type)).chain(path_types).collect()
+}
+
+/// A path element argument, after [`coerce_json_args`]
+enum PathArg {
+    Scalar(Option<PathElement>),
+    Array(ArrayRef),
+}
+
+impl PathArg {
+    fn try_new(arg: &ColumnarValue) -> Result<Self> {
+        Ok(match arg {
+            ColumnarValue::Scalar(ScalarValue::Int64(idx)) => {
+                PathArg::Scalar(idx.map(PathElement::Index))
+            }
+            ColumnarValue::Scalar(value) => match value.try_as_str() {
+                Some(key) => {
+                    PathArg::Scalar(key.map(|key| PathElement::Key(key.to_string())))
+                }
+                None => return exec_err!("Unsupported JSON path element {value}"),
+            },
+            ColumnarValue::Array(array) => PathArg::Array(Arc::clone(array)),
+        })
+    }
+
+    fn get(&self, row: usize) -> Option<PathElement> {
+        match self {
+            PathArg::Scalar(element) => element.clone(),
+            PathArg::Array(array) if array.is_null(row) => None,
+            PathArg::Array(array) => match array.data_type() {
+                DataType::Int64 => Some(PathElement::Index(
+                    array
+                        .as_primitive::<arrow::datatypes::Int64Type>()
+                        .value(row),
+                )),
+                _ => Some(PathElement::Key(
+                    array.as_string::<i32>().value(row).to_string(),
+                )),
+            },
+        }
+    }
+}
+
+/// Evaluates `f` on the value at the path of each row of a function called as
+/// `f(json, path...)`
+///
+/// `f` is called with `None` if the document is not valid JSON or the path
+/// does not exist in it. The result is `NULL` without calling `f` if the
+/// document or a path element is `NULL`. The rows are evaluated once if all
+/// arguments are scalars.
+pub(super) fn invoke_json<T>(
+    args: &[ColumnarValue],
+    number_rows: usize,
+    f: impl Fn(Option<&Value>) -> Option<T>,
+) -> Result<Vec<Option<T>>> {
+    let number_rows = if args
+        .iter()
+        .all(|arg| matches!(arg, ColumnarValue::Scalar(_)))
+    {
+        1
+    } else {
+        number_rows
+    };
+    let path_args = args[1..]
+        .iter()
+        .map(PathArg::try_new)
+        .collect::<Result<Vec<_>>>()?;
+    // The path is only built once if it is constant
+    let scalar_path = path_args
+        .iter()
+        .map(|arg| match arg {
+            PathArg::Scalar(element) => element.clone(),
+            PathArg::Array(_) => None,
+        })
+        .collect::<Option<Vec<_>>>();
+
+    let json = args[0].to_array(number_rows)?;
+    let mut results = Vec::with_capacity(number_rows);
+    let mut row_path = vec![];
+    for row in 0..number_rows {
+        let Some(json) = json_string(&json, row) else {
+            results.push(None);
+            continue;
+        };
+        let path = match &scalar_path {
+            Some(path) => path,
+            None => {
+                row_path.clear();
+                for path_arg in &path_args {
+                    match path_arg.get(row) {
+                        Some(element) => row_path.push(element),
+                        None => break,
+                    }
+                }
+                if row_path.len() < path_args.len() {
+                    results.push(None);
+                    continue;
+                }
+                &row_path
+            }
+        };
+        let value = serde_json::from_str::<Value>(json).ok();
+        results.push(f(value.as_ref().and_then(|value| get_path(value, path))));
+    }
+    Ok(results)
+}
+
+fn json_string(array: &ArrayRef, row: usize) -> Option<&str> {
+    if array.is_null(row) {
+        return None;
+    }
+    match array.data_type() {
+        DataType::Utf8View => Some(array.as_string_view().value(row)),
+        DataType::LargeUtf8 => Some(array.as_string::<i64>().value(row)),
+        _ => Some(array.as_string::<i32>().value(row)),
+    }
+}
+
+/// Wraps the result of [`invoke_json`], returning a scalar if all `args` are
+/// scalars
+pub(super) fn to_columnar_value(
+    array: ArrayRef,
+    args: &[ColumnarValue],
+) -> Result<ColumnarValue> {
+    if args
+        .iter()
+        .all(|arg| matches!(arg, ColumnarValue::Scalar(_)))
+    {
+        Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+            &array, 0,
+        )?))
+    } else {
+        Ok(ColumnarValue::Array(array))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_path() {
+        let value: Value =
+            serde_json::from_str(r#"{"a": {"b": [1, {"c": true}]}, "d": null}"#).unwrap();
+        let key = |key: &str| PathElement::Key(key.to_string());
+        let get = |path: &[PathElement]| get_path(&value, path).map(|v| v.to_string());
+
+        assert_eq!(get(&[]), Some(value.to_string()));
+        assert_eq!(
+            get(&[key("a"), key("b"), PathElement::Index(0)]),
+            Some("1".into())
+        );
+        assert_eq!(
+            get(&[key("a"), key("b"), key("1"), key("c")]),
+            Some("true".into())
+        );
+        assert_eq!(
+            get(&[key("a"), key("b"), PathElement::Index(-1)]),
+            Some(r#"{"c":true}"#.into())
+        );
+        assert_eq!(get(&[key("d")]), Some("null".into()));
+        assert_eq!(get(&[key("a"), key("b"), PathElement::Index(2)]), None);
+        assert_eq!(get(&[key("a"), key("b"), PathElement::Index(-3)]), None);
+        assert_eq!(get(&[key("a"), PathElement::Index(0)]), None);
+        assert_eq!(get(&[key("e")]), None);
+    }
+}
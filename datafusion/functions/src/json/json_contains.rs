@@ -0,0 +1,95 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::BooleanArray;
+use arrow::datatypes::DataType;
+use datafusion_common::Result;
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility,
+};
+use datafusion_macros::user_doc;
+
+use super::common::{coerce_json_args, invoke_json, to_columnar_value};
+
+#[user_doc(
+    doc_section(label = "JSON Functions"),
+    description = "Returns true if the given path exists in a JSON document, including paths to `null` values, and false if it does not or the document is not valid JSON.",
+    syntax_example = "json_contains(json[, path...])",
+    sql_example = r#"```sql
+> select json_contains('{"a": {"b": null}}', 'a', 'b');
++---------------------------------------------------------------+
+| json_contains(Utf8("{"a": {"b": null}}"),Utf8("a"),Utf8("b")) |
++---------------------------------------------------------------+
+| true                                                          |
++---------------------------------------------------------------+
+```"#,
+    argument(name = "json", description = "JSON document to operate on."),
+    argument(
+        name = "path",
+        description = "Object keys or array indexes of the value to extract. String keys also select array elements if they are integers, and negative indexes count from the end of an array."
+    ),
+    related_udf(name = "json_get")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct JsonContainsFunc {
+    signature: Signature,
+}
+
+impl Default for JsonContainsFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonContainsFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonContainsFunc {
+    fn name(&self) -> &str {
+        "json_contains"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coerce_json_args(self.name(), arg_types)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let values =
+            invoke_json(&args.args, args.number_rows, |value| Some(value.is_some()))?;
+        to_columnar_value(Arc::new(BooleanArray::from(values)), &args.args)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
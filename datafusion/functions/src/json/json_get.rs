@@ -0,0 +1,105 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::StringArray;
+use arrow::datatypes::DataType;
+use datafusion_common::Result;
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility,
+};
+use datafusion_macros::user_doc;
+use serde_json::Value;
+
+use super::common::{coerce_json_args, invoke_json, to_columnar_value};
+
+#[user_doc(
+    doc_section(label = "JSON Functions"),
+    description = "Returns the JSON value at the given path of a JSON document, or `NULL` if the path does not exist or the document is not valid JSON.",
+    syntax_example = "json_get(json[, path...])",
+    alternative_syntax = "json -> path",
+    sql_example = r#"```sql
+> select json_get('{"a": {"b": [1, 2]}}', 'a', 'b');
++------------------------------------------------------------+
+| json_get(Utf8("{"a": {"b": [1, 2]}}"),Utf8("a"),Utf8("b")) |
++------------------------------------------------------------+
+| [1,2]                                                      |
++------------------------------------------------------------+
+```"#,
+    argument(name = "json", description = "JSON document to operate on."),
+    argument(
+        name = "path",
+        description = "Object keys or array indexes of the value to extract. String keys also select array elements if they are integers, and negative indexes count from the end of an array."
+    ),
+    related_udf(name = "json_as_text"),
+    related_udf(name = "json_get_str")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct JsonGetFunc {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Default for JsonGetFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonGetFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+            aliases: vec![String::from("json_extract_path")],
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonGetFunc {
+    fn name(&self) -> &str {
+        "json_get"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coerce_json_args(self.name(), arg_types)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let values = invoke_json(&args.args, args.number_rows, |value| {
+            value.map(Value::to_string)
+        })?;
+        to_columnar_value(Arc::new(StringArray::from(values)), &args.args)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
@@ -0,0 +1,113 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ListBuilder, StringBuilder};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::Result;
+use datafusion_expr::{
+    ColumnarValue, Documentation, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility,
+};
+use datafusion_macros::user_doc;
+use serde_json::Value;
+
+use super::common::{coerce_json_args, invoke_json, to_columnar_value};
+
+#[user_doc(
+    doc_section(label = "JSON Functions"),
+    description = "Returns the keys of the object at the given path of a JSON document in their original order, or `NULL` if the path does not exist or the value is not an object.",
+    syntax_example = "json_object_keys(json[, path...])",
+    sql_example = r#"```sql
+> select json_object_keys('{"a": 1, "b": 2}');
++--------------------------------------------+
+| json_object_keys(Utf8("{"a": 1, "b": 2}")) |
++--------------------------------------------+
+| [a, b]                                     |
++--------------------------------------------+
+```"#,
+    argument(name = "json", description = "JSON document to operate on."),
+    argument(
+        name = "path",
+        description = "Object keys or array indexes of the value to extract. String keys also select array elements if they are integers, and negative indexes count from the end of an array."
+    ),
+    related_udf(name = "json_length")
+)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct JsonObjectKeysFunc {
+    signature: Signature,
+}
+
+impl Default for JsonObjectKeysFunc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonObjectKeysFunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonObjectKeysFunc {
+    fn name(&self) -> &str {
+        "json_object_keys"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::List(Arc::new(Field::new_list_field(
+            DataType::Utf8,
+            true,
+        ))))
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        coerce_json_args(self.name(), arg_types)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let values = invoke_json(&args.args, args.number_rows, |value| match value? {
+            Value::Object(object) => Some(object.keys().cloned().collect::<Vec<_>>()),
+            _ => None,
+        })?;
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        for keys in values {
+            match keys {
+                Some(keys) => {
+                    for key in keys {
+                        builder.values().append_value(key);
+                    }
+                    builder.append(true);
+                }
+                None => builder.append_null(),
+            }
+        }
+        to_columnar_value(Arc::new(builder.finish()), &args.args)
+    }
+
+    fn documentation(&self) -> Option<&Documentation> {
+        self.doc()
+    }
+}
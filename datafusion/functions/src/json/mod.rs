@@ -0,0 +1,110 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! JSON functions, operating on JSON documents stored as strings
+
+use std::sync::Arc;
+
+use datafusion_expr::ScalarUDF;
+
+mod common;
+pub mod json_as_text;
+pub mod json_contains;
+pub mod json_get;
+pub mod json_get_bool;
+pub mod json_get_float;
+pub mod json_get_int;
+pub mod json_get_str;
+pub mod json_length;
+pub mod json_object_keys;
+pub mod planner;
+
+// create UDFs
+make_udf_function!(json_as_text::JsonAsTextFunc, json_as_text);
+make_udf_function!(json_contains::JsonContainsFunc, json_contains);
+make_udf_function!(json_get::JsonGetFunc, json_get);
+make_udf_function!(json_get_bool::JsonGetBoolFunc, json_get_bool);
+make_udf_function!(json_get_float::JsonGetFloatFunc, json_get_float);
+make_udf_function!(json_get_int::JsonGetIntFunc, json_get_int);
+make_udf_function!(json_get_str::JsonGetStrFunc, json_get_str);
+make_udf_function!(json_length::JsonLengthFunc, json_length);
+make_udf_function!(json_object_keys::JsonObjectKeysFunc, json_object_keys);
+
+pub mod expr_fn {
+    export_functions!(
+        (
+            json_as_text,
+            "returns the value at a path of a JSON document as text, unquoting strings",
+            args,
+        ),
+        (
+            json_contains,
+            "returns true if a path exists in a JSON document",
+            args,
+        ),
+        (
+            json_get,
+            "returns the JSON value at a path of a JSON document",
+            args,
+        ),
+        (
+            json_get_bool,
+            "returns the boolean at a path of a JSON document",
+            args,
+        ),
+        (
+            json_get_float,
+            "returns the number at a path of a JSON document as a float",
+            args,
+        ),
+        (
+            json_get_int,
+            "returns the integer at a path of a JSON document",
+            args,
+        ),
+        (
+            json_get_str,
+            "returns the string at a path of a JSON document",
+            args,
+        ),
+        (
+            json_length,
+            "returns the length of the array or object at a path of a JSON document",
+            args,
+        ),
+        (
+            json_object_keys,
+            "returns the keys of the object at a path of a JSON document",
+            args,
+        )
+    );
+}
+
+/// Returns all DataFusion functions defined in this package
+pub fn functions() -> Vec<Arc<ScalarUDF>> {
+    vec![
+        json_as_text(),
+        json_contains(),
+        json_get(),
+        json_get_bool(),
+        json_get_float(),
+        json_get_int(),
+        json_get_str(),
+        json_length(),
+        json_object_keys(),
+    ]
+}
@@ -0,0 +1,119 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! SQL planning extensions like [`JsonFunctionPlanner`]
+
+use datafusion_common::{DFSchema, Result};
+use datafusion_expr::expr::ScalarFunction;
+use datafusion_expr::planner::{ExprPlanner, PlannerResult, RawBinaryExpr};
+#[cfg(feature = "sql")]
+use datafusion_expr::sqlparser::ast::BinaryOperator;
+use datafusion_expr::{BinaryExpr, Expr, ExprSchemable, Operator};
+#[cfg(not(feature = "sql"))]
+use datafusion_expr_common::operator::Operator as BinaryOperator;
+
+use super::json_get::JsonGetFunc;
+
+/// Plans the `json -> path` and `json ->> path` operators on string columns
+/// as calls to `json_get` and `json_as_text`
+///
+/// A chain of operators such as `json -> 'a' ->> 'b'` is planned as a single
+/// call with the whole path, `json_as_text(json, 'a', 'b')`. A comparison on
+/// the right, as in `json ->> 'a' = 'x'`, compares the extracted value.
+#[derive(Default, Debug)]
+pub struct JsonFunctionPlanner;
+
+impl ExprPlanner for JsonFunctionPlanner {
+    fn plan_binary_op(
+        &self,
+        expr: RawBinaryExpr,
+        schema: &DFSchema,
+    ) -> Result<PlannerResult<RawBinaryExpr>> {
+        let func = match expr.op {
+            BinaryOperator::Arrow => super::json_get(),
+            BinaryOperator::LongArrow => super::json_as_text(),
+            _ => return Ok(PlannerResult::Original(expr)),
+        };
+        let RawBinaryExpr { op, left, right } = expr;
+
+        // The operators bind less tightly than comparisons, so `json ->> 'a' = 'x'`
+        // is parsed as `json ->> ('a' = 'x')`. A comparison is never a valid
+        // path element, so plan it as `(json ->> 'a') = 'x'` instead.
+        let right = match right {
+            Expr::BinaryExpr(BinaryExpr {
+                left: path,
+                op: comparison,
+                right: value,
+            }) if is_comparison(comparison) => {
+                let raw = RawBinaryExpr {
+                    op,
+                    left,
+                    right: *path,
+                };
+                return Ok(match self.plan_binary_op(raw, schema)? {
+                    PlannerResult::Planned(extracted) => {
+                        PlannerResult::Planned(Expr::BinaryExpr(BinaryExpr::new(
+                            Box::new(extracted),
+                            comparison,
+                            value,
+                        )))
+                    }
+                    PlannerResult::Original(RawBinaryExpr { op, left, right }) => {
+                        let right = Expr::BinaryExpr(BinaryExpr::new(
+                            Box::new(right),
+                            comparison,
+                            value,
+                        ));
+                        PlannerResult::Original(RawBinaryExpr { op, left, right })
+                    }
+                });
+            }
+            right => right,
+        };
+
+        // Extend the path of a `json -> path` on the left
+        let mut args = match left {
+            Expr::ScalarFunction(ScalarFunction { func, args })
+                if func.inner().is::<JsonGetFunc>() =>
+            {
+                args
+            }
+            left if left.get_type(schema)?.is_string() => vec![left],
+            left => {
+                return Ok(PlannerResult::Original(RawBinaryExpr { op, left, right }));
+            }
+        };
+        args.push(right);
+        Ok(PlannerResult::Planned(Expr::ScalarFunction(
+            ScalarFunction::new_udf(func, args),
+        )))
+    }
+}
+
+fn is_comparison(op: Operator) -> bool {
+    matches!(
+        op,
+        Operator::Eq
+            | Operator::NotEq
+            | Operator::Lt
+            | Operator::LtEq
+            | Operator::Gt
+            | Operator::GtEq
+            | Operator::IsDistinctFrom
+            | Operator::IsNotDistinctFrom
+    )
+}
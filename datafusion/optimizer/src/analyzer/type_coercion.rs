@@ -57,10 +57,10 @@ use datafusion_expr::type_coercion::{
 };
 use datafusion_expr::utils::merge_schema;
 use datafusion_expr::{
-    Cast, DmlStatement, Expr, ExprSchemable, Join, Limit, LogicalPlan, Operator,
-    Projection, Union, ValueOrLambda, WindowFrame, WindowFrameBound, WindowFrameUnits,
-    WriteOp, is_false, is_not_false, is_not_true, is_not_unknown, is_true, is_unknown,
-    lit, not,
+    AsOfJoin, AsOfMatch, Cast, DmlStatement, Expr, ExprSchemable, Join, Limit,
+    LogicalPlan, Operator, Projection, Union, ValueOrLambda, WindowFrame,
+    WindowFrameBound, WindowFrameUnits, WriteOp, is_false, is_not_false, is_not_true,
+    is_not_unknown, is_true, is_unknown, lit, not,
 };
 
 /// Performs type coercion by determining the schema
@@ -191,6 +191,7 @@ impl<'a> TypeCoercionRewriter<'a> {
     pub fn coerce_plan(&mut self, plan: LogicalPlan) -> Result<LogicalPlan> {
         match plan {
             LogicalPlan::Join(join) => self.coerce_join(join),
+            LogicalPlan::AsOfJoin(join) => self.coerce_asof_join(join),
             LogicalPlan::Union(union) => Self::coerce_union(union),
             LogicalPlan::Limit(limit) => Self::coerce_limit(limit),
             LogicalPlan::Dml(dml) => self.coerce_dml(dml),
@@ -284,6 +285,30 @@ impl<'a> TypeCoercionRewriter<'a> {
         Ok(LogicalPlan::Join(join))
     }
 
+    /// Coerce ASOF join equality expressions and match condition
+    ///
+    /// Like [`Self::coerce_join`], both are stored as pairs of left and right
+    /// expressions, which are coerced as though they were a single binary
+    /// comparison.
+    pub fn coerce_asof_join(&mut self, mut join: AsOfJoin) -> Result<LogicalPlan> {
+        let left_schema = join.left.schema();
+        let right_schema = join.right.schema();
+        join.on = join
+            .on
+            .into_iter()
+            .map(|(lhs, rhs)| {
+                self.coerce_binary_op(lhs, left_schema, Operator::Eq, rhs, right_schema)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let AsOfMatch { left, op, right } = *join.match_condition;
+        let (left, right) =
+            self.coerce_binary_op(left, left_schema, op, right, right_schema)?;
+        join.match_condition = Box::new(AsOfMatch { left, op, right });
+
+        Ok(LogicalPlan::AsOfJoin(join))
+    }
+
     /// Coerce the union’s inputs to a common schema compatible with all inputs.
     /// This occurs after wildcard expansion and the coercion of the input expressions.
     pub fn coerce_union(union_plan: Union) -> Result<LogicalPlan> {
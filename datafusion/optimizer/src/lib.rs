@@ -80,6 +80,7 @@ pub use analyzer::{Analyzer, AnalyzerRule};
 pub use optimizer::{
     ApplyOrder, Optimizer, OptimizerConfig, OptimizerContext, OptimizerRule,
 };
+pub use plan_signature::LogicalPlanSignature;
 
 pub(crate) mod join_key_set;
 mod plan_signature;
@@ -407,6 +407,19 @@ fn optimize_projections(
                 right_indices.with_projection_beneficial(),
             ]
         }
+        LogicalPlan::AsOfJoin(join) => {
+            // The output is laid out like the output of a left join
+            let left_len = join.left.schema().fields().len();
+            let (left_req_indices, right_req_indices) = indices.split_off(left_len);
+            vec![
+                left_req_indices
+                    .with_plan_exprs(&plan, join.left.schema())?
+                    .with_projection_beneficial(),
+                right_req_indices
+                    .with_plan_exprs(&plan, join.right.schema())?
+                    .with_projection_beneficial(),
+            ]
+        }
         // these nodes are explicitly rewritten in the match statement above
         LogicalPlan::Projection(_)
         | LogicalPlan::Aggregate(_)
@@ -35,10 +35,10 @@ use datafusion_common::{DFSchema, DataFusionError, HashSet, Result, internal_err
 use datafusion_expr::dml::CopyTo;
 use datafusion_expr::logical_plan::LogicalPlan;
 use datafusion_expr::{
-    Aggregate, Analyze, CreateMemoryTable, CreateView, DdlStatement, Distinct,
-    DistinctOn, DmlStatement, Explain, Expr, Extension, Filter, Join, Limit, Projection,
-    RecursiveQuery, Repartition, Sort, Statement, Subquery, SubqueryAlias, Union, Unnest,
-    Window,
+    Aggregate, Analyze, AsOfJoin, CreateMaterializedView, CreateMemoryTable, CreateView,
+    DdlStatement, Distinct, DistinctOn, DmlStatement, Explain, Expr, Extension, Filter,
+    Join, Limit, Projection, RecursiveQuery, Repartition, Sort, Statement, Subquery,
+    SubqueryAlias, Union, Unnest, Window,
 };
 
 use crate::common_subexpr_eliminate::CommonSubexprEliminate;
@@ -406,7 +406,8 @@ fn map_children_mut<F: FnMut(&mut LogicalPlan) -> Result<bool>>(
         | LogicalPlan::Copy(CopyTo { input, .. })
         | LogicalPlan::Unnest(Unnest { input, .. }) => f(Arc::make_mut(input))?,
         LogicalPlan::Subquery(Subquery { subquery, .. }) => f(Arc::make_mut(subquery))?,
-        LogicalPlan::Join(Join { left, right, .. }) => {
+        LogicalPlan::Join(Join { left, right, .. })
+        | LogicalPlan::AsOfJoin(AsOfJoin { left, right, .. }) => {
             let l = f(Arc::make_mut(left))?;
             let r = f(Arc::make_mut(right))?;
             l || r
@@ -427,9 +428,10 @@ fn map_children_mut<F: FnMut(&mut LogicalPlan) -> Result<bool>>(
             input,
             ..
         }))
-        | LogicalPlan::Ddl(DdlStatement::CreateView(CreateView { input, .. })) => {
-            f(Arc::make_mut(input))?
-        }
+        | LogicalPlan::Ddl(DdlStatement::CreateView(CreateView { input, .. }))
+        | LogicalPlan::Ddl(DdlStatement::CreateMaterializedView(
+            CreateMaterializedView { input, .. },
+        )) => f(Arc::make_mut(input))?,
         LogicalPlan::RecursiveQuery(RecursiveQuery {
             static_term,
             recursive_term,
@@ -479,6 +481,9 @@ fn map_children_mut<F: FnMut(&mut LogicalPlan) -> Result<bool>>(
         | LogicalPlan::Ddl(DdlStatement::DropCatalogSchema(_))
         | LogicalPlan::Ddl(DdlStatement::CreateFunction(_))
         | LogicalPlan::Ddl(DdlStatement::DropFunction(_))
+        | LogicalPlan::Ddl(DdlStatement::AnalyzeTable(_))
+        | LogicalPlan::Ddl(DdlStatement::AlterTable(_))
+        | LogicalPlan::Ddl(DdlStatement::RefreshMaterializedView(_))
         | LogicalPlan::Statement(_) => false,
     })
 }
@@ -68,6 +68,15 @@ impl LogicalPlanSignature {
             plan_hash: hasher.finish(),
         }
     }
+
+    /// Returns the signature as a single `u64`, for use as a cheap key of
+    /// caches that compare the plans themselves on a match, such as the query
+    /// result cache.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
 }
 
 /// Get total number of [`LogicalPlan`]s in the plan.
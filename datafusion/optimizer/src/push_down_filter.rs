@@ -1115,6 +1115,29 @@ impl OptimizerRule for PushDownFilter {
                 result.map_data(|plan| Ok(with_filters(keep_predicates, plan)))
             }
             LogicalPlan::Join(join) => push_down_join(join, Some(filter.predicate)),
+            LogicalPlan::AsOfJoin(mut join) => {
+                // Like a left join, an ASOF join preserves its left rows, and
+                // the match of a left row does not depend on the other left
+                // rows: predicates on the left columns alone can be pushed to
+                // the left input.
+                let mut checker =
+                    ColumnChecker::new(join.left.schema(), join.right.schema());
+                let (left_push, keep_predicates): (Vec<_>, Vec<_>) =
+                    split_conjunction_owned(filter.predicate)
+                        .into_iter()
+                        .partition(|predicate| checker.is_left_only(predicate));
+                let Some(predicate) = conjunction(left_push) else {
+                    filter.predicate = conjunction(keep_predicates).unwrap();
+                    filter.input = Arc::new(LogicalPlan::AsOfJoin(join));
+                    return Ok(Transformed::no(LogicalPlan::Filter(filter)));
+                };
+                join.left =
+                    Arc::new(LogicalPlan::Filter(Filter::try_new(predicate, join.left)?));
+                Ok(Transformed::yes(with_filters(
+                    keep_predicates,
+                    LogicalPlan::AsOfJoin(join),
+                )))
+            }
             LogicalPlan::TableScan(mut scan) => {
                 let filter_predicates = split_conjunction(&filter.predicate);
                 // Filters containing scalar subqueries cannot be pushed to
@@ -3004,6 +3027,46 @@ mod tests {
         )
     }
 
+    /// predicates on the left input alone are pushed below an ASOF join, the
+    /// rest stay above it
+    #[test]
+    fn filter_asof_join() -> Result<()> {
+        let left = test_table_scan()?;
+        let right = test_table_scan_with_name("test2")?;
+        let plan = LogicalPlanBuilder::from(left)
+            .join_asof(
+                right,
+                vec![col("test.a").eq(col("test2.a"))],
+                col("test.b").gt_eq(col("test2.b")),
+            )?
+            .filter(
+                col("test.c")
+                    .gt(lit(1u32))
+                    .and(col("test2.c").gt(lit(4u32)))
+                    .and(col("test.c").lt(col("test2.c"))),
+            )?
+            .build()?;
+
+        // not part of the test, just good to know:
+        assert_snapshot!(plan,
+        @r"
+        Filter: test.c > UInt32(1) AND test2.c > UInt32(4) AND test.c < test2.c
+          AsOf Join: test.a = test2.a, Match: test.b >= test2.b
+            TableScan: test
+            TableScan: test2
+        ",
+        );
+        assert_optimized_plan_equal!(
+            plan,
+            @r"
+        Filter: test2.c > UInt32(4) AND test.c < test2.c
+          AsOf Join: test.a = test2.a, Match: test.b >= test2.b
+            TableScan: test, full_filters=[test.c > UInt32(1)]
+            TableScan: test2
+        "
+        )
+    }
+
     /// single table predicate parts of ON condition should be pushed to left input
     #[test]
     fn right_join_on_with_filter() -> Result<()> {
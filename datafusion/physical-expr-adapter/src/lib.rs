@@ -25,8 +25,12 @@
 //! Physical expression schema adaptation utilities for DataFusion
 
 pub mod rewrite;
+pub mod schema_evolution;
 pub mod schema_rewriter;
 
+pub use schema_evolution::{
+    SCHEMA_EVOLUTION_METADATA_KEY, SchemaEvolution, SchemaEvolutionAdapterFactory,
+};
 pub use schema_rewriter::{
     BatchAdapter, BatchAdapterFactory, DefaultPhysicalExprAdapter,
     DefaultPhysicalExprAdapterFactory, PhysicalExprAdapter, PhysicalExprAdapterFactory,
@@ -0,0 +1,485 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`SchemaEvolution`] and [`SchemaEvolutionAdapterFactory`], to read files
+//! written before the schema of a table was altered.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Schema, SchemaRef};
+use datafusion_common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion_common::{Result, ScalarValue, plan_datafusion_err, plan_err};
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr_common::physical_expr::PhysicalExpr;
+
+use crate::{
+    PhysicalExprAdapter, PhysicalExprAdapterFactory, replace_columns_with_literals,
+};
+
+/// The key of the schema metadata storing the [`SchemaEvolution`] of a table,
+/// see [`SchemaEvolution::to_metadata_value`]
+pub const SCHEMA_EVOLUTION_METADATA_KEY: &str = "datafusion.schema_evolution";
+
+/// The changes applied to the columns of a table after some of its files
+/// were written.
+///
+/// The columns of a file are matched with the columns of the table by name.
+/// [`SchemaEvolution`] records enough about the changes to read a file
+/// written with an older schema of the table:
+///
+/// - A renamed column is read from the column of the file with one of its
+///   previous names.
+/// - An added column that is missing from the file is filled with the value
+///   it was added with.
+/// - A dropped column is ignored.
+///
+/// To keep matching columns by name unambiguous, the name of a dropped or
+/// renamed column cannot be used for another column.
+///
+/// The changes can be stored with the schema of the table, in its metadata
+/// under [`SCHEMA_EVOLUTION_METADATA_KEY`], so that they are kept wherever
+/// the schema is persisted.
+///
+/// See [`SchemaEvolutionAdapterFactory`] to apply it to file scans.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaEvolution {
+    /// The previous names of renamed columns, oldest first, by current name
+    previous_names: HashMap<String, Vec<String>>,
+    /// The values of added columns for files written before they were added
+    default_values: HashMap<String, ScalarValue>,
+    /// The names of dropped and renamed columns
+    retired_names: HashSet<String>,
+}
+
+impl SchemaEvolution {
+    /// Creates an empty [`SchemaEvolution`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if no changes were recorded
+    pub fn is_empty(&self) -> bool {
+        self.previous_names.is_empty()
+            && self.default_values.is_empty()
+            && self.retired_names.is_empty()
+    }
+
+    /// Records that column `name` was added. Files without the column read
+    /// it as `default`, or as null if `default` is `None`.
+    pub fn add_column(&mut self, name: &str, default: Option<ScalarValue>) -> Result<()> {
+        self.check_name_available(name)?;
+        if let Some(default) = default.filter(|default| !default.is_null()) {
+            self.default_values.insert(name.to_string(), default);
+        }
+        Ok(())
+    }
+
+    /// Records that column `name` was dropped
+    pub fn drop_column(&mut self, name: &str) {
+        self.previous_names.remove(name);
+        self.default_values.remove(name);
+        self.retired_names.insert(name.to_string());
+    }
+
+    /// Records that column `old_name` was renamed to `new_name`
+    pub fn rename_column(&mut self, old_name: &str, new_name: &str) -> Result<()> {
+        self.check_name_available(new_name)?;
+        let mut previous_names = self.previous_names.remove(old_name).unwrap_or_default();
+        previous_names.push(old_name.to_string());
+        self.previous_names
+            .insert(new_name.to_string(), previous_names);
+        if let Some(default) = self.default_values.remove(old_name) {
+            self.default_values.insert(new_name.to_string(), default);
+        }
+        self.retired_names.insert(old_name.to_string());
+        Ok(())
+    }
+
+    /// Returns the previous names of column `name`, oldest first
+    pub fn previous_names(&self, name: &str) -> &[String] {
+        self.previous_names
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the value of column `name` for files written before it was
+    /// added, if it is not null
+    pub fn default_value(&self, name: &str) -> Option<&ScalarValue> {
+        self.default_values.get(name)
+    }
+
+    /// Returns `true` if files written before the changes may store column
+    /// `name` with a previous name, or lack it although it is not null.
+    ///
+    /// The statistics of such a file, which match columns by name, are then
+    /// not valid for the column.
+    pub fn is_evolved(&self, name: &str) -> bool {
+        self.previous_names.contains_key(name) || self.default_values.contains_key(name)
+    }
+
+    /// Encodes the changes as the value of [`SCHEMA_EVOLUTION_METADATA_KEY`].
+    ///
+    /// Default values are stored as strings, so an error is returned if one
+    /// cannot be cast to a string and back without changing its value.
+    pub fn to_metadata_value(&self) -> Result<String> {
+        let mut previous_names = self.previous_names.iter().collect::<Vec<_>>();
+        previous_names.sort_unstable();
+        let mut default_values = self.default_values.iter().collect::<Vec<_>>();
+        default_values.sort_unstable_by_key(|(name, _)| *name);
+        let mut retired_names = self.retired_names.iter().collect::<Vec<_>>();
+        retired_names.sort_unstable();
+
+        let default_values = default_values
+            .into_iter()
+            .map(|(name, default)| {
+                let value = default
+                    .cast_to(&DataType::Utf8)
+                    .map(|value| value.to_string())
+                    .ok()
+                    .filter(|value| {
+                        ScalarValue::try_from_string(value.clone(), &default.data_type())
+                            .is_ok_and(|restored| &restored == default)
+                    });
+                let Some(value) = value else {
+                    return plan_err!(
+                        "Default value {default} of column {name} cannot be stored with the table schema"
+                    );
+                };
+                Ok((name.clone(), serde_json::Value::from(value)))
+            })
+            .collect::<Result<serde_json::Map<_, _>>>()?;
+        let previous_names = previous_names
+            .into_iter()
+            .map(|(name, previous)| {
+                (name.clone(), serde_json::Value::from(previous.clone()))
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        Ok(serde_json::json!({
+            "previous_names": previous_names,
+            "default_values": default_values,
+            "retired_names": retired_names,
+        })
+        .to_string())
+    }
+
+    /// Decodes the changes stored in the metadata of `schema` by
+    /// [`Self::to_metadata_value`], or returns an empty [`SchemaEvolution`]
+    /// if there are none. Default values are cast to the type of their
+    /// column in `schema`.
+    pub fn try_from_schema_metadata(schema: &Schema) -> Result<Self> {
+        let Some(value) = schema.metadata().get(SCHEMA_EVOLUTION_METADATA_KEY) else {
+            return Ok(Self::new());
+        };
+        let invalid =
+            || plan_datafusion_err!("Invalid schema evolution metadata: {value}");
+        let value = serde_json::from_str::<serde_json::Value>(value).map_err(|e| {
+            plan_datafusion_err!("Invalid schema evolution metadata: {e}")
+        })?;
+        let strings = |value: &serde_json::Value| {
+            value
+                .as_array()
+                .ok_or_else(invalid)?
+                .iter()
+                .map(|name| name.as_str().map(str::to_string).ok_or_else(invalid))
+                .collect::<Result<Vec<_>>>()
+        };
+
+        let mut evolution = Self::new();
+        for (name, previous) in value["previous_names"].as_object().ok_or_else(invalid)? {
+            evolution
+                .previous_names
+                .insert(name.clone(), strings(previous)?);
+        }
+        for (name, default) in value["default_values"].as_object().ok_or_else(invalid)? {
+            let data_type = schema.field_with_name(name)?.data_type();
+            let default = default.as_str().ok_or_else(invalid)?;
+            evolution.default_values.insert(
+                name.clone(),
+                ScalarValue::try_from_string(default.to_string(), data_type)?,
+            );
+        }
+        evolution
+            .retired_names
+            .extend(strings(&value["retired_names"])?);
+        Ok(evolution)
+    }
+
+    fn check_name_available(&self, name: &str) -> Result<()> {
+        if self.retired_names.contains(name) {
+            return plan_err!(
+                "Column name {name} was used by a dropped or renamed column and cannot be reused"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A [`PhysicalExprAdapterFactory`] that applies a [`SchemaEvolution`] and
+/// delegates the remaining adaptation, such as casting, to another factory.
+///
+/// Renamed columns are read from the columns of the file with their previous
+/// names, and added columns missing from the file are replaced with their
+/// default values.
+#[derive(Debug, Clone)]
+pub struct SchemaEvolutionAdapterFactory {
+    evolution: Arc<SchemaEvolution>,
+    inner: Arc<dyn PhysicalExprAdapterFactory>,
+}
+
+impl SchemaEvolutionAdapterFactory {
+    /// Creates a factory applying `evolution` before the adapters of `inner`
+    pub fn new(
+        evolution: Arc<SchemaEvolution>,
+        inner: Arc<dyn PhysicalExprAdapterFactory>,
+    ) -> Self {
+        Self { evolution, inner }
+    }
+}
+
+impl PhysicalExprAdapterFactory for SchemaEvolutionAdapterFactory {
+    fn create(
+        &self,
+        logical_file_schema: SchemaRef,
+        physical_file_schema: SchemaRef,
+    ) -> Result<Arc<dyn PhysicalExprAdapter>> {
+        // Give the columns of the file with a previous name their current name,
+        // so that the inner adapter matches them with the table columns
+        let mut fields = physical_file_schema.fields().to_vec();
+        let mut file_names = HashMap::new();
+        for field in logical_file_schema.fields() {
+            let name = field.name();
+            if physical_file_schema.index_of(name).is_ok() {
+                continue;
+            }
+            let previous = self.evolution.previous_names(name);
+            if let Some(index) = previous
+                .iter()
+                .rev()
+                .find_map(|previous| physical_file_schema.index_of(previous).ok())
+            {
+                file_names.insert(name.clone(), fields[index].name().clone());
+                fields[index] = Arc::new(fields[index].as_ref().clone().with_name(name));
+            }
+        }
+        let renamed_file_schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            physical_file_schema.metadata().clone(),
+        ));
+
+        let default_values = logical_file_schema
+            .fields()
+            .iter()
+            .filter(|field| renamed_file_schema.index_of(field.name()).is_err())
+            .filter_map(|field| {
+                let default = self.evolution.default_value(field.name())?;
+                Some(
+                    default
+                        .cast_to(field.data_type())
+                        .map(|default| (field.name().clone(), default)),
+                )
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        Ok(Arc::new(SchemaEvolutionAdapter {
+            inner: self
+                .inner
+                .create(logical_file_schema, renamed_file_schema)?,
+            file_names,
+            default_values,
+        }))
+    }
+}
+
+/// The [`PhysicalExprAdapter`] created by [`SchemaEvolutionAdapterFactory`]
+#[derive(Debug)]
+struct SchemaEvolutionAdapter {
+    inner: Arc<dyn PhysicalExprAdapter>,
+    /// The names in the file of renamed columns, by current name
+    file_names: HashMap<String, String>,
+    /// The values of the added columns missing from the file
+    default_values: HashMap<String, ScalarValue>,
+}
+
+impl PhysicalExprAdapter for SchemaEvolutionAdapter {
+    fn rewrite(&self, expr: Arc<dyn PhysicalExpr>) -> Result<Arc<dyn PhysicalExpr>> {
+        let expr = if self.default_values.is_empty() {
+            expr
+        } else {
+            replace_columns_with_literals(expr, &self.default_values)?
+        };
+        let expr = self.inner.rewrite(expr)?;
+        if self.file_names.is_empty() {
+            return Ok(expr);
+        }
+        // Refer to renamed columns with their name in the file, which is used
+        // to look up the statistics of the column, for example for pruning
+        expr.transform(|expr| {
+            if let Some(column) = expr.downcast_ref::<Column>()
+                && let Some(file_name) = self.file_names.get(column.name())
+            {
+                return Ok(Transformed::yes(Arc::new(Column::new(
+                    file_name,
+                    column.index(),
+                )) as _));
+            }
+            Ok(Transformed::no(expr))
+        })
+        .data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::DefaultPhysicalExprAdapterFactory;
+    use arrow::array::{Int32Array, Int64Array, RecordBatch, StringArray};
+    use arrow::datatypes::{DataType, Field};
+    use datafusion_physical_expr::expressions::{Literal, col};
+    use datafusion_physical_expr::utils::collect_columns;
+
+    fn evolved() -> Result<SchemaEvolution> {
+        let mut evolution = SchemaEvolution::new();
+        evolution.rename_column("a", "b")?;
+        evolution.rename_column("b", "c")?;
+        evolution.add_column("d", Some(ScalarValue::Int32(Some(7))))?;
+        evolution.add_column("e", None)?;
+        evolution.drop_column("x");
+        Ok(evolution)
+    }
+
+    #[test]
+    fn test_schema_evolution_records_changes() -> Result<()> {
+        let evolution = evolved()?;
+        assert_eq!(evolution.previous_names("c"), ["a", "b"]);
+        assert!(evolution.previous_names("b").is_empty());
+        assert_eq!(
+            evolution.default_value("d"),
+            Some(&ScalarValue::Int32(Some(7)))
+        );
+        assert_eq!(evolution.default_value("e"), None);
+        assert!(evolution.is_evolved("c"));
+        assert!(evolution.is_evolved("d"));
+        assert!(!evolution.is_evolved("e"));
+
+        for name in ["a", "b", "x"] {
+            let err = SchemaEvolution::clone(&evolution)
+                .add_column(name, None)
+                .unwrap_err();
+            assert!(err.to_string().contains("cannot be reused"), "{err}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_evolution_metadata_round_trip() -> Result<()> {
+        let evolution = evolved()?;
+        let schema = Schema::new(vec![
+            Field::new("c", DataType::Int64, true),
+            Field::new("d", DataType::Int32, true),
+            Field::new("e", DataType::Utf8, true),
+        ]);
+        assert_eq!(
+            SchemaEvolution::try_from_schema_metadata(&schema)?,
+            SchemaEvolution::new()
+        );
+
+        let schema = schema.with_metadata(HashMap::from([(
+            SCHEMA_EVOLUTION_METADATA_KEY.to_string(),
+            evolution.to_metadata_value()?,
+        )]));
+        assert_eq!(
+            SchemaEvolution::try_from_schema_metadata(&schema)?,
+            evolution
+        );
+
+        // A default value that cannot be restored from a string is rejected
+        let mut evolution = SchemaEvolution::new();
+        evolution.add_column("f", Some(ScalarValue::Binary(Some(vec![0xff, 0xfe]))))?;
+        let err = evolution.to_metadata_value().unwrap_err();
+        assert!(err.to_string().contains("cannot be stored"), "{err}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_evolution_adapter() -> Result<()> {
+        let factory = SchemaEvolutionAdapterFactory::new(
+            Arc::new(evolved()?),
+            Arc::new(DefaultPhysicalExprAdapterFactory),
+        );
+        let logical_schema = Arc::new(Schema::new(vec![
+            Field::new("c", DataType::Int64, true),
+            Field::new("d", DataType::Int64, true),
+            Field::new("e", DataType::Utf8, true),
+        ]));
+        // A file written before all changes
+        let file_schema = Arc::new(Schema::new(vec![
+            Field::new("x", DataType::Utf8, true),
+            Field::new("a", DataType::Int32, true),
+        ]));
+        let adapter =
+            factory.create(Arc::clone(&logical_schema), Arc::clone(&file_schema))?;
+
+        let batch = RecordBatch::try_new(
+            file_schema,
+            vec![
+                Arc::new(StringArray::from(vec!["dropped", "dropped"])),
+                Arc::new(Int32Array::from(vec![1, 2])),
+            ],
+        )?;
+        let c = adapter.rewrite(col("c", &logical_schema)?)?;
+        // The renamed column is referenced by its name in the file
+        assert_eq!(
+            collect_columns(&c),
+            std::iter::once(Column::new("a", 1)).collect()
+        );
+        let c = c.evaluate(&batch)?.into_array(2)?;
+        assert_eq!(c.as_ref(), &Int64Array::from(vec![1, 2]));
+
+        let d = adapter.rewrite(col("d", &logical_schema)?)?;
+        let d = d.downcast_ref::<Literal>().expect("literal");
+        assert_eq!(d.value(), &ScalarValue::Int64(Some(7)));
+
+        let e = adapter.rewrite(col("e", &logical_schema)?)?;
+        let e = e.downcast_ref::<Literal>().expect("literal");
+        assert_eq!(e.value(), &ScalarValue::Utf8(None));
+        Ok(())
+    }
+
+    #[test]
+    fn test_schema_evolution_adapter_current_file() -> Result<()> {
+        let factory = SchemaEvolutionAdapterFactory::new(
+            Arc::new(evolved()?),
+            Arc::new(DefaultPhysicalExprAdapterFactory),
+        );
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("c", DataType::Int32, true),
+            Field::new("d", DataType::Int32, true),
+        ]));
+        // A file written after all changes is read as is
+        let adapter = factory.create(Arc::clone(&schema), Arc::clone(&schema))?;
+        for name in ["c", "d"] {
+            let expr = col(name, &schema)?;
+            let rewritten = adapter.rewrite(Arc::clone(&expr))?;
+            assert_eq!(&rewritten, &expr);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,583 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Cost-based join enumeration for trees of inner hash joins.
+//!
+//! A maximal tree of inner equi-joins (without filters, embedded projections
+//! or fetches, possibly with column projections in between) is flattened into a join graph whose vertices are the join
+//! inputs and whose edges are the equi-join key pairs. The cheapest join
+//! order for the graph is then searched for:
+//!
+//! - Graphs with at most `datafusion.optimizer.join_enumeration_dp_threshold`
+//!   relations are enumerated exhaustively with dynamic programming over
+//!   connected subgraphs. For binary join edges this considers the same
+//!   connected subgraph / complement pairs as DPhyp, so no cross products
+//!   are introduced.
+//! - Larger graphs use greedy operator ordering, which repeatedly joins the
+//!   two connected components with the smallest estimated result.
+//!
+//! Cardinalities are estimated with the [`StatisticsRegistry`]: statistics
+//! of the join inputs are computed once, and the statistics of every
+//! candidate join are derived from the statistics of its two inputs (by
+//! default with the [`JoinStatisticsProvider`]). The cost of a join tree is
+//! the sum of the estimated output rows of all its joins (`C_out`).
+//!
+//! The new order is only used if its estimated cost is lower than the cost of
+//! the order written in the query. The chosen estimates are recorded on the
+//! rebuilt [`HashJoinExec`]s so they are visible in `EXPLAIN`.
+//!
+//! [`JoinStatisticsProvider`]: datafusion_physical_plan::operator_statistics::JoinStatisticsProvider
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::datatypes::Schema;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::tree_node::{
+    Transformed, TransformedResult, TreeNode, TreeNodeRecursion,
+};
+use datafusion_common::{JoinType, NullEquality, Result};
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_plan::ExecutionPlan;
+use datafusion_physical_plan::execution_plan::replace_children_if_necessary;
+use datafusion_physical_plan::joins::utils::JoinCostEstimate;
+use datafusion_physical_plan::joins::{HashJoinExec, HashJoinExecBuilder, PartitionMode};
+use datafusion_physical_plan::operator_statistics::{
+    ExtendedStatistics, StatisticsRegistry,
+};
+use datafusion_physical_plan::projection::{ProjectionExec, ProjectionExpr};
+
+/// The maximum number of relations of a reordered join tree, which are
+/// tracked in a 64 bit set
+const MAX_RELATIONS: usize = 64;
+
+/// Reorders all trees of inner hash joins in `plan` with at least three
+/// inputs, using `registry` to estimate their cost.
+pub(crate) fn enumerate_join_orders(
+    plan: Arc<dyn ExecutionPlan>,
+    config: &ConfigOptions,
+    registry: &StatisticsRegistry,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let enumerator = JoinEnumerator {
+        registry,
+        dp_threshold: config.optimizer.join_enumeration_dp_threshold.get(),
+    };
+    enumerator.optimize(plan)
+}
+
+/// A column of one of the relations of a join graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RelationColumn {
+    relation: usize,
+    index: usize,
+}
+
+/// An equi-join key pair between two relations of a join graph
+#[derive(Debug, Clone, Copy)]
+struct JoinEdge {
+    left: RelationColumn,
+    right: RelationColumn,
+}
+
+/// The inputs and equi-join conditions of a tree of inner hash joins
+struct JoinGraph {
+    relations: Vec<Arc<dyn ExecutionPlan>>,
+    /// Single relation join trees, `None` if the row count is unknown
+    leaves: Vec<Option<JoinTree>>,
+    edges: Vec<JoinEdge>,
+    mode: PartitionMode,
+}
+
+impl JoinGraph {
+    /// Returns the set of relations that are connected to `set` by an edge
+    fn neighbors(&self, set: u64) -> u64 {
+        self.edges.iter().fold(0, |neighbors, edge| {
+            let left = 1 << edge.left.relation;
+            let right = 1 << edge.right.relation;
+            if set & left != 0 {
+                neighbors | right
+            } else if set & right != 0 {
+                neighbors | left
+            } else {
+                neighbors
+            }
+        }) & !set
+    }
+}
+
+/// A (partial) join tree over a set of relations of a join graph
+#[derive(Clone)]
+struct JoinTree {
+    plan: Arc<dyn ExecutionPlan>,
+    /// Bitset of the relations covered by this tree
+    relations: u64,
+    /// The relation column of every output column of `plan`
+    columns: Vec<RelationColumn>,
+    stats: ExtendedStatistics,
+    rows: usize,
+    cost: usize,
+}
+
+impl JoinTree {
+    /// Returns true if this tree is estimated to be larger than `other`,
+    /// comparing in-memory sizes if both are known and row counts otherwise
+    fn is_larger_than(&self, other: &JoinTree) -> bool {
+        match (
+            self.stats.base().total_byte_size.get_value(),
+            other.stats.base().total_byte_size.get_value(),
+        ) {
+            (Some(size), Some(other_size)) => size > other_size,
+            _ => self.rows > other.rows,
+        }
+    }
+
+    fn column(&self, column: RelationColumn) -> Option<Arc<dyn PhysicalExpr>> {
+        let index = self.columns.iter().position(|c| *c == column)?;
+        let schema = self.plan.schema();
+        let name = schema.field(index).name();
+        Some(Arc::new(Column::new(name, index)))
+    }
+}
+
+struct JoinEnumerator<'a> {
+    registry: &'a StatisticsRegistry,
+    dp_threshold: usize,
+}
+
+impl JoinEnumerator<'_> {
+    fn optimize(&self, plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(|plan| {
+            if !is_reorderable(plan.as_ref()) {
+                return Ok(Transformed::no(plan));
+            }
+            // Reorder the whole join tree at once. Its inputs are optimized
+            // recursively, so there is no need to visit the children again.
+            let new_plan = self.reorder(Arc::clone(&plan))?;
+            Ok(Transformed::new(new_plan, true, TreeNodeRecursion::Jump))
+        })
+        .data()
+    }
+
+    /// Reorders the join tree rooted at `plan`, returning it with reordered
+    /// inputs only if no cheaper order is found
+    fn reorder(&self, plan: Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>> {
+        let Some(hash_join) = plan.downcast_ref::<HashJoinExec>() else {
+            return Ok(plan);
+        };
+        let mut graph = JoinGraph {
+            relations: vec![],
+            leaves: vec![],
+            edges: vec![],
+            mode: hash_join.mode,
+        };
+        let original = self.flatten(&plan, &mut graph)?;
+        let leaves = std::mem::take(&mut graph.leaves)
+            .into_iter()
+            .collect::<Option<Vec<_>>>();
+        let (Some(original), Some(leaves), 3..MAX_RELATIONS) =
+            (original, leaves, graph.relations.len())
+        else {
+            return self.rebuild_with_optimized_inputs(&plan, &graph);
+        };
+
+        let best = if graph.relations.len() <= self.dp_threshold {
+            self.dynamic_programming(&graph, leaves)?
+        } else {
+            self.greedy(&graph, leaves)?
+        };
+        match best {
+            Some(best) if best.cost < original.cost => {
+                restore_column_order(best, &original.columns, &plan.schema())
+            }
+            _ => self.rebuild_with_optimized_inputs(&plan, &graph),
+        }
+    }
+
+    /// Flattens the join tree rooted at `plan` into `graph`, optimizing its
+    /// inputs along the way. Returns the tree with the estimated cost of the
+    /// original join order, or `None` if statistics are unavailable.
+    fn flatten(
+        &self,
+        plan: &Arc<dyn ExecutionPlan>,
+        graph: &mut JoinGraph,
+    ) -> Result<Option<JoinTree>> {
+        if !is_join_tree_node(plan.as_ref()) {
+            let relation = graph.relations.len();
+            let input = self.optimize(Arc::clone(plan))?;
+            // Larger join trees are not reordered, but their inputs are still
+            // collected to be rebuilt
+            let leaf = if relation < MAX_RELATIONS {
+                self.leaf(relation, &input)?
+            } else {
+                None
+            };
+            graph.relations.push(input);
+            graph.leaves.push(leaf.clone());
+            return Ok(leaf);
+        }
+
+        if let Some(projection) = plan.downcast_ref::<ProjectionExec>() {
+            let Some(input) = self.flatten(projection.input(), graph)? else {
+                return Ok(None);
+            };
+            let columns = projection
+                .expr()
+                .iter()
+                .filter_map(|expr| expr.expr.downcast_ref::<Column>())
+                .map(|column| input.columns[column.index()])
+                .collect();
+            let stats = self.registry.compute_with_child_stats(
+                plan.as_ref(),
+                std::slice::from_ref(&input.stats),
+            )?;
+            return Ok(Some(JoinTree {
+                plan: Arc::clone(plan),
+                columns,
+                stats,
+                ..input
+            }));
+        }
+
+        let Some(hash_join) = plan.downcast_ref::<HashJoinExec>() else {
+            return Ok(None);
+        };
+        let left = self.flatten(hash_join.left(), graph)?;
+        let right = self.flatten(hash_join.right(), graph)?;
+        let (Some(left), Some(right)) = (left, right) else {
+            return Ok(None);
+        };
+        for (left_key, right_key) in hash_join.on() {
+            let (Some(left_key), Some(right_key)) = (
+                left_key.downcast_ref::<Column>(),
+                right_key.downcast_ref::<Column>(),
+            ) else {
+                return Ok(None);
+            };
+            graph.edges.push(JoinEdge {
+                left: left.columns[left_key.index()],
+                right: right.columns[right_key.index()],
+            });
+        }
+        let stats = self.registry.compute_with_child_stats(
+            plan.as_ref(),
+            &[left.stats.clone(), right.stats.clone()],
+        )?;
+        Ok(Some(join_tree(Arc::clone(plan), left, right, stats)))
+    }
+
+    /// Rebuilds the join tree rooted at `plan` in its original order, with the
+    /// optimized inputs collected in `graph`
+    fn rebuild_with_optimized_inputs(
+        &self,
+        plan: &Arc<dyn ExecutionPlan>,
+        graph: &JoinGraph,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        fn rebuild(
+            plan: &Arc<dyn ExecutionPlan>,
+            relations: &mut impl Iterator<Item = Arc<dyn ExecutionPlan>>,
+        ) -> Result<Arc<dyn ExecutionPlan>> {
+            if !is_join_tree_node(plan.as_ref()) {
+                return Ok(relations.next().unwrap_or_else(|| Arc::clone(plan)));
+            }
+            let children = plan
+                .children()
+                .into_iter()
+                .map(|child| rebuild(child, relations))
+                .collect::<Result<Vec<_>>>()?;
+            replace_children_if_necessary(Arc::clone(plan), children)
+        }
+        rebuild(plan, &mut graph.relations.iter().cloned())
+    }
+
+    /// Returns the join tree of a single relation, or `None` if its row count
+    /// is unknown
+    fn leaf(
+        &self,
+        relation: usize,
+        plan: &Arc<dyn ExecutionPlan>,
+    ) -> Result<Option<JoinTree>> {
+        let stats = self.registry.compute(plan.as_ref())?;
+        let Some(&rows) = stats.base().num_rows.get_value() else {
+            return Ok(None);
+        };
+        let columns = (0..plan.schema().fields().len())
+            .map(|index| RelationColumn { relation, index })
+            .collect();
+        Ok(Some(JoinTree {
+            plan: Arc::clone(plan),
+            relations: 1 << relation,
+            columns,
+            stats,
+            rows,
+            cost: 0,
+        }))
+    }
+
+    /// Joins two disjoint join trees on all edges between them, placing the
+    /// smaller one on the build side. Returns `None` if they are not connected.
+    fn join(
+        &self,
+        graph: &JoinGraph,
+        left: &JoinTree,
+        right: &JoinTree,
+    ) -> Result<Option<JoinTree>> {
+        let (left, right) = if left.is_larger_than(right) {
+            (right, left)
+        } else {
+            (left, right)
+        };
+        let mut on = vec![];
+        for edge in &graph.edges {
+            let (left_column, right_column) = if left.columns.contains(&edge.left)
+                && right.columns.contains(&edge.right)
+            {
+                (edge.left, edge.right)
+            } else if left.columns.contains(&edge.right)
+                && right.columns.contains(&edge.left)
+            {
+                (edge.right, edge.left)
+            } else {
+                continue;
+            };
+            let (Some(left_key), Some(right_key)) =
+                (left.column(left_column), right.column(right_column))
+            else {
+                continue;
+            };
+            on.push((left_key, right_key));
+        }
+        if on.is_empty() {
+            return Ok(None);
+        }
+
+        let plan = HashJoinExecBuilder::new(
+            Arc::clone(&left.plan),
+            Arc::clone(&right.plan),
+            on,
+            JoinType::Inner,
+        )
+        .with_partition_mode(graph.mode)
+        .build_exec()?;
+        let stats = self.registry.compute_with_child_stats(
+            plan.as_ref(),
+            &[left.stats.clone(), right.stats.clone()],
+        )?;
+        let tree = join_tree(plan, left.clone(), right.clone(), stats);
+        let estimate = JoinCostEstimate {
+            rows: tree.rows,
+            cost: tree.cost,
+        };
+        let Some(hash_join) = tree.plan.downcast_ref::<HashJoinExec>() else {
+            return Ok(Some(tree));
+        };
+        let plan = hash_join
+            .builder()
+            .with_cost_estimate(Some(estimate))
+            .build_exec()?;
+        Ok(Some(JoinTree { plan, ..tree }))
+    }
+
+    /// Finds the cheapest join tree with dynamic programming over the
+    /// connected subgraphs of `graph`, in order of increasing size
+    fn dynamic_programming(
+        &self,
+        graph: &JoinGraph,
+        leaves: Vec<JoinTree>,
+    ) -> Result<Option<JoinTree>> {
+        let num_relations = graph.relations.len();
+        let mut best: HashMap<u64, JoinTree> = leaves
+            .into_iter()
+            .map(|leaf| (leaf.relations, leaf))
+            .collect();
+
+        let mut sets = (1..1u64 << num_relations)
+            .filter(|set| set.count_ones() > 1)
+            .collect::<Vec<_>>();
+        sets.sort_by_key(|set| set.count_ones());
+
+        for set in sets {
+            // Enumerate every split of `set` into two connected, adjacent
+            // subsets once, by requiring the first to hold the lowest relation
+            let lowest = set & set.wrapping_neg();
+            let mut best_split: Option<(u64, u64, usize)> = None;
+            let mut subset = (set - 1) & set;
+            while subset != 0 {
+                let complement = set & !subset;
+                if subset & lowest != 0
+                    && let (Some(left), Some(right)) =
+                        (best.get(&subset), best.get(&complement))
+                    && graph.neighbors(subset) & complement != 0
+                {
+                    let cost = left.cost.saturating_add(right.cost);
+                    if best_split.is_none_or(|(_, _, best_cost)| cost < best_cost) {
+                        best_split = Some((subset, complement, cost));
+                    }
+                }
+                subset = (subset - 1) & set;
+            }
+            if let Some((left, right, _)) = best_split
+                && let Some(tree) = self.join(graph, &best[&left], &best[&right])?
+            {
+                best.insert(set, tree);
+            }
+        }
+        Ok(best.remove(&((1u64 << (num_relations - 1) << 1).wrapping_sub(1))))
+    }
+
+    /// Builds a join tree greedily by repeatedly joining the two connected
+    /// trees with the smallest estimated result
+    fn greedy(
+        &self,
+        graph: &JoinGraph,
+        leaves: Vec<JoinTree>,
+    ) -> Result<Option<JoinTree>> {
+        let mut trees: Vec<Option<JoinTree>> = leaves.into_iter().map(Some).collect();
+        let mut candidates: HashMap<(usize, usize), JoinTree> = HashMap::new();
+        for i in 0..trees.len() {
+            for j in i + 1..trees.len() {
+                self.add_candidate(graph, &trees, i, j, &mut candidates)?;
+            }
+        }
+
+        while let Some((&(i, j), _)) = candidates
+            .iter()
+            .min_by_key(|((i, j), tree)| (tree.rows, tree.cost, *i, *j))
+        {
+            let tree = candidates.remove(&(i, j));
+            candidates.retain(|&(a, b), _| a != i && a != j && b != i && b != j);
+            trees[i] = None;
+            trees[j] = None;
+            trees.push(tree);
+            let new = trees.len() - 1;
+            for other in 0..new {
+                self.add_candidate(graph, &trees, other, new, &mut candidates)?;
+            }
+        }
+
+        let mut remaining = trees.into_iter().flatten();
+        match (remaining.next(), remaining.next()) {
+            (Some(tree), None) => Ok(Some(tree)),
+            // The graph is not connected
+            _ => Ok(None),
+        }
+    }
+
+    fn add_candidate(
+        &self,
+        graph: &JoinGraph,
+        trees: &[Option<JoinTree>],
+        i: usize,
+        j: usize,
+        candidates: &mut HashMap<(usize, usize), JoinTree>,
+    ) -> Result<()> {
+        if let (Some(left), Some(right)) = (&trees[i], &trees[j])
+            && graph.neighbors(left.relations) & right.relations != 0
+            && let Some(tree) = self.join(graph, left, right)?
+        {
+            candidates.insert((i, j), tree);
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if `plan` is a join that can be reordered with its inputs
+fn is_reorderable(plan: &dyn ExecutionPlan) -> bool {
+    plan.downcast_ref::<HashJoinExec>()
+        .is_some_and(|hash_join| {
+            *hash_join.join_type() == JoinType::Inner
+                && hash_join.filter().is_none()
+                && !hash_join.contains_projection()
+                && hash_join.fetch().is_none()
+                && !hash_join.null_aware
+                && hash_join.null_equality() == NullEquality::NullEqualsNothing
+                && hash_join.on().iter().all(|(left, right)| {
+                    left.downcast_ref::<Column>().is_some()
+                        && right.downcast_ref::<Column>().is_some()
+                })
+        })
+}
+
+/// Returns true if `plan` is part of a tree of reorderable joins: either a
+/// reorderable join, or a projection of columns on top of one
+fn is_join_tree_node(plan: &dyn ExecutionPlan) -> bool {
+    if let Some(projection) = plan.downcast_ref::<ProjectionExec>() {
+        projection
+            .expr()
+            .iter()
+            .all(|expr| expr.expr.downcast_ref::<Column>().is_some())
+            && is_join_tree_node(projection.input().as_ref())
+    } else {
+        is_reorderable(plan)
+    }
+}
+
+/// Combines two join trees under `plan`, which joins them
+fn join_tree(
+    plan: Arc<dyn ExecutionPlan>,
+    left: JoinTree,
+    right: JoinTree,
+    stats: ExtendedStatistics,
+) -> JoinTree {
+    let rows = stats
+        .base()
+        .num_rows
+        .get_value()
+        .copied()
+        .unwrap_or_else(|| left.rows.saturating_mul(right.rows));
+    let cost = rows.saturating_add(left.cost).saturating_add(right.cost);
+    let mut columns = left.columns;
+    columns.extend(right.columns);
+    JoinTree {
+        plan,
+        relations: left.relations | right.relations,
+        columns,
+        stats,
+        rows,
+        cost,
+    }
+}
+
+/// Projects the output of `tree` back to the columns of the original join tree,
+/// whose output schema is `schema`
+fn restore_column_order(
+    tree: JoinTree,
+    columns: &[RelationColumn],
+    schema: &Schema,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let tree_schema = tree.plan.schema();
+    if tree.columns == columns && tree_schema.fields() == schema.fields() {
+        return Ok(tree.plan);
+    }
+    let exprs = columns
+        .iter()
+        .zip(schema.fields())
+        .map(|(column, field)| {
+            let index = tree
+                .columns
+                .iter()
+                .position(|c| c == column)
+                .expect("join trees cover the same relations");
+            let name = tree_schema.field(index).name();
+            ProjectionExpr {
+                expr: Arc::new(Column::new(name, index)) as Arc<dyn PhysicalExpr>,
+                alias: field.name().to_owned(),
+            }
+        })
+        .collect::<Vec<_>>();
+    Ok(Arc::new(ProjectionExec::try_new(exprs, tree.plan)?))
+}
@@ -22,8 +22,11 @@
 //! into a runnable query by replacing pipeline-breaking join operations with
 //! pipeline-friendly ones. To achieve the second goal, it selects the proper
 //! `PartitionMode` and the build side using the available statistics for hash joins.
+//! When `datafusion.optimizer.enable_join_enumeration` is set, it first reorders
+//! trees of inner hash joins using cost-based join enumeration.
 
 use crate::PhysicalOptimizerRule;
+use crate::join_enumeration::enumerate_join_orders;
 use crate::optimizer::{ConfigOnlyContext, PhysicalOptimizerContext};
 use datafusion_common::Statistics;
 use datafusion_common::config::ConfigOptions;
@@ -153,9 +156,13 @@ impl PhysicalOptimizerRule for JoinSelection {
         context: &dyn PhysicalOptimizerContext,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         let config = context.config_options();
+        // Join enumeration needs cross-operator estimates for the intermediate
+        // joins, so it always uses a registry, also for the join swaps below
+        let enumerate_joins =
+            config.optimizer.join_reordering && config.optimizer.enable_join_enumeration;
         let mut default_registry = None;
         let registry: Option<&StatisticsRegistry> =
-            if config.optimizer.use_statistics_registry {
+            if config.optimizer.use_statistics_registry || enumerate_joins {
                 Some(context.statistics_registry().unwrap_or_else(|| {
                     default_registry
                         .insert(StatisticsRegistry::default_with_builtin_providers())
@@ -163,6 +170,12 @@ impl PhysicalOptimizerRule for JoinSelection {
             } else {
                 None
             };
+        let plan = match registry {
+            Some(registry) if enumerate_joins => {
+                enumerate_join_orders(plan, config, registry)?
+            }
+            _ => plan,
+        };
         let subrules: Vec<Box<PipelineFixerSubrule>> = vec![
             Box::new(hash_join_convert_symmetric_subrule),
             Box::new(hash_join_swap_subrule),
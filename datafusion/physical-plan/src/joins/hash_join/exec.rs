@@ -15,6 +15,7 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::any::Any;
 use std::collections::HashSet;
 use std::fmt;
 use std::mem::size_of;
@@ -23,7 +24,7 @@ use std::sync::{Arc, OnceLock};
 use std::vec;
 
 use crate::execution_plan::{
-    EmissionType, boundedness_from_children, has_same_children_properties,
+    EmissionType, boundedness_from_children, displayable, has_same_children_properties,
     plan_contains_expression_id, stub_properties,
 };
 use crate::filter_pushdown::{
@@ -32,6 +33,7 @@ use crate::filter_pushdown::{
 };
 use crate::joins::Map;
 use crate::joins::array_map::ArrayMap;
+use crate::joins::hash_join::grace::GraceHashJoin;
 use crate::joins::hash_join::inlist_builder::build_struct_inlist_values;
 use crate::joins::hash_join::shared_bounds::{
     ColumnBounds, PartitionBounds, PushdownStrategy, SharedBuildAccumulator,
@@ -45,12 +47,13 @@ use crate::joins::utils::{
     swap_join_projection, update_hash,
 };
 use crate::joins::{JoinOn, JoinOnRef, PartitionMode, SharedBitmapBuilder};
-use crate::metrics::{Count, MetricBuilder, MetricCategory};
+use crate::metrics::{Count, MetricBuilder, MetricCategory, SpillMetrics};
 use crate::projection::{
     EmbeddedProjection, JoinData, ProjectionExec, try_embed_projection,
     try_pushdown_through_join_with_column_indices,
 };
 use crate::repartition::REPARTITION_RANDOM_STATE;
+use crate::spill::SpillManager;
 use crate::statistics::{ChildStats, StatisticsArgs};
 use crate::{
     ChildrenPropertiesMode, ExecutionPlanProperties, ReplaceChildrenOptions,
@@ -62,9 +65,10 @@ use crate::{
     SendableRecordBatchStream, Statistics,
     common::can_project,
     joins::utils::{
-        BuildProbeJoinMetrics, ColumnIndex, JoinFilter, JoinHashMapType,
-        build_join_schema, check_join_is_valid, estimate_join_statistics,
-        need_produce_result_in_final, symmetric_join_output_partitioning,
+        BuildProbeJoinMetrics, ColumnIndex, JoinCostEstimate, JoinFilter,
+        JoinHashMapType, build_join_schema, check_join_is_valid,
+        estimate_join_statistics, need_produce_result_in_final,
+        symmetric_join_output_partitioning,
     },
     metrics::{ExecutionPlanMetricsSet, MetricsSet},
 };
@@ -83,6 +87,9 @@ use datafusion_common::{
     plan_err, project_schema,
 };
 use datafusion_execution::TaskContext;
+use datafusion_execution::cache::cache_manager::{
+    CachedJoinBuildSide, JoinBuildSide, JoinBuildSideCache, JoinBuildSideCacheKey,
+};
 use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_expr::Accumulator;
 use datafusion_functions_aggregate_common::min_max::{MaxAccumulator, MinAccumulator};
@@ -96,7 +103,8 @@ use datafusion_physical_expr::{PhysicalExpr, PhysicalExprRef};
 use datafusion_common::hash_utils::RandomState;
 use datafusion_physical_expr_common::physical_expr::fmt_sql;
 use datafusion_physical_expr_common::utils::evaluate_expressions_to_arrays;
-use futures::TryStreamExt;
+use futures::{FutureExt, TryFutureExt, TryStreamExt, future};
+use object_store::ObjectMeta;
 use parking_lot::Mutex;
 
 use super::partitioned_hash_eval::SeededRandomState;
@@ -107,6 +115,10 @@ pub(crate) const HASH_JOIN_SEED: SeededRandomState =
 
 const ARRAY_MAP_CREATED_COUNT_METRIC_NAME: &str = "array_map_created_count";
 
+const BUILD_SIDE_CACHE_HITS_METRIC_NAME: &str = "build_side_cache_hits";
+
+const BUILD_SIDE_CACHE_MISSES_METRIC_NAME: &str = "build_side_cache_misses";
+
 #[expect(clippy::too_many_arguments)]
 fn try_create_array_map(
     bounds: &Option<PartitionBounds>,
@@ -208,7 +220,7 @@ pub(super) struct JoinLeftData {
     /// Without holding onto this reservation, the recorded memory usage would become inconsistent with actual usage.
     /// This could hide potential out-of-memory issues, especially when upstream operators increase their memory consumption.
     /// The MemoryReservation ensures proper tracking of memory resources throughout the join operation's lifecycle.
-    _reservation: MemoryReservation,
+    reservation: MemoryReservation,
     /// Bounds computed from the build side for dynamic filter pushdown.
     /// If the partition is empty (no rows) this will be None.
     /// If the partition has some rows this will be Some with the bounds for each join key column.
@@ -274,6 +286,107 @@ impl JoinLeftData {
     pub(super) fn report_probe_completed(&self) -> bool {
         self.probe_threads_counter.fetch_sub(1, Ordering::Relaxed) == 1
     }
+
+    /// Returns the parts of this build side that can be shared with other
+    /// executions of the join.
+    fn to_shared(&self) -> SharedJoinLeftData {
+        SharedJoinLeftData {
+            map: Arc::clone(&self.map),
+            batch: self.batch.clone(),
+            values: self.values.clone(),
+            bounds: self.bounds.clone(),
+            membership: self.membership.clone(),
+            build_side_has_null: self.build_side_has_null,
+            memory_size: self.reservation.size(),
+        }
+    }
+
+    /// Creates the build side of an execution of the join from a build side
+    /// shared by a previous execution.
+    fn try_from_shared(
+        shared: &SharedJoinLeftData,
+        mut reservation: MemoryReservation,
+        with_visited_indices_bitmap: bool,
+        probe_threads_count: usize,
+        metrics: &BuildProbeJoinMetrics,
+    ) -> Result<Self> {
+        let visited_indices_bitmap = new_visited_indices_bitmap(
+            shared.batch.num_rows(),
+            with_visited_indices_bitmap,
+            &mut reservation,
+            metrics,
+        )?;
+
+        Ok(Self {
+            map: Arc::clone(&shared.map),
+            batch: shared.batch.clone(),
+            values: shared.values.clone(),
+            visited_indices_bitmap: Mutex::new(visited_indices_bitmap),
+            probe_threads_counter: AtomicUsize::new(probe_threads_count),
+            reservation,
+            bounds: shared.bounds.clone(),
+            membership: shared.membership.clone(),
+            probe_side_non_empty: AtomicBool::new(false),
+            probe_side_has_null: AtomicBool::new(false),
+            build_side_has_null: shared.build_side_has_null,
+        })
+    }
+}
+
+/// The parts of a [`JoinLeftData`] that are not modified while probing, stored
+/// in the [`JoinBuildSideCache`] so that later executions of a
+/// [`PartitionMode::CollectLeft`] join over the same build side can skip
+/// building it.
+struct SharedJoinLeftData {
+    map: Arc<Map>,
+    batch: RecordBatch,
+    values: Vec<ArrayRef>,
+    bounds: Option<PartitionBounds>,
+    membership: PushdownStrategy,
+    build_side_has_null: bool,
+    /// Memory reserved while building the build side
+    memory_size: usize,
+}
+
+impl JoinBuildSide for SharedJoinLeftData {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn memory_size(&self) -> usize {
+        self.memory_size
+    }
+}
+
+/// Lookup of the build side of a [`HashJoinExec`] in the [`JoinBuildSideCache`]
+/// of the runtime, see [`HashJoinExec::build_side_snapshot`].
+struct BuildSideCacheLookup {
+    cache: Arc<JoinBuildSideCache>,
+    key: JoinBuildSideCacheKey,
+    hits: Count,
+    misses: Count,
+}
+
+impl BuildSideCacheLookup {
+    /// Returns the cached build side, if any.
+    fn get(&self) -> Option<CachedJoinBuildSide> {
+        let cached = self
+            .cache
+            .get(&self.key)
+            .filter(|cached| cached.build_side.as_any().is::<SharedJoinLeftData>());
+        if cached.is_some() {
+            self.hits.add(1);
+        } else {
+            self.misses.add(1);
+        }
+        cached
+    }
+
+    /// Stores `left_data` in the cache.
+    fn put(&self, left_data: &JoinLeftData) {
+        let shared = Arc::new(left_data.to_shared());
+        self.cache.put(&self.key, CachedJoinBuildSide::new(shared));
+    }
 }
 
 /// Helps to build [`HashJoinExec`].
@@ -318,6 +431,8 @@ impl HashJoinExecBuilder {
                 null_equality: NullEquality::NullEqualsNothing,
                 null_aware: false,
                 dynamic_filter: None,
+                cost_estimate: None,
+                build_side_snapshot: None,
                 // Will be computed at when plan will be built.
                 cache: stub_properties(),
                 join_schema: Arc::new(Schema::empty()),
@@ -385,6 +500,20 @@ impl HashJoinExecBuilder {
         self
     }
 
+    /// Set the cost estimate recorded by the join enumeration rule.
+    pub fn with_cost_estimate(mut self, cost_estimate: Option<JoinCostEstimate>) -> Self {
+        self.exec.cost_estimate = cost_estimate;
+        self
+    }
+
+    /// Set the versions of the files read by the build side.
+    ///
+    /// See [`HashJoinExec::build_side_snapshot`].
+    pub fn with_build_side_snapshot(mut self, snapshot: Option<Vec<ObjectMeta>>) -> Self {
+        self.exec.build_side_snapshot = snapshot.map(Into::into);
+        self
+    }
+
     /// Require to recompute plan properties.
     pub fn recompute_properties(mut self) -> Self {
         self.preserve_properties = false;
@@ -402,7 +531,12 @@ impl HashJoinExecBuilder {
         );
         self.preserve_properties &= has_same_children_properties(&self.exec, &children)?;
         self.exec.right = children.swap_remove(1);
-        self.exec.left = children.swap_remove(0);
+        let left = children.swap_remove(0);
+        // the snapshot describes the files read by the previous build side
+        if !Arc::ptr_eq(&self.exec.left, &left) {
+            self.exec.build_side_snapshot = None;
+        }
+        self.exec.left = left;
         Ok(self)
     }
 
@@ -472,6 +606,8 @@ impl HashJoinExecBuilder {
             null_aware,
             dynamic_filter,
             fetch,
+            cost_estimate,
+            build_side_snapshot,
             // Recomputed.
             join_schema: _,
             column_indices: _,
@@ -521,6 +657,8 @@ impl HashJoinExecBuilder {
             cache: Arc::new(cache),
             dynamic_filter,
             fetch,
+            cost_estimate,
+            build_side_snapshot,
         })
     }
 
@@ -551,6 +689,8 @@ impl From<&HashJoinExec> for HashJoinExecBuilder {
                 cache: Arc::clone(&exec.cache),
                 dynamic_filter: exec.dynamic_filter.clone(),
                 fetch: exec.fetch,
+                cost_estimate: exec.cost_estimate,
+                build_side_snapshot: exec.build_side_snapshot.clone(),
             },
             preserve_properties: true,
         }
@@ -792,6 +932,12 @@ pub struct HashJoinExec {
     dynamic_filter: Option<HashJoinExecDynamicFilter>,
     /// Maximum number of rows to return
     fetch: Option<usize>,
+    /// Cost estimate of the join order chosen by the join enumeration rule,
+    /// shown in `EXPLAIN` output
+    cost_estimate: Option<JoinCostEstimate>,
+    /// Versions of the files read by the build side, if it only reads files.
+    /// See [`Self::build_side_snapshot`].
+    build_side_snapshot: Option<Arc<[ObjectMeta]>>,
 }
 
 #[derive(Clone)]
@@ -820,6 +966,7 @@ impl fmt::Debug for HashJoinExec {
             .field("column_indices", &self.column_indices)
             .field("null_equality", &self.null_equality)
             .field("cache", &self.cache)
+            .field("build_side_snapshot", &self.build_side_snapshot)
             // Explicitly exclude dynamic_filter to avoid runtime state differences in tests
             .finish()
     }
@@ -947,6 +1094,74 @@ impl HashJoinExec {
         }
     }
 
+    /// Cost estimate recorded by the join enumeration rule, if any
+    pub fn cost_estimate(&self) -> Option<JoinCostEstimate> {
+        self.cost_estimate
+    }
+
+    /// Versions of the files read by the build side, if known.
+    ///
+    /// When set, and the runtime has a [`JoinBuildSideCache`], a
+    /// [`PartitionMode::CollectLeft`] join stores its build side in the cache,
+    /// and later executions of the same join over unchanged files reuse it
+    /// instead of building it again. The snapshot must only be set when the
+    /// output of the build side is fully determined by these files, i.e. it has
+    /// no other leaves, volatile expressions or dynamic filters.
+    ///
+    /// The cache lookups are reported by the `build_side_cache_hits` and
+    /// `build_side_cache_misses` metrics.
+    pub fn build_side_snapshot(&self) -> Option<&[ObjectMeta]> {
+        self.build_side_snapshot.as_deref()
+    }
+
+    /// Returns the lookup of the build side in the [`JoinBuildSideCache`] of
+    /// the runtime, if there is one and the build side has a snapshot.
+    fn build_side_cache_lookup(
+        &self,
+        context: &TaskContext,
+        partition: usize,
+        enable_dynamic_filter_pushdown: bool,
+    ) -> Option<BuildSideCacheLookup> {
+        let sources = self.build_side_snapshot.as_ref()?;
+        let cache = context
+            .runtime_env()
+            .cache_manager
+            .get_join_build_side_cache()?;
+
+        // Everything the built table depends on, besides the files.
+        let options = context.session_config().options();
+        let on_left = self
+            .on
+            .iter()
+            .map(|(left, _)| left.to_string())
+            .collect::<Vec<_>>();
+        let signature = format!(
+            "{}on=[{}], null_equality={:?}, null_aware_right_anti={}, seed={}, \
+             bounds={}, perfect_hash_join=({}, {}), inlist_pushdown=({}, {})",
+            displayable(self.left.as_ref()).indent(true),
+            on_left.join(", "),
+            self.null_equality,
+            self.null_aware && self.join_type == JoinType::RightAnti,
+            self.random_state.seed(),
+            enable_dynamic_filter_pushdown,
+            options.execution.perfect_hash_join_small_build_threshold,
+            options.execution.perfect_hash_join_min_key_density,
+            options.optimizer.hash_join_inlist_pushdown_max_size,
+            options
+                .optimizer
+                .hash_join_inlist_pushdown_max_distinct_values,
+        );
+
+        Some(BuildSideCacheLookup {
+            cache,
+            key: JoinBuildSideCacheKey::new(signature, sources.to_vec()),
+            hits: MetricBuilder::new(&self.metrics)
+                .counter(BUILD_SIDE_CACHE_HITS_METRIC_NAME, partition),
+            misses: MetricBuilder::new(&self.metrics)
+                .counter(BUILD_SIDE_CACHE_MISSES_METRIC_NAME, partition),
+        })
+    }
+
     /// left (build) side which gets hashed
     pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
         &self.left
@@ -1041,6 +1256,92 @@ impl HashJoinExec {
         JoinSide::Right
     }
 
+    /// Returns true if this join falls back to a grace hash join when its
+    /// build side does not fit in memory, see `enable_hash_join_spill`.
+    ///
+    /// Spilling reorders the probe side, so joins that maintain the probe side
+    /// order never spill. A `CollectLeft` build side is shared by all probe
+    /// partitions, so it can only spill when there is a single probe partition.
+    fn can_spill(&self, context: &TaskContext) -> bool {
+        let options = context.session_config().options();
+        let single_probe_stream = match self.mode {
+            PartitionMode::Partitioned => true,
+            PartitionMode::CollectLeft => {
+                self.right.output_partitioning().partition_count() == 1
+            }
+            PartitionMode::Auto => false,
+        };
+
+        options.execution.enable_hash_join_spill
+            && context.runtime_env().disk_manager.tmp_files_enabled()
+            && single_probe_stream
+            && !self.null_aware
+            && self.fetch.is_none()
+            && self.right.output_ordering().is_none()
+    }
+
+    /// Executes `partition` as a grace hash join, see [`GraceHashJoin`].
+    fn execute_with_spill(
+        &self,
+        partition: usize,
+        context: &Arc<TaskContext>,
+        on_left: Vec<PhysicalExprRef>,
+        join_metrics: BuildProbeJoinMetrics,
+        array_map_created_count: Count,
+        build_accumulator: Option<Arc<SharedBuildAccumulator>>,
+    ) -> Result<SendableRecordBatchStream> {
+        let left_partition = match self.mode {
+            PartitionMode::CollectLeft => 0,
+            _ => partition,
+        };
+        let build = self.left.execute(left_partition, Arc::clone(context))?;
+        let probe = self.right.execute(partition, Arc::clone(context))?;
+
+        let spill_metrics = SpillMetrics::new(&self.metrics, partition);
+        let spill_compression = context.session_config().spill_compression();
+        let build_spill_manager = SpillManager::new(
+            context.runtime_env(),
+            spill_metrics.clone(),
+            self.left.schema(),
+        )
+        .with_compression_type(spill_compression);
+        let probe_spill_manager =
+            SpillManager::new(context.runtime_env(), spill_metrics, self.right.schema())
+                .with_compression_type(spill_compression);
+
+        let column_indices = match self.projection.as_ref() {
+            Some(projection) => projection
+                .iter()
+                .map(|i| self.column_indices[*i].clone())
+                .collect(),
+            None => self.column_indices.clone(),
+        };
+        let options = Arc::clone(context.session_config().options());
+
+        let grace = GraceHashJoin {
+            partition,
+            schema: self.schema(),
+            on_left,
+            on_right: self.on.iter().map(|(_, r)| Arc::clone(r)).collect(),
+            filter: self.filter.clone(),
+            join_type: self.join_type,
+            mode: self.mode,
+            random_state: self.random_state.random_state().clone(),
+            join_metrics,
+            column_indices,
+            null_equality: self.null_equality,
+            batch_size: context.session_config().batch_size(),
+            num_partitions: options.execution.hash_join_spill_partitions.get(),
+            config: options,
+            array_map_created_count,
+            memory_pool: Arc::clone(context.memory_pool()),
+            build_spill_manager,
+            probe_spill_manager,
+        };
+
+        Ok(Arc::new(grace).execute(build, probe, build_accumulator, 0))
+    }
+
     /// Return whether the join contains a projection
     pub fn contains_projection(&self) -> bool {
         self.projection.is_some()
@@ -1239,6 +1540,9 @@ impl DisplayAs for HashJoinExec {
                     .map_or_else(String::new, |f| format!(", fetch={f}"));
                 let display_null_aware =
                     if self.null_aware { ", null_aware" } else { "" };
+                let display_cost_estimate = self
+                    .cost_estimate
+                    .map_or_else(String::new, |estimate| format!(", {estimate}"));
                 let on = self
                     .on
                     .iter()
@@ -1247,7 +1551,7 @@ impl DisplayAs for HashJoinExec {
                     .join(", ");
                 write!(
                     f,
-                    "HashJoinExec: mode={:?}, join_type={:?}, on=[{}]{}{}{}{}{}",
+                    "HashJoinExec: mode={:?}, join_type={:?}, on=[{}]{}{}{}{}{}{}",
                     self.mode,
                     self.join_type,
                     on,
@@ -1256,6 +1560,7 @@ impl DisplayAs for HashJoinExec {
                     display_null_equality,
                     display_fetch,
                     display_null_aware,
+                    display_cost_estimate,
                 )
             }
             DisplayFormatType::TreeRender => {
@@ -1499,27 +1804,71 @@ impl ExecutionPlan for HashJoinExec {
             .flatten()
             .flatten();
 
+        if self.can_spill(&context) {
+            return self.execute_with_spill(
+                partition,
+                &context,
+                on_left,
+                join_metrics,
+                array_map_created_count,
+                build_accumulator,
+            );
+        }
+
         let left_fut = match self.mode {
             PartitionMode::CollectLeft => self.left_fut.try_once(|| {
-                let left_stream = self.left.execute(0, Arc::clone(&context))?;
-
                 let reservation =
                     MemoryConsumer::new("HashJoinInput").register(context.memory_pool());
+                let with_visited_indices_bitmap =
+                    need_produce_result_in_final(self.join_type);
+                let probe_threads_count =
+                    self.right().output_partitioning().partition_count();
+
+                let build_side_cache = self.build_side_cache_lookup(
+                    &context,
+                    partition,
+                    enable_dynamic_filter_pushdown,
+                );
+                if let Some(cached) = build_side_cache.as_ref().and_then(|c| c.get())
+                    && let Some(shared) = cached
+                        .build_side
+                        .as_any()
+                        .downcast_ref::<SharedJoinLeftData>()
+                {
+                    let left_data = JoinLeftData::try_from_shared(
+                        shared,
+                        reservation,
+                        with_visited_indices_bitmap,
+                        probe_threads_count,
+                        &join_metrics,
+                    )?;
+                    return Ok(future::ready(Ok(left_data)).boxed());
+                }
 
-                Ok(collect_left_input(
+                let left_stream = self.left.execute(0, Arc::clone(&context))?;
+                let left_fut = collect_left_input(
                     self.random_state.random_state().clone(),
                     left_stream,
                     on_left.clone(),
                     join_metrics.clone(),
                     reservation,
-                    need_produce_result_in_final(self.join_type),
-                    self.right().output_partitioning().partition_count(),
+                    with_visited_indices_bitmap,
+                    probe_threads_count,
                     enable_dynamic_filter_pushdown,
                     Arc::clone(context.session_config().options()),
                     self.null_equality,
                     self.null_aware && self.join_type == JoinType::RightAnti,
                     array_map_created_count,
-                ))
+                );
+                Ok(match build_side_cache {
+                    Some(build_side_cache) => left_fut
+                        .map_ok(move |left_data| {
+                            build_side_cache.put(&left_data);
+                            left_data
+                        })
+                        .boxed(),
+                    None => left_fut.boxed(),
+                })
             })?,
             PartitionMode::Partitioned => {
                 let left_stream = self.left.execute(partition, Arc::clone(&context))?;
@@ -1886,6 +2235,10 @@ impl ExecutionPlan for HashJoinExec {
             column_indices: _,
             // recomputed by the builder on decode
             cache: _,
+            // optimizer estimate shown in EXPLAIN only, not part of the plan
+            cost_estimate: _,
+            // versions of the files read when planning, not part of the plan
+            build_side_snapshot: _,
         } = self;
 
         let left = ctx.encode_child(left)?;
@@ -2566,7 +2919,7 @@ fn should_collect_min_max_for_perfect_hash(
 /// `JoinLeftData` containing the hash map, consolidated batch, join key values,
 /// visited indices bitmap, and computed bounds (if requested).
 #[expect(clippy::too_many_arguments)]
-async fn collect_left_input(
+pub(super) async fn collect_left_input(
     random_state: RandomState,
     left_stream: SendableRecordBatchStream,
     on_left: Vec<PhysicalExprRef>,
@@ -2709,18 +3062,12 @@ async fn collect_left_input(
             (Map::HashMap(hashmap), batch, left_values)
         };
 
-    // Reserve additional memory for visited indices bitmap and create shared builder
-    let visited_indices_bitmap = if with_visited_indices_bitmap {
-        let bitmap_size = bit_util::ceil(batch.num_rows(), 8);
-        reservation.try_grow(bitmap_size)?;
-        metrics.build_mem_used.add(bitmap_size);
-
-        let mut bitmap_buffer = BooleanBufferBuilder::new(batch.num_rows());
-        bitmap_buffer.append_n(num_rows, false);
-        bitmap_buffer
-    } else {
-        BooleanBufferBuilder::new(0)
-    };
+    let visited_indices_bitmap = new_visited_indices_bitmap(
+        num_rows,
+        with_visited_indices_bitmap,
+        &mut reservation,
+        &metrics,
+    )?;
 
     let map = Arc::new(join_hash_map);
 
@@ -2764,7 +3111,7 @@ async fn collect_left_input(
         values: left_values,
         visited_indices_bitmap: Mutex::new(visited_indices_bitmap),
         probe_threads_counter: AtomicUsize::new(probe_threads_count),
-        _reservation: reservation,
+        reservation,
         bounds,
         membership,
         probe_side_non_empty: AtomicBool::new(false),
@@ -2775,6 +3122,27 @@ async fn collect_left_input(
     Ok(data)
 }
 
+/// Creates the visited indices bitmap of a build side with `num_rows` rows,
+/// reserving its memory, or an empty one if `with_visited_indices_bitmap` is
+/// false.
+fn new_visited_indices_bitmap(
+    num_rows: usize,
+    with_visited_indices_bitmap: bool,
+    reservation: &mut MemoryReservation,
+    metrics: &BuildProbeJoinMetrics,
+) -> Result<BooleanBufferBuilder> {
+    if !with_visited_indices_bitmap {
+        return Ok(BooleanBufferBuilder::new(0));
+    }
+    let bitmap_size = bit_util::ceil(num_rows, 8);
+    reservation.try_grow(bitmap_size)?;
+    metrics.build_mem_used.add(bitmap_size);
+
+    let mut bitmap_buffer = BooleanBufferBuilder::new(num_rows);
+    bitmap_buffer.append_n(num_rows, false);
+    Ok(bitmap_buffer)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2832,6 +3200,7 @@ mod tests {
     };
     use arrow::buffer::NullBuffer;
     use arrow::datatypes::{DataType, Field};
+    use datafusion_common::config::ConfigNonZeroUsize;
     use datafusion_common::hash_utils::create_hashes;
     use datafusion_common::test_util::{batches_to_sort_string, batches_to_string};
     use datafusion_common::{
@@ -6476,6 +6845,192 @@ mod tests {
         Ok(())
     }
 
+    /// Runs `join` on partition 0 with the given memory limit and hash join
+    /// spilling enabled, returning the sorted output and the spill count.
+    async fn join_collect_with_spill(
+        join: &HashJoinExec,
+        memory_limit: usize,
+    ) -> Result<(String, usize)> {
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(memory_limit, 1.0)
+            .build_arc()?;
+        let mut session_config = SessionConfig::default().with_batch_size(64);
+        session_config
+            .options_mut()
+            .execution
+            .enable_hash_join_spill = true;
+        session_config
+            .options_mut()
+            .execution
+            .hash_join_spill_partitions = ConfigNonZeroUsize::try_new(8)?;
+        let task_ctx = TaskContext::default()
+            .with_session_config(session_config)
+            .with_runtime(runtime);
+
+        let join = join.builder().reset_state().build_exec()?;
+        let stream = join.execute(0, Arc::new(task_ctx))?;
+        let batches = common::collect(stream).await?;
+        let spill_count = join.metrics().unwrap().spill_count().unwrap_or(0);
+        Ok((batches_to_sort_string(&batches), spill_count))
+    }
+
+    #[tokio::test]
+    async fn join_spills_build_side_under_memory_pressure() -> Result<()> {
+        let n = 2000;
+        let keys: Vec<i32> = (0..n).map(|i| i % 500).collect();
+        let values: Vec<i32> = (0..n).collect();
+        let left = build_table(("a1", &keys), ("b1", &values), ("c1", &values));
+        let right_keys: Vec<i32> = (250..750).collect();
+        let right = build_table(
+            ("a2", &right_keys),
+            ("b2", &right_keys),
+            ("c2", &right_keys),
+        );
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("a2", &right.schema())?) as _,
+        )];
+
+        for join_type in [
+            JoinType::Inner,
+            JoinType::Left,
+            JoinType::Right,
+            JoinType::Full,
+            JoinType::LeftSemi,
+            JoinType::LeftAnti,
+            JoinType::RightSemi,
+            JoinType::RightAnti,
+            JoinType::LeftMark,
+            JoinType::RightMark,
+        ] {
+            let join = join(
+                Arc::clone(&left),
+                Arc::clone(&right),
+                on.clone(),
+                &join_type,
+                NullEquality::NullEqualsNothing,
+            )?;
+
+            let (expected, spill_count) =
+                join_collect_with_spill(&join, usize::MAX).await?;
+            assert_eq!(spill_count, 0, "{join_type} join spilled without pressure");
+
+            let (actual, spill_count) = join_collect_with_spill(&join, 40_000).await?;
+            assert!(spill_count > 0, "{join_type} join did not spill");
+            assert_eq!(expected, actual, "{join_type} join results differ");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_spill_respects_partitioned_mode_and_filter() -> Result<()> {
+        let n = 2000;
+        let keys: Vec<i32> = (0..n).map(|i| i % 300).collect();
+        let values: Vec<i32> = (0..n).collect();
+        let left_batch = build_table_i32(("a1", &keys), ("b1", &values), ("c1", &values));
+        let left: Arc<dyn ExecutionPlan> = TestMemoryExec::try_new_exec(
+            &[vec![left_batch.clone()]],
+            left_batch.schema(),
+            None,
+        )?;
+        let right_keys: Vec<i32> = (0..600).collect();
+        let right_batch = build_table_i32(
+            ("a2", &right_keys),
+            ("b2", &right_keys),
+            ("c2", &right_keys),
+        );
+        let right: Arc<dyn ExecutionPlan> = TestMemoryExec::try_new_exec(
+            &[vec![right_batch.clone()]],
+            right_batch.schema(),
+            None,
+        )?;
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a1", &left_batch.schema())?) as _,
+            Arc::new(Column::new_with_schema("a2", &right_batch.schema())?) as _,
+        )];
+
+        // b1 < b2 + 1000
+        let column_indices = vec![
+            ColumnIndex {
+                index: 1,
+                side: JoinSide::Left,
+            },
+            ColumnIndex {
+                index: 1,
+                side: JoinSide::Right,
+            },
+        ];
+        let intermediate_schema = Schema::new(vec![
+            Field::new("x", DataType::Int32, true),
+            Field::new("x", DataType::Int32, true),
+        ]);
+        let filter_expression = Arc::new(BinaryExpr::new(
+            Arc::new(Column::new("x", 0)),
+            Operator::Lt,
+            Arc::new(BinaryExpr::new(
+                Arc::new(Column::new("x", 1)),
+                Operator::Plus,
+                lit(1000),
+            )),
+        )) as Arc<dyn PhysicalExpr>;
+        let filter = JoinFilter::new(
+            filter_expression,
+            column_indices,
+            Arc::new(intermediate_schema),
+        );
+
+        for join_type in [JoinType::Inner, JoinType::Full, JoinType::LeftAnti] {
+            let join = HashJoinExec::try_new(
+                Arc::clone(&left),
+                Arc::clone(&right),
+                on.clone(),
+                Some(filter.clone()),
+                &join_type,
+                None,
+                PartitionMode::Partitioned,
+                NullEquality::NullEqualsNothing,
+                false,
+            )?;
+
+            let (expected, _) = join_collect_with_spill(&join, usize::MAX).await?;
+            let (actual, spill_count) = join_collect_with_spill(&join, 40_000).await?;
+            assert!(spill_count > 0, "{join_type} join did not spill");
+            assert_eq!(expected, actual, "{join_type} join results differ");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_spill_disabled_by_default() -> Result<()> {
+        let n = 2000;
+        let values: Vec<i32> = (0..n).collect();
+        let left = build_table(("a1", &values), ("b1", &values), ("c1", &values));
+        let right = build_table(("a2", &values), ("b2", &values), ("c2", &values));
+        let on = vec![(
+            Arc::new(Column::new_with_schema("a1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("a2", &right.schema())?) as _,
+        )];
+        let join = join(
+            left,
+            right,
+            on,
+            &JoinType::Inner,
+            NullEquality::NullEqualsNothing,
+        )?;
+
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_limit(16_000, 1.0)
+            .build_arc()?;
+        let task_ctx = Arc::new(TaskContext::default().with_runtime(runtime));
+        let stream = join.execute(0, task_ctx)?;
+        let err = common::collect(stream).await.unwrap_err();
+        assert_contains!(err.to_string(), "Resources exhausted");
+
+        Ok(())
+    }
+
     fn build_table_struct(
         struct_name: &str,
         field_name_and_values: (&str, &Vec<Option<i32>>),
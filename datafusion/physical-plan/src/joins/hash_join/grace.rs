@@ -0,0 +1,344 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Grace hash join: the spilling fallback of [`super::HashJoinExec`].
+//!
+//! When `datafusion.execution.enable_hash_join_spill` is set, the build side is
+//! first buffered under a memory reservation. If it fits (including the
+//! estimated size of the hash table), the join runs exactly like the in-memory
+//! [`HashJoinStream`]. Otherwise both inputs are hash partitioned on their join
+//! keys into spill files, and matching partition pairs are joined one after
+//! another:
+//!
+//! ```text
+//!  build ──► hash(keys) % N ──► build spill 0 .. N-1 ─┐
+//!                                                     ├─► join(build i, probe i) for i in 0..N
+//!  probe ──► hash(keys) % N ──► probe spill 0 .. N-1 ─┘
+//! ```
+//!
+//! Rows with equal join keys always land in the same partition, so each pair
+//! can be joined independently, including outer, semi, anti and mark joins. A
+//! partition whose build side still does not fit is partitioned again with a
+//! different hash seed, up to [`MAX_RECURSION_LEVEL`] times.
+
+use std::mem::size_of;
+use std::sync::Arc;
+
+use arrow::array::UInt32Array;
+use arrow::compute::take_record_batch;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::hash_utils::{RandomState, create_hashes};
+use datafusion_common::utils::memory::{RecordBatchMemoryCounter, estimate_memory_size};
+use datafusion_common::{JoinType, NullEquality, Result};
+use datafusion_execution::SpillFile;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryPool};
+use datafusion_physical_expr::PhysicalExprRef;
+use datafusion_physical_expr_common::utils::evaluate_expressions_to_arrays;
+use futures::{StreamExt, TryStreamExt, stream};
+
+use crate::SendableRecordBatchStream;
+use crate::joins::PartitionMode;
+use crate::joins::hash_join::exec::collect_left_input;
+use crate::joins::hash_join::shared_bounds::SharedBuildAccumulator;
+use crate::joins::hash_join::stream::{
+    BuildSide, BuildSideInitialState, HashJoinStream, HashJoinStreamState,
+};
+use crate::joins::join_hash_map::JoinHashMapU64;
+use crate::joins::utils::{
+    BuildProbeJoinMetrics, ColumnIndex, JoinFilter, OnceFut, need_produce_result_in_final,
+};
+use crate::metrics::Count;
+use crate::spill::SpillManager;
+use crate::spill::in_progress_spill_file::InProgressSpillFile;
+use crate::stream::{EmptyRecordBatchStream, RecordBatchStreamAdapter};
+
+/// Maximum number of times a single partition is re-partitioned before the
+/// join gives up and reports the memory pool error.
+pub(super) const MAX_RECURSION_LEVEL: usize = 3;
+
+/// Base seed for partitioning spilled inputs. It differs from both
+/// `HASH_JOIN_SEED` and `REPARTITION_RANDOM_STATE`, so that the rows of one
+/// `RepartitionExec` output partition are spread over all spill partitions.
+const GRACE_PARTITION_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// State shared by all (recursive) partitions of one grace hash join stream.
+pub(super) struct GraceHashJoin {
+    pub(super) partition: usize,
+    pub(super) schema: SchemaRef,
+    pub(super) on_left: Vec<PhysicalExprRef>,
+    pub(super) on_right: Vec<PhysicalExprRef>,
+    pub(super) filter: Option<JoinFilter>,
+    pub(super) join_type: JoinType,
+    pub(super) mode: PartitionMode,
+    pub(super) random_state: RandomState,
+    pub(super) join_metrics: BuildProbeJoinMetrics,
+    pub(super) column_indices: Vec<ColumnIndex>,
+    pub(super) null_equality: NullEquality,
+    pub(super) batch_size: usize,
+    pub(super) config: Arc<ConfigOptions>,
+    pub(super) array_map_created_count: Count,
+    pub(super) memory_pool: Arc<dyn MemoryPool>,
+    /// Spill manager for the build (left) side
+    pub(super) build_spill_manager: SpillManager,
+    /// Spill manager for the probe (right) side
+    pub(super) probe_spill_manager: SpillManager,
+    /// Number of partitions each input is split into when spilling
+    pub(super) num_partitions: usize,
+}
+
+impl GraceHashJoin {
+    /// Joins `build` with `probe`, spilling both inputs if `build` does not fit
+    /// in memory.
+    ///
+    /// `build_accumulator` is only passed for the top level join. If the build
+    /// side spills, the partition is reported as canceled so that the dynamic
+    /// filter of sibling partitions stays permissive for it.
+    pub(super) fn execute(
+        self: Arc<Self>,
+        build: SendableRecordBatchStream,
+        probe: SendableRecordBatchStream,
+        build_accumulator: Option<Arc<SharedBuildAccumulator>>,
+        level: usize,
+    ) -> SendableRecordBatchStream {
+        let schema = Arc::clone(&self.schema);
+        let stream =
+            stream::once(self.join(build, probe, build_accumulator, level)).try_flatten();
+        Box::pin(RecordBatchStreamAdapter::new(schema, stream))
+    }
+
+    async fn join(
+        self: Arc<Self>,
+        mut build: SendableRecordBatchStream,
+        probe: SendableRecordBatchStream,
+        build_accumulator: Option<Arc<SharedBuildAccumulator>>,
+        level: usize,
+    ) -> Result<SendableRecordBatchStream> {
+        let build_schema = build.schema();
+        let reservation =
+            MemoryConsumer::new(format!("HashJoinInput[{}]", self.partition))
+                .with_can_spill(true)
+                .register(&self.memory_pool);
+        let mut memory_counter = RecordBatchMemoryCounter::new();
+        let mut buffered = vec![];
+        let mut num_rows = 0;
+        let mut fits = true;
+
+        while let Some(batch) = build.next().await {
+            let batch = batch?;
+            num_rows += batch.num_rows();
+            let batch_size = memory_counter.count_batch(&batch);
+            buffered.push(batch);
+            if reservation.try_grow(batch_size).is_err() {
+                fits = false;
+                break;
+            }
+        }
+
+        // The hash table must fit as well, otherwise building it fails later
+        if fits {
+            let estimated_hashtable_size = estimate_memory_size::<(u64, u64)>(
+                num_rows,
+                size_of::<JoinHashMapU64>(),
+            )?;
+            fits = reservation.try_grow(estimated_hashtable_size).is_ok();
+        }
+        // `collect_left_input` reserves the memory again while building
+        reservation.free();
+
+        let buffered = stream::iter(buffered.into_iter().map(Ok));
+        if fits || level >= MAX_RECURSION_LEVEL {
+            let build = Box::pin(RecordBatchStreamAdapter::new(
+                build_schema,
+                buffered.chain(build),
+            ));
+            return Ok(self.hash_join_stream(build, probe, build_accumulator, level));
+        }
+
+        if let Some(build_accumulator) = build_accumulator {
+            build_accumulator.report_canceled_partition(self.partition);
+        }
+
+        let random_state =
+            RandomState::with_seed(GRACE_PARTITION_SEED.wrapping_add(level as u64));
+        let build_files = self
+            .spill_partitioned(
+                &self.build_spill_manager,
+                &self.on_left,
+                &random_state,
+                buffered.chain(build),
+            )
+            .await?;
+        let probe_files = self
+            .spill_partitioned(
+                &self.probe_spill_manager,
+                &self.on_right,
+                &random_state,
+                probe,
+            )
+            .await?;
+
+        let join_type = self.join_type;
+        let partitions = build_files
+            .into_iter()
+            .zip(probe_files)
+            .filter(
+                move |(build_file, probe_file)| match (build_file, probe_file) {
+                    (None, None) => false,
+                    (None, Some(_)) => {
+                        !join_type.empty_build_side_produces_empty_result()
+                    }
+                    (Some(_), _) => true,
+                },
+            )
+            .collect::<Vec<_>>();
+
+        let schema = Arc::clone(&self.schema);
+        let stream = stream::iter(partitions)
+            .map(move |(build_file, probe_file)| -> Result<_> {
+                let build = Self::read_spill(&self.build_spill_manager, build_file)?;
+                let probe = Self::read_spill(&self.probe_spill_manager, probe_file)?;
+                Ok(Arc::clone(&self).execute(build, probe, None, level + 1))
+            })
+            .try_flatten();
+        Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+    }
+
+    /// Builds an in-memory [`HashJoinStream`] over `build` and `probe`.
+    fn hash_join_stream(
+        &self,
+        build: SendableRecordBatchStream,
+        probe: SendableRecordBatchStream,
+        build_accumulator: Option<Arc<SharedBuildAccumulator>>,
+        level: usize,
+    ) -> SendableRecordBatchStream {
+        let reservation = MemoryConsumer::new(if level == 0 {
+            format!("HashJoinInput[{}]", self.partition)
+        } else {
+            format!("HashJoinInput[{}] (spilled)", self.partition)
+        })
+        .register(&self.memory_pool);
+
+        let left_fut = OnceFut::new(collect_left_input(
+            self.random_state.clone(),
+            build,
+            self.on_left.clone(),
+            self.join_metrics.clone(),
+            reservation,
+            need_produce_result_in_final(self.join_type),
+            1,
+            build_accumulator.is_some(),
+            Arc::clone(&self.config),
+            self.null_equality,
+            false,
+            self.array_map_created_count.clone(),
+        ));
+
+        Box::pin(HashJoinStream::new(
+            self.partition,
+            Arc::clone(&self.schema),
+            self.on_right.clone(),
+            self.filter.clone(),
+            self.join_type,
+            probe,
+            self.random_state.clone(),
+            self.join_metrics.clone(),
+            self.column_indices.clone(),
+            self.null_equality,
+            HashJoinStreamState::WaitBuildSide,
+            BuildSide::Initial(BuildSideInitialState { left_fut }),
+            self.batch_size,
+            vec![],
+            false,
+            build_accumulator,
+            self.mode,
+            false,
+            None,
+        ))
+    }
+
+    /// Hash partitions `input` on `on` into `num_partitions` spill files.
+    ///
+    /// Returns one entry per partition, `None` for partitions without rows.
+    async fn spill_partitioned(
+        &self,
+        spill_manager: &SpillManager,
+        on: &[PhysicalExprRef],
+        random_state: &RandomState,
+        mut input: impl futures::Stream<Item = Result<RecordBatch>> + Unpin,
+    ) -> Result<Vec<Option<Arc<dyn SpillFile>>>> {
+        let mut files = (0..self.num_partitions)
+            .map(|_| None)
+            .collect::<Vec<Option<InProgressSpillFile>>>();
+        let mut hashes_buffer = vec![];
+        let mut indices = vec![vec![]; self.num_partitions];
+
+        while let Some(batch) = input.next().await {
+            let batch = batch?;
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            let keys = evaluate_expressions_to_arrays(on, &batch)?;
+            hashes_buffer.clear();
+            hashes_buffer.resize(batch.num_rows(), 0);
+            create_hashes(&keys, random_state, &mut hashes_buffer)?;
+
+            indices.iter_mut().for_each(Vec::clear);
+            for (row, hash) in hashes_buffer.iter().enumerate() {
+                indices[(*hash % self.num_partitions as u64) as usize].push(row as u32);
+            }
+
+            for (partition, rows) in indices.iter().enumerate() {
+                if rows.is_empty() {
+                    continue;
+                }
+                let partition_batch = if rows.len() == batch.num_rows() {
+                    batch.clone()
+                } else {
+                    take_record_batch(&batch, &UInt32Array::from(rows.clone()))?
+                };
+                let file = match &mut files[partition] {
+                    Some(file) => file,
+                    file @ None => file
+                        .insert(spill_manager.create_in_progress_file("HashJoinSpill")?),
+                };
+                file.append_batch(&partition_batch)?;
+            }
+        }
+
+        files
+            .into_iter()
+            .map(|file| match file {
+                Some(mut file) => file.finish(),
+                None => Ok(None),
+            })
+            .collect()
+    }
+
+    fn read_spill(
+        spill_manager: &SpillManager,
+        file: Option<Arc<dyn SpillFile>>,
+    ) -> Result<SendableRecordBatchStream> {
+        match file {
+            Some(file) => spill_manager.read_spill_as_stream(file, None),
+            None => Ok(Box::pin(EmptyRecordBatchStream::new(Arc::clone(
+                spill_manager.schema(),
+            )))),
+        }
+    }
+}
@@ -21,6 +21,7 @@ pub use exec::{HashJoinExec, HashJoinExecBuilder};
 pub use partitioned_hash_eval::{HashExpr, HashTableLookupExpr, SeededRandomState};
 
 mod exec;
+mod grace;
 mod inlist_builder;
 mod partitioned_hash_eval;
 mod shared_bounds;
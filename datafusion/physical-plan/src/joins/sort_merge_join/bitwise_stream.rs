@@ -123,11 +123,11 @@ use std::cmp::Ordering;
 use std::sync::Arc;
 
 use crate::EmptyRecordBatchStream;
+use crate::joins::sort_merge_join::key_group_spill::KeyGroupSpill;
 use crate::joins::utils::{JoinFilter, JoinKeyComparator, compare_join_arrays};
 use crate::metrics::{
     BaselineMetrics, Count, ExecutionPlanMetricsSet, Gauge, MetricBuilder, Time,
 };
-use crate::spill::in_progress_spill_file::InProgressSpillFile;
 use crate::spill::spill_manager::SpillManager;
 use crate::stream::{ObservedStream, RecordBatchStreamAdapter};
 use arrow::array::{Array, ArrayRef, BooleanArray, BooleanBufferBuilder, RecordBatch};
@@ -140,9 +140,7 @@ use datafusion_common::{
     DataFusionError, JoinSide, JoinType, NullEquality, Result, ScalarValue, internal_err,
 };
 use datafusion_execution::memory_pool::MemoryReservation;
-use datafusion_execution::{
-    SendableRecordBatchStream, SpillFile, TryEmitter, async_try_stream,
-};
+use datafusion_execution::{SendableRecordBatchStream, TryEmitter, async_try_stream};
 use datafusion_physical_expr_common::physical_expr::PhysicalExprRef;
 
 use futures::StreamExt;
@@ -236,7 +234,7 @@ pub(crate) struct BitwiseSortMergeJoinStream {
     // Only populated when a filter is present. Unbounded — a single key
     // with many inner rows will buffer them all. See "Degenerate cases"
     // in exec.rs. On memory pool overflow the buffered slices move to a
-    // per-group spill file (see [`Self::buffer_inner_key_group`]).
+    // per-group [`KeyGroupSpill`] (see [`Self::buffer_inner_key_group`]).
     inner_key_buffer: Vec<RecordBatch>,
 
     // Join ON expressions, evaluated against each new batch to produce
@@ -444,21 +442,21 @@ impl BitwiseSortMergeJoinStream {
     }
 
     /// Spill the in-memory inner key buffer to disk and clear it. One key
-    /// group can spill repeatedly; every call appends to `writer` — the
-    /// group's single open spill file — creating it on first use.
-    fn spill_inner_key_buffer(
+    /// group can spill repeatedly; every call appends to `spill` — the
+    /// group's single key group spill — creating it on first use.
+    async fn spill_inner_key_buffer(
         &mut self,
-        writer: &mut Option<InProgressSpillFile>,
+        spill: &mut Option<KeyGroupSpill>,
     ) -> Result<()> {
-        if writer.is_none() {
-            *writer = Some(
-                self.spill_manager
-                    .create_in_progress_file("semi_anti_smj_inner_key_spill")?,
-            );
-        }
-        let writer = writer.as_mut().unwrap();
-        for batch in self.inner_key_buffer.drain(..) {
-            writer.append_batch(&batch)?;
+        let spill = match spill {
+            Some(spill) => spill,
+            None => spill.insert(KeyGroupSpill::try_new(
+                self.spill_manager.clone(),
+                "semi_anti_smj_inner_key_spill",
+            )?),
+        };
+        for batch in std::mem::take(&mut self.inner_key_buffer) {
+            spill.append(batch).await?;
         }
         self.inner_buffer_size = 0;
         // Should succeed now — inner buffer has been spilled.
@@ -645,12 +643,12 @@ impl BitwiseSortMergeJoinStream {
     /// across batch boundaries. Sets `inner_batch` to `None` if inner is
     /// exhausted.
     ///
-    /// Slices that overflow the memory pool are appended to a single spill
-    /// file, returned finished — ready for reading — once the whole group
-    /// has been buffered. `None` means the group fit in memory.
-    async fn buffer_inner_key_group(&mut self) -> Result<Option<Arc<dyn SpillFile>>> {
+    /// Slices that overflow the memory pool are appended to a single
+    /// [`KeyGroupSpill`], returned finished — ready for replay — once the
+    /// whole group has been buffered. `None` means the group fit in memory.
+    async fn buffer_inner_key_group(&mut self) -> Result<Option<KeyGroupSpill>> {
         self.clear_inner_key_group();
-        let mut writer: Option<InProgressSpillFile> = None;
+        let mut spill: Option<KeyGroupSpill> = None;
 
         while let Some(inner_batch) = &self.inner_batch {
             let num_inner = inner_batch.num_rows();
@@ -667,7 +665,7 @@ impl BitwiseSortMergeJoinStream {
             // is exhausted, spill the entire buffer to disk.
             if self.try_resize_reservation().is_err() {
                 if self.runtime_env.disk_manager.tmp_files_enabled() {
-                    self.spill_inner_key_buffer(&mut writer)?;
+                    self.spill_inner_key_buffer(&mut spill).await?;
                 } else {
                     // Re-attempt to get the error message
                     self.try_resize_reservation().map_err(|e| {
@@ -701,20 +699,20 @@ impl BitwiseSortMergeJoinStream {
             }
         }
 
-        match writer {
-            Some(mut writer) => writer.finish(),
-            None => Ok(None),
+        if let Some(spill) = spill.as_mut() {
+            spill.finish().await?;
         }
+        Ok(spill)
     }
 
     /// Process a key match with a filter. For each inner row in the buffered
-    /// key group — the spilled slices in `spill` plus the in-memory
-    /// `inner_key_buffer` — evaluates the filter against the outer key group
-    /// and ORs the results into the matched bitset using u64-chunked bitwise
-    /// ops.
+    /// key group — the spilled slices in `spill`, replayed one at a time,
+    /// plus the in-memory `inner_key_buffer` — evaluates the filter against
+    /// the outer key group and ORs the results into the matched bitset using
+    /// u64-chunked bitwise ops.
     async fn process_key_match_with_filter(
         &mut self,
-        spill: Option<&Arc<dyn SpillFile>>,
+        spill: Option<&mut KeyGroupSpill>,
     ) -> Result<()> {
         let num_outer = self.outer_batch.as_ref().unwrap().num_rows();
 
@@ -751,40 +749,33 @@ impl BitwiseSortMergeJoinStream {
 
         // Process spilled inner batches first asynchronously.
         if matched_count < outer_group_len
-            && let Some(spill_file) = spill
+            && let Some(spill) = spill
         {
-            let mut spill_stream = self
-                .spill_manager
-                .read_spill_as_stream(Arc::clone(spill_file), None)?;
-            let mut spill_stream_has_data = false;
+            if spill.num_batches() == 0 {
+                return internal_err!("Spill file was empty");
+            }
 
             // Note: the clock keeps running across the spill reads — the
             // spill file is the join's own data, so reading it back is
             // join work (unlike the child inputs' `next()`).
-            while matched_count < outer_group_len {
-                match spill_stream.next().await {
-                    Some(Ok(inner_slice)) => {
-                        spill_stream_has_data = true;
-                        matched_count = eval_filter_for_inner_slice(
-                            self.outer_is_left,
-                            filter,
-                            &outer_slice,
-                            &inner_slice,
-                            &mut self.matched,
-                            outer_group_start,
-                            outer_group_len,
-                            matched_count,
-                        )?;
-                    }
-                    Some(Err(e)) => return Err(e),
-                    None => {
-                        if !spill_stream_has_data {
-                            return internal_err!("Spill file was empty");
-                        }
-                        break;
-                    }
+            for _ in 0..spill.num_batches() {
+                if matched_count == outer_group_len {
+                    break;
                 }
+                let inner_slice = spill.next_batch().await?;
+                matched_count = eval_filter_for_inner_slice(
+                    self.outer_is_left,
+                    filter,
+                    &outer_slice,
+                    &inner_slice,
+                    &mut self.matched,
+                    outer_group_start,
+                    outer_group_len,
+                    matched_count,
+                )?;
             }
+            // All outer rows may match before the pass reads every slice.
+            spill.end_pass();
         }
 
         // Then process in-memory inner batches.
@@ -820,10 +811,10 @@ impl BitwiseSortMergeJoinStream {
     /// deletes the group's temp file.
     async fn process_filtered_match_loop(
         &mut self,
-        spill: Option<Arc<dyn SpillFile>>,
+        mut spill: Option<KeyGroupSpill>,
     ) -> Result<()> {
         loop {
-            self.process_key_match_with_filter(spill.as_ref()).await?;
+            self.process_key_match_with_filter(spill.as_mut()).await?;
 
             let outer_batch = self.outer_batch.as_ref().unwrap();
             if self.outer_offset < outer_batch.num_rows() {
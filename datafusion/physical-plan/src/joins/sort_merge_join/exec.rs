@@ -94,6 +94,11 @@ use datafusion_physical_expr_common::sort_expr::{LexOrdering, OrderingRequiremen
 /// the algorithm understands when it is not needed anymore, and releases the buffered batches
 /// from memory/disk. Buffered input batches are represented by `BufferedBatch`.
 ///
+/// Buffered batches that only hold rows of one join key, as in long runs of equal keys, are
+/// spilled to a single replayable spill file per key group. The group is then joined by
+/// reading it back from the spill file one batch at a time, once per matching streamed row,
+/// so that a run of equal keys of any size can be joined within the memory limit.
+///
 /// Depending on the type of join, left or right input may be selected as streamed or buffered
 /// respectively. For example, in a left-outer join, the left execution plan will be selected as
 /// streamed input while in a right-outer join, the right execution plan will be selected as the
@@ -0,0 +1,220 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Spilling of the buffered rows of one join key group.
+//!
+//! See comments in [`KeyGroupSpill`] for details.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use arrow::array::RecordBatch;
+use datafusion_common::{Result, internal_datafusion_err, internal_err};
+use datafusion_execution::SendableRecordBatchStream;
+use futures::StreamExt;
+use futures::channel::mpsc::{UnboundedSender, unbounded};
+
+use crate::spill::replayable_spill_input::ReplayableStreamSource;
+use crate::spill::spill_manager::SpillManager;
+use crate::stream::RecordBatchStreamAdapter;
+
+/// Buffered rows of one join key group that did not fit in memory.
+///
+/// Every row of a key group is joined with every row of the matching key
+/// group on the other side, so the buffered group is scanned once per
+/// matching row (or batch) of the other side. Instead of reading spilled
+/// batches back into memory for good, the group is written to a
+/// [`ReplayableStreamSource`] while it is buffered:
+///
+/// ```text
+///   buffering:  append(batch) ──► first pass ──► spill file
+///
+///   scanning:   spill file ──► pass 1 ──► next_batch() ... next_batch()
+///               spill file ──► pass 2 ──► next_batch() ... next_batch()
+/// ```
+///
+/// Each scan is a pass over the spill file, holding a single spilled batch
+/// in memory at a time, so an equal-key run of any size can be joined.
+/// Passes are sequential: a pass ends once all spilled batches were read,
+/// or when ended early by [`Self::end_pass`].
+pub(super) struct KeyGroupSpill {
+    source: ReplayableStreamSource,
+    /// Feeds the first pass of `source` while the group is buffered; `None`
+    /// once [`Self::finish`] was called.
+    sender: Option<UnboundedSender<Result<RecordBatch>>>,
+    /// The pass in progress, if any.
+    pass: Option<SendableRecordBatchStream>,
+    /// Number of spilled batches.
+    num_batches: usize,
+    /// Number of batches read by the pass in progress.
+    pass_position: usize,
+}
+
+impl KeyGroupSpill {
+    /// Creates an empty spill, whose spill file is described as
+    /// `request_description`.
+    pub(super) fn try_new(
+        spill_manager: SpillManager,
+        request_description: &str,
+    ) -> Result<Self> {
+        let (sender, receiver) = unbounded();
+        let input = Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(spill_manager.schema()),
+            receiver,
+        ));
+        let mut source =
+            ReplayableStreamSource::new(input, spill_manager, request_description);
+        // The first pass writes the batches it forwards to the spill file.
+        let pass = source.open_pass()?;
+        Ok(Self {
+            source,
+            sender: Some(sender),
+            pass: Some(pass),
+            num_batches: 0,
+            pass_position: 0,
+        })
+    }
+
+    /// Number of batches spilled so far.
+    pub(super) fn num_batches(&self) -> usize {
+        self.num_batches
+    }
+
+    /// Appends `batch` to the spill file. Only valid before [`Self::finish`].
+    pub(super) async fn append(&mut self, batch: RecordBatch) -> Result<()> {
+        let (Some(sender), Some(pass)) = (&self.sender, &mut self.pass) else {
+            return internal_err!("Cannot append to a finished key group spill");
+        };
+        sender
+            .unbounded_send(Ok(batch))
+            .map_err(|e| internal_datafusion_err!("Key group spill closed: {e}"))?;
+        match pass.next().await {
+            Some(result) => result?,
+            None => return internal_err!("Key group spill ended while appending"),
+        };
+        self.num_batches += 1;
+        Ok(())
+    }
+
+    /// Completes the spill file once the whole group was appended, so that
+    /// it can be replayed by [`Self::next_batch`].
+    pub(super) async fn finish(&mut self) -> Result<()> {
+        if self.sender.take().is_none() {
+            return Ok(());
+        }
+        if let Some(mut pass) = self.pass.take() {
+            while let Some(result) = pass.next().await {
+                result?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the next spilled batch of the pass in progress, opening a new
+    /// pass if there is none. The pass ends after its last batch.
+    pub(super) async fn next_batch(&mut self) -> Result<RecordBatch> {
+        if self.sender.is_some() {
+            return internal_err!("Cannot replay an unfinished key group spill");
+        }
+        if self.pass.is_none() {
+            self.pass = Some(self.source.open_pass()?);
+            self.pass_position = 0;
+        }
+        let pass = self.pass.as_mut().unwrap();
+        let Some(batch) = pass.next().await.transpose()? else {
+            return internal_err!(
+                "Key group spill ended after {} of {} batches",
+                self.pass_position,
+                self.num_batches
+            );
+        };
+        self.pass_position += 1;
+        if self.pass_position == self.num_batches {
+            self.end_pass();
+        }
+        Ok(batch)
+    }
+
+    /// Ends the pass in progress, if any, without reading its remaining
+    /// batches.
+    pub(super) fn end_pass(&mut self) {
+        if self.sender.is_none() {
+            self.pass = None;
+        }
+    }
+}
+
+impl Debug for KeyGroupSpill {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyGroupSpill")
+            .field("num_batches", &self.num_batches)
+            .field("finished", &self.sender.is_none())
+            .field("pass_position", &self.pass_position)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::{ExecutionPlanMetricsSet, SpillMetrics};
+
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+
+    fn spill_manager(schema: SchemaRef) -> Result<SpillManager> {
+        let runtime = Arc::new(RuntimeEnvBuilder::new().build()?);
+        let metrics = SpillMetrics::new(&ExecutionPlanMetricsSet::new(), 0);
+        Ok(SpillManager::new(runtime, metrics, schema))
+    }
+
+    #[tokio::test]
+    async fn test_key_group_spill_replays_batches() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batches = (0..3)
+            .map(|i| {
+                RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![Arc::new(Int32Array::from(vec![i, i + 10]))],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut spill = KeyGroupSpill::try_new(spill_manager(schema)?, "test")?;
+        for batch in &batches {
+            spill.append(batch.clone()).await?;
+        }
+        assert!(spill.next_batch().await.is_err());
+        spill.finish().await?;
+        assert_eq!(spill.num_batches(), 3);
+
+        // complete passes
+        for _ in 0..2 {
+            for batch in &batches {
+                assert_eq!(&spill.next_batch().await?, batch);
+            }
+        }
+
+        // a pass ended early
+        assert_eq!(spill.next_batch().await?, batches[0]);
+        spill.end_pass();
+        assert_eq!(spill.next_batch().await?, batches[0]);
+
+        Ok(())
+    }
+}
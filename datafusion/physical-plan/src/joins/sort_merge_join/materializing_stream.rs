@@ -33,6 +33,7 @@ use crate::joins::sort_merge_join::filter::{
     FilterMetadata, filter_record_batch_by_join_type, get_corrected_filter_mask,
     get_filter_columns, needs_deferred_filtering,
 };
+use crate::joins::sort_merge_join::key_group_spill::KeyGroupSpill;
 use crate::joins::sort_merge_join::metrics::SortMergeJoinMetrics;
 use crate::joins::utils::{JoinFilter, JoinKeyComparator};
 use crate::metrics::Time;
@@ -233,9 +234,7 @@ impl BufferedBatch {
 
         let size_estimation = batch.get_array_memory_size()
             + join_arrays_mem
-            + batch.num_rows().next_power_of_two() * size_of::<usize>()
-            + size_of::<Range<usize>>()
-            + size_of::<usize>();
+            + Self::bookkeeping_mem(batch.num_rows());
 
         let num_rows = batch.num_rows();
         BufferedBatch {
@@ -252,6 +251,31 @@ impl BufferedBatch {
     }
 }
 
+impl BufferedBatch {
+    /// Estimated memory of the per-row bookkeeping of a batch of `num_rows`
+    /// rows, which stays in memory while the batch is spilled.
+    fn bookkeeping_mem(num_rows: usize) -> usize {
+        num_rows.next_power_of_two() * size_of::<usize>()
+            + size_of::<Range<usize>>()
+            + size_of::<usize>()
+    }
+
+    /// The batch, if it is currently in memory.
+    fn in_memory_batch(&self) -> Option<&RecordBatch> {
+        match &self.batch {
+            BufferedBatchState::InMemory(batch)
+            | BufferedBatchState::Replayed(Some(batch)) => Some(batch),
+            BufferedBatchState::Spilled(_) | BufferedBatchState::Replayed(None) => None,
+        }
+    }
+
+    /// True if the batch is in the key group spill, and not currently read
+    /// back from it.
+    fn needs_replay(&self) -> bool {
+        matches!(self.batch, BufferedBatchState::Replayed(None))
+    }
+}
+
 // TODO: Spill join arrays (https://github.com/apache/datafusion/pull/17429)
 // Used to represent whether the buffered data is currently in memory or written to disk
 pub(super) enum BufferedBatchState {
@@ -259,6 +283,9 @@ pub(super) enum BufferedBatchState {
     InMemory(RecordBatch),
     // Spilled temp file
     Spilled(Arc<dyn SpillFile>),
+    // Spilled to the key group spill of `BufferedData`, holding the batch
+    // while it is read back by the current pass over the group
+    Replayed(Option<RecordBatch>),
 }
 
 impl Debug for BufferedBatchState {
@@ -268,6 +295,7 @@ impl Debug for BufferedBatchState {
             Self::Spilled(_) => {
                 write!(f, "Spilled(Custom_Backend)")
             }
+            Self::Replayed(batch) => f.debug_tuple("Replayed").field(batch).finish(),
         }
     }
 }
@@ -663,6 +691,7 @@ impl MaterializingSortMergeJoinStream {
                 Ordering::Equal => {
                     while !self.pair_streamed_row_with_group() {
                         self.freeze_and_emit(emitter).await?;
+                        self.replay_scanning_batch().await?;
                     }
                     if !self.try_advance_streamed_row() {
                         self.load_next_streamed_batch().await?;
@@ -690,21 +719,33 @@ impl MaterializingSortMergeJoinStream {
     /// buffered key group, and mark the group as matched.
     ///
     /// Returns false when a full batch of pairs has accumulated (the scan
-    /// may or may not be complete): the caller must materialize
-    /// (`freeze_and_emit`) and call again, which resumes the scan where it
-    /// paused. Returns true when the group scan is complete and there is
-    /// room for more pairs.
+    /// may or may not be complete), or when the scan reaches or leaves a
+    /// batch of the key group spill: the caller must materialize
+    /// (`freeze_and_emit`), read the spilled batch back
+    /// (`replay_scanning_batch`) and call again, which resumes the scan
+    /// where it paused. Returns true when the group scan is complete and
+    /// there is room for more pairs.
     fn pair_streamed_row_with_group(&mut self) -> bool {
         while !self.buffered_data.scanning_finished()
             && self.num_unfrozen_pairs() < self.batch_size
         {
+            if self.buffered_data.scanning_batch().needs_replay() {
+                return false;
+            }
+            let scanning_batch_idx = self.buffered_data.scanning_batch_idx;
             let scanning_idx = self.buffered_data.scanning_idx();
             self.streamed_batch.append_output_pair(
-                Some(self.buffered_data.scanning_batch_idx),
+                Some(scanning_batch_idx),
                 Some(scanning_idx),
                 self.batch_size,
             );
             self.buffered_data.scanning_advance();
+            // The pairs of a replayed batch are frozen before it is unloaded
+            if self.buffered_data.loaded_batch_idx == Some(scanning_batch_idx)
+                && self.buffered_data.scanning_batch_idx != scanning_batch_idx
+            {
+                return false;
+            }
         }
         if self.num_unfrozen_pairs() >= self.batch_size {
             return false;
@@ -1071,7 +1112,10 @@ impl MaterializingSortMergeJoinStream {
         }
     }
 
-    fn allocate_reservation(&mut self, mut buffered_batch: BufferedBatch) -> Result<()> {
+    async fn allocate_reservation(
+        &mut self,
+        mut buffered_batch: BufferedBatch,
+    ) -> Result<()> {
         match self.reservation.try_grow(buffered_batch.size_estimation) {
             Ok(_) => {
                 buffered_batch.reserved_amount = buffered_batch.size_estimation;
@@ -1080,6 +1124,12 @@ impl MaterializingSortMergeJoinStream {
                     .set_max(self.reservation.size());
                 Ok(())
             }
+            Err(_)
+                if self.runtime_env.disk_manager.tmp_files_enabled()
+                    && self.is_within_buffered_group(&buffered_batch)? =>
+            {
+                self.spill_to_group(&mut buffered_batch).await
+            }
             Err(_) if self.runtime_env.disk_manager.tmp_files_enabled() => {
                 // Spill buffered batch to disk
 
@@ -1123,6 +1173,65 @@ impl MaterializingSortMergeJoinStream {
         Ok(())
     }
 
+    /// True if all rows of `buffered_batch` belong to the current buffered
+    /// key group, i.e. its last row has the key of the group.
+    fn is_within_buffered_group(&self, buffered_batch: &BufferedBatch) -> Result<bool> {
+        if self.buffered_data.batches.is_empty() {
+            return Ok(false);
+        }
+        let head_batch = self.buffered_data.head_batch();
+        let cmp = JoinKeyComparator::new(
+            &head_batch.join_arrays,
+            &buffered_batch.join_arrays,
+            &self.sort_options,
+            // is_join_arrays_equal treats both-null as equal
+            NullEquality::NullEqualsNull,
+        )?;
+        Ok(cmp.is_equal(head_batch.range.start, buffered_batch.num_rows - 1))
+    }
+
+    /// Append a buffered batch that does not fit in memory, and only holds
+    /// rows of the current key group, to the group's [`KeyGroupSpill`].
+    ///
+    /// Unlike batches spilled on their own, which are restored to memory
+    /// for good once the group is joined, replayed batches are read back
+    /// one at a time by each pass over the group, so that a key group of
+    /// any size can be joined.
+    async fn spill_to_group(&mut self, buffered_batch: &mut BufferedBatch) -> Result<()> {
+        let BufferedBatchState::InMemory(batch) = std::mem::replace(
+            &mut buffered_batch.batch,
+            BufferedBatchState::Replayed(None),
+        ) else {
+            return internal_err!("Buffered batch has empty body");
+        };
+        let group_spill = match self.buffered_data.group_spill.as_mut() {
+            Some(group_spill) => group_spill,
+            None => self
+                .buffered_data
+                .group_spill
+                .insert(KeyGroupSpill::try_new(
+                    self.spill_manager.clone(),
+                    "sort_merge_join_buffered_group_spill",
+                )?),
+        };
+        group_spill.append(batch).await?;
+
+        // All rows belong to the group, whose key is known from its head
+        // batch, so the join key arrays are not needed either.
+        buffered_batch.range = 0..buffered_batch.num_rows;
+        buffered_batch.join_arrays = vec![];
+
+        // Only the per-row bookkeeping stays in memory; force-grow like
+        // the join key arrays of batches spilled on their own.
+        let bookkeeping_mem = BufferedBatch::bookkeeping_mem(buffered_batch.num_rows);
+        self.reservation.grow(bookkeeping_mem);
+        buffered_batch.reserved_amount = bookkeeping_mem;
+        self.join_metrics
+            .peak_mem_used()
+            .set_max(self.reservation.size());
+        Ok(())
+    }
+
     /// Sync fast path of [`Self::advance_buffered_group`]: when the next
     /// group starts in the single remaining buffered batch and provably ends
     /// within it (the common case — a group only reaches a batch boundary
@@ -1185,7 +1294,12 @@ impl MaterializingSortMergeJoinStream {
             tail_batch.range.end += 1;
         }
 
-        self.extend_buffered_group().await
+        self.extend_buffered_group().await?;
+        // The group is complete: its spilled batches can now be replayed.
+        if let Some(group_spill) = self.buffered_data.group_spill.as_mut() {
+            group_spill.finish().await?;
+        }
+        Ok(())
     }
 
     /// Dequeue buffered batches fully consumed by the previous group,
@@ -1202,6 +1316,13 @@ impl MaterializingSortMergeJoinStream {
             // load the spilled head batch before dequeuing
             let needed = self.get_required_batch_indices(1);
             self.restore_spilled_batches(&needed).await?;
+            // Full joins produce the pending null-joined rows of replayed
+            // batches, read back in order as they are dequeued
+            if self.join_type == JoinType::Full
+                && self.buffered_data.head_batch().needs_replay()
+            {
+                self.load_replayed_batch(0).await?;
+            }
 
             self.freeze_dequeuing_buffered()?;
             if let Some(mut buffered_batch) = self.buffered_data.batches.pop_front() {
@@ -1213,6 +1334,17 @@ impl MaterializingSortMergeJoinStream {
                 head_changed = true;
             }
         }
+        // Only whole batches of the finished group are replayed, so all of
+        // them were dequeued along with the group.
+        debug_assert!(
+            !self
+                .buffered_data
+                .batches
+                .iter()
+                .any(|batch| matches!(batch.batch, BufferedBatchState::Replayed(_)))
+        );
+        self.buffered_data.group_spill = None;
+        self.buffered_data.loaded_batch_idx = None;
         if head_changed {
             self.streamed_buffered_cmp = None;
             self.buffered_equality_cmp = None;
@@ -1243,7 +1375,7 @@ impl MaterializingSortMergeJoinStream {
                     if batch.num_rows() > 0 {
                         let buffered_batch =
                             BufferedBatch::new(batch, 0..1, &self.on_buffered);
-                        self.allocate_reservation(buffered_batch)?;
+                        self.allocate_reservation(buffered_batch).await?;
                         self.streamed_buffered_cmp = None;
                         return Ok(true);
                     }
@@ -1298,7 +1430,7 @@ impl MaterializingSortMergeJoinStream {
                         if batch.num_rows() > 0 {
                             let buffered_batch =
                                 BufferedBatch::new(batch, 0..0, &self.on_buffered);
-                            self.allocate_reservation(buffered_batch)?;
+                            self.allocate_reservation(buffered_batch).await?;
                             self.buffered_equality_cmp = None;
                         }
                     }
@@ -1346,6 +1478,65 @@ impl MaterializingSortMergeJoinStream {
         Ok(())
     }
 
+    /// Keep the key group spill in step with the group scan: unload the
+    /// replayed batch the scan has left (its pairs are frozen by now), and
+    /// read back the one it has reached from the current pass over the spill.
+    async fn replay_scanning_batch(&mut self) -> Result<()> {
+        let scanning_batch_idx = (!self.buffered_data.scanning_finished())
+            .then_some(self.buffered_data.scanning_batch_idx);
+        if let Some(idx) = self.buffered_data.loaded_batch_idx
+            && Some(idx) != scanning_batch_idx
+        {
+            self.unload_replayed_batch(idx);
+            self.buffered_data.loaded_batch_idx = None;
+        }
+        if let Some(idx) = scanning_batch_idx
+            && self.buffered_data.batches[idx].needs_replay()
+        {
+            self.load_replayed_batch(idx).await?;
+            self.buffered_data.loaded_batch_idx = Some(idx);
+        }
+        Ok(())
+    }
+
+    /// Read the replayed buffered batch at `idx` back from the key group
+    /// spill. Passes over the spill read its batches in order, so the
+    /// replayed batches of the group must be loaded in order as well.
+    async fn load_replayed_batch(&mut self, idx: usize) -> Result<()> {
+        let Some(group_spill) = self.buffered_data.group_spill.as_mut() else {
+            return internal_err!("Replayed buffered batch without key group spill");
+        };
+        let batch = group_spill.next_batch().await?;
+
+        // Like restored spilled batches, the replayed batch is needed to
+        // produce output, so force-grow the reservation.
+        let buffered_batch = &mut self.buffered_data.batches[idx];
+        buffered_batch.batch = BufferedBatchState::Replayed(Some(batch));
+        let newly_allocated = buffered_batch
+            .size_estimation
+            .saturating_sub(buffered_batch.reserved_amount);
+        buffered_batch.reserved_amount += newly_allocated;
+        self.reservation.grow(newly_allocated);
+        self.join_metrics
+            .peak_mem_used()
+            .set_max(self.reservation.size());
+        Ok(())
+    }
+
+    /// Release the replayed buffered batch at `idx`, which stays in the key
+    /// group spill for the next pass.
+    fn unload_replayed_batch(&mut self, idx: usize) {
+        let buffered_batch = &mut self.buffered_data.batches[idx];
+        if let BufferedBatchState::Replayed(loaded) = &mut buffered_batch.batch
+            && loaded.take().is_some()
+        {
+            let bookkeeping_mem = BufferedBatch::bookkeeping_mem(buffered_batch.num_rows);
+            self.reservation
+                .shrink(buffered_batch.reserved_amount - bookkeeping_mem);
+            buffered_batch.reserved_amount = bookkeeping_mem;
+        }
+    }
+
     fn freeze_all(&mut self) -> Result<()> {
         self.freeze_buffered(self.buffered_data.batches.len())?;
         self.freeze_streamed()?;
@@ -1385,6 +1576,11 @@ impl MaterializingSortMergeJoinStream {
             return Ok(());
         }
         for buffered_batch in self.buffered_data.batches.range_mut(..batch_count) {
+            // Replayed batches produce their null-joined rows once read back
+            // as they are dequeued
+            if buffered_batch.needs_replay() {
+                continue;
+            }
             let buffered_indices = UInt64Array::from_iter_values(
                 buffered_batch.null_joined.iter().map(|&index| index as u64),
             );
@@ -1695,9 +1891,9 @@ impl MaterializingSortMergeJoinStream {
             .iter()
             .map(|&idx| {
                 let bb = &self.buffered_data.batches[idx];
-                match &bb.batch {
-                    BufferedBatchState::InMemory(batch) => Ok(batch.clone()),
-                    BufferedBatchState::Spilled(_) => {
+                match bb.in_memory_batch() {
+                    Some(batch) => Ok(batch.clone()),
+                    None => {
                         internal_err!("Buffered batch should have been unspilled before fetching columns")
                     }
                 }
@@ -1909,15 +2105,15 @@ fn fetch_right_columns_from_batch_by_idxs(
     buffered_batch: &BufferedBatch,
     buffered_indices: &UInt64Array,
 ) -> Result<Vec<ArrayRef>> {
-    match &buffered_batch.batch {
-        BufferedBatchState::InMemory(batch) => {
+    match buffered_batch.in_memory_batch() {
+        Some(batch) => {
             if let Some(range) = is_contiguous_range(buffered_indices) {
                 Ok(batch.slice(range.start, range.len()).columns().to_vec())
             } else {
                 Ok(take_arrays(batch.columns(), buffered_indices, None)?)
             }
         }
-        BufferedBatchState::Spilled(_) => {
+        None => {
             internal_err!(
                 "Buffered batch should have been unspilled before fetching columns"
             )
@@ -1930,6 +2126,11 @@ fn fetch_right_columns_from_batch_by_idxs(
 pub(super) struct BufferedData {
     /// Buffered batches with the same key
     pub batches: VecDeque<BufferedBatch>,
+    /// Batches of the current key group that did not fit in memory, in the
+    /// order of their [`BufferedBatchState::Replayed`] entries in `batches`
+    pub group_spill: Option<KeyGroupSpill>,
+    /// Index of the replayed batch read back for the group scan, if any
+    pub loaded_batch_idx: Option<usize>,
     /// current scanning batch index used by the group-scan phase
     pub scanning_batch_idx: usize,
     /// current scanning offset used by the group-scan phase
@@ -22,6 +22,7 @@ pub use exec::SortMergeJoinExec;
 pub(crate) mod bitwise_stream;
 mod exec;
 mod filter;
+mod key_group_spill;
 pub(crate) mod materializing_stream;
 mod metrics;
 
@@ -44,7 +44,7 @@ use crate::{
 };
 use arrow::array::{
     BinaryArray, BooleanArray, Date32Array, Date64Array, FixedSizeBinaryArray,
-    Int32Array, RecordBatch, UInt64Array,
+    Int32Array, RecordBatch, StringArray, UInt64Array,
 };
 use arrow::compute::{BatchCoalescer, SortOptions, filter_record_batch};
 use arrow::datatypes::{DataType, Field, Schema};
@@ -65,7 +65,7 @@ use datafusion_execution::config::SessionConfig;
 use datafusion_execution::disk_manager::{
     DiskManager, DiskManagerBuilder, DiskManagerMode,
 };
-use datafusion_execution::memory_pool::MemoryConsumer;
+use datafusion_execution::memory_pool::{FairSpillPool, MemoryConsumer};
 use datafusion_execution::runtime_env::RuntimeEnvBuilder;
 use datafusion_execution::spill_file::{SpillFile, SpillWriter, TempFileFactory};
 use datafusion_execution::{SendableRecordBatchStream, TaskContext};
@@ -3044,6 +3044,151 @@ async fn spill_full_join_filter_not_matched() -> Result<()> {
     Ok(())
 }
 
+/// Builds a batch with join keys `b{side}` = `keys`, filter column `c{side}`
+/// counting from `c_start`, and a wide `d{side}` payload, so that rows are
+/// much larger than their join keys and per-row bookkeeping.
+fn build_wide_batch(side: &str, keys: Vec<i32>, c_start: i32) -> RecordBatch {
+    let num_rows = keys.len() as i32;
+    let schema = Arc::new(Schema::new(vec![
+        Field::new(format!("a{side}"), DataType::Int32, false),
+        Field::new(format!("b{side}"), DataType::Int32, false),
+        Field::new(format!("c{side}"), DataType::Int32, false),
+        Field::new(format!("d{side}"), DataType::Utf8, false),
+    ]));
+    RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(Int32Array::from_iter_values(0..num_rows)),
+            Arc::new(Int32Array::from(keys)),
+            Arc::new(Int32Array::from_iter_values(c_start..c_start + num_rows)),
+            Arc::new(StringArray::from_iter_values(
+                (0..num_rows).map(|i| format!("{i:064}")),
+            )),
+        ],
+    )
+    .unwrap()
+}
+
+/// Joins a long run of equal keys, spanning many buffered batches, under a
+/// memory pool much smaller than the run. Every join type must stream the
+/// run from its spill file instead of holding it in memory, and produce the
+/// same results as without a memory limit.
+#[tokio::test]
+async fn spill_long_equal_key_run() -> Result<()> {
+    const RUN_BATCHES: i32 = 16;
+    const BATCH_ROWS: i32 = 50;
+
+    // The side holding the run: a key 1 run followed by an unmatched key 3
+    // run, so that FULL joins also null-join a spilled group.
+    let long_side = |side: &str| {
+        let key_1_run = (0..RUN_BATCHES)
+            .map(|i| build_wide_batch(side, vec![1; BATCH_ROWS as usize], i * BATCH_ROWS))
+            .collect::<Vec<_>>();
+        let key_3_run = (0..4).map(|i| {
+            build_wide_batch(side, vec![3; BATCH_ROWS as usize], i * BATCH_ROWS)
+        });
+        let run_mem: usize = key_1_run.iter().map(|b| b.get_array_memory_size()).sum();
+        let batches = key_1_run.into_iter().chain(key_3_run).collect();
+        (build_table_from_batches(batches), run_mem)
+    };
+    // The other side: a few rows of key 1, among unmatched keys
+    let short_side = |side: &str| {
+        build_table_from_batches(vec![
+            build_wide_batch(side, vec![0, 1, 1], 9),
+            build_wide_batch(side, vec![1, 2], 300),
+        ])
+    };
+
+    let runtime = RuntimeEnvBuilder::new()
+        .with_memory_pool(Arc::new(FairSpillPool::new(6 * 1024)))
+        .with_disk_manager_builder(
+            DiskManagerBuilder::default().with_mode(DiskManagerMode::OsTmpDirectory),
+        )
+        .build_arc()?;
+
+    let join_types = [
+        Inner, Left, Right, Full, LeftSemi, LeftAnti, RightSemi, RightAnti, LeftMark,
+        RightMark,
+    ];
+    for join_type in join_types {
+        // The run is on the buffered side (or the inner side of semi, anti
+        // and mark joins)
+        let run_is_left = matches!(join_type, Right | RightSemi | RightAnti | RightMark);
+        let (left, right, run_mem) = if run_is_left {
+            let (left, run_mem) = long_side("1");
+            (left, short_side("2"), run_mem)
+        } else {
+            let (right, run_mem) = long_side("2");
+            (short_side("1"), right, run_mem)
+        };
+        let on = vec![(
+            Arc::new(Column::new_with_schema("b1", &left.schema())?) as _,
+            Arc::new(Column::new_with_schema("b2", &right.schema())?) as _,
+        )];
+        let filter = build_c1_lt_c2_filter(&left.schema(), &right.schema());
+
+        for filter in [None, Some(filter)] {
+            // Semi, anti and mark joins only buffer the run to evaluate a
+            // filter
+            let buffers_run =
+                matches!(join_type, Inner | Left | Right | Full) || filter.is_some();
+
+            for batch_size in [8, 1024] {
+                let session_config = SessionConfig::default().with_batch_size(batch_size);
+                let run = |task_ctx: TaskContext| {
+                    let join = SortMergeJoinExec::try_new(
+                        Arc::clone(&left),
+                        Arc::clone(&right),
+                        on.clone(),
+                        filter.clone(),
+                        join_type,
+                        vec![SortOptions::default(); on.len()],
+                        NullEquality::NullEqualsNothing,
+                    );
+                    async move {
+                        let join = join?;
+                        let stream = join.execute(0, Arc::new(task_ctx))?;
+                        let batches = common::collect(stream).await?;
+                        Ok::<_, datafusion_common::DataFusionError>((
+                            batches_to_sort_string(&batches),
+                            join.metrics().unwrap(),
+                        ))
+                    }
+                };
+
+                let (spilled, metrics) = run(TaskContext::default()
+                    .with_session_config(session_config.clone())
+                    .with_runtime(Arc::clone(&runtime)))
+                .await?;
+                let (expected, _) =
+                    run(TaskContext::default().with_session_config(session_config))
+                        .await?;
+                let case = format!(
+                    "{join_type:?} filter={} batch_size={batch_size}",
+                    filter.is_some()
+                );
+                assert_eq!(spilled, expected, "{case}");
+                assert_eq!(runtime.memory_pool.reserved(), 0, "{case}");
+
+                if buffers_run {
+                    assert!(metrics.spill_count().unwrap() > 0, "{case}");
+                    let peak_mem = metrics
+                        .sum_by_name("peak_mem_used")
+                        .map(|m| m.as_usize())
+                        .unwrap_or(0);
+                    assert!(
+                        peak_mem < run_mem / 2,
+                        "{case}: peak_mem_used ({peak_mem}) should be well below \
+                         the memory of the run ({run_mem})"
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn build_joined_record_batches() -> Result<JoinedRecordBatches> {
     let schema = Arc::new(Schema::new(vec![
         Field::new("a", DataType::Int32, true),
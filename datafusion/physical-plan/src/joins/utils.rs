@@ -238,6 +238,29 @@ pub struct ColumnIndex {
     pub side: JoinSide,
 }
 
+/// Estimated output rows and cumulative cost of a join, as computed by a
+/// cost-based join enumeration rule.
+///
+/// The cost is the sum of the estimated output rows of this join and all
+/// joins below it (the `C_out` cost model).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JoinCostEstimate {
+    /// Estimated number of output rows
+    pub rows: usize,
+    /// Estimated cumulative cost
+    pub cost: usize,
+}
+
+impl fmt::Display for JoinCostEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "estimated_rows={}, estimated_cost={}",
+            self.rows, self.cost
+        )
+    }
+}
+
 /// Returns the output field given the input field. Outer joins may
 /// insert nulls even if the input was not null
 fn output_join_field(old_field: &Field, join_type: &JoinType, is_left: bool) -> Field {
@@ -87,6 +87,7 @@ pub mod filter_pushdown;
 pub mod joins;
 pub mod limit;
 pub mod memory;
+pub mod merge_into;
 pub mod metrics;
 pub mod operator_statistics;
 pub mod placeholder_row;
@@ -0,0 +1,1362 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the MERGE INTO operator: [`MergeIntoExec`] computes the new
+//! contents of the target table, and [`MergeIntoResultExec`] reports the
+//! number of affected rows once they have been written.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::execution_plan::{Boundedness, EmissionType};
+use crate::joins::JoinOn;
+use crate::metrics::{
+    BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder, MetricValue,
+    MetricsSet,
+};
+use crate::stream::RecordBatchStreamAdapter;
+use crate::{
+    ChildrenPropertiesMode, DisplayAs, DisplayFormatType, Distribution, ExecutionPlan,
+    ExecutionPlanProperties, InputDistributionRequirements, Partitioning, PlanProperties,
+    ReplaceChildrenOptions, SendableRecordBatchStream, execute_stream,
+    validate_child_count,
+};
+
+use arrow::array::{
+    Array, ArrayRef, AsArray, BooleanArray, RecordBatch, UInt32Array, UInt64Array,
+    new_null_array,
+};
+use arrow::compute::{
+    and, and_not, concat_batches, filter, filter_record_batch, prep_null_mask_filter,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, UInt32Type};
+use arrow::row::{RowConverter, SortField};
+use datafusion_common::tree_node::{Transformed, TreeNode, TreeNodeRecursion};
+use datafusion_common::{
+    DataFusionError, Result, assert_eq_or_internal_err, exec_err, internal_err, plan_err,
+};
+use datafusion_execution::TaskContext;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_expr::Operator;
+use datafusion_expr::dml::MergeIntoClauseKind;
+use datafusion_physical_expr::expressions::{BinaryExpr, Column};
+use datafusion_physical_expr::utils::{collect_columns, split_conjunction};
+use datafusion_physical_expr::{EquivalenceProperties, PhysicalExpr};
+use futures::{StreamExt, TryStreamExt, stream};
+
+/// A `WHEN` clause of a [`MergeIntoExec`].
+///
+/// The expressions are evaluated against rows made of the target columns
+/// followed by the source columns. For rows without a matching target
+/// (respectively source) row, the target (respectively source) columns are
+/// all null.
+#[derive(Debug, Clone)]
+pub struct PhysicalMergeClause {
+    /// The rows the clause applies to
+    pub kind: MergeIntoClauseKind,
+    /// Additional condition of the clause (`AND <expr>`)
+    pub predicate: Option<Arc<dyn PhysicalExpr>>,
+    /// The action performed on the rows the clause applies to
+    pub action: PhysicalMergeAction,
+}
+
+/// The action of a [`PhysicalMergeClause`].
+#[derive(Debug, Clone)]
+pub enum PhysicalMergeAction {
+    /// Replaces the target row with a row computed by one expression per
+    /// target column
+    Update(Vec<Arc<dyn PhysicalExpr>>),
+    /// Inserts a row computed by one expression per target column
+    Insert(Vec<Arc<dyn PhysicalExpr>>),
+    /// Removes the target row
+    Delete,
+}
+
+impl fmt::Display for PhysicalMergeClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind.canonical() {
+            MergeIntoClauseKind::Matched => "matched",
+            MergeIntoClauseKind::NotMatchedBySource => "not matched by source",
+            _ => "not matched",
+        };
+        let action = match self.action {
+            PhysicalMergeAction::Update(_) => "update",
+            PhysicalMergeAction::Insert(_) => "insert",
+            PhysicalMergeAction::Delete => "delete",
+        };
+        match &self.predicate {
+            Some(predicate) => write!(f, "{kind} and {predicate}: {action}"),
+            None => write!(f, "{kind}: {action}"),
+        }
+    }
+}
+
+/// Executes the `MERGE INTO` statement `MERGE INTO target USING source ON on
+/// WHEN ...`, producing the new contents of the target table.
+///
+/// Each target row is joined to the source rows satisfying `on`. The first
+/// clause (in the order of the statement) whose kind and predicate match a
+/// row determines what happens to it:
+///
+/// * matched target rows are updated or deleted by `WHEN MATCHED` clauses,
+/// * source rows without matching target row are inserted by `WHEN NOT
+///   MATCHED` clauses,
+/// * target rows without matching source row are updated or deleted by `WHEN
+///   NOT MATCHED BY SOURCE` clauses,
+///
+/// and all other target rows are kept as is. It is an error for a target row
+/// to match more than one source row.
+///
+/// The source is buffered in memory, accounted for in the memory pool, and
+/// indexed on the equality predicates of `on` between target and source
+/// columns, if any, while the target is streamed. Without such predicates,
+/// every target row is compared to every source row, `batch_size` pairs at a
+/// time. The output is typically written back to the table by a sink
+/// replacing its contents, wrapped in a [`MergeIntoResultExec`] to report the
+/// number of rows affected. See [`Merger`] to merge a target read by other
+/// means, e.g. one file at a time.
+#[derive(Debug)]
+pub struct MergeIntoExec {
+    /// The current contents of the target table
+    target: Arc<dyn ExecutionPlan>,
+    /// The rows to merge into the target
+    source: Arc<dyn ExecutionPlan>,
+    /// The join condition and clauses
+    spec: MergeIntoSpec,
+    /// Number of rows updated, deleted or inserted, shared by the copies of
+    /// this plan
+    rows_affected: Count,
+    metrics: ExecutionPlanMetricsSet,
+    cache: Arc<PlanProperties>,
+}
+
+impl MergeIntoExec {
+    /// Creates a new [`MergeIntoExec`], whose output has the schema of `target`
+    pub fn try_new(
+        target: Arc<dyn ExecutionPlan>,
+        source: Arc<dyn ExecutionPlan>,
+        on: Arc<dyn PhysicalExpr>,
+        clauses: Vec<PhysicalMergeClause>,
+    ) -> Result<Self> {
+        let spec = MergeIntoSpec::try_new(target.schema(), source.schema(), on, clauses)?;
+        let cache = Arc::new(PlanProperties::new(
+            EquivalenceProperties::new(target.schema()),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Incremental,
+            Boundedness::Bounded,
+        ));
+        Ok(Self {
+            target,
+            source,
+            spec,
+            rows_affected: Count::new(),
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache,
+        })
+    }
+
+    /// The current contents of the target table
+    pub fn target(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.target
+    }
+
+    /// The rows to merge into the target
+    pub fn source(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.source
+    }
+
+    /// The join condition
+    pub fn on(&self) -> &Arc<dyn PhysicalExpr> {
+        self.spec.on()
+    }
+
+    /// The `WHEN` clauses, in order
+    pub fn clauses(&self) -> &[PhysicalMergeClause] {
+        self.spec.clauses()
+    }
+
+    /// Number of rows updated, deleted or inserted by the executions of this
+    /// plan, including the copies created by [`ExecutionPlan::with_new_children`]
+    pub fn rows_affected(&self) -> Count {
+        self.rows_affected.clone()
+    }
+}
+
+/// The join condition and `WHEN` clauses of a `MERGE INTO` statement,
+/// validated against the schemas of its target and source.
+#[derive(Debug, Clone)]
+pub struct MergeIntoSpec {
+    target_schema: SchemaRef,
+    source_schema: SchemaRef,
+    /// The join condition, evaluated against target columns followed by
+    /// source columns
+    on: Arc<dyn PhysicalExpr>,
+    /// The `WHEN` clauses, in order
+    clauses: Vec<PhysicalMergeClause>,
+    /// Equality predicates of `on`: target side evaluated against the target
+    /// and source side evaluated against the source
+    equi_keys: JoinOn,
+}
+
+impl MergeIntoSpec {
+    /// Creates a new [`MergeIntoSpec`] merging rows of `source_schema` into a
+    /// target of `target_schema`
+    pub fn try_new(
+        target_schema: SchemaRef,
+        source_schema: SchemaRef,
+        on: Arc<dyn PhysicalExpr>,
+        clauses: Vec<PhysicalMergeClause>,
+    ) -> Result<Self> {
+        let merge_schema = merge_schema(&target_schema, &source_schema);
+        if on.data_type(&merge_schema)? != DataType::Boolean {
+            return plan_err!("MERGE ON condition must be boolean");
+        }
+        for clause in &clauses {
+            match (&clause.action, clause.kind.canonical()) {
+                (PhysicalMergeAction::Insert(exprs), kind)
+                    if kind.is_not_matched_by_target() =>
+                {
+                    check_row_exprs(exprs, &target_schema, &merge_schema)?
+                }
+                (PhysicalMergeAction::Update(exprs), kind)
+                    if !kind.is_not_matched_by_target() =>
+                {
+                    check_row_exprs(exprs, &target_schema, &merge_schema)?
+                }
+                (PhysicalMergeAction::Delete, kind)
+                    if !kind.is_not_matched_by_target() => {}
+                (_, kind) => {
+                    return plan_err!("Invalid MERGE action for {kind:?} clause");
+                }
+            }
+        }
+        let equi_keys = equi_keys(&on, target_schema.fields().len(), &merge_schema)?;
+        Ok(Self {
+            target_schema,
+            source_schema,
+            on,
+            clauses,
+            equi_keys,
+        })
+    }
+
+    /// The join condition
+    pub fn on(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.on
+    }
+
+    /// The `WHEN` clauses, in order
+    pub fn clauses(&self) -> &[PhysicalMergeClause] {
+        &self.clauses
+    }
+
+    /// Whether the merge inserts rows, i.e. has `WHEN NOT MATCHED` clauses
+    pub fn inserts(&self) -> bool {
+        self.clauses
+            .iter()
+            .any(|clause| clause.kind.is_not_matched_by_target())
+    }
+
+    /// Creates a [`Merger`] executing this merge, accounting for the memory of
+    /// the source in the memory pool of `context`
+    pub fn merger(&self, context: &Arc<TaskContext>) -> Merger {
+        Merger {
+            spec: self.clone(),
+            batch_size: context.session_config().batch_size(),
+            reservation: MemoryConsumer::new("MergeIntoExec")
+                .register(context.memory_pool()),
+            source: None,
+        }
+    }
+
+    /// Builds the rows the merge expressions are evaluated against, with null
+    /// columns for a missing side
+    fn merge_rows(
+        &self,
+        target: Option<RecordBatch>,
+        source: Option<RecordBatch>,
+        num_rows: usize,
+    ) -> Result<RecordBatch> {
+        let side = |batch: Option<RecordBatch>, schema: &SchemaRef| match batch {
+            Some(batch) => batch.columns().to_vec(),
+            None => schema
+                .fields()
+                .iter()
+                .map(|field| new_null_array(field.data_type(), num_rows))
+                .collect(),
+        };
+        let mut columns = side(target, &self.target_schema);
+        columns.extend(side(source, &self.source_schema));
+        Ok(RecordBatch::try_new_with_options(
+            merge_schema(&self.target_schema, &self.source_schema),
+            columns,
+            &arrow::array::RecordBatchOptions::new().with_row_count(Some(num_rows)),
+        )?)
+    }
+
+    /// Evaluates the join condition on candidate (target row, source row)
+    /// pairs, returning the matching ones
+    fn join(
+        &self,
+        target: &RecordBatch,
+        source: &RecordBatch,
+        target_indices: &[u32],
+        source_indices: &[u32],
+    ) -> Result<(UInt32Array, UInt32Array)> {
+        let target_indices = UInt32Array::from(target_indices.to_vec());
+        let source_indices = UInt32Array::from(source_indices.to_vec());
+        let rows = self.merge_rows(
+            Some(take_record_batch(target, &target_indices)?),
+            Some(take_record_batch(source, &source_indices)?),
+            target_indices.len(),
+        )?;
+        let on = self.on.evaluate(&rows)?.into_array(rows.num_rows())?;
+        let on = null_as_false(on.as_boolean());
+        Ok((
+            filter(&target_indices, &on)?.as_primitive().clone(),
+            filter(&source_indices, &on)?.as_primitive().clone(),
+        ))
+    }
+
+    /// Applies the clauses of `kind` to `rows`, the first matching clause
+    /// winning. Returns the number of rows affected, the rows updated or
+    /// inserted, and the indices in `target_indices` of the target rows that
+    /// were updated or deleted.
+    fn apply_clauses(
+        &self,
+        kind: MergeIntoClauseKind,
+        rows: &RecordBatch,
+        target_indices: Option<&UInt32Array>,
+    ) -> Result<(usize, Vec<RecordBatch>, Vec<u32>)> {
+        let mut rows_affected = 0;
+        let mut output = vec![];
+        let mut replaced = vec![];
+        let mut remaining = BooleanArray::from(vec![true; rows.num_rows()]);
+        for clause in &self.clauses {
+            if clause.kind.canonical() != kind.canonical() {
+                continue;
+            }
+            if remaining.true_count() == 0 {
+                break;
+            }
+            // Only evaluate the predicate on the rows not handled by a
+            // previous clause
+            let selected = match &clause.predicate {
+                Some(predicate) => {
+                    let matches = predicate
+                        .evaluate_selection(rows, &remaining)?
+                        .into_array(rows.num_rows())?;
+                    and(&remaining, &null_as_false(matches.as_boolean()))?
+                }
+                None => remaining.clone(),
+            };
+            let count = selected.true_count();
+            if count == 0 {
+                continue;
+            }
+            remaining = and_not(&remaining, &selected)?;
+            rows_affected += count;
+
+            if let Some(target_indices) = target_indices {
+                replaced.extend(
+                    filter(target_indices, &selected)?
+                        .as_primitive::<UInt32Type>()
+                        .values(),
+                );
+            }
+            match &clause.action {
+                PhysicalMergeAction::Update(exprs)
+                | PhysicalMergeAction::Insert(exprs) => {
+                    let selected_rows = filter_record_batch(rows, &selected)?;
+                    let columns = exprs
+                        .iter()
+                        .map(|expr| {
+                            expr.evaluate(&selected_rows)?
+                                .into_array(selected_rows.num_rows())
+                        })
+                        .collect::<Result<Vec<ArrayRef>>>()?;
+                    output.push(RecordBatch::try_new_with_options(
+                        Arc::clone(&self.target_schema),
+                        columns,
+                        &arrow::array::RecordBatchOptions::new()
+                            .with_row_count(Some(selected_rows.num_rows())),
+                    )?);
+                }
+                PhysicalMergeAction::Delete => {}
+            }
+        }
+        Ok((rows_affected, output, replaced))
+    }
+}
+
+/// Schema of the rows the expressions of a [`MergeIntoExec`] are evaluated
+/// against: the target columns followed by the source columns, all nullable
+fn merge_schema(target_schema: &Schema, source_schema: &Schema) -> SchemaRef {
+    let fields = target_schema
+        .fields()
+        .iter()
+        .chain(source_schema.fields())
+        .map(|field| field.as_ref().clone().with_nullable(true))
+        .collect::<Vec<Field>>();
+    Arc::new(Schema::new(fields))
+}
+
+/// Checks that `exprs` compute a row of the target table
+fn check_row_exprs(
+    exprs: &[Arc<dyn PhysicalExpr>],
+    target_schema: &Schema,
+    merge_schema: &Schema,
+) -> Result<()> {
+    if exprs.len() != target_schema.fields().len() {
+        return plan_err!(
+            "MERGE action has {} expression(s) but the target has {} column(s)",
+            exprs.len(),
+            target_schema.fields().len()
+        );
+    }
+    for (expr, field) in exprs.iter().zip(target_schema.fields()) {
+        let data_type = expr.data_type(merge_schema)?;
+        if &data_type != field.data_type() {
+            return plan_err!(
+                "MERGE value for column '{}' has type {data_type}, expected {}",
+                field.name(),
+                field.data_type()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Extracts the `target_expr = source_expr` conjuncts of `on`, rewriting the
+/// source side to be evaluated against the source alone
+fn equi_keys(
+    on: &Arc<dyn PhysicalExpr>,
+    num_target_columns: usize,
+    merge_schema: &Schema,
+) -> Result<JoinOn> {
+    // Whether `expr` only references target (`Some(true)`) or source
+    // (`Some(false)`) columns
+    let side = |expr: &Arc<dyn PhysicalExpr>| {
+        let columns = collect_columns(expr);
+        if columns.is_empty() {
+            None
+        } else if columns.iter().all(|c| c.index() < num_target_columns) {
+            Some(true)
+        } else if columns.iter().all(|c| c.index() >= num_target_columns) {
+            Some(false)
+        } else {
+            None
+        }
+    };
+
+    let mut keys = vec![];
+    for expr in split_conjunction(on) {
+        let Some(binary) = expr.downcast_ref::<BinaryExpr>() else {
+            continue;
+        };
+        if *binary.op() != Operator::Eq
+            || binary.left().data_type(merge_schema)?
+                != binary.right().data_type(merge_schema)?
+        {
+            continue;
+        }
+        let (target_key, source_key) = match (side(binary.left()), side(binary.right())) {
+            (Some(true), Some(false)) => (binary.left(), binary.right()),
+            (Some(false), Some(true)) => (binary.right(), binary.left()),
+            _ => continue,
+        };
+        let source_key = Arc::clone(source_key)
+            .transform(|expr| {
+                Ok(match expr.downcast_ref::<Column>() {
+                    Some(column) => Transformed::yes(Arc::new(Column::new(
+                        column.name(),
+                        column.index() - num_target_columns,
+                    )) as _),
+                    None => Transformed::no(expr),
+                })
+            })?
+            .data;
+        keys.push((Arc::clone(target_key), source_key));
+    }
+    Ok(keys)
+}
+
+impl DisplayAs for MergeIntoExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        let clauses = self
+            .clauses()
+            .iter()
+            .map(|clause| clause.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "MergeIntoExec: on={}, clauses=[{clauses}]", self.on())
+            }
+            DisplayFormatType::TreeRender => {
+                writeln!(f, "on={}", self.on())?;
+                write!(f, "clauses=[{clauses}]")
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for MergeIntoExec {
+    fn name(&self) -> &'static str {
+        "MergeIntoExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.target, &self.source]
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        self.input_distribution_requirements().into_per_child()
+    }
+
+    fn input_distribution_requirements(&self) -> InputDistributionRequirements {
+        InputDistributionRequirements::new(vec![
+            Distribution::SinglePartition,
+            Distribution::SinglePartition,
+        ])
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn apply_expressions(
+        &self,
+        f: &mut dyn FnMut(&Arc<dyn PhysicalExpr>) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        let mut tnr = f(self.on())?;
+        for clause in self.clauses() {
+            let exprs = match &clause.action {
+                PhysicalMergeAction::Update(exprs)
+                | PhysicalMergeAction::Insert(exprs) => exprs.as_slice(),
+                PhysicalMergeAction::Delete => &[],
+            };
+            for expr in clause.predicate.iter().chain(exprs) {
+                tnr = tnr.visit_sibling(|| f(expr))?;
+            }
+        }
+        Ok(tnr)
+    }
+
+    fn replace_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+        _: ReplaceChildrenOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        validate_child_count!(self, children);
+        let source = children.swap_remove(1);
+        let target = children.swap_remove(0);
+        Ok(Arc::new(Self {
+            target,
+            source,
+            spec: self.spec.clone(),
+            rows_affected: self.rows_affected.clone(),
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache: Arc::clone(&self.cache),
+        }))
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.replace_children(
+            children,
+            ReplaceChildrenOptions::new(ChildrenPropertiesMode::Recompute),
+        )
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        assert_eq_or_internal_err!(
+            partition,
+            0,
+            "MergeIntoExec invalid partition. Expected 0, got {partition}"
+        );
+        assert_eq_or_internal_err!(
+            self.target.output_partitioning().partition_count()
+                + self.source.output_partitioning().partition_count(),
+            2,
+            "Invalid MergeIntoExec, the inputs must have a single partition, \
+             consider using CoalescePartitionsExec or the EnforceDistribution rule"
+        );
+
+        let target = self.target.execute(0, Arc::clone(&context))?;
+        let source = self.source.execute(0, Arc::clone(&context))?;
+        MetricBuilder::new(&self.metrics).build(MetricValue::Count {
+            name: "rows_affected".into(),
+            count: self.rows_affected.clone(),
+        });
+        let mut merger = self.spec.merger(&context);
+        let rows_affected = self.rows_affected.clone();
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+
+        // Merges one target batch at a time, then inserts the unmatched
+        // source rows once the target is exhausted
+        let stream = stream::once(async move {
+            merger.load_source(source).await?;
+            Ok::<_, DataFusionError>(stream::try_unfold(
+                (merger, Some(target)),
+                move |(mut merger, target)| {
+                    let rows_affected = rows_affected.clone();
+                    async move {
+                        let Some(mut target) = target else {
+                            return Ok::<_, DataFusionError>(None);
+                        };
+                        match target.next().await.transpose()? {
+                            Some(batch) => {
+                                let merged = merger.merge_target(&batch)?;
+                                rows_affected.add(merged.rows_affected);
+                                let batches = std::iter::once(merged.kept)
+                                    .chain(merged.updated)
+                                    .collect::<Vec<_>>();
+                                Ok(Some((batches, (merger, Some(target)))))
+                            }
+                            None => {
+                                let (count, batches) = merger.inserted()?;
+                                rows_affected.add(count);
+                                Ok(Some((batches, (merger, None))))
+                            }
+                        }
+                    }
+                },
+            ))
+        })
+        .try_flatten()
+        .map_ok(|batches| stream::iter(batches.into_iter().map(Ok)))
+        .try_flatten()
+        .try_filter(|batch| futures::future::ready(batch.num_rows() > 0))
+        .inspect_ok(move |batch| baseline_metrics.record_output(batch.num_rows()));
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+}
+
+/// Indices of the source rows by encoded join key
+type KeyIndex = HashMap<Vec<u8>, Vec<u32>>;
+
+/// The source rows of a [`Merger`], indexed on the equality predicates of the
+/// join condition
+#[derive(Debug)]
+struct Source {
+    batch: RecordBatch,
+    /// Source rows by encoded key, or `None` to join every source row
+    index: Option<(RowConverter, KeyIndex)>,
+    /// Whether each source row matched a target row
+    matched: Vec<bool>,
+}
+
+/// Result of [`Merger::merge_target`]
+#[derive(Debug)]
+pub struct MergedBatch {
+    /// The target rows that were neither updated nor deleted
+    pub kept: RecordBatch,
+    /// The new values of the updated target rows
+    pub updated: Vec<RecordBatch>,
+    /// Number of target rows updated or deleted
+    pub rows_affected: usize,
+}
+
+/// Executes a merge described by a [`MergeIntoSpec`]: the source is loaded
+/// with [`Self::load_source`], then each batch of the target is merged with
+/// [`Self::merge_target`], and finally the source rows that did not match
+/// any target row are inserted by [`Self::inserted`].
+///
+/// Merging the same target row again returns the same result, so a target can
+/// be read once to find the rows affected and once more to rewrite them.
+#[derive(Debug)]
+pub struct Merger {
+    spec: MergeIntoSpec,
+    batch_size: usize,
+    reservation: MemoryReservation,
+    source: Option<Source>,
+}
+
+impl Merger {
+    /// Buffers and indexes the source
+    pub async fn load_source(&mut self, source: SendableRecordBatchStream) -> Result<()> {
+        let mut batches = vec![];
+        let mut source = source;
+        while let Some(batch) = source.next().await.transpose()? {
+            self.reservation.try_grow(batch.get_array_memory_size())?;
+            batches.push(batch);
+        }
+        let batch = concat_batches(&self.spec.source_schema, &batches)?;
+        drop(batches);
+        self.reservation.try_resize(batch.get_array_memory_size())?;
+        let num_rows = batch.num_rows();
+
+        let index = if self.spec.equi_keys.is_empty() {
+            None
+        } else {
+            let keys = self
+                .spec
+                .equi_keys
+                .iter()
+                .map(|(_, source_key)| source_key.evaluate(&batch)?.into_array(num_rows))
+                .collect::<Result<Vec<_>>>()?;
+            let converter = RowConverter::new(
+                keys.iter()
+                    .map(|key| SortField::new(key.data_type().clone()))
+                    .collect(),
+            )?;
+            let rows = converter.convert_columns(&keys)?;
+            self.reservation.try_grow(rows.size())?;
+            let mut index = KeyIndex::new();
+            for i in 0..num_rows {
+                // Null keys never match
+                if keys.iter().any(|key| key.is_null(i)) {
+                    continue;
+                }
+                index
+                    .entry(rows.row(i).as_ref().to_vec())
+                    .or_default()
+                    .push(i as u32);
+            }
+            Some((converter, index))
+        };
+
+        self.source = Some(Source {
+            batch,
+            index,
+            matched: vec![false; num_rows],
+        });
+        Ok(())
+    }
+
+    /// Merges a batch of the target with the source, returning the rows that
+    /// replace it
+    pub fn merge_target(&mut self, target: &RecordBatch) -> Result<MergedBatch> {
+        let Self {
+            spec,
+            batch_size,
+            source,
+            ..
+        } = self;
+        let Some(source) = source.as_mut() else {
+            return internal_err!("MERGE INTO source was not loaded");
+        };
+        let num_rows = target.num_rows();
+
+        // Join the target rows to their candidate source rows, `batch_size`
+        // pairs at a time. Each target row matches at most one source row.
+        let mut target_matched = vec![false; num_rows];
+        let mut matched_target = vec![];
+        let mut matched_source = vec![];
+        let mut target_indices = Vec::with_capacity(*batch_size);
+        let mut source_indices = Vec::with_capacity(*batch_size);
+        let mut join = |target_indices: &mut Vec<u32>,
+                        source_indices: &mut Vec<u32>,
+                        source: &mut Source|
+         -> Result<()> {
+            let (t, s) =
+                spec.join(target, &source.batch, target_indices, source_indices)?;
+            for (t, s) in t.values().iter().zip(s.values()) {
+                if std::mem::replace(&mut target_matched[*t as usize], true) {
+                    return exec_err!(
+                        "MERGE INTO: a target row matches more than one source row"
+                    );
+                }
+                source.matched[*s as usize] = true;
+                matched_target.push(*t);
+                matched_source.push(*s);
+            }
+            target_indices.clear();
+            source_indices.clear();
+            Ok(())
+        };
+
+        let keys = match &source.index {
+            Some((converter, _)) => {
+                let keys = spec
+                    .equi_keys
+                    .iter()
+                    .map(|(target_key, _)| {
+                        target_key.evaluate(target)?.into_array(num_rows)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let rows = converter.convert_columns(&keys)?;
+                Some((keys, rows))
+            }
+            None => None,
+        };
+        let num_source_rows = source.batch.num_rows() as u32;
+        for t in 0..num_rows {
+            let candidates: Vec<u32> = match (&keys, &source.index) {
+                (Some((keys, rows)), Some((_, index))) => {
+                    if keys.iter().any(|key| key.is_null(t)) {
+                        continue;
+                    }
+                    match index.get(rows.row(t).as_ref()) {
+                        Some(candidates) => candidates.clone(),
+                        None => continue,
+                    }
+                }
+                _ => (0..num_source_rows).collect(),
+            };
+            for s in candidates {
+                target_indices.push(t as u32);
+                source_indices.push(s);
+                if target_indices.len() == *batch_size {
+                    join(&mut target_indices, &mut source_indices, source)?;
+                }
+            }
+        }
+        if !target_indices.is_empty() {
+            join(&mut target_indices, &mut source_indices, source)?;
+        }
+
+        let mut rows_affected = 0;
+        let mut updated = vec![];
+        let mut replaced = vec![false; num_rows];
+
+        // Matched target rows
+        if !matched_target.is_empty() {
+            let matched_target = UInt32Array::from(matched_target);
+            let matched_source = UInt32Array::from(matched_source);
+            let rows = spec.merge_rows(
+                Some(take_record_batch(target, &matched_target)?),
+                Some(take_record_batch(&source.batch, &matched_source)?),
+                matched_target.len(),
+            )?;
+            let (count, output, replaced_rows) = spec.apply_clauses(
+                MergeIntoClauseKind::Matched,
+                &rows,
+                Some(&matched_target),
+            )?;
+            rows_affected += count;
+            updated.extend(output);
+            for i in replaced_rows {
+                replaced[i as usize] = true;
+            }
+        }
+
+        // Target rows without matching source row
+        let unmatched = target_matched
+            .iter()
+            .enumerate()
+            .filter_map(|(i, matched)| (!matched).then_some(i as u32))
+            .collect::<UInt32Array>();
+        if !unmatched.is_empty() {
+            let rows = spec.merge_rows(
+                Some(take_record_batch(target, &unmatched)?),
+                None,
+                unmatched.len(),
+            )?;
+            let (count, output, replaced_rows) = spec.apply_clauses(
+                MergeIntoClauseKind::NotMatchedBySource,
+                &rows,
+                Some(&unmatched),
+            )?;
+            rows_affected += count;
+            updated.extend(output);
+            for i in replaced_rows {
+                replaced[i as usize] = true;
+            }
+        }
+
+        let kept = replaced
+            .iter()
+            .map(|replaced| Some(!replaced))
+            .collect::<BooleanArray>();
+        Ok(MergedBatch {
+            kept: filter_record_batch(target, &kept)?,
+            updated,
+            rows_affected,
+        })
+    }
+
+    /// Returns the number of rows inserted, and the rows inserted for the
+    /// source rows that did not match any target row
+    pub fn inserted(&self) -> Result<(usize, Vec<RecordBatch>)> {
+        let Some(source) = &self.source else {
+            return internal_err!("MERGE INTO source was not loaded");
+        };
+        let unmatched = source
+            .matched
+            .iter()
+            .enumerate()
+            .filter_map(|(i, matched)| (!matched).then_some(i as u32))
+            .collect::<UInt32Array>();
+        let mut rows_affected = 0;
+        let mut inserted = vec![];
+        for offset in (0..unmatched.len()).step_by(self.batch_size) {
+            let len = self.batch_size.min(unmatched.len() - offset);
+            let unmatched = unmatched.slice(offset, len);
+            let rows = self.spec.merge_rows(
+                None,
+                Some(take_record_batch(&source.batch, &unmatched)?),
+                len,
+            )?;
+            let (count, output, _) = self.spec.apply_clauses(
+                MergeIntoClauseKind::NotMatchedByTarget,
+                &rows,
+                None,
+            )?;
+            rows_affected += count;
+            inserted.extend(output);
+        }
+        Ok((rows_affected, inserted))
+    }
+}
+
+/// Converts the null values of a predicate result to `false`
+fn null_as_false(array: &BooleanArray) -> BooleanArray {
+    if array.null_count() > 0 {
+        prep_null_mask_filter(array)
+    } else {
+        array.clone()
+    }
+}
+
+/// Takes the rows at `indices` of every column of `batch`
+fn take_record_batch(batch: &RecordBatch, indices: &UInt32Array) -> Result<RecordBatch> {
+    Ok(arrow::compute::take_record_batch(batch, indices)?)
+}
+
+/// Executes its input, typically a sink writing the output of a
+/// [`MergeIntoExec`] to the target table, and returns a single row with the
+/// number of rows affected by the merge in a `count` column.
+#[derive(Debug)]
+pub struct MergeIntoResultExec {
+    input: Arc<dyn ExecutionPlan>,
+    rows_affected: Count,
+    cache: Arc<PlanProperties>,
+}
+
+impl MergeIntoResultExec {
+    /// Creates a new [`MergeIntoResultExec`] reporting `rows_affected`, see
+    /// [`MergeIntoExec::rows_affected`]
+    pub fn new(input: Arc<dyn ExecutionPlan>, rows_affected: Count) -> Self {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "count",
+            DataType::UInt64,
+            false,
+        )]));
+        let cache = Arc::new(PlanProperties::new(
+            EquivalenceProperties::new(schema),
+            Partitioning::UnknownPartitioning(1),
+            EmissionType::Final,
+            Boundedness::Bounded,
+        ));
+        Self {
+            input,
+            rows_affected,
+            cache,
+        }
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+}
+
+impl DisplayAs for MergeIntoResultExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "MergeIntoResultExec")
+            }
+            DisplayFormatType::TreeRender => write!(f, ""),
+        }
+    }
+}
+
+impl ExecutionPlan for MergeIntoResultExec {
+    fn name(&self) -> &'static str {
+        "MergeIntoResultExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.cache
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn apply_expressions(
+        &self,
+        _f: &mut dyn FnMut(&Arc<dyn PhysicalExpr>) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        Ok(TreeNodeRecursion::Continue)
+    }
+
+    fn replace_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+        _: ReplaceChildrenOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        validate_child_count!(self, children);
+        Ok(Arc::new(Self {
+            input: children.swap_remove(0),
+            rows_affected: self.rows_affected.clone(),
+            cache: Arc::clone(&self.cache),
+        }))
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.replace_children(
+            children,
+            ReplaceChildrenOptions::new(ChildrenPropertiesMode::Recompute),
+        )
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return internal_err!(
+                "MergeIntoResultExec invalid partition. Expected 0, got {partition}"
+            );
+        }
+        let input = execute_stream(Arc::clone(&self.input), context)?;
+        let rows_affected = self.rows_affected.clone();
+        let schema = self.schema();
+        let stream = stream::once(async move {
+            let before = rows_affected.value();
+            input.try_for_each(|_| async { Ok(()) }).await?;
+            let count = (rows_affected.value() - before) as u64;
+            Ok(RecordBatch::try_new(
+                schema,
+                vec![Arc::new(UInt64Array::from(vec![count]))],
+            )?)
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collect;
+    use crate::test::TestMemoryExec;
+
+    use arrow::array::{Int32Array, StringArray};
+    use datafusion_common::{DataFusionError, ScalarValue};
+    use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
+    use datafusion_physical_expr::expressions::{binary, col, lit};
+
+    fn target_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new("val", DataType::Utf8, true),
+        ]))
+    }
+
+    fn memory_exec(ids: Vec<i32>, vals: Vec<&str>) -> Arc<dyn ExecutionPlan> {
+        let schema = target_schema();
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(Int32Array::from(ids)),
+                Arc::new(StringArray::from(vals)),
+            ],
+        )
+        .unwrap();
+        TestMemoryExec::try_new_exec(&[vec![batch]], schema, None).unwrap()
+    }
+
+    /// `MERGE INTO target USING source ON target.id = source.id` with the
+    /// given clauses, returning the sorted new target rows and rows affected
+    async fn merge(
+        target: Arc<dyn ExecutionPlan>,
+        source: Arc<dyn ExecutionPlan>,
+        on: Option<Arc<dyn PhysicalExpr>>,
+        clauses: impl FnOnce(&Schema) -> Vec<PhysicalMergeClause>,
+    ) -> Result<(Vec<(i32, String)>, usize)> {
+        merge_with_context(target, source, on, clauses, TaskContext::default()).await
+    }
+
+    /// Same as [`merge`], executed in `context`
+    async fn merge_with_context(
+        target: Arc<dyn ExecutionPlan>,
+        source: Arc<dyn ExecutionPlan>,
+        on: Option<Arc<dyn PhysicalExpr>>,
+        clauses: impl FnOnce(&Schema) -> Vec<PhysicalMergeClause>,
+        context: TaskContext,
+    ) -> Result<(Vec<(i32, String)>, usize)> {
+        let schema = merge_schema(&target.schema(), &source.schema());
+        let on = match on {
+            Some(on) => on,
+            None => binary(
+                Arc::new(Column::new("id", 0)),
+                Operator::Eq,
+                Arc::new(Column::new("id", 2)),
+                &schema,
+            )?,
+        };
+        let exec = Arc::new(MergeIntoExec::try_new(
+            target,
+            source,
+            on,
+            clauses(&schema),
+        )?);
+        let batches = collect(Arc::clone(&exec) as _, Arc::new(context)).await?;
+        let mut rows = vec![];
+        for batch in batches {
+            let ids = batch
+                .column(0)
+                .as_primitive::<arrow::datatypes::Int32Type>();
+            let vals = batch.column(1).as_string::<i32>();
+            for i in 0..batch.num_rows() {
+                rows.push((ids.value(i), vals.value(i).to_string()));
+            }
+        }
+        rows.sort();
+        Ok((rows, exec.rows_affected().value()))
+    }
+
+    fn source_columns() -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![
+            Arc::new(Column::new("id", 2)),
+            Arc::new(Column::new("val", 3)),
+        ]
+    }
+
+    fn update_val() -> PhysicalMergeAction {
+        PhysicalMergeAction::Update(vec![
+            Arc::new(Column::new("id", 0)),
+            Arc::new(Column::new("val", 3)),
+        ])
+    }
+
+    fn rows(rows: &[(i32, &str)]) -> Vec<(i32, String)> {
+        rows.iter()
+            .map(|(id, val)| (*id, val.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn merge_update_insert_delete() -> Result<()> {
+        let target = memory_exec(vec![1, 2, 3], vec!["a", "b", "c"]);
+        let source = memory_exec(vec![2, 3, 4], vec!["x", "y", "z"]);
+        let (rows_after, affected) = merge(target, source, None, |schema| {
+            vec![
+                PhysicalMergeClause {
+                    kind: MergeIntoClauseKind::Matched,
+                    predicate: Some(
+                        binary(col("id", schema).unwrap(), Operator::Eq, lit(3), schema)
+                            .unwrap(),
+                    ),
+                    action: PhysicalMergeAction::Delete,
+                },
+                PhysicalMergeClause {
+                    kind: MergeIntoClauseKind::Matched,
+                    predicate: None,
+                    action: update_val(),
+                },
+                PhysicalMergeClause {
+                    kind: MergeIntoClauseKind::NotMatched,
+                    predicate: None,
+                    action: PhysicalMergeAction::Insert(source_columns()),
+                },
+            ]
+        })
+        .await?;
+        assert_eq!(rows_after, rows(&[(1, "a"), (2, "x"), (4, "z")]));
+        assert_eq!(affected, 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn merge_not_matched_by_source() -> Result<()> {
+        let target = memory_exec(vec![1, 2, 3], vec!["a", "b", "c"]);
+        let source = memory_exec(vec![2], vec!["x"]);
+        let (rows_after, affected) = merge(target, source, None, |schema| {
+            vec![
+                PhysicalMergeClause {
+                    kind: MergeIntoClauseKind::NotMatchedBySource,
+                    predicate: Some(
+                        binary(col("id", schema).unwrap(), Operator::Eq, lit(1), schema)
+                            .unwrap(),
+                    ),
+                    action: PhysicalMergeAction::Update(vec![
+                        Arc::new(Column::new("id", 0)),
+                        lit(ScalarValue::from("orphan")),
+                    ]),
+                },
+                PhysicalMergeClause {
+                    kind: MergeIntoClauseKind::NotMatchedBySource,
+                    predicate: None,
+                    action: PhysicalMergeAction::Delete,
+                },
+            ]
+        })
+        .await?;
+        assert_eq!(rows_after, rows(&[(1, "orphan"), (2, "b")]));
+        assert_eq!(affected, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn merge_without_equi_keys() -> Result<()> {
+        let target = memory_exec(vec![1, 2, 3], vec!["a", "b", "c"]);
+        let source = memory_exec(vec![2], vec!["x"]);
+        let schema = merge_schema(&target.schema(), &source.schema());
+        // target.id > source.id
+        let on = binary(
+            Arc::new(Column::new("id", 0)),
+            Operator::Gt,
+            Arc::new(Column::new("id", 2)),
+            &schema,
+        )?;
+        let (rows_after, affected) = merge(target, source, Some(on), |_| {
+            vec![PhysicalMergeClause {
+                kind: MergeIntoClauseKind::Matched,
+                predicate: None,
+                action: update_val(),
+            }]
+        })
+        .await?;
+        assert_eq!(rows_after, rows(&[(1, "a"), (2, "b"), (3, "x")]));
+        assert_eq!(affected, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn merge_multiple_matches() -> Result<()> {
+        let target = memory_exec(vec![1, 2], vec!["a", "b"]);
+        let source = memory_exec(vec![2, 2], vec!["x", "y"]);
+        let err = merge(target, source, None, |_| {
+            vec![PhysicalMergeClause {
+                kind: MergeIntoClauseKind::Matched,
+                predicate: None,
+                action: update_val(),
+            }]
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Execution error: MERGE INTO: a target row matches more than one source row"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn merge_without_equi_keys_in_batches() -> Result<()> {
+        let target = memory_exec(vec![1, 2, 3, 4, 5], vec!["a", "b", "c", "d", "e"]);
+        let source = memory_exec(vec![2, 4, 6], vec!["x", "y", "z"]);
+        let schema = merge_schema(&target.schema(), &source.schema());
+        // target.id >= source.id AND target.id <= source.id
+        let on = binary(
+            binary(
+                Arc::new(Column::new("id", 0)),
+                Operator::GtEq,
+                Arc::new(Column::new("id", 2)),
+                &schema,
+            )?,
+            Operator::And,
+            binary(
+                Arc::new(Column::new("id", 0)),
+                Operator::LtEq,
+                Arc::new(Column::new("id", 2)),
+                &schema,
+            )?,
+            &schema,
+        )?;
+        // The 15 candidate pairs are joined 2 at a time
+        let context = TaskContext::default()
+            .with_session_config(SessionConfig::new().with_batch_size(2));
+        let (rows_after, affected) = merge_with_context(
+            target,
+            source,
+            Some(on),
+            |_| {
+                vec![
+                    PhysicalMergeClause {
+                        kind: MergeIntoClauseKind::Matched,
+                        predicate: None,
+                        action: update_val(),
+                    },
+                    PhysicalMergeClause {
+                        kind: MergeIntoClauseKind::NotMatched,
+                        predicate: None,
+                        action: PhysicalMergeAction::Insert(source_columns()),
+                    },
+                ]
+            },
+            context,
+        )
+        .await?;
+        assert_eq!(
+            rows_after,
+            rows(&[(1, "a"), (2, "x"), (3, "c"), (4, "y"), (5, "e"), (6, "z")])
+        );
+        assert_eq!(affected, 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn merge_source_exceeds_memory_limit() -> Result<()> {
+        let target = memory_exec(vec![1], vec!["a"]);
+        let source = memory_exec((0..1000).collect(), vec!["x"; 1000]);
+        let runtime = RuntimeEnvBuilder::default()
+            .with_memory_limit(1024, 1.0)
+            .build_arc()?;
+        let err = merge_with_context(
+            target,
+            source,
+            None,
+            |_| {
+                vec![PhysicalMergeClause {
+                    kind: MergeIntoClauseKind::Matched,
+                    predicate: None,
+                    action: update_val(),
+                }]
+            },
+            TaskContext::default().with_runtime(runtime),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(err.find_root(), DataFusionError::ResourcesExhausted(_)),
+            "{err}"
+        );
+        Ok(())
+    }
+}
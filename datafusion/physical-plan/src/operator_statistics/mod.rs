@@ -41,11 +41,13 @@
 //!
 //! The following providers are included and can be registered in this order:
 //!
-//! 1. [`FilterStatisticsProvider`] - selectivity-based filter estimation
+//! 1. [`FilterStatisticsProvider`] - selectivity-based filter estimation, using
+//!    [`ColumnHistograms`] when available
 //! 2. [`ProjectionStatisticsProvider`] - column mapping through projections
 //! 3. [`PassthroughStatisticsProvider`] - passthrough for cardinality-preserving operators
 //! 4. [`AggregateStatisticsProvider`] - NDV-based GROUP BY cardinality estimation
-//! 5. [`JoinStatisticsProvider`] - NDV-based join output estimation (hash, sort-merge, cross)
+//! 5. [`JoinStatisticsProvider`] - histogram and NDV-based join output estimation
+//!    (hash, sort-merge, cross)
 //! 6. [`LimitStatisticsProvider`] - caps output at the fetch limit (local and global)
 //! 7. [`UnionStatisticsProvider`] - sums input row counts
 //! 8. [`DefaultStatisticsProvider`] - fallback to `partition_statistics(None)`
@@ -86,12 +88,19 @@
 //! let stats = registry.compute(plan.as_ref())?;
 //! ```
 
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt::{self, Debug};
+use std::ops::Bound;
 use std::sync::Arc;
 
 use datafusion_common::extensions::Extensions;
-use datafusion_common::stats::Precision;
-use datafusion_common::{Result, Statistics};
+use datafusion_common::stats::{Histogram, Precision};
+use datafusion_common::{Result, ScalarValue, Statistics};
+use datafusion_expr::Operator;
+use datafusion_physical_expr::expressions::{BinaryExpr, Column, Literal};
+use datafusion_physical_expr::utils::collect_columns;
+use datafusion_physical_expr::{PhysicalExpr, conjunction, split_conjunction};
 
 use crate::ExecutionPlan;
 use crate::statistics::{StatisticsArgs, StatisticsContext};
@@ -197,6 +206,43 @@ impl From<ExtendedStatistics> for Statistics {
     }
 }
 
+/// [`ExtendedStatistics`] extension with the histograms of the output columns
+/// of a plan, for example the ones computed by `ANALYZE TABLE`.
+///
+/// Used by [`FilterStatisticsProvider`] to estimate the selectivity of
+/// comparisons between a column and a literal.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnHistograms {
+    histograms: Vec<Option<Arc<Histogram>>>,
+}
+
+impl ColumnHistograms {
+    /// Create a new `ColumnHistograms` with one entry per output column.
+    pub fn new(histograms: Vec<Option<Arc<Histogram>>>) -> Self {
+        Self { histograms }
+    }
+
+    /// Returns the histogram of the column at `index`, if any.
+    pub fn get(&self, index: usize) -> Option<&Arc<Histogram>> {
+        self.histograms.get(index).and_then(Option::as_ref)
+    }
+
+    /// Returns `true` if no column has a histogram.
+    pub fn is_empty(&self) -> bool {
+        self.histograms.iter().all(Option::is_none)
+    }
+
+    /// Returns the histograms of the columns at `indices`.
+    pub fn project(&self, indices: &[usize]) -> Self {
+        Self::new(
+            indices
+                .iter()
+                .map(|&i| self.histograms.get(i).cloned().flatten())
+                .collect(),
+        )
+    }
+}
+
 // ============================================================================
 // StatisticsProvider trait and registry
 // ============================================================================
@@ -376,8 +422,23 @@ impl StatisticsRegistry {
                 .collect::<Result<Vec<_>>>()?
         };
 
+        self.compute_with_child_stats(plan, &child_stats)
+    }
+
+    /// Compute extended statistics for a single plan node through the provider
+    /// chain, using already computed statistics for its children.
+    ///
+    /// Unlike [`Self::compute`], this does not walk the plan's inputs, which
+    /// lets callers that build many candidate plans over the same inputs (e.g.
+    /// join enumeration) reuse the statistics of those inputs. `child_stats`
+    /// must be in the same order as `plan.children()`.
+    pub fn compute_with_child_stats(
+        &self,
+        plan: &dyn ExecutionPlan,
+        child_stats: &[ExtendedStatistics],
+    ) -> Result<ExtendedStatistics> {
         for provider in &self.providers {
-            match provider.compute_statistics(plan, &child_stats)? {
+            match provider.compute_statistics(plan, child_stats)? {
                 StatisticsResult::Computed(stats) => return Ok(stats),
                 StatisticsResult::Delegate => continue,
             }
@@ -542,14 +603,41 @@ impl StatisticsProvider for FilterStatisticsProvider {
 
         let input_stats = (*child_stats[0].base).clone();
         let input_rows = input_stats.num_rows;
+        let input_schema = filter.input().schema();
         let mut stats = FilterExec::statistics_helper(
-            &filter.input().schema(),
-            input_stats,
+            &input_schema,
+            input_stats.clone(),
             filter.predicate(),
             filter.default_selectivity(),
             // TODO: pass filter.expression_analyzer_registry() once #21122 lands
         )?;
 
+        // Refine the row count with the histograms of the input columns
+        let histograms = child_stats[0]
+            .get_extension::<ColumnHistograms>()
+            .filter(|histograms| !histograms.is_empty());
+        if let Some(histograms) = histograms
+            && let Some(&orig_rows) = input_rows.get_value()
+            && let Some(estimate) =
+                HistogramSelectivity::estimate(filter.predicate(), histograms, orig_rows)
+        {
+            let residual_rows = if estimate.residual.is_empty() {
+                orig_rows as f64
+            } else {
+                FilterExec::statistics_helper(
+                    &input_schema,
+                    input_stats,
+                    &conjunction(estimate.residual),
+                    filter.default_selectivity(),
+                )?
+                .num_rows
+                .get_value()
+                .map_or(orig_rows as f64, |&rows| rows as f64)
+            };
+            let rows = (residual_rows * estimate.selectivity).round() as usize;
+            rescale_byte_size(&mut stats, Precision::Inexact(rows));
+        }
+
         // Adjust distinct_count for each column using the selectivity ratio
         // via the probabilistic survival model from
         // ndv_after_selectivity to account for rows removed by the filter.
@@ -568,10 +656,212 @@ impl StatisticsProvider for FilterStatisticsProvider {
         }
 
         let stats = stats.project(filter.projection().as_ref());
-        Ok(StatisticsResult::Computed(ExtendedStatistics::new(stats)))
+        let mut stats = ExtendedStatistics::new(stats);
+        if let Some(histograms) = histograms {
+            // The filtered columns no longer follow their histograms, the
+            // other columns are assumed to be independent of the predicate
+            let filtered = collect_columns(filter.predicate());
+            let histograms = ColumnHistograms::new(
+                (0..input_schema.fields().len())
+                    .map(|i| {
+                        histograms
+                            .get(i)
+                            .filter(|_| !filtered.iter().any(|c| c.index() == i))
+                            .cloned()
+                    })
+                    .collect(),
+            );
+            let histograms = match filter.projection() {
+                Some(projection) => histograms.project(projection),
+                None => histograms,
+            };
+            if !histograms.is_empty() {
+                stats.set_extension(histograms);
+            }
+        }
+        Ok(StatisticsResult::Computed(stats))
+    }
+}
+
+/// Selectivity of the `column <op> literal` conjuncts of a predicate,
+/// estimated from the histograms of the columns.
+struct HistogramSelectivity {
+    /// Combined selectivity of the estimated conjuncts
+    selectivity: f64,
+    /// Conjuncts that could not be estimated from histograms
+    residual: Vec<Arc<dyn PhysicalExpr>>,
+}
+
+/// Bounds on a column collected from the conjuncts of a predicate.
+#[derive(Default)]
+struct ColumnBounds {
+    conjuncts: Vec<Arc<dyn PhysicalExpr>>,
+    equal: Option<ScalarValue>,
+    lower: Option<(ScalarValue, bool)>,
+    upper: Option<(ScalarValue, bool)>,
+}
+
+impl HistogramSelectivity {
+    /// Returns `None` if no conjunct of `predicate` can be estimated.
+    fn estimate(
+        predicate: &Arc<dyn PhysicalExpr>,
+        histograms: &ColumnHistograms,
+        num_rows: usize,
+    ) -> Option<Self> {
+        let mut residual = vec![];
+        let mut bounds: HashMap<usize, ColumnBounds> = HashMap::new();
+        for conjunct in split_conjunction(predicate) {
+            match column_comparison(conjunct) {
+                Some((index, op, value))
+                    if histograms.get(index).is_some() && !value.is_null() =>
+                {
+                    let bounds = bounds.entry(index).or_default();
+                    bounds.conjuncts.push(Arc::clone(conjunct));
+                    if !bounds.add(op, value) {
+                        // not comparable, leave it to the default estimation
+                        residual.append(&mut bounds.conjuncts);
+                        bounds.equal = None;
+                        bounds.lower = None;
+                        bounds.upper = None;
+                    }
+                }
+                _ => residual.push(Arc::clone(conjunct)),
+            }
+        }
+
+        let mut selectivity = 1.0;
+        let mut estimated = false;
+        for (index, bounds) in bounds {
+            if bounds.conjuncts.is_empty() {
+                continue;
+            }
+            let histogram = histograms.get(index)?;
+            match bounds.selectivity(histogram) {
+                Some(column_selectivity) => {
+                    // the histogram only covers the non null values
+                    let non_null = if num_rows > 0 {
+                        (histogram.total_count() as f64 / num_rows as f64).min(1.0)
+                    } else {
+                        1.0
+                    };
+                    selectivity *= column_selectivity * non_null;
+                    estimated = true;
+                }
+                None => residual.extend(bounds.conjuncts),
+            }
+        }
+        estimated.then_some(Self {
+            selectivity,
+            residual,
+        })
+    }
+}
+
+impl ColumnBounds {
+    /// Adds `column <op> value`, returning `false` if `value` can not be
+    /// compared with the existing bounds.
+    fn add(&mut self, op: Operator, value: ScalarValue) -> bool {
+        fn tighter(
+            current: &mut Option<(ScalarValue, bool)>,
+            value: ScalarValue,
+            inclusive: bool,
+            wanted: Ordering,
+        ) -> bool {
+            let replace = match current {
+                None => true,
+                Some((existing, existing_inclusive)) => {
+                    match value.partial_cmp(existing) {
+                        Some(Ordering::Equal) => *existing_inclusive && !inclusive,
+                        Some(ordering) => ordering == wanted,
+                        None => return false,
+                    }
+                }
+            };
+            if replace {
+                *current = Some((value, inclusive));
+            }
+            true
+        }
+        match op {
+            Operator::Eq => match &self.equal {
+                Some(existing) => existing.partial_cmp(&value).is_some(),
+                None => {
+                    self.equal = Some(value);
+                    true
+                }
+            },
+            Operator::Gt => tighter(&mut self.lower, value, false, Ordering::Greater),
+            Operator::GtEq => tighter(&mut self.lower, value, true, Ordering::Greater),
+            Operator::Lt => tighter(&mut self.upper, value, false, Ordering::Less),
+            Operator::LtEq => tighter(&mut self.upper, value, true, Ordering::Less),
+            _ => false,
+        }
+    }
+
+    fn selectivity(&self, histogram: &Histogram) -> Option<f64> {
+        fn bound((value, inclusive): &(ScalarValue, bool)) -> Bound<&ScalarValue> {
+            if *inclusive {
+                Bound::Included(value)
+            } else {
+                Bound::Excluded(value)
+            }
+        }
+        match &self.equal {
+            Some(value) => {
+                let in_range = histogram.range_selectivity(
+                    self.lower.as_ref().map_or(Bound::Unbounded, bound),
+                    self.upper.as_ref().map_or(Bound::Unbounded, bound),
+                )?;
+                let in_lower = self.lower.as_ref().is_none_or(|(lower, inclusive)| {
+                    match value.partial_cmp(lower) {
+                        Some(Ordering::Greater) => true,
+                        Some(Ordering::Equal) => *inclusive,
+                        _ => false,
+                    }
+                });
+                let in_upper = self.upper.as_ref().is_none_or(|(upper, inclusive)| {
+                    match value.partial_cmp(upper) {
+                        Some(Ordering::Less) => true,
+                        Some(Ordering::Equal) => *inclusive,
+                        _ => false,
+                    }
+                });
+                if !(in_lower && in_upper) || in_range == 0.0 {
+                    return Some(0.0);
+                }
+                histogram.equality_selectivity(value)
+            }
+            None => histogram.range_selectivity(
+                self.lower.as_ref().map_or(Bound::Unbounded, bound),
+                self.upper.as_ref().map_or(Bound::Unbounded, bound),
+            ),
+        }
     }
 }
 
+/// Matches `column <op> literal` and `literal <op> column`, returning the
+/// column index, the operator (with the column on the left) and the literal.
+fn column_comparison(
+    expr: &Arc<dyn PhysicalExpr>,
+) -> Option<(usize, Operator, ScalarValue)> {
+    let binary = expr.downcast_ref::<BinaryExpr>()?;
+    let left = binary.left();
+    let right = binary.right();
+    if let (Some(column), Some(literal)) = (
+        left.downcast_ref::<Column>(),
+        right.downcast_ref::<Literal>(),
+    ) {
+        return Some((column.index(), *binary.op(), literal.value().clone()));
+    }
+    let (Some(literal), Some(column)) = (
+        left.downcast_ref::<Literal>(),
+        right.downcast_ref::<Column>(),
+    ) else {
+        return None;
+    };
+    Some((column.index(), binary.op().swap()?, literal.value().clone()))
+}
+
 /// Statistics provider for [`ProjectionExec`](crate::projection::ProjectionExec)
 /// that uses pre-computed enhanced child statistics from the registry walk.
 ///
@@ -603,7 +893,25 @@ impl StatisticsProvider for ProjectionStatisticsProvider {
         let stats = proj
             .projection_expr()
             .project_statistics(input_stats, &output_schema)?;
-        Ok(StatisticsResult::Computed(ExtendedStatistics::new(stats)))
+        let mut stats = ExtendedStatistics::new(stats);
+        if let Some(histograms) = child_stats[0].get_extension::<ColumnHistograms>() {
+            let histograms = ColumnHistograms::new(
+                proj.expr()
+                    .iter()
+                    .map(|proj_expr| {
+                        proj_expr
+                            .expr
+                            .downcast_ref::<Column>()
+                            .and_then(|column| histograms.get(column.index()))
+                            .cloned()
+                    })
+                    .collect(),
+            );
+            if !histograms.is_empty() {
+                stats.set_extension(histograms);
+            }
+        }
+        Ok(StatisticsResult::Computed(stats))
     }
 }
 
@@ -740,8 +1048,11 @@ impl StatisticsProvider for AggregateStatisticsProvider {
 ///
 /// For equi-joins, estimates output cardinality as
 /// `left_rows * right_rows / product(max(left_ndv_i, right_ndv_i))`
-/// across all join key columns (assuming independence between keys),
-/// falling back to the Cartesian product when any key lacks NDV on both sides.
+/// across all join key columns (assuming independence between keys).
+/// Keys with [`ColumnHistograms`] on both sides use the overlap of the
+/// histograms instead of NDV, see [`Histogram::join_selectivity`]. Falls back
+/// to the Cartesian product when any key lacks both histograms and NDV on both
+/// sides.
 /// For cross joins, uses the exact Cartesian product.
 ///
 /// The base inner-join estimate is then adjusted for the join type:
@@ -771,24 +1082,54 @@ impl StatisticsProvider for JoinStatisticsProvider {
             return Ok(StatisticsResult::Delegate);
         }
 
-        let left = &child_stats[0].base;
-        let right = &child_stats[1].base;
+        let left = &child_stats[0];
+        let right = &child_stats[1];
 
-        let (Some(&left_rows), Some(&right_rows)) =
-            (left.num_rows.get_value(), right.num_rows.get_value())
-        else {
+        let (Some(&left_rows), Some(&right_rows)) = (
+            left.base.num_rows.get_value(),
+            right.base.num_rows.get_value(),
+        ) else {
             return Ok(StatisticsResult::Delegate);
         };
 
         use crate::joins::JoinOnRef;
 
-        /// Estimate equi-join output using NDV of join key columns:
+        /// Selectivity of an equi-join on the columns `left_key` and
+        /// `right_key` estimated from their [`ColumnHistograms`], which only
+        /// cover the non null values
+        fn histogram_selectivity(
+            left_key: &Column,
+            right_key: &Column,
+            left: &ExtendedStatistics,
+            right: &ExtendedStatistics,
+            left_rows: usize,
+            right_rows: usize,
+        ) -> Option<f64> {
+            let left_histogram = left
+                .get_extension::<ColumnHistograms>()?
+                .get(left_key.index())?;
+            let right_histogram = right
+                .get_extension::<ColumnHistograms>()?
+                .get(right_key.index())?;
+            let non_null = |histogram: &Histogram, rows: usize| {
+                (histogram.total_count() as f64 / rows.max(1) as f64).min(1.0)
+            };
+            Some(
+                left_histogram.join_selectivity(right_histogram)?
+                    * non_null(left_histogram, left_rows)
+                    * non_null(right_histogram, right_rows),
+            )
+        }
+
+        /// Estimate equi-join output from the overlap of the histograms of the
+        /// join key columns, or from their NDV:
         ///   left_rows * right_rows / product(max(left_ndv_i, right_ndv_i))
-        /// Falls back to Cartesian product if any key lacks NDV on both sides.
+        /// Falls back to Cartesian product if any key lacks both histograms
+        /// and NDV on both sides.
         fn equi_join_estimate(
             on: JoinOnRef,
-            left: &Statistics,
-            right: &Statistics,
+            left: &ExtendedStatistics,
+            right: &ExtendedStatistics,
             left_rows: usize,
             right_rows: usize,
         ) -> usize {
@@ -796,14 +1137,29 @@ impl StatisticsProvider for JoinStatisticsProvider {
                 return left_rows.saturating_mul(right_rows);
             }
             let mut ndv_divisor: usize = 1;
+            let mut selectivity = None;
             for (left_key, right_key) in on {
-                let left_ndv = left_key
-                    .downcast_ref::<Column>()
-                    .and_then(|c| left.column_statistics.get(c.index()))
+                let (Some(left_key), Some(right_key)) = (
+                    left_key.downcast_ref::<Column>(),
+                    right_key.downcast_ref::<Column>(),
+                ) else {
+                    return left_rows.saturating_mul(right_rows);
+                };
+                if let Some(key_selectivity) = histogram_selectivity(
+                    left_key, right_key, left, right, left_rows, right_rows,
+                ) {
+                    *selectivity.get_or_insert(1.0) *= key_selectivity;
+                    continue;
+                }
+                let left_ndv = left
+                    .base
+                    .column_statistics
+                    .get(left_key.index())
                     .and_then(|s| s.distinct_count.get_value().copied());
-                let right_ndv = right_key
-                    .downcast_ref::<Column>()
-                    .and_then(|c| right.column_statistics.get(c.index()))
+                let right_ndv = right
+                    .base
+                    .column_statistics
+                    .get(right_key.index())
                     .and_then(|s| s.distinct_count.get_value().copied());
                 match (left_ndv, right_ndv) {
                     (Some(l), Some(r)) if l > 0 && r > 0 => {
@@ -813,7 +1169,11 @@ impl StatisticsProvider for JoinStatisticsProvider {
                 }
             }
             let max_rows = left_rows.saturating_mul(right_rows);
-            max_rows.checked_div(ndv_divisor).unwrap_or(max_rows)
+            let rows = max_rows.checked_div(ndv_divisor).unwrap_or(max_rows);
+            match selectivity {
+                Some(selectivity) => (rows as f64 * selectivity).round() as usize,
+                None => rows,
+            }
         }
 
         let (inner_estimate, is_exact_cartesian, join_type) = if let Some(hash_join) =
@@ -826,8 +1186,8 @@ impl StatisticsProvider for JoinStatisticsProvider {
             let est = equi_join_estimate(smj.on(), left, right, left_rows, right_rows);
             (est, false, smj.join_type())
         } else if plan.downcast_ref::<CrossJoinExec>().is_some() {
-            let both_exact = left.num_rows.is_exact().unwrap_or(false)
-                && right.num_rows.is_exact().unwrap_or(false);
+            let both_exact = left.base.num_rows.is_exact().unwrap_or(false)
+                && right.base.num_rows.is_exact().unwrap_or(false);
             (
                 left_rows.saturating_mul(right_rows),
                 both_exact,
@@ -1435,6 +1795,137 @@ mod tests {
         Ok(())
     }
 
+    /// Source with 1000 rows: 900 rows with `a = 1` and 100 rows with
+    /// `a` in 101..=200, `b` uniformly distributed in 1..=800.
+    fn make_skewed_source_with_histograms() -> (Arc<dyn ExecutionPlan>, StatisticsRegistry)
+    {
+        let values = std::iter::repeat_n(1, 900)
+            .chain(101..=200)
+            .map(|v| ScalarValue::Int32(Some(v)))
+            .collect::<Vec<_>>();
+        let histogram = Arc::new(Histogram::try_new_equi_depth(&values, 10).unwrap());
+        let col_stats = vec![
+            ColumnStatistics::new_unknown()
+                .with_min_value(Precision::Exact(ScalarValue::Int32(Some(1))))
+                .with_max_value(Precision::Exact(ScalarValue::Int32(Some(200))))
+                .with_null_count(Precision::Exact(0)),
+            ColumnStatistics::new_unknown()
+                .with_min_value(Precision::Exact(ScalarValue::Int32(Some(1))))
+                .with_max_value(Precision::Exact(ScalarValue::Int32(Some(800))))
+                .with_null_count(Precision::Exact(0)),
+        ];
+        let source: Arc<dyn ExecutionPlan> = Arc::new(MockSourceExec::with_column_stats(
+            make_schema(),
+            Precision::Exact(1000),
+            col_stats,
+        ));
+        let source_provider = ClosureStatisticsProvider::new(move |plan, _| {
+            let Some(source) = plan.downcast_ref::<MockSourceExec>() else {
+                return Ok(StatisticsResult::Delegate);
+            };
+            let base =
+                StatisticsContext::new().compute(source, &StatisticsArgs::new())?;
+            let mut stats = ExtendedStatistics::new_arc(base);
+            stats.set_extension(ColumnHistograms::new(vec![
+                Some(Arc::clone(&histogram)),
+                None,
+            ]));
+            Ok(StatisticsResult::Computed(stats))
+        });
+        let registry = StatisticsRegistry::with_providers(vec![
+            Arc::new(source_provider),
+            Arc::new(FilterStatisticsProvider),
+            Arc::new(ProjectionStatisticsProvider),
+            Arc::new(DefaultStatisticsProvider),
+        ]);
+        (source, registry)
+    }
+
+    #[test]
+    fn test_filter_uses_histograms() -> Result<()> {
+        let (source, registry) = make_skewed_source_with_histograms();
+
+        // a < 10: interval analysis assumes a uniform distribution in [1, 200]
+        // and estimates ~45 rows, the histogram knows 900 rows have a = 1
+        let predicate = Arc::new(BinaryExpr::new(
+            col("a", &source.schema())?,
+            Operator::Lt,
+            lit(ScalarValue::Int32(Some(10))),
+        ));
+        let filter: Arc<dyn ExecutionPlan> =
+            Arc::new(FilterExec::try_new(predicate, Arc::clone(&source))?);
+        let stats = registry.compute(filter.as_ref())?;
+        assert_eq!(stats.base.num_rows, Precision::Inexact(900));
+        // the filtered column no longer follows its histogram
+        assert!(stats.get_extension::<ColumnHistograms>().is_none());
+
+        // 1 = a: equality on the heavy hitter, with the literal on the left
+        let predicate = Arc::new(BinaryExpr::new(
+            lit(ScalarValue::Int32(Some(1))),
+            Operator::Eq,
+            col("a", &source.schema())?,
+        ));
+        let filter: Arc<dyn ExecutionPlan> =
+            Arc::new(FilterExec::try_new(predicate, Arc::clone(&source))?);
+        let stats = registry.compute(filter.as_ref())?;
+        assert_eq!(stats.base.num_rows, Precision::Inexact(900));
+
+        // a < 10 AND b <= 400: b has no histogram and is estimated from its
+        // interval, which halves the estimate
+        let predicate = Arc::new(BinaryExpr::new(
+            Arc::new(BinaryExpr::new(
+                col("a", &source.schema())?,
+                Operator::Lt,
+                lit(ScalarValue::Int32(Some(10))),
+            )),
+            Operator::And,
+            Arc::new(BinaryExpr::new(
+                col("b", &source.schema())?,
+                Operator::LtEq,
+                lit(ScalarValue::Int32(Some(400))),
+            )),
+        ));
+        let filter: Arc<dyn ExecutionPlan> =
+            Arc::new(FilterExec::try_new(predicate, Arc::clone(&source))?);
+        let stats = registry.compute(filter.as_ref())?;
+        assert_eq!(stats.base.num_rows, Precision::Inexact(450));
+        Ok(())
+    }
+
+    #[test]
+    fn test_histograms_propagate_through_filter_and_projection() -> Result<()> {
+        let (source, registry) = make_skewed_source_with_histograms();
+
+        // filter on b keeps the histogram of a
+        let predicate = Arc::new(BinaryExpr::new(
+            col("b", &source.schema())?,
+            Operator::LtEq,
+            lit(ScalarValue::Int32(Some(400))),
+        ));
+        let filter: Arc<dyn ExecutionPlan> =
+            Arc::new(FilterExec::try_new(predicate, source)?);
+        // SELECT b, a AS c
+        let projection: Arc<dyn ExecutionPlan> = Arc::new(ProjectionExec::try_new(
+            vec![
+                (col("b", &filter.schema())?, "b".to_string()),
+                (col("a", &filter.schema())?, "c".to_string()),
+            ],
+            Arc::clone(&filter),
+        )?);
+        let predicate = Arc::new(BinaryExpr::new(
+            col("c", &projection.schema())?,
+            Operator::Gt,
+            lit(ScalarValue::Int32(Some(150))),
+        ));
+        let filter: Arc<dyn ExecutionPlan> =
+            Arc::new(FilterExec::try_new(predicate, projection)?);
+
+        // 1000 * 0.5 (b <= 400) * 0.05 (a > 150)
+        let stats = registry.compute(filter.as_ref())?;
+        assert_eq!(stats.base.num_rows, Precision::Inexact(25));
+        Ok(())
+    }
+
     #[test]
     fn test_projection_statistics_propagation() -> Result<()> {
         let engine = StatisticsRegistry::new();
@@ -1963,6 +2454,20 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_join_provider_uses_histograms() -> Result<()> {
+        // Self join of the skewed column without NDV: the 900 rows with a = 1
+        // match each other, and the 100 distinct values in 101..=200 match
+        // once, instead of the Cartesian product of 1_000_000 rows
+        let (source, mut registry) = make_skewed_source_with_histograms();
+        registry.register(Arc::new(JoinStatisticsProvider));
+        let join = make_hash_join(Arc::clone(&source), source)?;
+
+        let stats = registry.compute(join.as_ref())?;
+        assert_eq!(stats.base.num_rows, Precision::Inexact(900 * 900 + 100));
+        Ok(())
+    }
+
     #[test]
     fn test_nl_join_delegates() -> Result<()> {
         use crate::joins::NestedLoopJoinExec;
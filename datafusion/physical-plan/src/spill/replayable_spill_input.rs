@@ -72,7 +72,8 @@ use crate::spill::spill_manager::SpillManager;
 ///
 /// # Concurrency assumption
 /// Passes must be opened and consumed sequentially.
-/// Opening another pass before exhausting the current one returns an error.
+/// Opening another pass before exhausting (or dropping) the current one returns
+/// an error.
 pub(crate) struct ReplayableStreamSource {
     schema: SchemaRef,
     input: Option<SendableRecordBatchStream>,
@@ -314,14 +315,17 @@ impl RecordBatchStream for ReplayableSpillStream {
 }
 
 impl Drop for ReplayableSpillStream {
-    /// If a stream is dropped before it finishes, poison the state so later
-    /// replay attempts fail.
+    /// If the first pass is dropped before it finishes, poison the state so
+    /// later replay attempts fail.
     ///
     /// A partial first pass leaves the spill file incomplete, so replaying it
-    /// would be unsafe.
+    /// would be unsafe. A partial replay leaves the spill file intact, so a
+    /// later pass can still replay it.
     fn drop(&mut self) {
-        if self.held_state.is_some() {
+        if self.spill_file.is_some() {
             self.poison();
+        } else {
+            self.restore_held_state();
         }
     }
 }
@@ -444,4 +448,36 @@ mod tests {
 
         Ok(())
     }
+
+    // Drop a replay pass before it finishes, then replay again.
+    // The spill file is complete, so the next pass replays all batches.
+    #[tokio::test]
+    async fn test_replayable_spill_input_replays_after_replay_pass_dropped() -> Result<()>
+    {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch1 = build_batch(Arc::clone(&schema), vec![1, 2])?;
+        let batch2 = build_batch(Arc::clone(&schema), vec![3, 4])?;
+
+        let input = Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(&schema),
+            futures::stream::iter(vec![Ok(batch1.clone()), Ok(batch2.clone())]),
+        ));
+        let spill_manager = build_spill_manager(Arc::clone(&schema))?;
+        let mut replayable =
+            ReplayableStreamSource::new(input, spill_manager, "test replayable spill");
+
+        let pass1 = replayable.open_pass()?;
+        let _ = pass1.try_collect::<Vec<_>>().await?;
+
+        let mut pass2 = replayable.open_pass()?;
+        let first = pass2.next().await.transpose()?;
+        assert_eq!(first, Some(batch1.clone()));
+        drop(pass2);
+
+        let pass3 = replayable.open_pass()?;
+        let pass3_batches = pass3.try_collect::<Vec<_>>().await?;
+        assert_eq!(pass3_batches, vec![batch1, batch2]);
+
+        Ok(())
+    }
 }
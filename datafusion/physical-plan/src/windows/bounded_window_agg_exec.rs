@@ -26,8 +26,12 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 
+use super::spill::{PartialPartition, can_replay, partition_at_a_time_stream};
 use super::utils::create_schema;
-use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet, SpillMetrics,
+};
+use crate::spill::spill_manager::{GetSlicedSize, SpillManager};
 use crate::statistics::{ChildStats, StatisticsArgs};
 use crate::stream::EmptyRecordBatchStream;
 use crate::windows::{
@@ -56,8 +60,10 @@ use datafusion_common::utils::{
 };
 use datafusion_common::{
     HashMap, Result, ScalarValue, arrow_datafusion_err, exec_datafusion_err, exec_err,
+    internal_err,
 };
 use datafusion_execution::TaskContext;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_expr::ColumnarValue;
 use datafusion_expr::window_state::{PartitionBatchState, WindowAggState};
 use datafusion_physical_expr::window::{
@@ -290,11 +296,11 @@ impl BoundedWindowAggExec {
                         "All partition by columns should have an ordering in Sorted mode."
                     );
                 }
-                Box::new(SortedSearch {
+                Box::new(SortedSearch::new(
                     partition_by_sort_keys,
                     ordered_partition_by_indices,
                     input_schema,
-                })
+                ))
             }
             InputOrderMode::Linear | InputOrderMode::PartiallySorted(_) => Box::new(
                 LinearSearch::new(ordered_partition_by_indices, input_schema),
@@ -513,9 +519,25 @@ impl ExecutionPlan for BoundedWindowAggExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let input = self.input.execute(partition, context)?;
+        let input = self.input.execute(partition, Arc::clone(&context))?;
         let search_mode = self.get_search_algo()?;
-        let stream = Box::pin(BoundedWindowAggStream::new(
+        // Partitions arrive one after another in `Sorted` mode, so the
+        // partition that outgrows memory can be spilled and evaluated from disk
+        let spill_manager = (self.input_order_mode == InputOrderMode::Sorted
+            && context.runtime_env().disk_manager.tmp_files_enabled())
+        .then(|| {
+            SpillManager::new(
+                context.runtime_env(),
+                SpillMetrics::new(&self.metrics, partition),
+                input.schema(),
+            )
+            .with_compression_type(context.session_config().spill_compression())
+        });
+        let reservation =
+            MemoryConsumer::new(format!("BoundedWindowAggExec[{partition}]"))
+                .with_can_spill(spill_manager.is_some())
+                .register(context.memory_pool());
+        let mut stream = BoundedWindowAggStream::new(
             Arc::clone(&self.schema),
             self.window_expr.clone(),
             input,
@@ -523,8 +545,16 @@ impl ExecutionPlan for BoundedWindowAggExec {
             search_mode,
             partition,
             self.state_observer.clone(),
-        )?);
-        Ok(stream)
+            reservation,
+        )?;
+        if let Some(spill_manager) = spill_manager {
+            stream = stream.with_spilling(
+                spill_manager,
+                self.partition_by_sort_keys()?,
+                self.ordered_partition_by_indices.clone(),
+            );
+        }
+        Ok(Box::pin(stream))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -626,7 +656,7 @@ impl ExecutionPlan for BoundedWindowAggExec {
 
 /// Trait that specifies how we search for (or calculate) partitions. It has two
 /// implementations: [`SortedSearch`] and [`LinearSearch`].
-trait PartitionSearcher: Send {
+pub(super) trait PartitionSearcher: Send {
     /// This method constructs output columns using the result of each window expression
     /// (each entry in the output vector comes from a window expression).
     /// Executor when producing output concatenates `input_buffer` (corresponding section), and
@@ -1106,6 +1136,18 @@ impl PartitionSearcher for SortedSearch {
 }
 
 impl SortedSearch {
+    pub(super) fn new(
+        partition_by_sort_keys: Vec<PhysicalSortExpr>,
+        ordered_partition_by_indices: Vec<usize>,
+        input_schema: SchemaRef,
+    ) -> Self {
+        Self {
+            partition_by_sort_keys,
+            ordered_partition_by_indices,
+            input_schema,
+        }
+    }
+
     /// Calculates how many rows we can output.
     fn calculate_n_out_row(
         &mut self,
@@ -1215,6 +1257,23 @@ pub struct BoundedWindowAggStream {
     /// finalized per-window-expression state for every partition key that is
     /// about to be dropped.
     state_observer: Option<Arc<dyn WindowStateObserver>>,
+    /// Tracks the memory used by the rows buffered in `partition_buffers`,
+    /// e.g. for frames that reach far back into a large partition.
+    reservation: MemoryReservation,
+    /// Set by [`Self::with_spilling`] if the buffered partition can be
+    /// evaluated from disk once it no longer fits in `reservation`.
+    spill: Option<BoundedWindowSpill>,
+    /// Evaluates the rest of the input once the buffered partition was
+    /// handed over to [`partition_at_a_time_stream`].
+    fallback: Option<SendableRecordBatchStream>,
+}
+
+/// What a [`BoundedWindowAggStream`] needs to hand its buffered partition
+/// over to [`partition_at_a_time_stream`].
+struct BoundedWindowSpill {
+    spill_manager: SpillManager,
+    partition_by_sort_keys: Vec<PhysicalSortExpr>,
+    ordered_partition_by_indices: Vec<usize>,
 }
 
 impl BoundedWindowAggStream {
@@ -1284,7 +1343,8 @@ impl Stream for BoundedWindowAggStream {
 
 impl BoundedWindowAggStream {
     /// Create a new BoundedWindowAggStream
-    fn new(
+    #[expect(clippy::too_many_arguments)]
+    pub(super) fn new(
         schema: SchemaRef,
         window_expr: Vec<Arc<dyn WindowExpr>>,
         input: SendableRecordBatchStream,
@@ -1292,6 +1352,7 @@ impl BoundedWindowAggStream {
         search_mode: Box<dyn PartitionSearcher>,
         partition_idx: usize,
         state_observer: Option<Arc<dyn WindowStateObserver>>,
+        reservation: MemoryReservation,
     ) -> Result<Self> {
         let state = window_expr.iter().map(|_| IndexMap::default()).collect();
         let empty_batch = RecordBatch::new_empty(Arc::clone(&schema));
@@ -1308,9 +1369,102 @@ impl BoundedWindowAggStream {
             most_recent_row: None,
             partition_idx,
             state_observer,
+            reservation,
+            spill: None,
+            fallback: None,
         })
     }
 
+    /// Lets the stream spill the partition it buffers when the memory pool
+    /// cannot provide for it. Only valid if the input is sorted on the
+    /// `PARTITION BY` expressions, i.e. with a [`SortedSearch`].
+    pub(super) fn with_spilling(
+        mut self,
+        spill_manager: SpillManager,
+        partition_by_sort_keys: Vec<PhysicalSortExpr>,
+        ordered_partition_by_indices: Vec<usize>,
+    ) -> Self {
+        self.spill = can_replay(&self.window_expr).then_some(BoundedWindowSpill {
+            spill_manager,
+            partition_by_sort_keys,
+            ordered_partition_by_indices,
+        });
+        self
+    }
+
+    /// Resizes the memory reservation to the size of the buffered partition
+    /// rows. If the memory pool cannot provide it, the partition is spilled
+    /// when possible and otherwise kept in memory over the limit: frames
+    /// that reach far back need these rows either way.
+    fn update_reservation(&mut self) -> Result<()> {
+        // Buffered rows are usually slices of larger batches, only count the
+        // memory of the rows themselves
+        let size = self
+            .partition_buffers
+            .values()
+            .map(|state| state.record_batch.get_sliced_size())
+            .sum::<Result<usize>>()?;
+        if self.reservation.try_resize(size).is_ok() {
+            return Ok(());
+        }
+        if self.spill.is_some()
+            && self.state_observer.is_none()
+            && self.partition_buffers.len() <= 1
+        {
+            return self.start_spilling();
+        }
+        self.reservation.resize(size);
+        Ok(())
+    }
+
+    /// Hands the buffered partition and the rest of the input over to
+    /// [`partition_at_a_time_stream`], which spills the partition to disk.
+    fn start_spilling(&mut self) -> Result<()> {
+        let Some(spill) = self.spill.take() else {
+            return internal_err!("BoundedWindowAggStream cannot spill");
+        };
+        let partial = match self.partition_buffers.pop() {
+            Some((key, state)) => {
+                let batch = state.record_batch;
+                // Rows that are not emitted yet are the last buffered rows
+                let Some(skip) =
+                    batch.num_rows().checked_sub(self.input_buffer.num_rows())
+                else {
+                    return internal_err!(
+                        "Unemitted window rows are missing from the partition buffer"
+                    );
+                };
+                Some(PartialPartition {
+                    key,
+                    offset: state.n_rows_received - batch.num_rows(),
+                    skip,
+                    batch,
+                })
+            }
+            None => None,
+        };
+        for window_agg_state in self.window_agg_states.iter_mut() {
+            window_agg_state.clear();
+        }
+        let input_schema = self.input.schema();
+        self.input_buffer = RecordBatch::new_empty(Arc::clone(&input_schema));
+        let input = std::mem::replace(
+            &mut self.input,
+            Box::pin(EmptyRecordBatchStream::new(input_schema)),
+        );
+        self.fallback = Some(partition_at_a_time_stream(
+            Arc::clone(&self.schema),
+            self.window_expr.clone(),
+            input,
+            self.reservation.take(),
+            Some(spill.spill_manager),
+            spill.partition_by_sort_keys,
+            spill.ordered_partition_by_indices,
+            partial,
+        )?);
+        Ok(())
+    }
+
     fn compute_aggregates(&mut self) -> Result<Option<RecordBatch>> {
         // calculate window cols
         let eval_ctx = WindowEvalContext::default()
@@ -1366,6 +1520,14 @@ impl BoundedWindowAggStream {
         if self.finished {
             return Poll::Ready(None);
         }
+        if let Some(fallback) = self.fallback.as_mut() {
+            let poll = ready!(fallback.poll_next_unpin(cx));
+            if poll.is_none() {
+                self.finished = true;
+                self.fallback = None;
+            }
+            return Poll::Ready(poll);
+        }
 
         let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
         match ready!(self.input.poll_next_unpin(cx)) {
@@ -1383,7 +1545,9 @@ impl BoundedWindowAggStream {
                     &self.window_expr,
                     &mut self.partition_buffers,
                 )?;
-                if let Some(batch) = self.compute_aggregates()? {
+                let output = self.compute_aggregates()?;
+                self.update_reservation()?;
+                if let Some(batch) = output {
                     return Poll::Ready(Some(Ok(batch)));
                 }
                 self.poll_next_inner(cx)
@@ -1624,6 +1788,10 @@ mod tests {
         BoundedWindowAggExec, InputOrderMode, create_udwf_window_expr, create_window_expr,
     };
     use crate::{ExecutionPlan, WindowExpr, displayable, execute_stream};
+    use arrow::array::Int64Array;
+    use arrow::compute::concat_batches;
+    use datafusion_execution::memory_pool::FairSpillPool;
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
 
     use arrow::array::{
         RecordBatch,
@@ -3138,4 +3306,83 @@ mod tests {
         assert!(Arc::ptr_eq(result[0].1.column(0), single.column(0)));
         Ok(())
     }
+
+    /// `SUM(sn) OVER (ORDER BY sn ROWS BETWEEN n_preceding PRECEDING AND
+    /// CURRENT ROW)` over 20000 sorted rows
+    fn preceding_sum_exec(n_preceding: u64) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("sn", DataType::Int64, false)]));
+        let batches = (0..20_000)
+            .step_by(100)
+            .map(|start| {
+                RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![Arc::new(Int64Array::from_iter_values(start..start + 100))],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let sort_expr = PhysicalSortExpr::new_default(col("sn", &schema)?);
+        let source = TestMemoryExec::try_new(&[batches], Arc::clone(&schema), None)?
+            .try_with_sort_information(vec![[sort_expr.clone()].into()])?;
+        let source = Arc::new(TestMemoryExec::update_cache(&Arc::new(source)));
+        let window_expr = create_window_expr(
+            &WindowFunctionDefinition::AggregateUDF(sum_udaf()),
+            "sum(sn)".to_string(),
+            &[col("sn", &schema)?],
+            &[],
+            &[sort_expr],
+            Arc::new(WindowFrame::new_bounds(
+                WindowFrameUnits::Rows,
+                WindowFrameBound::Preceding(ScalarValue::UInt64(Some(n_preceding))),
+                WindowFrameBound::CurrentRow,
+            )),
+            schema,
+            false,
+            false,
+            None,
+        )?;
+        Ok(Arc::new(BoundedWindowAggExec::try_new(
+            vec![window_expr],
+            source,
+            InputOrderMode::Sorted,
+            false,
+        )?))
+    }
+
+    /// Runs `window` with a `FairSpillPool` of `pool_size` bytes, returning
+    /// the output and the number of spill files.
+    async fn run_with_pool(
+        window: &Arc<dyn ExecutionPlan>,
+        pool_size: usize,
+    ) -> Result<(Vec<RecordBatch>, usize)> {
+        let runtime = RuntimeEnvBuilder::new()
+            .with_memory_pool(Arc::new(FairSpillPool::new(pool_size)))
+            .build_arc()?;
+        let task_ctx = Arc::new(TaskContext::default().with_runtime(runtime));
+        let batches = collect(window.execute(0, task_ctx)?).await?;
+        let spill_count = window.metrics().unwrap().spill_count().unwrap_or(0);
+        Ok((batches, spill_count))
+    }
+
+    #[tokio::test]
+    async fn test_buffered_rows_respect_memory_limit() -> Result<()> {
+        // A short frame only buffers a few rows at a time, a frame reaching
+        // back far buffers more rows than the pool provides and spills them
+        for (n_preceding, expect_spill) in [(10, false), (5_000, true), (100_000, true)] {
+            let expected = collect(
+                preceding_sum_exec(n_preceding)?
+                    .execute(0, Arc::new(TaskContext::default()))?,
+            )
+            .await?;
+            let window = preceding_sum_exec(n_preceding)?;
+            let (actual, spill_count) = run_with_pool(&window, 20_000).await?;
+            assert_eq!(spill_count > 0, expect_spill, "{n_preceding} PRECEDING");
+            assert_eq!(
+                concat_batches(&window.schema(), &actual)?,
+                concat_batches(&window.schema(), &expected)?,
+                "{n_preceding} PRECEDING"
+            );
+        }
+        Ok(())
+    }
 }
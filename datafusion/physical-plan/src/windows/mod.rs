@@ -20,6 +20,7 @@
 mod bounded_window_agg_exec;
 #[cfg(feature = "proto")]
 mod proto;
+mod spill;
 mod utils;
 mod window_agg_exec;
 
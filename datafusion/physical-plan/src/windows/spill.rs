@@ -0,0 +1,1088 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Out-of-core evaluation for [`WindowAggExec`](super::WindowAggExec).
+//!
+//! When the buffered input of a `WindowAggExec` no longer fits in memory, the
+//! window expressions are evaluated over the (already partition-sorted) input
+//! stream instead of one concatenated batch. Depending on the expression:
+//!
+//! * Aggregates whose value is constant in a partition (e.g.
+//!   `SUM(x) OVER (PARTITION BY k)`) need every row of a partition before the
+//!   first row can be emitted. The input is read twice through a
+//!   [`ReplayableStreamSource`]: the first pass computes one value per
+//!   partition while caching the input to a spill file, the second pass
+//!   replays the spill file and attaches the values.
+//! * Expressions that support bounded execution (e.g. `ROW_NUMBER()`, running
+//!   `SUM`) are evaluated incrementally with a [`BoundedWindowAggStream`].
+//! * Any other expression is evaluated one partition at a time. A partition
+//!   that does not fit in memory is written to a spill file and, if every
+//!   expression is a `ROW_NUMBER()` or an aggregate over a `ROWS` frame or a
+//!   `RANGE` frame starting at `UNBOUNDED PRECEDING`, evaluated by reading the
+//!   file back once per frame boundary (see [`ReplayedWindowExpr`]).
+//!   Otherwise the largest partition has to fit in memory.
+//!
+//! A [`BoundedWindowAggStream`] that runs out of memory because frames keep
+//! too many rows of a partition buffered hands the partition over to the
+//! partition-at-a-time evaluation in the same way.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, BooleanArray, UInt64Array, new_empty_array};
+use arrow::compute::{concat, concat_batches, filter};
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::utils::{evaluate_partition_ranges, get_row_at_idx};
+use datafusion_common::{
+    DataFusionError, Result, ScalarValue, exec_datafusion_err, internal_err,
+};
+use datafusion_execution::memory_pool::MemoryReservation;
+use datafusion_execution::spill_file::SpillFile;
+use datafusion_expr::{Accumulator, WindowFrameBound, WindowFrameUnits};
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_expr::window::{
+    AggregateWindowExpr, PlainAggregateWindowExpr, SlidingAggregateWindowExpr,
+    StandardWindowExpr, WindowExpr,
+};
+use datafusion_physical_expr_common::sort_expr::PhysicalSortExpr;
+use futures::{StreamExt, TryStreamExt, stream};
+
+use super::WindowUDFExpr;
+use super::bounded_window_agg_exec::{BoundedWindowAggStream, SortedSearch};
+use super::utils::create_schema;
+use super::window_agg_exec::compute_partitioned_window_aggregates;
+use crate::SendableRecordBatchStream;
+use crate::metrics::BaselineMetrics;
+use crate::spill::in_progress_spill_file::InProgressSpillFile;
+use crate::spill::replayable_spill_input::ReplayableStreamSource;
+use crate::spill::spill_manager::{GetSlicedSize, SpillManager};
+use crate::stream::RecordBatchStreamAdapter;
+
+/// Splits partition-sorted input into the ranges of its `PARTITION BY` groups,
+/// remembering the key of the last group across batches.
+struct PartitionSplitter {
+    partition_by_sort_keys: Vec<PhysicalSortExpr>,
+    ordered_partition_by_indices: Vec<usize>,
+    last_key: Option<Vec<ScalarValue>>,
+}
+
+impl PartitionSplitter {
+    fn new(
+        partition_by_sort_keys: Vec<PhysicalSortExpr>,
+        ordered_partition_by_indices: Vec<usize>,
+    ) -> Self {
+        Self {
+            partition_by_sort_keys,
+            ordered_partition_by_indices,
+            last_key: None,
+        }
+    }
+
+    /// Returns the row ranges of the groups in `batch`, each with a flag that
+    /// is true when the range starts a new group (as opposed to continuing the
+    /// last group of the previous batch).
+    fn split(
+        &mut self,
+        batch: &RecordBatch,
+    ) -> Result<Vec<(std::ops::Range<usize>, bool)>> {
+        let sort_columns = self
+            .ordered_partition_by_indices
+            .iter()
+            .map(|idx| self.partition_by_sort_keys[*idx].evaluate_to_sort_column(batch))
+            .collect::<Result<Vec<_>>>()?;
+        let ranges = evaluate_partition_ranges(batch.num_rows(), &sort_columns)?;
+        let key_columns = sort_columns
+            .into_iter()
+            .map(|column| column.values)
+            .collect::<Vec<ArrayRef>>();
+
+        let mut result = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            let key = get_row_at_idx(&key_columns, range.start)?;
+            let is_new = self.last_key.as_ref() != Some(&key);
+            self.last_key = Some(key);
+            result.push((range, is_new));
+        }
+        Ok(result)
+    }
+
+    /// Treats the next rows with partition key `key` (in the order of the
+    /// `PARTITION BY` expressions) as continuing the current group.
+    fn continue_partition(&mut self, key: &[ScalarValue]) {
+        self.last_key = Some(
+            self.ordered_partition_by_indices
+                .iter()
+                .map(|idx| key[*idx].clone())
+                .collect(),
+        );
+    }
+}
+
+/// Returns a stream evaluating `window_expr` over `input` without buffering
+/// the whole input, see the [module documentation](self).
+#[expect(clippy::too_many_arguments)]
+pub(super) fn spilled_window_agg_stream(
+    schema: SchemaRef,
+    window_expr: Vec<Arc<dyn WindowExpr>>,
+    input: SendableRecordBatchStream,
+    spill_manager: SpillManager,
+    reservation: MemoryReservation,
+    partition_by_sort_keys: Vec<PhysicalSortExpr>,
+    ordered_partition_by_indices: Vec<usize>,
+    baseline_metrics: BaselineMetrics,
+    partition: usize,
+) -> SendableRecordBatchStream {
+    let output_schema = Arc::clone(&schema);
+    let stream = stream::once(async move {
+        let input_schema = input.schema();
+        let (constant_exprs, other_exprs): (Vec<_>, Vec<_>) = window_expr
+            .iter()
+            .cloned()
+            .enumerate()
+            .partition(|(_, expr)| is_constant_in_partition(expr.as_ref()));
+
+        // First pass: compute the partition-constant aggregates, caching the
+        // input to disk so that it can be replayed for the second pass.
+        let (constant_values, input) = if constant_exprs.is_empty() {
+            (VecDeque::new(), input)
+        } else {
+            let mut source = ReplayableStreamSource::new(
+                input,
+                spill_manager.clone(),
+                "WindowAggExec",
+            );
+            let splitter = PartitionSplitter::new(
+                partition_by_sort_keys.clone(),
+                ordered_partition_by_indices.clone(),
+            );
+            let values = compute_constant_aggregates(
+                source.open_pass()?,
+                &constant_exprs,
+                splitter,
+            )
+            .await?;
+            (values, source.open_pass()?)
+        };
+
+        // Second pass: evaluate the remaining expressions
+        let exprs = other_exprs
+            .iter()
+            .map(|(_, expr)| Arc::clone(expr))
+            .collect::<Vec<_>>();
+        let evaluated = if exprs.is_empty() {
+            input
+        } else if exprs.iter().all(|expr| expr.uses_bounded_memory()) {
+            let search_mode = Box::new(SortedSearch::new(
+                partition_by_sort_keys.clone(),
+                ordered_partition_by_indices.clone(),
+                Arc::clone(&input_schema),
+            ));
+            let stream = BoundedWindowAggStream::new(
+                Arc::new(create_schema(&input_schema, &exprs)?),
+                exprs,
+                input,
+                baseline_metrics,
+                search_mode,
+                partition,
+                None,
+                reservation,
+            )?
+            .with_spilling(
+                spill_manager,
+                partition_by_sort_keys,
+                ordered_partition_by_indices,
+            );
+            Box::pin(stream) as SendableRecordBatchStream
+        } else {
+            partition_at_a_time_stream(
+                Arc::new(create_schema(&input_schema, &exprs)?),
+                exprs,
+                input,
+                reservation,
+                Some(spill_manager),
+                partition_by_sort_keys,
+                ordered_partition_by_indices,
+                None,
+            )?
+        };
+
+        let mut assembler = OutputAssembler {
+            schema,
+            num_input_columns: input_schema.fields().len(),
+            num_window_exprs: window_expr.len(),
+            constant_expr_indices: constant_exprs.iter().map(|(idx, _)| *idx).collect(),
+            other_expr_indices: other_exprs.iter().map(|(idx, _)| *idx).collect(),
+            constant_values,
+        };
+        Ok::<_, DataFusionError>(evaluated.map(move |batch| assembler.assemble(&batch?)))
+    })
+    .try_flatten();
+
+    Box::pin(RecordBatchStreamAdapter::new(output_schema, stream))
+}
+
+/// Returns true if `expr` produces the same value for every row of a
+/// partition.
+fn is_constant_in_partition(expr: &dyn WindowExpr) -> bool {
+    expr.as_any()
+        .downcast_ref::<PlainAggregateWindowExpr>()
+        .is_some_and(|expr| expr.is_constant_in_partition())
+}
+
+/// Values of the partition-constant aggregates of one partition.
+struct ConstantPartitionValues {
+    num_rows: usize,
+    values: Vec<ScalarValue>,
+}
+
+/// Computes the value of every partition-constant aggregate for each
+/// partition of `input`, in input order.
+async fn compute_constant_aggregates(
+    mut input: SendableRecordBatchStream,
+    exprs: &[(usize, Arc<dyn WindowExpr>)],
+    mut splitter: PartitionSplitter,
+) -> Result<VecDeque<ConstantPartitionValues>> {
+    let exprs = exprs
+        .iter()
+        .map(|(_, expr)| {
+            expr.as_any()
+                .downcast_ref::<PlainAggregateWindowExpr>()
+                .ok_or_else(|| exec_datafusion_err!("Expected an aggregate window"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut partitions = VecDeque::new();
+    let mut current: Option<(usize, Vec<Box<dyn Accumulator>>)> = None;
+    while let Some(batch) = input.next().await {
+        let batch = batch?;
+        for (range, is_new) in splitter.split(&batch)? {
+            if is_new {
+                if let Some((num_rows, accumulators)) = current.take() {
+                    partitions.push_back(finish_partition(num_rows, accumulators)?);
+                }
+                let accumulators = exprs
+                    .iter()
+                    .map(|expr| expr.get_accumulator())
+                    .collect::<Result<Vec<_>>>()?;
+                current = Some((0, accumulators));
+            }
+            let Some((num_rows, accumulators)) = current.as_mut() else {
+                unreachable!("the first range always starts a new partition")
+            };
+            let slice = batch.slice(range.start, range.end - range.start);
+            for (expr, accumulator) in exprs.iter().zip(accumulators.iter_mut()) {
+                let values = evaluate_aggregate_args(*expr, expr.filter_expr(), &slice)?;
+                accumulator.update_batch(&values)?;
+            }
+            *num_rows += slice.num_rows();
+        }
+    }
+    if let Some((num_rows, accumulators)) = current {
+        partitions.push_back(finish_partition(num_rows, accumulators)?);
+    }
+    Ok(partitions)
+}
+
+fn finish_partition(
+    num_rows: usize,
+    accumulators: Vec<Box<dyn Accumulator>>,
+) -> Result<ConstantPartitionValues> {
+    let values = accumulators
+        .into_iter()
+        .map(|mut accumulator| accumulator.evaluate())
+        .collect::<Result<_>>()?;
+    Ok(ConstantPartitionValues { num_rows, values })
+}
+
+/// Rows of a partition whose evaluation was started by a
+/// [`BoundedWindowAggStream`] before it ran out of memory.
+pub(super) struct PartialPartition {
+    /// Key of the partition, in the order of the `PARTITION BY` expressions
+    pub(super) key: Vec<ScalarValue>,
+    /// Buffered rows of the partition that window frames may still need
+    pub(super) batch: RecordBatch,
+    /// Index of the first buffered row in the partition
+    pub(super) offset: usize,
+    /// Number of leading buffered rows whose output was already produced
+    pub(super) skip: usize,
+}
+
+/// Evaluates `window_expr` one `PARTITION BY` group at a time, buffering only
+/// the rows of the group that is still being received.
+///
+/// If a `spill_manager` is given and every expression can be evaluated by
+/// replaying the group (see [`ReplayedWindowExpr`]), a group that does not fit
+/// in memory is written to a spill file and evaluated by reading it back.
+/// Otherwise running out of memory is an error. Evaluation continues `partial`
+/// before reading `input`, which requires spilling.
+#[expect(clippy::too_many_arguments)]
+pub(super) fn partition_at_a_time_stream(
+    schema: SchemaRef,
+    window_expr: Vec<Arc<dyn WindowExpr>>,
+    input: SendableRecordBatchStream,
+    reservation: MemoryReservation,
+    spill_manager: Option<SpillManager>,
+    partition_by_sort_keys: Vec<PhysicalSortExpr>,
+    ordered_partition_by_indices: Vec<usize>,
+    partial: Option<PartialPartition>,
+) -> Result<SendableRecordBatchStream> {
+    let replay = spill_manager.and_then(|spill_manager| {
+        Some((
+            spill_manager,
+            ReplayedWindowExpr::try_new_all(&window_expr)?,
+        ))
+    });
+    let mut state = PartitionAtATime {
+        schema: Arc::clone(&schema),
+        window_expr,
+        input,
+        reservation,
+        splitter: PartitionSplitter::new(
+            partition_by_sort_keys.clone(),
+            ordered_partition_by_indices.clone(),
+        ),
+        partition_by_sort_keys,
+        ordered_partition_by_indices,
+        replay,
+        pending: PendingGroup::default(),
+        finished: false,
+    };
+    if let Some(partial) = partial {
+        // The buffered rows do not start at the beginning of their partition
+        // and can only be evaluated by replaying them
+        state.splitter.continue_partition(&partial.key);
+        state.pending = PendingGroup {
+            num_rows: partial.batch.num_rows(),
+            batches: vec![partial.batch],
+            spill: None,
+            offset: partial.offset,
+            skip: partial.skip,
+        };
+        state.spill_pending()?;
+    }
+
+    let stream = stream::try_unfold(state, |mut state| async move {
+        Ok::<_, DataFusionError>(state.next_output().await?.map(|output| (output, state)))
+    })
+    .try_flatten();
+    Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+}
+
+/// The rows of the `PARTITION BY` group that is still being received.
+#[derive(Default)]
+struct PendingGroup {
+    /// Rows of the group held in memory
+    batches: Vec<RecordBatch>,
+    /// Set once the group no longer fits in memory, all of its rows are
+    /// then written to this file
+    spill: Option<InProgressSpillFile>,
+    /// Number of rows in `batches` or `spill`
+    num_rows: usize,
+    /// Index of the first row in the partition
+    offset: usize,
+    /// Number of leading rows whose output was already produced
+    skip: usize,
+}
+
+struct PartitionAtATime {
+    schema: SchemaRef,
+    window_expr: Vec<Arc<dyn WindowExpr>>,
+    input: SendableRecordBatchStream,
+    reservation: MemoryReservation,
+    splitter: PartitionSplitter,
+    partition_by_sort_keys: Vec<PhysicalSortExpr>,
+    ordered_partition_by_indices: Vec<usize>,
+    /// Set if groups that do not fit in memory can be spilled and replayed
+    replay: Option<(SpillManager, Vec<ReplayedWindowExpr>)>,
+    pending: PendingGroup,
+    finished: bool,
+}
+
+impl PartitionAtATime {
+    /// Reads input until at least one group is complete and returns the
+    /// output of the complete groups, or `None` once all groups are done.
+    async fn next_output(&mut self) -> Result<Option<SendableRecordBatchStream>> {
+        while !self.finished {
+            let Some(batch) = self.input.next().await else {
+                // End of input: the pending rows form the last group
+                self.finished = true;
+                let group = std::mem::take(&mut self.pending);
+                self.reservation.free();
+                if group.num_rows == 0 {
+                    break;
+                }
+                return self.evaluate_groups(vec![group]).map(Some);
+            };
+            let batch = batch?;
+            if batch.num_rows() == 0 {
+                continue;
+            }
+
+            let mut complete = vec![];
+            for (range, is_new) in self.splitter.split(&batch)? {
+                if is_new && self.pending.num_rows > 0 {
+                    complete.push(std::mem::take(&mut self.pending));
+                    self.reservation.free();
+                }
+                self.push_pending(batch.slice(range.start, range.len()))?;
+            }
+            if !complete.is_empty() {
+                return self.evaluate_groups(complete).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Adds `rows` to the pending group, spilling the group if it no longer
+    /// fits in memory.
+    fn push_pending(&mut self, rows: RecordBatch) -> Result<()> {
+        self.pending.num_rows += rows.num_rows();
+        if let Some(file) = self.pending.spill.as_mut() {
+            file.append_batch(&rows)?;
+            return Ok(());
+        }
+        let grown = self.reservation.try_grow(rows.get_sliced_size()?);
+        self.pending.batches.push(rows);
+        match grown {
+            Ok(()) => Ok(()),
+            Err(_) if self.replay.is_some() => self.spill_pending(),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Moves the rows of the pending group from memory to a spill file.
+    fn spill_pending(&mut self) -> Result<()> {
+        let Some((spill_manager, _)) = &self.replay else {
+            return internal_err!("Window expressions cannot be replayed from disk");
+        };
+        let mut file = spill_manager.create_in_progress_file("WindowAggExec")?;
+        for batch in std::mem::take(&mut self.pending.batches) {
+            file.append_batch(&batch)?;
+        }
+        self.pending.spill = Some(file);
+        self.reservation.free();
+        Ok(())
+    }
+
+    /// Returns the output of the complete `groups`, in order.
+    fn evaluate_groups(
+        &self,
+        groups: Vec<PendingGroup>,
+    ) -> Result<SendableRecordBatchStream> {
+        let mut outputs = vec![];
+        let mut in_memory = vec![];
+        for group in groups {
+            let Some(mut file) = group.spill else {
+                in_memory.extend(group.batches);
+                continue;
+            };
+            if !in_memory.is_empty() {
+                outputs.push(self.evaluate_in_memory(&std::mem::take(&mut in_memory))?);
+            }
+            let Some((spill_manager, window_expr)) = &self.replay else {
+                return internal_err!("Window expressions cannot be replayed from disk");
+            };
+            let Some(file) = file.finish()? else {
+                continue;
+            };
+            outputs.push(replay_spilled_partition(
+                Arc::clone(&self.schema),
+                window_expr.clone(),
+                spill_manager,
+                &file,
+                group.offset,
+                group.offset + group.num_rows,
+                group.skip,
+            )?);
+        }
+        if !in_memory.is_empty() {
+            outputs.push(self.evaluate_in_memory(&in_memory)?);
+        }
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(&self.schema),
+            stream::iter(outputs).flatten(),
+        )))
+    }
+
+    /// Evaluates complete groups held in memory.
+    fn evaluate_in_memory(
+        &self,
+        batches: &[RecordBatch],
+    ) -> Result<SendableRecordBatchStream> {
+        let batch = concat_batches(&self.input.schema(), batches)?;
+        let output = compute_partitioned_window_aggregates(
+            &self.schema,
+            &self.window_expr,
+            &batch,
+            &self.partition_by_sort_keys,
+            &self.ordered_partition_by_indices,
+        )?;
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            Arc::clone(&self.schema),
+            stream::iter(output.map(Ok)),
+        )))
+    }
+}
+
+/// Returns true if a partition spilled to disk can be evaluated for every
+/// expression of `window_expr`, see [`ReplayedWindowExpr`].
+pub(super) fn can_replay(window_expr: &[Arc<dyn WindowExpr>]) -> bool {
+    ReplayedWindowExpr::try_new_all(window_expr).is_some()
+}
+
+/// A window expression that can be evaluated over a spilled partition by
+/// reading the partition back in order, without holding it in memory.
+#[derive(Clone)]
+enum ReplayedWindowExpr {
+    /// `ROW_NUMBER()`
+    RowNumber,
+    /// An aggregate whose frame starts `start` rows from the current row, or
+    /// at the start of the partition if `start` is `None`
+    Aggregate {
+        expr: Arc<dyn WindowExpr>,
+        start: Option<i64>,
+        end: ReplayedFrameBound,
+    },
+}
+
+/// A frame bound of a [`ReplayedWindowExpr`].
+#[derive(Clone, Copy)]
+enum ReplayedFrameBound {
+    /// This many rows after (or before, if negative) the current row
+    Rows(i64),
+    /// The last peer of the current row
+    Peers,
+    /// The start or the end of the partition
+    Unbounded,
+}
+
+impl ReplayedWindowExpr {
+    /// Returns `None` if any of `window_expr` cannot be replayed.
+    fn try_new_all(window_expr: &[Arc<dyn WindowExpr>]) -> Option<Vec<Self>> {
+        window_expr.iter().map(Self::try_new).collect()
+    }
+
+    fn try_new(expr: &Arc<dyn WindowExpr>) -> Option<Self> {
+        if is_row_number(expr.as_ref()) {
+            return Some(Self::RowNumber);
+        }
+        let aggregate = as_aggregate_window_expr(expr.as_ref())?;
+        let frame = expr.get_window_frame();
+        let (start, end) = match frame.units {
+            WindowFrameUnits::Rows => {
+                let start = match rows_bound(&frame.start_bound)? {
+                    ReplayedFrameBound::Rows(offset) => Some(offset),
+                    _ => None,
+                };
+                (start, rows_bound(&frame.end_bound)?)
+            }
+            WindowFrameUnits::Range if frame.start_bound.is_unbounded() => {
+                let end = match &frame.end_bound {
+                    WindowFrameBound::CurrentRow => ReplayedFrameBound::Peers,
+                    bound if bound.is_unbounded() => ReplayedFrameBound::Unbounded,
+                    _ => return None,
+                };
+                (None, end)
+            }
+            _ => return None,
+        };
+        // A moving frame start retracts the rows that leave the frame
+        if start.is_some() && !aggregate.get_accumulator().ok()?.supports_retract_batch()
+        {
+            return None;
+        }
+        Some(Self::Aggregate {
+            expr: Arc::clone(expr),
+            start,
+            end,
+        })
+    }
+}
+
+fn is_row_number(expr: &dyn WindowExpr) -> bool {
+    expr.as_any()
+        .downcast_ref::<StandardWindowExpr>()
+        .and_then(|expr| {
+            expr.get_standard_func_expr()
+                .as_any()
+                .downcast_ref::<WindowUDFExpr>()
+        })
+        .is_some_and(|expr| expr.fun().name() == "row_number")
+}
+
+fn as_aggregate_window_expr(expr: &dyn WindowExpr) -> Option<&dyn AggregateWindowExpr> {
+    let any = expr.as_any();
+    if let Some(expr) = any.downcast_ref::<PlainAggregateWindowExpr>() {
+        return Some(expr);
+    }
+    any.downcast_ref::<SlidingAggregateWindowExpr>()
+        .map(|expr| expr as &dyn AggregateWindowExpr)
+}
+
+/// Returns the offset of a `ROWS` frame bound from the current row, or
+/// `None` if the bound is not supported.
+fn rows_bound(bound: &WindowFrameBound) -> Option<ReplayedFrameBound> {
+    let offset = |value: &ScalarValue, sign: i64| match value {
+        value if value.is_null() => Some(ReplayedFrameBound::Unbounded),
+        ScalarValue::UInt64(Some(n)) => i64::try_from(*n)
+            .ok()
+            .map(|n| ReplayedFrameBound::Rows(sign * n)),
+        _ => None,
+    };
+    match bound {
+        WindowFrameBound::CurrentRow => Some(ReplayedFrameBound::Rows(0)),
+        WindowFrameBound::Preceding(value) => offset(value, -1),
+        WindowFrameBound::Following(value) => offset(value, 1),
+    }
+}
+
+/// Evaluates the arguments of an aggregate window expression over `batch`,
+/// dropping the rows rejected by its `FILTER` clause.
+fn evaluate_aggregate_args(
+    expr: &dyn WindowExpr,
+    filter_expr: Option<&Arc<dyn PhysicalExpr>>,
+    batch: &RecordBatch,
+) -> Result<Vec<ArrayRef>> {
+    let values = expr.evaluate_args(batch)?;
+    let Some(filter_expr) = filter_expr else {
+        return Ok(values);
+    };
+    let mask = filter_expr.evaluate(batch)?.into_array(batch.num_rows())?;
+    let mask = mask
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .ok_or_else(|| exec_datafusion_err!("Window FILTER must be a boolean"))?;
+    values
+        .iter()
+        .map(|values| Ok(filter(values, mask)?))
+        .collect()
+}
+
+/// Reads the rows of a spilled partition in order.
+struct ReplayCursor {
+    stream: SendableRecordBatchStream,
+    /// Rows of the last read batch that were not returned yet
+    current: Option<RecordBatch>,
+}
+
+impl ReplayCursor {
+    fn new(stream: SendableRecordBatchStream) -> Self {
+        Self {
+            stream,
+            current: None,
+        }
+    }
+
+    /// Returns up to `max_rows` of the next rows, or `None` at the end.
+    async fn next_slice(&mut self, max_rows: usize) -> Result<Option<RecordBatch>> {
+        loop {
+            match self.current.take() {
+                Some(batch) if batch.num_rows() > 0 => {
+                    let n = max_rows.min(batch.num_rows());
+                    if n < batch.num_rows() {
+                        self.current = Some(batch.slice(n, batch.num_rows() - n));
+                    }
+                    return Ok(Some(batch.slice(0, n)));
+                }
+                _ => match self.stream.next().await {
+                    Some(batch) => self.current = Some(batch?),
+                    None => return Ok(None),
+                },
+            }
+        }
+    }
+
+    /// Returns the next `n` rows.
+    async fn take(&mut self, mut n: usize) -> Result<Vec<RecordBatch>> {
+        let mut slices = vec![];
+        while n > 0 {
+            let Some(slice) = self.next_slice(n).await? else {
+                return internal_err!("Spilled window partition ended unexpectedly");
+            };
+            n -= slice.num_rows();
+            slices.push(slice);
+        }
+        Ok(slices)
+    }
+}
+
+/// Reads the peer groups of a spilled partition in order.
+struct PeerCursor {
+    cursor: ReplayCursor,
+    splitter: PartitionSplitter,
+    /// Slices of the last read batch that were not returned yet, each with a
+    /// flag that is true if the slice starts a new peer group
+    slices: VecDeque<(RecordBatch, bool)>,
+}
+
+impl PeerCursor {
+    /// Returns the rows of the next peer group.
+    async fn next_group(&mut self) -> Result<Vec<RecordBatch>> {
+        let mut group = vec![];
+        loop {
+            if self.slices.is_empty() {
+                let Some(batch) = self.cursor.next_slice(usize::MAX).await? else {
+                    break;
+                };
+                for (range, is_new) in self.splitter.split(&batch)? {
+                    self.slices
+                        .push_back((batch.slice(range.start, range.len()), is_new));
+                }
+            }
+            match self.slices.front() {
+                Some((_, true)) if !group.is_empty() => break,
+                Some(_) => group.extend(self.slices.pop_front().map(|(rows, _)| rows)),
+                None => {}
+            }
+        }
+        if group.is_empty() {
+            return internal_err!("Spilled window partition ended unexpectedly");
+        }
+        Ok(group)
+    }
+}
+
+/// State of a [`ReplayedWindowExpr`] while replaying a partition.
+enum ReplayState {
+    RowNumber,
+    Aggregate(Box<ReplayedAggregate>),
+}
+
+impl ReplayState {
+    fn try_new(
+        expr: ReplayedWindowExpr,
+        offset: usize,
+        open: &impl Fn() -> Result<ReplayCursor>,
+    ) -> Result<Self> {
+        let ReplayedWindowExpr::Aggregate { expr, start, end } = expr else {
+            return Ok(Self::RowNumber);
+        };
+        let Some(aggregate) = as_aggregate_window_expr(expr.as_ref()) else {
+            return internal_err!("Expected an aggregate window expression");
+        };
+        let aggregate_expr = match expr
+            .as_any()
+            .downcast_ref::<PlainAggregateWindowExpr>()
+        {
+            Some(plain) => plain.get_aggregate_expr(),
+            None => expr
+                .as_any()
+                .downcast_ref::<SlidingAggregateWindowExpr>()
+                .map(|sliding| sliding.get_aggregate_expr())
+                .ok_or_else(|| exec_datafusion_err!("Expected an aggregate window"))?,
+        };
+        let peers = match end {
+            ReplayedFrameBound::Peers => Some(PeerCursor {
+                cursor: open()?,
+                splitter: PartitionSplitter::new(
+                    expr.order_by().to_vec(),
+                    (0..expr.order_by().len()).collect(),
+                ),
+                slices: VecDeque::new(),
+            }),
+            _ => None,
+        };
+        Ok(Self::Aggregate(Box::new(ReplayedAggregate {
+            accumulator: aggregate.get_accumulator()?,
+            default_value: aggregate_expr
+                .default_value(aggregate_expr.field().data_type())?,
+            filter_expr: aggregate.filter_expr().cloned(),
+            add: open()?,
+            retract: start.is_some().then(open).transpose()?,
+            peers,
+            peer_value: None,
+            acc_start: offset,
+            acc_end: offset,
+            expr,
+            start,
+            end,
+        })))
+    }
+
+    /// Returns the values of the `len` rows starting with row `first` of a
+    /// partition of `num_rows` rows.
+    async fn evaluate(
+        &mut self,
+        first: usize,
+        len: usize,
+        num_rows: usize,
+    ) -> Result<ArrayRef> {
+        match self {
+            Self::RowNumber => Ok(Arc::new(UInt64Array::from_iter_values(
+                (first..first + len).map(|idx| idx as u64 + 1),
+            ))),
+            Self::Aggregate(aggregate) => {
+                let mut values = Vec::with_capacity(len);
+                for idx in first..first + len {
+                    values.push(aggregate.evaluate_row(idx, num_rows).await?);
+                }
+                ScalarValue::iter_to_array(values)
+            }
+        }
+    }
+}
+
+/// Evaluates an aggregate over the frames of consecutive rows, adding the
+/// rows that enter the frame and retracting the rows that leave it as they
+/// are read back from the spill file.
+struct ReplayedAggregate {
+    expr: Arc<dyn WindowExpr>,
+    filter_expr: Option<Arc<dyn PhysicalExpr>>,
+    start: Option<i64>,
+    end: ReplayedFrameBound,
+    accumulator: Box<dyn Accumulator>,
+    default_value: ScalarValue,
+    /// The accumulator holds the rows `acc_start..acc_end` of the partition
+    acc_start: usize,
+    acc_end: usize,
+    /// Reads the rows entering the frame, positioned at `acc_end`
+    add: ReplayCursor,
+    /// Reads the rows leaving the frame, positioned at `acc_start`
+    retract: Option<ReplayCursor>,
+    /// Reads the peer groups entering a frame that ends with the peers of
+    /// the current row, positioned at `acc_end`
+    peers: Option<PeerCursor>,
+    /// Value of the frames of the rows before `acc_end`, for peer frames
+    peer_value: Option<ScalarValue>,
+}
+
+impl ReplayedAggregate {
+    async fn evaluate_row(&mut self, idx: usize, num_rows: usize) -> Result<ScalarValue> {
+        let frame_idx = |offset: i64| {
+            (idx as i64)
+                .saturating_add(offset)
+                .clamp(0, num_rows as i64) as usize
+        };
+        let start = self.start.map_or(0, frame_idx);
+        if start < self.acc_start {
+            return internal_err!("Window frame starts before the replayed rows");
+        }
+
+        if let Some(peers) = self.peers.as_mut() {
+            if let Some(value) = &self.peer_value
+                && idx < self.acc_end
+            {
+                return Ok(value.clone());
+            }
+            while self.acc_end <= idx {
+                for rows in peers.next_group().await? {
+                    self.acc_end += rows.num_rows();
+                    let values = evaluate_aggregate_args(
+                        self.expr.as_ref(),
+                        self.filter_expr.as_ref(),
+                        &rows,
+                    )?;
+                    self.accumulator.update_batch(&values)?;
+                }
+            }
+            let value = self.accumulator.evaluate()?;
+            self.peer_value = Some(value.clone());
+            return Ok(value);
+        }
+
+        let end = match self.end {
+            ReplayedFrameBound::Rows(offset) => frame_idx(offset.saturating_add(1)),
+            _ => num_rows,
+        };
+        if start >= end {
+            return Ok(self.default_value.clone());
+        }
+        // Retract the rows that left the frame
+        let retract_end = start.min(self.acc_end);
+        if retract_end > self.acc_start {
+            let Some(retract) = self.retract.as_mut() else {
+                return internal_err!("Window frame start is not expected to move");
+            };
+            for rows in retract.take(retract_end - self.acc_start).await? {
+                let values = evaluate_aggregate_args(
+                    self.expr.as_ref(),
+                    self.filter_expr.as_ref(),
+                    &rows,
+                )?;
+                self.accumulator.retract_batch(&values)?;
+            }
+            self.acc_start = retract_end;
+        }
+        // Skip the rows between the previous frame and this one
+        if start > self.acc_end {
+            let gap = start - self.acc_end;
+            self.add.take(gap).await?;
+            if let Some(retract) = self.retract.as_mut() {
+                retract.take(gap).await?;
+            }
+            self.acc_start = start;
+            self.acc_end = start;
+        }
+        // Add the rows that entered the frame
+        if end > self.acc_end {
+            for rows in self.add.take(end - self.acc_end).await? {
+                let values = evaluate_aggregate_args(
+                    self.expr.as_ref(),
+                    self.filter_expr.as_ref(),
+                    &rows,
+                )?;
+                self.accumulator.update_batch(&values)?;
+            }
+            self.acc_end = end;
+        }
+        self.accumulator.evaluate()
+    }
+}
+
+/// Returns a stream evaluating `window_expr` over a spilled partition of
+/// `num_rows` rows, whose first spilled row has index `offset` in the
+/// partition. The output omits the first `skip` spilled rows.
+fn replay_spilled_partition(
+    schema: SchemaRef,
+    window_expr: Vec<ReplayedWindowExpr>,
+    spill_manager: &SpillManager,
+    file: &Arc<dyn SpillFile>,
+    offset: usize,
+    num_rows: usize,
+    skip: usize,
+) -> Result<SendableRecordBatchStream> {
+    let open = || {
+        spill_manager
+            .read_spill_as_stream(Arc::clone(file), None)
+            .map(ReplayCursor::new)
+    };
+    let states = window_expr
+        .into_iter()
+        .map(|expr| ReplayState::try_new(expr, offset, &open))
+        .collect::<Result<Vec<_>>>()?;
+    let state = ReplayedPartition {
+        schema: Arc::clone(&schema),
+        rows: open()?,
+        states,
+        next_row: offset,
+        num_rows,
+        skip,
+    };
+    let stream = stream::try_unfold(state, |mut state| async move {
+        Ok::<_, DataFusionError>(state.next_batch().await?.map(|batch| (batch, state)))
+    });
+    Ok(Box::pin(RecordBatchStreamAdapter::new(schema, stream)))
+}
+
+struct ReplayedPartition {
+    schema: SchemaRef,
+    rows: ReplayCursor,
+    states: Vec<ReplayState>,
+    /// Index of the next row of `rows` in the partition
+    next_row: usize,
+    num_rows: usize,
+    skip: usize,
+}
+
+impl ReplayedPartition {
+    async fn next_batch(&mut self) -> Result<Option<RecordBatch>> {
+        loop {
+            let Some(batch) = self.rows.next_slice(usize::MAX).await? else {
+                return Ok(None);
+            };
+            let skip = self.skip.min(batch.num_rows());
+            self.skip -= skip;
+            let first = self.next_row + skip;
+            self.next_row += batch.num_rows();
+            if skip == batch.num_rows() {
+                continue;
+            }
+
+            let batch = batch.slice(skip, batch.num_rows() - skip);
+            let mut columns = batch.columns().to_vec();
+            for state in self.states.iter_mut() {
+                columns.push(
+                    state
+                        .evaluate(first, batch.num_rows(), self.num_rows)
+                        .await?,
+                );
+            }
+            return Ok(Some(RecordBatch::try_new(
+                Arc::clone(&self.schema),
+                columns,
+            )?));
+        }
+    }
+}
+
+/// Interleaves the input columns, the partition-constant aggregates and the
+/// other window columns in the order of the output schema.
+struct OutputAssembler {
+    schema: SchemaRef,
+    num_input_columns: usize,
+    num_window_exprs: usize,
+    constant_expr_indices: Vec<usize>,
+    other_expr_indices: Vec<usize>,
+    constant_values: VecDeque<ConstantPartitionValues>,
+}
+
+impl OutputAssembler {
+    /// `batch` holds the input columns followed by the columns of the
+    /// window expressions that are not constant in a partition.
+    fn assemble(&mut self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let num_rows = batch.num_rows();
+        let mut window_columns: Vec<Option<ArrayRef>> = vec![None; self.num_window_exprs];
+        for (column, idx) in batch
+            .columns()
+            .iter()
+            .skip(self.num_input_columns)
+            .zip(&self.other_expr_indices)
+        {
+            window_columns[*idx] = Some(Arc::clone(column));
+        }
+
+        // The partition-constant values of the rows of this batch, in pieces
+        // of consecutive rows belonging to the same partition
+        let mut pieces = vec![vec![]; self.constant_expr_indices.len()];
+        let mut remaining = num_rows;
+        while remaining > 0 && !self.constant_expr_indices.is_empty() {
+            let Some(partition) = self.constant_values.front_mut() else {
+                return internal_err!(
+                    "Replayed window input has more rows than expected"
+                );
+            };
+            let n = remaining.min(partition.num_rows);
+            for (pieces, value) in pieces.iter_mut().zip(&partition.values) {
+                pieces.push(value.to_array_of_size(n)?);
+            }
+            partition.num_rows -= n;
+            remaining -= n;
+            if partition.num_rows == 0 {
+                self.constant_values.pop_front();
+            }
+        }
+        for (pieces, idx) in pieces.iter().zip(&self.constant_expr_indices) {
+            let column = if pieces.is_empty() {
+                let field = self.schema.field(self.num_input_columns + idx);
+                new_empty_array(field.data_type())
+            } else {
+                concat(&pieces.iter().map(|a| a.as_ref()).collect::<Vec<_>>())?
+            };
+            window_columns[*idx] = Some(column);
+        }
+
+        let columns = batch
+            .columns()
+            .iter()
+            .take(self.num_input_columns)
+            .cloned()
+            .chain(window_columns.into_iter().flatten())
+            .collect();
+        Ok(RecordBatch::try_new(Arc::clone(&self.schema), columns)?)
+    }
+}
@@ -23,11 +23,16 @@ use std::task::{Context, Poll};
 
 #[cfg(feature = "proto")]
 use super::proto::{decode_physical_window_expr, encode_physical_window_expr};
+use super::spill::spilled_window_agg_stream;
 use super::utils::create_schema;
 use crate::execution_plan::{CardinalityEffect, EmissionType};
-use crate::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use crate::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet, SpillMetrics,
+};
+use crate::spill::get_record_batch_memory_size;
+use crate::spill::spill_manager::SpillManager;
 use crate::statistics::{ChildStats, StatisticsArgs};
-use crate::stream::EmptyRecordBatchStream;
+use crate::stream::{EmptyRecordBatchStream, RecordBatchStreamAdapter};
 use crate::windows::{
     calc_requirements, get_ordered_partition_by_indices, get_partition_by_sort_exprs,
     window_equivalence_properties,
@@ -49,6 +54,7 @@ use datafusion_common::tree_node::TreeNodeRecursion;
 use datafusion_common::utils::{evaluate_partition_ranges, transpose};
 use datafusion_common::{Result, assert_eq_or_internal_err};
 use datafusion_execution::TaskContext;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
 use datafusion_physical_expr_common::sort_expr::{
     OrderingRequirements, PhysicalSortExpr,
 };
@@ -307,15 +313,34 @@ impl ExecutionPlan for WindowAggExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
-        let input = self.input.execute(partition, context)?;
-        let stream = Box::pin(WindowAggStream::new(
-            Arc::clone(&self.schema),
-            self.window_expr.clone(),
-            input,
-            BaselineMetrics::new(&self.metrics, partition),
-            self.partition_by_sort_keys()?,
-            self.ordered_partition_by_indices.clone(),
-        )?);
+        let input = self.input.execute(partition, Arc::clone(&context))?;
+        let spill_manager =
+            context
+                .runtime_env()
+                .disk_manager
+                .tmp_files_enabled()
+                .then(|| {
+                    SpillManager::new(
+                        context.runtime_env(),
+                        SpillMetrics::new(&self.metrics, partition),
+                        input.schema(),
+                    )
+                    .with_compression_type(context.session_config().spill_compression())
+                });
+        let reservation = MemoryConsumer::new(format!("WindowAggExec[{partition}]"))
+            .with_can_spill(spill_manager.is_some())
+            .register(context.memory_pool());
+        let stream = Box::pin(
+            WindowAggStream::new(
+                Arc::clone(&self.schema),
+                self.window_expr.clone(),
+                input,
+                BaselineMetrics::new(&self.metrics, partition),
+                self.partition_by_sort_keys()?,
+                self.ordered_partition_by_indices.clone(),
+            )?
+            .with_memory_limit(reservation, spill_manager, partition),
+        );
         Ok(stream)
     }
 
@@ -492,6 +517,54 @@ fn compute_window_aggregates(
         .collect()
 }
 
+/// Evaluates `window_expr` over `batch`, whose rows are sorted by the
+/// `PARTITION BY` keys, and appends the results to the columns of `batch`.
+pub(super) fn compute_partitioned_window_aggregates(
+    schema: &SchemaRef,
+    window_expr: &[Arc<dyn WindowExpr>],
+    batch: &RecordBatch,
+    partition_by_sort_keys: &[PhysicalSortExpr],
+    ordered_partition_by_indices: &[usize],
+) -> Result<Option<RecordBatch>> {
+    if batch.num_rows() == 0 {
+        return Ok(None);
+    }
+
+    let partition_by_sort_keys = ordered_partition_by_indices
+        .iter()
+        .map(|idx| partition_by_sort_keys[*idx].evaluate_to_sort_column(batch))
+        .collect::<Result<Vec<_>>>()?;
+    let partition_points =
+        evaluate_partition_ranges(batch.num_rows(), &partition_by_sort_keys)?;
+
+    let mut partition_results = vec![];
+    // Calculate window cols
+    for partition_point in partition_points {
+        let length = partition_point.end - partition_point.start;
+        partition_results.push(compute_window_aggregates(
+            window_expr,
+            &batch.slice(partition_point.start, length),
+        )?)
+    }
+    let columns = transpose(partition_results)
+        .iter()
+        .map(|elems| concat(&elems.iter().map(|x| x.as_ref()).collect::<Vec<_>>()))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .collect::<Result<Vec<ArrayRef>, ArrowError>>()?;
+
+    // combine with the original cols
+    // note the setup of window aggregates is that they newly calculated window
+    // expression results are always appended to the columns
+    let mut batch_columns = batch.columns().to_vec();
+    // calculate window cols
+    batch_columns.extend_from_slice(&columns);
+    Ok(Some(RecordBatch::try_new(
+        Arc::clone(schema),
+        batch_columns,
+    )?))
+}
+
 /// stream for window aggregation plan
 pub struct WindowAggStream {
     schema: SchemaRef,
@@ -502,6 +575,14 @@ pub struct WindowAggStream {
     partition_by_sort_keys: Vec<PhysicalSortExpr>,
     baseline_metrics: BaselineMetrics,
     ordered_partition_by_indices: Vec<usize>,
+    /// Tracks the memory used by `batches`, if the stream is memory-limited
+    reservation: Option<MemoryReservation>,
+    /// Used to spill the input when `batches` no longer fit in memory. If
+    /// `None`, running out of memory is an error.
+    spill_manager: Option<SpillManager>,
+    /// Once spilling has started, all output comes from this stream
+    spilled: Option<SendableRecordBatchStream>,
+    partition: usize,
 }
 
 impl WindowAggStream {
@@ -529,52 +610,97 @@ impl WindowAggStream {
             baseline_metrics,
             partition_by_sort_keys,
             ordered_partition_by_indices,
+            reservation: None,
+            spill_manager: None,
+            spilled: None,
+            partition: 0,
         })
     }
 
+    /// Accounts the buffered input in `reservation`. When the reservation
+    /// cannot grow and a `spill_manager` is given, the stream switches to
+    /// evaluating the window expressions over spilled input.
+    pub(super) fn with_memory_limit(
+        mut self,
+        reservation: MemoryReservation,
+        spill_manager: Option<SpillManager>,
+        partition: usize,
+    ) -> Self {
+        self.reservation = Some(reservation);
+        self.spill_manager = spill_manager;
+        self.partition = partition;
+        self
+    }
+
     fn compute_aggregates(&self) -> Result<Option<RecordBatch>> {
         // record compute time on drop
         let _timer = self.baseline_metrics.elapsed_compute().timer();
 
         let batch = concat_batches(&self.input.schema(), &self.batches)?;
-        if batch.num_rows() == 0 {
-            return Ok(None);
-        }
+        compute_partitioned_window_aggregates(
+            &self.schema,
+            &self.window_expr,
+            &batch,
+            &self.partition_by_sort_keys,
+            &self.ordered_partition_by_indices,
+        )
+    }
 
-        let partition_by_sort_keys = self
-            .ordered_partition_by_indices
-            .iter()
-            .map(|idx| self.partition_by_sort_keys[*idx].evaluate_to_sort_column(&batch))
-            .collect::<Result<Vec<_>>>()?;
-        let partition_points =
-            evaluate_partition_ranges(batch.num_rows(), &partition_by_sort_keys)?;
-
-        let mut partition_results = vec![];
-        // Calculate window cols
-        for partition_point in partition_points {
-            let length = partition_point.end - partition_point.start;
-            partition_results.push(compute_window_aggregates(
-                &self.window_expr,
-                &batch.slice(partition_point.start, length),
-            )?)
+    /// Buffers `batch`, switching to spilled evaluation if it does not fit
+    /// in memory.
+    fn buffer_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        let Some(reservation) = &self.reservation else {
+            self.batches.push(batch);
+            return Ok(());
+        };
+        if let Err(e) = reservation.try_grow(get_record_batch_memory_size(&batch)) {
+            let Some(spill_manager) = self.spill_manager.take() else {
+                return Err(e);
+            };
+            // The buffered batches are released as the spilled stream
+            // consumes them, which then tracks its own memory usage.
+            reservation.free();
+            let reservation = reservation.new_empty();
+            self.start_spilling(batch, spill_manager, reservation);
+            return Ok(());
         }
-        let columns = transpose(partition_results)
-            .iter()
-            .map(|elems| concat(&elems.iter().map(|x| x.as_ref()).collect::<Vec<_>>()))
-            .collect::<Vec<_>>()
+        self.batches.push(batch);
+        Ok(())
+    }
+
+    /// Replaces the buffered batches by a stream that evaluates the window
+    /// expressions over the buffered and the remaining input without holding
+    /// all of it in memory.
+    fn start_spilling(
+        &mut self,
+        batch: RecordBatch,
+        spill_manager: SpillManager,
+        reservation: MemoryReservation,
+    ) {
+        let input_schema = self.input.schema();
+        let input = std::mem::replace(
+            &mut self.input,
+            Box::pin(EmptyRecordBatchStream::new(Arc::clone(&input_schema))),
+        );
+        let buffered = std::mem::take(&mut self.batches)
             .into_iter()
-            .collect::<Result<Vec<ArrayRef>, ArrowError>>()?;
-
-        // combine with the original cols
-        // note the setup of window aggregates is that they newly calculated window
-        // expression results are always appended to the columns
-        let mut batch_columns = batch.columns().to_vec();
-        // calculate window cols
-        batch_columns.extend_from_slice(&columns);
-        Ok(Some(RecordBatch::try_new(
+            .chain(std::iter::once(batch))
+            .map(Ok);
+        let input = Box::pin(RecordBatchStreamAdapter::new(
+            input_schema,
+            futures::stream::iter(buffered).chain(input),
+        ));
+        self.spilled = Some(spilled_window_agg_stream(
             Arc::clone(&self.schema),
-            batch_columns,
-        )?))
+            self.window_expr.clone(),
+            input,
+            spill_manager,
+            reservation,
+            self.partition_by_sort_keys.clone(),
+            self.ordered_partition_by_indices.clone(),
+            self.baseline_metrics.intermediate(),
+            self.partition,
+        ));
     }
 }
 
@@ -601,18 +727,26 @@ impl WindowAggStream {
         }
 
         loop {
+            if let Some(spilled) = self.spilled.as_mut() {
+                return spilled.poll_next_unpin(cx);
+            }
             return Poll::Ready(Some(match ready!(self.input.poll_next_unpin(cx)) {
-                Some(Ok(batch)) => {
-                    self.batches.push(batch);
-                    continue;
-                }
+                Some(Ok(batch)) => match self.buffer_batch(batch) {
+                    Ok(()) => continue,
+                    Err(e) => Err(e),
+                },
                 Some(Err(e)) => Err(e),
                 None => {
                     // Release the input pipeline's resources before computing
                     // the final aggregates.
                     let input_schema = self.input.schema();
                     self.input = Box::pin(EmptyRecordBatchStream::new(input_schema));
-                    let Some(result) = self.compute_aggregates()? else {
+                    let result = self.compute_aggregates();
+                    self.batches.clear();
+                    if let Some(reservation) = &self.reservation {
+                        reservation.free();
+                    }
+                    let Some(result) = result? else {
                         return Poll::Ready(None);
                     };
                     self.finished = true;
@@ -636,21 +770,30 @@ impl RecordBatchStream for WindowAggStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::collect;
+    use crate::expressions::col;
     use crate::test::TestMemoryExec;
     use crate::windows::create_window_expr;
+    use arrow::array::Int64Array;
     use arrow::datatypes::{DataType, Field, Schema};
-    use datafusion_common::ScalarValue;
+    use datafusion_common::test_util::batches_to_string;
+    use datafusion_common::{ScalarValue, assert_contains};
+    use datafusion_execution::disk_manager::{DiskManagerBuilder, DiskManagerMode};
+    use datafusion_execution::runtime_env::RuntimeEnvBuilder;
     use datafusion_expr::{
         WindowFrame, WindowFrameBound, WindowFrameUnits, WindowFunctionDefinition,
     };
     use datafusion_functions_aggregate::count::count_udaf;
+    use datafusion_functions_aggregate::sum::sum_udaf;
+    use datafusion_functions_window::row_number::row_number_udwf;
+    use datafusion_physical_expr_common::sort_expr::LexOrdering;
 
     #[test]
     fn test_window_agg_cardinality_effect() -> Result<()> {
         let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
         let input: Arc<dyn ExecutionPlan> =
             Arc::new(TestMemoryExec::try_new(&[], Arc::clone(&schema), None)?);
-        let args = vec![crate::expressions::col("a", &schema)?];
+        let args = vec![col("a", &schema)?];
         let window_expr = create_window_expr(
             &WindowFunctionDefinition::AggregateUDF(count_udaf()),
             "count(a)".to_string(),
@@ -675,4 +818,198 @@ mod tests {
         ));
         Ok(())
     }
+
+    /// Input sorted by `k` with `n_partitions` partitions of `partition_size`
+    /// rows each, split into batches of 100 rows.
+    fn skewed_input(
+        n_partitions: i64,
+        partition_size: i64,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("k", DataType::Int64, false),
+            Field::new("o", DataType::Int64, false),
+            Field::new("x", DataType::Int64, true),
+        ]));
+        let n = n_partitions * partition_size;
+        let batches = (0..n)
+            .step_by(100)
+            .map(|start| {
+                let rows = start..(start + 100).min(n);
+                RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![
+                        Arc::new(Int64Array::from_iter_values(
+                            rows.clone().map(|i| i / partition_size),
+                        )),
+                        Arc::new(Int64Array::from_iter_values(rows.clone())),
+                        Arc::new(Int64Array::from_iter(
+                            rows.map(|i| (i % 7 != 0).then_some(i % 13)),
+                        )),
+                    ],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let ordering = LexOrdering::new(vec![
+            PhysicalSortExpr::new_default(col("k", &schema)?),
+            PhysicalSortExpr::new_default(col("o", &schema)?),
+        ])
+        .unwrap();
+        let source = TestMemoryExec::try_new(&[batches], schema, None)?
+            .try_with_sort_information(vec![ordering])?;
+        Ok(Arc::new(TestMemoryExec::update_cache(&Arc::new(source))))
+    }
+
+    /// `SUM(x) OVER (PARTITION BY k)`, which is constant in a partition
+    fn partition_sum(schema: &SchemaRef) -> Result<Arc<dyn WindowExpr>> {
+        create_window_expr(
+            &WindowFunctionDefinition::AggregateUDF(sum_udaf()),
+            "sum(x)".to_string(),
+            &[col("x", schema)?],
+            &[col("k", schema)?],
+            &[],
+            Arc::new(WindowFrame::new(None)),
+            Arc::clone(schema),
+            false,
+            false,
+            None,
+        )
+    }
+
+    /// `ROW_NUMBER() OVER (PARTITION BY k ORDER BY o)`
+    fn row_number(schema: &SchemaRef) -> Result<Arc<dyn WindowExpr>> {
+        create_window_expr(
+            &WindowFunctionDefinition::WindowUDF(row_number_udwf()),
+            "row_number".to_string(),
+            &[],
+            &[col("k", schema)?],
+            &[PhysicalSortExpr::new_default(col("o", schema)?)],
+            Arc::new(WindowFrame::new(Some(true))),
+            Arc::clone(schema),
+            false,
+            false,
+            None,
+        )
+    }
+
+    /// `SUM(x) OVER (PARTITION BY k ORDER BY o)`, whose frame ends with the
+    /// peers of the current row
+    fn running_sum(schema: &SchemaRef) -> Result<Arc<dyn WindowExpr>> {
+        create_window_expr(
+            &WindowFunctionDefinition::AggregateUDF(sum_udaf()),
+            "running_sum(x)".to_string(),
+            &[col("x", schema)?],
+            &[col("k", schema)?],
+            &[PhysicalSortExpr::new_default(col("o", schema)?)],
+            Arc::new(WindowFrame::new(Some(true))),
+            Arc::clone(schema),
+            false,
+            false,
+            None,
+        )
+    }
+
+    /// `COUNT(x) OVER (PARTITION BY k ORDER BY o ROWS BETWEEN CURRENT ROW
+    /// AND UNBOUNDED FOLLOWING)`, which needs the whole partition
+    fn remaining_count(schema: &SchemaRef) -> Result<Arc<dyn WindowExpr>> {
+        create_window_expr(
+            &WindowFunctionDefinition::AggregateUDF(count_udaf()),
+            "count(x)".to_string(),
+            &[col("x", schema)?],
+            &[col("k", schema)?],
+            &[PhysicalSortExpr::new_default(col("o", schema)?)],
+            Arc::new(WindowFrame::new_bounds(
+                WindowFrameUnits::Rows,
+                WindowFrameBound::CurrentRow,
+                WindowFrameBound::Following(ScalarValue::UInt64(None)),
+            )),
+            Arc::clone(schema),
+            false,
+            false,
+            None,
+        )
+    }
+
+    /// Runs `window` with the given runtime, returning the output and the
+    /// number of spill files written.
+    async fn run_window(
+        window: &WindowAggExec,
+        runtime_builder: RuntimeEnvBuilder,
+    ) -> Result<(Vec<RecordBatch>, usize)> {
+        let window = window.clone();
+        let task_ctx = TaskContext::default().with_runtime(runtime_builder.build_arc()?);
+        let batches = collect(window.execute(0, Arc::new(task_ctx))?).await?;
+        let spill_count = window.metrics().unwrap().spill_count().unwrap_or(0);
+        Ok((batches, spill_count))
+    }
+
+    type CreateWindowExpr = fn(&SchemaRef) -> Result<Arc<dyn WindowExpr>>;
+
+    /// Checks that `window_expr` produce the same output when the input, made
+    /// of partitions of `partition_size` rows, does not fit in memory.
+    async fn assert_spilled_output_matches(
+        window_expr: &[CreateWindowExpr],
+        partition_size: i64,
+        expect_spill_files: bool,
+    ) -> Result<()> {
+        let input = skewed_input(4, partition_size)?;
+        let window_expr = window_expr
+            .iter()
+            .map(|create| create(&input.schema()))
+            .collect::<Result<_>>()?;
+        let window = WindowAggExec::try_new(window_expr, input, false)?;
+
+        let (expected, _) = run_window(&window, RuntimeEnvBuilder::new()).await?;
+        let (actual, spill_count) = run_window(
+            &window,
+            RuntimeEnvBuilder::new().with_memory_limit(40_000, 1.0),
+        )
+        .await?;
+
+        assert_eq!(batches_to_string(&expected), batches_to_string(&actual));
+        assert_eq!(spill_count > 0, expect_spill_files);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_window_agg_spill_partition_constant() -> Result<()> {
+        assert_spilled_output_matches(&[partition_sum, remaining_count], 1500, true).await
+    }
+
+    #[tokio::test]
+    async fn test_window_agg_spill_bounded() -> Result<()> {
+        assert_spilled_output_matches(&[row_number, partition_sum], 1500, true).await
+    }
+
+    #[tokio::test]
+    async fn test_window_agg_spill_partition_at_a_time() -> Result<()> {
+        // Every partition fits in memory
+        assert_spilled_output_matches(&[remaining_count, row_number], 1500, false)
+            .await?;
+        // Partitions that do not fit in memory are spilled and replayed
+        assert_spilled_output_matches(
+            &[remaining_count, row_number, running_sum],
+            5000,
+            true,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_window_agg_memory_limit_without_disk() -> Result<()> {
+        let input = skewed_input(4, 1500)?;
+        let window =
+            WindowAggExec::try_new(vec![partition_sum(&input.schema())?], input, false)?;
+        let err = run_window(
+            &window,
+            RuntimeEnvBuilder::new()
+                .with_memory_limit(40_000, 1.0)
+                .with_disk_manager_builder(
+                    DiskManagerBuilder::default().with_mode(DiskManagerMode::Disabled),
+                ),
+        )
+        .await
+        .unwrap_err();
+        assert_contains!(err.to_string(), "WindowAggExec[0]");
+        Ok(())
+    }
 }
@@ -776,8 +776,14 @@ impl serde::Serialize for AvroFormat {
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let len = 0;
-        let struct_ser = serializer.serialize_struct("datafusion_common.AvroFormat", len)?;
+        let mut len = 0;
+        if !self.compression.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("datafusion_common.AvroFormat", len)?;
+        if !self.compression.is_empty() {
+            struct_ser.serialize_field("compression", &self.compression)?;
+        }
         struct_ser.end()
     }
 }
@@ -788,10 +794,12 @@ impl<'de> serde::Deserialize<'de> for AvroFormat {
         D: serde::Deserializer<'de>,
     {
         const FIELDS: &[&str] = &[
+            "compression",
         ];
 
         #[allow(clippy::enum_variant_names)]
         enum GeneratedField {
+            Compression,
         }
         impl<'de> serde::Deserialize<'de> for GeneratedField {
             fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
@@ -812,7 +820,10 @@ impl<'de> serde::Deserialize<'de> for AvroFormat {
                     where
                         E: serde::de::Error,
                     {
-                            Err(serde::de::Error::unknown_field(value, FIELDS))
+                        match value {
+                            "compression" => Ok(GeneratedField::Compression),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
                     }
                 }
                 deserializer.deserialize_identifier(GeneratedVisitor)
@@ -830,10 +841,19 @@ impl<'de> serde::Deserialize<'de> for AvroFormat {
                 where
                     V: serde::de::MapAccess<'de>,
             {
-                while map_.next_key::<GeneratedField>()?.is_some() {
-                    let _ = map_.next_value::<serde::de::IgnoredAny>()?;
+                let mut compression__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Compression => {
+                            if compression__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("compression"));
+                            }
+                            compression__ = Some(map_.next_value()?);
+                        }
+                    }
                 }
                 Ok(AvroFormat {
+                    compression: compression__.unwrap_or_default(),
                 })
             }
         }
@@ -38,8 +38,13 @@ pub struct ParquetFormat {
     #[prost(message, optional, tag = "2")]
     pub options: ::core::option::Option<TableParquetOptions>,
 }
-#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
-pub struct AvroFormat {}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct AvroFormat {
+    /// Block compression codec used when writing, e.g. "zstd". Empty when
+    /// writing uncompressed blocks.
+    #[prost(string, tag = "1")]
+    pub compression: ::prost::alloc::string::String,
+}
 #[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct NdJsonFormat {
     #[prost(message, optional, tag = "1")]
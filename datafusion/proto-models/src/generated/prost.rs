@@ -5,7 +5,7 @@
 pub struct LogicalPlanNode {
     #[prost(
         oneof = "logical_plan_node::LogicalPlanType",
-        tags = "1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34"
+        tags = "1, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35"
     )]
     pub logical_plan_type: ::core::option::Option<logical_plan_node::LogicalPlanType>,
 }
@@ -79,6 +79,8 @@ pub mod logical_plan_node {
         Dml(::prost::alloc::boxed::Box<super::DmlNode>),
         #[prost(message, tag = "34")]
         EmptyTableScan(super::EmptyTableScanNode),
+        #[prost(message, tag = "35")]
+        AsofJoin(::prost::alloc::boxed::Box<super::AsOfJoinNode>),
     }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -421,6 +423,21 @@ pub struct JoinNode {
     pub null_aware: bool,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AsOfJoinNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub left: ::core::option::Option<::prost::alloc::boxed::Box<LogicalPlanNode>>,
+    #[prost(message, optional, boxed, tag = "2")]
+    pub right: ::core::option::Option<::prost::alloc::boxed::Box<LogicalPlanNode>>,
+    #[prost(message, repeated, tag = "3")]
+    pub left_join_key: ::prost::alloc::vec::Vec<LogicalExprNode>,
+    #[prost(message, repeated, tag = "4")]
+    pub right_join_key: ::prost::alloc::vec::Vec<LogicalExprNode>,
+    #[prost(message, optional, boxed, tag = "5")]
+    pub match_condition: ::core::option::Option<
+        ::prost::alloc::boxed::Box<LogicalExprNode>,
+    >,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct DistinctNode {
     #[prost(message, optional, boxed, tag = "1")]
     pub input: ::core::option::Option<::prost::alloc::boxed::Box<LogicalPlanNode>>,
@@ -1342,7 +1359,7 @@ pub mod table_reference {
 pub struct PhysicalPlanNode {
     #[prost(
         oneof = "physical_plan_node::PhysicalPlanType",
-        tags = "1, 2, 3, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39"
+        tags = "1, 2, 3, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41"
     )]
     pub physical_plan_type: ::core::option::Option<physical_plan_node::PhysicalPlanType>,
 }
@@ -1428,6 +1445,10 @@ pub mod physical_plan_node {
         ArrowScan(super::ArrowScanExecNode),
         #[prost(message, tag = "39")]
         ScalarSubquery(::prost::alloc::boxed::Box<super::ScalarSubqueryExecNode>),
+        #[prost(message, tag = "40")]
+        AsofJoin(::prost::alloc::boxed::Box<super::AsOfJoinExecNode>),
+        #[prost(message, tag = "41")]
+        AvroSink(::prost::alloc::boxed::Box<super::AvroSinkExecNode>),
     }
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -1520,6 +1541,25 @@ pub struct ParquetSinkExecNode {
     pub sort_order: ::core::option::Option<PhysicalSortExprNodeCollection>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AvroSink {
+    #[prost(message, optional, tag = "1")]
+    pub config: ::core::option::Option<FileSinkConfig>,
+    /// Block compression codec, e.g. "zstd". Empty for uncompressed blocks.
+    #[prost(string, tag = "2")]
+    pub compression: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AvroSinkExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(message, optional, tag = "2")]
+    pub sink: ::core::option::Option<AvroSink>,
+    #[prost(message, optional, tag = "3")]
+    pub sink_schema: ::core::option::Option<super::datafusion_common::Schema>,
+    #[prost(message, optional, tag = "4")]
+    pub sort_order: ::core::option::Option<PhysicalSortExprNodeCollection>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UnnestExecNode {
     #[prost(message, optional, boxed, tag = "1")]
     pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
@@ -2561,6 +2601,24 @@ pub struct SortMergeJoinExecNode {
     pub null_equality: i32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AsOfJoinExecNode {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub left: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(message, optional, boxed, tag = "2")]
+    pub right: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
+    #[prost(message, repeated, tag = "3")]
+    pub on: ::prost::alloc::vec::Vec<JoinOn>,
+    #[prost(message, optional, tag = "4")]
+    pub match_left: ::core::option::Option<PhysicalExprNode>,
+    #[prost(string, tag = "5")]
+    pub match_op: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "6")]
+    pub match_right: ::core::option::Option<PhysicalExprNode>,
+    /// Empty means no projection; a single u32::MAX entry means an empty projection.
+    #[prost(uint32, repeated, tag = "7")]
+    pub projection: ::prost::alloc::vec::Vec<u32>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct AsyncFuncExecNode {
     #[prost(message, optional, boxed, tag = "1")]
     pub input: ::core::option::Option<::prost::alloc::boxed::Box<PhysicalPlanNode>>,
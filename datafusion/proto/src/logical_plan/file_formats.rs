@@ -23,6 +23,8 @@ use datafusion_common::config::{CsvOptions, JsonOptions};
 use datafusion_common::{TableReference, exec_datafusion_err, exec_err, not_impl_err};
 use datafusion_datasource::file_format::FileFormatFactory;
 use datafusion_datasource_arrow::file_format::ArrowFormatFactory;
+#[cfg(feature = "avro")]
+use datafusion_datasource_avro::file_format::AvroFormatFactory;
 use datafusion_datasource_csv::file_format::CsvFormatFactory;
 use datafusion_datasource_json::file_format::JsonFormatFactory;
 use datafusion_execution::TaskContext;
@@ -397,6 +399,19 @@ impl LogicalExtensionCodec for ArrowLogicalExtensionCodec {
     }
 }
 
+/// Returns true if `node` is the Avro [`FileFormatFactory`].
+pub(super) fn is_avro_format_factory(node: &Arc<dyn FileFormatFactory>) -> bool {
+    #[cfg(feature = "avro")]
+    {
+        node.downcast_ref::<AvroFormatFactory>().is_some()
+    }
+    #[cfg(not(feature = "avro"))]
+    {
+        let _ = node;
+        false
+    }
+}
+
 #[derive(Debug)]
 pub struct AvroLogicalExtensionCodec;
 
@@ -443,7 +458,12 @@ impl LogicalExtensionCodec for AvroLogicalExtensionCodec {
         __buf: &[u8],
         __ctx: &TaskContext,
     ) -> datafusion_common::Result<Arc<dyn FileFormatFactory>> {
-        Ok(Arc::new(ArrowFormatFactory::new()))
+        #[cfg(feature = "avro")]
+        {
+            Ok(Arc::new(AvroFormatFactory::new()))
+        }
+        #[cfg(not(feature = "avro"))]
+        not_impl_err!("Avro support requires the 'avro' feature")
     }
 
     fn try_encode_file_format(
@@ -63,12 +63,12 @@ use datafusion_expr::{
 };
 use datafusion_expr::{
     DistinctOn, DropView, Expr, JoinConstraint, LogicalPlan, LogicalPlanBuilder,
-    ScalarUDF, SortExpr, Statement, WindowUDF, dml,
+    ScalarUDF, SortExpr, Statement, WindowUDF, binary_expr, dml,
     logical_plan::{
-        Aggregate, CreateCatalog, CreateCatalogSchema, CreateExternalTable, CreateView,
-        DdlStatement, Distinct, EmptyRelation, Extension, Join, Prepare, Projection,
-        Repartition, Sort, SubqueryAlias, TableScan, TableScanBuilder, Values, Window,
-        builder::project,
+        Aggregate, AsOfJoin, CreateCatalog, CreateCatalogSchema, CreateExternalTable,
+        CreateView, DdlStatement, Distinct, EmptyRelation, Extension, Join, Prepare,
+        Projection, Repartition, Sort, SubqueryAlias, TableScan, TableScanBuilder,
+        Values, Window, builder::project,
     },
 };
 use datafusion_proto_common::protobuf_common;
@@ -314,6 +314,10 @@ impl LogicalExtensionCodec for DefaultLogicalExtensionCodec {
             file_formats::ArrowLogicalExtensionCodec
                 .try_encode_file_format(&mut encoded_file_format, Arc::clone(&node))?;
             protobuf::FileFormatKind::Arrow
+        } else if file_formats::is_avro_format_factory(&node) {
+            file_formats::AvroLogicalExtensionCodec
+                .try_encode_file_format(&mut encoded_file_format, Arc::clone(&node))?;
+            protobuf::FileFormatKind::Avro
         } else {
             #[cfg(feature = "parquet")]
             {
@@ -627,10 +631,11 @@ impl AsLogicalPlan for LogicalPlanNode {
                             }
                             Arc::new(json)
                         }
-                        FileFormatType::Avro(..) => {
+                        #[cfg_attr(not(feature = "avro"), expect(unused_variables))]
+                        FileFormatType::Avro(avro) => {
                             #[cfg(feature = "avro")]
                             {
-                                Arc::new(AvroFormat)
+                                Arc::new(AvroFormat::try_from(avro)?)
                             }
                             #[cfg(not(feature = "avro"))]
                             {
@@ -1054,6 +1059,36 @@ impl AsLogicalPlan for LogicalPlanNode {
                     join.null_aware,
                 )?))
             }
+            LogicalPlanType::AsofJoin(join) => {
+                let left_keys: Vec<Expr> =
+                    from_proto::parse_exprs(&join.left_join_key, ctx, extension_codec)?;
+                let right_keys: Vec<Expr> =
+                    from_proto::parse_exprs(&join.right_join_key, ctx, extension_codec)?;
+                if left_keys.len() != right_keys.len() {
+                    return Err(proto_error(format!(
+                        "Received an AsOfJoinNode message with left_join_key and right_join_key of different lengths: {} and {}",
+                        left_keys.len(),
+                        right_keys.len()
+                    )));
+                }
+                let match_condition = join
+                    .match_condition
+                    .as_ref()
+                    .map(|expr| from_proto::parse_expr(expr, ctx, extension_codec))
+                    .transpose()?
+                    .ok_or_else(|| {
+                        proto_error("AsOfJoinNode is missing its match_condition")
+                    })?;
+                let left = into_logical_plan!(join.left, ctx, extension_codec)?;
+                let right = into_logical_plan!(join.right, ctx, extension_codec)?;
+
+                Ok(LogicalPlan::AsOfJoin(AsOfJoin::try_new(
+                    Arc::new(left),
+                    Arc::new(right),
+                    left_keys.into_iter().zip(right_keys).collect(),
+                    match_condition,
+                )?))
+            }
             LogicalPlanType::Union(union) => {
                 assert_or_internal_err!(
                     union.inputs.len() >= 2,
@@ -1389,9 +1424,9 @@ impl AsLogicalPlan for LogicalPlanNode {
                         }
 
                         #[cfg(feature = "avro")]
-                        if format.is::<AvroFormat>() {
+                        if let Some(avro) = format.downcast_ref::<AvroFormat>() {
                             maybe_some_type =
-                                Some(FileFormatType::Avro(protobuf::AvroFormat {}))
+                                Some(FileFormatType::Avro(avro.into()))
                         }
 
                         if format.is::<ArrowFormat>() {
@@ -1710,6 +1745,55 @@ impl AsLogicalPlan for LogicalPlanNode {
                     ))),
                 })
             }
+            LogicalPlan::AsOfJoin(AsOfJoin {
+                left,
+                right,
+                on,
+                match_condition,
+                // Not encoded; recomputed by `AsOfJoin::try_new` on decode.
+                schema: _,
+            }) => {
+                let left: LogicalPlanNode = LogicalPlanNode::try_from_logical_plan(
+                    left.as_ref(),
+                    extension_codec,
+                )?;
+                let right: LogicalPlanNode = LogicalPlanNode::try_from_logical_plan(
+                    right.as_ref(),
+                    extension_codec,
+                )?;
+                let (left_join_key, right_join_key) = on
+                    .iter()
+                    .map(|(l, r)| {
+                        Ok((
+                            serialize_expr(l, extension_codec)?,
+                            serialize_expr(r, extension_codec)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, ToProtoError>>()?
+                    .into_iter()
+                    .unzip();
+                // Re-encoded as a binary expression; `AsOfJoin::try_new`
+                // splits and orients it again on decode.
+                let match_condition = serialize_expr(
+                    &binary_expr(
+                        match_condition.left.clone(),
+                        match_condition.op,
+                        match_condition.right.clone(),
+                    ),
+                    extension_codec,
+                )?;
+                Ok(LogicalPlanNode {
+                    logical_plan_type: Some(LogicalPlanType::AsofJoin(Box::new(
+                        protobuf::AsOfJoinNode {
+                            left: Some(Box::new(left)),
+                            right: Some(Box::new(right)),
+                            left_join_key,
+                            right_join_key,
+                            match_condition: Some(Box::new(match_condition)),
+                        },
+                    ))),
+                })
+            }
             LogicalPlan::Subquery(subquery) => {
                 // Serialize the inner subquery plan directly — the
                 // LogicalPlan::Subquery wrapper is reconstructed during
@@ -2129,6 +2213,22 @@ impl AsLogicalPlan for LogicalPlanNode {
             LogicalPlan::Ddl(DdlStatement::DropFunction(_)) => Err(proto_error(
                 "LogicalPlan serde is not yet implemented for DropFunction",
             )),
+            LogicalPlan::Ddl(DdlStatement::AnalyzeTable(_)) => Err(proto_error(
+                "LogicalPlan serde is not yet implemented for AnalyzeTable",
+            )),
+            LogicalPlan::Ddl(DdlStatement::AlterTable(_)) => Err(proto_error(
+                "LogicalPlan serde is not yet implemented for AlterTable",
+            )),
+            LogicalPlan::Ddl(DdlStatement::CreateMaterializedView(_)) => {
+                Err(proto_error(
+                    "LogicalPlan serde is not yet implemented for CreateMaterializedView",
+                ))
+            }
+            LogicalPlan::Ddl(DdlStatement::RefreshMaterializedView(_)) => {
+                Err(proto_error(
+                    "LogicalPlan serde is not yet implemented for RefreshMaterializedView",
+                ))
+            }
             LogicalPlan::Statement(_) => Err(proto_error(
                 "LogicalPlan serde is not yet implemented for Statement",
             )),
@@ -28,6 +28,8 @@ use datafusion_common::{
 };
 use datafusion_datasource_arrow::source::ArrowSource;
 #[cfg(feature = "avro")]
+use datafusion_datasource_avro::file_format::AvroSink;
+#[cfg(feature = "avro")]
 use datafusion_datasource_avro::source::AvroSource;
 use datafusion_datasource_csv::file_format::CsvSink;
 use datafusion_datasource_csv::source::CsvSource;
@@ -60,7 +62,7 @@ use datafusion_physical_plan::empty::EmptyExec;
 use datafusion_physical_plan::explain::ExplainExec;
 use datafusion_physical_plan::filter::FilterExec;
 use datafusion_physical_plan::joins::{
-    CrossJoinExec, HashJoinExec, NestedLoopJoinExec, SortMergeJoinExec,
+    AsOfJoinExec, CrossJoinExec, HashJoinExec, NestedLoopJoinExec, SortMergeJoinExec,
     SymmetricHashJoinExec,
 };
 use datafusion_physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
@@ -1188,6 +1190,14 @@ pub trait PhysicalPlanNodeExt: Sized {
                 #[cfg(not(feature = "parquet"))]
                 not_impl_err!("ParquetSink requires the `parquet` feature")
             }
+            PhysicalPlanType::AvroSink(_) => {
+                #[cfg(feature = "avro")]
+                {
+                    AvroSink::try_from_proto(self.node(), &decode_ctx)
+                }
+                #[cfg(not(feature = "avro"))]
+                not_impl_err!("AvroSink requires the `avro` feature")
+            }
             PhysicalPlanType::Unnest(_) => {
                 UnnestExec::try_from_proto(self.node(), &decode_ctx)
             }
@@ -1209,6 +1219,9 @@ pub trait PhysicalPlanNodeExt: Sized {
             PhysicalPlanType::ScalarSubquery(_) => {
                 ScalarSubqueryExec::try_from_proto(self.node(), &decode_ctx)
             }
+            PhysicalPlanType::AsofJoin(_) => {
+                AsOfJoinExec::try_from_proto(self.node(), &decode_ctx)
+            }
         }
     }
 
@@ -27,8 +27,8 @@ use datafusion::physical_plan::empty::EmptyExec;
 use datafusion::physical_plan::expressions::{BinaryExpr, Column, PhysicalSortExpr};
 use datafusion::physical_plan::joins::utils::{ColumnIndex, JoinFilter};
 use datafusion::physical_plan::joins::{
-    HashJoinExec, NestedLoopJoinExec, PartitionMode, SortMergeJoinExec,
-    StreamJoinPartitionMode, SymmetricHashJoinExec,
+    AsOfJoinExec, AsOfMatchExpr, HashJoinExec, NestedLoopJoinExec, PartitionMode,
+    SortMergeJoinExec, StreamJoinPartitionMode, SymmetricHashJoinExec,
 };
 use datafusion::prelude::SessionContext;
 use datafusion_common::{JoinSide, NullEquality, Result};
@@ -431,6 +431,39 @@ fn roundtrip_sort_merge_join() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn roundtrip_asof_join() -> Result<()> {
+    let schema_left = Arc::new(Schema::new(vec![
+        Field::new("sym", DataType::Utf8, false),
+        Field::new("ts", DataType::Int64, false),
+    ]));
+    let schema_right = Arc::new(Schema::new(vec![
+        Field::new("quote_sym", DataType::Utf8, false),
+        Field::new("quote_ts", DataType::Int64, false),
+    ]));
+    let on = vec![(
+        Arc::new(Column::new("sym", 0)) as _,
+        Arc::new(Column::new("quote_sym", 0)) as _,
+    )];
+
+    for op in [Operator::Lt, Operator::LtEq, Operator::Gt, Operator::GtEq] {
+        for projection in [None, Some(vec![]), Some(vec![1, 3])] {
+            roundtrip_test(Arc::new(AsOfJoinExec::try_new(
+                Arc::new(EmptyExec::new(schema_left.clone())),
+                Arc::new(EmptyExec::new(schema_right.clone())),
+                on.clone(),
+                AsOfMatchExpr::new(
+                    Arc::new(Column::new("ts", 1)),
+                    op,
+                    Arc::new(Column::new("quote_ts", 1)),
+                ),
+                projection,
+            )?))?;
+        }
+    }
+    Ok(())
+}
+
 #[tokio::test]
 async fn roundtrip_logical_plan_sort_merge_join() -> Result<()> {
     let ctx = SessionContext::new();
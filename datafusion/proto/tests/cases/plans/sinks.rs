@@ -236,6 +236,62 @@ fn roundtrip_json_sink() -> Result<()> {
     )))
 }
 
+#[cfg(feature = "avro")]
+#[test]
+fn roundtrip_avro_sink() -> Result<()> {
+    use datafusion_datasource_avro::file_format::{
+        AvroSink, compression_codec_name, parse_compression_codec,
+    };
+
+    let field_a = Field::new("plan_type", DataType::Utf8, false);
+    let field_b = Field::new("plan", DataType::Utf8, false);
+    let schema = Arc::new(Schema::new(vec![field_a, field_b]));
+    let input = Arc::new(PlaceholderRowExec::new(schema.clone()));
+
+    let file_sink_config = FileSinkConfig {
+        original_url: String::default(),
+        object_store_url: ObjectStoreUrl::local_filesystem(),
+        file_group: FileGroup::new(vec![PartitionedFile::new("/tmp".to_string(), 1)]),
+        table_paths: vec![ListingTableUrl::parse("file:///")?],
+        output_schema: schema.clone(),
+        table_partition_cols: vec![("plan_type".to_string(), DataType::Utf8)],
+        insert_op: InsertOp::Overwrite,
+        keep_partition_by_columns: true,
+        file_extension: "avro".into(),
+        file_output_mode: FileOutputMode::SingleFile,
+    };
+    let data_sink = Arc::new(AvroSink::new(
+        file_sink_config,
+        parse_compression_codec("zstd")?,
+    ));
+    let sort_order = [PhysicalSortRequirement::new(
+        Arc::new(Column::new("plan_type", 0)),
+        Some(SortOptions {
+            descending: true,
+            nulls_first: false,
+        }),
+    )]
+    .into();
+
+    let ctx = SessionContext::new();
+    let codec = DefaultPhysicalExtensionCodec {};
+    let proto_converter = DefaultPhysicalProtoConverter {};
+    let roundtripped = roundtrip_test_and_return(
+        Arc::new(DataSinkExec::new(input, data_sink, Some(sort_order))),
+        &ctx,
+        &codec,
+        &proto_converter,
+    )?;
+
+    let roundtripped = roundtripped.downcast_ref::<DataSinkExec>().unwrap();
+    let avro_sink = roundtripped.sink().downcast_ref::<AvroSink>().unwrap();
+    assert_eq!(
+        avro_sink.compression().map(compression_codec_name),
+        Some("zstd")
+    );
+    Ok(())
+}
+
 #[test]
 fn roundtrip_csv_sink() -> Result<()> {
     let field_a = Field::new("plan_type", DataType::Utf8, false);
@@ -3610,6 +3610,51 @@ async fn roundtrip_arrow_scan() -> Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "avro")]
+#[tokio::test]
+async fn roundtrip_avro_listing_table_compression() -> Result<()> {
+    use datafusion::datasource::source_as_provider;
+    use datafusion_datasource_avro::file_format::{
+        AvroFormat, compression_codec_name, parse_compression_codec,
+    };
+
+    let ctx = SessionContext::new();
+    let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+    let file_format =
+        AvroFormat::default().with_compression(parse_compression_codec("zstd")?);
+    let listing_options = ListingOptions::new(Arc::new(file_format));
+    let config = ListingTableConfig::new(ListingTableUrl::parse("file:///tmp/avro/")?)
+        .with_listing_options(listing_options)
+        .with_schema(schema);
+    let listing_table: Arc<dyn TableProvider> = Arc::new(ListingTable::try_new(config)?);
+
+    let plan = LogicalPlanBuilder::scan(
+        "avro_table",
+        Arc::new(DefaultTableSource::new(listing_table)),
+        None,
+    )?
+    .build()?;
+
+    let bytes = logical_plan_to_bytes(&plan)?;
+    let new_plan = logical_plan_from_bytes(&bytes, &ctx.task_ctx())?;
+
+    let LogicalPlan::TableScan(scan) = &new_plan else {
+        panic!("expected TableScan, got {new_plan:?}");
+    };
+    let provider = source_as_provider(&scan.source)?;
+    let listing_table = provider.downcast_ref::<ListingTable>().unwrap();
+    let avro_format = listing_table
+        .options()
+        .format
+        .downcast_ref::<AvroFormat>()
+        .unwrap();
+    assert_eq!(
+        avro_format.compression().map(compression_codec_name),
+        Some("zstd")
+    );
+    Ok(())
+}
+
 #[tokio::test]
 async fn roundtrip_mixed_case_table_reference() -> Result<()> {
     // Prepare "client" database
@@ -3783,6 +3828,35 @@ async fn roundtrip_join_null_equality() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn roundtrip_asof_join() -> Result<()> {
+    let ctx = SessionContext::new();
+    let trades = Arc::new(Schema::new(vec![
+        Field::new("sym", DataType::Utf8, true),
+        Field::new("ts", DataType::Int32, true),
+    ]));
+    let quotes = Arc::new(Schema::new(vec![
+        Field::new("sym", DataType::Utf8, true),
+        Field::new("ts", DataType::Int32, true),
+        Field::new("price", DataType::Int32, true),
+    ]));
+    ctx.register_table("trades", Arc::new(EmptyTable::new(trades)))?;
+    ctx.register_table("quotes", Arc::new(EmptyTable::new(quotes)))?;
+
+    for match_condition in ["t.ts >= q.ts", "q.ts < t.ts"] {
+        let sql = format!(
+            "SELECT t.sym, q.price FROM trades t \
+             ASOF JOIN quotes q MATCH_CONDITION({match_condition}) ON t.sym = q.sym"
+        );
+        let plan = ctx.sql(&sql).await?.into_optimized_plan()?;
+        let bytes = logical_plan_to_bytes(&plan)?;
+        let logical_round_trip = logical_plan_from_bytes(&bytes, &ctx.task_ctx())?;
+        assert_eq!(format!("{plan:?}"), format!("{logical_round_trip:?}"));
+    }
+
+    Ok(())
+}
+
 // Single column, single split point range partitioning
 #[tokio::test]
 async fn roundtrip_range_partitioning_single_col() -> Result<()> {
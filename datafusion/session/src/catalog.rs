@@ -20,8 +20,8 @@ use std::fmt::Debug;
 use std::sync::Arc;
 
 pub use crate::schema::SchemaProvider;
-use datafusion_common::Result;
 use datafusion_common::not_impl_err;
+use datafusion_common::{Result, TableStatistics};
 
 /// A catalog list that contains no catalogs.
 ///
@@ -171,6 +171,45 @@ pub trait CatalogProvider: Any + Debug + Sync + Send {
     ) -> Result<Option<Arc<dyn SchemaProvider>>> {
         not_impl_err!("Deregistering new schemas is not supported")
     }
+
+    /// Returns the store for the statistics of the tables in this catalog,
+    /// such as the ones computed by `ANALYZE TABLE`.
+    ///
+    /// By default returns `None`, in which case statistics can not be
+    /// persisted for tables in this catalog.
+    fn statistics_store(&self) -> Option<Arc<dyn TableStatisticsStore>> {
+        None
+    }
+}
+
+/// Stores [`TableStatistics`] for the tables of a [`CatalogProvider`].
+///
+/// Statistics are keyed by schema and table name. The query planner uses the
+/// stored statistics to improve cardinality estimates for scans of the table.
+///
+/// Implementations may persist the statistics, for example in a remote
+/// metastore, so they survive the session.
+pub trait TableStatisticsStore: Debug + Sync + Send {
+    /// Returns the statistics of table `table` in schema `schema`, if any.
+    fn table_statistics(&self, schema: &str, table: &str)
+    -> Option<Arc<TableStatistics>>;
+
+    /// Stores `statistics` for table `table` in schema `schema`, replacing
+    /// any existing statistics of the table.
+    fn store_table_statistics(
+        &self,
+        schema: &str,
+        table: &str,
+        statistics: TableStatistics,
+    ) -> Result<()>;
+
+    /// Removes the statistics of table `table` in schema `schema`, returning
+    /// them if they existed.
+    fn remove_table_statistics(
+        &self,
+        schema: &str,
+        table: &str,
+    ) -> Result<Option<Arc<TableStatistics>>>;
 }
 
 impl dyn CatalogProvider {
@@ -53,7 +53,7 @@ pub mod session;
 pub mod table;
 
 pub use crate::catalog::{
-    CatalogProvider, CatalogProviderList, EmptyCatalogProviderList,
+    CatalogProvider, CatalogProviderList, EmptyCatalogProviderList, TableStatisticsStore,
 };
 pub use crate::physical_optimizer::{PhysicalOptimizerContext, PhysicalOptimizerRule};
 pub use crate::planner::{
@@ -63,5 +63,5 @@ pub use crate::schema::SchemaProvider;
 pub use crate::session::{Session, SessionStore};
 pub use crate::table::{
     ScanArgs, ScanResult, TableFunction, TableFunctionArgs, TableFunctionImpl,
-    TableProvider, TableProviderFactory,
+    TableProvider, TableProviderFactory, TableSnapshot,
 };
@@ -82,6 +82,21 @@ pub trait SchemaProvider: Any + Debug + Sync + Send {
         exec_err!("schema provider does not support deregistering tables")
     }
 
+    /// If supported by the implementation, renames the `name` table of this
+    /// schema to `new_name`.
+    ///
+    /// The default implementation deregisters the table and registers it
+    /// again with the new name.
+    fn rename_table(&self, name: &str, new_name: String) -> Result<()> {
+        if self.table_exist(&new_name) {
+            return exec_err!("The table {new_name} already exists");
+        }
+        match self.deregister_table(name)? {
+            Some(table) => self.register_table(new_name, table).map(|_| ()),
+            None => exec_err!("The table {name} does not exist"),
+        }
+    }
+
     /// Returns true if table exist in the schema provider, false otherwise.
     fn table_exist(&self, name: &str) -> bool;
 }
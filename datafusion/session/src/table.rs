@@ -32,7 +32,8 @@ use futures::future::BoxFuture;
 
 use datafusion_expr::dml::{InsertOp, MergeIntoClause};
 use datafusion_expr::{
-    CreateExternalTable, LogicalPlan, TableProviderFilterPushDown, TableType,
+    AlterTableOperation, CreateExternalTable, LogicalPlan, TableProviderFilterPushDown,
+    TableType,
 };
 use datafusion_physical_plan::ExecutionPlan;
 
@@ -410,6 +411,27 @@ pub trait TableProvider: Any + Debug + Sync + Send {
         not_impl_err!("TRUNCATE not supported for {} table", self.table_type())
     }
 
+    /// Apply an `ALTER TABLE` operation, such as adding, dropping or renaming a
+    /// column, to this table.
+    ///
+    /// Returns the altered table, which replaces this table in its schema.
+    /// The operation has been validated against [`Self::schema`] with
+    /// [`AlterTableOperation::apply_to_schema`], and the schema of the
+    /// returned table should be the schema computed by that method.
+    ///
+    /// [`AlterTableOperation::RenameTable`] is applied by the schema of the
+    /// table with [`SchemaProvider::rename_table`] and is not passed to this
+    /// method.
+    ///
+    /// [`SchemaProvider::rename_table`]: crate::SchemaProvider::rename_table
+    async fn alter(
+        &self,
+        _state: &dyn Session,
+        _operation: &AlterTableOperation,
+    ) -> Result<Arc<dyn TableProvider>> {
+        not_impl_err!("ALTER TABLE not supported for {} table", self.table_type())
+    }
+
     /// Merge rows from a source into this table.
     ///
     /// The `source` is an [`ExecutionPlan`] representing the USING clause.
@@ -441,8 +463,57 @@ pub trait TableProvider: Any + Debug + Sync + Send {
             self.table_type()
         )))
     }
+
+    /// Take a snapshot of the rows of this table, which can later be passed
+    /// to [`Self::appended_rows`] to find the rows appended since.
+    ///
+    /// Used to refresh materialized views incrementally. Returns `None`, the
+    /// default, if the table does not support snapshots.
+    // Hand-written `#[async_trait]` expansion to reduce compile time. See
+    // <https://github.com/apache/datafusion/issues/13814#issuecomment-5292709677>
+    fn snapshot<'life0, 'life1, 'async_trait>(
+        &'life0 self,
+        _state: &'life1 dyn Session,
+    ) -> BoxFuture<'async_trait, Result<Option<TableSnapshot>>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(ready(Ok(None)))
+    }
+
+    /// Returns a table of the rows of snapshot `to` that are not in snapshot
+    /// `from`, or of all the rows of `to` if `from` is `None`. Both snapshots
+    /// were returned by [`Self::snapshot`] of this table.
+    ///
+    /// Returns `None` if the rows of `from` were not all kept in `to`, for
+    /// example because rows were deleted or the table was overwritten, so the
+    /// difference is not only made of appended rows.
+    fn appended_rows(
+        &self,
+        _from: Option<&TableSnapshot>,
+        _to: &TableSnapshot,
+    ) -> Result<Option<Arc<dyn TableProvider>>> {
+        Ok(None)
+    }
+
+    /// Returns `true` if snapshots `a` and `b`, both returned by
+    /// [`Self::snapshot`] of this table, hold the same rows.
+    ///
+    /// Used to tell whether a materialized view reading this table is up to
+    /// date. Returns `false`, the default, if it cannot tell.
+    fn same_snapshot(&self, _a: &TableSnapshot, _b: &TableSnapshot) -> bool {
+        false
+    }
 }
 
+/// A snapshot of the rows of a table, see [`TableProvider::snapshot`].
+///
+/// The content of the snapshot is specific to the [`TableProvider`] that
+/// created it.
+pub type TableSnapshot = Arc<dyn Any + Send + Sync>;
+
 impl dyn TableProvider {
     /// Returns `true` if the table provider is of type `T`.
     ///
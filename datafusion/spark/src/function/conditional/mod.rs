@@ -27,8 +27,11 @@ pub mod expr_fn {
     use datafusion_functions::export_functions;
 
     export_functions!((r#if, "If arg1 evaluates to true, then returns arg2; otherwise returns arg3", arg1 arg2 arg3));
+
+    pub use datafusion_functions::math::expr_fn::nanvl;
 }
 
 pub fn functions() -> Vec<Arc<ScalarUDF>> {
-    vec![r#if()]
+    // Spark's `nanvl` has the same semantics as the default one
+    vec![r#if(), datafusion_functions::math::nanvl()]
 }
@@ -0,0 +1,221 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use datafusion_common::{Result, ScalarValue};
+
+use crate::function::options_utils::{bool_option, char_option, parse_options};
+
+/// The CSV dialect of Spark's CSV functions, configured by their `options`
+/// map argument
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct CsvFormat {
+    sep: char,
+    quote: char,
+    escape: char,
+    null_value: String,
+    ignore_leading_whitespace: bool,
+    ignore_trailing_whitespace: bool,
+}
+
+impl CsvFormat {
+    /// Builds the format of a function reading CSV
+    pub(super) fn try_new_reader(
+        function_name: &str,
+        options: Option<&ScalarValue>,
+    ) -> Result<Self> {
+        Self::try_new(function_name, options, false)
+    }
+
+    /// Builds the format of a function writing CSV, which trims whitespace
+    /// around values by default
+    pub(super) fn try_new_writer(
+        function_name: &str,
+        options: Option<&ScalarValue>,
+    ) -> Result<Self> {
+        Self::try_new(function_name, options, true)
+    }
+
+    fn try_new(
+        function_name: &str,
+        options: Option<&ScalarValue>,
+        ignore_whitespace: bool,
+    ) -> Result<Self> {
+        let options = parse_options(function_name, options)?;
+        let sep = match options.get("delimiter") {
+            Some(_) => char_option(function_name, &options, "delimiter", ',')?,
+            None => char_option(function_name, &options, "sep", ',')?,
+        };
+        Ok(Self {
+            sep,
+            quote: char_option(function_name, &options, "quote", '"')?,
+            escape: char_option(function_name, &options, "escape", '\\')?,
+            null_value: options.get("nullvalue").cloned().unwrap_or_default(),
+            ignore_leading_whitespace: bool_option(
+                function_name,
+                &options,
+                "ignoreleadingwhitespace",
+                ignore_whitespace,
+            )?,
+            ignore_trailing_whitespace: bool_option(
+                function_name,
+                &options,
+                "ignoretrailingwhitespace",
+                ignore_whitespace,
+            )?,
+        })
+    }
+
+    /// Splits a CSV record into its values. Unquoted values equal to the
+    /// `nullValue` option are returned as `None`.
+    pub(super) fn parse_record(&self, record: &str) -> Vec<Option<String>> {
+        let mut values = vec![];
+        let mut chars = record.chars().peekable();
+        loop {
+            let mut value = String::new();
+            let mut quoted = false;
+            if self.ignore_leading_whitespace {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            }
+            if chars.next_if_eq(&self.quote).is_some() {
+                quoted = true;
+                while let Some(c) = chars.next() {
+                    if c == self.escape
+                        && c != self.quote
+                        && chars.peek().is_some_and(|&next| {
+                            next == self.quote || next == self.escape
+                        })
+                    {
+                        value.extend(chars.next());
+                    } else if c == self.quote {
+                        // A doubled quote inside a quoted value is a literal quote
+                        if chars.next_if_eq(&self.quote).is_some() {
+                            value.push(c);
+                        } else {
+                            break;
+                        }
+                    } else {
+                        value.push(c);
+                    }
+                }
+            }
+            // Characters following a closing quote are kept, like Spark does
+            while let Some(c) = chars.next_if(|&c| c != self.sep) {
+                value.push(c);
+            }
+            if self.ignore_trailing_whitespace && !quoted {
+                value.truncate(value.trim_end().len());
+            }
+
+            if !quoted && value == self.null_value {
+                values.push(None);
+            } else {
+                values.push(Some(value));
+            }
+            if chars.next().is_none() {
+                return values;
+            }
+        }
+    }
+
+    /// Appends `value` to a CSV record, quoting it when needed
+    pub(super) fn write_value(&self, value: Option<&str>, record: &mut String) {
+        let Some(mut value) = value else {
+            record.push_str(&self.null_value);
+            return;
+        };
+        if self.ignore_leading_whitespace {
+            value = value.trim_start();
+        }
+        if self.ignore_trailing_whitespace {
+            value = value.trim_end();
+        }
+        if value.is_empty() {
+            record.push(self.quote);
+            record.push(self.quote);
+            return;
+        }
+
+        let needs_quotes = value.chars().any(|c| {
+            c == self.sep || c == self.quote || c == self.escape || c == '\n' || c == '\r'
+        });
+        if !needs_quotes {
+            record.push_str(value);
+            return;
+        }
+        record.push(self.quote);
+        for c in value.chars() {
+            if c == self.quote || c == self.escape {
+                record.push(self.escape);
+            }
+            record.push(c);
+        }
+        record.push(self.quote);
+    }
+
+    /// Appends the separator between two values of a CSV record
+    pub(super) fn write_separator(&self, record: &mut String) {
+        record.push(self.sep);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reader() -> CsvFormat {
+        CsvFormat::try_new_reader("from_csv", None).unwrap()
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let format = reader();
+        assert_eq!(
+            format.parse_record("1,abc,,\"x,y\""),
+            vec![
+                Some("1".to_string()),
+                Some("abc".to_string()),
+                None,
+                Some("x,y".to_string())
+            ]
+        );
+        assert_eq!(
+            format.parse_record(r#""a\"b","c""d","""#),
+            vec![
+                Some("a\"b".to_string()),
+                Some("c\"d".to_string()),
+                Some("".to_string())
+            ]
+        );
+        assert_eq!(format.parse_record(""), vec![None]);
+    }
+
+    #[test]
+    fn test_write_value() {
+        let format = CsvFormat::try_new_writer("to_csv", None).unwrap();
+        let mut record = String::new();
+        for (idx, value) in [Some("a"), None, Some(" b "), Some("x,\"y"), Some("")]
+            .into_iter()
+            .enumerate()
+        {
+            if idx > 0 {
+                format.write_separator(&mut record);
+            }
+            format.write_value(value, &mut record);
+        }
+        assert_eq!(record, r#"a,,b,"x,\"y","""#);
+    }
+}
@@ -0,0 +1,208 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringBuilder, StructArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion_common::{Result, exec_err, internal_err, plan_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility,
+};
+
+use super::csv_format::CsvFormat;
+use crate::function::ddl_utils::{parse_ddl_schema, to_ddl_type};
+
+/// Spark-compatible `from_csv` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#from_csv>
+///
+/// `from_csv(csv_str, schema[, options])` parses a CSV record into a struct
+/// with the fields of the DDL `schema` string, e.g. `'a INT, b STRING'`.
+/// Missing values and values that cannot be converted to the type of their
+/// field are `NULL`, as in Spark's default `PERMISSIVE` mode.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkFromCsv {
+    signature: Signature,
+}
+
+impl Default for SparkFromCsv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkFromCsv {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkFromCsv {
+    fn name(&self) -> &str {
+        "from_csv"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        let schema = match args.scalar_arguments.get(1) {
+            Some(Some(value)) => value.try_as_str().flatten(),
+            _ => None,
+        };
+        let Some(schema) = schema else {
+            return plan_err!("The schema of from_csv must be a STRING literal");
+        };
+        let fields = parse_ddl_schema(schema)?
+            .iter()
+            .map(|field| {
+                if field.data_type().is_nested() {
+                    return plan_err!(
+                        "from_csv does not support the type {} of field '{}'",
+                        to_ddl_type(field.data_type()),
+                        field.name()
+                    );
+                }
+                Ok(Field::new(field.name(), field.data_type().clone(), true))
+            })
+            .collect::<Result<Fields>>()?;
+        Ok(Arc::new(Field::new(
+            self.name(),
+            DataType::Struct(fields),
+            true,
+        )))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let DataType::Struct(fields) = args.return_field.data_type() else {
+            return internal_err!("from_csv must return a struct");
+        };
+        let options = match args.args.get(2) {
+            None => None,
+            Some(ColumnarValue::Scalar(options)) => Some(options),
+            Some(ColumnarValue::Array(_)) => {
+                return exec_err!("The options of from_csv must be a MAP literal");
+            }
+        };
+        let format = CsvFormat::try_new_reader(self.name(), options)?;
+        let records = args.args[0].to_array(args.number_rows)?;
+        Ok(ColumnarValue::Array(from_csv_inner(
+            &records, fields, &format,
+        )?))
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        match arg_types {
+            [_, _] => Ok(vec![DataType::Utf8, DataType::Utf8]),
+            [_, _, options @ (DataType::Map(_, _) | DataType::Null)] => {
+                Ok(vec![DataType::Utf8, DataType::Utf8, options.clone()])
+            }
+            [_, _, other] => {
+                plan_err!("The options of from_csv must be a MAP, got {other}")
+            }
+            _ => plan_err!("from_csv expects 2 or 3 arguments, got {}", arg_types.len()),
+        }
+    }
+}
+
+fn from_csv_inner(
+    records: &ArrayRef,
+    fields: &Fields,
+    format: &CsvFormat,
+) -> Result<ArrayRef> {
+    let records = records.as_string::<i32>();
+    let mut builders = fields
+        .iter()
+        .map(|_| StringBuilder::with_capacity(records.len(), 0))
+        .collect::<Vec<_>>();
+
+    for record in records.iter() {
+        let mut values = match record {
+            Some(record) => format.parse_record(record).into_iter(),
+            None => vec![].into_iter(),
+        };
+        for builder in &mut builders {
+            builder.append_option(values.next().flatten());
+        }
+    }
+
+    let columns = builders
+        .iter_mut()
+        .zip(fields.iter())
+        .map(|(builder, field)| {
+            let values: ArrayRef = Arc::new(builder.finish());
+            Ok(cast(&values, field.data_type())?)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Arc::new(StructArray::try_new(
+        fields.clone(),
+        columns,
+        records.nulls().cloned(),
+    )?))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Float64Array, Int32Array, StringArray};
+
+    use super::*;
+
+    #[test]
+    fn test_from_csv_malformed_records() {
+        let fields = Fields::from(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new("c", DataType::Float64, true),
+        ]);
+        let records: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("1,x,2.5"),
+            Some("\"unterminated,2"),
+            Some("abc,\"q\"\"\",x"),
+            Some("1"),
+            Some("1,y,3,4"),
+            None,
+        ]));
+        let format = CsvFormat::try_new_reader("from_csv", None).unwrap();
+        let result = from_csv_inner(&records, &fields, &format).unwrap();
+        let result = result.as_struct();
+
+        assert_eq!(
+            result.column(0).as_primitive(),
+            &Int32Array::from(vec![Some(1), None, None, Some(1), Some(1), None])
+        );
+        assert_eq!(
+            result.column(1).as_string::<i32>(),
+            &StringArray::from(vec![Some("x"), None, Some("q\""), None, Some("y"), None])
+        );
+        assert_eq!(
+            result.column(2).as_primitive(),
+            &Float64Array::from(vec![Some(2.5), None, None, None, Some(3.0), None])
+        );
+        assert_eq!(result.null_count(), 1);
+        assert!(result.is_null(5));
+    }
+}
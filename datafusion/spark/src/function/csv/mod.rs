@@ -15,11 +15,35 @@
 // specific language governing permissions and limitations
 // under the License.
 
+mod csv_format;
+pub mod from_csv;
+pub mod schema_of_csv;
+pub mod to_csv;
+
 use datafusion_expr::ScalarUDF;
+use datafusion_functions::make_udf_function;
 use std::sync::Arc;
 
-pub mod expr_fn {}
+make_udf_function!(from_csv::SparkFromCsv, from_csv);
+make_udf_function!(schema_of_csv::SparkSchemaOfCsv, schema_of_csv);
+make_udf_function!(to_csv::SparkToCsv, to_csv);
+
+pub mod expr_fn {
+    use datafusion_functions::export_functions;
+
+    export_functions!((
+        from_csv,
+        "Parses a CSV string into a struct with the given DDL schema.",
+        args,
+    ));
+    export_functions!((
+        schema_of_csv,
+        "Returns the schema of a CSV string in DDL format.",
+        args,
+    ));
+    export_functions!((to_csv, "Formats a struct as a CSV string.", args,));
+}
 
 pub fn functions() -> Vec<Arc<ScalarUDF>> {
-    vec![]
+    vec![from_csv(), schema_of_csv(), to_csv()]
 }
@@ -0,0 +1,184 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, StringArray};
+use arrow::datatypes::{DataType, Field, Fields, TimeUnit};
+use chrono::NaiveDateTime;
+use datafusion_common::{Result, exec_err, plan_err};
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+
+use super::csv_format::CsvFormat;
+use crate::function::ddl_utils::to_ddl_type;
+
+/// Spark-compatible `schema_of_csv` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#schema_of_csv>
+///
+/// `schema_of_csv(csv[, options])` infers the schema of a CSV record and
+/// returns it in DDL format, e.g. `STRUCT<_c0: INT, _c1: STRING>`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkSchemaOfCsv {
+    signature: Signature,
+}
+
+impl Default for SparkSchemaOfCsv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkSchemaOfCsv {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkSchemaOfCsv {
+    fn name(&self) -> &str {
+        "schema_of_csv"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let options = match args.args.get(1) {
+            None => None,
+            Some(ColumnarValue::Scalar(options)) => Some(options),
+            Some(ColumnarValue::Array(_)) => {
+                return exec_err!("The options of schema_of_csv must be a MAP literal");
+            }
+        };
+        let format = CsvFormat::try_new_reader(self.name(), options)?;
+        let records = args.args[0].to_array(args.number_rows)?;
+        let schemas: StringArray = records
+            .as_string::<i32>()
+            .iter()
+            .map(|record| record.map(|record| infer_schema(record, &format)))
+            .collect();
+        Ok(ColumnarValue::Array(Arc::new(schemas) as ArrayRef))
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        match arg_types {
+            [_] => Ok(vec![DataType::Utf8]),
+            [_, options @ (DataType::Map(_, _) | DataType::Null)] => {
+                Ok(vec![DataType::Utf8, options.clone()])
+            }
+            [_, other] => {
+                plan_err!("The options of schema_of_csv must be a MAP, got {other}")
+            }
+            _ => plan_err!(
+                "schema_of_csv expects 1 or 2 arguments, got {}",
+                arg_types.len()
+            ),
+        }
+    }
+}
+
+fn infer_schema(record: &str, format: &CsvFormat) -> String {
+    let fields = format
+        .parse_record(record)
+        .iter()
+        .enumerate()
+        .map(|(idx, value)| {
+            let data_type = value.as_deref().map_or(DataType::Utf8, infer_type);
+            Field::new(format!("_c{idx}"), data_type, true)
+        })
+        .collect::<Fields>();
+    to_ddl_type(&DataType::Struct(fields))
+}
+
+/// Infers the type of a CSV value, trying the same types in the same order
+/// as Spark's `CSVInferSchema`
+fn infer_type(value: &str) -> DataType {
+    if value.parse::<i32>().is_ok() {
+        DataType::Int32
+    } else if value.parse::<i64>().is_ok() {
+        DataType::Int64
+    } else if let Some(precision) = integer_precision(value) {
+        DataType::Decimal128(precision, 0)
+    } else if is_double(value) {
+        DataType::Float64
+    } else if is_timestamp(value) {
+        DataType::Timestamp(TimeUnit::Microsecond, Some(Arc::from("UTC")))
+    } else if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+        DataType::Boolean
+    } else {
+        DataType::Utf8
+    }
+}
+
+/// Returns the number of digits of an integer too large for a `BIGINT`, if
+/// it fits a `DECIMAL`
+fn integer_precision(value: &str) -> Option<u8> {
+    let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+    (!digits.is_empty()
+        && digits.len() <= 38
+        && digits.bytes().all(|b| b.is_ascii_digit()))
+    .then_some(digits.len() as u8)
+}
+
+fn is_double(value: &str) -> bool {
+    // Spark also accepts the `NaN` and `Infinity` spellings of Java
+    matches!(value, "NaN" | "Infinity" | "-Infinity" | "+Infinity")
+        || (value.parse::<f64>().is_ok()
+            && value.bytes().all(|b| {
+                b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'+' | b'e' | b'E')
+            }))
+}
+
+fn is_timestamp(value: &str) -> bool {
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .any(|format| NaiveDateTime::parse_from_str(value, format).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_schema() {
+        let format = CsvFormat::try_new_reader("schema_of_csv", None).unwrap();
+        for (record, schema) in [
+            (
+                "1,99999999999,1.5e3,true,2020-01-01T00:00:00,x",
+                "STRUCT<_c0: INT, _c1: BIGINT, _c2: DOUBLE, _c3: BOOLEAN, _c4: TIMESTAMP, _c5: STRING>",
+            ),
+            ("", "STRUCT<_c0: STRING>"),
+            ("1,\"unterminated,2", "STRUCT<_c0: INT, _c1: STRING>"),
+            (
+                "\"1\"x,1.5.5,--1",
+                "STRUCT<_c0: STRING, _c1: STRING, _c2: STRING>",
+            ),
+        ] {
+            assert_eq!(infer_schema(record, &format), schema, "{record}");
+        }
+    }
+}
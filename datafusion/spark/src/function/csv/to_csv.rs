@@ -0,0 +1,196 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, StringBuilder};
+use arrow::datatypes::DataType;
+use arrow::util::display::{ArrayFormatter, FormatOptions};
+use datafusion_common::{Result, exec_err, plan_err};
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+
+use super::csv_format::CsvFormat;
+use crate::function::ddl_utils::to_ddl_type;
+
+/// Spark's default `timestampFormat` of CSV, `yyyy-MM-dd'T'HH:mm:ss.SSSXXX`
+const TIMESTAMP_TZ_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f%:z";
+/// Spark's default `timestampNTZFormat` of CSV
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f";
+
+/// Spark-compatible `to_csv` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#to_csv>
+///
+/// `to_csv(struct[, options])` formats a struct as a CSV record. `NULL`
+/// fields are written as the `nullValue` option, an empty string by default.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkToCsv {
+    signature: Signature,
+}
+
+impl Default for SparkToCsv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkToCsv {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkToCsv {
+    fn name(&self) -> &str {
+        "to_csv"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Utf8)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let options = match args.args.get(1) {
+            None => None,
+            Some(ColumnarValue::Scalar(options)) => Some(options),
+            Some(ColumnarValue::Array(_)) => {
+                return exec_err!("The options of to_csv must be a MAP literal");
+            }
+        };
+        let format = CsvFormat::try_new_writer(self.name(), options)?;
+        let structs = args.args[0].to_array(args.number_rows)?;
+        Ok(ColumnarValue::Array(to_csv_inner(&structs, &format)?))
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let (struct_type, options) = match arg_types {
+            [struct_type] => (struct_type, None),
+            [
+                struct_type,
+                options @ (DataType::Map(_, _) | DataType::Null),
+            ] => (struct_type, Some(options)),
+            [_, other] => {
+                return plan_err!("The options of to_csv must be a MAP, got {other}");
+            }
+            _ => {
+                return plan_err!(
+                    "to_csv expects 1 or 2 arguments, got {}",
+                    arg_types.len()
+                );
+            }
+        };
+        let DataType::Struct(fields) = struct_type else {
+            return plan_err!("to_csv expects a STRUCT argument, got {struct_type}");
+        };
+        if let Some(field) = fields.iter().find(|field| field.data_type().is_nested()) {
+            return plan_err!(
+                "to_csv does not support the type {} of field '{}'",
+                to_ddl_type(field.data_type()),
+                field.name()
+            );
+        }
+        Ok([Some(struct_type), options]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect())
+    }
+}
+
+fn to_csv_inner(structs: &ArrayRef, format: &CsvFormat) -> Result<ArrayRef> {
+    let structs = structs.as_struct();
+    let options = FormatOptions::new()
+        .with_timestamp_format(Some(TIMESTAMP_FORMAT))
+        .with_timestamp_tz_format(Some(TIMESTAMP_TZ_FORMAT));
+    let formatters = structs
+        .columns()
+        .iter()
+        .map(|column| ArrayFormatter::try_new(column.as_ref(), &options))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut builder = StringBuilder::with_capacity(structs.len(), 0);
+    let mut record = String::new();
+    let mut value = String::new();
+    for row in 0..structs.len() {
+        if structs.is_null(row) {
+            builder.append_null();
+            continue;
+        }
+        record.clear();
+        for (idx, (column, formatter)) in
+            structs.columns().iter().zip(&formatters).enumerate()
+        {
+            if idx > 0 {
+                format.write_separator(&mut record);
+            }
+            if column.is_null(row) {
+                format.write_value(None, &mut record);
+                continue;
+            }
+            value.clear();
+            value.push_str(&formatter.value(row).try_to_string()?);
+            if matches!(column.data_type(), DataType::Timestamp(_, Some(_)))
+                && value.ends_with("+00:00")
+            {
+                value.truncate(value.len() - "+00:00".len());
+                value.push('Z');
+            }
+            format.write_value(Some(&value), &mut record);
+        }
+        builder.append_value(&record);
+    }
+    Ok(Arc::new(builder.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int32Array, StringArray, StructArray};
+    use arrow::buffer::NullBuffer;
+    use arrow::datatypes::{Field, Fields};
+
+    use super::*;
+
+    #[test]
+    fn test_to_csv() {
+        let fields = Fields::from(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]);
+        let structs: ArrayRef = Arc::new(StructArray::new(
+            fields,
+            vec![
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(3)])),
+                Arc::new(StringArray::from(vec![Some(" a "), Some("x,\"y"), None])),
+            ],
+            Some(NullBuffer::from(vec![true, true, false])),
+        ));
+        let format = CsvFormat::try_new_writer("to_csv", None).unwrap();
+        let result = to_csv_inner(&structs, &format).unwrap();
+        assert_eq!(
+            result.as_string::<i32>(),
+            &StringArray::from(vec![Some("1,a"), Some(r#","x,\"y""#), None])
+        );
+    }
+}
@@ -0,0 +1,370 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Parsing and formatting of Spark DDL schema strings, such as
+//! `a INT, b ARRAY<STRING>` or `STRUCT<a: INT, b: MAP<STRING, DOUBLE>>`
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Fields, TimeUnit};
+use datafusion_common::{Result, plan_err};
+
+/// Parses a Spark DDL schema string, either a comma separated list of fields
+/// or a `STRUCT<...>` type, into the fields of a struct
+pub fn parse_ddl_schema(ddl: &str) -> Result<Fields> {
+    let mut parser = DdlParser::new(ddl);
+    let fields = if parser.consume_keyword("STRUCT") && parser.consume('<') {
+        if parser.consume('>') {
+            Fields::empty()
+        } else {
+            parser.parse_fields(Some('>'))?
+        }
+    } else {
+        parser.pos = 0;
+        parser.parse_fields(None)?
+    };
+    parser.skip_whitespace();
+    if !parser.is_eof() {
+        return parser.error("end of schema");
+    }
+    Ok(fields)
+}
+
+/// Formats `data_type` the way Spark displays it in DDL, e.g. `STRUCT<a: INT>`
+pub fn to_ddl_type(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Null => "VOID".to_string(),
+        DataType::Boolean => "BOOLEAN".to_string(),
+        DataType::Int8 => "TINYINT".to_string(),
+        DataType::Int16 => "SMALLINT".to_string(),
+        DataType::Int32 => "INT".to_string(),
+        DataType::Int64 => "BIGINT".to_string(),
+        DataType::Float32 => "FLOAT".to_string(),
+        DataType::Float64 => "DOUBLE".to_string(),
+        DataType::Decimal128(p, s) => format!("DECIMAL({p},{s})"),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => "STRING".to_string(),
+        DataType::Binary | DataType::LargeBinary | DataType::BinaryView => {
+            "BINARY".to_string()
+        }
+        DataType::Date32 => "DATE".to_string(),
+        DataType::Timestamp(_, Some(_)) => "TIMESTAMP".to_string(),
+        DataType::Timestamp(_, None) => "TIMESTAMP_NTZ".to_string(),
+        DataType::List(field)
+        | DataType::LargeList(field)
+        | DataType::FixedSizeList(field, _) => {
+            format!("ARRAY<{}>", to_ddl_type(field.data_type()))
+        }
+        DataType::Map(field, _) => match field.data_type() {
+            DataType::Struct(entries) if entries.len() == 2 => format!(
+                "MAP<{}, {}>",
+                to_ddl_type(entries[0].data_type()),
+                to_ddl_type(entries[1].data_type())
+            ),
+            other => other.to_string(),
+        },
+        DataType::Struct(fields) => {
+            let fields = fields
+                .iter()
+                .map(|field| {
+                    format!("{}: {}", field.name(), to_ddl_type(field.data_type()))
+                })
+                .collect::<Vec<_>>();
+            format!("STRUCT<{}>", fields.join(", "))
+        }
+        other => other.to_string(),
+    }
+}
+
+struct DdlParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> DdlParser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn is_eof(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.rest().chars().next()
+    }
+
+    fn peek_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        let rest = self.rest();
+        rest.len() >= keyword.len()
+            && rest[..keyword.len()].eq_ignore_ascii_case(keyword)
+            && !rest[keyword.len()..]
+                .starts_with(|c: char| c.is_alphanumeric() || c == '_')
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        let matched = self.peek_keyword(keyword);
+        if matched {
+            self.pos += keyword.len();
+        }
+        matched
+    }
+
+    fn consume(&mut self, c: char) -> bool {
+        let matched = self.peek() == Some(c);
+        if matched {
+            self.pos += c.len_utf8();
+        }
+        matched
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.consume(c) {
+            Ok(())
+        } else {
+            self.error(&format!("'{c}'"))
+        }
+    }
+
+    fn error<T>(&self, expected: &str) -> Result<T> {
+        plan_err!(
+            "Invalid DDL schema '{}': expected {expected} at position {}",
+            self.input,
+            self.pos
+        )
+    }
+
+    fn parse_identifier(&mut self) -> Result<String> {
+        if self.consume('`') {
+            let mut name = String::new();
+            loop {
+                let Some(c) = self.rest().chars().next() else {
+                    return self.error("'`'");
+                };
+                self.pos += c.len_utf8();
+                if c == '`' {
+                    // A doubled backtick escapes a backtick
+                    if self.rest().starts_with('`') {
+                        self.pos += 1;
+                    } else {
+                        return Ok(name);
+                    }
+                }
+                name.push(c);
+            }
+        }
+        self.skip_whitespace();
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or_else(|| self.rest().len());
+        if len == 0 {
+            return self.error("an identifier");
+        }
+        let name = self.rest()[..len].to_string();
+        self.pos += len;
+        Ok(name)
+    }
+
+    fn parse_integer(&mut self) -> Result<u32> {
+        self.skip_whitespace();
+        let len = self
+            .rest()
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or_else(|| self.rest().len());
+        match self.rest()[..len].parse() {
+            Ok(value) => {
+                self.pos += len;
+                Ok(value)
+            }
+            Err(_) => self.error("an integer"),
+        }
+    }
+
+    fn parse_string_literal(&mut self) -> Result<String> {
+        let Some(quote @ ('\'' | '"')) = self.peek() else {
+            return self.error("a string literal");
+        };
+        self.pos += 1;
+        let Some(len) = self.rest().find(quote) else {
+            return self.error(&format!("{quote}"));
+        };
+        let value = self.rest()[..len].to_string();
+        self.pos += len + 1;
+        Ok(value)
+    }
+
+    /// Parses `name [:] type [NOT NULL] [COMMENT '...'], ...` up to `end`
+    fn parse_fields(&mut self, end: Option<char>) -> Result<Fields> {
+        let mut fields = vec![];
+        loop {
+            let name = self.parse_identifier()?;
+            self.consume(':');
+            let data_type = self.parse_type()?;
+            let nullable = if self.consume_keyword("NOT") {
+                if !self.consume_keyword("NULL") {
+                    return self.error("NULL");
+                }
+                false
+            } else {
+                true
+            };
+            if self.consume_keyword("COMMENT") {
+                self.parse_string_literal()?;
+            }
+            fields.push(Field::new(name, data_type, nullable));
+
+            if !self.consume(',') {
+                break;
+            }
+        }
+        if let Some(end) = end {
+            self.expect(end)?;
+        }
+        Ok(fields.into())
+    }
+
+    /// Parses an optional `(n[, m])` type parameter list
+    fn parse_type_parameters(&mut self) -> Result<Vec<u32>> {
+        let mut parameters = vec![];
+        if self.consume('(') {
+            loop {
+                parameters.push(self.parse_integer()?);
+                if !self.consume(',') {
+                    break;
+                }
+            }
+            self.expect(')')?;
+        }
+        Ok(parameters)
+    }
+
+    fn parse_type(&mut self) -> Result<DataType> {
+        let name = self.parse_identifier()?.to_ascii_uppercase();
+        let data_type = match name.as_str() {
+            "BOOLEAN" => DataType::Boolean,
+            "TINYINT" | "BYTE" => DataType::Int8,
+            "SMALLINT" | "SHORT" => DataType::Int16,
+            "INT" | "INTEGER" => DataType::Int32,
+            "BIGINT" | "LONG" => DataType::Int64,
+            "FLOAT" | "REAL" => DataType::Float32,
+            "DOUBLE" => DataType::Float64,
+            "DECIMAL" | "DEC" | "NUMERIC" => {
+                match self.parse_type_parameters()?.as_slice() {
+                    [] => DataType::Decimal128(10, 0),
+                    [precision] => DataType::Decimal128(*precision as u8, 0),
+                    [precision, scale] => {
+                        DataType::Decimal128(*precision as u8, *scale as i8)
+                    }
+                    _ => return self.error("DECIMAL(precision, scale)"),
+                }
+            }
+            "STRING" | "VARCHAR" | "CHAR" => {
+                self.parse_type_parameters()?;
+                DataType::Utf8
+            }
+            "BINARY" => DataType::Binary,
+            "DATE" => DataType::Date32,
+            "TIMESTAMP" | "TIMESTAMP_LTZ" => {
+                DataType::Timestamp(TimeUnit::Microsecond, Some(Arc::from("UTC")))
+            }
+            "TIMESTAMP_NTZ" => DataType::Timestamp(TimeUnit::Microsecond, None),
+            "VOID" => DataType::Null,
+            "ARRAY" => {
+                self.expect('<')?;
+                let element = self.parse_type()?;
+                self.expect('>')?;
+                DataType::new_list(element, true)
+            }
+            "MAP" => {
+                self.expect('<')?;
+                let key = self.parse_type()?;
+                self.expect(',')?;
+                let value = self.parse_type()?;
+                self.expect('>')?;
+                DataType::Map(
+                    Arc::new(Field::new(
+                        "entries",
+                        DataType::Struct(Fields::from(vec![
+                            Field::new("key", key, false),
+                            Field::new("value", value, true),
+                        ])),
+                        false,
+                    )),
+                    false,
+                )
+            }
+            "STRUCT" => {
+                self.expect('<')?;
+                if self.consume('>') {
+                    DataType::Struct(Fields::empty())
+                } else {
+                    DataType::Struct(self.parse_fields(Some('>'))?)
+                }
+            }
+            _ => {
+                return plan_err!(
+                    "Unsupported data type '{name}' in DDL schema '{}'",
+                    self.input
+                );
+            }
+        };
+        Ok(data_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ddl_schema() {
+        let fields = parse_ddl_schema(
+            "a INT, `b c` string NOT NULL, d: DECIMAL(5, 2) COMMENT 'x', \
+             e ARRAY<STRUCT<f: BIGINT>>, g MAP<STRING, DOUBLE>",
+        )
+        .unwrap();
+        let ddl = to_ddl_type(&DataType::Struct(fields.clone()));
+        assert_eq!(
+            ddl,
+            "STRUCT<a: INT, b c: STRING, d: DECIMAL(5,2), e: ARRAY<STRUCT<f: BIGINT>>, \
+             g: MAP<STRING, DOUBLE>>"
+        );
+        assert!(!fields[1].is_nullable());
+
+        let nested = parse_ddl_schema("STRUCT<a: INT, b: STRING>").unwrap();
+        assert_eq!(nested.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_ddl_schema_errors() {
+        assert!(parse_ddl_schema("a INT,").is_err());
+        assert!(parse_ddl_schema("a UNKNOWN").is_err());
+        assert!(parse_ddl_schema("a ARRAY<INT").is_err());
+        assert!(parse_ddl_schema("a INT b").is_err());
+    }
+}
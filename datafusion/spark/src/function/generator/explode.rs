@@ -0,0 +1,225 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, Int32Array, ListArray, StructArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion_common::utils::take_function_args;
+use datafusion_common::{Result, exec_err, internal_err, plan_err};
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+use datafusion_functions::utils::make_scalar_function;
+
+/// Spark-compatible `explode`, `explode_outer`, `posexplode` and
+/// `posexplode_outer` generator functions.
+///
+/// Returns, for each input row, a list with one struct per array element
+/// (field `col`) or map entry (fields `key` and `value`). The `posexplode`
+/// variants prepend the 0-based position of the element as field `pos`.
+/// [`SparkFunctionPlanner`](crate::planner::SparkFunctionPlanner) unnests the
+/// list into rows; the `_outer` variants produce a single row of `NULL`s for a
+/// `NULL` or empty input instead of no rows.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#explode>
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#posexplode>
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkExplode {
+    signature: Signature,
+    name: &'static str,
+    position: bool,
+    outer: bool,
+}
+
+impl Default for SparkExplode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkExplode {
+    fn new_with(name: &'static str, position: bool, outer: bool) -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+            name,
+            position,
+            outer,
+        }
+    }
+
+    /// `explode(expr)`
+    pub fn new() -> Self {
+        Self::new_with("explode", false, false)
+    }
+
+    /// `explode_outer(expr)`
+    pub fn new_outer() -> Self {
+        Self::new_with("explode_outer", false, true)
+    }
+
+    /// `posexplode(expr)`
+    pub fn new_posexplode() -> Self {
+        Self::new_with("posexplode", true, false)
+    }
+
+    /// `posexplode_outer(expr)`
+    pub fn new_posexplode_outer() -> Self {
+        Self::new_with("posexplode_outer", true, true)
+    }
+
+    /// Returns true for the `_outer` variants
+    pub fn is_outer(&self) -> bool {
+        self.outer
+    }
+}
+
+impl ScalarUDFImpl for SparkExplode {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        let [arg_type] = take_function_args(self.name(), arg_types)?;
+        let mut fields = vec![];
+        if self.position {
+            fields.push(Field::new("pos", DataType::Int32, true));
+        }
+        match arg_type {
+            DataType::List(field)
+            | DataType::LargeList(field)
+            | DataType::FixedSizeList(field, _) => {
+                fields.push(Field::new("col", field.data_type().clone(), true));
+            }
+            DataType::Map(field, _) => {
+                let DataType::Struct(entries) = field.data_type() else {
+                    return internal_err!("Map entries must be a struct");
+                };
+                let [key, value] = take_function_args("map entries", entries.iter())?;
+                fields.push(Field::new("key", key.data_type().clone(), true));
+                fields.push(Field::new("value", value.data_type().clone(), true));
+            }
+            other => {
+                return plan_err!(
+                    "{} expects an ARRAY or MAP argument, got {other}",
+                    self.name()
+                );
+            }
+        }
+        Ok(DataType::List(generated_row_field(fields.into())))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let fields = generated_row_fields(args.return_field.data_type())?;
+        let position = self.position;
+        make_scalar_function(
+            move |args: &[ArrayRef]| explode_inner(args, &fields, position),
+            vec![],
+        )(&args.args)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let [arg_type] = take_function_args(self.name(), arg_types)?;
+        match arg_type {
+            DataType::List(_)
+            | DataType::LargeList(_)
+            | DataType::FixedSizeList(_, _)
+            | DataType::Map(_, _) => Ok(vec![arg_type.clone()]),
+            other => plan_err!(
+                "{} expects an ARRAY or MAP argument, got {other}",
+                self.name()
+            ),
+        }
+    }
+}
+
+/// The list item of the type returned by generator functions: a generated row
+pub(super) fn generated_row_field(fields: Fields) -> FieldRef {
+    Arc::new(Field::new_list_field(DataType::Struct(fields), true))
+}
+
+/// The fields of the rows generated by a function returning `return_type`
+pub(super) fn generated_row_fields(return_type: &DataType) -> Result<Fields> {
+    match return_type {
+        DataType::List(field) => match field.data_type() {
+            DataType::Struct(fields) => Ok(fields.clone()),
+            other => {
+                internal_err!("Generator must return a list of structs, got {other}")
+            }
+        },
+        other => internal_err!("Generator must return a list of structs, got {other}"),
+    }
+}
+
+fn explode_inner(args: &[ArrayRef], fields: &Fields, position: bool) -> Result<ArrayRef> {
+    let [array] = take_function_args("explode", args)?;
+    let (offsets, mut columns, nulls) = match array.data_type() {
+        DataType::Map(_, _) => {
+            let map = array.as_map();
+            (
+                map.offsets().clone(),
+                map.entries().columns().to_vec(),
+                map.nulls().cloned(),
+            )
+        }
+        DataType::List(_) => {
+            let list = array.as_list::<i32>();
+            (
+                list.offsets().clone(),
+                vec![Arc::clone(list.values())],
+                list.nulls().cloned(),
+            )
+        }
+        DataType::LargeList(field) | DataType::FixedSizeList(field, _) => {
+            let array = cast(array, &DataType::List(Arc::clone(field)))?;
+            let list = array.as_list::<i32>();
+            (
+                list.offsets().clone(),
+                vec![Arc::clone(list.values())],
+                list.nulls().cloned(),
+            )
+        }
+        other => {
+            return exec_err!("explode expects an ARRAY or MAP argument, got {other}");
+        }
+    };
+
+    if position {
+        // Elements outside of any list are never unnested, so their position is irrelevant
+        let mut positions = vec![0; columns[0].len()];
+        for window in offsets.windows(2) {
+            let (start, end) = (window[0] as usize, window[1] as usize);
+            for (pos, idx) in (start..end).enumerate() {
+                positions[idx] = pos as i32;
+            }
+        }
+        columns.insert(0, Arc::new(Int32Array::from(positions)));
+    }
+
+    let rows = StructArray::try_new(fields.clone(), columns, None)?;
+    Ok(Arc::new(ListArray::try_new(
+        generated_row_field(fields.clone()),
+        offsets,
+        Arc::new(rows),
+        nulls,
+    )?))
+}
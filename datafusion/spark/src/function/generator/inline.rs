@@ -0,0 +1,109 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::datatypes::DataType;
+use datafusion_common::utils::take_function_args;
+use datafusion_common::{Result, plan_err};
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+
+use super::explode::generated_row_field;
+
+/// Spark-compatible `inline` and `inline_outer` generator functions.
+///
+/// Explodes an array of structs into rows, with one column per struct field.
+/// The array is returned as is and unnested into rows by
+/// [`SparkFunctionPlanner`](crate::planner::SparkFunctionPlanner).
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#inline>
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkInline {
+    signature: Signature,
+    outer: bool,
+}
+
+impl Default for SparkInline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkInline {
+    /// `inline(expr)`
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+            outer: false,
+        }
+    }
+
+    /// `inline_outer(expr)`
+    pub fn new_outer() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+            outer: true,
+        }
+    }
+
+    /// Returns true for `inline_outer`
+    pub fn is_outer(&self) -> bool {
+        self.outer
+    }
+}
+
+impl ScalarUDFImpl for SparkInline {
+    fn name(&self) -> &str {
+        if self.outer { "inline_outer" } else { "inline" }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        let [arg_type] = take_function_args(self.name(), arg_types)?;
+        match arg_type {
+            DataType::List(field)
+            | DataType::LargeList(field)
+            | DataType::FixedSizeList(field, _) => match field.data_type() {
+                DataType::Struct(fields) => {
+                    Ok(DataType::List(generated_row_field(fields.clone())))
+                }
+                other => plan_err!(
+                    "{} expects an ARRAY<STRUCT> argument, got ARRAY<{other}>",
+                    self.name()
+                ),
+            },
+            other => plan_err!(
+                "{} expects an ARRAY<STRUCT> argument, got {other}",
+                self.name()
+            ),
+        }
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let [arg] = take_function_args(self.name(), args.args)?;
+        arg.cast_to(args.return_field.data_type(), None)
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let [arg_type] = take_function_args(self.name(), arg_types)?;
+        self.return_type(arg_types)?;
+        Ok(vec![arg_type.clone()])
+    }
+}
@@ -15,11 +15,101 @@
 // specific language governing permissions and limitations
 // under the License.
 
+pub mod explode;
+pub mod inline;
+pub mod stack;
+
 use datafusion_expr::ScalarUDF;
+use datafusion_functions::make_udf_function;
 use std::sync::Arc;
 
-pub mod expr_fn {}
+make_udf_function!(explode::SparkExplode, explode);
+make_udf_function!(
+    explode::SparkExplode,
+    explode_outer,
+    explode::SparkExplode::new_outer
+);
+make_udf_function!(
+    explode::SparkExplode,
+    posexplode,
+    explode::SparkExplode::new_posexplode
+);
+make_udf_function!(
+    explode::SparkExplode,
+    posexplode_outer,
+    explode::SparkExplode::new_posexplode_outer
+);
+make_udf_function!(inline::SparkInline, inline);
+make_udf_function!(
+    inline::SparkInline,
+    inline_outer,
+    inline::SparkInline::new_outer
+);
+make_udf_function!(stack::SparkStack, stack);
+
+pub mod expr_fn {
+    use datafusion_functions::export_functions;
+
+    export_functions!((
+        explode,
+        "Returns a list with one row for each element of an array or entry of a map.",
+        arg1
+    ));
+    export_functions!((
+        explode_outer,
+        "Like explode, but generates a row of NULLs for a NULL or empty input.",
+        arg1
+    ));
+    export_functions!((
+        posexplode,
+        "Like explode, but also returns the 0-based position of each element.",
+        arg1
+    ));
+    export_functions!((
+        posexplode_outer,
+        "Like posexplode, but generates a row of NULLs for a NULL or empty input.",
+        arg1
+    ));
+    export_functions!((
+        inline,
+        "Returns a list with one row for each struct of an array of structs.",
+        arg1
+    ));
+    export_functions!((
+        inline_outer,
+        "Like inline, but generates a row of NULLs for a NULL or empty input.",
+        arg1
+    ));
+    export_functions!((
+        stack,
+        "Separates the values into the given number of rows.",
+        args,
+    ));
+}
 
 pub fn functions() -> Vec<Arc<ScalarUDF>> {
-    vec![]
+    vec![
+        explode(),
+        explode_outer(),
+        posexplode(),
+        posexplode_outer(),
+        inline(),
+        inline_outer(),
+        stack(),
+    ]
+}
+
+/// Returns `Some(outer)` if `func` is a generator function, where `outer` is
+/// true for the `_outer` variants, which keep `NULL` and empty inputs
+pub fn generator_outer(func: &ScalarUDF) -> Option<bool> {
+    let inner = func.inner();
+    if let Some(explode) = inner.downcast_ref::<explode::SparkExplode>() {
+        Some(explode.is_outer())
+    } else if let Some(inline) = inner.downcast_ref::<inline::SparkInline>() {
+        Some(inline.is_outer())
+    } else if inner.is::<stack::SparkStack>() {
+        Some(false)
+    } else {
+        None
+    }
 }
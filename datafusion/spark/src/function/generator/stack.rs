@@ -0,0 +1,173 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, ListArray, StructArray, new_null_array};
+use arrow::buffer::OffsetBuffer;
+use arrow::compute::{cast, interleave};
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion_common::{Result, ScalarValue, exec_err, internal_err, plan_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility,
+};
+
+use super::explode::{generated_row_field, generated_row_fields};
+
+/// Spark-compatible `stack` generator function.
+///
+/// `stack(n, expr1, ..., exprk)` separates `expr1, ..., exprk` into `n` rows
+/// of `ceil(k / n)` columns named `col0`, `col1`, ... Missing trailing values
+/// are `NULL`. The values of a column must share the same type, apart from
+/// `NULL`s.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#stack>
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkStack {
+    signature: Signature,
+}
+
+impl Default for SparkStack {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkStack {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::variadic_any(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkStack {
+    fn name(&self) -> &str {
+        "stack"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        if args.arg_fields.len() < 2 {
+            return plan_err!(
+                "stack requires at least 2 arguments, got {}",
+                args.arg_fields.len()
+            );
+        }
+        let num_rows = match args.scalar_arguments[0] {
+            Some(value) => num_rows(value)?,
+            None => {
+                return plan_err!("The number of rows of stack must be an INT literal");
+            }
+        };
+        let values = &args.arg_fields[1..];
+        let num_columns = values.len().div_ceil(num_rows);
+
+        let fields = (0..num_columns)
+            .map(|column| {
+                let mut data_type = DataType::Null;
+                for (idx, field) in values.iter().enumerate().skip(column).step_by(num_columns) {
+                    match (&data_type, field.data_type()) {
+                        (_, DataType::Null) => {}
+                        (DataType::Null, other) => data_type = other.clone(),
+                        (expected, other) if expected == other => {}
+                        (expected, other) => {
+                            return plan_err!(
+                                "stack argument {} ({other}) must have the same type as the \
+                                other values of column col{column} ({expected})",
+                                idx + 2
+                            );
+                        }
+                    }
+                }
+                Ok(Field::new(format!("col{column}"), data_type, true))
+            })
+            .collect::<Result<Fields>>()?;
+        Ok(Arc::new(Field::new(
+            self.name(),
+            DataType::List(generated_row_field(fields)),
+            false,
+        )))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let fields = generated_row_fields(args.return_field.data_type())?;
+        let ColumnarValue::Scalar(value) = &args.args[0] else {
+            return exec_err!("The number of rows of stack must be an INT literal");
+        };
+        let num_rows = num_rows(value)?;
+        let num_columns = fields.len();
+        let values = ColumnarValue::values_to_arrays(&args.args[1..])?;
+        let num_input_rows = args.number_rows;
+
+        // The generated rows of every input row, in order
+        let indices = (0..num_input_rows)
+            .flat_map(|row| (0..num_rows).map(move |generated| (generated, row)))
+            .collect::<Vec<_>>();
+        let columns = fields
+            .iter()
+            .enumerate()
+            .map(|(column, field)| {
+                let sources = (0..num_rows)
+                    .map(
+                        |generated| match values.get(generated * num_columns + column) {
+                            Some(value) => cast(value, field.data_type()),
+                            None => Ok(new_null_array(field.data_type(), num_input_rows)),
+                        },
+                    )
+                    .collect::<Result<Vec<ArrayRef>, _>>()?;
+                let sources = sources.iter().map(|s| s.as_ref()).collect::<Vec<_>>();
+                Ok(interleave(&sources, &indices)?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let item_field = generated_row_field(fields.clone());
+        let rows = StructArray::try_new(fields, columns, None)?;
+        let offsets = OffsetBuffer::from_repeated_length(num_rows, num_input_rows);
+        Ok(ColumnarValue::Array(Arc::new(ListArray::try_new(
+            item_field,
+            offsets,
+            Arc::new(rows),
+            None,
+        )?)))
+    }
+}
+
+/// Returns the number of rows `stack` generates for each input row
+fn num_rows(value: &ScalarValue) -> Result<usize> {
+    let num_rows = match value {
+        ScalarValue::Int8(Some(v)) => i64::from(*v),
+        ScalarValue::Int16(Some(v)) => i64::from(*v),
+        ScalarValue::Int32(Some(v)) => i64::from(*v),
+        ScalarValue::Int64(Some(v)) => *v,
+        _ => return plan_err!("The number of rows of stack must be an INT literal"),
+    };
+    if num_rows <= 0 || num_rows > i64::from(i32::MAX) {
+        return plan_err!(
+            "The number of rows of stack must be a positive INT (current value = {num_rows})"
+        );
+    }
+    Ok(num_rows as usize)
+}
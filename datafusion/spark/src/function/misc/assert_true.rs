@@ -0,0 +1,121 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::DataType;
+use datafusion_common::{Result, ScalarValue, exec_err, plan_err};
+use datafusion_expr::expr::ScalarFunction;
+use datafusion_expr::simplify::{ExprSimplifyResult, SimplifyContext};
+use datafusion_expr::{
+    ColumnarValue, Expr, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility, lit,
+};
+
+use super::raise_error::{coerce_message, user_raised_error};
+
+/// Spark-compatible `assert_true` function.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#assert_true>
+///
+/// Returns null if the condition is true, and fails the query with the
+/// optional message otherwise. The default message names the condition.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkAssertTrue {
+    signature: Signature,
+}
+
+impl Default for SparkAssertTrue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkAssertTrue {
+    pub fn new() -> Self {
+        Self {
+            // Volatile so that it is not evaluated at planning time
+            signature: Signature::user_defined(Volatility::Volatile),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkAssertTrue {
+    fn name(&self) -> &str {
+        "assert_true"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let (condition, message) = match arg_types {
+            [condition] => (condition, None),
+            [condition, message] => (condition, Some(message)),
+            _ => {
+                return plan_err!(
+                    "Function 'assert_true' expects 1 or 2 arguments but received {}",
+                    arg_types.len()
+                );
+            }
+        };
+        if !matches!(condition, DataType::Boolean | DataType::Null) {
+            return plan_err!(
+                "The condition of assert_true must be a boolean, got {condition}"
+            );
+        }
+        let mut coerced = vec![DataType::Boolean];
+        if let Some(message) = message {
+            coerced.push(coerce_message(self.name(), message)?);
+        }
+        Ok(coerced)
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Null)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let conditions = args.args[0].to_array(args.number_rows)?;
+        let conditions = conditions.as_boolean();
+        let failed_row = (0..conditions.len())
+            .find(|&row| conditions.is_null(row) || !conditions.value(row));
+        match (failed_row, args.args.get(1)) {
+            (None, _) => Ok(ColumnarValue::Scalar(ScalarValue::Null)),
+            (Some(row), Some(message)) => Err(user_raised_error(message, row)?),
+            (Some(_), None) => exec_err!("Assertion is not true!"),
+        }
+    }
+
+    /// Adds the default message naming the condition, which is not known when
+    /// the function is invoked
+    fn simplify(
+        &self,
+        args: Vec<Expr>,
+        _info: &SimplifyContext,
+    ) -> Result<ExprSimplifyResult> {
+        let [condition] = args.as_slice() else {
+            return Ok(ExprSimplifyResult::Original(args));
+        };
+        let message = format!("'{}' is not true!", condition.human_display());
+        Ok(ExprSimplifyResult::Simplified(Expr::ScalarFunction(
+            ScalarFunction::new_udf(
+                super::assert_true(),
+                vec![condition.clone(), lit(message)],
+            ),
+        )))
+    }
+}
@@ -0,0 +1,89 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::{Result, ScalarValue, internal_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility,
+};
+
+/// Spark-compatible `current_user` function, also available as `user` and
+/// `session_user`.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#current_user>
+///
+/// Returns the user running the query, which, like Spark, is read from the
+/// `SPARK_USER` environment variable, or else from the operating system user
+/// variables.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkCurrentUser {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Default for SparkCurrentUser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkCurrentUser {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::nullary(Volatility::Stable),
+            aliases: vec![String::from("user"), String::from("session_user")],
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkCurrentUser {
+    fn name(&self) -> &str {
+        "current_user"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, _args: ReturnFieldArgs) -> Result<FieldRef> {
+        Ok(Arc::new(Field::new(self.name(), DataType::Utf8, false)))
+    }
+
+    fn invoke_with_args(&self, _args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        Ok(ColumnarValue::Scalar(ScalarValue::Utf8(Some(
+            current_user(),
+        ))))
+    }
+}
+
+fn current_user() -> String {
+    ["SPARK_USER", "USER", "USERNAME"]
+        .into_iter()
+        .find_map(|name| std::env::var(name).ok().filter(|user| !user.is_empty()))
+        .unwrap_or_else(|| String::from("unknown"))
+}
@@ -15,11 +15,70 @@
 // specific language governing permissions and limitations
 // under the License.
 
+pub mod assert_true;
+pub mod current_user;
+pub mod input_file_block_start;
+pub mod monotonically_increasing_id;
+pub mod raise_error;
+pub mod spark_partition_id;
+pub mod type_of;
+
 use datafusion_expr::ScalarUDF;
+use datafusion_functions::make_udf_function;
 use std::sync::Arc;
 
-pub mod expr_fn {}
+make_udf_function!(assert_true::SparkAssertTrue, assert_true);
+make_udf_function!(current_user::SparkCurrentUser, current_user);
+make_udf_function!(
+    input_file_block_start::SparkInputFileBlockStart,
+    input_file_block_start
+);
+make_udf_function!(
+    monotonically_increasing_id::SparkMonotonicallyIncreasingId,
+    monotonically_increasing_id
+);
+make_udf_function!(raise_error::SparkRaiseError, raise_error);
+make_udf_function!(spark_partition_id::SparkPartitionId, spark_partition_id);
+make_udf_function!(type_of::SparkTypeOf, r#typeof);
+
+pub mod expr_fn {
+    use datafusion_functions::export_functions;
+
+    export_functions!((
+        assert_true,
+        "Returns null if the condition is true, and raises an error with the optional message otherwise.",
+        args,
+    ));
+    export_functions!((current_user, "Returns the user running the query.",));
+    export_functions!((
+        input_file_block_start,
+        "Returns the start offset of the file block being read, or -1 if not available.",
+    ));
+    export_functions!((
+        monotonically_increasing_id,
+        "Returns unique 64-bit integers that increase within each partition.",
+    ));
+    export_functions!((raise_error, "Raises an error with the given message.", arg1));
+    export_functions!((spark_partition_id, "Returns the partition id of the rows.",));
+    export_functions!((
+        r#typeof,
+        "Returns the Spark name of the data type of the argument.",
+        arg1
+    ));
+
+    pub use datafusion_functions::string::expr_fn::uuid;
+}
 
 pub fn functions() -> Vec<Arc<ScalarUDF>> {
-    vec![]
+    vec![
+        assert_true(),
+        current_user(),
+        input_file_block_start(),
+        monotonically_increasing_id(),
+        raise_error(),
+        spark_partition_id(),
+        r#typeof(),
+        // Spark's `uuid` has the same semantics as the default one
+        datafusion_functions::string::uuid(),
+    ]
 }
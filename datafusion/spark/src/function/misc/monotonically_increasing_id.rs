@@ -0,0 +1,82 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::{Result, exec_err, internal_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility,
+};
+
+/// Spark-compatible `monotonically_increasing_id` function.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#monotonically_increasing_id>
+///
+/// Returns 64-bit integers that are unique and increase within each
+/// partition, but are not consecutive. As in Spark, the id of a row is the
+/// partition id shifted left by 33 bits plus the index of the row within its
+/// partition.
+///
+/// Scalar functions do not know the partition of the rows, so sessions built
+/// with `SessionStateBuilderSpark::with_spark_features` replace calls in
+/// projections and filters with the ids computed by `PartitionContextExec`.
+/// Evaluating the function anywhere else is an error.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkMonotonicallyIncreasingId {
+    signature: Signature,
+}
+
+impl Default for SparkMonotonicallyIncreasingId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkMonotonicallyIncreasingId {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::nullary(Volatility::Volatile),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkMonotonicallyIncreasingId {
+    fn name(&self) -> &str {
+        "monotonically_increasing_id"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, _args: ReturnFieldArgs) -> Result<FieldRef> {
+        Ok(Arc::new(Field::new(self.name(), DataType::Int64, false)))
+    }
+
+    fn invoke_with_args(&self, _args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        exec_err!(
+            "{} is only supported in projections and filters of sessions with the Spark features",
+            self.name()
+        )
+    }
+}
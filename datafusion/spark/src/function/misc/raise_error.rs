@@ -0,0 +1,101 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::datatypes::DataType;
+use datafusion_common::utils::take_function_args;
+use datafusion_common::{
+    DataFusionError, Result, ScalarValue, exec_datafusion_err, plan_err,
+};
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+
+/// Spark-compatible `raise_error` function.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#raise_error>
+///
+/// Fails the query with the given message when evaluated on any row.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkRaiseError {
+    signature: Signature,
+}
+
+impl Default for SparkRaiseError {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkRaiseError {
+    pub fn new() -> Self {
+        Self {
+            // Volatile so that it is not evaluated at planning time
+            signature: Signature::user_defined(Volatility::Volatile),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkRaiseError {
+    fn name(&self) -> &str {
+        "raise_error"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        let [message] = take_function_args(self.name(), arg_types)?;
+        Ok(vec![coerce_message(self.name(), message)?])
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Null)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let [message] = take_function_args(self.name(), &args.args)?;
+        if args.number_rows > 0 {
+            return Err(user_raised_error(message, 0)?);
+        }
+        Ok(ColumnarValue::Scalar(ScalarValue::Null))
+    }
+}
+
+/// Coerces the message argument of `raise_error` and `assert_true` to a string
+pub(super) fn coerce_message(name: &str, message: &DataType) -> Result<DataType> {
+    match message {
+        DataType::Null | DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => {
+            Ok(DataType::Utf8)
+        }
+        other => plan_err!("The message of {name} must be a string, got {other}"),
+    }
+}
+
+/// Returns the error raised with the message at `row` of `message`
+pub(super) fn user_raised_error(
+    message: &ColumnarValue,
+    row: usize,
+) -> Result<DataFusionError> {
+    let message = match message {
+        ColumnarValue::Scalar(scalar) => scalar.clone(),
+        ColumnarValue::Array(array) => ScalarValue::try_from_array(array, row)?,
+    };
+    // Spark raises null messages as "null"
+    let message = message.try_as_str().flatten().unwrap_or("null");
+    Ok(exec_datafusion_err!("{message}"))
+}
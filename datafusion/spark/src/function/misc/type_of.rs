@@ -0,0 +1,181 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, FieldRef, IntervalUnit, TimeUnit};
+use datafusion_common::utils::take_function_args;
+use datafusion_common::{Result, ScalarValue, internal_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility,
+};
+
+/// Spark-compatible `typeof` function.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#typeof>
+///
+/// Returns the Spark name of the type of the argument, such as `int` or
+/// `array<string>`. Types without a Spark equivalent are named as by
+/// `arrow_typeof`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkTypeOf {
+    signature: Signature,
+}
+
+impl Default for SparkTypeOf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkTypeOf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkTypeOf {
+    fn name(&self) -> &str {
+        "typeof"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, _args: ReturnFieldArgs) -> Result<FieldRef> {
+        Ok(Arc::new(Field::new(self.name(), DataType::Utf8, false)))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let [field] = take_function_args(self.name(), &args.arg_fields)?;
+        Ok(ColumnarValue::Scalar(ScalarValue::Utf8(Some(
+            spark_type_name(field.data_type()),
+        ))))
+    }
+}
+
+/// Returns the name Spark gives to `data_type`
+fn spark_type_name(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Null => "void".to_string(),
+        DataType::Boolean => "boolean".to_string(),
+        DataType::Int8 => "tinyint".to_string(),
+        DataType::Int16 | DataType::UInt8 => "smallint".to_string(),
+        DataType::Int32 | DataType::UInt16 => "int".to_string(),
+        DataType::Int64 | DataType::UInt32 => "bigint".to_string(),
+        // Spark reads unsigned 64-bit integers as decimals
+        DataType::UInt64 => "decimal(20,0)".to_string(),
+        DataType::Float16 | DataType::Float32 => "float".to_string(),
+        DataType::Float64 => "double".to_string(),
+        DataType::Decimal32(precision, scale)
+        | DataType::Decimal64(precision, scale)
+        | DataType::Decimal128(precision, scale)
+        | DataType::Decimal256(precision, scale) => {
+            format!("decimal({precision},{scale})")
+        }
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => "string".to_string(),
+        DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView
+        | DataType::FixedSizeBinary(_) => "binary".to_string(),
+        DataType::Date32 | DataType::Date64 => "date".to_string(),
+        DataType::Timestamp(_, Some(_)) => "timestamp".to_string(),
+        DataType::Timestamp(_, None) => "timestamp_ntz".to_string(),
+        DataType::Time64(TimeUnit::Microsecond) => "time(6)".to_string(),
+        DataType::Interval(IntervalUnit::YearMonth) => {
+            "interval year to month".to_string()
+        }
+        DataType::Interval(IntervalUnit::DayTime) | DataType::Duration(_) => {
+            "interval day to second".to_string()
+        }
+        DataType::Interval(IntervalUnit::MonthDayNano) => "interval".to_string(),
+        DataType::List(field)
+        | DataType::LargeList(field)
+        | DataType::ListView(field)
+        | DataType::LargeListView(field)
+        | DataType::FixedSizeList(field, _) => {
+            format!("array<{}>", spark_type_name(field.data_type()))
+        }
+        DataType::Map(field, _) => match field.data_type() {
+            DataType::Struct(fields) if fields.len() == 2 => format!(
+                "map<{},{}>",
+                spark_type_name(fields[0].data_type()),
+                spark_type_name(fields[1].data_type())
+            ),
+            _ => data_type.to_string(),
+        },
+        DataType::Struct(fields) => {
+            let fields = fields
+                .iter()
+                .map(|field| {
+                    format!("{}:{}", field.name(), spark_type_name(field.data_type()))
+                })
+                .collect::<Vec<_>>();
+            format!("struct<{}>", fields.join(","))
+        }
+        DataType::Dictionary(_, value_type) => spark_type_name(value_type),
+        DataType::RunEndEncoded(_, values) => spark_type_name(values.data_type()),
+        _ => data_type.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::datatypes::Fields;
+
+    #[test]
+    fn test_spark_type_name() {
+        let cases = [
+            (DataType::Int32, "int"),
+            (DataType::Utf8View, "string"),
+            (DataType::Decimal128(10, 2), "decimal(10,2)"),
+            (
+                DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                "timestamp",
+            ),
+            (
+                DataType::Timestamp(TimeUnit::Microsecond, None),
+                "timestamp_ntz",
+            ),
+            (DataType::new_list(DataType::Int64, true), "array<bigint>"),
+            (
+                DataType::Struct(Fields::from(vec![
+                    Field::new("a", DataType::Int32, true),
+                    Field::new("b", DataType::new_list(DataType::Utf8, true), true),
+                ])),
+                "struct<a:int,b:array<string>>",
+            ),
+            (
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                "string",
+            ),
+        ];
+        for (data_type, expected) in cases {
+            assert_eq!(spark_type_name(&data_type), expected, "{data_type}");
+        }
+    }
+}
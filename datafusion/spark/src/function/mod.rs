@@ -24,6 +24,7 @@ pub mod conditional;
 pub mod conversion;
 pub mod csv;
 pub mod datetime;
+pub mod ddl_utils;
 pub mod error_utils;
 pub mod functions_nested_utils;
 pub mod generator;
@@ -34,6 +35,7 @@ pub mod map;
 pub mod math;
 pub mod misc;
 mod null_utils;
+pub mod options_utils;
 pub mod predicate;
 pub mod string;
 pub mod r#struct;
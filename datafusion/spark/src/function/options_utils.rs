@@ -0,0 +1,98 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Parsing of the `options` map accepted by Spark's data source functions,
+//! such as `from_csv(str, schema, map('sep', ';'))`
+
+use std::collections::HashMap;
+
+use arrow::array::{Array, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use datafusion_common::{Result, ScalarValue, plan_err};
+
+/// Returns the options of a constant `MAP<STRING, STRING>` argument, with
+/// lowercase keys since Spark matches option names case-insensitively
+pub fn parse_options(
+    function_name: &str,
+    options: Option<&ScalarValue>,
+) -> Result<HashMap<String, String>> {
+    let map = match options {
+        None => return Ok(HashMap::new()),
+        Some(ScalarValue::Map(map)) => map,
+        Some(value) if value.is_null() => return Ok(HashMap::new()),
+        Some(other) => {
+            return plan_err!(
+                "The options of {function_name} must be a MAP literal, got {}",
+                other.data_type()
+            );
+        }
+    };
+    if map.is_null(0) {
+        return Ok(HashMap::new());
+    }
+    let keys = cast(map.keys(), &DataType::Utf8)?;
+    let values = cast(map.values(), &DataType::Utf8)?;
+    let (keys, values) = (keys.as_string::<i32>(), values.as_string::<i32>());
+    Ok((0..keys.len())
+        .filter(|&idx| values.is_valid(idx))
+        .map(|idx| {
+            (
+                keys.value(idx).to_ascii_lowercase(),
+                values.value(idx).to_string(),
+            )
+        })
+        .collect())
+}
+
+/// Returns the single character value of option `name`, or `default`
+pub fn char_option(
+    function_name: &str,
+    options: &HashMap<String, String>,
+    name: &str,
+    default: char,
+) -> Result<char> {
+    match options.get(name) {
+        None => Ok(default),
+        Some(value) => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(c),
+                _ => plan_err!(
+                    "The option '{name}' of {function_name} must be a single character, got '{value}'"
+                ),
+            }
+        }
+    }
+}
+
+/// Returns the boolean value of option `name`, or `default`
+pub fn bool_option(
+    function_name: &str,
+    options: &HashMap<String, String>,
+    name: &str,
+    default: bool,
+) -> Result<bool> {
+    match options.get(name).map(|value| value.to_ascii_lowercase()) {
+        None => Ok(default),
+        Some(value) if value == "true" => Ok(true),
+        Some(value) if value == "false" => Ok(false),
+        Some(value) => plan_err!(
+            "The option '{name}' of {function_name} must be 'true' or 'false', got '{value}'"
+        ),
+    }
+}
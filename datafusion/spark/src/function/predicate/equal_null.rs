@@ -0,0 +1,81 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::datatypes::DataType;
+use datafusion_common::utils::take_function_args;
+use datafusion_common::{Result, internal_err};
+use datafusion_expr::simplify::{ExprSimplifyResult, SimplifyContext};
+use datafusion_expr::{
+    ColumnarValue, Expr, Operator, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility, binary_expr,
+};
+
+/// Spark-compatible `equal_null` function.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#equal_null>
+///
+/// Returns the same result as the `<=>` operator: true if both arguments are
+/// null or equal, false otherwise. It is simplified to `IS NOT DISTINCT FROM`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkEqualNull {
+    signature: Signature,
+}
+
+impl Default for SparkEqualNull {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkEqualNull {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::comparable(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkEqualNull {
+    fn name(&self) -> &str {
+        "equal_null"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke_with_args(&self, _args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        internal_err!("equal_null should have been simplified to IS NOT DISTINCT FROM")
+    }
+
+    fn simplify(
+        &self,
+        args: Vec<Expr>,
+        _info: &SimplifyContext,
+    ) -> Result<ExprSimplifyResult> {
+        let [left, right] = take_function_args(self.name(), args)?;
+        Ok(ExprSimplifyResult::Simplified(binary_expr(
+            left,
+            Operator::IsNotDistinctFrom,
+            right,
+        )))
+    }
+}
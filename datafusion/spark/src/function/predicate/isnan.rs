@@ -0,0 +1,136 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrowPrimitiveType, AsArray, BooleanArray, PrimitiveArray};
+use arrow::buffer::BooleanBuffer;
+use arrow::datatypes::{DataType, Field, FieldRef, Float32Type, Float64Type};
+use datafusion_common::types::{NativeType, logical_float64};
+use datafusion_common::utils::take_function_args;
+use datafusion_common::{Result, ScalarValue, internal_err};
+use datafusion_expr::{
+    Coercion, ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl,
+    Signature, TypeSignature, TypeSignatureClass, Volatility,
+};
+use num_traits::Float;
+
+/// Spark-compatible `isnan` function.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#isnan>
+///
+/// Returns true if the argument is NaN, and false otherwise. Unlike the
+/// default `isnan` function, it returns false rather than null for null
+/// arguments.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkIsNaN {
+    signature: Signature,
+}
+
+impl Default for SparkIsNaN {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkIsNaN {
+    pub fn new() -> Self {
+        Self {
+            // Floats are checked as they are, other numerics as doubles
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Exact(vec![DataType::Float32]),
+                    TypeSignature::Coercible(vec![Coercion::new_implicit(
+                        TypeSignatureClass::Native(logical_float64()),
+                        vec![TypeSignatureClass::Numeric],
+                        NativeType::Float64,
+                    )]),
+                ],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkIsNaN {
+    fn name(&self) -> &str {
+        "isnan"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, _args: ReturnFieldArgs) -> Result<FieldRef> {
+        Ok(Arc::new(Field::new(self.name(), DataType::Boolean, false)))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let [value] = take_function_args(self.name(), args.args)?;
+        match value {
+            ColumnarValue::Scalar(ScalarValue::Float64(value)) => {
+                Ok(ColumnarValue::Scalar(ScalarValue::Boolean(Some(
+                    value.is_some_and(f64::is_nan),
+                ))))
+            }
+            ColumnarValue::Scalar(ScalarValue::Float32(value)) => {
+                Ok(ColumnarValue::Scalar(ScalarValue::Boolean(Some(
+                    value.is_some_and(f32::is_nan),
+                ))))
+            }
+            ColumnarValue::Array(array) => match array.data_type() {
+                DataType::Float64 => Ok(ColumnarValue::Array(Arc::new(is_nan(
+                    array.as_primitive::<Float64Type>(),
+                )))),
+                DataType::Float32 => Ok(ColumnarValue::Array(Arc::new(is_nan(
+                    array.as_primitive::<Float32Type>(),
+                )))),
+                other => internal_err!("Unsupported data type {other} for isnan"),
+            },
+            other => internal_err!("Unsupported argument {other:?} for isnan"),
+        }
+    }
+}
+
+/// Returns whether each value is NaN, which is false for nulls
+fn is_nan<T>(array: &PrimitiveArray<T>) -> BooleanArray
+where
+    T: ArrowPrimitiveType,
+    T::Native: Float,
+{
+    let values = BooleanBuffer::collect_bool(array.len(), |i| {
+        array.is_valid(i) && array.value(i).is_nan()
+    });
+    BooleanArray::new(values, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::Float64Array;
+
+    #[test]
+    fn test_is_nan_of_nulls() {
+        let array = Float64Array::from(vec![Some(f64::NAN), None, Some(1.0)]);
+        assert_eq!(is_nan(&array), BooleanArray::from(vec![true, false, false]));
+    }
+}
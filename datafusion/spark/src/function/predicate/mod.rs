@@ -15,11 +15,31 @@
 // specific language governing permissions and limitations
 // under the License.
 
+pub mod equal_null;
+pub mod isnan;
+
 use datafusion_expr::ScalarUDF;
+use datafusion_functions::make_udf_function;
 use std::sync::Arc;
 
-pub mod expr_fn {}
+make_udf_function!(equal_null::SparkEqualNull, equal_null);
+make_udf_function!(isnan::SparkIsNaN, isnan);
+
+pub mod expr_fn {
+    use datafusion_functions::export_functions;
+
+    export_functions!((
+        equal_null,
+        "Returns true if both arguments are null or equal, like the <=> operator.",
+        arg1 arg2
+    ));
+    export_functions!((
+        isnan,
+        "Returns true if expr is NaN, and false otherwise, including for null.",
+        arg1
+    ));
+}
 
 pub fn functions() -> Vec<Arc<ScalarUDF>> {
-    vec![]
+    vec![equal_null(), isnan()]
 }
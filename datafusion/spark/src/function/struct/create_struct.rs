@@ -0,0 +1,119 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, StructArray};
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion_common::{Result, internal_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    TypeSignature, Volatility,
+};
+
+/// Spark-compatible `struct` function.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#struct>
+///
+/// Returns a struct of the arguments with fields named `col1`, `col2`, etc.
+/// Unlike the default `struct` function, the struct itself is never null and
+/// its fields are nullable only if the arguments are. The
+/// [`SparkFunctionPlanner`] names the fields of columns after the columns, as
+/// Spark does.
+///
+/// [`SparkFunctionPlanner`]: crate::planner::SparkFunctionPlanner
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkStruct {
+    signature: Signature,
+}
+
+impl Default for SparkStruct {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkStruct {
+    pub fn new() -> Self {
+        Self {
+            // Spark allows `struct()`, which returns an empty struct
+            signature: Signature::one_of(
+                vec![TypeSignature::Nullary, TypeSignature::VariadicAny],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkStruct {
+    fn name(&self) -> &str {
+        "struct"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        let fields = args
+            .arg_fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                Field::new(
+                    format!("col{}", i + 1),
+                    field.data_type().clone(),
+                    field.is_nullable(),
+                )
+            })
+            .collect::<Fields>();
+        Ok(Arc::new(Field::new(
+            self.name(),
+            DataType::Struct(fields),
+            false,
+        )))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let DataType::Struct(fields) = args.return_type() else {
+            return internal_err!("incorrect struct return type");
+        };
+        let arrays = ColumnarValue::values_to_arrays(&args.args)?;
+        Ok(ColumnarValue::Array(struct_array(
+            fields.clone(),
+            arrays,
+            args.number_rows,
+        )?))
+    }
+}
+
+/// Returns a non-null [`StructArray`] of `arrays`, which has `num_rows` rows
+/// if there are no fields
+pub(super) fn struct_array(
+    fields: Fields,
+    arrays: Vec<ArrayRef>,
+    num_rows: usize,
+) -> Result<ArrayRef> {
+    if fields.is_empty() {
+        return Ok(Arc::new(StructArray::new_empty_fields(num_rows, None)));
+    }
+    Ok(Arc::new(StructArray::try_new(fields, arrays, None)?))
+}
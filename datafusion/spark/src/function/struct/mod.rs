@@ -15,11 +15,31 @@
 // specific language governing permissions and limitations
 // under the License.
 
+pub mod create_struct;
+pub mod named_struct;
+
 use datafusion_expr::ScalarUDF;
+use datafusion_functions::make_udf_function;
 use std::sync::Arc;
 
-pub mod expr_fn {}
+make_udf_function!(create_struct::SparkStruct, r#struct);
+make_udf_function!(named_struct::SparkNamedStruct, named_struct);
+
+pub mod expr_fn {
+    use datafusion_functions::export_functions;
+
+    export_functions!((
+        r#struct,
+        "Returns a struct with the given values as fields col1, col2, etc.",
+        args,
+    ));
+    export_functions!((
+        named_struct,
+        "Returns a struct with the given names and values.",
+        args,
+    ));
+}
 
 pub fn functions() -> Vec<Arc<ScalarUDF>> {
-    vec![]
+    vec![r#struct(), named_struct()]
 }
@@ -0,0 +1,126 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion_common::{Result, exec_err, internal_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    TypeSignature, Volatility,
+};
+
+use super::create_struct::struct_array;
+
+/// Spark-compatible `named_struct` function.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#named_struct>
+///
+/// Returns a struct of the values at the even positions, named by the
+/// constant strings before them. Unlike the default `named_struct` function,
+/// the struct itself is never null and its fields are nullable only if the
+/// values are.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkNamedStruct {
+    signature: Signature,
+}
+
+impl Default for SparkNamedStruct {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkNamedStruct {
+    pub fn new() -> Self {
+        Self {
+            // Spark allows `named_struct()`, which returns an empty struct
+            signature: Signature::one_of(
+                vec![TypeSignature::Nullary, TypeSignature::VariadicAny],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkNamedStruct {
+    fn name(&self) -> &str {
+        "named_struct"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        if !args.arg_fields.len().is_multiple_of(2) {
+            return exec_err!(
+                "named_struct requires an even number of arguments, got {} instead",
+                args.arg_fields.len()
+            );
+        }
+        let fields = args
+            .scalar_arguments
+            .iter()
+            .step_by(2)
+            .zip(args.arg_fields.iter().skip(1).step_by(2))
+            .enumerate()
+            .map(|(i, (name, value))| {
+                let Some(name) = name.and_then(|name| name.try_as_str().flatten())
+                else {
+                    return exec_err!(
+                        "named_struct requires the name at position {} to be a constant non-null string",
+                        i * 2
+                    );
+                };
+                Ok(Field::new(
+                    name,
+                    value.data_type().clone(),
+                    value.is_nullable(),
+                ))
+            })
+            .collect::<Result<Fields>>()?;
+        Ok(Arc::new(Field::new(
+            self.name(),
+            DataType::Struct(fields),
+            false,
+        )))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let DataType::Struct(fields) = args.return_type() else {
+            return internal_err!("incorrect named_struct return type");
+        };
+        let values = args
+            .args
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .cloned()
+            .collect::<Vec<_>>();
+        let arrays = ColumnarValue::values_to_arrays(&values)?;
+        Ok(ColumnarValue::Array(struct_array(
+            fields.clone(),
+            arrays,
+            args.number_rows,
+        )?))
+    }
+}
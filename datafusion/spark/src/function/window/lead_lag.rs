@@ -0,0 +1,375 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, new_null_array};
+use arrow::compute::{cast, interleave};
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::{Result, ScalarValue, exec_err};
+use datafusion_expr::function::{PartitionEvaluatorArgs, WindowUDFFieldArgs};
+use datafusion_expr::{
+    LimitEffect, PartitionEvaluator, ReversedUDWF, Signature, TypeSignature, Volatility,
+    WindowUDF, WindowUDFImpl,
+};
+use datafusion_physical_expr::PhysicalExpr;
+
+use crate::function::window::utils::get_int_literal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum WindowShiftKind {
+    Lead,
+    Lag,
+}
+
+/// Spark-compatible `lead` and `lag` window functions.
+///
+/// `lead(input[, offset[, default]])` returns `input` at the `offset`-th
+/// row after the current row, and `lag` at the `offset`-th row before it,
+/// or the `offset`-th non-null value with `IGNORE NULLS`. The offset
+/// defaults to 1 and must be an `INT` literal, which may be negative or zero.
+/// Following Spark's typing rules:
+/// - the result has the type of `input`, or of `default` when `input` is
+///   an untyped `NULL`
+/// - `default` is cast to the result type, values that cannot be cast
+///   becoming `NULL`
+/// - `default` may be any expression, evaluated at the current row, and is
+///   `NULL` when omitted
+///
+/// Shifts reading the current or previous rows, such as `lag` with a positive
+/// offset, are evaluated one row at a time, keeping only the last `offset`
+/// rows (or the rows since the last `offset` non-null values with `IGNORE
+/// NULLS`) of each partition in memory.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#lead>
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#lag>
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkWindowShift {
+    signature: Signature,
+    kind: WindowShiftKind,
+}
+
+impl SparkWindowShift {
+    fn new(kind: WindowShiftKind) -> Self {
+        Self {
+            signature: Signature::one_of(
+                vec![
+                    TypeSignature::Any(1),
+                    TypeSignature::Any(2),
+                    TypeSignature::Any(3),
+                ],
+                Volatility::Immutable,
+            ),
+            kind,
+        }
+    }
+
+    pub fn lead() -> Self {
+        Self::new(WindowShiftKind::Lead)
+    }
+
+    pub fn lag() -> Self {
+        Self::new(WindowShiftKind::Lag)
+    }
+}
+
+/// Returns the result type from the fields of the input, offset and default
+fn return_type(input_fields: &[FieldRef]) -> DataType {
+    let input_type = input_fields[0].data_type();
+    match input_fields.get(2) {
+        Some(default) if input_type.is_null() => default.data_type().clone(),
+        _ => input_type.clone(),
+    }
+}
+
+impl WindowUDFImpl for SparkWindowShift {
+    fn name(&self) -> &str {
+        match self.kind {
+            WindowShiftKind::Lead => "lead",
+            WindowShiftKind::Lag => "lag",
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn partition_evaluator(
+        &self,
+        partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        let offset = match partition_evaluator_args.input_exprs().get(1) {
+            Some(expr) => match get_int_literal(expr) {
+                Some(offset) => offset as i64,
+                None => {
+                    return exec_err!(
+                        "The `offset` of {} must be an INT literal",
+                        self.name()
+                    );
+                }
+            },
+            None => 1,
+        };
+        let offset = match self.kind {
+            WindowShiftKind::Lead => offset,
+            WindowShiftKind::Lag => -offset,
+        };
+        Ok(Box::new(WindowShiftEvaluator {
+            offset,
+            return_type: return_type(partition_evaluator_args.input_fields()),
+            ignore_nulls: partition_evaluator_args.ignore_nulls(),
+            rows_seen: 0,
+            valid_rows: VecDeque::new(),
+        }))
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<FieldRef> {
+        let input_fields = field_args.input_fields();
+        let return_type = return_type(input_fields);
+        // The default replaces the input near the partition boundaries
+        let nullable = input_fields[0].is_nullable()
+            || input_fields.get(2).is_none_or(|default| {
+                default.is_nullable() || default.data_type() != &return_type
+            });
+        Ok(Field::new(field_args.name(), return_type, nullable).into())
+    }
+
+    fn limit_effect(&self, _args: &[Arc<dyn PhysicalExpr>]) -> LimitEffect {
+        match self.kind {
+            WindowShiftKind::Lag => LimitEffect::None,
+            WindowShiftKind::Lead => LimitEffect::Unknown,
+        }
+    }
+
+    fn reverse_expr(&self) -> ReversedUDWF {
+        let reversed = match self.kind {
+            WindowShiftKind::Lead => Self::lag(),
+            WindowShiftKind::Lag => Self::lead(),
+        };
+        ReversedUDWF::Reversed(Arc::new(WindowUDF::new_from_impl(reversed)))
+    }
+}
+
+#[derive(Debug)]
+struct WindowShiftEvaluator {
+    /// Number of rows after the current row to read the input from, negative
+    /// for rows before it
+    offset: i64,
+    return_type: DataType,
+    ignore_nulls: bool,
+    /// Number of rows of the partition evaluated by [`Self::evaluate`]
+    rows_seen: usize,
+    /// Positions in the partition of the last `-offset` non-null input
+    /// values before the current row, with `IGNORE NULLS`
+    valid_rows: VecDeque<usize>,
+}
+
+impl WindowShiftEvaluator {
+    /// Returns the row the input is read from for each row of the partition,
+    /// or `None` for rows evaluating to the default
+    fn source_rows(&self, input: &ArrayRef) -> Vec<Option<usize>> {
+        let num_rows = input.len();
+        if self.offset == 0 {
+            return (0..num_rows).map(Some).collect();
+        }
+        if !self.ignore_nulls || input.null_count() == 0 {
+            return (0..num_rows)
+                .map(|row| {
+                    let source = row as i64 + self.offset;
+                    (0..num_rows as i64)
+                        .contains(&source)
+                        .then_some(source as usize)
+                })
+                .collect();
+        }
+        let valid_rows = (0..num_rows)
+            .filter(|row| input.is_valid(*row))
+            .collect::<Vec<_>>();
+        let distance = self.offset.unsigned_abs() as usize;
+        (0..num_rows)
+            .map(|row| {
+                if self.offset > 0 {
+                    // valid rows after the current row start at `position`
+                    let position = valid_rows.partition_point(|valid| *valid <= row);
+                    valid_rows.get(position + distance - 1).copied()
+                } else {
+                    // valid rows before the current row end before `position`
+                    let position = valid_rows.partition_point(|valid| *valid < row);
+                    position
+                        .checked_sub(distance)
+                        .map(|position| valid_rows[position])
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns the value of `array` at `row` cast to `data_type`
+fn scalar_at(array: &ArrayRef, row: usize, data_type: &DataType) -> Result<ScalarValue> {
+    ScalarValue::try_from_array(&cast(&array.slice(row, 1), data_type)?, 0)
+}
+
+impl PartitionEvaluator for WindowShiftEvaluator {
+    fn get_range(&self, idx: usize, _n_rows: usize) -> Result<Range<usize>> {
+        // Only called when reading the current or previous rows, see
+        // `supports_bounded_execution`
+        let distance = self.offset.unsigned_abs() as usize;
+        let start = if self.ignore_nulls && distance > 0 {
+            match self.valid_rows.front() {
+                Some(first) => idx - (self.rows_seen - first),
+                None => idx,
+            }
+        } else {
+            idx.saturating_sub(distance)
+        };
+        Ok(start..idx + 1)
+    }
+
+    fn is_causal(&self) -> bool {
+        self.offset <= 0
+    }
+
+    fn evaluate(
+        &mut self,
+        values: &[ArrayRef],
+        range: &Range<usize>,
+    ) -> Result<ScalarValue> {
+        let row = range.end - 1;
+        let distance = self.offset.unsigned_abs() as usize;
+        let source = if self.ignore_nulls && distance > 0 {
+            let source = (self.valid_rows.len() == distance)
+                .then(|| row - (self.rows_seen - self.valid_rows[0]));
+            if !scalar_at(&values[0], row, &self.return_type)?.is_null() {
+                self.valid_rows.push_back(self.rows_seen);
+                if self.valid_rows.len() > distance {
+                    self.valid_rows.pop_front();
+                }
+            }
+            source
+        } else {
+            row.checked_sub(distance)
+        };
+        self.rows_seen += 1;
+        match (source, values.get(2)) {
+            (Some(source), _) => scalar_at(&values[0], source, &self.return_type),
+            (None, Some(default)) => scalar_at(default, row, &self.return_type),
+            (None, None) => ScalarValue::try_from(&self.return_type),
+        }
+    }
+
+    fn supports_bounded_execution(&self) -> bool {
+        self.offset <= 0
+    }
+
+    fn evaluate_all(&mut self, values: &[ArrayRef], num_rows: usize) -> Result<ArrayRef> {
+        let input = cast(&values[0], &self.return_type)?;
+        let default = match values.get(2) {
+            Some(default) => cast(default, &self.return_type)?,
+            None => new_null_array(&self.return_type, num_rows),
+        };
+        let indices = self
+            .source_rows(&input)
+            .into_iter()
+            .enumerate()
+            .map(|(row, source)| match source {
+                Some(source) => (0, source),
+                None => (1, row),
+            })
+            .collect::<Vec<_>>();
+        Ok(interleave(&[input.as_ref(), default.as_ref()], &indices)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int32Array;
+
+    fn evaluator(offset: i64, ignore_nulls: bool) -> WindowShiftEvaluator {
+        WindowShiftEvaluator {
+            offset,
+            return_type: DataType::Int32,
+            ignore_nulls,
+            rows_seen: 0,
+            valid_rows: VecDeque::new(),
+        }
+    }
+
+    /// Evaluates the rows one at a time like `BoundedWindowAggExec`, which
+    /// drops the rows before the range of the last evaluated row
+    fn evaluate_bounded(
+        evaluator: &mut WindowShiftEvaluator,
+        values: &[ArrayRef],
+    ) -> Result<Vec<ScalarValue>> {
+        let num_rows = values[0].len();
+        let mut pruned = 0;
+        let mut results = vec![];
+        for row in 0..num_rows {
+            let range = evaluator.get_range(row - pruned, num_rows - pruned)?;
+            let buffered = values
+                .iter()
+                .map(|values| values.slice(pruned, num_rows - pruned))
+                .collect::<Vec<_>>();
+            results.push(evaluator.evaluate(&buffered, &range)?);
+            pruned += range.start;
+        }
+        Ok(results)
+    }
+
+    #[test]
+    fn bounded_lag_matches_evaluate_all() -> Result<()> {
+        let input: ArrayRef = Arc::new(Int32Array::from(vec![
+            Some(1),
+            None,
+            None,
+            Some(4),
+            Some(5),
+            None,
+            Some(7),
+        ]));
+        let default: ArrayRef = Arc::new(Int32Array::from((0..7).collect::<Vec<_>>()));
+        for ignore_nulls in [false, true] {
+            for offset in [0, -1, -2, -3] {
+                for values in [
+                    vec![Arc::clone(&input)],
+                    vec![Arc::clone(&input), Arc::clone(&input), Arc::clone(&default)],
+                ] {
+                    let mut bounded = evaluator(offset, ignore_nulls);
+                    assert!(bounded.supports_bounded_execution());
+                    let actual = evaluate_bounded(&mut bounded, &values)?;
+                    let expected = evaluator(offset, ignore_nulls)
+                        .evaluate_all(&values, input.len())?;
+                    let expected = (0..expected.len())
+                        .map(|row| ScalarValue::try_from_array(&expected, row))
+                        .collect::<Result<Vec<_>>>()?;
+                    assert_eq!(
+                        actual, expected,
+                        "offset={offset}, ignore_nulls={ignore_nulls}"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn lead_is_not_bounded() {
+        assert!(!evaluator(1, false).supports_bounded_execution());
+        assert!(!evaluator(1, false).is_causal());
+    }
+}
@@ -18,8 +18,110 @@
 use datafusion_expr::WindowUDF;
 use std::sync::Arc;
 
-pub mod expr_fn {}
+pub mod lead_lag;
+pub mod nth_value;
+pub mod ntile;
+pub mod rank;
+mod utils;
+
+pub mod expr_fn {
+    use datafusion_expr::Expr;
+
+    /// Returns the rank of each row within its window partition, with gaps
+    pub fn rank() -> Expr {
+        super::rank().call(vec![])
+    }
+
+    /// Returns the rank of each row within its window partition, without gaps
+    pub fn dense_rank() -> Expr {
+        super::dense_rank().call(vec![])
+    }
+
+    /// Returns the relative rank of each row within its window partition, from
+    /// 0 to 1
+    pub fn percent_rank() -> Expr {
+        super::percent_rank().call(vec![])
+    }
+
+    /// Divides the rows of each window partition into `buckets` groups
+    /// numbered from 1
+    pub fn ntile(buckets: Expr) -> Expr {
+        super::ntile().call(vec![buckets])
+    }
+
+    /// Returns the value of `arg` at the `offset`-th row of the window frame
+    pub fn nth_value(arg: Expr, offset: Expr) -> Expr {
+        super::nth_value().call(vec![arg, offset])
+    }
+
+    /// Returns the value of `arg` at `offset` rows after the current row, or
+    /// `default` if there is no such row
+    pub fn lead(arg: Expr, offset: Expr, default: Expr) -> Expr {
+        super::lead().call(vec![arg, offset, default])
+    }
+
+    /// Returns the value of `arg` at `offset` rows before the current row, or
+    /// `default` if there is no such row
+    pub fn lag(arg: Expr, offset: Expr, default: Expr) -> Expr {
+        super::lag().call(vec![arg, offset, default])
+    }
+}
+
+pub fn rank() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(rank::SparkRank::basic()))
+}
+pub fn dense_rank() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(rank::SparkRank::dense_rank()))
+}
+pub fn percent_rank() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(rank::SparkRank::percent_rank()))
+}
+pub fn ntile() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(ntile::SparkNtile::new()))
+}
+pub fn nth_value() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(nth_value::SparkNthValue::new()))
+}
+pub fn lead() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(lead_lag::SparkWindowShift::lead()))
+}
+pub fn lag() -> Arc<WindowUDF> {
+    Arc::new(WindowUDF::new_from_impl(lead_lag::SparkWindowShift::lag()))
+}
 
 pub fn functions() -> Vec<Arc<WindowUDF>> {
-    vec![]
+    vec![
+        rank(),
+        dense_rank(),
+        percent_rank(),
+        ntile(),
+        nth_value(),
+        lead(),
+        lag(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn functions_are_registered() {
+        let names = functions()
+            .iter()
+            .map(|udwf| udwf.name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "rank",
+                "dense_rank",
+                "percent_rank",
+                "ntile",
+                "nth_value",
+                "lead",
+                "lag"
+            ]
+        );
+    }
 }
@@ -0,0 +1,101 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use std::sync::Arc;
+
+use arrow::datatypes::FieldRef;
+use datafusion_common::{Result, exec_err};
+use datafusion_expr::function::{PartitionEvaluatorArgs, WindowUDFFieldArgs};
+use datafusion_expr::{
+    LimitEffect, PartitionEvaluator, ReversedUDWF, Signature, Volatility, WindowUDFImpl,
+};
+use datafusion_functions_window::nth_value::NthValue;
+use datafusion_physical_expr::PhysicalExpr;
+
+use crate::function::window::utils::get_int_literal;
+
+/// Spark-compatible `nth_value` window function.
+///
+/// Returns the value of the `offset`-th row of the window frame, or of the
+/// `offset`-th non-null value with `IGNORE NULLS`. Unlike DataFusion's
+/// `nth_value`, the offset must be an `INT` literal in
+/// `(0, 2147483647]`: negative offsets do not count from the end of the frame.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#nth_value>
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkNthValue {
+    signature: Signature,
+    inner: NthValue,
+}
+
+impl Default for SparkNthValue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkNthValue {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+            inner: NthValue::nth(),
+        }
+    }
+}
+
+impl WindowUDFImpl for SparkNthValue {
+    fn name(&self) -> &str {
+        "nth_value"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn partition_evaluator(
+        &self,
+        partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        let Some(offset) = partition_evaluator_args
+            .input_exprs()
+            .get(1)
+            .and_then(get_int_literal)
+        else {
+            return exec_err!("The `offset` of nth_value must be an INT literal");
+        };
+        if offset <= 0 {
+            return exec_err!(
+                "The `offset` of nth_value must be between (0, {}] (current value = {offset})",
+                i32::MAX
+            );
+        }
+        // The frame of a reversed window is evaluated from its end, which the
+        // DataFusion evaluator handles by negating the offset
+        self.inner.partition_evaluator(partition_evaluator_args)
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<FieldRef> {
+        self.inner.field(field_args)
+    }
+
+    fn reverse_expr(&self) -> ReversedUDWF {
+        ReversedUDWF::Reversed(super::nth_value())
+    }
+
+    fn limit_effect(&self, args: &[Arc<dyn PhysicalExpr>]) -> LimitEffect {
+        self.inner.limit_effect(args)
+    }
+}
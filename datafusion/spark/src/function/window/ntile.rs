@@ -0,0 +1,92 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::{Result, exec_err};
+use datafusion_expr::function::{PartitionEvaluatorArgs, WindowUDFFieldArgs};
+use datafusion_expr::{LimitEffect, PartitionEvaluator, Signature, WindowUDFImpl};
+use datafusion_functions_window::ntile::Ntile;
+use datafusion_physical_expr::PhysicalExpr;
+
+use crate::function::window::utils::{Int32Evaluator, get_int_literal};
+
+/// Spark-compatible `ntile` window function.
+///
+/// Rows are split into buckets like DataFusion's `ntile`, but the result is
+/// an `INT`, and the number of buckets must be an `INT` literal in
+/// `(0, 2147483647]`.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#ntile>
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkNtile {
+    inner: Ntile,
+}
+
+impl Default for SparkNtile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkNtile {
+    pub fn new() -> Self {
+        Self {
+            inner: Ntile::new(),
+        }
+    }
+}
+
+impl WindowUDFImpl for SparkNtile {
+    fn name(&self) -> &str {
+        "ntile"
+    }
+
+    fn signature(&self) -> &Signature {
+        self.inner.signature()
+    }
+
+    fn partition_evaluator(
+        &self,
+        partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        let Some(buckets) = partition_evaluator_args
+            .input_exprs()
+            .first()
+            .and_then(get_int_literal)
+        else {
+            return exec_err!("The `buckets` of ntile must be an INT literal");
+        };
+        if buckets <= 0 {
+            return exec_err!(
+                "The `buckets` of ntile must be between (0, {}] (current value = {buckets})",
+                i32::MAX
+            );
+        }
+        Ok(Box::new(Int32Evaluator::new(
+            self.inner.partition_evaluator(partition_evaluator_args)?,
+        )))
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<FieldRef> {
+        Ok(Field::new(field_args.name(), DataType::Int32, false).into())
+    }
+
+    fn limit_effect(&self, args: &[Arc<dyn PhysicalExpr>]) -> LimitEffect {
+        self.inner.limit_effect(args)
+    }
+}
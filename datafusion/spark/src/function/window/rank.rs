@@ -0,0 +1,96 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use std::sync::Arc;
+
+use arrow::compute::SortOptions;
+use arrow::datatypes::{DataType, Field, FieldRef};
+use datafusion_common::Result;
+use datafusion_expr::function::{PartitionEvaluatorArgs, WindowUDFFieldArgs};
+use datafusion_expr::{LimitEffect, PartitionEvaluator, Signature, WindowUDFImpl};
+use datafusion_functions_window::rank::Rank;
+use datafusion_physical_expr::PhysicalExpr;
+
+use crate::function::window::utils::Int32Evaluator;
+
+/// Spark-compatible `rank`, `dense_rank` and `percent_rank` window functions.
+/// `rank` and `dense_rank` return an `INT` rather than DataFusion's `BIGINT
+/// UNSIGNED`, and `percent_rank` a `DOUBLE`.
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#rank>
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#dense_rank>
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#percent_rank>
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkRank {
+    inner: Rank,
+    return_type: DataType,
+}
+
+impl SparkRank {
+    pub fn basic() -> Self {
+        Self {
+            inner: Rank::basic(),
+            return_type: DataType::Int32,
+        }
+    }
+
+    pub fn dense_rank() -> Self {
+        Self {
+            inner: Rank::dense_rank(),
+            return_type: DataType::Int32,
+        }
+    }
+
+    pub fn percent_rank() -> Self {
+        Self {
+            inner: Rank::percent_rank(),
+            return_type: DataType::Float64,
+        }
+    }
+}
+
+impl WindowUDFImpl for SparkRank {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn signature(&self) -> &Signature {
+        self.inner.signature()
+    }
+
+    fn partition_evaluator(
+        &self,
+        partition_evaluator_args: PartitionEvaluatorArgs,
+    ) -> Result<Box<dyn PartitionEvaluator>> {
+        let evaluator = self.inner.partition_evaluator(partition_evaluator_args)?;
+        match self.return_type {
+            DataType::Int32 => Ok(Box::new(Int32Evaluator::new(evaluator))),
+            _ => Ok(evaluator),
+        }
+    }
+
+    fn field(&self, field_args: WindowUDFFieldArgs) -> Result<FieldRef> {
+        Ok(Field::new(field_args.name(), self.return_type.clone(), false).into())
+    }
+
+    fn sort_options(&self) -> Option<SortOptions> {
+        self.inner.sort_options()
+    }
+
+    fn limit_effect(&self, args: &[Arc<dyn PhysicalExpr>]) -> LimitEffect {
+        self.inner.limit_effect(args)
+    }
+}
@@ -0,0 +1,108 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow::array::ArrayRef;
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::PartitionEvaluator;
+use datafusion_expr::window_state::WindowAggState;
+use datafusion_physical_expr::PhysicalExpr;
+use datafusion_physical_expr::expressions::Literal;
+
+/// Returns the value of a non-null integer literal argument if it fits in
+/// an `i32`, the type of Spark's `INT` arguments such as offsets and bucket
+/// counts, and `None` otherwise.
+pub(super) fn get_int_literal(expr: &Arc<dyn PhysicalExpr>) -> Option<i32> {
+    let literal = expr.downcast_ref::<Literal>()?;
+    if !literal.value().data_type().is_integer() {
+        return None;
+    }
+    match literal.value().cast_to(&DataType::Int64).ok()? {
+        ScalarValue::Int64(Some(value)) => i32::try_from(value).ok(),
+        _ => None,
+    }
+}
+
+/// Wraps the [`PartitionEvaluator`] of a DataFusion window function that
+/// returns `UInt64` values, such as `rank`, and casts its results to `Int32`,
+/// the type Spark returns.
+#[derive(Debug)]
+pub(super) struct Int32Evaluator {
+    inner: Box<dyn PartitionEvaluator>,
+}
+
+impl Int32Evaluator {
+    pub(super) fn new(inner: Box<dyn PartitionEvaluator>) -> Self {
+        Self { inner }
+    }
+}
+
+impl PartitionEvaluator for Int32Evaluator {
+    fn memoize(&mut self, state: &mut WindowAggState) -> Result<()> {
+        self.inner.memoize(state)
+    }
+
+    fn get_range(&self, idx: usize, n_rows: usize) -> Result<Range<usize>> {
+        self.inner.get_range(idx, n_rows)
+    }
+
+    fn is_causal(&self) -> bool {
+        self.inner.is_causal()
+    }
+
+    fn evaluate_all(&mut self, values: &[ArrayRef], num_rows: usize) -> Result<ArrayRef> {
+        let result = self.inner.evaluate_all(values, num_rows)?;
+        Ok(cast(&result, &DataType::Int32)?)
+    }
+
+    fn evaluate(
+        &mut self,
+        values: &[ArrayRef],
+        range: &Range<usize>,
+    ) -> Result<ScalarValue> {
+        self.inner
+            .evaluate(values, range)?
+            .cast_to(&DataType::Int32)
+    }
+
+    fn evaluate_all_with_rank(
+        &self,
+        num_rows: usize,
+        ranks_in_partition: &[Range<usize>],
+    ) -> Result<ArrayRef> {
+        let result = self
+            .inner
+            .evaluate_all_with_rank(num_rows, ranks_in_partition)?;
+        Ok(cast(&result, &DataType::Int32)?)
+    }
+
+    fn supports_bounded_execution(&self) -> bool {
+        self.inner.supports_bounded_execution()
+    }
+
+    fn uses_window_frame(&self) -> bool {
+        self.inner.uses_window_frame()
+    }
+
+    fn include_rank(&self) -> bool {
+        self.inner.include_rank()
+    }
+}
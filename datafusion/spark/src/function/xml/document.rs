@@ -0,0 +1,124 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Parsing of the XML documents read by the XML functions, with
+//! [`sxd_document`]. Namespace prefixes are resolved, and DTDs are skipped.
+
+use datafusion_common::{Result, exec_datafusion_err, exec_err};
+use sxd_document::Package;
+use sxd_document::dom::{ChildOfElement, ChildOfRoot, Document, Element};
+
+/// The maximum nesting depth of the elements of a document. `sxd_document`
+/// resolves namespaces by walking the ancestors of every element, and
+/// `sxd_xpath` computes the string values of nodes recursively, so deeper
+/// documents could take quadratic time to parse or overflow the stack.
+pub(super) const MAX_DOCUMENT_DEPTH: usize = 1024;
+
+/// Parses an XML document, rejecting documents nested more than
+/// [`MAX_DOCUMENT_DEPTH`] levels deep
+pub(super) fn parse(xml: &str) -> Result<Package> {
+    check_depth(xml)?;
+    sxd_document::parser::parse(xml)
+        .map_err(|e| exec_datafusion_err!("Invalid XML document: {e}"))
+}
+
+/// Returns an error if the elements of `xml` may be nested more than
+/// [`MAX_DOCUMENT_DEPTH`] levels deep.
+///
+/// This only scans the tags, skipping comments, CDATA sections, processing
+/// instructions and declarations, so it may overestimate the depth of
+/// malformed documents, which fail to parse anyway.
+fn check_depth(xml: &str) -> Result<()> {
+    let skip_past =
+        |rest: &str, end: &str| rest.find(end).map_or(rest.len(), |i| i + end.len());
+    let mut depth = 0usize;
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start..];
+        let len = if rest.starts_with("<!--") {
+            skip_past(rest, "-->")
+        } else if rest.starts_with("<![CDATA[") {
+            skip_past(rest, "]]>")
+        } else if rest.starts_with("<?") {
+            skip_past(rest, "?>")
+        } else if rest.starts_with("<!") {
+            skip_past(rest, ">")
+        } else if rest.starts_with("</") {
+            depth = depth.saturating_sub(1);
+            skip_past(rest, ">")
+        } else {
+            let (len, self_closing) = start_tag_len(rest);
+            if !self_closing {
+                depth += 1;
+                if depth > MAX_DOCUMENT_DEPTH {
+                    return exec_err!(
+                        "Invalid XML document: elements are nested more than \
+                         {MAX_DOCUMENT_DEPTH} levels deep"
+                    );
+                }
+            }
+            len
+        };
+        rest = &rest[len..];
+    }
+    Ok(())
+}
+
+/// Returns the length of the start tag at the beginning of `xml`, whose
+/// attribute values may contain `>`, and whether it is self-closing
+fn start_tag_len(xml: &str) -> (usize, bool) {
+    let mut quote = None;
+    for (i, c) in xml.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => return (i + 1, xml[..i].ends_with('/')),
+            None => {}
+        }
+    }
+    (xml.len(), false)
+}
+
+/// Returns the root element of `doc`
+pub(super) fn document_element(doc: Document) -> Option<Element> {
+    doc.root()
+        .children()
+        .into_iter()
+        .find_map(|child| match child {
+            ChildOfRoot::Element(element) => Some(element),
+            _ => None,
+        })
+}
+
+/// The XPath string-value of `element`: the concatenated text of its
+/// descendants, in document order
+pub(super) fn string_value(element: Element) -> String {
+    let mut value = String::new();
+    let mut stack = element.children();
+    stack.reverse();
+    while let Some(child) = stack.pop() {
+        match child {
+            ChildOfElement::Text(text) => value.push_str(text.text()),
+            ChildOfElement::Element(element) => {
+                stack.extend(element.children().into_iter().rev())
+            }
+            _ => {}
+        }
+    }
+    value
+}
@@ -0,0 +1,391 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, AsArray, ListArray, StringArray, StructArray};
+use arrow::buffer::{NullBuffer, OffsetBuffer};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion_common::{Result, exec_err, internal_err, plan_err};
+use datafusion_expr::{
+    ColumnarValue, ReturnFieldArgs, ScalarFunctionArgs, ScalarUDFImpl, Signature,
+    Volatility,
+};
+use sxd_document::dom::{ChildOfElement, Element};
+
+use super::document::{document_element, parse, string_value};
+use crate::function::ddl_utils::{parse_ddl_schema, to_ddl_type};
+use crate::function::options_utils::{bool_option, parse_options};
+
+/// Spark-compatible `from_xml` expression
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#from_xml>
+///
+/// `from_xml(xml_str, schema[, options])` parses an XML document into a
+/// struct with the fields of the DDL `schema` string. The root element is the
+/// row: its child elements are matched by local name, its attributes by their
+/// local name prefixed with the `attributePrefix` option (`_` by default) and its own
+/// text by the `valueTag` option (`_VALUE` by default). Values that cannot be
+/// converted to the type of their field are `NULL`, and so is the row of a
+/// malformed document.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkFromXml {
+    signature: Signature,
+}
+
+impl Default for SparkFromXml {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkFromXml {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkFromXml {
+    fn name(&self) -> &str {
+        "from_xml"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        internal_err!("return_field_from_args should be used instead")
+    }
+
+    fn return_field_from_args(&self, args: ReturnFieldArgs) -> Result<FieldRef> {
+        let schema = match args.scalar_arguments.get(1) {
+            Some(Some(value)) => value.try_as_str().flatten(),
+            _ => None,
+        };
+        let Some(schema) = schema else {
+            return plan_err!("The schema of from_xml must be a STRING literal");
+        };
+        let fields = parse_ddl_schema(schema)?
+            .iter()
+            .map(|field| Ok(Field::new(field.name(), nullable(field.data_type())?, true)))
+            .collect::<Result<Fields>>()?;
+        Ok(Arc::new(Field::new(
+            self.name(),
+            DataType::Struct(fields),
+            true,
+        )))
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let options = match args.args.get(2) {
+            None => None,
+            Some(ColumnarValue::Scalar(options)) => Some(options),
+            Some(ColumnarValue::Array(_)) => {
+                return exec_err!("The options of from_xml must be a MAP literal");
+            }
+        };
+        let options = parse_options(self.name(), options)?;
+        let format = XmlFormat {
+            attribute_prefix: options
+                .get("attributeprefix")
+                .cloned()
+                .unwrap_or_else(|| "_".to_string()),
+            value_tag: options
+                .get("valuetag")
+                .cloned()
+                .unwrap_or_else(|| "_VALUE".to_string()),
+            ignore_surrounding_spaces: bool_option(
+                self.name(),
+                &options,
+                "ignoresurroundingspaces",
+                true,
+            )?,
+        };
+
+        let data_type = args.return_field.data_type();
+        let xml = args.args[0].to_array(args.number_rows)?;
+        let values = xml
+            .as_string::<i32>()
+            .iter()
+            .map(|xml| {
+                let Some(package) = xml.and_then(|xml| parse(xml).ok()) else {
+                    return XmlValue::Null;
+                };
+                match document_element(package.as_document()) {
+                    Some(element) => format.value(element, data_type),
+                    None => XmlValue::Null,
+                }
+            })
+            .collect::<Vec<_>>();
+        let values = values.iter().collect::<Vec<_>>();
+        Ok(ColumnarValue::Array(build_array(&values, data_type)?))
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        match arg_types {
+            [_, _] => Ok(vec![DataType::Utf8, DataType::Utf8]),
+            [_, _, options @ (DataType::Map(_, _) | DataType::Null)] => {
+                Ok(vec![DataType::Utf8, DataType::Utf8, options.clone()])
+            }
+            [_, _, other] => {
+                plan_err!("The options of from_xml must be a MAP, got {other}")
+            }
+            _ => plan_err!("from_xml expects 2 or 3 arguments, got {}", arg_types.len()),
+        }
+    }
+}
+
+/// Returns `data_type` with all nested fields nullable, rejecting the types
+/// that cannot be read from XML
+fn nullable(data_type: &DataType) -> Result<DataType> {
+    Ok(match data_type {
+        DataType::Struct(fields) => DataType::Struct(
+            fields
+                .iter()
+                .map(|field| {
+                    Ok(Field::new(field.name(), nullable(field.data_type())?, true))
+                })
+                .collect::<Result<Fields>>()?,
+        ),
+        DataType::List(field) => DataType::List(Arc::new(Field::new_list_field(
+            nullable(field.data_type())?,
+            true,
+        ))),
+        DataType::Map(_, _) => {
+            return plan_err!(
+                "from_xml does not support the type {}",
+                to_ddl_type(data_type)
+            );
+        }
+        other => other.clone(),
+    })
+}
+
+/// The value of an XML element or attribute, shaped like its target type
+enum XmlValue {
+    Null,
+    Text(String),
+    Struct(Vec<XmlValue>),
+    List(Vec<XmlValue>),
+}
+
+struct XmlFormat {
+    attribute_prefix: String,
+    value_tag: String,
+    ignore_surrounding_spaces: bool,
+}
+
+impl XmlFormat {
+    fn text(&self, value: String) -> XmlValue {
+        if self.ignore_surrounding_spaces {
+            XmlValue::Text(value.trim().to_string())
+        } else {
+            XmlValue::Text(value)
+        }
+    }
+
+    /// Converts `element` to a value of `data_type`
+    fn value(&self, element: Element, data_type: &DataType) -> XmlValue {
+        let DataType::Struct(fields) = data_type else {
+            return self.text(string_value(element));
+        };
+        let children = element.children();
+        let values = fields
+            .iter()
+            .map(|field| {
+                let name = field.name();
+                if name == &self.value_tag {
+                    let text = children
+                        .iter()
+                        .filter_map(|child| child.text().map(|text| text.text()))
+                        .collect::<String>();
+                    return match self.text(text) {
+                        XmlValue::Text(text) if text.is_empty() => XmlValue::Null,
+                        value => value,
+                    };
+                }
+                if !self.attribute_prefix.is_empty()
+                    && let Some(attribute) = name.strip_prefix(&self.attribute_prefix)
+                {
+                    return element
+                        .attributes()
+                        .into_iter()
+                        .find(|attr| attr.name().local_part() == attribute)
+                        .map(|attr| self.text(attr.value().to_string()))
+                        .unwrap_or(XmlValue::Null);
+                }
+                let mut children = children.iter().filter_map(|child| match child {
+                    ChildOfElement::Element(child)
+                        if child.name().local_part() == name =>
+                    {
+                        Some(*child)
+                    }
+                    _ => None,
+                });
+                match field.data_type() {
+                    DataType::List(item) => {
+                        let items = children
+                            .map(|child| self.value(child, item.data_type()))
+                            .collect::<Vec<_>>();
+                        if items.is_empty() {
+                            XmlValue::Null
+                        } else {
+                            XmlValue::List(items)
+                        }
+                    }
+                    data_type => children
+                        .next()
+                        .map(|child| self.value(child, data_type))
+                        .unwrap_or(XmlValue::Null),
+                }
+            })
+            .collect();
+        XmlValue::Struct(values)
+    }
+}
+
+/// Builds an array of `data_type` from `values`, casting text to the leaf
+/// types
+fn build_array(values: &[&XmlValue], data_type: &DataType) -> Result<ArrayRef> {
+    let nulls = NullBuffer::from_iter(
+        values.iter().map(|value| !matches!(value, XmlValue::Null)),
+    );
+    match data_type {
+        DataType::Struct(fields) => {
+            let columns = fields
+                .iter()
+                .enumerate()
+                .map(|(idx, field)| {
+                    let children = values
+                        .iter()
+                        .map(|value| match value {
+                            XmlValue::Struct(children) => &children[idx],
+                            _ => &XmlValue::Null,
+                        })
+                        .collect::<Vec<_>>();
+                    build_array(&children, field.data_type())
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Arc::new(StructArray::try_new(
+                fields.clone(),
+                columns,
+                Some(nulls),
+            )?))
+        }
+        DataType::List(field) => {
+            let items = values
+                .iter()
+                .flat_map(|value| match value {
+                    XmlValue::List(items) => items.as_slice(),
+                    _ => &[],
+                })
+                .collect::<Vec<_>>();
+            let offsets =
+                OffsetBuffer::from_lengths(values.iter().map(|value| match value {
+                    XmlValue::List(items) => items.len(),
+                    _ => 0,
+                }));
+            Ok(Arc::new(ListArray::try_new(
+                Arc::clone(field),
+                offsets,
+                build_array(&items, field.data_type())?,
+                Some(nulls),
+            )?))
+        }
+        _ => {
+            let text = values
+                .iter()
+                .map(|value| match value {
+                    XmlValue::Text(text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<StringArray>();
+            Ok(cast(&text, data_type)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, Int32Array};
+    use datafusion_common::ScalarValue;
+
+    use super::super::document::MAX_DOCUMENT_DEPTH;
+    use super::*;
+
+    fn from_xml(xml: Vec<Option<String>>, data_type: DataType) -> StructArray {
+        let number_rows = xml.len();
+        let args = ScalarFunctionArgs {
+            args: vec![
+                ColumnarValue::Array(Arc::new(StringArray::from(xml))),
+                ColumnarValue::Scalar(ScalarValue::Utf8(Some(to_ddl_type(&data_type)))),
+            ],
+            arg_fields: vec![
+                Arc::new(Field::new("xml", DataType::Utf8, true)),
+                Arc::new(Field::new("schema", DataType::Utf8, false)),
+            ],
+            number_rows,
+            return_field: Arc::new(Field::new("from_xml", data_type, true)),
+            config_options: Arc::new(Default::default()),
+        };
+        let result = SparkFromXml::new().invoke_with_args(args).unwrap();
+        result.to_array(number_rows).unwrap().as_struct().clone()
+    }
+
+    #[test]
+    fn test_malformed_documents_are_null() {
+        let data_type = DataType::Struct(Fields::from(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("_b", DataType::Utf8, true),
+        ]));
+        let depth = MAX_DOCUMENT_DEPTH - 1;
+        let xml = [
+            Some("<row b='x'><a>1</a></row>".to_string()),
+            Some("<row><a>1</a>".to_string()),
+            Some("<row><a>1</b></row>".to_string()),
+            Some("<a>".repeat(100_000)),
+            Some(format!(
+                "<row><a>2</a>{}{}</row>",
+                "<c>".repeat(depth),
+                "</c>".repeat(depth)
+            )),
+            Some(format!(
+                "<row>{}{}</row>",
+                "<c>".repeat(100_000),
+                "</c>".repeat(100_000)
+            )),
+            None,
+        ];
+        let result = from_xml(xml.to_vec(), data_type);
+        let nulls = (0..result.len())
+            .map(|row| result.is_null(row))
+            .collect::<Vec<_>>();
+        assert_eq!(nulls, [false, true, true, true, false, true, true]);
+        let a = result
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(a.value(0), 1);
+        assert_eq!(a.value(4), 2);
+        assert_eq!(result.column(1).as_string::<i32>().value(0), "x");
+    }
+}
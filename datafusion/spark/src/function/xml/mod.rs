@@ -15,11 +15,96 @@
 // specific language governing permissions and limitations
 // under the License.
 
+mod document;
+pub mod from_xml;
+pub mod xpath;
+
 use datafusion_expr::ScalarUDF;
+use datafusion_functions::make_udf_function;
 use std::sync::Arc;
 
-pub mod expr_fn {}
+make_udf_function!(from_xml::SparkFromXml, from_xml);
+make_udf_function!(xpath::SparkXPath, xpath);
+make_udf_function!(
+    xpath::SparkXPath,
+    xpath_string,
+    xpath::SparkXPath::new_string
+);
+make_udf_function!(
+    xpath::SparkXPath,
+    xpath_boolean,
+    xpath::SparkXPath::new_boolean
+);
+make_udf_function!(xpath::SparkXPath, xpath_short, xpath::SparkXPath::new_short);
+make_udf_function!(xpath::SparkXPath, xpath_int, xpath::SparkXPath::new_int);
+make_udf_function!(xpath::SparkXPath, xpath_long, xpath::SparkXPath::new_long);
+make_udf_function!(xpath::SparkXPath, xpath_float, xpath::SparkXPath::new_float);
+make_udf_function!(
+    xpath::SparkXPath,
+    xpath_double,
+    xpath::SparkXPath::new_double
+);
+
+pub mod expr_fn {
+    use datafusion_functions::export_functions;
+
+    export_functions!((
+        from_xml,
+        "Parses an XML string into a struct with the given DDL schema.",
+        args,
+    ));
+    export_functions!((
+        xpath,
+        "Returns the values of the nodes of an XML string selected by an XPath expression.",
+        xml path
+    ));
+    export_functions!((
+        xpath_string,
+        "Returns the string value of an XPath expression evaluated on an XML string.",
+        xml path
+    ));
+    export_functions!((
+        xpath_boolean,
+        "Returns true if an XPath expression evaluates to true or selects a node of an XML string.",
+        xml path
+    ));
+    export_functions!((
+        xpath_short,
+        "Returns the short value of an XPath expression evaluated on an XML string.",
+        xml path
+    ));
+    export_functions!((
+        xpath_int,
+        "Returns the integer value of an XPath expression evaluated on an XML string.",
+        xml path
+    ));
+    export_functions!((
+        xpath_long,
+        "Returns the long value of an XPath expression evaluated on an XML string.",
+        xml path
+    ));
+    export_functions!((
+        xpath_float,
+        "Returns the float value of an XPath expression evaluated on an XML string.",
+        xml path
+    ));
+    export_functions!((
+        xpath_double,
+        "Returns the double value of an XPath expression evaluated on an XML string.",
+        xml path
+    ));
+}
 
 pub fn functions() -> Vec<Arc<ScalarUDF>> {
-    vec![]
+    vec![
+        from_xml(),
+        xpath(),
+        xpath_string(),
+        xpath_boolean(),
+        xpath_short(),
+        xpath_int(),
+        xpath_long(),
+        xpath_float(),
+        xpath_double(),
+    ]
 }
@@ -0,0 +1,483 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, AsArray, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array,
+    Int64Array, ListBuilder, StringArray, StringBuilder, new_null_array,
+};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::{Result, exec_err, plan_err};
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDFImpl, Signature, Volatility,
+};
+use sxd_xpath::nodeset::Node;
+use sxd_xpath::{Context, Factory, Value, XPath};
+
+use super::document::parse;
+
+/// The maximum nesting depth of the parentheses and predicates of an XPath.
+/// Together with [`MAX_XPATH_OPERATORS`] this bounds the depth of the
+/// expression tree, which `sxd_xpath` parses and evaluates recursively.
+const MAX_XPATH_DEPTH: usize = 64;
+
+/// The maximum number of operators of an XPath
+const MAX_XPATH_OPERATORS: usize = 100;
+
+/// The result type of an xpath function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum XPathKind {
+    /// The values of the selected nodes
+    Values,
+    String,
+    Boolean,
+    Short,
+    Int,
+    Long,
+    Float,
+    Double,
+}
+
+/// Spark-compatible `xpath`, `xpath_string`, `xpath_boolean`, `xpath_short`,
+/// `xpath_int`, `xpath_long`, `xpath_float` and `xpath_double` expressions
+///
+/// <https://spark.apache.org/docs/latest/api/sql/index.html#xpath>
+///
+/// Each function evaluates an XPath 1.0 expression against an XML string with
+/// [`sxd_xpath`] and converts the result to its return type. `xpath` returns the values of the
+/// selected nodes, which are `NULL` for elements. The result is `NULL` when
+/// the XML or the path is `NULL` or empty.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct SparkXPath {
+    signature: Signature,
+    kind: XPathKind,
+    aliases: Vec<String>,
+}
+
+impl Default for SparkXPath {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SparkXPath {
+    /// `xpath(xml, path)`
+    pub fn new() -> Self {
+        Self::new_with_kind(XPathKind::Values)
+    }
+
+    /// `xpath_string(xml, path)`
+    pub fn new_string() -> Self {
+        Self::new_with_kind(XPathKind::String)
+    }
+
+    /// `xpath_boolean(xml, path)`
+    pub fn new_boolean() -> Self {
+        Self::new_with_kind(XPathKind::Boolean)
+    }
+
+    /// `xpath_short(xml, path)`
+    pub fn new_short() -> Self {
+        Self::new_with_kind(XPathKind::Short)
+    }
+
+    /// `xpath_int(xml, path)`
+    pub fn new_int() -> Self {
+        Self::new_with_kind(XPathKind::Int)
+    }
+
+    /// `xpath_long(xml, path)`
+    pub fn new_long() -> Self {
+        Self::new_with_kind(XPathKind::Long)
+    }
+
+    /// `xpath_float(xml, path)`
+    pub fn new_float() -> Self {
+        Self::new_with_kind(XPathKind::Float)
+    }
+
+    /// `xpath_double(xml, path)`, also known as `xpath_number`
+    pub fn new_double() -> Self {
+        Self::new_with_kind(XPathKind::Double)
+    }
+
+    fn new_with_kind(kind: XPathKind) -> Self {
+        let aliases = match kind {
+            XPathKind::Double => vec![String::from("xpath_number")],
+            _ => vec![],
+        };
+        Self {
+            signature: Signature::user_defined(Volatility::Immutable),
+            kind,
+            aliases,
+        }
+    }
+}
+
+impl ScalarUDFImpl for SparkXPath {
+    fn name(&self) -> &str {
+        match self.kind {
+            XPathKind::Values => "xpath",
+            XPathKind::String => "xpath_string",
+            XPathKind::Boolean => "xpath_boolean",
+            XPathKind::Short => "xpath_short",
+            XPathKind::Int => "xpath_int",
+            XPathKind::Long => "xpath_long",
+            XPathKind::Float => "xpath_float",
+            XPathKind::Double => "xpath_double",
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(match self.kind {
+            XPathKind::Values => {
+                DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+            }
+            XPathKind::String => DataType::Utf8,
+            XPathKind::Boolean => DataType::Boolean,
+            XPathKind::Short => DataType::Int16,
+            XPathKind::Int => DataType::Int32,
+            XPathKind::Long => DataType::Int64,
+            XPathKind::Float => DataType::Float32,
+            XPathKind::Double => DataType::Float64,
+        })
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let xml = args.args[0].to_array(args.number_rows)?;
+        let xml = xml.as_string::<i32>();
+        let results = match &args.args[1] {
+            ColumnarValue::Scalar(path) => {
+                match path.try_as_str().flatten().filter(|path| !path.is_empty()) {
+                    Some(path) => {
+                        let xpath = compile(path)?;
+                        xml.iter()
+                            .map(|xml| self.evaluate(xml, Some((path, &xpath))))
+                            .collect::<Result<Vec<_>>>()?
+                    }
+                    None => {
+                        let return_type = args.return_field.data_type();
+                        return Ok(ColumnarValue::Array(new_null_array(
+                            return_type,
+                            args.number_rows,
+                        )));
+                    }
+                }
+            }
+            ColumnarValue::Array(paths) => xml
+                .iter()
+                .zip(paths.as_string::<i32>().iter())
+                .map(|(xml, path)| {
+                    let path = path.filter(|path| !path.is_empty());
+                    let xpath = path.map(compile).transpose()?;
+                    self.evaluate(xml, path.zip(xpath.as_ref()))
+                })
+                .collect::<Result<Vec<_>>>()?,
+        };
+
+        let to_number = |result: &XPathResult| match result {
+            XPathResult::Number(number) => *number,
+            _ => f64::NAN,
+        };
+        let array: ArrayRef = match self.kind {
+            XPathKind::Values => {
+                let mut builder = ListBuilder::new(StringBuilder::new());
+                for result in &results {
+                    match result {
+                        Some(XPathResult::Values(values)) => {
+                            for value in values {
+                                builder.values().append_option(value.as_deref());
+                            }
+                            builder.append(true);
+                        }
+                        _ => builder.append_null(),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            XPathKind::String => Arc::new(
+                results
+                    .iter()
+                    .map(|result| match result {
+                        Some(XPathResult::String(value)) => Some(value.as_str()),
+                        _ => None,
+                    })
+                    .collect::<StringArray>(),
+            ),
+            XPathKind::Boolean => Arc::new(
+                results
+                    .iter()
+                    .map(|result| match result {
+                        Some(XPathResult::Boolean(value)) => Some(*value),
+                        _ => None,
+                    })
+                    .collect::<BooleanArray>(),
+            ),
+            XPathKind::Short => Arc::new(
+                results
+                    .iter()
+                    .map(|result| result.as_ref().map(|r| to_number(r) as i16))
+                    .collect::<Int16Array>(),
+            ),
+            XPathKind::Int => Arc::new(
+                results
+                    .iter()
+                    .map(|result| result.as_ref().map(|r| to_number(r) as i32))
+                    .collect::<Int32Array>(),
+            ),
+            XPathKind::Long => Arc::new(
+                results
+                    .iter()
+                    .map(|result| result.as_ref().map(|r| to_number(r) as i64))
+                    .collect::<Int64Array>(),
+            ),
+            XPathKind::Float => Arc::new(
+                results
+                    .iter()
+                    .map(|result| result.as_ref().map(|r| to_number(r) as f32))
+                    .collect::<Float32Array>(),
+            ),
+            XPathKind::Double => Arc::new(
+                results
+                    .iter()
+                    .map(|result| result.as_ref().map(to_number))
+                    .collect::<Float64Array>(),
+            ),
+        };
+        Ok(ColumnarValue::Array(array))
+    }
+
+    fn coerce_types(&self, arg_types: &[DataType]) -> Result<Vec<DataType>> {
+        if arg_types.len() != 2 {
+            return plan_err!(
+                "{} expects 2 arguments, got {}",
+                self.name(),
+                arg_types.len()
+            );
+        }
+        Ok(vec![DataType::Utf8, DataType::Utf8])
+    }
+}
+
+/// The result of an xpath function for one row, converted from the XPath
+/// value while its document is alive
+enum XPathResult {
+    /// The node values of the selected nodes, in document order
+    Values(Vec<Option<String>>),
+    String(String),
+    Boolean(bool),
+    Number(f64),
+}
+
+impl SparkXPath {
+    /// Evaluates `path` against `xml`, or returns `None` if either is `NULL` or
+    /// empty
+    fn evaluate(
+        &self,
+        xml: Option<&str>,
+        path: Option<(&str, &XPath)>,
+    ) -> Result<Option<XPathResult>> {
+        let (Some(xml), Some((path, xpath))) = (xml.filter(|xml| !xml.is_empty()), path)
+        else {
+            return Ok(None);
+        };
+        let package = parse(xml)?;
+        let doc = package.as_document();
+        let value = match xpath.evaluate(&Context::new(), doc.root()) {
+            Ok(value) => value,
+            Err(e) => return exec_err!("Failed to evaluate XPath '{path}': {e}"),
+        };
+        Ok(Some(match self.kind {
+            XPathKind::Values => {
+                let Value::Nodeset(nodes) = value else {
+                    return exec_err!(
+                        "The XPath of xpath must select a node-set, got {value:?}"
+                    );
+                };
+                XPathResult::Values(
+                    nodes.document_order().into_iter().map(node_value).collect(),
+                )
+            }
+            XPathKind::String => XPathResult::String(value.string()),
+            XPathKind::Boolean => XPathResult::Boolean(value.boolean()),
+            _ => XPathResult::Number(value.number()),
+        }))
+    }
+}
+
+/// The DOM node value: the value of attributes, text, comments and processing
+/// instructions, `None` for the root and elements
+fn node_value(node: Node) -> Option<String> {
+    match node {
+        Node::Root(_) | Node::Element(_) => None,
+        node => Some(node.string_value()),
+    }
+}
+
+/// Compiles an XPath 1.0 expression, rejecting expressions nested deeper than
+/// [`MAX_XPATH_DEPTH`] or with more than [`MAX_XPATH_OPERATORS`] operators
+fn compile(path: &str) -> Result<XPath> {
+    check_xpath_complexity(path)?;
+    match Factory::new().build(path) {
+        Ok(Some(xpath)) => Ok(xpath),
+        Ok(None) => exec_err!("Invalid XPath '{path}': empty expression"),
+        Err(e) => exec_err!("Invalid XPath '{path}': {e}"),
+    }
+}
+
+/// Bounds the size of the expression tree of `path` before it is parsed.
+///
+/// Outside of string literals, every `(` and `[` opens a nesting level, and
+/// every character or keyword that may be an operator counts as one, even
+/// when it is part of a name or a wildcard.
+fn check_xpath_complexity(path: &str) -> Result<()> {
+    let mut depth = 0usize;
+    let mut operators = 0usize;
+    let mut quote = None;
+    let mut word = String::new();
+    for c in path.chars() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        if c.is_alphanumeric() {
+            word.push(c);
+            continue;
+        }
+        if matches!(word.as_str(), "and" | "or" | "div" | "mod") {
+            operators += 1;
+        }
+        word.clear();
+        match c {
+            '\'' | '"' => quote = Some(c),
+            '(' | '[' => {
+                depth += 1;
+                if depth > MAX_XPATH_DEPTH {
+                    return exec_err!(
+                        "Invalid XPath '{path}': expression is nested more than \
+                         {MAX_XPATH_DEPTH} levels deep"
+                    );
+                }
+            }
+            ')' | ']' => depth = depth.saturating_sub(1),
+            '+' | '-' | '*' | '|' | '=' | '<' | '>' => operators += 1,
+            _ => {}
+        }
+    }
+    if matches!(word.as_str(), "and" | "or" | "div" | "mod") {
+        operators += 1;
+    }
+    if operators > MAX_XPATH_OPERATORS {
+        return exec_err!(
+            "Invalid XPath '{path}': expression has more than \
+             {MAX_XPATH_OPERATORS} operators"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion_common::ScalarValue;
+
+    use super::super::document::MAX_DOCUMENT_DEPTH;
+
+    use super::*;
+
+    fn xpath_int(xml: Vec<Option<String>>, path: &str) -> Result<ArrayRef> {
+        let number_rows = xml.len();
+        let args = ScalarFunctionArgs {
+            args: vec![
+                ColumnarValue::Array(Arc::new(StringArray::from(xml))),
+                ColumnarValue::Scalar(ScalarValue::Utf8(Some(path.to_string()))),
+            ],
+            arg_fields: vec![
+                Arc::new(Field::new("xml", DataType::Utf8, true)),
+                Arc::new(Field::new("path", DataType::Utf8, false)),
+            ],
+            number_rows,
+            return_field: Arc::new(Field::new("xpath_int", DataType::Int32, true)),
+            config_options: Arc::new(Default::default()),
+        };
+        SparkXPath::new_int()
+            .invoke_with_args(args)?
+            .to_array(number_rows)
+    }
+
+    #[test]
+    fn test_deeply_nested_document() {
+        let depth = MAX_DOCUMENT_DEPTH;
+        let xml = format!("{}1{}", "<a>".repeat(depth), "</a>".repeat(depth));
+        let result = xpath_int(vec![Some(xml), None], "count(//a) + /a").unwrap();
+        assert_eq!(
+            result.as_primitive::<arrow::datatypes::Int32Type>(),
+            &Int32Array::from(vec![Some(depth as i32 + 1), None])
+        );
+
+        let depth = 100_000;
+        let xml = format!("{}1{}", "<a>".repeat(depth), "</a>".repeat(depth));
+        let err = xpath_int(vec![Some(xml)], "count(//a)").unwrap_err();
+        assert!(err.to_string().contains("nested more than"), "{err}");
+    }
+
+    #[test]
+    fn test_wide_document() {
+        let xml = format!(
+            "<a x='>'>{}<!-- <c> --><![CDATA[<c>]]></a>",
+            "<b/><b y='/>'></b>".repeat(MAX_DOCUMENT_DEPTH)
+        );
+        let result = xpath_int(vec![Some(xml)], "count(a/b)").unwrap();
+        assert_eq!(
+            result.as_primitive::<arrow::datatypes::Int32Type>(),
+            &Int32Array::from(vec![Some(2 * MAX_DOCUMENT_DEPTH as i32)])
+        );
+    }
+
+    #[test]
+    fn test_namespaces() {
+        let xml = r#"<a xmlns:x="urn:x"><x:b>1</x:b><b>2</b></a>"#.to_string();
+        let result = xpath_int(vec![Some(xml)], "sum(a/*)").unwrap();
+        assert_eq!(
+            result.as_primitive::<arrow::datatypes::Int32Type>(),
+            &Int32Array::from(vec![Some(3)])
+        );
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        for xml in ["<a>".repeat(100_000), "<a><b></a>".to_string()] {
+            let err = xpath_int(vec![Some(xml)], "count(//a)").unwrap_err();
+            assert!(err.to_string().contains("Invalid XML document"), "{err}");
+        }
+        let path = format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000));
+        for path in [path.as_str(), "a[", "count(a"] {
+            let err = xpath_int(vec![Some("<a/>".to_string())], path).unwrap_err();
+            assert!(err.to_string().contains("Invalid XPath"), "{err}");
+        }
+    }
+}
@@ -131,9 +131,13 @@
 pub mod function;
 pub mod planner;
 
+#[cfg(feature = "core")]
+mod partition_context;
 #[cfg(feature = "core")]
 mod session_state;
 
+#[cfg(feature = "core")]
+pub use partition_context::{AddPartitionContext, PartitionContextExec};
 #[cfg(feature = "core")]
 pub use session_state::SessionStateBuilderSpark;
 
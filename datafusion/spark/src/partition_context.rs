@@ -0,0 +1,372 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Evaluation of the Spark functions that depend on the partition of the rows,
+//! `spark_partition_id` and `monotonically_increasing_id`.
+
+use std::fmt::{self, Formatter};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::array::{ArrayRef, Int32Array, Int64Array, RecordBatch};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use datafusion::common::config::ConfigOptions;
+use datafusion::common::tree_node::{Transformed, TreeNode, TreeNodeRecursion};
+use datafusion::common::{Result, exec_err};
+use datafusion::execution::{RecordBatchStream, SendableRecordBatchStream, TaskContext};
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_expr::{
+    EquivalenceProperties, PhysicalExpr, ScalarFunctionExpr,
+};
+use datafusion::physical_optimizer::PhysicalOptimizerRule;
+use datafusion::physical_plan::filter::{FilterExec, FilterExecBuilder};
+use datafusion::physical_plan::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet, RecordOutput,
+};
+use datafusion::physical_plan::projection::{ProjectionExec, ProjectionExpr};
+use datafusion::physical_plan::{
+    ChildrenPropertiesMode, DisplayAs, DisplayFormatType, ExecutionPlan,
+    ExecutionPlanProperties, PlanProperties, ReplaceChildrenOptions,
+};
+use futures::{Stream, StreamExt, ready};
+
+use crate::function::misc::monotonically_increasing_id::SparkMonotonicallyIncreasingId;
+use crate::function::misc::spark_partition_id::SparkPartitionId;
+
+/// Number of bits of the ids of `monotonically_increasing_id` that hold the
+/// index of the row within its partition, as in Spark
+const ROW_INDEX_BITS: u32 = 33;
+
+/// Appends the partition id of each row and the id returned for it by
+/// `monotonically_increasing_id` to the rows of its input.
+///
+/// The partition id is appended as an `Int32` column and the id as an `Int64`
+/// column. The id of a row is its partition id shifted left by 33 bits plus
+/// the index of the row within its partition.
+#[derive(Debug)]
+pub struct PartitionContextExec {
+    input: Arc<dyn ExecutionPlan>,
+    metrics: ExecutionPlanMetricsSet,
+    cache: Arc<PlanProperties>,
+}
+
+impl PartitionContextExec {
+    /// Create a new `PartitionContextExec` appending the columns to the rows
+    /// of `input`
+    pub fn new(input: Arc<dyn ExecutionPlan>) -> Self {
+        let mut fields = input.schema().fields().to_vec();
+        fields.push(Arc::new(Field::new(
+            "spark_partition_id",
+            DataType::Int32,
+            false,
+        )));
+        fields.push(Arc::new(Field::new(
+            "monotonically_increasing_id",
+            DataType::Int64,
+            false,
+        )));
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            input.schema().metadata().clone(),
+        ));
+
+        // The appended columns are at the end, so the orderings of the input
+        // are still valid
+        let orderings = input
+            .equivalence_properties()
+            .oeq_class()
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let cache = PlanProperties::new(
+            EquivalenceProperties::new_with_orderings(schema, orderings),
+            input.properties().partitioning.clone(),
+            input.properties().emission_type,
+            input.properties().boundedness,
+        );
+
+        Self {
+            input,
+            metrics: ExecutionPlanMetricsSet::new(),
+            cache: Arc::new(cache),
+        }
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+}
+
+impl DisplayAs for PartitionContextExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "PartitionContextExec")
+            }
+            DisplayFormatType::TreeRender => Ok(()),
+        }
+    }
+}
+
+impl ExecutionPlan for PartitionContextExec {
+    fn name(&self) -> &'static str {
+        "PartitionContextExec"
+    }
+
+    fn properties(&self) -> &Arc<PlanProperties> {
+        &self.cache
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn children(&self) -> Vec<&Arc<dyn ExecutionPlan>> {
+        vec![&self.input]
+    }
+
+    fn replace_children(
+        self: Arc<Self>,
+        mut children: Vec<Arc<dyn ExecutionPlan>>,
+        _: ReplaceChildrenOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self::new(children.swap_remove(0))))
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.replace_children(
+            children,
+            ReplaceChildrenOptions::new(ChildrenPropertiesMode::Recompute),
+        )
+    }
+
+    fn apply_expressions(
+        &self,
+        _f: &mut dyn FnMut(&Arc<dyn PhysicalExpr>) -> Result<TreeNodeRecursion>,
+    ) -> Result<TreeNodeRecursion> {
+        Ok(TreeNodeRecursion::Continue)
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let Ok(partition_id) = i32::try_from(partition) else {
+            return exec_err!(
+                "Partition {partition} does not fit in a Spark partition id"
+            );
+        };
+        Ok(Box::pin(PartitionContextStream {
+            input: self.input.execute(partition, context)?,
+            schema: self.schema(),
+            partition_id,
+            next_row_index: 0,
+            metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+}
+
+/// Stream appending the partition id and the row ids to each batch
+struct PartitionContextStream {
+    input: SendableRecordBatchStream,
+    schema: SchemaRef,
+    partition_id: i32,
+    /// The index within the partition of the first row of the next batch
+    next_row_index: i64,
+    metrics: BaselineMetrics,
+}
+
+impl PartitionContextStream {
+    fn append_columns(&mut self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let num_rows = batch.num_rows();
+        let start = ((self.partition_id as i64) << ROW_INDEX_BITS) + self.next_row_index;
+        self.next_row_index += num_rows as i64;
+
+        let mut columns = batch.columns().to_vec();
+        columns.push(
+            Arc::new(Int32Array::from_value(self.partition_id, num_rows)) as ArrayRef,
+        );
+        columns.push(Arc::new(Int64Array::from_iter_values(
+            start..start + num_rows as i64,
+        )));
+        Ok(RecordBatch::try_new(Arc::clone(&self.schema), columns)?)
+    }
+}
+
+impl Stream for PartitionContextStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        match ready!(self.input.poll_next_unpin(cx)) {
+            Some(Ok(batch)) => {
+                let elapsed = self.metrics.elapsed_compute().clone();
+                let _timer = elapsed.timer();
+                let result = self.append_columns(&batch);
+                Poll::Ready(Some(result.record_output(&self.metrics)))
+            }
+            other => Poll::Ready(other),
+        }
+    }
+}
+
+impl RecordBatchStream for PartitionContextStream {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+}
+
+/// Physical optimizer rule that evaluates `spark_partition_id` and
+/// `monotonically_increasing_id` in projections and filters.
+///
+/// The input of a [`ProjectionExec`] or [`FilterExec`] calling one of the
+/// functions is wrapped in a [`PartitionContextExec`], and the calls are
+/// replaced with the columns it appends. The rule must run before projections
+/// are pushed into the sources, as the sources cannot evaluate the functions.
+#[derive(Debug, Default)]
+pub struct AddPartitionContext {}
+
+impl AddPartitionContext {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for AddPartitionContext {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(|plan| {
+            if let Some(projection) = plan.downcast_ref::<ProjectionExec>() {
+                if !projection
+                    .expr()
+                    .iter()
+                    .any(|expr| uses_partition_context(&expr.expr))
+                {
+                    return Ok(Transformed::no(plan));
+                }
+
+                let num_fields = projection.input().schema().fields().len();
+                let exprs = projection
+                    .expr()
+                    .iter()
+                    .map(|expr| {
+                        Ok(ProjectionExpr {
+                            expr: resolve_partition_context(&expr.expr, num_fields)?,
+                            alias: expr.alias.clone(),
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let input =
+                    Arc::new(PartitionContextExec::new(Arc::clone(projection.input())));
+                Ok(Transformed::yes(Arc::new(ProjectionExec::try_new(
+                    exprs, input,
+                )?)))
+            } else if let Some(filter) = plan.downcast_ref::<FilterExec>() {
+                if !uses_partition_context(filter.predicate()) {
+                    return Ok(Transformed::no(plan));
+                }
+
+                let num_fields = filter.input().schema().fields().len();
+                let predicate =
+                    resolve_partition_context(filter.predicate(), num_fields)?;
+                let input =
+                    Arc::new(PartitionContextExec::new(Arc::clone(filter.input())));
+                let builder = FilterExecBuilder::from(filter)
+                    .with_input(input)
+                    .with_predicate(predicate);
+                // Only output the columns of the original input
+                let builder = match filter.projection() {
+                    Some(_) => builder,
+                    None => builder.apply_projection(Some((0..num_fields).collect()))?,
+                };
+                Ok(Transformed::yes(Arc::new(builder.build()?)))
+            } else {
+                Ok(Transformed::no(plan))
+            }
+        })
+        .map(|t| t.data)
+    }
+
+    fn name(&self) -> &str {
+        "AddPartitionContext"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Returns true if `expr` calls a function evaluated by [`PartitionContextExec`]
+fn uses_partition_context(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    expr.exists(|expr| {
+        Ok(
+            ScalarFunctionExpr::try_downcast_func::<SparkPartitionId>(expr.as_ref())
+                .is_some()
+                || ScalarFunctionExpr::try_downcast_func::<
+                    SparkMonotonicallyIncreasingId,
+                >(expr.as_ref())
+                .is_some(),
+        )
+    })
+    .unwrap_or(false)
+}
+
+/// Replaces the calls in `expr` with the columns appended by
+/// [`PartitionContextExec`] to an input with `num_fields` fields
+fn resolve_partition_context(
+    expr: &Arc<dyn PhysicalExpr>,
+    num_fields: usize,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    Arc::clone(expr)
+        .transform_up(|expr| {
+            if ScalarFunctionExpr::try_downcast_func::<SparkPartitionId>(expr.as_ref())
+                .is_some()
+            {
+                Ok(Transformed::yes(Arc::new(Column::new(
+                    "spark_partition_id",
+                    num_fields,
+                ))))
+            } else if ScalarFunctionExpr::try_downcast_func::<
+                SparkMonotonicallyIncreasingId,
+            >(expr.as_ref())
+            .is_some()
+            {
+                Ok(Transformed::yes(Arc::new(Column::new(
+                    "monotonically_increasing_id",
+                    num_fields + 1,
+                ))))
+            } else {
+                Ok(Transformed::no(expr))
+            }
+        })
+        .map(|t| t.data)
+}
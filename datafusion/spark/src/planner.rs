@@ -15,9 +15,12 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use datafusion_expr::Expr;
-use datafusion_expr::expr::ScalarFunction;
+use datafusion_common::DFSchema;
+use datafusion_expr::expr::{ScalarFunction, Unnest};
 use datafusion_expr::planner::{ExprPlanner, PlannerResult};
+use datafusion_expr::{Expr, lit};
+
+use crate::function::generator::generator_outer;
 
 #[derive(Default, Debug)]
 pub struct SparkFunctionPlanner;
@@ -40,4 +43,50 @@ impl ExprPlanner for SparkFunctionPlanner {
             ScalarFunction::new_udf(crate::function::string::substring(), args),
         )))
     }
+
+    /// Plans struct literals with the Spark `named_struct`. Unnamed fields are
+    /// named after their columns, or `col1`, `col2`, etc. for other values, as
+    /// Spark does.
+    fn plan_struct_literal(
+        &self,
+        args: Vec<Expr>,
+        is_named_struct: bool,
+    ) -> datafusion_common::Result<PlannerResult<Vec<Expr>>> {
+        let args = if is_named_struct {
+            args
+        } else {
+            args.into_iter()
+                .enumerate()
+                .flat_map(|(i, arg)| {
+                    let name = match &arg {
+                        Expr::Column(column) => column.name.clone(),
+                        _ => format!("col{}", i + 1),
+                    };
+                    [lit(name), arg]
+                })
+                .collect()
+        };
+        Ok(PlannerResult::Planned(Expr::ScalarFunction(
+            ScalarFunction::new_udf(crate::function::r#struct::named_struct(), args),
+        )))
+    }
+
+    /// Plans generator functions, such as `explode`, as an unnest of the rows
+    /// they return. The `_outer` variants produce a row of `NULL`s for `NULL`
+    /// and empty inputs, as Spark does.
+    fn plan_generator(
+        &self,
+        expr: Expr,
+        _schema: &DFSchema,
+    ) -> datafusion_common::Result<PlannerResult<Expr>> {
+        let outer = match &expr {
+            Expr::ScalarFunction(ScalarFunction { func, .. }) => generator_outer(func),
+            _ => None,
+        };
+        Ok(match outer {
+            Some(true) => PlannerResult::Planned(Expr::Unnest(Unnest::new_outer(expr))),
+            Some(false) => PlannerResult::Planned(Expr::Unnest(Unnest::new(expr))),
+            None => PlannerResult::Original(expr),
+        })
+    }
 }
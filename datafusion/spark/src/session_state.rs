@@ -19,7 +19,9 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use datafusion::execution::SessionStateBuilder;
+use datafusion::physical_optimizer::optimizer::PhysicalOptimizer;
 
+use crate::partition_context::AddPartitionContext;
 use crate::planner::SparkFunctionPlanner;
 use crate::{
     all_default_aggregate_functions, all_default_scalar_functions,
@@ -47,7 +49,9 @@ use crate::{
 /// ```
 pub trait SessionStateBuilderSpark {
     /// Adds all expr_planners, scalar, aggregate, window and table functions
-    /// compatible with Apache Spark.
+    /// compatible with Apache Spark, and the [`AddPartitionContext`] physical
+    /// optimizer rule evaluating the functions that depend on the partition of
+    /// the rows.
     ///
     /// Note: This overwrites any previously registered items with the same name.
     fn with_spark_features(self) -> Self;
@@ -81,6 +85,18 @@ impl SessionStateBuilderSpark for SessionStateBuilder {
                     .map(|f| (f.name().to_string(), f)),
             );
 
+        // Runs before projections are pushed into the sources, which cannot
+        // evaluate the functions depending on the partition of the rows
+        let rules = &mut self
+            .physical_optimizers()
+            .get_or_insert_with(PhysicalOptimizer::new)
+            .rules;
+        let index = rules
+            .iter()
+            .position(|rule| rule.name() == "ProjectionPushdown")
+            .unwrap_or(rules.len());
+        rules.insert(index, Arc::new(AddPartitionContext::new()));
+
         self
     }
 }
@@ -110,6 +126,21 @@ mod tests {
             !state.expr_planners().is_empty(),
             "Apache Spark expr planners should be registered"
         );
+
+        let rules = state
+            .physical_optimizers()
+            .iter()
+            .map(|rule| rule.name())
+            .collect::<Vec<_>>();
+        let index = rules.iter().position(|name| *name == "AddPartitionContext");
+        assert_eq!(
+            index,
+            rules
+                .iter()
+                .position(|name| *name == "ProjectionPushdown")
+                .map(|i| i - 1),
+            "AddPartitionContext should run before projections are pushed down: {rules:?}"
+        );
     }
 
     #[tokio::test]
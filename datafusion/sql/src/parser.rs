@@ -324,6 +324,29 @@ impl fmt::Display for ResetStatement {
     }
 }
 
+/// DataFusion extension for `REFRESH MATERIALIZED VIEW`
+///
+/// ```sql
+/// REFRESH MATERIALIZED VIEW <name> [FULL]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshMaterializedViewStatement {
+    /// The name of the view
+    pub name: ObjectName,
+    /// Recompute the view from all the rows of its source tables
+    pub full: bool,
+}
+
+impl fmt::Display for RefreshMaterializedViewStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "REFRESH MATERIALIZED VIEW {}", self.name)?;
+        if self.full {
+            write!(f, " FULL")?;
+        }
+        Ok(())
+    }
+}
+
 /// DataFusion SQL Statement.
 ///
 /// This can either be a [`Statement`] from [`sqlparser`] from a
@@ -343,6 +366,8 @@ pub enum Statement {
     Explain(ExplainStatement),
     /// Extension: `RESET`
     Reset(ResetStatement),
+    /// Extension: `REFRESH MATERIALIZED VIEW`
+    RefreshMaterializedView(RefreshMaterializedViewStatement),
 }
 
 impl fmt::Display for Statement {
@@ -353,6 +378,7 @@ impl fmt::Display for Statement {
             Statement::CopyTo(stmt) => write!(f, "{stmt}"),
             Statement::Explain(stmt) => write!(f, "{stmt}"),
             Statement::Reset(stmt) => write!(f, "{stmt}"),
+            Statement::RefreshMaterializedView(stmt) => write!(f, "{stmt}"),
         }
     }
 }
@@ -635,6 +661,10 @@ impl<'a> DFParser<'a> {
                         self.parser.next_token(); // RESET
                         self.parse_reset()
                     }
+                    Keyword::REFRESH => {
+                        self.parser.next_token(); // REFRESH
+                        self.parse_refresh()
+                    }
                     _ => {
                         // use sqlparser-rs parser
                         self.parse_and_handle_statement()
@@ -866,6 +896,17 @@ impl<'a> DFParser<'a> {
         }))
     }
 
+    /// Parse a SQL `REFRESH MATERIALIZED VIEW` statement
+    pub fn parse_refresh(&mut self) -> Result<Statement, DataFusionError> {
+        self.parser
+            .expect_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])?;
+        let name = self.parser.parse_object_name(true)?;
+        let full = self.parser.parse_keyword(Keyword::FULL);
+        Ok(Statement::RefreshMaterializedView(
+            RefreshMaterializedViewStatement { name, full },
+        ))
+    }
+
     /// Parse a SQL `RESET`
     pub fn parse_reset(&mut self) -> Result<Statement, DataFusionError> {
         let mut parts: Vec<String> = Vec::new();
@@ -2075,7 +2116,8 @@ mod tests {
     fn test_multistatement() {
         let sql = "COPY foo TO bar STORED AS CSV; \
              CREATE EXTERNAL TABLE t(c1 int) STORED AS CSV LOCATION 'foo.csv'; \
-             RESET var;";
+             RESET var; \
+             REFRESH MATERIALIZED VIEW mv FULL;";
         let statements = DFParser::parse_sql(sql).unwrap();
         assert_eq!(
             statements,
@@ -2097,7 +2139,11 @@ mod tests {
                 {
                     let name = ObjectName::from(vec![Ident::from("var")]);
                     Statement::Reset(ResetStatement::Variable(name))
-                }
+                },
+                Statement::RefreshMaterializedView(RefreshMaterializedViewStatement {
+                    name: ObjectName::from(vec![Ident::from("mv")]),
+                    full: true,
+                }),
             ]
         );
     }
@@ -19,7 +19,8 @@ use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
 use datafusion_common::{Column, Result, not_impl_err, plan_datafusion_err};
 use datafusion_expr::{JoinType, LogicalPlan, LogicalPlanBuilder};
 use sqlparser::ast::{
-    Join, JoinConstraint, JoinOperator, ObjectName, TableFactor, TableWithJoins,
+    Expr as SQLExpr, Join, JoinConstraint, JoinOperator, ObjectName, TableFactor,
+    TableWithJoins,
 };
 use std::collections::HashSet;
 
@@ -98,6 +99,16 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
             JoinOperator::CrossJoin(JoinConstraint::None) => {
                 self.parse_cross_join(left, right)
             }
+            JoinOperator::AsOf {
+                match_condition,
+                constraint,
+            } => self.parse_asof_join(
+                left,
+                right,
+                match_condition,
+                constraint,
+                planner_context,
+            ),
             other => not_impl_err!("Unsupported JOIN operator {other:?}"),
         }
     }
@@ -110,6 +121,34 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         LogicalPlanBuilder::from(left).cross_join(right)?.build()
     }
 
+    /// Plans `ASOF JOIN <right> MATCH_CONDITION(<expr>) [ON <expr>]`.
+    fn parse_asof_join(
+        &self,
+        left: LogicalPlan,
+        right: LogicalPlan,
+        match_condition: SQLExpr,
+        constraint: JoinConstraint,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let join_schema = left.schema().join(right.schema())?;
+        let match_condition =
+            self.sql_to_expr(match_condition, &join_schema, planner_context)?;
+        let on = match constraint {
+            JoinConstraint::On(sql_expr) => {
+                vec![self.sql_to_expr(sql_expr, &join_schema, planner_context)?]
+            }
+            JoinConstraint::None => vec![],
+            other => {
+                return not_impl_err!(
+                    "Unsupported ASOF JOIN constraint {other:?}, expected ON"
+                );
+            }
+        };
+        LogicalPlanBuilder::from(left)
+            .join_asof(right, on, match_condition)?
+            .build()
+    }
+
     fn parse_join(
         &self,
         left: LogicalPlan,
@@ -186,6 +186,9 @@ fn visit_statement(statement: &DFStatement, visitor: &mut RelationVisitor) -> Re
             visit_statement(&explain.statement, visitor)?;
         }
         DFStatement::Reset(_) => {}
+        DFStatement::RefreshMaterializedView(refresh) => {
+            control_flow_to_result(visitor.insert_relation(&refresh.name))?;
+        }
     }
     Ok(())
 }
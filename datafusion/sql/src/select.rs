@@ -32,7 +32,10 @@ use crate::utils::{
 use arrow::datatypes::DataType;
 use datafusion_common::error::DataFusionErrorBuilder;
 use datafusion_common::tree_node::{TreeNode, TreeNodeRecursion};
-use datafusion_common::{Column, DFSchema, DFSchemaRef, Result, not_impl_err, plan_err};
+use datafusion_common::{
+    Column, DFSchema, DFSchemaRef, Result, ScalarValue, TableReference, internal_err,
+    not_impl_err, plan_datafusion_err, plan_err,
+};
 use datafusion_common::{NullHandling, RecursionUnnestOption, UnnestOptions};
 use datafusion_expr::ExprSchemable;
 use datafusion_expr::builder::get_struct_unnested_columns;
@@ -41,13 +44,14 @@ use datafusion_expr::expr::{PlannedReplaceSelectItem, WildcardOptions};
 use datafusion_expr::expr_rewriter::{
     normalize_col, normalize_col_with_schemas_and_ambiguity_check, normalize_sorts,
 };
+use datafusion_expr::planner::{PlannerResult, RawFieldAccessExpr};
 use datafusion_expr::select_expr::SelectExpr;
 use datafusion_expr::utils::{
     expr_as_column_expr, expr_to_columns, find_aggregate_exprs, find_window_exprs,
 };
 use datafusion_expr::{
-    Aggregate, Expr, Filter, GroupingSet, LogicalPlan, LogicalPlanBuilder,
-    LogicalPlanBuilderOptions, Partitioning, SortExpr,
+    Aggregate, Expr, Filter, GetFieldAccess, GroupingSet, LogicalPlan,
+    LogicalPlanBuilder, LogicalPlanBuilderOptions, Partitioning, SortExpr,
 };
 
 use indexmap::IndexMap;
@@ -56,7 +60,9 @@ use sqlparser::ast::{
     SelectItemQualifiedWildcardKind, WildcardAdditionalOptions, WindowType,
     visit_expressions_mut,
 };
-use sqlparser::ast::{NamedWindowDefinition, Select, SelectItem, TableWithJoins};
+use sqlparser::ast::{
+    Ident, LateralView, NamedWindowDefinition, Select, SelectItem, TableWithJoins,
+};
 
 /// Result of the `aggregate` function, containing the aggregate plan and
 /// rewritten expressions that reference the aggregate output columns.
@@ -82,6 +88,13 @@ struct DistinctOnUnnestPlanResult {
     order_by_exprs: Vec<SortExpr>,
 }
 
+/// A planned SELECT item: generator functions expand to several columns
+#[expect(clippy::large_enum_variant)]
+enum PlannedSelectItem {
+    Expr(SelectExpr),
+    Generator(Vec<Expr>),
+}
+
 struct RewrittenUnnestExprGroups {
     plan: LogicalPlan,
     expr_groups: Vec<Vec<Expr>>,
@@ -103,10 +116,6 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         if !select.cluster_by.is_empty() {
             return not_impl_err!("CLUSTER BY");
         }
-        if !select.lateral_views.is_empty() {
-            return not_impl_err!("LATERAL VIEWS");
-        }
-
         if select.top.is_some() {
             return not_impl_err!("TOP");
         }
@@ -122,6 +131,15 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         let plan = self.plan_from_tables(select.from, planner_context)?;
         let empty_from = matches!(plan, LogicalPlan::EmptyRelation(_));
 
+        // Process `lateral view` clauses, which may be referenced by `where`
+        let plan =
+            select
+                .lateral_views
+                .into_iter()
+                .try_fold(plan, |plan, lateral_view| {
+                    self.plan_lateral_view(plan, lateral_view, planner_context)
+                })?;
+
         // Process `where` clause
         let base_plan = self.plan_selection(select.selection, plan, planner_context)?;
 
@@ -969,14 +987,25 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         planner_context: &mut PlannerContext,
     ) -> Result<Vec<SelectExpr>> {
         let mut prepared_select_exprs = vec![];
+        let mut generators = 0;
         let mut error_builder = DataFusionErrorBuilder::new();
 
         for expr in projection {
             match self.sql_select_to_rex(expr, plan, empty_from, planner_context) {
-                Ok(expr) => prepared_select_exprs.push(expr),
+                Ok(PlannedSelectItem::Expr(expr)) => prepared_select_exprs.push(expr),
+                Ok(PlannedSelectItem::Generator(exprs)) => {
+                    generators += 1;
+                    prepared_select_exprs
+                        .extend(exprs.into_iter().map(SelectExpr::Expression));
+                }
                 Err(err) => error_builder.add_error(err),
             }
         }
+        if generators > 1 {
+            error_builder.add_error(plan_datafusion_err!(
+                "Only one generator is allowed per SELECT clause, but found {generators}"
+            ));
+        }
         error_builder.error_or(prepared_select_exprs)
     }
 
@@ -987,26 +1016,39 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         plan: &LogicalPlan,
         empty_from: bool,
         planner_context: &mut PlannerContext,
-    ) -> Result<SelectExpr> {
+    ) -> Result<PlannedSelectItem> {
         match sql {
             SelectItem::UnnamedExpr(expr) => {
-                let expr = self.sql_to_expr(expr, plan.schema(), planner_context)?;
-                let col = normalize_col_with_schemas_and_ambiguity_check(
-                    expr,
-                    &[&[plan.schema()]],
-                    &plan.using_columns()?,
-                )?;
-
-                Ok(SelectExpr::Expression(col))
+                let col = self.sql_select_item_to_expr(expr, plan, planner_context)?;
+                match self.plan_generator(col, plan.schema())? {
+                    PlannerResult::Planned(generator) => {
+                        Ok(PlannedSelectItem::Generator(self.generator_columns(
+                            &generator,
+                            vec![],
+                            None,
+                            plan.schema(),
+                        )?))
+                    }
+                    PlannerResult::Original(col) => {
+                        Ok(PlannedSelectItem::Expr(SelectExpr::Expression(col)))
+                    }
+                }
             }
             SelectItem::ExprWithAlias { expr, alias } => {
-                let select_expr =
-                    self.sql_to_expr(expr, plan.schema(), planner_context)?;
-                let col = normalize_col_with_schemas_and_ambiguity_check(
-                    select_expr,
-                    &[&[plan.schema()]],
-                    &plan.using_columns()?,
-                )?;
+                let col = self.sql_select_item_to_expr(expr, plan, planner_context)?;
+                let col = match self.plan_generator(col, plan.schema())? {
+                    PlannerResult::Planned(generator) => {
+                        return Ok(PlannedSelectItem::Generator(
+                            self.generator_columns(
+                                &generator,
+                                vec![alias],
+                                None,
+                                plan.schema(),
+                            )?,
+                        ));
+                    }
+                    PlannerResult::Original(col) => col,
+                };
                 let name = self.ident_normalizer.normalize(alias);
                 // avoiding adding an alias if the column name is the same.
                 let expr = match &col {
@@ -1014,10 +1056,23 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     _ => col.alias(name),
                 };
 
-                Ok(SelectExpr::Expression(expr))
+                Ok(PlannedSelectItem::Expr(SelectExpr::Expression(expr)))
             }
-            SelectItem::ExprWithAliases { .. } => {
-                not_impl_err!("SELECT item with multiple aliases is not supported")
+            SelectItem::ExprWithAliases { expr, aliases } => {
+                let col = self.sql_select_item_to_expr(expr, plan, planner_context)?;
+                match self.plan_generator(col, plan.schema())? {
+                    PlannerResult::Planned(generator) => {
+                        Ok(PlannedSelectItem::Generator(self.generator_columns(
+                            &generator,
+                            aliases,
+                            None,
+                            plan.schema(),
+                        )?))
+                    }
+                    PlannerResult::Original(_) => not_impl_err!(
+                        "SELECT item with multiple aliases is only supported for generator functions"
+                    ),
+                }
             }
             SelectItem::Wildcard(options) => {
                 Self::check_wildcard_options(&options)?;
@@ -1031,7 +1086,9 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     options,
                 )?;
 
-                Ok(SelectExpr::Wildcard(planned_options))
+                Ok(PlannedSelectItem::Expr(SelectExpr::Wildcard(
+                    planned_options,
+                )))
             }
             SelectItem::QualifiedWildcard(object_name, options) => {
                 Self::check_wildcard_options(&options)?;
@@ -1053,9 +1110,160 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     options,
                 )?;
 
-                Ok(SelectExpr::QualifiedWildcard(qualifier, planned_options))
+                Ok(PlannedSelectItem::Expr(SelectExpr::QualifiedWildcard(
+                    qualifier,
+                    planned_options,
+                )))
+            }
+        }
+    }
+
+    /// Plans the expression of a SELECT item, resolving its columns against `plan`
+    fn sql_select_item_to_expr(
+        &self,
+        expr: SQLExpr,
+        plan: &LogicalPlan,
+        planner_context: &mut PlannerContext,
+    ) -> Result<Expr> {
+        let expr = self.sql_to_expr(expr, plan.schema(), planner_context)?;
+        normalize_col_with_schemas_and_ambiguity_check(
+            expr,
+            &[&[plan.schema()]],
+            &plan.using_columns()?,
+        )
+    }
+
+    /// Plans `expr` as a generator function, such as Spark's `explode`, using
+    /// the registered [`ExprPlanner`]s
+    ///
+    /// [`ExprPlanner`]: datafusion_expr::planner::ExprPlanner
+    fn plan_generator(
+        &self,
+        mut expr: Expr,
+        schema: &DFSchema,
+    ) -> Result<PlannerResult<Expr>> {
+        for planner in self.context_provider.get_expr_planners() {
+            match planner.plan_generator(expr, schema)? {
+                PlannerResult::Planned(generator) => {
+                    return Ok(PlannerResult::Planned(generator));
+                }
+                PlannerResult::Original(original) => expr = original,
             }
         }
+        Ok(PlannerResult::Original(expr))
+    }
+
+    /// Returns one column per field of the structs produced by a planned
+    /// generator, named after the field or after the matching entry of `aliases`
+    fn generator_columns(
+        &self,
+        generator: &Expr,
+        aliases: Vec<Ident>,
+        qualifier: Option<&TableReference>,
+        schema: &DFSchema,
+    ) -> Result<Vec<Expr>> {
+        let Expr::Unnest(UnnestExpr { expr, .. }) = generator else {
+            return internal_err!("Generator must be planned as an unnest: {generator}");
+        };
+        let fields = match expr.get_type(schema)? {
+            DataType::List(field)
+            | DataType::LargeList(field)
+            | DataType::FixedSizeList(field, _) => match field.data_type() {
+                DataType::Struct(fields) => fields.clone(),
+                other => {
+                    return internal_err!(
+                        "Generator must produce a list of structs, got list of {other}"
+                    );
+                }
+            },
+            other => {
+                return internal_err!(
+                    "Generator must produce a list of structs, got {other}"
+                );
+            }
+        };
+        if !aliases.is_empty() && aliases.len() != fields.len() {
+            return plan_err!(
+                "The number of aliases supplied in the AS clause does not match the \
+                number of columns output by the generator: expected {} aliases but got {}",
+                fields.len(),
+                aliases.len()
+            );
+        }
+
+        let mut aliases = aliases.into_iter();
+        fields
+            .iter()
+            .map(|field| {
+                let mut field_access_expr = RawFieldAccessExpr {
+                    expr: generator.clone(),
+                    field_access: GetFieldAccess::NamedStructField {
+                        name: ScalarValue::from(field.name().as_str()),
+                    },
+                };
+                let name = match aliases.next() {
+                    Some(alias) => self.ident_normalizer.normalize(alias),
+                    None => field.name().clone(),
+                };
+                for planner in self.context_provider.get_expr_planners() {
+                    match planner.plan_field_access(field_access_expr, schema)? {
+                        PlannerResult::Planned(expr) => {
+                            return Ok(expr.alias_qualified(qualifier.cloned(), name));
+                        }
+                        PlannerResult::Original(expr) => field_access_expr = expr,
+                    }
+                }
+                not_impl_err!(
+                    "GetFieldAccess not supported by ExprPlanner: {field_access_expr:?}"
+                )
+            })
+            .collect()
+    }
+
+    /// Plans a `LATERAL VIEW [OUTER] generator(...) name [AS alias, ...]`,
+    /// joining every row of `input` with the rows generated from it
+    fn plan_lateral_view(
+        &self,
+        input: LogicalPlan,
+        lateral_view: LateralView,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let LateralView {
+            lateral_view,
+            lateral_view_name,
+            lateral_col_alias,
+            outer,
+        } = lateral_view;
+        let expr = self.sql_select_item_to_expr(lateral_view, &input, planner_context)?;
+        let generator = match self.plan_generator(expr, input.schema())? {
+            PlannerResult::Planned(Expr::Unnest(unnest)) => Expr::Unnest(UnnestExpr {
+                outer: unnest.outer || outer,
+                ..unnest
+            }),
+            PlannerResult::Planned(other) => {
+                return internal_err!("Generator must be planned as an unnest: {other}");
+            }
+            PlannerResult::Original(expr) => {
+                return plan_err!(
+                    "LATERAL VIEW requires a generator function, got {expr}"
+                );
+            }
+        };
+        let qualifier = self.object_name_to_table_reference(lateral_view_name)?;
+
+        let mut select_exprs = input
+            .schema()
+            .columns()
+            .into_iter()
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+        select_exprs.extend(self.generator_columns(
+            &generator,
+            lateral_col_alias,
+            Some(&qualifier),
+            input.schema(),
+        )?);
+        self.try_process_unnest(input, select_exprs)
     }
 
     fn check_wildcard_options(options: &WildcardAdditionalOptions) -> Result<()> {
@@ -1111,7 +1319,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 .collect::<Result<Vec<_>>>()?
                 .into_iter()
                 .filter_map(|expr| match expr {
-                    SelectExpr::Expression(expr) => Some(expr),
+                    PlannedSelectItem::Expr(SelectExpr::Expression(expr)) => Some(expr),
                     _ => None,
                 })
                 .collect::<Vec<_>>();
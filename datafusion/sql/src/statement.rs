@@ -22,7 +22,8 @@ use std::sync::Arc;
 
 use crate::parser::{
     CopyToSource, CopyToStatement, CreateExternalTable, DFParser, ExplainStatement,
-    LexOrdering, ResetStatement, Statement as DFStatement,
+    LexOrdering, RefreshMaterializedViewStatement, ResetStatement,
+    Statement as DFStatement,
 };
 use crate::planner::{
     ContextProvider, PlannerContext, SqlToRel, object_name_to_qualifier,
@@ -48,13 +49,14 @@ use datafusion_expr::logical_plan::DdlStatement;
 use datafusion_expr::logical_plan::builder::project;
 use datafusion_expr::utils::expr_to_columns;
 use datafusion_expr::{
-    Analyze, CreateCatalog, CreateCatalogSchema,
-    CreateExternalTable as PlanCreateExternalTable, CreateFunction, CreateFunctionBody,
-    CreateIndex as PlanCreateIndex, CreateMemoryTable, CreateView, Deallocate,
-    DescribeTable, DmlStatement, DropCatalogSchema, DropFunction, DropTable, DropView,
-    EmptyRelation, Execute, Explain, ExplainFormat, Expr, ExprSchemable, Filter,
-    LogicalPlan, LogicalPlanBuilder, OperateFunctionArg, PlanType, Prepare,
-    ResetVariable, SetVariable, SortExpr, Statement as PlanStatement, ToStringifiedPlan,
+    AlterTable, AlterTableOperation, Analyze, AnalyzeTable, CreateCatalog,
+    CreateCatalogSchema, CreateExternalTable as PlanCreateExternalTable, CreateFunction,
+    CreateFunctionBody, CreateIndex as PlanCreateIndex, CreateMaterializedView,
+    CreateMemoryTable, CreateView, Deallocate, DescribeTable, DmlStatement,
+    DropCatalogSchema, DropFunction, DropTable, DropView, EmptyRelation, Execute,
+    Explain, ExplainFormat, Expr, ExprSchemable, Filter, LogicalPlan, LogicalPlanBuilder,
+    OperateFunctionArg, PlanType, Prepare, RefreshMaterializedView, ResetVariable,
+    SetVariable, SortExpr, Statement as PlanStatement, ToStringifiedPlan,
     TransactionAccessMode, TransactionConclusion, TransactionEnd,
     TransactionIsolationLevel, TransactionStart, Volatility, WriteOp, cast,
 };
@@ -235,6 +237,9 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 self.explain_to_plan(options, *statement)
             }
             DFStatement::Reset(statement) => self.reset_statement_to_plan(statement),
+            DFStatement::RefreshMaterializedView(statement) => {
+                self.refresh_materialized_view_to_plan(statement)
+            }
         }
     }
 
@@ -627,7 +632,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 name,
                 columns,
                 query,
-                options: CreateTableOptions::None,
+                options,
                 cluster_by,
                 comment,
                 with_no_schema_binding,
@@ -640,8 +645,8 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 name_before_not_exists,
                 copy_grants,
             }) => {
-                if materialized {
-                    return not_impl_err!("Materialized views not supported")?;
+                if !materialized && options != CreateTableOptions::None {
+                    return not_impl_err!("Options not supported for views")?;
                 }
                 if !cluster_by.is_empty() {
                     return not_impl_err!("Cluster by not supported")?;
@@ -652,7 +657,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 if with_no_schema_binding {
                     return not_impl_err!("With no schema binding not supported")?;
                 }
-                if if_not_exists {
+                if if_not_exists && !materialized {
                     return not_impl_err!("If not exists not supported")?;
                 }
                 if to.is_some() {
@@ -670,7 +675,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     name,
                     columns,
                     query,
-                    options: CreateTableOptions::None,
+                    options,
                     cluster_by,
                     comment,
                     with_no_schema_binding,
@@ -688,6 +693,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                     name,
                     columns,
                     query,
+                    options,
                     or_replace,
                     temporary,
                     ..
@@ -712,6 +718,22 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                 let mut plan = self.query_to_plan(*query, &mut PlannerContext::new())?;
                 plan = self.apply_expr_alias(plan, columns)?;
 
+                if materialized {
+                    if temporary {
+                        return not_impl_err!(
+                            "Temporary materialized views not supported"
+                        );
+                    }
+                    return self.create_materialized_view_to_plan(
+                        name,
+                        plan,
+                        options,
+                        if_not_exists,
+                        or_replace,
+                        sql,
+                    );
+                }
+
                 Ok(LogicalPlan::Ddl(DdlStatement::CreateView(CreateView {
                     name: self.object_name_to_table_reference(name)?,
                     input: Arc::new(plan),
@@ -777,7 +799,7 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
                             schema: DFSchemaRef::new(DFSchema::empty()),
                         })))
                     }
-                    ObjectType::View => {
+                    ObjectType::View | ObjectType::MaterializedView => {
                         Ok(LogicalPlan::Ddl(DdlStatement::DropView(DropView {
                             name,
                             if_exists,
@@ -1221,6 +1243,12 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
 
             Statement::Merge(merge) => self.merge_to_plan(merge),
 
+            Statement::Analyze(analyze) => self.analyze_table_to_plan(analyze),
+
+            Statement::AlterTable(alter_table) => {
+                self.alter_table_to_plan(alter_table, planner_context)
+            }
+
             Statement::StartTransaction {
                 modes,
                 begin: false,
@@ -1662,6 +1690,196 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         }))
     }
 
+    fn analyze_table_to_plan(&self, analyze: ast::Analyze) -> Result<LogicalPlan> {
+        let ast::Analyze {
+            table_name,
+            partitions,
+            for_columns: _,
+            columns,
+            cache_metadata,
+            noscan,
+            compute_statistics: _,
+            has_table_keyword: _,
+        } = analyze;
+        let Some(table_name) = table_name else {
+            return not_impl_err!("ANALYZE without a table name is not supported");
+        };
+        if partitions.is_some() {
+            return not_impl_err!("ANALYZE with PARTITION is not supported");
+        }
+        if cache_metadata {
+            return not_impl_err!("ANALYZE with CACHE METADATA is not supported");
+        }
+        if noscan {
+            return not_impl_err!("ANALYZE with NOSCAN is not supported");
+        }
+
+        let name = self.object_name_to_table_reference(table_name)?;
+        let table_schema = self
+            .context_provider
+            .get_table_source(name.clone())?
+            .schema();
+        let columns = columns
+            .into_iter()
+            .map(|ident| {
+                let column = self.ident_normalizer.normalize(ident);
+                if table_schema.field_with_name(&column).is_err() {
+                    return plan_err!("Column {column} does not exist in table {name}");
+                }
+                Ok(column)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(LogicalPlan::Ddl(DdlStatement::AnalyzeTable(AnalyzeTable {
+            name,
+            columns,
+            schema: DFSchemaRef::new(DFSchema::empty()),
+        })))
+    }
+
+    fn alter_table_to_plan(
+        &self,
+        alter_table: ast::AlterTable,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let ast::AlterTable {
+            name,
+            if_exists,
+            only: _,
+            operations,
+            location,
+            on_cluster,
+            table_type,
+            end_token: _,
+        } = alter_table;
+        if location.is_some() {
+            return not_impl_err!("ALTER TABLE with SET LOCATION is not supported");
+        }
+        if on_cluster.is_some() {
+            return not_impl_err!("ALTER TABLE with ON CLUSTER is not supported");
+        }
+        if let Some(table_type) = table_type
+            && table_type != ast::AlterTableType::External
+        {
+            return not_impl_err!("ALTER {table_type:?} TABLE is not supported");
+        }
+
+        let mut plan_operations = Vec::with_capacity(operations.len());
+        for operation in operations {
+            match operation {
+                ast::AlterTableOperation::AddColumn {
+                    column_keyword: _,
+                    if_not_exists,
+                    column_def,
+                    column_position,
+                } => {
+                    if column_position.is_some() {
+                        return not_impl_err!(
+                            "ALTER TABLE ADD COLUMN with FIRST or AFTER is not supported"
+                        );
+                    }
+                    for option in &column_def.options {
+                        if !matches!(
+                            option.option,
+                            ast::ColumnOption::Null
+                                | ast::ColumnOption::NotNull
+                                | ast::ColumnOption::Default(_)
+                        ) {
+                            return not_impl_err!(
+                                "ALTER TABLE ADD COLUMN with {} is not supported",
+                                option.option
+                            );
+                        }
+                    }
+                    let columns = vec![column_def];
+                    let default = self
+                        .build_column_defaults(&columns, planner_context)?
+                        .pop()
+                        .map(|(_, default)| default);
+                    let schema = self.build_schema(columns)?;
+                    let field = Arc::clone(&schema.fields()[0]);
+                    let default = default
+                        .map(|default| {
+                            default.cast_to(field.data_type(), &DFSchema::empty())
+                        })
+                        .transpose()?;
+                    plan_operations.push(AlterTableOperation::AddColumn {
+                        field,
+                        default,
+                        if_not_exists,
+                    });
+                }
+                ast::AlterTableOperation::DropColumn {
+                    has_column_keyword: _,
+                    column_names,
+                    if_exists,
+                    drop_behavior,
+                } => {
+                    if drop_behavior == Some(ast::DropBehavior::Cascade) {
+                        return not_impl_err!(
+                            "ALTER TABLE DROP COLUMN with CASCADE is not supported"
+                        );
+                    }
+                    plan_operations.extend(column_names.into_iter().map(|column| {
+                        AlterTableOperation::DropColumn {
+                            name: self.ident_normalizer.normalize(column),
+                            if_exists,
+                        }
+                    }));
+                }
+                ast::AlterTableOperation::RenameColumn {
+                    old_column_name,
+                    new_column_name,
+                } => plan_operations.push(AlterTableOperation::RenameColumn {
+                    old_name: self.ident_normalizer.normalize(old_column_name),
+                    new_name: self.ident_normalizer.normalize(new_column_name),
+                }),
+                ast::AlterTableOperation::RenameTable { table_name } => {
+                    let (ast::RenameTableNameKind::As(new_name)
+                    | ast::RenameTableNameKind::To(new_name)) = table_name;
+                    plan_operations.push(AlterTableOperation::RenameTable {
+                        new_name: self.object_name_to_table_reference(new_name)?,
+                    });
+                }
+                ast::AlterTableOperation::SetTblProperties {
+                    table_properties: options,
+                }
+                | ast::AlterTableOperation::SetOptionsParens { options } => {
+                    let options = options
+                        .into_iter()
+                        .map(|option| match option {
+                            ast::SqlOption::KeyValue {
+                                key,
+                                value: SQLExpr::Value(value),
+                            } => Ok((key.value, value.value)),
+                            option => {
+                                not_impl_err!("Unsupported ALTER TABLE option {option}")
+                            }
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    let mut options = self
+                        .parse_options_map(options, false)?
+                        .into_iter()
+                        .collect::<Vec<_>>();
+                    options.sort();
+                    plan_operations.push(AlterTableOperation::SetOptions { options });
+                }
+                operation => {
+                    return not_impl_err!(
+                        "Unsupported ALTER TABLE operation {operation}"
+                    );
+                }
+            }
+        }
+
+        Ok(LogicalPlan::Ddl(DdlStatement::AlterTable(AlterTable {
+            name: self.object_name_to_table_reference(name)?,
+            if_exists,
+            operations: plan_operations,
+            schema: DFSchemaRef::new(DFSchema::empty()),
+        })))
+    }
+
     fn describe_query_to_plan(&self, query: Query) -> Result<LogicalPlan> {
         let plan = self.query_to_plan(query, &mut PlannerContext::new())?;
 
@@ -2230,6 +2448,96 @@ impl<S: ContextProvider> SqlToRel<'_, S> {
         }
     }
 
+    fn create_materialized_view_to_plan(
+        &self,
+        name: ObjectName,
+        input: LogicalPlan,
+        options: CreateTableOptions,
+        if_not_exists: bool,
+        or_replace: bool,
+        definition: String,
+    ) -> Result<LogicalPlan> {
+        if if_not_exists && or_replace {
+            return plan_err!("'IF NOT EXISTS' cannot coexist with 'REPLACE'");
+        }
+        let options = match options {
+            CreateTableOptions::None => vec![],
+            CreateTableOptions::With(options) => options,
+            options => {
+                return not_impl_err!("Unsupported materialized view options {options}");
+            }
+        };
+
+        // The location and format of the stored results are options as well,
+        // the other options are options of the file format
+        let mut location = None;
+        let mut file_type = None;
+        let mut format_options = vec![];
+        for option in options {
+            let (key, value) = match option {
+                ast::SqlOption::KeyValue {
+                    key,
+                    value: SQLExpr::Value(value),
+                } => (key.value, value.value),
+                option => {
+                    return not_impl_err!(
+                        "Unsupported materialized view option {option}"
+                    );
+                }
+            };
+            match key.to_lowercase().as_str() {
+                "location" | "format" => {
+                    let Some(value) = crate::utils::value_to_string(&value) else {
+                        return plan_err!("Unsupported Value {value}");
+                    };
+                    if key.eq_ignore_ascii_case("location") {
+                        location = Some(value);
+                    } else {
+                        file_type = Some(value.to_uppercase());
+                    }
+                }
+                _ => format_options.push((key, value)),
+            }
+        }
+        let mut options = self
+            .parse_options_map(format_options, false)?
+            .into_iter()
+            .collect::<Vec<_>>();
+        options.sort();
+        if location.is_none() && (file_type.is_some() || !options.is_empty()) {
+            return plan_err!(
+                "The format and format options of a materialized view require a location"
+            );
+        }
+
+        Ok(LogicalPlan::Ddl(DdlStatement::CreateMaterializedView(
+            CreateMaterializedView {
+                name: self.object_name_to_table_reference(name)?,
+                input: Arc::new(input),
+                if_not_exists,
+                or_replace,
+                location,
+                file_type: file_type.unwrap_or_else(|| "PARQUET".to_string()),
+                options,
+                definition: Some(definition),
+            },
+        )))
+    }
+
+    fn refresh_materialized_view_to_plan(
+        &self,
+        statement: RefreshMaterializedViewStatement,
+    ) -> Result<LogicalPlan> {
+        let RefreshMaterializedViewStatement { name, full } = statement;
+        Ok(LogicalPlan::Ddl(DdlStatement::RefreshMaterializedView(
+            RefreshMaterializedView {
+                name: self.object_name_to_table_reference(name)?,
+                full,
+                schema: DFSchemaRef::new(DFSchema::empty()),
+            },
+        )))
+    }
+
     fn reset_statement_to_plan(&self, statement: ResetStatement) -> Result<LogicalPlan> {
         match statement {
             ResetStatement::Variable(variable) => {
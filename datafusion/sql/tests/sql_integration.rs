@@ -4936,9 +4936,9 @@ fn test_select_qualify_complex_condition() {
     "SELECT customer_name, sum(order_total) as total_order_amount FROM orders CLUSTER BY customer_name",
     "This feature is not implemented: CLUSTER BY"
 )]
-#[case::select_lateral_view_unsupported(
-    "SELECT id, number FROM person LATERAL VIEW explode(numbers) exploded_table AS number",
-    "This feature is not implemented: LATERAL VIEWS"
+#[case::select_lateral_view_without_generator(
+    "SELECT id, number FROM person LATERAL VIEW (age + 1) exploded_table AS number",
+    "Error during planning: LATERAL VIEW requires a generator function, got person.age + Int64(1)"
 )]
 #[case::select_top_unsupported(
     "SELECT TOP (5) * FROM person",
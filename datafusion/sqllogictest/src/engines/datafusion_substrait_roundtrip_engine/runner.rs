@@ -24,7 +24,8 @@ use crate::engines::output::{DFColumnType, DFOutput};
 use crate::{DFSqlLogicTestError, convert_batches, convert_schema_to_types};
 use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
-use datafusion::logical_expr::LogicalPlan;
+use datafusion::logical_expr::dml::InsertOp;
+use datafusion::logical_expr::{DdlStatement, EmptyRelation, Expr, LogicalPlan, WriteOp};
 use datafusion::physical_plan::common::collect;
 use datafusion::physical_plan::execute_stream;
 use datafusion::prelude::SessionContext;
@@ -138,30 +139,75 @@ impl sqllogictest::AsyncDB for DataFusionSubstraitRoundTrip {
     async fn shutdown(&mut self) {}
 }
 
+/// Returns true for the DDL and DML statements that Substrait cannot express
+fn is_unsupported_statement(plan: &LogicalPlan) -> bool {
+    match plan {
+        LogicalPlan::Ddl(DdlStatement::CreateMemoryTable(create)) => {
+            // Column defaults are only expressed as literals of an empty table
+            let empty_table = matches!(
+                create.input.as_ref(),
+                LogicalPlan::EmptyRelation(EmptyRelation {
+                    produce_one_row: false,
+                    ..
+                })
+            ) && !create.if_not_exists;
+            create.temporary
+                || !create.constraints.is_empty()
+                || create.column_defaults.iter().any(|(_, default)| {
+                    !empty_table || !matches!(default, Expr::Literal(..))
+                })
+        }
+        LogicalPlan::Ddl(
+            DdlStatement::CreateExternalTable(_)
+            | DdlStatement::CreateCatalogSchema(_)
+            | DdlStatement::CreateCatalog(_)
+            | DdlStatement::CreateIndex(_)
+            | DdlStatement::DropCatalogSchema(_)
+            | DdlStatement::CreateFunction(_)
+            | DdlStatement::DropFunction(_)
+            | DdlStatement::AnalyzeTable(_)
+            | DdlStatement::AlterTable(_)
+            | DdlStatement::CreateMaterializedView(_)
+            | DdlStatement::RefreshMaterializedView(_),
+        ) => true,
+        LogicalPlan::Dml(dml) => matches!(
+            dml.op,
+            WriteOp::Insert(InsertOp::Overwrite | InsertOp::Replace)
+                | WriteOp::Truncate
+                | WriteOp::MergeInto(_)
+        ),
+        _ => false,
+    }
+}
+
 async fn run_query_substrait_round_trip(
     ctx: &SessionContext,
     sql: impl Into<String>,
 ) -> Result<DFOutput> {
-    let df = ctx.sql(sql.into().as_str()).await?;
-    let task_ctx = Arc::new(df.task_ctx());
-
+    // DDL statements run as soon as a DataFrame is created for them, so plan
+    // the statement first and only execute it once it has been round tripped
     let state = ctx.state();
-    let round_tripped_plan = match df.logical_plan() {
+    let logical_plan = state.create_logical_plan(sql.into().as_str()).await?;
+    let round_tripped_plan = match logical_plan {
         // Substrait does not handle these plans
-        LogicalPlan::Ddl(_)
-        | LogicalPlan::Explain(_)
-        | LogicalPlan::Dml(_)
-        | LogicalPlan::Copy(_)
+        LogicalPlan::Explain(_)
         | LogicalPlan::DescribeTable(_)
-        | LogicalPlan::Statement(_) => df.logical_plan().clone(),
+        | LogicalPlan::Statement(_)
+        // Substrait does not carry the SQL definition of a view
+        | LogicalPlan::Ddl(DdlStatement::CreateView(_)) => logical_plan,
+        // Statements Substrait cannot express run as planned
+        logical_plan if is_unsupported_statement(&logical_plan) => logical_plan,
         // For any other plan, convert to Substrait
         logical_plan => {
-            let plan = to_substrait_plan(logical_plan, &state)?;
+            let plan = to_substrait_plan(&logical_plan, &state)?;
             from_substrait_plan(&state, &plan).await?
         }
     };
 
-    let physical_plan = state.create_physical_plan(&round_tripped_plan).await?;
+    let df = ctx.execute_logical_plan(round_tripped_plan).await?;
+    let task_ctx = Arc::new(df.task_ctx());
+    let state = ctx.state();
+    let physical_plan = state.create_physical_plan(df.logical_plan()).await?;
     let schema = physical_plan.schema();
     let stream = execute_stream(physical_plan, task_ctx)?;
     let types = convert_schema_to_types(stream.schema().fields());
@@ -0,0 +1,106 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::logical_plan::consumer::{
+    SubstraitConsumer, from_substrait_literal_without_names, from_substrait_named_struct,
+    from_substrait_table_names,
+};
+use datafusion::common::{
+    Constraints, DFSchema, DFSchemaRef, not_impl_err, substrait_err,
+};
+use datafusion::logical_expr::{
+    CreateMemoryTable, CreateView, DdlStatement, DropTable, DropView, EmptyRelation,
+    Expr, LogicalPlan,
+};
+use std::sync::Arc;
+use substrait::proto::DdlRel;
+use substrait::proto::ddl_rel::{DdlObject, DdlOp, WriteType};
+
+pub async fn from_ddl_rel(
+    consumer: &impl SubstraitConsumer,
+    ddl: &DdlRel,
+) -> datafusion::common::Result<LogicalPlan> {
+    let Some(WriteType::NamedObject(object)) = &ddl.write_type else {
+        return not_impl_err!("Unsupported DdlRel object: {:?}", ddl.write_type);
+    };
+    let name = from_substrait_table_names(&object.names)?;
+
+    let ddl = match (ddl.object(), ddl.op()) {
+        (DdlObject::Table, op @ (DdlOp::Create | DdlOp::CreateOrReplace)) => {
+            let Some(table_schema) = ddl.table_schema.as_ref() else {
+                return substrait_err!("DdlRel creating a table must have a schema");
+            };
+            let schema =
+                DFSchemaRef::new(from_substrait_named_struct(consumer, table_schema)?);
+            // Columns without a default have a NULL default
+            let mut column_defaults = vec![];
+            if let Some(table_defaults) = ddl.table_defaults.as_ref() {
+                for (field, default) in schema.fields().iter().zip(&table_defaults.fields)
+                {
+                    let value = from_substrait_literal_without_names(consumer, default)?;
+                    if !value.is_null() {
+                        column_defaults
+                            .push((field.name().clone(), Expr::Literal(value, None)));
+                    }
+                }
+            }
+            DdlStatement::CreateMemoryTable(CreateMemoryTable {
+                name,
+                constraints: Constraints::default(),
+                input: Arc::new(LogicalPlan::EmptyRelation(EmptyRelation {
+                    produce_one_row: false,
+                    schema,
+                })),
+                if_not_exists: false,
+                or_replace: op == DdlOp::CreateOrReplace,
+                column_defaults,
+                temporary: false,
+            })
+        }
+        (DdlObject::View, op @ (DdlOp::Create | DdlOp::CreateOrReplace)) => {
+            let Some(view_definition) = ddl.view_definition.as_ref() else {
+                return substrait_err!("DdlRel creating a view must have a definition");
+            };
+            let input = consumer.consume_rel(view_definition).await?;
+            DdlStatement::CreateView(CreateView {
+                name,
+                input: Arc::new(input),
+                or_replace: op == DdlOp::CreateOrReplace,
+                definition: None,
+                temporary: false,
+            })
+        }
+        (DdlObject::Table, op @ (DdlOp::Drop | DdlOp::DropIfExist)) => {
+            DdlStatement::DropTable(DropTable {
+                name,
+                if_exists: op == DdlOp::DropIfExist,
+                schema: DFSchemaRef::new(DFSchema::empty()),
+            })
+        }
+        (DdlObject::View, op @ (DdlOp::Drop | DdlOp::DropIfExist)) => {
+            DdlStatement::DropView(DropView {
+                name,
+                if_exists: op == DdlOp::DropIfExist,
+                schema: DFSchemaRef::new(DFSchema::empty()),
+            })
+        }
+        (object, op) => {
+            return not_impl_err!("Unsupported DdlRel operation {op:?} on {object:?}");
+        }
+    };
+    Ok(LogicalPlan::Ddl(ddl))
+}
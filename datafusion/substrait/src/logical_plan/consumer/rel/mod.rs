@@ -17,6 +17,7 @@
 
 mod aggregate_rel;
 mod cross_rel;
+mod ddl_rel;
 mod exchange_rel;
 mod fetch_rel;
 mod filter_rel;
@@ -25,9 +26,11 @@ mod project_rel;
 mod read_rel;
 mod set_rel;
 mod sort_rel;
+mod write_rel;
 
 pub use aggregate_rel::*;
 pub use cross_rel::*;
+pub use ddl_rel::*;
 pub use exchange_rel::*;
 pub use fetch_rel::*;
 pub use filter_rel::*;
@@ -36,6 +39,7 @@ pub use project_rel::*;
 pub use read_rel::*;
 pub use set_rel::*;
 pub use sort_rel::*;
+pub use write_rel::*;
 
 use crate::logical_plan::consumer::SubstraitConsumer;
 use crate::logical_plan::consumer::utils::NameTracker;
@@ -72,6 +76,9 @@ pub async fn from_substrait_rel(
                 consumer.consume_consistent_partition_window(rel).await
             }
             RelType::Exchange(rel) => consumer.consume_exchange(rel).await,
+            RelType::Write(rel) => consumer.consume_write(rel).await,
+            RelType::Ddl(rel) => consumer.consume_ddl(rel).await,
+            RelType::Update(rel) => consumer.consume_update(rel).await,
             rt => not_impl_err!("{rt:?} rel not supported yet"),
         },
         None => return substrait_err!("rel must set rel_type"),
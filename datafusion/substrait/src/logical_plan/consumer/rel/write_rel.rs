@@ -0,0 +1,214 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::logical_plan::consumer::{SubstraitConsumer, from_substrait_table_names};
+use datafusion::common::{
+    Column, Constraints, TableReference, not_impl_err, plan_err,
+    substrait_datafusion_err, substrait_err,
+};
+use datafusion::datasource::provider_as_source;
+use datafusion::logical_expr::dml::{CopyTo, InsertOp};
+use datafusion::logical_expr::{
+    CreateMemoryTable, DdlStatement, DmlStatement, Expr, LogicalPlan, LogicalPlanBuilder,
+    TableSource, WriteOp,
+};
+use pbjson_types::{Struct, Value, value::Kind};
+use prost::Message;
+use std::sync::Arc;
+use substrait::proto::update_rel::UpdateType;
+use substrait::proto::write_rel::{self, CreateMode, WriteType};
+use substrait::proto::{ExtensionObject, UpdateRel, WriteRel};
+
+pub async fn from_write_rel(
+    consumer: &impl SubstraitConsumer,
+    write: &WriteRel,
+) -> datafusion::common::Result<LogicalPlan> {
+    let Some(input) = write.input.as_ref() else {
+        return substrait_err!("WriteRel must have an input");
+    };
+    let input = Arc::new(consumer.consume_rel(input).await?);
+
+    let table = match &write.write_type {
+        Some(WriteType::NamedTable(table)) => from_substrait_table_names(&table.names)?,
+        Some(WriteType::ExtensionTable(table)) => {
+            return from_copy_to(consumer, table, input);
+        }
+        None => return substrait_err!("WriteRel must specify a table"),
+    };
+    let op = match write.op() {
+        write_rel::WriteOp::Insert => WriteOp::Insert(InsertOp::Append),
+        write_rel::WriteOp::Delete => WriteOp::Delete,
+        write_rel::WriteOp::Update => WriteOp::Update,
+        write_rel::WriteOp::Ctas => {
+            let (if_not_exists, or_replace) = match write.create_mode() {
+                CreateMode::Unspecified | CreateMode::ErrorIfExists => (false, false),
+                CreateMode::IgnoreIfExists => (true, false),
+                CreateMode::ReplaceIfExists => (false, true),
+                CreateMode::AppendIfExists => {
+                    return not_impl_err!(
+                        "Unsupported create mode: {:?}",
+                        write.create_mode()
+                    );
+                }
+            };
+            // The table does not exist yet, so a CTAS creates it from its input
+            return Ok(LogicalPlan::Ddl(DdlStatement::CreateMemoryTable(
+                CreateMemoryTable {
+                    name: table,
+                    constraints: Constraints::default(),
+                    input,
+                    if_not_exists,
+                    or_replace,
+                    column_defaults: vec![],
+                    temporary: false,
+                },
+            )));
+        }
+        write_rel::WriteOp::Unspecified => {
+            return substrait_err!("WriteRel must specify an operation");
+        }
+    };
+
+    let target = resolve_table_source(consumer, &table).await?;
+    Ok(LogicalPlan::Dml(DmlStatement::new(
+        table, target, op, input,
+    )))
+}
+
+pub async fn from_update_rel(
+    consumer: &impl SubstraitConsumer,
+    update: &UpdateRel,
+) -> datafusion::common::Result<LogicalPlan> {
+    let Some(UpdateType::NamedTable(table)) = &update.update_type else {
+        return substrait_err!("UpdateRel must specify a table");
+    };
+    let table = from_substrait_table_names(&table.names)?;
+    let target = resolve_table_source(consumer, &table).await?;
+
+    // Plan the update like the SQL planner does: the projection of all the
+    // columns of the (filtered) table, with the updated ones transformed
+    let scan = LogicalPlanBuilder::scan(table.clone(), Arc::clone(&target), None)?;
+    let schema = Arc::clone(scan.schema());
+    let source = match &update.condition {
+        Some(condition) => {
+            let predicate = consumer.consume_expression(condition, &schema).await?;
+            scan.filter(predicate)?
+        }
+        None => scan,
+    };
+    let mut exprs: Vec<Expr> = schema
+        .iter()
+        .map(|(qualifier, field)| Expr::Column(Column::from((qualifier, field))))
+        .collect();
+    for transformation in &update.transformations {
+        let Some(expr) = transformation.transformation.as_ref() else {
+            return substrait_err!("UpdateRel transformation must have an expression");
+        };
+        let expr = consumer.consume_expression(expr, &schema).await?;
+        match exprs.get_mut(transformation.column_target as usize) {
+            Some(target) => *target = expr,
+            None => {
+                return substrait_err!(
+                    "Invalid column target {} in UpdateRel",
+                    transformation.column_target
+                );
+            }
+        }
+    }
+    let exprs = exprs
+        .into_iter()
+        .zip(schema.fields())
+        .map(|(expr, field)| expr.alias(field.name()));
+    let input = source.project(exprs)?.build()?;
+
+    Ok(LogicalPlan::Dml(DmlStatement::new(
+        table,
+        target,
+        WriteOp::Update,
+        Arc::new(input),
+    )))
+}
+
+/// Consumes the COPY TO written as the insert into an extension table, see
+/// [crate::logical_plan::producer::from_copy_to]
+fn from_copy_to(
+    consumer: &impl SubstraitConsumer,
+    table: &ExtensionObject,
+    input: Arc<LogicalPlan>,
+) -> datafusion::common::Result<LogicalPlan> {
+    let Some(detail) = table.detail.as_ref() else {
+        return substrait_err!("Unexpected empty detail in the extension table");
+    };
+    if detail.type_url != "google.protobuf.Struct" {
+        return not_impl_err!("Unsupported extension table: {}", detail.type_url);
+    }
+    let detail = Struct::decode(detail.value.clone()).map_err(|err| {
+        substrait_datafusion_err!("Failed to decode the extension table: {err}")
+    })?;
+
+    let field = |name: &str| {
+        detail
+            .fields
+            .get(name)
+            .and_then(|value| value.kind.as_ref())
+            .ok_or_else(|| substrait_datafusion_err!("Missing {name} of COPY TO"))
+    };
+    let string = |value: &Value| match &value.kind {
+        Some(Kind::StringValue(value)) => Ok(value.clone()),
+        kind => substrait_err!("Expected a string in COPY TO, found {kind:?}"),
+    };
+    let Kind::StringValue(output_url) = field("output_url")? else {
+        return substrait_err!("COPY TO output_url must be a string");
+    };
+    let Kind::StringValue(format) = field("format")? else {
+        return substrait_err!("COPY TO format must be a string");
+    };
+    let Kind::ListValue(partition_by) = field("partition_by")? else {
+        return substrait_err!("COPY TO partition_by must be a list");
+    };
+    let Kind::StructValue(options) = field("options")? else {
+        return substrait_err!("COPY TO options must be a struct");
+    };
+    let partition_by = partition_by
+        .values
+        .iter()
+        .map(string)
+        .collect::<datafusion::common::Result<_>>()?;
+    let options = options
+        .fields
+        .iter()
+        .map(|(key, value)| Ok((key.clone(), string(value)?)))
+        .collect::<datafusion::common::Result<_>>()?;
+
+    Ok(LogicalPlan::Copy(CopyTo::new(
+        input,
+        output_url.clone(),
+        partition_by,
+        consumer.resolve_file_type(format)?,
+        options,
+    )))
+}
+
+async fn resolve_table_source(
+    consumer: &impl SubstraitConsumer,
+    table: &TableReference,
+) -> datafusion::common::Result<Arc<dyn TableSource>> {
+    match consumer.resolve_table_ref(table).await? {
+        Some(provider) => Ok(provider_as_source(provider)),
+        None => plan_err!("No table named '{table}'"),
+    }
+}
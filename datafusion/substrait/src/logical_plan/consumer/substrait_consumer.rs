@@ -16,11 +16,12 @@
 // under the License.
 
 use super::{
-    from_aggregate_rel, from_cast, from_cross_rel, from_exchange_rel, from_fetch_rel,
-    from_field_reference, from_filter_rel, from_if_then, from_join_rel, from_literal,
-    from_nested, from_project_rel, from_read_rel, from_scalar_function, from_set_rel,
-    from_singular_or_list, from_sort_rel, from_subquery, from_substrait_rel,
-    from_substrait_rex, from_window_function,
+    from_aggregate_rel, from_cast, from_cross_rel, from_ddl_rel, from_exchange_rel,
+    from_fetch_rel, from_field_reference, from_filter_rel, from_if_then, from_join_rel,
+    from_literal, from_nested, from_project_rel, from_read_rel, from_scalar_function,
+    from_set_rel, from_singular_or_list, from_sort_rel, from_subquery,
+    from_substrait_rel, from_substrait_rex, from_update_rel, from_window_function,
+    from_write_rel,
 };
 use crate::extensions::Extensions;
 use crate::logical_plan::consumer::{
@@ -30,9 +31,11 @@ use async_trait::async_trait;
 use datafusion::arrow::datatypes::{DataType, FieldRef};
 use datafusion::catalog::TableProvider;
 use datafusion::common::datatype::FieldExt;
+use datafusion::common::file_options::file_type::FileType;
 use datafusion::common::{
-    DFSchema, ScalarValue, TableReference, not_impl_err, substrait_err,
+    DFSchema, ScalarValue, TableReference, not_impl_err, plan_err, substrait_err,
 };
+use datafusion::datasource::file_format::format_as_file_type;
 use datafusion::execution::{FunctionRegistry, SessionState};
 use datafusion::logical_expr::expr::LambdaVariable;
 use datafusion::logical_expr::{Expr, Extension, LogicalPlan};
@@ -45,9 +48,10 @@ use substrait::proto::expression::{
 };
 use substrait::proto::{self, Type};
 use substrait::proto::{
-    AggregateRel, ConsistentPartitionWindowRel, CrossRel, DynamicParameter, ExchangeRel,
-    Expression, ExtensionLeafRel, ExtensionMultiRel, ExtensionSingleRel, FetchRel,
-    FilterRel, JoinRel, ProjectRel, ReadRel, Rel, SetRel, SortRel, r#type,
+    AggregateRel, ConsistentPartitionWindowRel, CrossRel, DdlRel, DynamicParameter,
+    ExchangeRel, Expression, ExtensionLeafRel, ExtensionMultiRel, ExtensionSingleRel,
+    FetchRel, FilterRel, JoinRel, ProjectRel, ReadRel, Rel, SetRel, SortRel, UpdateRel,
+    WriteRel, r#type,
 };
 
 #[async_trait]
@@ -289,6 +293,24 @@ pub trait SubstraitConsumer: Send + Sync + Sized {
         from_exchange_rel(self, rel).await
     }
 
+    async fn consume_write(
+        &self,
+        rel: &WriteRel,
+    ) -> datafusion::common::Result<LogicalPlan> {
+        from_write_rel(self, rel).await
+    }
+
+    async fn consume_ddl(&self, rel: &DdlRel) -> datafusion::common::Result<LogicalPlan> {
+        from_ddl_rel(self, rel).await
+    }
+
+    async fn consume_update(
+        &self,
+        rel: &UpdateRel,
+    ) -> datafusion::common::Result<LogicalPlan> {
+        from_update_rel(self, rel).await
+    }
+
     // Expression Methods
     // There is one method per Substrait expression to allow for easy overriding of consumer behaviour
     // These methods have default implementations calling the common handler code, to allow for users
@@ -488,6 +510,14 @@ pub trait SubstraitConsumer: Send + Sync + Sized {
         substrait_err!("Missing handler for ExtensionMultiRel")
     }
 
+    /// Resolves the [FileType] written by a COPY TO from its file extension
+    fn resolve_file_type(
+        &self,
+        extension: &str,
+    ) -> datafusion::common::Result<Arc<dyn FileType>> {
+        not_impl_err!("Missing handler for file type: {extension}")
+    }
+
     // Users can bring their own types to Substrait which require custom handling
 
     fn consume_user_defined_type(
@@ -593,6 +623,16 @@ impl SubstraitConsumer for DefaultSubstraitConsumer<'_> {
         self.state
     }
 
+    fn resolve_file_type(
+        &self,
+        extension: &str,
+    ) -> datafusion::common::Result<Arc<dyn FileType>> {
+        match self.state.get_file_format_factory(extension) {
+            Some(factory) => Ok(format_as_file_type(factory)),
+            None => plan_err!("No file format registered for '{extension}'"),
+        }
+    }
+
     fn push_outer_schema(&self, schema: Arc<DFSchema>) {
         self.outer_schemas.write().unwrap().push(schema);
     }
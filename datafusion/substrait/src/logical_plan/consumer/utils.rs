@@ -18,7 +18,7 @@
 use crate::logical_plan::consumer::SubstraitConsumer;
 use datafusion::arrow::datatypes::{DataType, Field, Schema, TimeUnit, UnionFields};
 use datafusion::common::{
-    DFSchema, DFSchemaRef, TableReference, exec_err, not_impl_err,
+    DFSchema, DFSchemaRef, TableReference, exec_err, not_impl_err, plan_err,
     substrait_datafusion_err, substrait_err,
 };
 use datafusion::logical_expr::expr::Sort;
@@ -563,6 +563,27 @@ pub(crate) fn from_substrait_precision(
     }
 }
 
+/// Converts the names of a Substrait named table or object into a [TableReference]
+pub(crate) fn from_substrait_table_names(
+    names: &[String],
+) -> datafusion::common::Result<TableReference> {
+    match names {
+        [] => plan_err!("No table name found in NamedTable"),
+        [table] => Ok(TableReference::Bare {
+            table: table.clone().into(),
+        }),
+        [schema, table] => Ok(TableReference::Partial {
+            schema: schema.clone().into(),
+            table: table.clone().into(),
+        }),
+        [catalog, schema, table, ..] => Ok(TableReference::Full {
+            catalog: catalog.clone().into(),
+            schema: schema.clone().into(),
+            table: table.clone().into(),
+        }),
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use super::{NameTracker, ensure_schema_compatibility, make_renamed_schema};
@@ -0,0 +1,199 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use crate::logical_plan::producer::{
+    SubstraitProducer, to_substrait_literal, to_substrait_named_struct,
+};
+use datafusion::common::{ScalarValue, TableReference, not_impl_err};
+use datafusion::logical_expr::{
+    CreateMemoryTable, DdlStatement, EmptyRelation, Expr, LogicalPlan,
+};
+use substrait::proto::ddl_rel::{DdlObject, DdlOp, WriteType};
+use substrait::proto::expression::literal::Struct;
+use substrait::proto::rel::RelType;
+use substrait::proto::write_rel::{self, CreateMode, OutputMode};
+use substrait::proto::{DdlRel, NamedObjectWrite, NamedStruct, Rel, WriteRel};
+
+pub fn from_ddl(
+    producer: &mut impl SubstraitProducer,
+    ddl: &DdlStatement,
+) -> datafusion::common::Result<Box<Rel>> {
+    match ddl {
+        DdlStatement::CreateMemoryTable(create) => {
+            from_create_memory_table(producer, create)
+        }
+        DdlStatement::CreateView(create) => {
+            if create.temporary {
+                return not_impl_err!("Temporary views are not supported");
+            }
+            let table_schema =
+                to_substrait_named_struct(producer, create.input.schema())?;
+            let view_definition = producer.handle_plan(create.input.as_ref())?;
+            let op = if create.or_replace {
+                DdlOp::CreateOrReplace
+            } else {
+                DdlOp::Create
+            };
+            Ok(ddl_rel(
+                &create.name,
+                Some(table_schema),
+                None,
+                DdlObject::View,
+                op,
+                Some(view_definition),
+            ))
+        }
+        DdlStatement::DropTable(drop) => Ok(ddl_rel(
+            &drop.name,
+            None,
+            None,
+            DdlObject::Table,
+            drop_op(drop.if_exists),
+            None,
+        )),
+        DdlStatement::DropView(drop) => Ok(ddl_rel(
+            &drop.name,
+            None,
+            None,
+            DdlObject::View,
+            drop_op(drop.if_exists),
+            None,
+        )),
+        ddl => not_impl_err!("Unsupported DDL statement: {}", ddl.name()),
+    }
+}
+
+/// A CREATE TABLE without rows is produced as a [DdlRel], with the defaults of
+/// the columns as its `table_defaults`. Otherwise, and for `IF NOT EXISTS`
+/// which has no DDL operation, it is produced as a CTAS [WriteRel].
+fn from_create_memory_table(
+    producer: &mut impl SubstraitProducer,
+    create: &CreateMemoryTable,
+) -> datafusion::common::Result<Box<Rel>> {
+    if create.temporary {
+        return not_impl_err!("Temporary tables are not supported");
+    }
+    if !create.constraints.is_empty() {
+        return not_impl_err!("Table constraints are not supported");
+    }
+    let table_schema = to_substrait_named_struct(producer, create.input.schema())?;
+
+    match create.input.as_ref() {
+        LogicalPlan::EmptyRelation(EmptyRelation {
+            produce_one_row: false,
+            schema,
+        }) if !create.if_not_exists => {
+            let mut fields = Vec::with_capacity(schema.fields().len());
+            for field in schema.fields() {
+                let default = create
+                    .column_defaults
+                    .iter()
+                    .find(|(name, _)| name == field.name())
+                    .map(|(_, default)| default);
+                // Columns without a default have a NULL default
+                let value = match default {
+                    Some(Expr::Literal(value, _)) => value.clone(),
+                    Some(default) => {
+                        return not_impl_err!(
+                            "Unsupported default of column {}: {default}",
+                            field.name()
+                        );
+                    }
+                    None => ScalarValue::try_from(field.data_type())?,
+                };
+                fields.push(to_substrait_literal(producer, &value)?);
+            }
+            let op = if create.or_replace {
+                DdlOp::CreateOrReplace
+            } else {
+                DdlOp::Create
+            };
+            Ok(ddl_rel(
+                &create.name,
+                Some(table_schema),
+                Some(Struct { fields }),
+                DdlObject::Table,
+                op,
+                None,
+            ))
+        }
+        input => {
+            if !create.column_defaults.is_empty() {
+                return not_impl_err!(
+                    "Column defaults are not supported in CREATE TABLE AS"
+                );
+            }
+            let create_mode = if create.or_replace {
+                CreateMode::ReplaceIfExists
+            } else if create.if_not_exists {
+                CreateMode::IgnoreIfExists
+            } else {
+                CreateMode::ErrorIfExists
+            };
+            let input = producer.handle_plan(input)?;
+            Ok(Box::new(Rel {
+                rel_type: Some(RelType::Write(Box::new(WriteRel {
+                    write_type: Some(write_rel::WriteType::NamedTable(
+                        NamedObjectWrite {
+                            names: create.name.to_vec(),
+                            advanced_extension: None,
+                        },
+                    )),
+                    table_schema: Some(table_schema),
+                    op: write_rel::WriteOp::Ctas as i32,
+                    input: Some(input),
+                    create_mode: create_mode as i32,
+                    output: OutputMode::Unspecified as i32,
+                    common: None,
+                    advanced_extension: None,
+                }))),
+            }))
+        }
+    }
+}
+
+fn drop_op(if_exists: bool) -> DdlOp {
+    if if_exists {
+        DdlOp::DropIfExist
+    } else {
+        DdlOp::Drop
+    }
+}
+
+fn ddl_rel(
+    name: &TableReference,
+    table_schema: Option<NamedStruct>,
+    table_defaults: Option<Struct>,
+    object: DdlObject,
+    op: DdlOp,
+    view_definition: Option<Box<Rel>>,
+) -> Box<Rel> {
+    Box::new(Rel {
+        rel_type: Some(RelType::Ddl(Box::new(DdlRel {
+            write_type: Some(WriteType::NamedObject(NamedObjectWrite {
+                names: name.to_vec(),
+                advanced_extension: None,
+            })),
+            table_schema,
+            table_defaults,
+            object: object as i32,
+            op: op as i32,
+            view_definition,
+            common: None,
+            advanced_extension: None,
+        }))),
+    })
+}
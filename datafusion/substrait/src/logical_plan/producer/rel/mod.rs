@@ -16,6 +16,7 @@
 // under the License.
 
 mod aggregate_rel;
+mod ddl_rel;
 mod exchange_rel;
 mod fetch_rel;
 mod filter_rel;
@@ -24,8 +25,10 @@ mod project_rel;
 mod read_rel;
 mod set_rel;
 mod sort_rel;
+mod write_rel;
 
 pub use aggregate_rel::*;
+pub use ddl_rel::*;
 pub use exchange_rel::*;
 pub use fetch_rel::*;
 pub use filter_rel::*;
@@ -34,6 +37,7 @@ pub use project_rel::*;
 pub use read_rel::*;
 pub use set_rel::*;
 pub use sort_rel::*;
+pub use write_rel::*;
 
 use crate::logical_plan::producer::SubstraitProducer;
 use datafusion::common::not_impl_err;
@@ -51,6 +55,7 @@ pub fn to_substrait_rel(
         LogicalPlan::Aggregate(plan) => producer.handle_aggregate(plan),
         LogicalPlan::Sort(plan) => producer.handle_sort(plan),
         LogicalPlan::Join(plan) => producer.handle_join(plan),
+        LogicalPlan::AsOfJoin(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
         LogicalPlan::Repartition(plan) => producer.handle_repartition(plan),
         LogicalPlan::Union(plan) => producer.handle_union(plan),
         LogicalPlan::TableScan(plan) => producer.handle_table_scan(plan),
@@ -64,9 +69,9 @@ pub fn to_substrait_rel(
         LogicalPlan::Analyze(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
         LogicalPlan::Extension(plan) => producer.handle_extension(plan),
         LogicalPlan::Distinct(plan) => producer.handle_distinct(plan),
-        LogicalPlan::Dml(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
-        LogicalPlan::Ddl(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
-        LogicalPlan::Copy(plan) => not_impl_err!("Unsupported plan type: {plan:?}")?,
+        LogicalPlan::Dml(plan) => producer.handle_dml(plan),
+        LogicalPlan::Ddl(plan) => producer.handle_ddl(plan),
+        LogicalPlan::Copy(plan) => producer.handle_copy_to(plan),
         LogicalPlan::DescribeTable(plan) => {
             not_impl_err!("Unsupported plan type: {plan:?}")?
         }
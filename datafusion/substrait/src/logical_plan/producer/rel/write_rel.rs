@@ -0,0 +1,202 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+use crate::logical_plan::producer::{SubstraitProducer, to_substrait_named_struct};
+use datafusion::common::{Column, DFSchema, DFSchemaRef, GetExt, not_impl_err};
+use datafusion::logical_expr::dml::{CopyTo, InsertOp};
+use datafusion::logical_expr::{DmlStatement, Expr, LogicalPlan, WriteOp};
+use pbjson_types::{ListValue, Struct, Value, value::Kind};
+use prost::Message;
+use substrait::proto::rel::RelType;
+use substrait::proto::update_rel::{TransformExpression, UpdateType};
+use substrait::proto::write_rel::{self, CreateMode, OutputMode, WriteType};
+use substrait::proto::{
+    ExtensionObject, NamedObjectWrite, NamedTable, Rel, UpdateRel, WriteRel,
+};
+
+pub fn from_dml(
+    producer: &mut impl SubstraitProducer,
+    dml: &DmlStatement,
+) -> datafusion::common::Result<Box<Rel>> {
+    let op = match &dml.op {
+        WriteOp::Insert(InsertOp::Append) => write_rel::WriteOp::Insert,
+        WriteOp::Delete => write_rel::WriteOp::Delete,
+        WriteOp::Update => match to_update_rel(producer, dml)? {
+            Some(rel) => return Ok(rel),
+            None => write_rel::WriteOp::Update,
+        },
+        WriteOp::Ctas => write_rel::WriteOp::Ctas,
+        op => return not_impl_err!("Unsupported write operation: {op}"),
+    };
+    let create_mode = if op == write_rel::WriteOp::Ctas {
+        CreateMode::ErrorIfExists
+    } else {
+        CreateMode::Unspecified
+    };
+
+    let table_schema = DFSchemaRef::new(DFSchema::try_from(dml.target.schema())?);
+    let table_schema = to_substrait_named_struct(producer, &table_schema)?;
+    let input = producer.handle_plan(dml.input.as_ref())?;
+    Ok(Box::new(Rel {
+        rel_type: Some(RelType::Write(Box::new(WriteRel {
+            write_type: Some(WriteType::NamedTable(NamedObjectWrite {
+                names: dml.table_name.to_vec(),
+                advanced_extension: None,
+            })),
+            table_schema: Some(table_schema),
+            op: op as i32,
+            input: Some(input),
+            create_mode: create_mode as i32,
+            // DataFusion returns the number of written rows, which is not an
+            // output mode of Substrait
+            output: OutputMode::Unspecified as i32,
+            common: None,
+            advanced_extension: None,
+        }))),
+    }))
+}
+
+/// Produces an [UpdateRel] if the UPDATE only reads the updated table, that is
+/// if its input is a projection of an optionally filtered scan of the table.
+/// Other updates, such as those with a `FROM` clause, are produced as a
+/// [WriteRel] of their input.
+fn to_update_rel(
+    producer: &mut impl SubstraitProducer,
+    dml: &DmlStatement,
+) -> datafusion::common::Result<Option<Box<Rel>>> {
+    let LogicalPlan::Projection(projection) = dml.input.as_ref() else {
+        return Ok(None);
+    };
+    let (condition, input) = match projection.input.as_ref() {
+        LogicalPlan::Filter(filter) => (Some(&filter.predicate), filter.input.as_ref()),
+        input => (None, input),
+    };
+    let LogicalPlan::TableScan(scan) = input else {
+        return Ok(None);
+    };
+    let schema = &scan.projected_schema;
+    if scan.table_name != dml.table_name
+        || scan.projection.is_some()
+        || !scan.filters.is_empty()
+        || scan.fetch.is_some()
+        || projection.expr.len() != schema.fields().len()
+    {
+        return Ok(None);
+    }
+
+    let condition = condition
+        .map(|condition| producer.handle_expr(condition, schema))
+        .transpose()?
+        .map(Box::new);
+    let mut transformations = vec![];
+    for (i, expr) in projection.expr.iter().enumerate() {
+        let expr = match expr {
+            Expr::Alias(alias) => alias.expr.as_ref(),
+            expr => expr,
+        };
+        // Columns that are not assigned are projected unchanged
+        if let Expr::Column(column) = expr
+            && *column == Column::from(schema.qualified_field(i))
+        {
+            continue;
+        }
+        transformations.push(TransformExpression {
+            transformation: Some(producer.handle_expr(expr, schema)?),
+            column_target: i as i32,
+        });
+    }
+
+    let table_schema = DFSchemaRef::new(DFSchema::try_from(dml.target.schema())?);
+    Ok(Some(Box::new(Rel {
+        rel_type: Some(RelType::Update(Box::new(UpdateRel {
+            update_type: Some(UpdateType::NamedTable(NamedTable {
+                names: dml.table_name.to_vec(),
+                advanced_extension: None,
+            })),
+            table_schema: Some(to_substrait_named_struct(producer, &table_schema)?),
+            condition,
+            transformations,
+            advanced_extension: None,
+        }))),
+    })))
+}
+
+/// A COPY TO is produced as the insert into an extension table, whose detail
+/// is a [Struct] with the `output_url`, `format`, `partition_by` and `options`
+/// of the copy.
+pub fn from_copy_to(
+    producer: &mut impl SubstraitProducer,
+    copy: &CopyTo,
+) -> datafusion::common::Result<Box<Rel>> {
+    let string_value = |value: &str| Value {
+        kind: Some(Kind::StringValue(value.to_string())),
+    };
+    let partition_by = copy
+        .partition_by
+        .iter()
+        .map(|column| string_value(column))
+        .collect();
+    let options = copy
+        .options
+        .iter()
+        .map(|(key, value)| (key.clone(), string_value(value)))
+        .collect();
+    let detail = Struct {
+        fields: [
+            ("output_url".to_string(), string_value(&copy.output_url)),
+            (
+                "format".to_string(),
+                string_value(&copy.file_type.get_ext()),
+            ),
+            (
+                "partition_by".to_string(),
+                Value {
+                    kind: Some(Kind::ListValue(ListValue {
+                        values: partition_by,
+                    })),
+                },
+            ),
+            (
+                "options".to_string(),
+                Value {
+                    kind: Some(Kind::StructValue(Struct { fields: options })),
+                },
+            ),
+        ]
+        .into_iter()
+        .collect(),
+    };
+
+    let table_schema = to_substrait_named_struct(producer, copy.input.schema())?;
+    let input = producer.handle_plan(copy.input.as_ref())?;
+    Ok(Box::new(Rel {
+        rel_type: Some(RelType::Write(Box::new(WriteRel {
+            write_type: Some(WriteType::ExtensionTable(ExtensionObject {
+                detail: Some(pbjson_types::Any {
+                    type_url: "google.protobuf.Struct".to_string(),
+                    value: detail.encode_to_vec().into(),
+                }),
+            })),
+            table_schema: Some(table_schema),
+            op: write_rel::WriteOp::Insert as i32,
+            input: Some(input),
+            create_mode: CreateMode::Unspecified as i32,
+            output: OutputMode::Unspecified as i32,
+            common: None,
+            advanced_extension: None,
+        }))),
+    }))
+}
@@ -14,216 +14,849 @@
 // KIND, either express or implied.  See the License for the
 // specific language governing permissions and limitations
 // under the License.
-
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use datafusion::arrow::datatypes::{DataType, Field, Schema};
-use datafusion::common::{not_impl_err, substrait_err};
+use datafusion::arrow::compute::SortOptions;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::common::{
+    DFSchema, JoinType, NullEquality, ScalarValue, not_impl_err, plan_err,
+    substrait_datafusion_err, substrait_err,
+};
 use datafusion::datasource::listing::PartitionedFile;
 use datafusion::datasource::object_store::ObjectStoreUrl;
 use datafusion::datasource::physical_plan::{
     FileGroup, FileScanConfigBuilder, ParquetSource,
 };
 use datafusion::error::{DataFusionError, Result};
-use datafusion::physical_plan::ExecutionPlan;
+use datafusion::execution::SessionState;
+use datafusion::logical_expr::Expr;
+use datafusion::logical_expr::physical_planning_context::PhysicalPlanningContext;
+use datafusion::physical_expr::aggregate::LoweredAggregateBuilder;
+use datafusion::physical_expr::{
+    LexOrdering, PhysicalExpr, create_physical_expr, create_physical_sort_exprs,
+};
+use datafusion::physical_plan::aggregates::{
+    AggregateExec, AggregateInputMode, AggregateMode, LimitOptions, PhysicalGroupBy,
+};
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::expressions::Column;
+use datafusion::physical_plan::filter::FilterExecBuilder;
+use datafusion::physical_plan::joins::utils::JoinFilter;
+use datafusion::physical_plan::joins::{HashJoinExec, PartitionMode, SortMergeJoinExec};
+use datafusion::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use datafusion::physical_plan::projection::{ProjectionExec, ProjectionExpr};
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use datafusion::physical_plan::union::UnionExec;
+use datafusion::physical_plan::{ExecutionPlan, Partitioning};
 use datafusion::prelude::SessionContext;
 
-use crate::variation_const::{
-    DEFAULT_CONTAINER_TYPE_VARIATION_REF, LARGE_CONTAINER_TYPE_VARIATION_REF,
-    VIEW_CONTAINER_TYPE_VARIATION_REF,
+use crate::extensions::Extensions;
+use crate::logical_plan::consumer::{
+    DefaultSubstraitConsumer, SubstraitConsumer, from_substrait_agg_func,
+    from_substrait_field_reference, from_substrait_named_struct, from_substrait_sorts,
+};
+use crate::physical_plan::{
+    AGGREGATE_LIMIT, AGGREGATE_MODE, LOCAL_LIMIT, NULL_AWARE, PARTITION_MODE,
+    PRESERVE_ORDER, PRESERVE_PARTITIONING, SORT_PRESERVING_MERGE, find_physical_property,
+    indexed_df_schema,
 };
 use async_recursion::async_recursion;
 use chrono::DateTime;
 use datafusion::datasource::memory::DataSourceExec;
 use object_store::ObjectMeta;
-use substrait::proto::Type;
+use substrait::proto::aggregate_function::AggregationInvocation;
+use substrait::proto::comparison_join_key::{SimpleComparisonType, comparison_type};
+use substrait::proto::exchange_rel::ExchangeKind;
+use substrait::proto::expression::FieldReference;
 use substrait::proto::read_rel::local_files::file_or_files::PathType;
-use substrait::proto::r#type::{Kind, Nullability};
+use substrait::proto::rel_common::EmitKind;
 use substrait::proto::{
-    Rel, expression::MaskExpression, read_rel::ReadType, rel::RelType,
+    AggregateRel, AggregationPhase, ComparisonJoinKey, ExchangeRel, Expression, FetchRel,
+    FilterRel, HashJoinRel, MergeJoinRel, ProjectRel, ReadRel, Rel, RelCommon, SetRel,
+    SortRel, expression::MaskExpression, fetch_rel, hash_join_rel, read_rel::ReadType,
+    rel::RelType, set_rel,
 };
 
 /// Convert Substrait Rel to DataFusion ExecutionPlan
-#[async_recursion]
 pub async fn from_substrait_rel(
-    _ctx: &SessionContext,
+    ctx: &SessionContext,
     rel: &Rel,
-    _extensions: &HashMap<u32, &String>,
+    extensions: &HashMap<u32, &String>,
 ) -> Result<Arc<dyn ExecutionPlan>> {
-    let mut base_config_builder;
+    let extensions = Extensions {
+        functions: extensions
+            .iter()
+            .map(|(anchor, name)| (*anchor, (*name).clone()))
+            .collect(),
+        ..Default::default()
+    };
+    let state = ctx.state();
+    let consumer = DefaultSubstraitConsumer::new(&extensions, &state);
+    from_substrait_rel_with_consumer(&consumer, &state, rel).await
+}
 
+#[async_recursion]
+async fn from_substrait_rel_with_consumer<'a>(
+    consumer: &DefaultSubstraitConsumer<'a>,
+    state: &SessionState,
+    rel: &Rel,
+) -> Result<Arc<dyn ExecutionPlan>> {
     match &rel.rel_type {
-        Some(RelType::Read(read)) => {
-            if read.filter.is_some() || read.best_effort_filter.is_some() {
-                return not_impl_err!("Read with filter is not supported");
-            }
+        Some(RelType::Read(read)) => from_read_rel(consumer, state, read).await,
+        Some(RelType::Filter(filter)) => from_filter_rel(consumer, state, filter).await,
+        Some(RelType::Project(project)) => {
+            from_project_rel(consumer, state, project).await
+        }
+        Some(RelType::Aggregate(aggregate)) => {
+            from_aggregate_rel(consumer, state, aggregate).await
+        }
+        Some(RelType::HashJoin(join)) => from_hash_join_rel(consumer, state, join).await,
+        Some(RelType::MergeJoin(join)) => {
+            from_merge_join_rel(consumer, state, join).await
+        }
+        Some(RelType::Sort(sort)) => from_sort_rel(consumer, state, sort).await,
+        Some(RelType::Fetch(fetch)) => from_fetch_rel(consumer, state, fetch).await,
+        Some(RelType::Set(set)) => from_set_rel(consumer, state, set).await,
+        Some(RelType::Exchange(exchange)) => {
+            from_exchange_rel(consumer, state, exchange).await
+        }
+        _ => not_impl_err!("Unsupported Reltype: {:?}", rel.rel_type),
+    }
+}
 
-            if read.advanced_extension.is_some() {
-                return not_impl_err!("Read with AdvancedExtension is not supported");
-            }
+async fn from_input(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    input: Option<&Rel>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    match input {
+        Some(input) => from_substrait_rel_with_consumer(consumer, state, input).await,
+        None => substrait_err!("Missing input in the relation"),
+    }
+}
 
-            let Some(schema) = read.base_schema.as_ref() else {
-                return substrait_err!("Missing base schema in the read");
-            };
+async fn from_read_rel(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    read: &ReadRel,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if read.filter.is_some() {
+        return not_impl_err!("Read with filter is not supported");
+    }
 
-            let Some(r#struct) = schema.r#struct.as_ref() else {
-                return substrait_err!("Missing struct in the schema");
-            };
+    if read.advanced_extension.is_some() {
+        return not_impl_err!("Read with AdvancedExtension is not supported");
+    }
 
-            match schema
-                .names
-                .iter()
-                .zip(r#struct.types.iter())
-                .map(|(name, r#type)| to_field(name, r#type))
-                .collect::<Result<Vec<Field>>>()
-            {
-                Ok(fields) => {
-                    let schema = Arc::new(Schema::new(fields));
-                    let source = Arc::new(ParquetSource::new(Arc::clone(&schema)));
-                    base_config_builder = FileScanConfigBuilder::new(
-                        ObjectStoreUrl::local_filesystem(),
-                        source,
+    let Some(schema) = read.base_schema.as_ref() else {
+        return substrait_err!("Missing base schema in the read");
+    };
+    let schema = Arc::clone(from_substrait_named_struct(consumer, schema)?.inner());
+
+    let mut source = ParquetSource::new(Arc::clone(&schema));
+    if let Some(predicate) = read.best_effort_filter.as_deref() {
+        let predicate =
+            from_substrait_expr(consumer, state, predicate, &indexed_df_schema(&schema)?)
+                .await?;
+        source = source.with_predicate(predicate);
+    }
+    let mut base_config_builder =
+        FileScanConfigBuilder::new(ObjectStoreUrl::local_filesystem(), Arc::new(source));
+
+    match &read.read_type {
+        Some(ReadType::LocalFiles(files)) => {
+            let mut file_groups = vec![];
+
+            for file in &files.items {
+                let path = if let Some(path_type) = &file.path_type {
+                    match path_type {
+                        PathType::UriPath(path) => Ok(path.clone()),
+                        PathType::UriPathGlob(path) => Ok(path.clone()),
+                        PathType::UriFile(path) => Ok(path.clone()),
+                        PathType::UriFolder(path) => Ok(path.clone()),
+                    }
+                } else {
+                    Err(DataFusionError::Substrait("Missing PathType".to_string()))
+                }?;
+
+                // TODO substrait plans do not have `last_modified` or `size` but `ObjectMeta`
+                // requires them both - perhaps we can change the object-store crate
+                // to make these optional? We cannot guarantee that we have access to the
+                // files to get this information, depending on how this library is being
+                // used. Whole files are read, so their length is their size.
+                let last_modified = DateTime::parse_from_str(
+                    "1970 Jan 1 00:00:00.000 +0000",
+                    "%Y %b %d %H:%M:%S%.3f %z",
+                )
+                .unwrap();
+                if file.start != 0 {
+                    return not_impl_err!(
+                        "Reading a range of {path} is not supported in Substrait physical plans"
                     );
                 }
-                Err(e) => return Err(e),
+
+                let partitioned_file = PartitionedFile::new_from_meta(ObjectMeta {
+                    last_modified: last_modified.into(),
+                    location: path.into(),
+                    size: file.length,
+                    e_tag: None,
+                    version: None,
+                });
+
+                let part_index = file.partition_index as usize;
+                while part_index >= file_groups.len() {
+                    file_groups.push(FileGroup::default());
+                }
+                file_groups[part_index].push(partitioned_file)
+            }
+
+            base_config_builder = base_config_builder.with_file_groups(file_groups);
+
+            if let Some(MaskExpression { select, .. }) = &read.projection
+                && let Some(projection) = &select.as_ref()
+            {
+                let column_indices: Vec<usize> = projection
+                    .struct_items
+                    .iter()
+                    .map(|item| item.field as usize)
+                    .collect();
+                base_config_builder =
+                    base_config_builder.with_projection_indices(Some(column_indices))?;
+            }
+
+            Ok(
+                DataSourceExec::from_data_source(base_config_builder.build())
+                    as Arc<dyn ExecutionPlan>,
+            )
+        }
+        _ => not_impl_err!("Only LocalFile reads are supported when parsing physical"),
+    }
+}
+
+async fn from_filter_rel(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    filter: &FilterRel,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let input = from_input(consumer, state, filter.input.as_deref()).await?;
+    let Some(condition) = filter.condition.as_deref() else {
+        return substrait_err!("Missing condition in the filter");
+    };
+    let predicate = from_substrait_expr(
+        consumer,
+        state,
+        condition,
+        &indexed_df_schema(&input.schema())?,
+    )
+    .await?;
+    let filter = FilterExecBuilder::new(predicate, input)
+        .apply_projection(output_mapping(filter.common.as_ref()))?
+        .build()?;
+    Ok(Arc::new(filter))
+}
+
+async fn from_project_rel(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    project: &ProjectRel,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let input = from_input(consumer, state, project.input.as_deref()).await?;
+    let input_schema = input.schema();
+    let schema = indexed_df_schema(&input_schema)?;
+
+    // A Substrait project outputs its input fields followed by the expressions
+    let mut columns: Vec<(Arc<dyn PhysicalExpr>, String)> = input_schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            (
+                Arc::new(Column::new(field.name(), i)) as Arc<dyn PhysicalExpr>,
+                field.name().clone(),
+            )
+        })
+        .collect();
+    for expr in &project.expressions {
+        let expr = from_substrait_expr(consumer, state, expr, &schema).await?;
+        let name = expr.to_string();
+        columns.push((expr, name));
+    }
+
+    let names = output_names(project.common.as_ref());
+    let output_mapping = output_mapping(project.common.as_ref())
+        .unwrap_or_else(|| (0..columns.len()).collect());
+    let exprs = output_mapping
+        .iter()
+        .enumerate()
+        .map(|(i, index)| {
+            let Some((expr, name)) = columns.get(*index) else {
+                return substrait_err!("Invalid output mapping {index} in the project");
             };
+            let name = names.get(i).unwrap_or(name);
+            Ok(ProjectionExpr::new(Arc::clone(expr), name.clone()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Arc::new(ProjectionExec::try_new(exprs, input)?))
+}
+
+async fn from_aggregate_rel(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    aggregate: &AggregateRel,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let input = from_input(consumer, state, aggregate.input.as_deref()).await?;
+    let extension = aggregate.advanced_extension.as_ref();
+    let mode = match find_physical_property(extension, AGGREGATE_MODE)?.as_deref() {
+        Some("Partial") => AggregateMode::Partial,
+        Some("Final") => AggregateMode::Final,
+        Some("FinalPartitioned") => AggregateMode::FinalPartitioned,
+        Some("Single") => AggregateMode::Single,
+        Some("SinglePartitioned") => AggregateMode::SinglePartitioned,
+        Some(mode) => return substrait_err!("Invalid aggregate mode {mode}"),
+        None => {
+            let phase = aggregate
+                .measures
+                .first()
+                .and_then(|measure| measure.measure.as_ref())
+                .map(|measure| measure.phase());
+            match phase {
+                Some(AggregationPhase::InitialToIntermediate) => AggregateMode::Partial,
+                Some(AggregationPhase::IntermediateToResult) => AggregateMode::Final,
+                _ => AggregateMode::Single,
+            }
+        }
+    };
 
-            match &read.as_ref().read_type {
-                Some(ReadType::LocalFiles(files)) => {
-                    let mut file_groups = vec![];
-
-                    for file in &files.items {
-                        let path = if let Some(path_type) = &file.path_type {
-                            match path_type {
-                                PathType::UriPath(path) => Ok(path.clone()),
-                                PathType::UriPathGlob(path) => Ok(path.clone()),
-                                PathType::UriFile(path) => Ok(path.clone()),
-                                PathType::UriFolder(path) => Ok(path.clone()),
-                            }
-                        } else {
-                            Err(DataFusionError::Substrait(
-                                "Missing PathType".to_string(),
-                            ))
-                        }?;
-
-                        // TODO substrait plans do not have `last_modified` or `size` but `ObjectMeta`
-                        // requires them both - perhaps we can change the object-store crate
-                        // to make these optional? We cannot guarantee that we have access to the
-                        // files to get this information, depending on how this library is being
-                        // used
-                        let last_modified = DateTime::parse_from_str(
-                            "1970 Jan 1 00:00:00.000 +0000",
-                            "%Y %b %d %H:%M:%S%.3f %z",
+    let grouping_expressions = match aggregate.groupings.as_slice() {
+        [] => vec![],
+        #[expect(deprecated)]
+        [grouping] if grouping.expression_references.is_empty() => {
+            grouping.grouping_expressions.iter().collect()
+        }
+        [grouping] => grouping
+            .expression_references
+            .iter()
+            .map(|i| {
+                aggregate
+                    .grouping_expressions
+                    .get(*i as usize)
+                    .ok_or_else(|| {
+                        substrait_datafusion_err!(
+                            "Invalid grouping expression reference {i}"
                         )
-                        .unwrap();
-                        let size = 0;
-
-                        let partitioned_file =
-                            PartitionedFile::new_from_meta(ObjectMeta {
-                                last_modified: last_modified.into(),
-                                location: path.into(),
-                                size,
-                                e_tag: None,
-                                version: None,
-                            });
-
-                        let part_index = file.partition_index as usize;
-                        while part_index >= file_groups.len() {
-                            file_groups.push(FileGroup::default());
-                        }
-                        file_groups[part_index].push(partitioned_file)
-                    }
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?,
+        _ => {
+            return not_impl_err!(
+                "Aggregate with grouping sets is not supported when parsing physical"
+            );
+        }
+    };
 
-                    base_config_builder =
-                        base_config_builder.with_file_groups(file_groups);
-
-                    if let Some(MaskExpression { select, .. }) = &read.projection
-                        && let Some(projection) = &select.as_ref()
-                    {
-                        let column_indices: Vec<usize> = projection
-                            .struct_items
-                            .iter()
-                            .map(|item| item.field as usize)
-                            .collect();
-                        base_config_builder = base_config_builder
-                            .with_projection_indices(Some(column_indices))?;
-                    }
+    let names = output_names(aggregate.common.as_ref());
+    let schema = indexed_df_schema(&input.schema())?;
+    let mut group_exprs = vec![];
+    for (i, expr) in grouping_expressions.into_iter().enumerate() {
+        let expr = from_substrait_expr(consumer, state, expr, &schema).await?;
+        let name = names.get(i).cloned().unwrap_or_else(|| expr.to_string());
+        group_exprs.push((expr, name));
+    }
 
-                    Ok(
-                        DataSourceExec::from_data_source(base_config_builder.build())
-                            as Arc<dyn ExecutionPlan>,
-                    )
-                }
-                _ => not_impl_err!(
-                    "Only LocalFile reads are supported when parsing physical"
-                ),
+    // Aggregate arguments refer to the input of the first aggregation phase
+    let aggregate_input_schema = match mode.input_mode() {
+        AggregateInputMode::Raw => input.schema(),
+        AggregateInputMode::Partial => partial_aggregate_input_schema(&input)?,
+    };
+    let aggregate_schema = indexed_df_schema(&aggregate_input_schema)?;
+    let planning_ctx = PhysicalPlanningContext::default();
+    let mut aggr_exprs = vec![];
+    let mut filter_exprs = vec![];
+    for (i, measure) in aggregate.measures.iter().enumerate() {
+        let Some(function) = measure.measure.as_ref() else {
+            return not_impl_err!(
+                "Aggregate without aggregate function is not supported"
+            );
+        };
+        let filter = match &measure.filter {
+            Some(filter) => Some(Box::new(
+                consumer
+                    .consume_expression(filter, &aggregate_schema)
+                    .await?,
+            )),
+            None => None,
+        };
+        let order_by =
+            from_substrait_sorts(consumer, &function.sorts, &aggregate_schema).await?;
+        let distinct = function.invocation == AggregationInvocation::Distinct as i32;
+        let expr = from_substrait_agg_func(
+            consumer,
+            function,
+            &aggregate_schema,
+            filter,
+            order_by,
+            distinct,
+        )
+        .await?;
+
+        let mut builder = LoweredAggregateBuilder::new(
+            &expr,
+            &aggregate_schema,
+            &aggregate_input_schema,
+            state.execution_props(),
+            &planning_ctx,
+        );
+        if let Some(name) = names.get(group_exprs.len() + i) {
+            builder = builder.with_name(name.as_str());
+        }
+        let lowered = builder.build()?;
+        aggr_exprs.push(lowered.aggregate);
+        filter_exprs.push(lowered.filter);
+    }
+
+    let mut aggregate_exec = AggregateExec::try_new(
+        mode,
+        PhysicalGroupBy::new_single(group_exprs),
+        aggr_exprs,
+        filter_exprs,
+        input,
+        aggregate_input_schema,
+    )?;
+    if let Some(limit) = find_physical_property(extension, AGGREGATE_LIMIT)? {
+        aggregate_exec =
+            aggregate_exec.with_limit_options(Some(parse_limit_options(&limit)?));
+    }
+    Ok(Arc::new(aggregate_exec))
+}
+
+/// Returns the input schema of the first aggregation phase below `plan`
+fn partial_aggregate_input_schema(plan: &Arc<dyn ExecutionPlan>) -> Result<SchemaRef> {
+    let mut plan = plan;
+    loop {
+        if let Some(aggregate) = plan.downcast_ref::<AggregateExec>()
+            && matches!(aggregate.mode().input_mode(), AggregateInputMode::Raw)
+        {
+            return Ok(aggregate.input_schema());
+        }
+        match plan.children().as_slice() {
+            [child] => plan = *child,
+            _ => {
+                return substrait_err!(
+                    "Missing the partial aggregation of a final aggregation"
+                );
             }
         }
-        _ => not_impl_err!("Unsupported Reltype: {:?}", rel.rel_type),
     }
 }
 
-fn to_field(name: &String, r#type: &Type) -> Result<Field> {
-    let Some(kind) = r#type.kind.as_ref() else {
-        return substrait_err!("Missing kind in the type with name {}", name);
+fn parse_limit_options(limit: &str) -> Result<LimitOptions> {
+    let parse = |limit: &str| {
+        limit.parse::<usize>().map_err(|e| {
+            substrait_datafusion_err!("Invalid aggregate limit {limit}: {e}")
+        })
     };
+    match limit.split_once(' ') {
+        Some((limit, "DESC")) => Ok(LimitOptions::new_with_order(parse(limit)?, true)),
+        Some((limit, "ASC")) => Ok(LimitOptions::new_with_order(parse(limit)?, false)),
+        Some(_) => substrait_err!("Invalid aggregate limit {limit}"),
+        None => Ok(LimitOptions::new(parse(limit)?)),
+    }
+}
+
+async fn from_hash_join_rel(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    join: &HashJoinRel,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if join.build_input() == hash_join_rel::BuildInput::Right {
+        return not_impl_err!("Hash join building the right input is not supported");
+    }
+    let left = from_input(consumer, state, join.left.as_deref()).await?;
+    let right = from_input(consumer, state, join.right.as_deref()).await?;
+    let (on, filter, null_equality) = from_substrait_join_parts(
+        consumer,
+        state,
+        &left,
+        &right,
+        &join.keys,
+        join.post_join_filter.as_deref(),
+    )
+    .await?;
+
+    let extension = join.advanced_extension.as_ref();
+    let partition_mode =
+        match find_physical_property(extension, PARTITION_MODE)?.as_deref() {
+            Some("Partitioned") => PartitionMode::Partitioned,
+            Some("CollectLeft") | None => PartitionMode::CollectLeft,
+            Some("Auto") => PartitionMode::Auto,
+            Some(mode) => return substrait_err!("Invalid partition mode {mode}"),
+        };
+    let null_aware = find_physical_property(extension, NULL_AWARE)?.is_some();
+
+    Ok(Arc::new(HashJoinExec::try_new(
+        left,
+        right,
+        on,
+        filter,
+        &from_substrait_join_type(join.r#type)?,
+        output_mapping(join.common.as_ref()),
+        partition_mode,
+        null_equality,
+        null_aware,
+    )?))
+}
+
+async fn from_merge_join_rel(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    join: &MergeJoinRel,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let left = from_input(consumer, state, join.left.as_deref()).await?;
+    let right = from_input(consumer, state, join.right.as_deref()).await?;
+    let (on, filter, null_equality) = from_substrait_join_parts(
+        consumer,
+        state,
+        &left,
+        &right,
+        &join.keys,
+        join.post_join_filter.as_deref(),
+    )
+    .await?;
+    let sort_options = vec![SortOptions::default(); on.len()];
+
+    // MergeJoinRel shares the join type numbering of HashJoinRel
+    Ok(Arc::new(SortMergeJoinExec::try_new(
+        left,
+        right,
+        on,
+        filter,
+        from_substrait_join_type(join.r#type)?,
+        sort_options,
+        null_equality,
+    )?))
+}
+
+type JoinParts = (
+    Vec<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)>,
+    Option<JoinFilter>,
+    NullEquality,
+);
+
+async fn from_substrait_join_parts(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    left: &Arc<dyn ExecutionPlan>,
+    right: &Arc<dyn ExecutionPlan>,
+    keys: &[ComparisonJoinKey],
+    post_join_filter: Option<&Expression>,
+) -> Result<JoinParts> {
+    let left_schema = indexed_df_schema(&left.schema())?;
+    let right_schema = indexed_df_schema(&right.schema())?;
 
-    let mut nullable = false;
-    let data_type = match kind {
-        Kind::Bool(boolean) => {
-            nullable = is_nullable(boolean.nullability);
-            Ok(DataType::Boolean)
-        }
-        Kind::I64(i64) => {
-            nullable = is_nullable(i64.nullability);
-            Ok(DataType::Int64)
-        }
-        Kind::Fp64(fp64) => {
-            nullable = is_nullable(fp64.nullability);
-            Ok(DataType::Float64)
-        }
-        Kind::String(string) => {
-            nullable = is_nullable(string.nullability);
-            match string.type_variation_reference {
-                DEFAULT_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::Utf8),
-                LARGE_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::LargeUtf8),
-                VIEW_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::Utf8View),
-                _ => substrait_err!(
-                    "Invalid type variation found for substrait string type class: {}",
-                    string.type_variation_reference
-                ),
+    let mut on = vec![];
+    let mut null_equalities = vec![];
+    for key in keys {
+        let (Some(left_key), Some(right_key)) = (&key.left, &key.right) else {
+            return substrait_err!("Missing field reference in the join key");
+        };
+        let comparison = key
+            .comparison
+            .as_ref()
+            .and_then(|comparison| comparison.inner_type.as_ref());
+        let null_equality = match comparison {
+            Some(comparison_type::InnerType::Simple(simple))
+                if *simple == SimpleComparisonType::Eq as i32 =>
+            {
+                NullEquality::NullEqualsNothing
             }
-        }
-        Kind::Binary(binary) => {
-            nullable = is_nullable(binary.nullability);
-            match binary.type_variation_reference {
-                DEFAULT_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::Binary),
-                LARGE_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::LargeBinary),
-                VIEW_CONTAINER_TYPE_VARIATION_REF => Ok(DataType::BinaryView),
-                _ => substrait_err!(
-                    "Invalid type variation found for substrait binary type class: {}",
-                    binary.type_variation_reference
-                ),
+            Some(comparison_type::InnerType::Simple(simple))
+                if *simple == SimpleComparisonType::IsNotDistinctFrom as i32 =>
+            {
+                NullEquality::NullEqualsNull
             }
+            _ => return not_impl_err!("Unsupported join key comparison {comparison:?}"),
+        };
+        null_equalities.push(null_equality);
+        on.push((
+            from_substrait_key(consumer, state, left_key, &left_schema)?,
+            from_substrait_key(consumer, state, right_key, &right_schema)?,
+        ));
+    }
+    let null_equality = match null_equalities.as_slice() {
+        [] => NullEquality::NullEqualsNothing,
+        [first, rest @ ..] if rest.iter().all(|n| n == first) => *first,
+        _ => {
+            return not_impl_err!(
+                "Join keys with different null equalities are not supported"
+            );
+        }
+    };
+
+    let filter = match post_join_filter {
+        Some(expr) => {
+            let left_field_count = left.schema().fields().len();
+            let join_schema = Arc::new(Schema::new(
+                left.schema()
+                    .fields()
+                    .iter()
+                    .chain(right.schema().fields().iter())
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            ));
+            let join_schema = indexed_df_schema(&join_schema)?;
+            let expr = consumer.consume_expression(expr, &join_schema).await?;
+
+            // Like the physical planner, the intermediate batch of the filter
+            // holds the fields it uses of the left input, then of the right one
+            let mut indices = expr
+                .column_refs()
+                .into_iter()
+                .map(|column| join_schema.index_of_column(column))
+                .collect::<Result<Vec<_>>>()?;
+            indices.sort_unstable();
+            let filter_df_schema = DFSchema::new_with_metadata(
+                indices
+                    .iter()
+                    .map(|i| {
+                        let (qualifier, field) = join_schema.qualified_field(*i);
+                        (qualifier.cloned(), Arc::clone(field))
+                    })
+                    .collect(),
+                HashMap::new(),
+            )?;
+            let filter_expr = create_physical_expr(
+                &expr,
+                &filter_df_schema,
+                state.execution_props(),
+                &PhysicalPlanningContext::default(),
+            )?;
+            let (left_indices, right_indices): (Vec<_>, Vec<_>) =
+                indices.into_iter().partition(|i| *i < left_field_count);
+            let column_indices = JoinFilter::build_column_indices(
+                left_indices,
+                right_indices
+                    .into_iter()
+                    .map(|i| i - left_field_count)
+                    .collect(),
+            );
+            Some(JoinFilter::new(
+                filter_expr,
+                column_indices,
+                Arc::clone(filter_df_schema.inner()),
+            ))
         }
-        _ => substrait_err!(
-            "Unsupported kind: {:?} in the type with name {}",
-            kind,
-            name
-        ),
-    }?;
+        None => None,
+    };
 
-    Ok(Field::new(name, data_type, nullable))
+    Ok((on, filter, null_equality))
 }
 
-fn is_nullable(nullability: i32) -> bool {
-    let Ok(nullability) = Nullability::try_from(nullability) else {
-        return true;
+fn from_substrait_key(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    field_ref: &FieldReference,
+    schema: &DFSchema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let expr = from_substrait_field_reference(consumer, field_ref, schema)?;
+    create_physical_expr(
+        &expr,
+        schema,
+        state.execution_props(),
+        &PhysicalPlanningContext::default(),
+    )
+}
+
+fn from_substrait_join_type(join_type: i32) -> Result<JoinType> {
+    if let Ok(substrait_join_type) = hash_join_rel::JoinType::try_from(join_type) {
+        match substrait_join_type {
+            hash_join_rel::JoinType::Inner => Ok(JoinType::Inner),
+            hash_join_rel::JoinType::Left => Ok(JoinType::Left),
+            hash_join_rel::JoinType::Right => Ok(JoinType::Right),
+            hash_join_rel::JoinType::Outer => Ok(JoinType::Full),
+            hash_join_rel::JoinType::LeftAnti => Ok(JoinType::LeftAnti),
+            hash_join_rel::JoinType::LeftSemi => Ok(JoinType::LeftSemi),
+            hash_join_rel::JoinType::LeftMark => Ok(JoinType::LeftMark),
+            hash_join_rel::JoinType::RightMark => Ok(JoinType::RightMark),
+            hash_join_rel::JoinType::RightAnti => Ok(JoinType::RightAnti),
+            hash_join_rel::JoinType::RightSemi => Ok(JoinType::RightSemi),
+            _ => plan_err!("unsupported join type {substrait_join_type:?}"),
+        }
+    } else {
+        plan_err!("invalid join type variant {join_type}")
+    }
+}
+
+async fn from_sort_rel(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    sort: &SortRel,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let input = from_input(consumer, state, sort.input.as_deref()).await?;
+    let schema = indexed_df_schema(&input.schema())?;
+    let sorts = from_substrait_sorts(consumer, &sort.sorts, &schema).await?;
+    let ordering = create_physical_sort_exprs(
+        &sorts,
+        &schema,
+        state.execution_props(),
+        &PhysicalPlanningContext::default(),
+    )?;
+    let Some(ordering) = LexOrdering::new(ordering) else {
+        return substrait_err!("Missing sort fields in the sort");
     };
 
-    match nullability {
-        Nullability::Nullable | Nullability::Unspecified => true,
-        Nullability::Required => false,
+    let extension = sort.advanced_extension.as_ref();
+    if find_physical_property(extension, SORT_PRESERVING_MERGE)?.is_some() {
+        return Ok(Arc::new(SortPreservingMergeExec::new(ordering, input)));
     }
+    let preserve_partitioning =
+        find_physical_property(extension, PRESERVE_PARTITIONING)?.is_some();
+    Ok(Arc::new(
+        SortExec::new(ordering, input).with_preserve_partitioning(preserve_partitioning),
+    ))
+}
+
+async fn from_fetch_rel(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    fetch: &FetchRel,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let input = from_input(consumer, state, fetch.input.as_deref()).await?;
+    let empty_schema = DFSchema::empty();
+    let skip = match &fetch.offset_mode {
+        #[expect(deprecated)]
+        Some(fetch_rel::OffsetMode::Offset(offset)) => *offset as usize,
+        Some(fetch_rel::OffsetMode::OffsetExpr(expr)) => {
+            to_fetch_value(consumer.consume_expression(expr, &empty_schema).await?)?
+        }
+        None => 0,
+    };
+    let count = match &fetch.count_mode {
+        #[expect(deprecated)]
+        Some(fetch_rel::CountMode::Count(count)) => {
+            // -1 means that ALL records should be returned, equivalent to None
+            (*count != -1).then_some(*count as usize)
+        }
+        Some(fetch_rel::CountMode::CountExpr(expr)) => Some(to_fetch_value(
+            consumer.consume_expression(expr, &empty_schema).await?,
+        )?),
+        None => None,
+    };
+
+    if find_physical_property(fetch.advanced_extension.as_ref(), LOCAL_LIMIT)?.is_some() {
+        let Some(count) = count else {
+            return substrait_err!("Missing count in the local limit");
+        };
+        return Ok(Arc::new(LocalLimitExec::new(input, count)));
+    }
+
+    // The fetch of the operators that support it is produced as a FetchRel
+    let supports_fetch = input.downcast_ref::<SortExec>().is_some()
+        || input.downcast_ref::<SortPreservingMergeExec>().is_some()
+        || input.downcast_ref::<CoalescePartitionsExec>().is_some();
+    if skip == 0
+        && count.is_some()
+        && supports_fetch
+        && input.fetch().is_none()
+        && let Some(plan) = input.with_fetch(count)
+    {
+        return Ok(plan);
+    }
+    Ok(Arc::new(GlobalLimitExec::new(input, skip, count)))
+}
+
+fn to_fetch_value(expr: Expr) -> Result<usize> {
+    match expr {
+        Expr::Literal(ScalarValue::Int64(Some(value)), _) if value >= 0 => {
+            Ok(value as usize)
+        }
+        _ => not_impl_err!("Unsupported fetch value {expr}"),
+    }
+}
+
+async fn from_set_rel(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    set: &SetRel,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if set.op() != set_rel::SetOp::UnionAll {
+        return not_impl_err!("Unsupported set operation {:?}", set.op());
+    }
+    let mut inputs = vec![];
+    for input in &set.inputs {
+        inputs.push(from_substrait_rel_with_consumer(consumer, state, input).await?);
+    }
+    UnionExec::try_new(inputs)
+}
+
+async fn from_exchange_rel(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    exchange: &ExchangeRel,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let input = from_input(consumer, state, exchange.input.as_deref()).await?;
+    let partition_count = exchange.partition_count as usize;
+
+    // ref: https://substrait.io/relations/physical_relations/#exchange-types
+    let partitioning = match &exchange.exchange_kind {
+        Some(ExchangeKind::ScatterByFields(scatter_fields)) => {
+            let schema = indexed_df_schema(&input.schema())?;
+            let exprs = scatter_fields
+                .fields
+                .iter()
+                .map(|field_ref| from_substrait_key(consumer, state, field_ref, &schema))
+                .collect::<Result<Vec<_>>>()?;
+            Partitioning::Hash(exprs, partition_count)
+        }
+        Some(ExchangeKind::RoundRobin(_)) if partition_count == 1 => {
+            return Ok(Arc::new(CoalescePartitionsExec::new(input)));
+        }
+        Some(ExchangeKind::RoundRobin(_)) => {
+            Partitioning::RoundRobinBatch(partition_count)
+        }
+        exchange_kind => {
+            return not_impl_err!("Unsupported exchange kind: {exchange_kind:?}");
+        }
+    };
+
+    let mut repartition = RepartitionExec::try_new(input, partitioning)?;
+    if find_physical_property(exchange.advanced_extension.as_ref(), PRESERVE_ORDER)?
+        .is_some()
+    {
+        repartition = repartition.with_preserve_order();
+    }
+    Ok(Arc::new(repartition))
+}
+
+async fn from_substrait_expr(
+    consumer: &DefaultSubstraitConsumer<'_>,
+    state: &SessionState,
+    expr: &Expression,
+    schema: &DFSchema,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let expr = consumer.consume_expression(expr, schema).await?;
+    create_physical_expr(
+        &expr,
+        schema,
+        state.execution_props(),
+        &PhysicalPlanningContext::default(),
+    )
+}
+
+fn output_mapping(common: Option<&RelCommon>) -> Option<Vec<usize>> {
+    match common.and_then(|common| common.emit_kind.as_ref()) {
+        Some(EmitKind::Emit(emit)) => {
+            Some(emit.output_mapping.iter().map(|i| *i as usize).collect())
+        }
+        Some(EmitKind::Direct(_)) | None => None,
+    }
+}
+
+fn output_names(common: Option<&RelCommon>) -> &[String] {
+    common
+        .and_then(|common| common.hint.as_ref())
+        .map(|hint| hint.output_names.as_slice())
+        .unwrap_or_default()
 }
@@ -17,3 +17,64 @@
 
 pub mod consumer;
 pub mod producer;
+
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::{DFSchema, TableReference, substrait_datafusion_err};
+use datafusion::error::Result;
+use pbjson_types::Any as ProtoAny;
+use substrait::proto::extensions::AdvancedExtension;
+
+// DataFusion specific physical properties without a Substrait counterpart.
+// They are carried as `AdvancedExtension` optimizations, except for
+// `NULL_AWARE` which changes the join semantics and is an enhancement.
+const PHYSICAL_PROPERTY_TYPE_URL_PREFIX: &str = "datafusion.physical.";
+pub(crate) const AGGREGATE_MODE: &str = "AggregateMode";
+pub(crate) const AGGREGATE_LIMIT: &str = "AggregateLimit";
+pub(crate) const PARTITION_MODE: &str = "PartitionMode";
+pub(crate) const NULL_AWARE: &str = "NullAware";
+pub(crate) const PRESERVE_PARTITIONING: &str = "PreservePartitioning";
+pub(crate) const PRESERVE_ORDER: &str = "PreserveOrder";
+pub(crate) const SORT_PRESERVING_MERGE: &str = "SortPreservingMerge";
+pub(crate) const LOCAL_LIMIT: &str = "LocalLimit";
+
+/// Encodes the physical property `name` with the given `value`
+pub(crate) fn physical_property(name: &str, value: impl Into<String>) -> ProtoAny {
+    ProtoAny {
+        type_url: format!("{PHYSICAL_PROPERTY_TYPE_URL_PREFIX}{name}"),
+        value: value.into().into_bytes().into(),
+    }
+}
+
+/// Returns the value of the physical property `name`, if `extension` carries it
+pub(crate) fn find_physical_property(
+    extension: Option<&AdvancedExtension>,
+    name: &str,
+) -> Result<Option<String>> {
+    let Some(extension) = extension else {
+        return Ok(None);
+    };
+    let type_url = format!("{PHYSICAL_PROPERTY_TYPE_URL_PREFIX}{name}");
+    extension
+        .optimization
+        .iter()
+        .chain(extension.enhancement.as_ref())
+        .find(|any| any.type_url == type_url)
+        .map(|any| {
+            String::from_utf8(any.value.to_vec()).map_err(|e| {
+                substrait_datafusion_err!("Invalid physical property {name}: {e}")
+            })
+        })
+        .transpose()
+}
+
+/// Qualifies every field of a physical schema by its index.
+///
+/// Physical plans address columns by index and may contain duplicate names,
+/// e.g. the output of a join. The qualifiers keep every field addressable
+/// while expressions go through the logical Substrait producer and consumer.
+pub(crate) fn indexed_df_schema(schema: &SchemaRef) -> Result<DFSchema> {
+    let qualifiers = (0..schema.fields().len())
+        .map(|i| Some(TableReference::bare(i.to_string())))
+        .collect();
+    DFSchema::from_field_specific_qualified_schema(qualifiers, schema)
+}
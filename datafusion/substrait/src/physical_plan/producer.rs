@@ -16,187 +16,913 @@
 // under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use crate::variation_const::{
-    DEFAULT_CONTAINER_TYPE_VARIATION_REF, LARGE_CONTAINER_TYPE_VARIATION_REF,
-    VIEW_CONTAINER_TYPE_VARIATION_REF,
+use crate::extensions::Extensions;
+use crate::logical_plan::producer::{
+    SubstraitProducer, from_aggregate_function, parse_flat_grouping_exprs,
+    substrait_sort_field, to_substrait_named_struct, try_to_substrait_field_reference,
 };
+use crate::physical_plan::{
+    AGGREGATE_LIMIT, AGGREGATE_MODE, LOCAL_LIMIT, NULL_AWARE, PARTITION_MODE,
+    PRESERVE_ORDER, PRESERVE_PARTITIONING, SORT_PRESERVING_MERGE, indexed_df_schema,
+    physical_property,
+};
+use crate::variation_const::DEFAULT_TYPE_VARIATION_REF;
 
-use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::compute::SortOptions;
+use datafusion::arrow::datatypes::Schema;
+use datafusion::common::tree_node::{Transformed, TransformedResult, TreeNode};
+use datafusion::common::{
+    DFSchema, DFSchemaRef, JoinSide, JoinType, NullEquality, internal_err, not_impl_err,
+};
+use datafusion::datasource::physical_plan::ParquetSource;
 use datafusion::datasource::source::DataSourceExec;
 use datafusion::error::{DataFusionError, Result};
-use datafusion::physical_plan::{ExecutionPlan, displayable};
+use datafusion::logical_expr::Expr;
+use datafusion::logical_expr::expr::{
+    AggregateFunction, BinaryExpr, Case, Cast, InList, Like, ScalarFunction, Sort,
+    TryCast,
+};
+use datafusion::physical_expr::{LexOrdering, PhysicalExpr, ScalarFunctionExpr};
+use datafusion::physical_plan::aggregates::{
+    AggregateExec, AggregateInputMode, AggregateOutputMode,
+};
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::expressions;
+use datafusion::physical_plan::filter::FilterExec;
+use datafusion::physical_plan::joins::utils::JoinFilter;
+use datafusion::physical_plan::joins::{HashJoinExec, SortMergeJoinExec};
+use datafusion::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use datafusion::physical_plan::union::UnionExec;
+use datafusion::physical_plan::{ExecutionPlan, Partitioning, displayable};
 
-use datafusion::datasource::physical_plan::ParquetSource;
-use substrait::proto::ReadRel;
-use substrait::proto::Rel;
-use substrait::proto::expression::MaskExpression;
+use pbjson_types::Any as ProtoAny;
+use substrait::proto::aggregate_rel::Measure;
+use substrait::proto::comparison_join_key::{
+    ComparisonType, SimpleComparisonType, comparison_type,
+};
+use substrait::proto::exchange_rel::{ExchangeKind, RoundRobin, ScatterFields};
+use substrait::proto::expression::literal::LiteralType;
 use substrait::proto::expression::mask_expression::{StructItem, StructSelect};
+use substrait::proto::expression::{Literal, MaskExpression, RexType};
+use substrait::proto::extensions::AdvancedExtension;
 use substrait::proto::read_rel::LocalFiles;
 use substrait::proto::read_rel::ReadType;
 use substrait::proto::read_rel::local_files::FileOrFiles;
 use substrait::proto::read_rel::local_files::file_or_files::ParquetReadOptions;
 use substrait::proto::read_rel::local_files::file_or_files::{FileFormat, PathType};
 use substrait::proto::rel::RelType;
-use substrait::proto::r#type::{
-    Binary, Boolean, Fp64, I64, Kind, Nullability, String as SubstraitString, Struct,
+use substrait::proto::rel_common::EmitKind;
+use substrait::proto::{
+    AggregateRel, AggregationPhase, ComparisonJoinKey, ExchangeRel, Expression, FetchRel,
+    FilterRel, HashJoinRel, MergeJoinRel, ProjectRel, ReadRel, Rel, RelCommon, SetRel,
+    SortRel, extensions, fetch_rel, hash_join_rel, rel_common, set_rel,
 };
-use substrait::proto::{NamedStruct, Type, extensions};
 
 /// Convert DataFusion ExecutionPlan to Substrait Rel
 pub fn to_substrait_rel(
     plan: &dyn ExecutionPlan,
-    _extension_info: &mut (
+    extension_info: &mut (
         Vec<extensions::SimpleExtensionDeclaration>,
         HashMap<String, u32>,
     ),
 ) -> Result<Box<Rel>> {
-    if let Some(data_source_exec) = plan.downcast_ref::<DataSourceExec>()
-        && let Some((file_config, _)) =
-            data_source_exec.downcast_to_file_source::<ParquetSource>()
-    {
-        let mut substrait_files = vec![];
-        for (partition_index, files) in file_config.file_groups.iter().enumerate() {
-            for file in files.iter() {
-                substrait_files.push(FileOrFiles {
-                    partition_index: partition_index.try_into().unwrap(),
-                    start: 0,
-                    length: file.object_meta.size,
-                    path_type: Some(PathType::UriPath(
-                        file.object_meta.location.as_ref().to_string(),
-                    )),
-                    file_format: Some(FileFormat::Parquet(ParquetReadOptions {})),
-                });
-            }
-        }
+    let mut producer = PhysicalPlanProducer {
+        extensions: Extensions::try_from(&extension_info.0)?,
+    };
+    let rel = to_substrait_rel_with_producer(&mut producer, plan)?;
 
-        let mut names = vec![];
-        let mut types = vec![];
+    let extensions = producer.get_extensions();
+    extension_info.1 = extensions
+        .functions
+        .iter()
+        .map(|(anchor, name)| (name.clone(), *anchor))
+        .collect();
+    extension_info.0 = extensions.into();
+    Ok(rel)
+}
 
-        for field in file_config.file_schema().fields.iter() {
-            let t = to_substrait_type(field.data_type(), field.is_nullable())?;
-            names.push(field.name().clone());
-            types.push(t);
-        }
+/// Producer used to reuse the logical plan producer for the expressions of a
+/// physical plan
+struct PhysicalPlanProducer {
+    extensions: Extensions,
+}
 
-        let type_info = Struct {
-            types,
-            // FIXME: duckdb doesn't set this field, keep it as default variant 0.
-            // https://github.com/duckdb/substrait/blob/b6f56643cb11d52de0e32c24a01dfd5947df62be/src/to_substrait.cpp#L1106-L1127
-            type_variation_reference: 0,
-            nullability: Nullability::Required.into(),
-        };
+impl SubstraitProducer for PhysicalPlanProducer {
+    fn register_function(&mut self, signature: String) -> u32 {
+        self.extensions.register_function(&signature)
+    }
 
-        let mut select_struct = None;
-        if let Some(projection) = file_config.file_source().projection().as_ref() {
-            let struct_items = projection
-                .column_indices()
-                .into_iter()
-                .map(|index| StructItem {
-                    field: index as i32,
-                    // FIXME: duckdb sets this to None, but it's not clear why.
-                    // https://github.com/duckdb/substrait/blob/b6f56643cb11d52de0e32c24a01dfd5947df62be/src/to_substrait.cpp#L1191
-                    child: None,
-                })
-                .collect();
+    fn register_type(&mut self, type_name: String) -> u32 {
+        self.extensions.register_type(&type_name)
+    }
 
-            select_struct = Some(StructSelect { struct_items });
-        }
+    fn get_extensions(self) -> Extensions {
+        self.extensions
+    }
+}
 
+fn to_substrait_rel_with_producer(
+    producer: &mut PhysicalPlanProducer,
+    plan: &dyn ExecutionPlan,
+) -> Result<Box<Rel>> {
+    if let Some(data_source_exec) = plan.downcast_ref::<DataSourceExec>()
+        && data_source_exec
+            .downcast_to_file_source::<ParquetSource>()
+            .is_some()
+    {
+        return from_parquet_exec(producer, data_source_exec);
+    }
+    if let Some(filter) = plan.downcast_ref::<FilterExec>() {
+        return from_filter_exec(producer, filter);
+    }
+    if let Some(projection) = plan.downcast_ref::<ProjectionExec>() {
+        return from_projection_exec(producer, projection);
+    }
+    if let Some(aggregate) = plan.downcast_ref::<AggregateExec>() {
+        return from_aggregate_exec(producer, aggregate);
+    }
+    if let Some(hash_join) = plan.downcast_ref::<HashJoinExec>() {
+        return from_hash_join_exec(producer, hash_join);
+    }
+    if let Some(sort_merge_join) = plan.downcast_ref::<SortMergeJoinExec>() {
+        return from_sort_merge_join_exec(producer, sort_merge_join);
+    }
+    if let Some(sort) = plan.downcast_ref::<SortExec>() {
+        let mut properties = vec![];
+        if sort.preserve_partitioning() {
+            properties.push(physical_property(PRESERVE_PARTITIONING, ""));
+        }
+        return from_sort(
+            producer,
+            sort.input().as_ref(),
+            sort.expr(),
+            sort.fetch(),
+            properties,
+        );
+    }
+    if let Some(merge) = plan.downcast_ref::<SortPreservingMergeExec>() {
+        return from_sort(
+            producer,
+            merge.input().as_ref(),
+            merge.expr(),
+            merge.fetch(),
+            vec![physical_property(SORT_PRESERVING_MERGE, "")],
+        );
+    }
+    if let Some(limit) = plan.downcast_ref::<GlobalLimitExec>() {
+        let input = to_substrait_rel_with_producer(producer, limit.input().as_ref())?;
+        return Ok(fetch_rel(input, limit.skip(), limit.fetch(), vec![]));
+    }
+    if let Some(limit) = plan.downcast_ref::<LocalLimitExec>() {
+        let input = to_substrait_rel_with_producer(producer, limit.input().as_ref())?;
+        return Ok(fetch_rel(
+            input,
+            0,
+            Some(limit.fetch()),
+            vec![physical_property(LOCAL_LIMIT, "")],
+        ));
+    }
+    if let Some(union) = plan.downcast_ref::<UnionExec>() {
+        let inputs = union
+            .inputs()
+            .iter()
+            .map(|input| {
+                to_substrait_rel_with_producer(producer, input.as_ref()).map(|rel| *rel)
+            })
+            .collect::<Result<Vec<_>>>()?;
         return Ok(Box::new(Rel {
-            rel_type: Some(RelType::Read(Box::new(ReadRel {
+            rel_type: Some(RelType::Set(SetRel {
                 common: None,
-                base_schema: Some(NamedStruct {
-                    names,
-                    r#struct: Some(type_info),
-                }),
-                filter: None,
-                best_effort_filter: None,
-                projection: Some(MaskExpression {
-                    select: select_struct,
-                    // FIXME: duckdb set this to true, but it's not clear why.
-                    // https://github.com/duckdb/substrait/blob/b6f56643cb11d52de0e32c24a01dfd5947df62be/src/to_substrait.cpp#L1186.
-                    maintain_singular_struct: true,
-                }),
+                inputs,
+                op: set_rel::SetOp::UnionAll as i32,
                 advanced_extension: None,
-                read_type: Some(ReadType::LocalFiles(LocalFiles {
-                    items: substrait_files,
-                    advanced_extension: None,
-                })),
-            }))),
+            })),
         }));
     }
+    if let Some(repartition) = plan.downcast_ref::<RepartitionExec>() {
+        return from_repartition_exec(producer, repartition);
+    }
+    if let Some(coalesce) = plan.downcast_ref::<CoalescePartitionsExec>() {
+        // Gathering all partitions into one is a round robin exchange to a
+        // single partition
+        let input = to_substrait_rel_with_producer(producer, coalesce.input().as_ref())?;
+        let rel = Box::new(Rel {
+            rel_type: Some(RelType::Exchange(Box::new(ExchangeRel {
+                common: None,
+                input: Some(input),
+                exchange_kind: Some(ExchangeKind::RoundRobin(RoundRobin::default())),
+                advanced_extension: None,
+                partition_count: 1,
+                targets: vec![],
+            }))),
+        });
+        return Ok(match coalesce.fetch() {
+            Some(fetch) => fetch_rel(rel, 0, Some(fetch), vec![]),
+            None => rel,
+        });
+    }
     Err(DataFusionError::Substrait(format!(
         "Unsupported plan in Substrait physical plan producer: {}",
         displayable(plan).one_line()
     )))
 }
 
-// see https://github.com/duckdb/substrait/blob/b6f56643cb11d52de0e32c24a01dfd5947df62be/src/to_substrait.cpp#L954-L1094.
-fn to_substrait_type(data_type: &DataType, nullable: bool) -> Result<Type> {
-    let nullability = if nullable {
-        Nullability::Nullable.into()
-    } else {
-        Nullability::Required.into()
+fn from_parquet_exec(
+    producer: &mut PhysicalPlanProducer,
+    data_source_exec: &DataSourceExec,
+) -> Result<Box<Rel>> {
+    let Some((file_config, parquet_source)) =
+        data_source_exec.downcast_to_file_source::<ParquetSource>()
+    else {
+        return internal_err!("Expected a parquet DataSourceExec");
     };
 
-    match data_type {
-        DataType::Boolean => Ok(Type {
-            kind: Some(Kind::Bool(Boolean {
-                type_variation_reference: 0,
-                nullability,
-            })),
-        }),
-        DataType::Int64 => Ok(Type {
-            kind: Some(Kind::I64(I64 {
-                type_variation_reference: 0,
-                nullability,
-            })),
-        }),
-        DataType::Float64 => Ok(Type {
-            kind: Some(Kind::Fp64(Fp64 {
-                type_variation_reference: 0,
-                nullability,
-            })),
-        }),
-        DataType::Utf8 => Ok(Type {
-            kind: Some(Kind::String(SubstraitString {
-                type_variation_reference: DEFAULT_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
-            })),
-        }),
-        DataType::LargeUtf8 => Ok(Type {
-            kind: Some(Kind::String(SubstraitString {
-                type_variation_reference: LARGE_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
-            })),
-        }),
-        DataType::Utf8View => Ok(Type {
-            kind: Some(Kind::String(SubstraitString {
-                type_variation_reference: VIEW_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
-            })),
-        }),
-        DataType::Binary => Ok(Type {
-            kind: Some(Kind::Binary(Binary {
-                type_variation_reference: DEFAULT_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
-            })),
-        }),
-        DataType::LargeBinary => Ok(Type {
-            kind: Some(Kind::Binary(Binary {
-                type_variation_reference: LARGE_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
+    let mut substrait_files = vec![];
+    for (partition_index, files) in file_config.file_groups.iter().enumerate() {
+        for file in files.iter() {
+            if file.range.is_some() {
+                return not_impl_err!(
+                    "Reading a range of {} is not supported in Substrait physical plans",
+                    file.object_meta.location
+                );
+            }
+            substrait_files.push(FileOrFiles {
+                partition_index: partition_index.try_into().unwrap(),
+                start: 0,
+                length: file.object_meta.size,
+                path_type: Some(PathType::UriPath(
+                    file.object_meta.location.as_ref().to_string(),
+                )),
+                file_format: Some(FileFormat::Parquet(ParquetReadOptions {})),
+            });
+        }
+    }
+
+    let schema = indexed_df_schema(file_config.file_schema())?;
+    let base_schema = to_substrait_named_struct(
+        producer,
+        &Arc::new(DFSchema::try_from(Arc::clone(file_config.file_schema()))?),
+    )?;
+
+    // The predicate is only used to prune the files, so it is a best effort filter
+    let best_effort_filter = parquet_source
+        .predicate()
+        .map(|predicate| to_substrait_expr(producer, predicate, &schema))
+        .transpose()?
+        .map(Box::new);
+
+    let mut select_struct = None;
+    if let Some(projection) = file_config.file_source().projection().as_ref() {
+        let struct_items = projection
+            .column_indices()
+            .into_iter()
+            .map(|index| StructItem {
+                field: index as i32,
+                // FIXME: duckdb sets this to None, but it's not clear why.
+                // https://github.com/duckdb/substrait/blob/b6f56643cb11d52de0e32c24a01dfd5947df62be/src/to_substrait.cpp#L1191
+                child: None,
+            })
+            .collect();
+
+        select_struct = Some(StructSelect { struct_items });
+    }
+
+    Ok(Box::new(Rel {
+        rel_type: Some(RelType::Read(Box::new(ReadRel {
+            common: None,
+            base_schema: Some(base_schema),
+            filter: None,
+            best_effort_filter,
+            projection: Some(MaskExpression {
+                select: select_struct,
+                // FIXME: duckdb set this to true, but it's not clear why.
+                // https://github.com/duckdb/substrait/blob/b6f56643cb11d52de0e32c24a01dfd5947df62be/src/to_substrait.cpp#L1186.
+                maintain_singular_struct: true,
+            }),
+            advanced_extension: None,
+            read_type: Some(ReadType::LocalFiles(LocalFiles {
+                items: substrait_files,
+                advanced_extension: None,
             })),
-        }),
-        DataType::BinaryView => Ok(Type {
-            kind: Some(Kind::Binary(Binary {
-                type_variation_reference: VIEW_CONTAINER_TYPE_VARIATION_REF,
-                nullability,
+        }))),
+    }))
+}
+
+fn from_filter_exec(
+    producer: &mut PhysicalPlanProducer,
+    filter: &FilterExec,
+) -> Result<Box<Rel>> {
+    if filter.fetch().is_some() {
+        return not_impl_err!(
+            "FilterExec with a fetch is not supported in Substrait physical plans"
+        );
+    }
+    let schema = indexed_df_schema(&filter.input().schema())?;
+    let condition = to_substrait_expr(producer, filter.predicate(), &schema)?;
+    let input = to_substrait_rel_with_producer(producer, filter.input().as_ref())?;
+    let output_mapping = filter
+        .projection()
+        .as_ref()
+        .map(|projection| projection.iter().map(|i| *i as i32).collect());
+
+    Ok(Box::new(Rel {
+        rel_type: Some(RelType::Filter(Box::new(FilterRel {
+            common: rel_common(output_mapping, vec![]),
+            input: Some(input),
+            condition: Some(Box::new(condition)),
+            advanced_extension: None,
+        }))),
+    }))
+}
+
+fn from_projection_exec(
+    producer: &mut PhysicalPlanProducer,
+    projection: &ProjectionExec,
+) -> Result<Box<Rel>> {
+    let input_schema = projection.input().schema();
+    let schema = indexed_df_schema(&input_schema)?;
+    let expressions = projection
+        .expr()
+        .iter()
+        .map(|e| to_substrait_expr(producer, &e.expr, &schema))
+        .collect::<Result<Vec<_>>>()?;
+    let output_names = projection.expr().iter().map(|e| e.alias.clone()).collect();
+
+    // A Substrait project outputs its input fields followed by the
+    // expressions, while a ProjectionExec only outputs the expressions
+    let input_field_count = input_schema.fields().len();
+    let output_mapping = (input_field_count..input_field_count + expressions.len())
+        .map(|i| i as i32)
+        .collect();
+
+    let input = to_substrait_rel_with_producer(producer, projection.input().as_ref())?;
+    Ok(Box::new(Rel {
+        rel_type: Some(RelType::Project(Box::new(ProjectRel {
+            common: rel_common(Some(output_mapping), output_names),
+            input: Some(input),
+            expressions,
+            advanced_extension: None,
+        }))),
+    }))
+}
+
+fn from_aggregate_exec(
+    producer: &mut PhysicalPlanProducer,
+    aggregate: &AggregateExec,
+) -> Result<Box<Rel>> {
+    let group_by = aggregate.group_expr();
+    if group_by.has_grouping_set() {
+        return not_impl_err!(
+            "AggregateExec with grouping sets is not supported in Substrait physical plans"
+        );
+    }
+
+    // Group expressions refer to the input of the aggregate, while aggregate
+    // arguments refer to the input of the first aggregation phase
+    let schema = Arc::new(indexed_df_schema(&aggregate.input().schema())?);
+    let group_exprs = group_by
+        .expr()
+        .iter()
+        .map(|(expr, _)| to_logical_expr(expr, &schema))
+        .collect::<Result<Vec<_>>>()?;
+    let mut grouping_expressions = vec![];
+    let grouping = parse_flat_grouping_exprs(
+        producer,
+        &group_exprs,
+        &schema,
+        &mut grouping_expressions,
+    )?;
+
+    let mode = aggregate.mode();
+    let phase = match (mode.input_mode(), mode.output_mode()) {
+        (AggregateInputMode::Raw, AggregateOutputMode::Partial) => {
+            AggregationPhase::InitialToIntermediate
+        }
+        (AggregateInputMode::Raw, AggregateOutputMode::Final) => {
+            AggregationPhase::InitialToResult
+        }
+        (AggregateInputMode::Partial, AggregateOutputMode::Partial) => {
+            AggregationPhase::IntermediateToIntermediate
+        }
+        (AggregateInputMode::Partial, AggregateOutputMode::Final) => {
+            AggregationPhase::IntermediateToResult
+        }
+    };
+    let aggregate_schema = Arc::new(indexed_df_schema(&aggregate.input_schema())?);
+    let measures = aggregate
+        .aggr_expr()
+        .iter()
+        .zip(aggregate.filter_expr())
+        .map(|(aggr, filter)| {
+            let mut measure =
+                to_substrait_measure(producer, aggr, filter.as_ref(), &aggregate_schema)?;
+            if let Some(measure) = measure.measure.as_mut() {
+                measure.phase = phase as i32;
+            }
+            Ok(measure)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let output_names = group_by
+        .expr()
+        .iter()
+        .map(|(_, name)| name.clone())
+        .chain(
+            aggregate
+                .aggr_expr()
+                .iter()
+                .map(|aggr| aggr.name().to_string()),
+        )
+        .collect();
+    let mut properties = vec![physical_property(AGGREGATE_MODE, format!("{mode:?}"))];
+    if let Some(limit_options) = aggregate.limit_options() {
+        let limit = match limit_options.descending() {
+            Some(true) => format!("{} DESC", limit_options.limit()),
+            Some(false) => format!("{} ASC", limit_options.limit()),
+            None => limit_options.limit().to_string(),
+        };
+        properties.push(physical_property(AGGREGATE_LIMIT, limit));
+    }
+
+    let input = to_substrait_rel_with_producer(producer, aggregate.input().as_ref())?;
+    Ok(Box::new(Rel {
+        rel_type: Some(RelType::Aggregate(Box::new(AggregateRel {
+            common: rel_common(None, output_names),
+            input: Some(input),
+            grouping_expressions,
+            groupings: vec![grouping],
+            measures,
+            advanced_extension: advanced_extension(properties, None),
+        }))),
+    }))
+}
+
+fn to_substrait_measure(
+    producer: &mut PhysicalPlanProducer,
+    aggr: &datafusion::physical_expr::aggregate::AggregateFunctionExpr,
+    filter: Option<&Arc<dyn PhysicalExpr>>,
+    schema: &DFSchemaRef,
+) -> Result<Measure> {
+    let args = aggr
+        .expressions()
+        .iter()
+        .map(|arg| to_logical_expr(arg, schema))
+        .collect::<Result<Vec<_>>>()?;
+    let filter = filter
+        .map(|filter| to_logical_expr(filter, schema))
+        .transpose()?
+        .map(Box::new);
+    let order_by = aggr
+        .order_bys()
+        .iter()
+        .map(|sort| {
+            Ok(Sort::new(
+                to_logical_expr(&sort.expr, schema)?,
+                !sort.options.descending,
+                sort.options.nulls_first,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let aggregate_function = AggregateFunction::new_udf(
+        Arc::new(aggr.fun().clone()),
+        args,
+        aggr.is_distinct(),
+        filter,
+        order_by,
+        None,
+    );
+    from_aggregate_function(producer, &aggregate_function, schema)
+}
+
+fn from_hash_join_exec(
+    producer: &mut PhysicalPlanProducer,
+    join: &HashJoinExec,
+) -> Result<Box<Rel>> {
+    let (left, right, keys, post_join_filter) = to_substrait_join_parts(
+        producer,
+        join.left(),
+        join.right(),
+        join.on(),
+        join.filter(),
+        join.null_equality(),
+    )?;
+    let output_mapping = join
+        .projection
+        .as_ref()
+        .map(|projection| projection.iter().map(|i| *i as i32).collect());
+    let enhancement = join
+        .null_aware
+        .then(|| physical_property(NULL_AWARE, "true"));
+
+    #[expect(deprecated)]
+    Ok(Box::new(Rel {
+        rel_type: Some(RelType::HashJoin(Box::new(HashJoinRel {
+            common: rel_common(output_mapping, vec![]),
+            left: Some(left),
+            right: Some(right),
+            left_keys: vec![],
+            right_keys: vec![],
+            keys,
+            post_join_filter,
+            r#type: to_substrait_join_type(join.join_type()) as i32,
+            build_input: hash_join_rel::BuildInput::Left as i32,
+            advanced_extension: advanced_extension(
+                vec![physical_property(
+                    PARTITION_MODE,
+                    format!("{:?}", join.partition_mode()),
+                )],
+                enhancement,
+            ),
+        }))),
+    }))
+}
+
+fn from_sort_merge_join_exec(
+    producer: &mut PhysicalPlanProducer,
+    join: &SortMergeJoinExec,
+) -> Result<Box<Rel>> {
+    if join
+        .sort_options()
+        .iter()
+        .any(|options| *options != SortOptions::default())
+    {
+        return not_impl_err!(
+            "SortMergeJoinExec with non default sort options is not supported in Substrait physical plans"
+        );
+    }
+    let (left, right, keys, post_join_filter) = to_substrait_join_parts(
+        producer,
+        join.left(),
+        join.right(),
+        join.on(),
+        join.filter().as_ref(),
+        join.null_equality(),
+    )?;
+
+    // MergeJoinRel shares the join type numbering of HashJoinRel
+    #[expect(deprecated)]
+    Ok(Box::new(Rel {
+        rel_type: Some(RelType::MergeJoin(Box::new(MergeJoinRel {
+            common: None,
+            left: Some(left),
+            right: Some(right),
+            left_keys: vec![],
+            right_keys: vec![],
+            keys,
+            post_join_filter,
+            r#type: to_substrait_join_type(&join.join_type()) as i32,
+            advanced_extension: None,
+        }))),
+    }))
+}
+
+type JoinParts = (
+    Box<Rel>,
+    Box<Rel>,
+    Vec<ComparisonJoinKey>,
+    Option<Box<Expression>>,
+);
+
+fn to_substrait_join_parts(
+    producer: &mut PhysicalPlanProducer,
+    left: &Arc<dyn ExecutionPlan>,
+    right: &Arc<dyn ExecutionPlan>,
+    on: &[(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)],
+    filter: Option<&JoinFilter>,
+    null_equality: NullEquality,
+) -> Result<JoinParts> {
+    let left_schema = Arc::new(indexed_df_schema(&left.schema())?);
+    let right_schema = Arc::new(indexed_df_schema(&right.schema())?);
+    let comparison = match null_equality {
+        NullEquality::NullEqualsNothing => SimpleComparisonType::Eq,
+        NullEquality::NullEqualsNull => SimpleComparisonType::IsNotDistinctFrom,
+    };
+    let keys = on
+        .iter()
+        .map(|(l, r)| {
+            Ok(ComparisonJoinKey {
+                left: Some(try_to_substrait_field_reference(
+                    &to_logical_expr(l, &left_schema)?,
+                    &left_schema,
+                )?),
+                right: Some(try_to_substrait_field_reference(
+                    &to_logical_expr(r, &right_schema)?,
+                    &right_schema,
+                )?),
+                comparison: Some(ComparisonType {
+                    inner_type: Some(comparison_type::InnerType::Simple(
+                        comparison as i32,
+                    )),
+                }),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // The post join filter refers to the fields of both inputs, while the
+    // JoinFilter refers to an intermediate batch of the fields it uses
+    let post_join_filter = filter
+        .map(|filter| {
+            let left_field_count = left.schema().fields().len();
+            let expr = Arc::clone(filter.expression())
+                .transform(|expr| {
+                    let Some(column) = expr.downcast_ref::<expressions::Column>() else {
+                        return Ok(Transformed::no(expr));
+                    };
+                    let column_index = &filter.column_indices()[column.index()];
+                    let index = match column_index.side {
+                        JoinSide::Left => column_index.index,
+                        JoinSide::Right => left_field_count + column_index.index,
+                        JoinSide::None => {
+                            return internal_err!(
+                                "Join filter column {column} belongs to neither input"
+                            );
+                        }
+                    };
+                    Ok(Transformed::yes(Arc::new(expressions::Column::new(
+                        column.name(),
+                        index,
+                    ))
+                        as Arc<dyn PhysicalExpr>))
+                })
+                .data()?;
+            let join_schema = Arc::new(Schema::new(
+                left.schema()
+                    .fields()
+                    .iter()
+                    .chain(right.schema().fields().iter())
+                    .cloned()
+                    .collect::<Vec<_>>(),
+            ));
+            let join_schema = indexed_df_schema(&join_schema)?;
+            to_substrait_expr(producer, &expr, &join_schema).map(Box::new)
+        })
+        .transpose()?;
+
+    let left = to_substrait_rel_with_producer(producer, left.as_ref())?;
+    let right = to_substrait_rel_with_producer(producer, right.as_ref())?;
+    Ok((left, right, keys, post_join_filter))
+}
+
+fn to_substrait_join_type(join_type: &JoinType) -> hash_join_rel::JoinType {
+    match join_type {
+        JoinType::Inner => hash_join_rel::JoinType::Inner,
+        JoinType::Left => hash_join_rel::JoinType::Left,
+        JoinType::Right => hash_join_rel::JoinType::Right,
+        JoinType::Full => hash_join_rel::JoinType::Outer,
+        JoinType::LeftSemi => hash_join_rel::JoinType::LeftSemi,
+        JoinType::RightSemi => hash_join_rel::JoinType::RightSemi,
+        JoinType::LeftAnti => hash_join_rel::JoinType::LeftAnti,
+        JoinType::RightAnti => hash_join_rel::JoinType::RightAnti,
+        JoinType::LeftMark => hash_join_rel::JoinType::LeftMark,
+        JoinType::RightMark => hash_join_rel::JoinType::RightMark,
+    }
+}
+
+fn from_sort(
+    producer: &mut PhysicalPlanProducer,
+    input: &dyn ExecutionPlan,
+    ordering: &LexOrdering,
+    fetch: Option<usize>,
+    properties: Vec<ProtoAny>,
+) -> Result<Box<Rel>> {
+    let schema = Arc::new(indexed_df_schema(&input.schema())?);
+    let sorts = ordering
+        .iter()
+        .map(|sort| {
+            let sort = Sort::new(
+                to_logical_expr(&sort.expr, &schema)?,
+                !sort.options.descending,
+                sort.options.nulls_first,
+            );
+            substrait_sort_field(producer, &sort, &schema)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let input = to_substrait_rel_with_producer(producer, input)?;
+
+    let sort_rel = Box::new(Rel {
+        rel_type: Some(RelType::Sort(Box::new(SortRel {
+            common: None,
+            input: Some(input),
+            sorts,
+            advanced_extension: advanced_extension(properties, None),
+        }))),
+    });
+    Ok(match fetch {
+        Some(fetch) => fetch_rel(sort_rel, 0, Some(fetch), vec![]),
+        None => sort_rel,
+    })
+}
+
+fn from_repartition_exec(
+    producer: &mut PhysicalPlanProducer,
+    repartition: &RepartitionExec,
+) -> Result<Box<Rel>> {
+    // ref: https://substrait.io/relations/physical_relations/#exchange-types
+    let exchange_kind = match repartition.partitioning() {
+        Partitioning::RoundRobinBatch(_) => {
+            ExchangeKind::RoundRobin(RoundRobin::default())
+        }
+        Partitioning::Hash(exprs, _) => {
+            let schema = Arc::new(indexed_df_schema(&repartition.input().schema())?);
+            let fields = exprs
+                .iter()
+                .map(|e| {
+                    try_to_substrait_field_reference(
+                        &to_logical_expr(e, &schema)?,
+                        &schema,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+            ExchangeKind::ScatterByFields(ScatterFields { fields })
+        }
+        partitioning => {
+            return not_impl_err!(
+                "{partitioning} repartitioning is not supported in Substrait physical plans"
+            );
+        }
+    };
+    let mut properties = vec![];
+    if repartition.preserve_order() {
+        properties.push(physical_property(PRESERVE_ORDER, ""));
+    }
+
+    let input = to_substrait_rel_with_producer(producer, repartition.input().as_ref())?;
+    Ok(Box::new(Rel {
+        rel_type: Some(RelType::Exchange(Box::new(ExchangeRel {
+            common: None,
+            input: Some(input),
+            exchange_kind: Some(exchange_kind),
+            advanced_extension: advanced_extension(properties, None),
+            partition_count: repartition.partitioning().partition_count() as i32,
+            targets: vec![],
+        }))),
+    }))
+}
+
+fn fetch_rel(
+    input: Box<Rel>,
+    skip: usize,
+    fetch: Option<usize>,
+    properties: Vec<ProtoAny>,
+) -> Box<Rel> {
+    let literal = |value: usize| {
+        Box::new(Expression {
+            rex_type: Some(RexType::Literal(Literal {
+                nullable: false,
+                type_variation_reference: DEFAULT_TYPE_VARIATION_REF,
+                literal_type: Some(LiteralType::I64(value as i64)),
             })),
+        })
+    };
+    Box::new(Rel {
+        rel_type: Some(RelType::Fetch(Box::new(FetchRel {
+            common: None,
+            input: Some(input),
+            offset_mode: (skip > 0)
+                .then(|| fetch_rel::OffsetMode::OffsetExpr(literal(skip))),
+            count_mode: fetch
+                .map(|fetch| fetch_rel::CountMode::CountExpr(literal(fetch))),
+            advanced_extension: advanced_extension(properties, None),
+        }))),
+    })
+}
+
+fn rel_common(
+    output_mapping: Option<Vec<i32>>,
+    output_names: Vec<String>,
+) -> Option<RelCommon> {
+    if output_mapping.is_none() && output_names.is_empty() {
+        return None;
+    }
+    Some(RelCommon {
+        emit_kind: output_mapping
+            .map(|output_mapping| EmitKind::Emit(rel_common::Emit { output_mapping })),
+        hint: (!output_names.is_empty()).then(|| rel_common::Hint {
+            output_names,
+            ..Default::default()
         }),
-        _ => Err(DataFusionError::Substrait(format!(
-            "Logical type {data_type} not implemented as substrait type"
-        ))),
+        advanced_extension: None,
+    })
+}
+
+fn advanced_extension(
+    optimization: Vec<ProtoAny>,
+    enhancement: Option<ProtoAny>,
+) -> Option<AdvancedExtension> {
+    if optimization.is_empty() && enhancement.is_none() {
+        return None;
+    }
+    Some(AdvancedExtension {
+        optimization,
+        enhancement,
+    })
+}
+
+fn to_substrait_expr(
+    producer: &mut PhysicalPlanProducer,
+    expr: &Arc<dyn PhysicalExpr>,
+    schema: &DFSchema,
+) -> Result<Expression> {
+    let schema = Arc::new(schema.clone());
+    let expr = to_logical_expr(expr, &schema)?;
+    producer.handle_expr(&expr, &schema)
+}
+
+/// Converts a [`PhysicalExpr`] into the equivalent logical [`Expr`] over
+/// `schema`, so that the logical expression producer can handle it
+fn to_logical_expr(expr: &Arc<dyn PhysicalExpr>, schema: &DFSchema) -> Result<Expr> {
+    let to_logical = |expr: &Arc<dyn PhysicalExpr>| -> Result<Box<Expr>> {
+        to_logical_expr(expr, schema).map(Box::new)
+    };
+
+    if let Some(column) = expr.downcast_ref::<expressions::Column>() {
+        return Ok(Expr::Column(datafusion::common::Column::from(
+            schema.qualified_field(column.index()),
+        )));
+    }
+    if let Some(literal) = expr.downcast_ref::<expressions::Literal>() {
+        return Ok(Expr::Literal(literal.value().clone(), None));
+    }
+    if let Some(binary) = expr.downcast_ref::<expressions::BinaryExpr>() {
+        return Ok(Expr::BinaryExpr(BinaryExpr::new(
+            to_logical(binary.left())?,
+            *binary.op(),
+            to_logical(binary.right())?,
+        )));
+    }
+    if let Some(cast) = expr.downcast_ref::<expressions::CastExpr>() {
+        return Ok(Expr::Cast(Cast::new(
+            to_logical(cast.expr())?,
+            cast.cast_type().clone(),
+        )));
+    }
+    if let Some(cast) = expr.downcast_ref::<expressions::TryCastExpr>() {
+        return Ok(Expr::TryCast(TryCast::new(
+            to_logical(cast.expr())?,
+            cast.cast_type().clone(),
+        )));
+    }
+    if let Some(not) = expr.downcast_ref::<expressions::NotExpr>() {
+        return Ok(Expr::Not(to_logical(not.arg())?));
+    }
+    if let Some(is_null) = expr.downcast_ref::<expressions::IsNullExpr>() {
+        return Ok(Expr::IsNull(to_logical(is_null.arg())?));
+    }
+    if let Some(is_not_null) = expr.downcast_ref::<expressions::IsNotNullExpr>() {
+        return Ok(Expr::IsNotNull(to_logical(is_not_null.arg())?));
+    }
+    if let Some(negative) = expr.downcast_ref::<expressions::NegativeExpr>() {
+        return Ok(Expr::Negative(to_logical(negative.arg())?));
+    }
+    if let Some(in_list) = expr.downcast_ref::<expressions::InListExpr>() {
+        let list = in_list
+            .list()
+            .iter()
+            .map(|e| to_logical_expr(e, schema))
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(Expr::InList(InList::new(
+            to_logical(in_list.expr())?,
+            list,
+            in_list.negated(),
+        )));
+    }
+    if let Some(case) = expr.downcast_ref::<expressions::CaseExpr>() {
+        let when_then_expr = case
+            .when_then_expr()
+            .iter()
+            .map(|(when, then)| Ok((to_logical(when)?, to_logical(then)?)))
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(Expr::Case(Case::new(
+            case.expr().map(to_logical).transpose()?,
+            when_then_expr,
+            case.else_expr().map(to_logical).transpose()?,
+        )));
+    }
+    if let Some(like) = expr.downcast_ref::<expressions::LikeExpr>() {
+        return Ok(Expr::Like(Like::new(
+            like.negated(),
+            to_logical(like.expr())?,
+            to_logical(like.pattern())?,
+            None,
+            like.case_insensitive(),
+        )));
+    }
+    if let Some(function) = expr.downcast_ref::<ScalarFunctionExpr>() {
+        let args = function
+            .args()
+            .iter()
+            .map(|e| to_logical_expr(e, schema))
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(Expr::ScalarFunction(ScalarFunction::new_udf(
+            Arc::new(function.fun().clone()),
+            args,
+        )));
     }
+    not_impl_err!("Unsupported expression in Substrait physical plan producer: {expr}")
 }
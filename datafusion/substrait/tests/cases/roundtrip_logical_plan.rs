@@ -2365,6 +2365,99 @@ impl HigherOrderUDFImpl for ArrayTransform {
     }
 }
 
+#[tokio::test]
+async fn roundtrip_insert() -> Result<()> {
+    let proto = roundtrip_statement("INSERT INTO data2 SELECT * FROM data").await?;
+    assert_root_rel_type(&proto, |rel| matches!(rel, RelType::Write(_)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn roundtrip_insert_values() -> Result<()> {
+    roundtrip_statement("INSERT INTO data (a, f) VALUES (1, 'x'), (2, 'y')").await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn roundtrip_delete() -> Result<()> {
+    let proto = roundtrip_statement("DELETE FROM data WHERE a > 1").await?;
+    assert_root_rel_type(&proto, |rel| matches!(rel, RelType::Write(_)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn roundtrip_update() -> Result<()> {
+    roundtrip_statement("UPDATE data SET a = a + 1, f = 'x' WHERE d").await?;
+    roundtrip_statement("UPDATE data SET e = 0").await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn roundtrip_create_table_as() -> Result<()> {
+    let proto =
+        roundtrip_statement("CREATE TABLE t AS SELECT a, f FROM data WHERE d").await?;
+    assert_root_rel_type(&proto, |rel| matches!(rel, RelType::Write(_)));
+    roundtrip_statement("CREATE OR REPLACE TABLE t AS SELECT a FROM data").await?;
+    roundtrip_statement("CREATE TABLE IF NOT EXISTS t AS VALUES (1)").await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn roundtrip_create_table() -> Result<()> {
+    let proto = roundtrip_statement(
+        "CREATE TABLE t (x BIGINT, y VARCHAR DEFAULT 'a', z DOUBLE NOT NULL)",
+    )
+    .await?;
+    assert_root_rel_type(&proto, |rel| matches!(rel, RelType::Ddl(_)));
+    roundtrip_statement("CREATE OR REPLACE TABLE t (x BIGINT)").await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn roundtrip_create_view() -> Result<()> {
+    let proto =
+        roundtrip_statement("CREATE VIEW v AS SELECT a, f FROM data WHERE d").await?;
+    assert_root_rel_type(&proto, |rel| matches!(rel, RelType::Ddl(_)));
+    roundtrip_statement("CREATE OR REPLACE VIEW v AS SELECT a FROM data").await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn roundtrip_drop() -> Result<()> {
+    roundtrip_statement("DROP TABLE data").await?;
+    roundtrip_statement("DROP TABLE IF EXISTS t").await?;
+    roundtrip_statement("DROP VIEW IF EXISTS v").await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn roundtrip_copy() -> Result<()> {
+    let proto = roundtrip_statement(
+        "COPY (SELECT a, f FROM data) TO 'out' STORED AS PARQUET PARTITIONED BY (f)",
+    )
+    .await?;
+    assert_root_rel_type(&proto, |rel| matches!(rel, RelType::Write(_)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn unsupported_statements() -> Result<()> {
+    let ctx = create_context().await?;
+    for sql in [
+        "INSERT OVERWRITE data2 SELECT * FROM data",
+        "CREATE TEMPORARY TABLE t AS SELECT a FROM data",
+        "CREATE TABLE t (x BIGINT PRIMARY KEY)",
+    ] {
+        let plan = ctx.state().create_logical_plan(sql).await?;
+        let err = to_substrait_plan(&plan, &ctx.state()).unwrap_err();
+        assert!(
+            err.to_string().contains("This feature is not implemented"),
+            "{sql}: {err}"
+        );
+    }
+    Ok(())
+}
+
 fn check_post_join_filters(rel: &Rel) -> Result<()> {
     // search for target_rel and field value in proto
     match &rel.rel_type {
@@ -2859,6 +2952,32 @@ async fn roundtrip_verify_read_filter_count(
     assert_read_filter_count(&proto, expected_filter_count)
 }
 
+/// Round trips a statement, which is planned but neither executed nor
+/// optimized, through Substrait
+async fn roundtrip_statement(sql: &str) -> Result<Box<Plan>> {
+    let ctx = create_context().await?;
+    let plan = ctx.state().create_logical_plan(sql).await?;
+    let proto = to_substrait_plan(&plan, &ctx.state())?;
+    let plan2 = from_substrait_plan(&ctx.state(), &proto).await?;
+
+    assert_eq!(format!("{plan}"), format!("{plan2}"));
+    assert_eq!(plan.schema(), plan2.schema());
+    Ok(proto)
+}
+
+fn assert_root_rel_type(proto: &Plan, expected: impl Fn(&RelType) -> bool) {
+    let rel = match &proto.relations[0].rel_type {
+        Some(plan_rel::RelType::Root(root)) => root.input.as_ref(),
+        Some(plan_rel::RelType::Rel(rel)) => Some(rel),
+        None => None,
+    };
+    let rel_type = rel.and_then(|rel| rel.rel_type.as_ref());
+    assert!(
+        rel_type.is_some_and(expected),
+        "unexpected root: {rel_type:?}"
+    );
+}
+
 async fn roundtrip_all_types(sql: &str) -> Result<()> {
     roundtrip_with_ctx(sql, create_all_type_context().await?).await?;
     Ok(())
@@ -19,6 +19,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use datafusion::arrow::datatypes::Schema;
+use datafusion::arrow::util::pretty::pretty_format_batches;
+use datafusion::common::test_util::datafusion_test_data;
 use datafusion::dataframe::DataFrame;
 use datafusion::datasource::listing::PartitionedFile;
 use datafusion::datasource::object_store::ObjectStoreUrl;
@@ -26,8 +28,8 @@ use datafusion::datasource::physical_plan::{
     FileGroup, FileScanConfigBuilder, ParquetSource,
 };
 use datafusion::error::Result;
-use datafusion::physical_plan::{ExecutionPlan, displayable};
-use datafusion::prelude::{ParquetReadOptions, SessionContext};
+use datafusion::physical_plan::{ExecutionPlan, collect, displayable};
+use datafusion::prelude::{ParquetReadOptions, SessionConfig, SessionContext};
 use datafusion_substrait::physical_plan::{consumer, producer};
 
 use datafusion::datasource::memory::DataSourceExec;
@@ -64,9 +66,12 @@ async fn parquet_exec() -> Result<()> {
 
     let ctx = SessionContext::new();
 
-    let parquet_exec_roundtrip =
-        consumer::from_substrait_rel(&ctx, substrait_rel.as_ref(), &HashMap::new())
-            .await?;
+    let parquet_exec_roundtrip = consumer::from_substrait_rel(
+        &ctx,
+        substrait_rel.as_ref(),
+        &extension_map(&extension_info.1),
+    )
+    .await?;
 
     let expected = format!("{}", displayable(parquet_exec.as_ref()).indent(true));
     let actual = format!(
@@ -100,6 +105,80 @@ async fn wildcard_select_alltypes() -> Result<()> {
     roundtrip_alltypes("SELECT * FROM alltypes_plain").await
 }
 
+#[tokio::test]
+async fn filter_and_projection() -> Result<()> {
+    roundtrip("SELECT a + 1 AS a1, b FROM data WHERE a > 1 AND c IS NOT NULL").await
+}
+
+#[tokio::test]
+async fn aggregate() -> Result<()> {
+    roundtrip("SELECT a, sum(b), count(distinct c) FROM data GROUP BY a").await
+}
+
+#[tokio::test]
+async fn aggregate_without_group_by() -> Result<()> {
+    roundtrip("SELECT avg(b), max(a) FROM data").await
+}
+
+#[tokio::test]
+async fn hash_join() -> Result<()> {
+    roundtrip(
+        "SELECT l.a, r.b FROM data l JOIN data r ON l.a = r.a AND l.b < r.b \
+         WHERE l.c IS NULL",
+    )
+    .await
+}
+
+#[tokio::test]
+async fn sort_merge_join() -> Result<()> {
+    let ctx = create_parquet_context().await?;
+    ctx.sql("SET datafusion.optimizer.prefer_hash_join = false")
+        .await?;
+    let df = ctx
+        .sql("SELECT l.a, r.b FROM data l LEFT JOIN data r ON l.a = r.a")
+        .await?;
+
+    roundtrip_parquet(df).await
+}
+
+#[tokio::test]
+async fn sort_with_limit() -> Result<()> {
+    roundtrip("SELECT a, b FROM data ORDER BY b DESC, a LIMIT 5").await?;
+    roundtrip("SELECT a, b FROM data ORDER BY a LIMIT 5 OFFSET 2").await
+}
+
+#[tokio::test]
+async fn limit() -> Result<()> {
+    roundtrip("SELECT a FROM data LIMIT 3 OFFSET 1").await
+}
+
+#[tokio::test]
+async fn union_all() -> Result<()> {
+    roundtrip("SELECT a FROM data UNION ALL SELECT a + 1 FROM data").await
+}
+
+#[tokio::test]
+async fn tpch_queries() -> Result<()> {
+    let ctx = create_tpch_context(true).await?;
+    // q11 and q22 compare against a scalar subquery, which is planned as a
+    // nested loop join
+    for query in (1..=22).filter(|query| ![11, 22].contains(query)) {
+        roundtrip_tpch(&ctx, query).await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tpch_queries_with_sort_merge_joins() -> Result<()> {
+    let ctx = create_tpch_context(false).await?;
+    for query in (1..=22).filter(|query| ![11, 22].contains(query)) {
+        roundtrip_tpch(&ctx, query).await?;
+    }
+
+    Ok(())
+}
+
 async fn roundtrip(sql: &str) -> Result<()> {
     let ctx = create_parquet_context().await?;
     let df = ctx.sql(sql).await?;
@@ -128,9 +207,12 @@ async fn roundtrip_parquet(df: DataFrame) -> Result<()> {
 
     // Convert the substrait Rel back into a physical plan
     let ctx = create_parquet_context().await?;
-    let physical_plan_roundtrip =
-        consumer::from_substrait_rel(&ctx, substrait_plan.as_ref(), &HashMap::new())
-            .await?;
+    let physical_plan_roundtrip = consumer::from_substrait_rel(
+        &ctx,
+        substrait_plan.as_ref(),
+        &extension_map(&extension_info.1),
+    )
+    .await?;
 
     // Compare the original and roundtrip physical plans
     let expected = format!("{}", displayable(physical_plan.as_ref()).indent(true));
@@ -166,3 +248,80 @@ async fn create_all_types_context() -> Result<SessionContext> {
 
     Ok(ctx)
 }
+
+/// Round trips the physical plan of every statement of a TPC-H query, checking
+/// that the consumed plan produces the same Substrait relation and results
+async fn roundtrip_tpch(ctx: &SessionContext, query: usize) -> Result<()> {
+    let path = format!("../../benchmarks/queries/q{query}.sql");
+    let sql = std::fs::read_to_string(&path)?;
+    let statements = sql.split(';').map(str::trim).filter(|s| !s.is_empty());
+    for statement in statements {
+        let df = ctx.sql(statement).await?;
+        if !statement.to_lowercase().starts_with("select") {
+            // Views are created and dropped while planning the statement
+            continue;
+        }
+        let physical_plan = df.create_physical_plan().await?;
+
+        let mut extension_info = (vec![], HashMap::new());
+        let substrait_rel =
+            producer::to_substrait_rel(physical_plan.as_ref(), &mut extension_info)?;
+        let physical_plan_roundtrip = consumer::from_substrait_rel(
+            ctx,
+            substrait_rel.as_ref(),
+            &extension_map(&extension_info.1),
+        )
+        .await?;
+
+        let mut roundtrip_extension_info = (vec![], HashMap::new());
+        let roundtrip_rel = producer::to_substrait_rel(
+            physical_plan_roundtrip.as_ref(),
+            &mut roundtrip_extension_info,
+        )?;
+        assert_eq!(substrait_rel, roundtrip_rel, "q{query}: {statement}");
+
+        let expected = collect(physical_plan, ctx.task_ctx()).await?;
+        let actual = collect(physical_plan_roundtrip, ctx.task_ctx()).await?;
+        assert_eq!(
+            pretty_format_batches(&expected)?.to_string(),
+            pretty_format_batches(&actual)?.to_string(),
+            "q{query}: {statement}"
+        );
+    }
+
+    Ok(())
+}
+
+async fn create_tpch_context(prefer_hash_join: bool) -> Result<SessionContext> {
+    let config = SessionConfig::new()
+        .with_target_partitions(4)
+        .set_bool("datafusion.optimizer.prefer_hash_join", prefer_hash_join)
+        // Dynamic filters and scalar subqueries have no Substrait physical mapping
+        .set_bool("datafusion.optimizer.enable_dynamic_filter_pushdown", false)
+        .set_bool(
+            "datafusion.optimizer.enable_physical_uncorrelated_scalar_subquery",
+            false,
+        );
+    let ctx = SessionContext::new_with_config(config);
+
+    let testdata = datafusion_test_data();
+    for table in [
+        "part", "supplier", "partsupp", "customer", "orders", "lineitem", "nation",
+        "region",
+    ] {
+        ctx.sql(&format!(
+            "CREATE EXTERNAL TABLE {table} STORED AS PARQUET \
+             LOCATION '{testdata}/tpch_{table}_small.parquet'"
+        ))
+        .await?;
+    }
+
+    Ok(ctx)
+}
+
+fn extension_map(extensions: &HashMap<String, u32>) -> HashMap<u32, &String> {
+    extensions
+        .iter()
+        .map(|(name, anchor)| (*anchor, name))
+        .collect()
+}